@@ -0,0 +1,46 @@
+//! Evaluating an expression at many points: the tree walk against the
+//! compiled expression.
+//!
+//! ```text
+//! cargo bench --bench compile
+//! ```
+
+use std::time::{Duration, Instant};
+
+use arithma::{parse_latex, Environment, Evaluator};
+
+const POINTS: usize = 20_000;
+
+fn time(work: impl Fn()) -> Duration {
+    work(); // warm up
+    let start = Instant::now();
+    work();
+    start.elapsed()
+}
+
+fn main() {
+    let env = Environment::new();
+    for latex in [
+        "3x^{3} - 2x^{2} + \\frac{x}{7} - 1",
+        "\\sin(x) e^{-x^{2}} + \\frac{\\cos(3x)}{1 + x^{2}}",
+    ] {
+        let expr = parse_latex(latex, &env).unwrap();
+        let xs: Vec<f64> = (0..POINTS).map(|i| i as f64 / POINTS as f64).collect();
+        let walked = time(|| {
+            let mut env = Environment::new();
+            for &x in &xs {
+                env.set("x", x);
+                Evaluator::evaluate(&expr, &env).unwrap();
+            }
+        });
+        let compiled = Evaluator::compile(&expr, &["x"]).unwrap();
+        let run = time(|| {
+            let total: f64 = xs.iter().map(|&x| compiled.eval(&[x])).sum();
+            assert!(total.is_finite());
+        });
+        println!(
+            "{latex} at {POINTS} points: {walked:?} walking the tree, {run:?} compiled ({:.0}x)",
+            walked.as_secs_f64() / run.as_secs_f64()
+        );
+    }
+}
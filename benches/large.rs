@@ -0,0 +1,69 @@
+//! Simplifying and differentiating long sums, doubling the number of
+//! terms each time: the times should roughly double too.
+//!
+//! ```text
+//! cargo bench --bench large
+//! ```
+
+use std::time::{Duration, Instant};
+
+use arithma::exact::ExactNum;
+use arithma::simplify::Simplifiable;
+use arithma::{differentiate, Environment, Node, NodeArena};
+
+fn time<T>(work: impl Fn() -> T) -> Duration {
+    work(); // warm up
+    let start = Instant::now();
+    work();
+    start.elapsed()
+}
+
+fn num(n: i64) -> Box<Node> {
+    Box::new(Node::Num(ExactNum::integer(n)))
+}
+
+fn x() -> Box<Node> {
+    Box::new(Node::Variable("x".to_string()))
+}
+
+/// `1 + 2x + 3x^{2} + …` up to `x^{n}`
+fn polynomial(n: i64) -> Node {
+    (1..=n).fold(*num(1), |sum, k| {
+        let term = Node::Multiply(num(k % 7 + 1), Box::new(Node::Power(x(), num(k))));
+        Node::Add(Box::new(sum), Box::new(term))
+    })
+}
+
+/// `\sin(x) + 2\sin(2x) + …` up to `n\sin(nx)`
+fn trig_sum(n: i64) -> Node {
+    (1..=n).fold(*num(0), |sum, k| {
+        let term = Node::Multiply(
+            num(k),
+            Box::new(Node::Function(
+                "sin".to_string(),
+                vec![Node::Multiply(num(k), x())],
+            )),
+        );
+        Node::Add(Box::new(sum), Box::new(term))
+    })
+}
+
+fn main() {
+    let env = Environment::new();
+    for (name, build) in [
+        ("polynomial", polynomial as fn(i64) -> Node),
+        ("trig sum", trig_sum),
+    ] {
+        for n in [1_000, 2_000, 4_000, 8_000] {
+            let expr = build(n);
+            let mut arena = NodeArena::new();
+            let id = arena.intern(&expr);
+            let simplify = time(|| expr.simplify(&env).unwrap());
+            let derive = time(|| differentiate(&expr, "x").unwrap());
+            println!(
+                "{name} of {n} terms ({} nodes): simplify {simplify:?}, differentiate {derive:?}",
+                arena.size(id)
+            );
+        }
+    }
+}
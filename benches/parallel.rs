@@ -0,0 +1,54 @@
+//! Speedup of the `parallel` feature: a large sum and a large matrix
+//! product, timed on one thread and on all of them.
+//!
+//! ```text
+//! cargo bench --features parallel --bench parallel
+//! ```
+
+use std::time::{Duration, Instant};
+
+use arithma::{parse_latex, Environment, Evaluator, ExactNum, Matrix, Node};
+
+fn time(threads: usize, work: impl Fn() + Send + Sync) -> Duration {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("thread pool");
+    pool.install(|| {
+        work(); // warm up
+        let start = Instant::now();
+        work();
+        start.elapsed()
+    })
+}
+
+fn report(name: &str, work: impl Fn() + Send + Sync) {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let one = time(1, &work);
+    let all = time(threads, &work);
+    println!(
+        "{name}: {one:?} on 1 thread, {all:?} on {threads} ({:.1}x)",
+        one.as_secs_f64() / all.as_secs_f64()
+    );
+}
+
+fn main() {
+    let env = Environment::new();
+
+    let sum = parse_latex("\\sum_{k=1}^{400000} \\frac{\\sin(k)}{k}", &env).unwrap();
+    report("sum of 400000 terms", || {
+        Evaluator::evaluate(&sum, &env).unwrap();
+    });
+
+    let n = 48;
+    let entries = |offset: i64| {
+        (0..n * n)
+            .map(|i| Node::Num(ExactNum::integer((i as i64 * 7 + offset) % 11 - 5)))
+            .collect()
+    };
+    let a = Matrix::new(n, n, entries(1)).unwrap();
+    let b = Matrix::new(n, n, entries(3)).unwrap();
+    report("48×48 matrix product", || {
+        a.multiply(&b, &env).unwrap();
+    });
+}
@@ -0,0 +1,473 @@
+//! Command-line arguments of the one-shot subcommands.
+//!
+//! Every subcommand still takes its variables and bounds positionally
+//! (`arithma diff 'x^2' x`); the flags (`--var`, `--from`/`--to`, `--for`)
+//! are the self-describing spelling and win over a positional value.
+//! Expressions and bounds may start with `-`.
+
+use arithma::{FormatOptions, MulSymbol, NumberStyle};
+use clap::{Parser, Subcommand, ValueEnum};
+
+const EXAMPLES: &str = "\
+All expressions accept LaTeX or natural notation (pi, inf, sqrt, sin, etc.).
+Run without a command for the interactive REPL.
+
+Examples:
+  arithma simplify 'x^2 + 2x + 1'
+  arithma diff 'sin(x^2)' --var x
+  arithma integrate '3x^2' x
+  arithma integrate '1/(x^2+1)' --from 0 --to 1
+  arithma solve '2x + 5 = 11' --for x
+  arithma factor 'x^4 - 1'
+  arithma prime-factorize 720
+  arithma eval 'x^2 + 1' x=3
+  arithma limit 'sin(x)/x' x 0
+  arithma taylor 'sin(x)' x 0 5
+  arithma variance x '(0, 1/4), (1, 1/2), (2, 1/4)'
+  arithma ode --cc 1 0 1
+  arithma --format latex simplify '\\frac{2}{4}'";
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "arithma",
+    version,
+    about = "Arithma — exact computer algebra from the command line",
+    after_help = EXAMPLES
+)]
+pub struct Cli {
+    /// How results are printed [default: plain in a terminal, latex when
+    /// piped]
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<Format>,
+
+    /// Same as --format latex
+    #[arg(long, global = true, conflicts_with = "unicode")]
+    pub latex: bool,
+
+    /// Same as --format plain
+    #[arg(long, global = true)]
+    pub unicode: bool,
+
+    /// Write fractions as decimals
+    #[arg(long, global = true)]
+    pub decimal: bool,
+
+    /// Significant digits of decimal numbers
+    #[arg(long, global = true, value_name = "DIGITS")]
+    pub precision: Option<usize>,
+
+    /// Multiplication sign between factors
+    #[arg(long, global = true, value_enum)]
+    pub mul: Option<MulSign>,
+
+    /// How expressions are written [default: latex]
+    #[arg(long, global = true, value_enum, value_name = "SYNTAX")]
+    pub input: Option<InputSyntax>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Raw LaTeX
+    Latex,
+    /// Readable Unicode text (x², √2, π)
+    #[value(alias = "unicode", alias = "text")]
+    Plain,
+    /// ASCII infix that reads back as input (3*x^2 + sqrt(x))
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum InputSyntax {
+    /// LaTeX, or natural notation the LaTeX parser accepts (sin(x), pi)
+    #[default]
+    Latex,
+    /// Plain infix: sin(x)^2 + 3*x, x**2, sqrt(x), abs(x), sum(k, k, 1, n)
+    Plain,
+    /// AsciiMath: (x+1)/(x-1), sqrt x, root(3)(x), sum_(k=1)^n k
+    Asciimath,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum MulSign {
+    /// x·y (\cdot)
+    Cdot,
+    /// x×y (\times)
+    Times,
+    /// x*y
+    #[value(alias = "star")]
+    Asterisk,
+}
+
+impl From<MulSign> for MulSymbol {
+    fn from(sign: MulSign) -> Self {
+        match sign {
+            MulSign::Cdot => MulSymbol::Cdot,
+            MulSign::Times => MulSymbol::Times,
+            MulSign::Asterisk => MulSymbol::Asterisk,
+        }
+    }
+}
+
+impl Cli {
+    /// The format asked for, if any, by `--format` or its shorthands.
+    pub fn requested_format(&self) -> Option<Format> {
+        self.format.or(if self.latex {
+            Some(Format::Latex)
+        } else if self.unicode {
+            Some(Format::Plain)
+        } else {
+            None
+        })
+    }
+
+    /// How numbers and products are written, from `--decimal`,
+    /// `--precision` and `--mul`.
+    pub fn format_options(&self) -> FormatOptions {
+        FormatOptions {
+            numbers: if self.decimal {
+                NumberStyle::Decimal
+            } else {
+                NumberStyle::Exact
+            },
+            precision: self.precision,
+            multiplication: self.mul.map_or(MulSymbol::Auto, MulSymbol::from),
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Parse and print canonical LaTeX (no simplify)
+    Format {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+    },
+    /// Simplify an expression
+    Simplify {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+    },
+    /// Differentiate
+    #[command(visible_alias = "diff")]
+    Differentiate {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// Variable to differentiate by [default: x]
+        #[arg(long)]
+        var: Option<String>,
+    },
+    /// Integral, definite between bounds
+    Integrate {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        #[arg(
+            value_name = "LOWER",
+            allow_hyphen_values = true,
+            requires = "upper_arg"
+        )]
+        lower_arg: Option<String>,
+        #[arg(value_name = "UPPER", allow_hyphen_values = true)]
+        upper_arg: Option<String>,
+        /// Variable of integration [default: x]
+        #[arg(long)]
+        var: Option<String>,
+        /// Lower bound of a definite integral
+        #[arg(long, allow_hyphen_values = true, requires = "to")]
+        from: Option<String>,
+        /// Upper bound of a definite integral
+        #[arg(long, allow_hyphen_values = true, requires = "from")]
+        to: Option<String>,
+    },
+    /// Solve an equation or inequality, or a linear system given as
+    /// "eq1, eq2" "x, y"
+    Solve {
+        #[arg(allow_hyphen_values = true)]
+        equation: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// Variable (or comma-separated variables) to solve for [default: x]
+        #[arg(long = "for", value_name = "VAR")]
+        for_var: Option<String>,
+    },
+    /// Factor a polynomial over Q
+    Factor {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// Variable of the polynomial [default: x]
+        #[arg(long)]
+        var: Option<String>,
+    },
+    /// Prime-factorize a positive integer
+    #[command(visible_alias = "factorint")]
+    PrimeFactorize { n: String },
+    /// Partial fraction decomposition
+    #[command(visible_alias = "pf")]
+    PartialFractions {
+        #[arg(allow_hyphen_values = true)]
+        numerator: String,
+        #[arg(allow_hyphen_values = true)]
+        denominator: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// Variable of the rational function [default: x]
+        #[arg(long)]
+        var: Option<String>,
+    },
+    /// Polynomial long division: quotient and remainder
+    #[command(visible_alias = "polydiv")]
+    PolyDiv {
+        #[arg(allow_hyphen_values = true)]
+        numerator: String,
+        #[arg(allow_hyphen_values = true)]
+        denominator: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// Variable of the polynomials [default: x]
+        #[arg(long)]
+        var: Option<String>,
+    },
+    /// Evaluate numerically
+    #[command(visible_alias = "eval")]
+    Evaluate {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        /// Values of variables, as var=val
+        #[arg(value_name = "VAR=VAL", allow_hyphen_values = true)]
+        values: Vec<String>,
+    },
+    /// Compute a limit
+    Limit {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// number, inf, -inf, or one-sided (0+, 0-, 3+, 3-) [default: 0]
+        #[arg(allow_hyphen_values = true)]
+        point: Option<String>,
+        /// Variable that approaches the point [default: x]
+        #[arg(long)]
+        var: Option<String>,
+    },
+    /// Taylor series expansion
+    Taylor {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// [default: 0]
+        #[arg(allow_hyphen_values = true)]
+        center: Option<String>,
+        /// [default: 5]
+        order: Option<String>,
+        /// Variable of the series [default: x]
+        #[arg(long)]
+        var: Option<String>,
+    },
+    /// E[expr] over "(x1, p1), (x2, p2), ..."
+    #[command(visible_alias = "E")]
+    Expectation {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        distribution: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// Random variable [default: x]
+        #[arg(long)]
+        var: Option<String>,
+    },
+    /// Var(expr) over a distribution
+    #[command(visible_alias = "Var")]
+    Variance {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        distribution: String,
+        #[arg(value_name = "VAR")]
+        var_arg: Option<String>,
+        /// Random variable [default: x]
+        #[arg(long)]
+        var: Option<String>,
+    },
+    /// Substitute a value for a variable
+    #[command(visible_alias = "sub")]
+    Substitute {
+        #[arg(allow_hyphen_values = true)]
+        expr: String,
+        var: String,
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+    /// Solve dy/dx = rhs, ay'' + by' + cy = 0 (--cc) or y' = Ay (--system)
+    Ode {
+        /// Coefficients of ay'' + by' + cy = 0
+        #[arg(
+            long,
+            num_args = 3,
+            value_names = ["A", "B", "C"],
+            allow_negative_numbers = true,
+            conflicts_with = "system"
+        )]
+        cc: Option<Vec<f64>>,
+        /// Matrix A of the linear system y' = Ay
+        #[arg(long, value_name = "MATRIX")]
+        system: Option<String>,
+        /// rhs [indep] [dep], or [indep] after --cc or --system
+        #[arg(value_name = "ARGS", allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+impl Command {
+    /// The expressions, bounds and values given to the command, for
+    /// rewriting from plain or AsciiMath input into LaTeX. Variable names and `eval`'s
+    /// `name=value` bindings are left out.
+    pub fn expressions_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            Command::Format { expr }
+            | Command::Simplify { expr }
+            | Command::Differentiate { expr, .. }
+            | Command::Factor { expr, .. }
+            | Command::Expectation { expr, .. }
+            | Command::Variance { expr, .. } => vec![expr],
+            Command::Integrate {
+                expr,
+                lower_arg,
+                upper_arg,
+                from,
+                to,
+                ..
+            } => std::iter::once(expr)
+                .chain([lower_arg, upper_arg, from, to].into_iter().flatten())
+                .collect(),
+            Command::Solve { equation, .. } => vec![equation],
+            Command::PrimeFactorize { .. } => Vec::new(),
+            Command::PartialFractions {
+                numerator,
+                denominator,
+                ..
+            }
+            | Command::PolyDiv {
+                numerator,
+                denominator,
+                ..
+            } => vec![numerator, denominator],
+            Command::Evaluate { expr, .. } => vec![expr],
+            Command::Limit { expr, point, .. } => std::iter::once(expr).chain(point).collect(),
+            Command::Taylor { expr, center, .. } => std::iter::once(expr).chain(center).collect(),
+            Command::Substitute { expr, value, .. } => vec![expr, value],
+            Command::Ode { args, .. } => args.iter_mut().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("arithma").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn flags_and_positional_arguments_both_parse() {
+        let Some(Command::Integrate {
+            from, to, var_arg, ..
+        }) = parse(&["integrate", "x^2", "--from", "-1", "--to", "1"]).command
+        else {
+            panic!("expected integrate");
+        };
+        assert_eq!((from.as_deref(), to.as_deref()), (Some("-1"), Some("1")));
+        assert_eq!(var_arg, None);
+
+        let Some(Command::Integrate {
+            lower_arg,
+            upper_arg,
+            ..
+        }) = parse(&["integrate", "x^2", "x", "-1", "1"]).command
+        else {
+            panic!("expected integrate");
+        };
+        assert_eq!(lower_arg.as_deref(), Some("-1"));
+        assert_eq!(upper_arg.as_deref(), Some("1"));
+
+        let Some(Command::PolyDiv { var_arg, var, .. }) =
+            parse(&["polydiv", "x^3+2x+1", "x-1", "--var", "t"]).command
+        else {
+            panic!("expected poly-div");
+        };
+        assert_eq!((var_arg, var.as_deref()), (None, Some("t")));
+
+        let Some(Command::Solve { for_var, .. }) =
+            parse(&["solve", "2x+5=11", "--for", "x"]).command
+        else {
+            panic!("expected solve");
+        };
+        assert_eq!(for_var.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn format_flag_and_shorthands() {
+        assert_eq!(
+            parse(&["simplify", "x", "--format", "latex"]).requested_format(),
+            Some(Format::Latex)
+        );
+        assert_eq!(
+            parse(&["--unicode", "simplify", "x"]).requested_format(),
+            Some(Format::Plain)
+        );
+        assert_eq!(parse(&["diff", "-x^2"]).requested_format(), None);
+        assert!(Cli::try_parse_from(["arithma", "--latex", "--unicode"]).is_err());
+        assert_eq!(
+            parse(&["--format", "ascii", "simplify", "x"]).requested_format(),
+            Some(Format::Ascii)
+        );
+    }
+
+    #[test]
+    fn plain_input_rewrites_expressions_only() {
+        let cli = parse(&["--input", "plain", "integrate", "x**2", "t", "0", "pi"]);
+        assert_eq!(cli.input, Some(InputSyntax::Plain));
+        let mut command = cli.command.unwrap();
+        let texts: Vec<String> = command
+            .expressions_mut()
+            .into_iter()
+            .map(|s| s.clone())
+            .collect();
+        assert_eq!(texts, ["x**2", "0", "pi"]);
+    }
+
+    #[test]
+    fn number_format_flags() {
+        assert_eq!(
+            parse(&["simplify", "x"]).format_options(),
+            FormatOptions::default()
+        );
+        let options = parse(&[
+            "eval",
+            "1/3",
+            "--decimal",
+            "--precision",
+            "4",
+            "--mul",
+            "star",
+        ])
+        .format_options();
+        assert_eq!(options.numbers, NumberStyle::Decimal);
+        assert_eq!(options.precision, Some(4));
+        assert_eq!(options.multiplication, MulSymbol::Asterisk);
+    }
+}
@@ -1,17 +1,30 @@
+mod args;
 mod unicode;
 
+use args::{Cli, Command, Format, InputSyntax, MulSign};
 use arithma::simplify::Simplifiable;
 use arithma::status::{ProofCertificate, StatusReport};
 use arithma::tokenizer::normalize_var;
 use arithma::{
-    build_expression_tree, parse_latex, parse_latex_raw, Environment, Evaluator, Node, Tokenizer,
+    build_expression_tree, parse_asciimath, parse_latex, parse_latex_raw, parse_plain,
+    ArithmaError, Environment, Evaluator, FormatOptions, MulSymbol, Node, NumberStyle, Session,
+    SessionOutcome, Tokenizer,
 };
+use clap::Parser;
+use clap::ValueEnum;
+use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Editor, Helper};
+use std::cell::{Cell, RefCell};
 use std::io::IsTerminal;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-static LATEX_OUTPUT: AtomicBool = AtomicBool::new(false);
 static USE_COLOR: AtomicBool = AtomicBool::new(false);
 
 mod ansi {
@@ -22,18 +35,72 @@ mod ansi {
     pub const CYAN: &str = "\x1b[36m";
 }
 
+thread_local! {
+    /// Results printed by the REPL command being run, for `ans`
+    static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+    /// How results are printed, and how their numbers and products are
+    /// written
+    static OUTPUT_FORMAT: Cell<Format> = const { Cell::new(Format::Plain) };
+    static NUMBER_FORMAT: Cell<FormatOptions> = Cell::new(FormatOptions::default());
+    /// How inputs are written
+    static INPUT_SYNTAX: Cell<InputSyntax> = const { Cell::new(InputSyntax::Latex) };
+}
+
 fn color_enabled() -> bool {
     USE_COLOR.load(Ordering::Relaxed)
 }
 
 fn output(s: &str) {
-    if LATEX_OUTPUT.load(Ordering::Relaxed) {
-        println!("{s}");
+    CAPTURED.with(|c| {
+        if let Some(lines) = c.borrow_mut().as_mut() {
+            lines.push(s.to_string());
+        }
+    });
+    let options = NUMBER_FORMAT.get();
+    // Results are LaTeX; other forms and options re-render the parsed
+    // result, and text that does not parse (prose, lists) is kept as is
+    let restyled = |write: &dyn Fn(&Node) -> String| {
+        parse_latex_raw(s)
+            .ok()
+            .map(|node| write(&node))
+            .unwrap_or_else(|| s.to_string())
+    };
+    let latex = if options == FormatOptions::default() {
+        s.to_string()
     } else {
-        println!("{}", unicode::latex_to_unicode(s));
+        restyled(&|node| node.to_latex_with(&options))
+    };
+    match OUTPUT_FORMAT.get() {
+        Format::Latex => println!("{latex}"),
+        Format::Plain => println!("{}", unicode::latex_to_unicode(&latex)),
+        Format::Ascii => match parse_latex_raw(s) {
+            Ok(node) => println!("{}", node.to_plain_string_with(&options)),
+            Err(_) => println!("{}", unicode::latex_to_unicode(&latex)),
+        },
     }
 }
 
+fn set_output_format(format: Format) {
+    OUTPUT_FORMAT.set(format);
+    print_output_format();
+}
+
+fn print_input_syntax() {
+    print_note(match INPUT_SYNTAX.get() {
+        InputSyntax::Latex => "Input: LaTeX",
+        InputSyntax::Plain => "Input: plain (sin(x)^2 + 3*x)",
+        InputSyntax::Asciimath => "Input: AsciiMath (sqrt x, root(3)(x), sum_(k=1)^n k)",
+    });
+}
+
+fn print_output_format() {
+    print_note(match OUTPUT_FORMAT.get() {
+        Format::Latex => "Output: LaTeX",
+        Format::Plain => "Output: Unicode",
+        Format::Ascii => "Output: ASCII",
+    });
+}
+
 fn print_error(msg: &str) {
     if color_enabled() {
         println!("{}{}{}{}", ansi::RED, ansi::BOLD, msg, ansi::RESET);
@@ -53,130 +120,141 @@ fn print_note(msg: &str) {
 fn main() {
     env_logger::init();
 
-    let raw_args: Vec<String> = std::env::args().collect();
-    let is_tty = std::io::stdout().is_terminal();
-    let force_unicode = raw_args.iter().any(|a| a == "--unicode");
-    if !force_unicode && (raw_args.iter().any(|a| a == "--latex") || !is_tty) {
-        LATEX_OUTPUT.store(true, Ordering::Relaxed);
+    // Natural notation (pi, inf) is read as LaTeX in every argument;
+    // plain input spells them itself
+    let args: Vec<String> = std::env::args().collect();
+    let latex = !args.iter().enumerate().any(|(i, a)| {
+        let value = a
+            .strip_prefix("--input=")
+            .or_else(|| (a == "--input").then(|| args.get(i + 1).map_or("", |v| v.as_str())));
+        matches!(value, Some("plain" | "asciimath"))
+    });
+    let mut cli = Cli::parse_from(args.into_iter().enumerate().map(|(i, a)| {
+        if i >= 1 && latex {
+            preprocess_input(&a)
+        } else {
+            a
+        }
+    }));
+    let input = cli.input.unwrap_or_default();
+    INPUT_SYNTAX.set(input);
+    if input != InputSyntax::Latex {
+        if let Some(command) = cli.command.as_mut() {
+            for text in command.expressions_mut() {
+                *text = input_to_latex(text);
+            }
+        }
     }
+    let is_tty = std::io::stdout().is_terminal();
+    let format =
+        cli.requested_format()
+            .unwrap_or(if is_tty { Format::Plain } else { Format::Latex });
+    OUTPUT_FORMAT.set(format);
+    NUMBER_FORMAT.set(cli.format_options());
     if is_tty && std::env::var_os("NO_COLOR").is_none() {
         USE_COLOR.store(true, Ordering::Relaxed);
     }
-    let args: Vec<String> = raw_args
-        .into_iter()
-        .filter(|a| a != "--latex" && a != "--unicode")
-        .enumerate()
-        .map(|(i, a)| if i >= 2 { preprocess_input(&a) } else { a })
-        .collect();
 
-    if args.len() < 2 {
+    let Some(command) = cli.command else {
         repl();
         return;
+    };
+    match command {
+        Command::Format { expr } => cmd_format(&expr),
+        Command::Simplify { expr } => cmd_simplify(&expr),
+        Command::Differentiate { expr, var_arg, var } => {
+            cmd_differentiate(&expr, &variable(var, var_arg, "x"))
+        }
+        Command::Integrate {
+            expr,
+            var_arg,
+            lower_arg,
+            upper_arg,
+            var,
+            from,
+            to,
+        } => {
+            let bounds = from.zip(to).or(lower_arg.zip(upper_arg));
+            cmd_integrate(&expr, &variable(var, var_arg, "x"), bounds)
+        }
+        Command::Solve {
+            equation,
+            var_arg,
+            for_var,
+        } => cmd_solve(&equation, for_var.or(var_arg)),
+        Command::Factor { expr, var_arg, var } => cmd_factor(&expr, &variable(var, var_arg, "x")),
+        Command::PrimeFactorize { n } => cmd_prime_factorize(&n),
+        Command::PartialFractions {
+            numerator,
+            denominator,
+            var_arg,
+            var,
+        } => cmd_partial_fractions(&numerator, &denominator, &variable(var, var_arg, "x")),
+        Command::PolyDiv {
+            numerator,
+            denominator,
+            var_arg,
+            var,
+        } => cmd_poly_div(&numerator, &denominator, &variable(var, var_arg, "x")),
+        Command::Evaluate { expr, values } => cmd_evaluate(&expr, &values),
+        Command::Limit {
+            expr,
+            var_arg,
+            point,
+            var,
+        } => cmd_limit(
+            &expr,
+            &variable(var, var_arg, "x"),
+            point.as_deref().unwrap_or("0"),
+        ),
+        Command::Taylor {
+            expr,
+            var_arg,
+            center,
+            order,
+            var,
+        } => cmd_taylor(
+            &expr,
+            &variable(var, var_arg, "x"),
+            center.as_deref().unwrap_or("0"),
+            order.and_then(|s| s.parse().ok()).unwrap_or(5),
+        ),
+        Command::Expectation {
+            expr,
+            distribution,
+            var_arg,
+            var,
+        } => cmd_distribution(
+            &expr,
+            &distribution,
+            &variable(var, var_arg, "x"),
+            arithma::probability::expectation_latex,
+        ),
+        Command::Variance {
+            expr,
+            distribution,
+            var_arg,
+            var,
+        } => cmd_distribution(
+            &expr,
+            &distribution,
+            &variable(var, var_arg, "x"),
+            arithma::probability::variance_latex,
+        ),
+        Command::Substitute { expr, var, value } => cmd_substitute(&expr, &var, &value),
+        Command::Ode { cc, system, args } => cmd_ode(cc, system, &args),
     }
-
-    let cmd = args[1].as_str();
-
-    if cmd == "--help" || cmd == "-h" || cmd == "help" {
-        print_help();
-        return;
-    }
-
-    if cmd == "--version" || cmd == "-V" {
-        println!("arithma {}", env!("CARGO_PKG_VERSION"));
-        return;
-    }
-
-    match cmd {
-        "format" => cmd_format(cmd, &args[2..]),
-        "simplify" => cmd_simplify(cmd, &args[2..]),
-        "differentiate" | "diff" => cmd_differentiate(cmd, &args[2..]),
-        "integrate" => cmd_integrate(cmd, &args[2..]),
-        "solve" => cmd_solve(cmd, &args[2..]),
-        "factor" => cmd_factor(cmd, &args[2..]),
-        "prime-factorize" | "factorint" => cmd_prime_factorize(cmd, &args[2..]),
-        "partial-fractions" | "pf" => cmd_partial_fractions(cmd, &args[2..]),
-        "evaluate" | "eval" => cmd_evaluate(cmd, &args[2..]),
-        "limit" => cmd_limit(cmd, &args[2..]),
-        "taylor" => cmd_taylor(cmd, &args[2..]),
-        "substitute" | "sub" => cmd_substitute(cmd, &args[2..]),
-        "ode" => cmd_ode(cmd, &args[2..]),
-        _ => {
-            eprintln!("Unknown command: {}", cmd);
-            eprintln!("Run 'arithma --help' for usage.");
-            std::process::exit(1);
-        }
-    }
-}
-
-fn print_help() {
-    println!(
-        "\
-Arithma — exact computer algebra from the command line
-
-Usage: arithma [command] [arguments]
-       arithma              (launches interactive REPL)
-
-Commands:
-  format <expr>                      Parse and print canonical LaTeX (no simplify)
-  simplify <expr>                    Simplify an expression
-  differentiate <expr> [var]         Differentiate (alias: diff)
-  integrate <expr> [var] [lo hi]      Integral (definite with bounds)
-  solve <equation> [var]             Solve an equation
-  solve \"eq1, eq2\" \"x, y\"           Solve a system of linear equations
-  factor <expr> [var]                Factor a polynomial over Q
-  prime-factorize <n>                Prime-factorize a positive integer (alias: factorint)
-  partial-fractions <n> <d> [var]    Partial fraction decomposition (alias: pf)
-  evaluate <expr> [var=val ...]      Evaluate numerically (alias: eval)
-  limit <expr> [var] [point]         Compute a limit
-  taylor <expr> [var] [center] [n]   Taylor series expansion
-  substitute <expr> <var> <value>    Substitute a value for a variable (alias: sub)
-  ode <rhs> [indep] [dep]            Solve first-order ODE: dy/dx = rhs
-  ode --cc <a> <b> <c> [indep]       Solve ay''+by'+cy=0
-
-Options:
-  --latex                          Output raw LaTeX (default when piped)
-  --unicode                        Output Unicode (default in terminal)
-
-All expressions accept LaTeX or natural notation (pi, inf, sqrt, sin, etc.).
-
-Examples:
-  arithma simplify 'x^2 + 2x + 1'
-  arithma diff 'sin(x^2)' x
-  arithma integrate '3x^2' x
-  arithma integrate '1/(x^2+1)' x 0 1
-  arithma solve 'x^2 - 4 = 0'
-  arithma factor 'x^4 - 1'
-  arithma prime-factorize 720
-  arithma eval 'x^2 + 1' x=3
-  arithma limit 'sin(x)/x' x 0
-  arithma taylor 'sin(x)' x 0 5
-  arithma ode --cc 1 0 1"
-    );
 }
 
-const NONE: &[&str] = &[];
-
-/// Print CLI usage and exit.
-///
-/// - `syntax` — primary fragment after `arithma {cmd}`
-/// - `alternates` — other valid invocations (each prefixed with `arithma {cmd}`); pass `NONE` if none
-/// - `hints` — free-form notes (indented, no command prefix); pass `NONE` if none
-fn usage(cmd: &str, syntax: &str, alternates: &[&str], hints: &[&str]) -> ! {
-    eprintln!("Usage: arithma {cmd} {syntax}");
-    for alt in alternates {
-        eprintln!("       arithma {cmd} {alt}");
-    }
-    for hint in hints {
-        eprintln!("  {hint}");
-    }
-    std::process::exit(1);
+/// The variable a subcommand works in: the flag, else the positional
+/// argument, else `default`.
+fn variable(flag: Option<String>, positional: Option<String>, default: &str) -> String {
+    flag.or(positional)
+        .map(|v| normalize_var(&v))
+        .unwrap_or_else(|| default.to_string())
 }
 
-fn cmd_format(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(cmd, "<expr>", NONE, NONE);
-    }
-    let expr = &args[0];
+fn cmd_format(expr: &str) {
     match parse_latex_raw(expr).map(|node| format!("{node}")) {
         Ok(result) => output(&result),
         Err(e) => {
@@ -186,11 +264,7 @@ fn cmd_format(cmd: &str, args: &[String]) {
     }
 }
 
-fn cmd_simplify(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(cmd, "<expr>", NONE, NONE);
-    }
-    let expr = &args[0];
+fn cmd_simplify(expr: &str) {
     let env = Environment::new();
     match parse_latex(expr, &env).map(|node| format!("{node}")) {
         Ok(result) => output(&result),
@@ -201,17 +275,24 @@ fn cmd_simplify(cmd: &str, args: &[String]) {
     }
 }
 
-fn cmd_differentiate(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(cmd, "<expr> [var]", NONE, NONE);
-    }
-    let expr = &args[0];
-    let var = args
-        .get(1)
-        .map(|s| normalize_var(s))
-        .unwrap_or_else(|| "x".to_string());
-    match arithma::derivative::differentiate_latex(expr, &var) {
-        Ok(result) => output(&result),
+/// Where a derivative taken through a floor, sign or other step function
+/// does not hold, e.g. `valid except where \floor{x} jumps`.
+fn step_function_note(expr: &str, var: &str) -> Option<String> {
+    let parsed = parse_latex_raw(expr).ok()?;
+    arithma::derivative::differentiate_with_domain(&parsed, var)
+        .ok()?
+        .domain_note()
+        .map(|note| unicode::latex_to_unicode(&note))
+}
+
+fn cmd_differentiate(expr: &str, var: &str) {
+    match arithma::derivative::differentiate_latex(expr, var) {
+        Ok(result) => {
+            output(&result);
+            if let Some(note) = step_function_note(expr, var) {
+                eprintln!("Note: {note}");
+            }
+        }
         Err(e) => {
             eprintln!("Error: {}", e);
             std::process::exit(1);
@@ -219,14 +300,13 @@ fn cmd_differentiate(cmd: &str, args: &[String]) {
     }
 }
 
-/// Render a NON_ELEMENTARY error as the status marker, so the CLI and the
-/// MCP server present impossibility identically (docs/result-status.md).
-/// When the antiderivative is a recognized special function (erf, Ei, li),
-/// the marker also names the form — strictly more information than the
-/// impossibility alone.
-fn non_elementary_marker(e: &str, integrand_latex: &str, var: &str) -> String {
-    let reason = e.replacen("NON_ELEMENTARY: ", "", 1);
-    let proof = ProofCertificate::non_elementary(&reason);
+/// Render a non-elementary integrand's certificate as the status marker, so
+/// the CLI and the MCP server present impossibility identically
+/// (docs/result-status.md). When the antiderivative is a recognized special
+/// function (erf, Ei, li), the marker also names the form — strictly more
+/// information than the impossibility alone.
+fn non_elementary_marker(reason: &str, integrand_latex: &str, var: &str) -> String {
+    let proof = ProofCertificate::non_elementary(reason);
     let status = StatusReport::provably_impossible(proof);
     let status =
         match arithma::special_functions::recognize_special_form_latex(integrand_latex, var) {
@@ -238,23 +318,12 @@ fn non_elementary_marker(e: &str, integrand_latex: &str, var: &str) -> String {
         .expect("provably_impossible always has a marker")
 }
 
-fn cmd_integrate(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(cmd, "<expr> [var] [lower upper]", NONE, NONE);
-    }
-    let expr = &args[0];
-    let var = args
-        .get(1)
-        .map(|s| normalize_var(s))
-        .unwrap_or_else(|| "x".to_string());
-
-    if args.len() >= 4 {
-        let lower = &args[2];
-        let upper = &args[3];
-        match arithma::integration::definite_integral_exact_latex(expr, &var, lower, upper) {
+fn cmd_integrate(expr: &str, var: &str, bounds: Option<(String, String)>) {
+    if let Some((lower, upper)) = bounds {
+        match arithma::integration::definite_integral_exact_latex(expr, var, &lower, &upper) {
             Ok(result) => output(&result),
-            Err(e) if e.starts_with("NON_ELEMENTARY:") => {
-                output(&non_elementary_marker(&e, expr, &var));
+            Err(ArithmaError::NonElementary(reason)) => {
+                output(&non_elementary_marker(&reason, expr, var));
             }
             Err(e) => {
                 eprintln!("Error: {}", e);
@@ -264,10 +333,10 @@ fn cmd_integrate(cmd: &str, args: &[String]) {
         return;
     }
 
-    match arithma::integration::integrate_latex(expr, &var) {
+    match arithma::integration::integrate_latex(expr, var) {
         Ok(result) => output(&result),
-        Err(e) if e.starts_with("NON_ELEMENTARY:") => {
-            output(&non_elementary_marker(&e, expr, &var));
+        Err(ArithmaError::NonElementary(reason)) => {
+            output(&non_elementary_marker(&reason, expr, var));
         }
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -276,18 +345,9 @@ fn cmd_integrate(cmd: &str, args: &[String]) {
     }
 }
 
-fn cmd_solve(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(
-            cmd,
-            "<equation> [var]",
-            &["\"eq1, eq2, ...\" \"x, y, ...\""],
-            NONE,
-        );
-    }
-    let equation = &args[0];
-
-    if let Some(vars_arg) = args.get(1) {
+/// `vars` is one variable, or comma-separated ones for a system.
+fn cmd_solve(equation: &str, vars: Option<String>) {
+    if let Some(vars_arg) = &vars {
         let vars: Vec<String> = vars_arg
             .split(',')
             .map(|s| normalize_var(s.trim()))
@@ -298,10 +358,7 @@ fn cmd_solve(cmd: &str, args: &[String]) {
         }
     }
 
-    let var = args
-        .get(1)
-        .map(|s| normalize_var(s))
-        .unwrap_or_else(|| "x".to_string());
+    let var = variable(None, vars, "x");
 
     let mut tokenizer = Tokenizer::new(equation);
     let tokens = tokenizer.tokenize();
@@ -318,8 +375,8 @@ fn cmd_solve(cmd: &str, args: &[String]) {
         expr,
         Node::Greater(_, _) | Node::GreaterEqual(_, _) | Node::Less(_, _) | Node::LessEqual(_, _)
     ) {
-        match arithma::solve_inequality(&expr, &var) {
-            Ok(result) => output(&result),
+        match arithma::solve_inequality_set(&expr, &var) {
+            Ok(set) => output(&set.to_latex_in(&var)),
             Err(e) => {
                 eprintln!("Error: {}", e);
                 std::process::exit(1);
@@ -330,7 +387,18 @@ fn cmd_solve(cmd: &str, args: &[String]) {
 
     match arithma::expression::solve_full(&expr, &var) {
         Ok(result) => {
-            if result.solutions.is_empty() && result.complex_omitted > 0 {
+            if !result.complex_solutions.is_empty() {
+                if result.solutions.is_empty() {
+                    println!("No real solutions");
+                }
+                for s in result.solutions.iter().chain(&result.complex_solutions) {
+                    output(&format!("{var} = {s}"));
+                }
+            } else if let (true, Some(reason)) =
+                (result.solutions.is_empty(), &result.impossibility_reason)
+            {
+                println!("{reason}");
+            } else if result.solutions.is_empty() && result.complex_omitted > 0 {
                 println!(
                     "No real solutions ({} complex root{} omitted)",
                     result.complex_omitted,
@@ -352,9 +420,29 @@ fn cmd_solve(cmd: &str, args: &[String]) {
             }
         }
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            if !print_numeric_roots(&expr, &var) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// When an equation has no symbolic solution, look for its real roots
+/// numerically on a fixed window. Returns false if there are none.
+fn print_numeric_roots(expr: &Node, var: &str) -> bool {
+    const WINDOW: f64 = 100.0;
+    match arithma::find_roots_in_interval(expr, var, -WINDOW, WINDOW) {
+        Ok(roots) if !roots.is_empty() => {
+            print_note(&format!(
+                "No closed form; roots found numerically in [-{WINDOW}, {WINDOW}]:"
+            ));
+            for root in roots {
+                output(&format!("{var} \\approx {root}"));
+            }
+            true
         }
+        _ => false,
     }
 }
 
@@ -414,16 +502,7 @@ fn cmd_solve_system(equations_str: &str, vars: &[String]) {
     }
 }
 
-fn cmd_factor(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(cmd, "<expr> [var]", NONE, NONE);
-    }
-    let expr = &args[0];
-    let var = args
-        .get(1)
-        .map(|s| normalize_var(s))
-        .unwrap_or_else(|| "x".to_string());
-
+fn cmd_factor(expr: &str, var: &str) {
     let mut tokenizer = Tokenizer::new(expr);
     let tokens = tokenizer.tokenize();
     let node = match build_expression_tree(tokens) {
@@ -434,7 +513,7 @@ fn cmd_factor(cmd: &str, args: &[String]) {
         }
     };
 
-    let poly = match arithma::polynomial::Polynomial::from_node(&node, &var) {
+    let poly = match arithma::polynomial::Polynomial::from_node(&node, var) {
         Ok(p) => p,
         Err(e) => {
             eprintln!("Not a polynomial: {}", e);
@@ -442,53 +521,35 @@ fn cmd_factor(cmd: &str, args: &[String]) {
         }
     };
 
-    let (content, factors) = arithma::mod_poly::factor_over_q(&poly);
+    let factorization = arithma::factor_polynomial(&poly);
 
     let mut parts: Vec<String> = Vec::new();
-
-    let content_node = arithma::Node::Num(arithma::ExactNum::rational(
-        content.numer().try_into().unwrap_or(1),
-        content.denom().try_into().unwrap_or(1),
-    ));
+    let content_node =
+        arithma::Node::Num(arithma::ExactNum::Rational(factorization.content.clone()));
     let content_str = format!("{}", content_node);
     if content_str != "1" {
         parts.push(content_str);
     }
-
-    let mut grouped: Vec<(String, usize)> = Vec::new();
-    for f in &factors {
-        let s = format!("{}", f);
-        if let Some(entry) = grouped.iter_mut().find(|(fs, _)| *fs == s) {
-            entry.1 += 1;
-        } else {
-            grouped.push((s, 1));
-        }
-    }
-
-    for (f_str, m) in &grouped {
+    for (f, m) in &factorization.factors {
         if *m == 1 {
-            parts.push(format!("({})", f_str));
+            parts.push(format!("({})", f));
         } else {
-            parts.push(format!("({})^{}", f_str, m));
+            parts.push(format!("({})^{}", f, m));
         }
     }
 
     if parts.is_empty() {
         output("1");
     } else {
-        let result = parts.join(" * ");
-        output(&result);
-        if factors.len() == 1 && factors[0].degree().unwrap_or(0) > 1 {
+        output(&parts.join(" * "));
+        if factorization.is_irreducible() {
             output("(irreducible over \\mathbb{Q})");
         }
     }
 }
 
-fn cmd_prime_factorize(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(cmd, "<n>", NONE, NONE);
-    }
-    let n = match arithma::parse_non_negative_integer(&args[0]) {
+fn cmd_prime_factorize(n: &str) {
+    let n = match arithma::parse_non_negative_integer(n) {
         Some(n) => n,
         None => {
             eprintln!("Error: expected a non-negative integer");
@@ -504,17 +565,8 @@ fn cmd_prime_factorize(cmd: &str, args: &[String]) {
     }
 }
 
-fn cmd_partial_fractions(cmd: &str, args: &[String]) {
-    if args.len() < 2 {
-        usage(cmd, "<numerator> <denominator> [var]", NONE, NONE);
-    }
-    let num = &args[0];
-    let den = &args[1];
-    let var = args
-        .get(2)
-        .map(|s| normalize_var(s))
-        .unwrap_or_else(|| "x".to_string());
-    match arithma::partial_fractions::partial_fractions_latex(num, den, &var) {
+fn cmd_partial_fractions(num: &str, den: &str, var: &str) {
+    match arithma::partial_fractions::partial_fractions_latex(num, den, var) {
         Ok(result) => output(&result),
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -523,12 +575,24 @@ fn cmd_partial_fractions(cmd: &str, args: &[String]) {
     }
 }
 
-fn cmd_evaluate(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(cmd, "<expr> [var=val ...]", NONE, NONE);
+fn cmd_poly_div(num: &str, den: &str, var: &str) {
+    match arithma::poly_div_latex(num, den, var) {
+        Ok((quotient, remainder)) => print_quotient(&quotient, &remainder),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
     }
-    let expr_str = &args[0];
+}
+
+/// The quotient on its own line, then the remainder.
+fn print_quotient(quotient: &str, remainder: &str) {
+    output(quotient);
+    print!("remainder ");
+    output(remainder);
+}
 
+fn cmd_evaluate(expr_str: &str, values: &[String]) {
     let mut tokenizer = Tokenizer::new(expr_str);
     let tokens = tokenizer.tokenize();
     let expr = match build_expression_tree(tokens) {
@@ -543,7 +607,7 @@ fn cmd_evaluate(cmd: &str, args: &[String]) {
     let simplified = expr.simplify(&env_s).unwrap_or_else(|_| expr.clone());
 
     let mut env = Environment::new();
-    for arg in &args[1..] {
+    for arg in values {
         if let Some((var, val_str)) = arg.split_once('=') {
             if let Ok(val) = val_str.parse::<f64>() {
                 if val == val.floor() && val.abs() < 1e15 {
@@ -558,31 +622,55 @@ fn cmd_evaluate(cmd: &str, args: &[String]) {
         }
     }
 
-    match Evaluator::evaluate_exact(&simplified, &env) {
-        Ok(val) => output(&format!("{}", arithma::Node::Num(val))),
+    let shown = with_progress_bar(|| match Evaluator::evaluate_exact(&simplified, &env) {
+        Ok(val) => format!("{}", arithma::Node::Num(val)),
         Err(_) => match Evaluator::evaluate(&simplified, &env) {
-            Ok(val) => output(&format!("{val}")),
-            Err(_) => output(&format!("{simplified}")),
+            Ok(val) => format!("{val}"),
+            Err(_) => format!("{simplified}"),
         },
-    }
+    });
+    output(&shown);
 }
 
-fn cmd_limit(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(
-            cmd,
-            "<expr> [var] [point]",
-            NONE,
-            &["point: number, inf, -inf, or one-sided (0+, 0-, 3+, 3-)"],
-        );
+/// How long a job runs before its progress bar appears, so quick
+/// evaluations never flash one.
+const PROGRESS_DELAY: Duration = Duration::from_millis(250);
+const PROGRESS_WIDTH: usize = 30;
+
+/// Run `work`, drawing a progress bar on stderr (when it is a terminal)
+/// for a long sum, product or numeric integral inside it. The bar is
+/// erased before returning.
+fn with_progress_bar<T>(work: impl FnOnce() -> T) -> T {
+    if !std::io::stderr().is_terminal() {
+        return work();
+    }
+    let started = Instant::now();
+    let drawn = Rc::new(Cell::new(false));
+    let bar_drawn = Rc::clone(&drawn);
+    let result = arithma::with_progress(
+        move |p| {
+            if started.elapsed() < PROGRESS_DELAY {
+                return;
+            }
+            let filled = (p.percent() / 100.0 * PROGRESS_WIDTH as f64) as usize;
+            eprint!(
+                "\r[{}{}] {:3.0}%",
+                "#".repeat(filled),
+                " ".repeat(PROGRESS_WIDTH - filled),
+                p.percent()
+            );
+            bar_drawn.set(true);
+        },
+        work,
+    );
+    if drawn.get() {
+        eprint!("\r\x1b[2K");
     }
-    let expr = &args[0];
-    let var = args
-        .get(1)
-        .map(|s| normalize_var(s))
-        .unwrap_or_else(|| "x".to_string());
-    let point_str = args.get(2).map(|s| s.as_str()).unwrap_or("0");
-    match arithma::limits::limit_latex_str(expr, &var, point_str) {
+    result
+}
+
+fn cmd_limit(expr: &str, var: &str, point: &str) {
+    match arithma::limits::limit_latex_str(expr, var, point) {
         Ok(result) => output(&result),
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -591,24 +679,9 @@ fn cmd_limit(cmd: &str, args: &[String]) {
     }
 }
 
-fn cmd_taylor(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(cmd, "<expr> [var] [center] [order]", NONE, NONE);
-    }
-    let expr = &args[0];
-    let var = args
-        .get(1)
-        .map(|s| normalize_var(s))
-        .unwrap_or_else(|| "x".to_string());
-    let order = args
-        .get(3)
-        .and_then(|s| s.parse::<usize>().ok())
-        .unwrap_or(5);
-
-    let center_str = args.get(2).map(|s| s.as_str()).unwrap_or("0");
-
+fn cmd_taylor(expr: &str, var: &str, center_str: &str, order: usize) {
     if let Ok(center_f64) = center_str.parse::<f64>() {
-        match arithma::series::taylor_series_latex(expr, &var, center_f64, order) {
+        match arithma::series::taylor_series_latex(expr, var, center_f64, order) {
             Ok(result) => output(&result),
             Err(e) => {
                 eprintln!("Error: {}", e);
@@ -617,7 +690,7 @@ fn cmd_taylor(cmd: &str, args: &[String]) {
         }
     } else {
         let center_normalized = normalize_var(center_str);
-        match arithma::series::taylor_series_latex_symbolic(expr, &var, &center_normalized, order) {
+        match arithma::series::taylor_series_latex_symbolic(expr, var, &center_normalized, order) {
             Ok(result) => output(&result),
             Err(e) => {
                 eprintln!("Error: {}", e);
@@ -627,13 +700,25 @@ fn cmd_taylor(cmd: &str, args: &[String]) {
     }
 }
 
-fn cmd_substitute(cmd: &str, args: &[String]) {
-    if args.len() < 3 {
-        usage(cmd, "<expr> <var> <value>", NONE, NONE);
+/// `expectation` and `variance`: `f` of a random variable with the given
+/// outcomes.
+fn cmd_distribution(
+    expr: &str,
+    distribution: &str,
+    var: &str,
+    compute: fn(&str, &str, &str) -> Result<String, String>,
+) {
+    match compute(expr, var, distribution) {
+        Ok(result) => output(&result),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
     }
-    let expr = &args[0];
-    let var = normalize_var(&args[1]);
-    let value = &args[2];
+}
+
+fn cmd_substitute(expr: &str, var: &str, value: &str) {
+    let var = normalize_var(var);
     let subs = vec![(var, value.to_string())];
     match arithma::substitute::substitute_latex(expr, &subs) {
         Ok(result) => {
@@ -650,59 +735,27 @@ fn cmd_substitute(cmd: &str, args: &[String]) {
     }
 }
 
-fn cmd_ode(cmd: &str, args: &[String]) {
-    if args.is_empty() {
-        usage(
-            cmd,
-            "<rhs> [indep] [dep]",
-            &["--cc <a> <b> <c> [indep]"],
-            NONE,
-        );
-    }
-
-    if args[0] == "--cc" {
-        if args.len() < 4 {
-            usage(cmd, "--cc <a> <b> <c> [indep]", NONE, NONE);
-        }
-        let a: f64 = args[1].parse().unwrap_or_else(|_| {
-            eprintln!("Invalid coefficient a: {}", args[1]);
-            std::process::exit(1);
-        });
-        let b: f64 = args[2].parse().unwrap_or_else(|_| {
-            eprintln!("Invalid coefficient b: {}", args[2]);
-            std::process::exit(1);
-        });
-        let c: f64 = args[3].parse().unwrap_or_else(|_| {
-            eprintln!("Invalid coefficient c: {}", args[3]);
-            std::process::exit(1);
-        });
-        let indep = args
-            .get(4)
-            .map(|s| normalize_var(s))
-            .unwrap_or_else(|| "x".to_string());
-        match arithma::ode::solve_constant_coeff_latex(a, b, c, &indep) {
-            Ok(result) => output(&result),
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
-        }
+/// `args` is `rhs [indep] [dep]`, or just `[indep]` after `--cc` or
+/// `--system`.
+fn cmd_ode(cc: Option<Vec<f64>>, system: Option<String>, args: &[String]) {
+    let var = |i: usize, default: &str| variable(None, args.get(i).cloned(), default);
+    let result = if let Some(matrix) = system {
+        arithma::ode::solve_constant_coeff_system_latex(&matrix, &var(0, "t"))
+    } else if let Some([a, b, c]) = cc.as_deref() {
+        arithma::ode::solve_constant_coeff_latex(*a, *b, *c, &var(0, "x"))
+    } else if let Some(rhs) = args.first() {
+        arithma::ode::solve_ode_latex(rhs, &var(1, "x"), &var(2, "y"))
     } else {
-        let rhs = &args[0];
-        let indep = args
-            .get(1)
-            .map(|s| normalize_var(s))
-            .unwrap_or_else(|| "x".to_string());
-        let dep = args
-            .get(2)
-            .map(|s| normalize_var(s))
-            .unwrap_or_else(|| "y".to_string());
-        match arithma::ode::solve_ode_latex(rhs, &indep, &dep) {
-            Ok(result) => output(&result),
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
+        eprintln!("Usage: arithma ode <rhs> [indep] [dep]");
+        eprintln!("       arithma ode --cc <a> <b> <c> [indep]");
+        eprintln!("       arithma ode --system <matrix> [indep]");
+        std::process::exit(1);
+    };
+    match result {
+        Ok(result) => output(&result),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
         }
     }
 }
@@ -732,6 +785,46 @@ fn split_args(input: &str) -> Vec<String> {
     args
 }
 
+/// Plain or AsciiMath input as LaTeX for the commands to parse. Text the
+/// selected parser rejects is passed on unchanged, so the LaTeX parser
+/// reports the error or reads a form the syntax lacks (a list of
+/// equations, `0+`).
+fn input_to_latex(input: &str) -> String {
+    let parsed = match INPUT_SYNTAX.get() {
+        InputSyntax::Latex => return input.to_string(),
+        InputSyntax::Plain => parse_plain(input),
+        InputSyntax::Asciimath => parse_asciimath(input),
+    };
+    match parsed {
+        Ok(node) => node.to_latex(),
+        Err(_) => input.to_string(),
+    }
+}
+
+/// A REPL line in plain or AsciiMath syntax as LaTeX: a command's
+/// arguments one by one, anything else whole.
+fn line_to_latex(line: &str) -> String {
+    let (cmd, rest) = match line.find(char::is_whitespace) {
+        Some(pos) => (&line[..pos], line[pos..].trim_start()),
+        None => (line, ""),
+    };
+    if !REPL_COMMANDS.contains(&cmd) {
+        return input_to_latex(line);
+    }
+    let args: Vec<String> = split_args(rest)
+        .iter()
+        .map(|arg| {
+            let latex = input_to_latex(arg);
+            if latex.contains(char::is_whitespace) {
+                format!("\"{latex}\"")
+            } else {
+                latex
+            }
+        })
+        .collect();
+    format!("{cmd} {}", args.join(" "))
+}
+
 /// Replace natural math notation with LaTeX equivalents.
 /// Converts standalone `pi` → `\pi`, `inf`/`infinity` → `\infty`.
 fn preprocess_input(input: &str) -> String {
@@ -803,11 +896,26 @@ Commands:
   ode --cc <a> <b> <c>             Solve ay'' + by' + cy = 0
   factorint <n>                    Prime factorization
   pf <num> <den> [var]             Partial fractions
+  polydiv <num> <den> [var]        Quotient and remainder
   format <expr>                    Show canonical LaTeX
+  <name> = <expr>                  Bind a variable for later inputs
+  ans                              The previous result
+  :vars                            List bound variables
+  :clear [name ...]                Unbind the named variables (default: all)
+  :latex [on|off]                  Print raw LaTeX or readable output
+  :format [latex|unicode|ascii]    Output form; ascii reads back as input
+  :precision [<digits>|off]        Significant digits of decimals
+  :decimal [on|off]                Write fractions as decimals
+  :mul [cdot|times|star]           Multiplication sign
+  :input [latex|plain|asciimath]   Input syntax; plain reads 3*x**2 + sqrt(x)
+  :help, :quit
 
 Or type any expression to simplify and evaluate.
 Constants: pi (= π), inf (= ∞). LaTeX notation also accepted.
-Toggle output: 'latex' for raw LaTeX, 'unicode' for readable output."
+Integers: gcd(a, b), lcm(a, b), a mod b, powmod(b, e, m), isprime(n),
+factorint(n).
+An input with unclosed brackets, or a line ending in \\, continues on the
+next line. Ctrl-C discards the current input; Ctrl-D quits."
     );
 }
 
@@ -833,7 +941,12 @@ fn repl_diff(rest: &str) {
         .map(|s| normalize_var(s))
         .unwrap_or_else(|| "x".into());
     match arithma::derivative::differentiate_latex(args[0], &var) {
-        Ok(r) => output(&r),
+        Ok(r) => {
+            output(&r);
+            if let Some(note) = step_function_note(args[0], &var) {
+                print_note(&note);
+            }
+        }
         Err(e) => print_error(&format!("Error: {e}")),
     }
 }
@@ -849,16 +962,16 @@ fn repl_integrate(rest: &str) {
     if args.len() >= 4 {
         match arithma::integration::definite_integral_exact_latex(expr, &var, args[2], args[3]) {
             Ok(r) => output(&r),
-            Err(e) if e.starts_with("NON_ELEMENTARY:") => {
-                output(&non_elementary_marker(&e, expr, &var));
+            Err(ArithmaError::NonElementary(reason)) => {
+                output(&non_elementary_marker(&reason, expr, &var));
             }
             Err(e) => print_error(&format!("Error: {e}")),
         }
     } else {
         match arithma::integration::integrate_latex(expr, &var) {
             Ok(r) => output(&r),
-            Err(e) if e.starts_with("NON_ELEMENTARY:") => {
-                output(&non_elementary_marker(&e, expr, &var));
+            Err(ArithmaError::NonElementary(reason)) => {
+                output(&non_elementary_marker(&reason, expr, &var));
             }
             Err(e) => print_error(&format!("Error: {e}")),
         }
@@ -900,8 +1013,8 @@ fn repl_solve(rest: &str) {
         expr,
         Node::Greater(_, _) | Node::GreaterEqual(_, _) | Node::Less(_, _) | Node::LessEqual(_, _)
     ) {
-        match arithma::solve_inequality(&expr, &var) {
-            Ok(r) => output(&r),
+        match arithma::solve_inequality_set(&expr, &var) {
+            Ok(set) => output(&set.to_latex_in(&var)),
             Err(e) => print_error(&format!("Error: {e}")),
         }
         return;
@@ -909,7 +1022,18 @@ fn repl_solve(rest: &str) {
 
     match arithma::expression::solve_full(&expr, &var) {
         Ok(result) => {
-            if result.solutions.is_empty() && result.complex_omitted > 0 {
+            if !result.complex_solutions.is_empty() {
+                if result.solutions.is_empty() {
+                    print_note("No real solutions");
+                }
+                for s in result.solutions.iter().chain(&result.complex_solutions) {
+                    output(&format!("{var} = {s}"));
+                }
+            } else if let (true, Some(reason)) =
+                (result.solutions.is_empty(), &result.impossibility_reason)
+            {
+                print_note(reason);
+            } else if result.solutions.is_empty() && result.complex_omitted > 0 {
                 print_note(&format!(
                     "No real solutions ({} complex root{} omitted)",
                     result.complex_omitted,
@@ -930,7 +1054,11 @@ fn repl_solve(rest: &str) {
                 }
             }
         }
-        Err(e) => print_error(&format!("Error: {e}")),
+        Err(e) => {
+            if !print_numeric_roots(&expr, &var) {
+                print_error(&format!("Error: {e}"));
+            }
+        }
     }
 }
 
@@ -1007,32 +1135,20 @@ fn repl_factor(rest: &str) {
         }
     };
 
-    let (content, factors) = arithma::mod_poly::factor_over_q(&poly);
+    let factorization = arithma::factor_polynomial(&poly);
 
     let mut parts: Vec<String> = Vec::new();
-    let content_node = arithma::Node::Num(arithma::ExactNum::rational(
-        content.numer().try_into().unwrap_or(1),
-        content.denom().try_into().unwrap_or(1),
-    ));
-    let cs = format!("{content_node}");
-    if cs != "1" {
-        parts.push(cs);
-    }
-
-    let mut grouped: Vec<(String, usize)> = Vec::new();
-    for f in &factors {
-        let s = format!("{f}");
-        if let Some(entry) = grouped.iter_mut().find(|(fs, _)| *fs == s) {
-            entry.1 += 1;
-        } else {
-            grouped.push((s, 1));
-        }
+    let content_node =
+        arithma::Node::Num(arithma::ExactNum::Rational(factorization.content.clone()));
+    let content_str = format!("{}", content_node);
+    if content_str != "1" {
+        parts.push(content_str);
     }
-    for (f, m) in &grouped {
+    for (f, m) in &factorization.factors {
         if *m == 1 {
-            parts.push(format!("({f})"));
+            parts.push(format!("({})", f));
         } else {
-            parts.push(format!("({f})^{m}"));
+            parts.push(format!("({})^{}", f, m));
         }
     }
 
@@ -1040,7 +1156,7 @@ fn repl_factor(rest: &str) {
         output("1");
     } else {
         output(&parts.join(" * "));
-        if factors.len() == 1 && factors[0].degree().unwrap_or(0) > 1 {
+        if factorization.is_irreducible() {
             output("(irreducible over \\mathbb{Q})");
         }
     }
@@ -1227,45 +1343,66 @@ fn repl_pf(rest: &str) {
     }
 }
 
-fn repl_expr(input: &str, env: &Environment) {
-    if input.contains("\\begin{pmatrix}")
-        && input.contains("\\cdot")
-        && input.contains("\\end{pmatrix}")
-    {
-        let parts: Vec<&str> = input.split("\\cdot").collect();
-        if parts.len() == 2 {
-            match (
-                arithma::matrix::parse_latex_matrix(parts[0].trim(), env),
-                arithma::matrix::parse_latex_matrix(parts[1].trim(), env),
-            ) {
-                (Ok(a), Ok(b)) => match a.multiply(&b, env) {
-                    Ok(result) => {
-                        output(&result.to_latex());
-                        return;
-                    }
-                    Err(e) => {
-                        print_error(&format!("Error: {e}"));
-                        return;
-                    }
-                },
-                (Err(e), _) | (_, Err(e)) => {
-                    print_error(&format!("Error: {e}"));
-                    return;
-                }
-            }
+fn repl_poly_div(rest: &str) {
+    let args_owned = split_args(rest);
+    let args: Vec<&str> = args_owned.iter().map(|s| s.as_str()).collect();
+    if args.len() < 2 {
+        print_note("Usage: polydiv <numerator> <denominator> [var]");
+        return;
+    }
+    let var = args
+        .get(2)
+        .map(|s| normalize_var(s))
+        .unwrap_or_else(|| "x".into());
+    match arithma::poly_div_latex(args[0], args[1], &var) {
+        Ok((quotient, remainder)) => print_quotient(&quotient, &remainder),
+        Err(e) => print_error(&format!("Error: {e}")),
+    }
+}
+
+fn repl_vars(session: &Session) {
+    let bindings = session.bindings();
+    if bindings.is_empty() {
+        print_note("No variables bound");
+    }
+    for (name, binding) in bindings {
+        output(&format!("{name} = {binding}"));
+    }
+}
+
+fn repl_clear(rest: &str, session: &mut Session) {
+    if rest.is_empty() {
+        session.clear_all();
+        print_note("Cleared all variables");
+        return;
+    }
+    for name in rest.split_whitespace() {
+        let name = normalize_var(name);
+        if !session.clear(&name) {
+            print_note(&format!("{name} is not bound"));
         }
     }
+}
 
-    let simplified = match parse_latex(input, env) {
-        Ok(node) => node,
+fn repl_expr(input: &str, session: &mut Session) {
+    let simplified = match session.execute(input) {
+        Ok(SessionOutcome::Evaluated(node)) => node,
+        Ok(SessionOutcome::Assigned { name, binding }) => {
+            output(&format!("{name} = {binding}"));
+            return;
+        }
         Err(e) => {
             print_error(&format!("Error: {e}"));
             return;
         }
     };
+    let env = session.environment();
 
-    // Try exact rational evaluation (e.g., 1/3+1/4 → 7/12)
-    if let Ok(arithma::ExactNum::Rational(ref r)) = Evaluator::evaluate_exact(&simplified, env) {
+    // Try exact rational evaluation (e.g., 1/3+1/4 → 7/12), except on a
+    // prime factorization, which is the answer itself
+    let exact =
+        (!is_prime_factorization(&simplified)).then(|| Evaluator::evaluate_exact(&simplified, env));
+    if let Some(Ok(arithma::ExactNum::Rational(ref r))) = exact {
         let val = arithma::ExactNum::Rational(r.clone());
         output(&format!("{}", Node::Num(val)));
         return;
@@ -1289,6 +1426,20 @@ fn repl_expr(input: &str, env: &Environment) {
     }
 }
 
+/// A product of powers of integers, such as `factorint(360)` simplifies
+/// to; any other product of numbers simplifies to a single number.
+fn is_prime_factorization(node: &Node) -> bool {
+    fn numeric(node: &Node) -> bool {
+        match node {
+            Node::Num(_) => true,
+            Node::Negate(inner) => numeric(inner),
+            Node::Multiply(l, r) | Node::Power(l, r) => numeric(l) && numeric(r),
+            _ => false,
+        }
+    }
+    !matches!(node, Node::Num(_)) && numeric(node)
+}
+
 fn has_unevaluated_functions(s: &str) -> bool {
     [
         "\\sin", "\\cos", "\\tan", "\\sec", "\\csc", "\\cot", "\\ln", "\\log", "\\exp", "\\arctan",
@@ -1298,6 +1449,207 @@ fn has_unevaluated_functions(s: &str) -> bool {
     .any(|f| s.contains(f))
 }
 
+/// The commands the REPL runs itself rather than through the session.
+const REPL_COMMANDS: [&str; 20] = [
+    "format",
+    "simplify",
+    "diff",
+    "differentiate",
+    "integrate",
+    "solve",
+    "factor",
+    "limit",
+    "taylor",
+    "eval",
+    "evaluate",
+    "sub",
+    "substitute",
+    "ode",
+    "prime-factorize",
+    "factorint",
+    "pf",
+    "partial-fractions",
+    "polydiv",
+    "poly-div",
+];
+
+/// Line-editor hooks: an input with unclosed brackets continues on the next
+/// line.
+struct ReplHelper;
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        Ok(if input_is_complete(ctx.input()) {
+            ValidationResult::Valid(None)
+        } else {
+            ValidationResult::Incomplete
+        })
+    }
+}
+
+impl Helper for ReplHelper {}
+
+/// Whether `input` is ready to run: its brackets are closed and it does not
+/// end with a lone `\` asking for another line.
+fn input_is_complete(input: &str) -> bool {
+    let depth = input.chars().fold(0i32, |depth, c| match c {
+        '(' | '[' | '{' => depth + 1,
+        ')' | ']' | '}' => depth - 1,
+        _ => depth,
+    });
+    let trimmed = input.trim_end();
+    let continued = trimmed.ends_with('\\') && !trimmed.ends_with("\\\\");
+    depth <= 0 && !continued
+}
+
+/// A multi-line input as one line.
+fn join_lines(input: &str) -> String {
+    input
+        .replace("\\\n", " ")
+        .replace('\n', " ")
+        .trim()
+        .to_string()
+}
+
+/// `input` with each standalone `ans` replaced by `(ans)`, for commands
+/// that do not go through the session.
+fn expand_ans(input: &str, ans: &str) -> String {
+    // A digit before `ans` is a coefficient (`2ans`), one after it is not
+    let is_word = |c: char| c.is_alphabetic() || c == '_' || c == '\\';
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find("ans") {
+        let before = rest[..pos].chars().next_back().or(out.chars().next_back());
+        let after = rest[pos + 3..].chars().next();
+        out.push_str(&rest[..pos]);
+        if before.is_some_and(is_word) || after.is_some_and(|c| is_word(c) || c.is_numeric()) {
+            out.push_str("ans");
+        } else {
+            out.push_str(&format!("({ans})"));
+        }
+        rest = &rest[pos + 3..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Run a REPL command; its result, to become `ans`, when it printed a
+/// single expression.
+fn captured_result(command: impl FnOnce()) -> Option<Node> {
+    CAPTURED.with(|c| *c.borrow_mut() = Some(Vec::new()));
+    command();
+    let lines = CAPTURED.with(|c| c.borrow_mut().take()).unwrap_or_default();
+    match lines.as_slice() {
+        [line] => parse_latex_raw(line)
+            .ok()
+            .filter(|result| !matches!(result, Node::Equation(..))),
+        _ => None,
+    }
+}
+
+/// `:precision [n|off]`, `:decimal [on|off]` and `:mul [cdot|times|star]`.
+fn repl_number_format(name: &str, rest: &str) {
+    let mut options = NUMBER_FORMAT.get();
+    let valid = match (name, rest) {
+        (_, "") => true,
+        ("precision", "off") => {
+            options.precision = None;
+            true
+        }
+        ("precision", digits) => match digits.parse::<usize>() {
+            Ok(n) if n > 0 => {
+                options.precision = Some(n);
+                true
+            }
+            _ => false,
+        },
+        ("decimal", "on") => {
+            options.numbers = NumberStyle::Decimal;
+            true
+        }
+        ("decimal", "off") => {
+            options.numbers = NumberStyle::Exact;
+            true
+        }
+        ("mul", sign) => match MulSign::from_str(sign, true) {
+            Ok(sign) => {
+                options.multiplication = sign.into();
+                true
+            }
+            Err(_) => false,
+        },
+        _ => false,
+    };
+    if !valid {
+        print_error(match name {
+            "precision" => "Usage: :precision <digits>|off",
+            "decimal" => "Usage: :decimal on|off",
+            _ => "Usage: :mul cdot|times|star",
+        });
+        return;
+    }
+    NUMBER_FORMAT.set(options);
+    print_note(&format!(
+        "Numbers: {}{}; multiplication: {}",
+        match options.numbers {
+            NumberStyle::Exact => "exact",
+            NumberStyle::Decimal => "decimal",
+        },
+        options
+            .precision
+            .map_or(String::new(), |n| format!(", {n} significant digits")),
+        match options.multiplication {
+            MulSymbol::Auto | MulSymbol::Cdot => "cdot",
+            MulSymbol::Times => "times",
+            MulSymbol::Asterisk => "star",
+        }
+    ));
+}
+
+/// Handle a `:command`; false when it asks to quit.
+fn repl_meta(command: &str, session: &mut Session) -> bool {
+    let (name, rest) = match command.find(char::is_whitespace) {
+        Some(pos) => (&command[..pos], command[pos..].trim()),
+        None => (command, ""),
+    };
+    match (name, rest) {
+        ("quit" | "q" | "exit", _) => return false,
+        ("help", _) => print_repl_help(),
+        ("vars", "") => repl_vars(session),
+        ("clear", _) => repl_clear(rest, session),
+        ("latex", "on") => set_output_format(Format::Latex),
+        ("latex", "off") => set_output_format(Format::Plain),
+        ("latex" | "format", "") => print_output_format(),
+        ("format", name) => match Format::from_str(name, true) {
+            Ok(format) => set_output_format(format),
+            Err(_) => print_error("Usage: :format latex|unicode|ascii"),
+        },
+        ("precision" | "decimal" | "mul", _) => repl_number_format(name, rest),
+        ("input", "") => print_input_syntax(),
+        ("input", syntax) => match InputSyntax::from_str(syntax, true) {
+            Ok(syntax) => {
+                INPUT_SYNTAX.set(syntax);
+                print_input_syntax();
+            }
+            Err(_) => print_error("Usage: :input latex|plain|asciimath"),
+        },
+        _ => print_note(&format!(
+            "Unknown command :{command} — type ':help' for details"
+        )),
+    }
+    true
+}
+
 fn history_path() -> Option<std::path::PathBuf> {
     dirs::home_dir().map(|h| h.join(".arithma_history"))
 }
@@ -1317,22 +1669,23 @@ fn repl() {
             ansi::RESET
         );
         println!(
-            "{}Type 'help' for details, 'exit' to quit.{}\n",
+            "{}Type ':help' for details, ':quit' to quit.{}\n",
             ansi::DIM,
             ansi::RESET
         );
     } else {
         println!("Arithma v{ver} — interactive mode");
         println!("Commands: simplify, diff, integrate, solve, factor, limit, taylor, eval");
-        println!("Type 'help' for details, 'exit' to quit.\n");
+        println!("Type ':help' for details, ':quit' to quit.\n");
     }
 
-    let mut rl = DefaultEditor::new().unwrap();
+    let mut rl = Editor::<ReplHelper, DefaultHistory>::new().unwrap();
+    rl.set_helper(Some(ReplHelper));
     if let Some(ref path) = history_path() {
         let _ = rl.load_history(path);
     }
 
-    let env = Environment::new();
+    let mut session = Session::new();
     let prompt = if color_enabled() {
         format!(
             "\x01{}{}\x02>>\x01{}\x02 ",
@@ -1347,7 +1700,8 @@ fn repl() {
     loop {
         match rl.readline(&prompt) {
             Ok(line) => {
-                let input = line.trim();
+                let input = join_lines(&line);
+                let input = input.as_str();
                 if input.is_empty() {
                     continue;
                 }
@@ -1357,14 +1711,20 @@ fn repl() {
 
                 let _ = rl.add_history_entry(input);
 
-                if input == "latex" {
-                    LATEX_OUTPUT.store(true, Ordering::Relaxed);
-                    print_note("Output: LaTeX");
+                if let Some(command) = input.strip_prefix(':') {
+                    if !repl_meta(command.trim(), &mut session) {
+                        break;
+                    }
                     continue;
                 }
-                if input == "unicode" {
-                    LATEX_OUTPUT.store(false, Ordering::Relaxed);
-                    print_note("Output: Unicode");
+
+                if let Some(format) = match input {
+                    "latex" => Some(Format::Latex),
+                    "unicode" => Some(Format::Plain),
+                    "ascii" => Some(Format::Ascii),
+                    _ => None,
+                } {
+                    set_output_format(format);
                     continue;
                 }
 
@@ -1373,41 +1733,53 @@ fn repl() {
                     continue;
                 }
 
-                let input = preprocess_input(input);
+                let input = match INPUT_SYNTAX.get() {
+                    InputSyntax::Latex => preprocess_input(input),
+                    InputSyntax::Plain | InputSyntax::Asciimath => line_to_latex(input),
+                };
 
                 let (cmd, rest) = match input.find(char::is_whitespace) {
                     Some(pos) => (&input[..pos], input[pos..].trim_start()),
                     None => (input.as_str(), ""),
                 };
-
                 match cmd {
-                    "format" if !rest.is_empty() => repl_format(rest),
-                    "simplify" if !rest.is_empty() => repl_simplify(rest, &env),
-                    "diff" | "differentiate" if !rest.is_empty() => repl_diff(rest),
-                    "integrate" if !rest.is_empty() => repl_integrate(rest),
-                    "solve" if !rest.is_empty() => repl_solve(rest),
-                    "factor" if !rest.is_empty() => repl_factor(rest),
-                    "limit" if !rest.is_empty() => repl_limit(rest),
-                    "taylor" if !rest.is_empty() => repl_taylor(rest),
-                    "eval" | "evaluate" if !rest.is_empty() => repl_eval(rest),
-                    "sub" | "substitute" if !rest.is_empty() => repl_sub(rest, &env),
-                    "ode" if !rest.is_empty() => repl_ode(rest),
-                    "prime-factorize" | "factorint" if !rest.is_empty() => {
-                        repl_prime_factorize(rest)
-                    }
-                    "pf" | "partial-fractions" if !rest.is_empty() => repl_pf(rest),
-                    "format" | "simplify" | "diff" | "differentiate" | "integrate" | "solve"
-                    | "factor" | "limit" | "taylor" | "eval" | "evaluate" | "sub"
-                    | "substitute" | "ode" | "prime-factorize" | "factorint" | "pf"
-                    | "partial-fractions" => {
-                        print_note(&format!(
-                            "Usage: {cmd} <expr> [args...] — type 'help' for details"
-                        ));
+                    "vars" if rest.is_empty() => repl_vars(&session),
+                    "clear" => repl_clear(rest, &mut session),
+                    _ if !REPL_COMMANDS.contains(&cmd) => repl_expr(&input, &mut session),
+                    _ => {
+                        let expanded = match session.ans() {
+                            Some(ans) => expand_ans(rest, &ans.to_string()),
+                            None => rest.to_string(),
+                        };
+                        let rest = expanded.as_str();
+                        let env = session.environment();
+                        let result = captured_result(|| match cmd {
+                            _ if rest.is_empty() => print_note(&format!(
+                                "Usage: {cmd} <expr> [args...] — type ':help' for details"
+                            )),
+                            "format" => repl_format(rest),
+                            "simplify" => repl_simplify(rest, env),
+                            "diff" | "differentiate" => repl_diff(rest),
+                            "integrate" => repl_integrate(rest),
+                            "solve" => repl_solve(rest),
+                            "factor" => repl_factor(rest),
+                            "limit" => repl_limit(rest),
+                            "taylor" => repl_taylor(rest),
+                            "eval" | "evaluate" => repl_eval(rest),
+                            "sub" | "substitute" => repl_sub(rest, env),
+                            "ode" => repl_ode(rest),
+                            "prime-factorize" | "factorint" => repl_prime_factorize(rest),
+                            "polydiv" | "poly-div" => repl_poly_div(rest),
+                            _ => repl_pf(rest),
+                        });
+                        if let Some(result) = result {
+                            session.set_ans(result);
+                        }
                     }
-                    _ => repl_expr(&input, &env),
                 }
             }
-            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
             Err(e) => {
                 print_error(&format!("Error: {e}"));
                 break;
@@ -1422,7 +1794,7 @@ fn repl() {
 
 #[cfg(test)]
 mod tests {
-    use super::preprocess_input;
+    use super::{expand_ans, input_is_complete, join_lines, preprocess_input};
 
     #[test]
     fn preprocess_converts_natural_notation() {
@@ -1444,4 +1816,22 @@ mod tests {
         assert_eq!(preprocess_input("α + α"), "α + α");
         assert_eq!(preprocess_input("2·3"), "2·3");
     }
+
+    #[test]
+    fn unclosed_brackets_and_trailing_backslash_continue_the_input() {
+        assert!(input_is_complete("\\frac{1}{2}"));
+        assert!(!input_is_complete("\\frac{1}{"));
+        assert!(!input_is_complete("(x + 1"));
+        assert!(!input_is_complete("x + \\"));
+        assert!(input_is_complete("\\begin{pmatrix} 1 \\\\"));
+        assert_eq!(join_lines("x + \\\n1"), "x +  1");
+        assert_eq!(join_lines("(x\n+ 1)"), "(x + 1)");
+    }
+
+    #[test]
+    fn ans_expands_as_a_whole_word() {
+        assert_eq!(expand_ans("ans^2 x", "x + 1"), "(x + 1)^2 x");
+        assert_eq!(expand_ans("2ans", "3"), "2(3)");
+        assert_eq!(expand_ans("answer + \\tans", "3"), "answer + \\tans");
+    }
 }
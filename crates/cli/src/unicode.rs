@@ -28,6 +28,25 @@ pub fn latex_to_unicode(input: &str) -> String {
                 }
             }
 
+            // \floor{x} → ⌊x⌋, \ceil{x} → ⌈x⌉, \round{x} → round(x)
+            if let Some((cmd, open, close)) = [
+                ("\\floor{", "⌊", "⌋"),
+                ("\\ceil{", "⌈", "⌉"),
+                ("\\round{", "round(", ")"),
+                ("\\trunc{", "trunc(", ")"),
+            ]
+            .into_iter()
+            .find(|(cmd, _, _)| rest.starts_with(cmd))
+            {
+                if let Some((end, content)) = extract_brace_group(input, i + cmd.len() - 1) {
+                    out.push_str(open);
+                    out.push_str(&latex_to_unicode(&content));
+                    out.push_str(close);
+                    i = end + 1;
+                    continue;
+                }
+            }
+
             // \mathbb{X}
             if rest.starts_with("\\mathbb{") {
                 if let Some((close, content)) = extract_brace_group(input, i + 7) {
@@ -53,7 +72,7 @@ pub fn latex_to_unicode(input: &str) -> String {
                 }
             }
             if rest.starts_with("\\operatorname{") {
-                if let Some((close, content)) = extract_brace_group(input, i + 14) {
+                if let Some((close, content)) = extract_brace_group(input, i + 13) {
                     out.push_str(&content);
                     i = close + 1;
                     continue;
@@ -266,7 +285,18 @@ fn match_simple_command(s: &str) -> Option<(usize, &'static str)> {
         ("\\lim", "lim"),
         ("\\max", "max"),
         ("\\min", "min"),
+        ("\\lfloor", "⌊"),
+        ("\\rfloor", "⌋"),
+        ("\\lceil", "⌈"),
+        ("\\rceil", "⌉"),
+        ("\\bmod", "mod"),
+        ("\\mod", "mod"),
+        ("\\sgn", "sgn"),
+        ("\\erfc", "erfc"),
         ("\\erf", "erf"),
+        ("\\lgamma", "lgamma"),
+        ("\\Gamma", "Γ"),
+        ("\\Beta", "B"),
         ("\\Ei", "Ei"),
         ("\\li", "li"),
         ("\\varepsilon", "ε"),
@@ -294,6 +324,7 @@ fn match_simple_command(s: &str) -> Option<(usize, &'static str)> {
         ("\\leq", "≤"),
         ("\\geq", "≥"),
         ("\\neq", "≠"),
+        ("\\approx", "≈"),
         ("\\pi", "π"),
         ("\\mu", "μ"),
         ("\\nu", "ν"),
@@ -301,6 +332,11 @@ fn match_simple_command(s: &str) -> Option<(usize, &'static str)> {
         ("\\pm", "±"),
         ("\\mp", "∓"),
         ("\\to", "→"),
+        ("\\emptyset", "∅"),
+        ("\\cup", "∪"),
+        ("\\lor", "∨"),
+        ("\\land", "∧"),
+        ("\\lnot", "¬"),
         ("\\in", "∈"),
         ("\\ ", " "),
     ];
@@ -415,6 +451,12 @@ mod tests {
         assert_eq!(latex_to_unicode("\\infty"), "∞");
         assert_eq!(latex_to_unicode("\\cdot"), "·");
         assert_eq!(latex_to_unicode("2\\pi"), "2π");
+        assert_eq!(latex_to_unicode("x < -2 \\lor x \\geq 1"), "x < -2 ∨ x ≥ 1");
+        assert_eq!(latex_to_unicode("\\lnot a \\land b"), "¬ a ∧ b");
+        assert_eq!(
+            latex_to_unicode("(-\\infty, 0) \\cup \\emptyset"),
+            "(-∞, 0) ∪ ∅"
+        );
     }
 
     #[test]
@@ -423,6 +465,10 @@ mod tests {
         assert_eq!(latex_to_unicode("\\cos(x)"), "cos(x)");
         assert_eq!(latex_to_unicode("\\arctan(x)"), "arctan(x)");
         assert_eq!(latex_to_unicode("\\ln(x)"), "ln(x)");
+        assert_eq!(latex_to_unicode("\\operatorname{area}(r)"), "area(r)");
+        assert_eq!(latex_to_unicode("\\Gamma(x) \\erfc(x)"), "Γ(x) erfc(x)");
+        assert_eq!(latex_to_unicode("\\floor{x + 1} \\bmod 3"), "⌊x + 1⌋ mod 3");
+        assert_eq!(latex_to_unicode("\\sgn(\\ceil{x})"), "sgn(⌈x⌉)");
     }
 
     #[test]
@@ -12,6 +12,7 @@ mod validate;
 use arithma::assumptions::Assumptions;
 use arithma::chain::{verify_chain, ChainResult, ChainStepInput, Relation};
 use arithma::derivative::differentiate_latex;
+use arithma::error::ArithmaError;
 use arithma::exact::ExactNum;
 use arithma::integration::{definite_integral_exact_latex, integrate_latex};
 use arithma::matrix::parse_latex_matrix;
@@ -28,8 +29,8 @@ use arithma::status::{
 use arithma::substitute::substitute_latex;
 use arithma::tokenizer::normalize_var;
 use arithma::{
-    build_expression_tree, factor_over_q, parse_latex, parse_latex_raw, partial_fractions_latex,
-    Environment, Evaluator, Node, Polynomial, Tokenizer,
+    build_expression_tree, factor_polynomial, parse_latex, parse_latex_raw,
+    partial_fractions_latex, Environment, Evaluator, Node, Polynomial, Tokenizer,
 };
 
 pub fn json_rpc_error(id: Option<Value>, code: i64, message: &str) -> Value {
@@ -659,7 +660,9 @@ fn env_from_args(args: &Value) -> Result<Environment, String> {
 }
 
 fn parse_and_simplify_with_env(expr_str: &str, env: &Environment) -> Result<String, String> {
-    parse_latex(expr_str, env).map(|node| format!("{node}"))
+    parse_latex(expr_str, env)
+        .map(|node| format!("{node}"))
+        .map_err(|e| e.render())
 }
 
 /// Three-way replay outcome: a replay check that conflates
@@ -860,10 +863,10 @@ fn tool_integrate(args: &Value) -> ToolResult {
                 };
                 Ok((format!("{value}"), status))
             }
-            Err(e) if e.starts_with("NON_ELEMENTARY:") => {
-                Ok((String::new(), non_elementary_status(&e, expr, &var)))
+            Err(ArithmaError::NonElementary(reason)) => {
+                Ok((String::new(), non_elementary_status(&reason, expr, &var)))
             }
-            Err(e) => Err(e),
+            Err(e) => Err(e.into()),
         };
     }
 
@@ -879,20 +882,19 @@ fn tool_integrate(args: &Value) -> ToolResult {
             };
             Ok((format!("{antiderivative}"), status))
         }
-        Err(e) if e.starts_with("NON_ELEMENTARY:") => {
-            Ok((String::new(), non_elementary_status(&e, expr, &var)))
+        Err(ArithmaError::NonElementary(reason)) => {
+            Ok((String::new(), non_elementary_status(&reason, expr, &var)))
         }
-        Err(e) => Err(e),
+        Err(e) => Err(e.into()),
     }
 }
 
-/// Build the provably_impossible status for a NON_ELEMENTARY error and, when
-/// the integrand's antiderivative is a recognized special function (erf, Ei,
-/// li), attach the named form — strictly more information than the
+/// Build the provably_impossible status for a non-elementary integrand and,
+/// when its antiderivative is a recognized special function (erf, Ei, li),
+/// attach the named form — strictly more information than the
 /// impossibility alone. Unrecognized integrands keep the bare certificate.
-fn non_elementary_status(error: &str, integrand_latex: &str, var: &str) -> StatusReport {
-    let reason = error.replacen("NON_ELEMENTARY: ", "", 1);
-    let proof = ProofCertificate::non_elementary(&reason);
+fn non_elementary_status(reason: &str, integrand_latex: &str, var: &str) -> StatusReport {
+    let proof = ProofCertificate::non_elementary(reason);
     let status = StatusReport::provably_impossible(proof);
     match recognize_special_form_latex(integrand_latex, var) {
         Some((name, form)) => status.with_special_form(&name, &form),
@@ -940,9 +942,9 @@ fn tool_solve(args: &Value) -> ToolResult {
         expr,
         Node::Greater(_, _) | Node::GreaterEqual(_, _) | Node::Less(_, _) | Node::LessEqual(_, _)
     ) {
-        return arithma::solve_inequality(&expr, &var).map(|t| {
+        return arithma::solve_inequality_set(&expr, &var).map(|set| {
             (
-                t,
+                set.to_latex_in(&var),
                 StatusReport::exact(Certificate::by_construction(
                     "sign_analysis — exact polynomial sign analysis",
                 )),
@@ -1209,50 +1211,31 @@ fn tool_factor(args: &Value) -> ToolResult {
     let poly =
         Polynomial::from_node(&node, &var).map_err(|e| format!("Not a polynomial: {}", e))?;
 
-    let (content, factors) = factor_over_q(&poly);
+    let factorization = factor_polynomial(&poly);
+    let content = &factorization.content;
 
     let mut parts: Vec<String> = Vec::new();
 
     let content_str = format!(
         "{}",
-        arithma::Node::Num(arithma::ExactNum::rational(
-            content.numer().try_into().unwrap_or(1),
-            content.denom().try_into().unwrap_or(1),
-        ))
+        arithma::Node::Num(arithma::ExactNum::Rational(content.clone()))
     );
     if content_str != "1" {
         parts.push(content_str);
     }
 
-    // Group factors with multiplicities
-    let mut grouped: Vec<(String, usize)> = Vec::new();
-    for f in &factors {
-        let s = format!("{}", f);
-        if let Some(entry) = grouped.iter_mut().find(|(fs, _)| *fs == s) {
-            entry.1 += 1;
-        } else {
-            grouped.push((s, 1));
-        }
-    }
-
-    for (f_str, m) in &grouped {
+    for (f, m) in &factorization.factors {
         if *m == 1 {
-            parts.push(format!("({})", f_str));
+            parts.push(format!("({})", f));
         } else {
-            parts.push(format!("({})^{}", f_str, m));
+            parts.push(format!("({})^{}", f, m));
         }
     }
 
     // Replay check: multiply factors back, take the difference with the
     // input, canonicalize to zero. Three outcomes the three-way replay protocol.
     let env = Environment::new();
-    let mut product_node: Node = Node::Num(ExactNum::rational(
-        content.numer().try_into().unwrap_or(1),
-        content.denom().try_into().unwrap_or(1),
-    ));
-    for f in &factors {
-        product_node = Node::Multiply(Box::new(product_node), Box::new(f.to_node()));
-    }
+    let product_node = factorization.to_node();
     let product_expanded = product_node.simplify(&env).unwrap_or(product_node);
     let input_expanded = node.simplify(&env).unwrap_or_else(|_| node.clone());
     let cert = match difference_is_zero(&product_expanded, &input_expanded, &env) {
@@ -1278,7 +1261,7 @@ fn tool_factor(args: &Value) -> ToolResult {
         Ok(("1".to_string(), StatusReport::exact(cert)))
     } else {
         let mut result = parts.join(" \\cdot ");
-        if factors.len() == 1 && factors[0].degree().unwrap_or(0) > 1 {
+        if factorization.is_irreducible() {
             result.push_str("  \\quad\\text{(irreducible over }\\mathbb{Q}\\text{)}");
         }
         Ok((result, StatusReport::exact(cert)))
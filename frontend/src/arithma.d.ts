@@ -4,18 +4,51 @@
 declare module 'arithma' {
   export default function init(options?: { path?: string }): Promise<void>;
 
+  /**
+   * Single entry point. Request JSON: {operation, latex, variable?, variables?, options?, env?};
+   * returns JSON {ok, operation, latex?, value?, results?, error?}.
+   */
+  export function run_operation_js(request_json: string): string;
+
   // Format / Evaluate / Simplify
   export function format_latex_js(latex: string): string;
   export function evaluate_latex_expression_js(latex: string, environment: string): string;
   export function simplify_latex_js(latex: string): string;
+  export function parse_latex_to_json_js(latex: string): string;
+  export function json_to_latex_js(ast_json: string): string;
+  export function asciimath_to_latex_js(asciimath: string): string;
+  export function evaluate_json_js(ast_json: string, env_json: string): number;
+
+  // Structured results: JSON {kind: "number" | "expression" | "equation" | "inequality",
+  // value, latex, holds?, solutions?, steps?}; value is null unless kind is "number".
+  export function evaluate_latex_expression_json(latex: string, environment: string): string;
+  export function simplify_latex_json(latex: string): string;
+  export function differentiate_expression_json(latex_expr: string, var_name: string): string;
+  export function integrate_expression_json(latex_expr: string, var_name: string): string;
+  export function solve_json(latex_equation: string, var_name: string): string;
 
   // Calculus
+  export function differentiate_expression_js(latex_expr: string, var_name: string): string;
+  /** Same as differentiate_expression_js. */
   export function differentiate_js(latex_expr: string, var_name: string): string;
+  /** Other variables are held constant. */
+  export function partial_derivative_js(latex_expr: string, var_name: string): string;
+  export function differentiate_many_js(latex_exprs_json: string, var_name: string): string;
+  export function integrate_many_js(latex_exprs_json: string, var_name: string): string;
   export function integrate_expression_js(latex_expr: string, var_name: string): string;
   export function definite_integral_js(latex_expr: string, var_name: string, lower: number, upper: number): string;
   export function limit_js(latex_expr: string, var_name: string, point: number): string;
   export function taylor_series_js(latex_expr: string, var_name: string, center: number, order: number): string;
   export function solve_ode_js(rhs_latex: string, indep_var: string, dep_var: string): string;
+  export function solve_ode_equation_js(equation_latex: string, indep_var: string, dep_var: string): string;
+  /** method: "rk4" | "rk45" | "implicit_euler". JSON: {t, y, rejected_steps}, sampled at evenly spaced t. */
+  export function solve_ivp_js(rhs_latex: string, indep_var: string, dep_var: string, t0: number, y0: number, t_end: number, method: string, samples: number): string;
+  /** JSON: {x, f, derivative, integral, derivative_latex}; non-finite samples are null. */
+  export function calculus_plot_js(latex_expr: string, var_name: string, x_min: number, x_max: number, samples: number): string;
+  /** JSON: {segments, discontinuities, undefined}; segments are [x, y] polylines split at gaps and jumps, undefined is [a, b] ranges. */
+  export function plot_js(latex_expr: string, var_name: string, x_min: number, x_max: number, samples: number): string;
+  /** grid_json: {x_min, x_max, y_min, y_max, nx, ny}. JSON: {x, y, slopes, directions}; slopes[j][i] is f(x[i], y[j]), null where undefined. */
+  export function slope_field_js(latex_rhs: string, x_var: string, y_var: string, grid_json: string): string;
 
   // Algebra
   export function solve_js(latex_equation: string, var_name: string): string;
@@ -33,4 +66,17 @@ declare module 'arithma' {
   export function matrix_rank_js(latex_expr: string, env_json: string): number;
   export function matrix_eigenvalues_js(latex_expr: string, env_json: string): string;
   export function solve_linear_system_js(matrix_a: string, vector_b: string, env_json: string): string;
+
+  // Sessions: results are kept as %1, %2, … for later inputs
+  export class SessionHandle {
+    constructor();
+    /** JSON: {label, input, result_latex, value}. */
+    evaluate(latex: string): string;
+    set_variable(name: string, value: number): void;
+    history_json(): string;
+    len(): number;
+    is_empty(): boolean;
+    clear_history(): void;
+    free(): void;
+  }
 }
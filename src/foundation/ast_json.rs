@@ -0,0 +1,406 @@
+//! The JSON form of an expression tree.
+//!
+//! Every node is an object with an `"op"` naming what it is and, for
+//! anything with children, an `"args"` array of nodes, so a program in any
+//! language can build an expression without generating LaTeX:
+//!
+//! ```json
+//! {"op": "add", "args": [
+//!     {"op": "power", "args": [{"op": "variable", "name": "x"}, {"op": "num", "value": 2}]},
+//!     {"op": "num", "value": "1/3"}
+//! ]}
+//! ```
+//!
+//! | `op` | other fields | `args` |
+//! |------|--------------|--------|
+//! | `num` | `value`: a JSON integer or a `"p"` / `"p/q"` string (exact), or a JSON number with a fraction or exponent (floating point) | — |
+//! | `variable` | `name` | — |
+//! | `add`, `multiply` | | two or more operands, grouped from the left |
+//! | `subtract`, `divide`, `power` | | `[left, right]` |
+//! | `negate`, `sqrt`, `abs`, `floor`, `ceil`, `round`, `trunc`, `factorial` | | `[operand]` |
+//! | `greater`, `less`, `greater_equal`, `less_equal`, `equal` | | `[left, right]` |
+//! | `and`, `or` | | `[left, right]` |
+//! | `not` | | `[operand]` |
+//! | `equation` | | `[left, right]` |
+//! | `piecewise` | | `[value, condition, value, condition, …]` |
+//! | `matrix` | `cols`: the number of columns | the entries, row by row |
+//! | `summation`, `product` | `var` | `[start, end, body]` |
+//! | `sum_over` | `var` | `[list, body]` |
+//! | `integral` | `var` | `[body]`, or `[lower, upper, body]` when definite |
+//! | `limit` | `var`, `direction`: `"both"` (default), `"left"` or `"right"` | `[point, body]` |
+//! | `derivative` | `var` | `[body]` |
+//! | `function` | `name` (`"sin"`, `"binom"`, …) | the arguments |
+//!
+//! Any other field is an error, as is a missing one, so a misspelt key
+//! never silently changes the expression. [`Node`]'s serde implementation
+//! uses this form too, so matrices and environments embed the same
+//! objects.
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Number, Value};
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, ToPrimitive};
+
+use crate::exact::ExactNum;
+use crate::node::{LimitDirection, Node};
+
+impl Node {
+    /// The tree as a JSON value in the `{"op", "args"}` form. Non-finite
+    /// floats have no JSON form and fail.
+    pub fn to_json_value(&self) -> Result<Value, String> {
+        let op = |name: &str, args: &[&Node]| -> Result<Value, String> {
+            let args = args
+                .iter()
+                .map(|a| a.to_json_value())
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(json!({"op": name, "args": args}))
+        };
+        let bound = |name: &str, var: &str, args: &[&Node]| -> Result<Value, String> {
+            let mut value = op(name, args)?;
+            value["var"] = json!(var);
+            Ok(value)
+        };
+        match self {
+            Node::Num(n) => Ok(json!({"op": "num", "value": number_value(n)?})),
+            Node::Variable(name) => Ok(json!({"op": "variable", "name": name})),
+            Node::Add(l, r) => op("add", &[l, r]),
+            Node::Subtract(l, r) => op("subtract", &[l, r]),
+            Node::Multiply(l, r) => op("multiply", &[l, r]),
+            Node::Divide(l, r) => op("divide", &[l, r]),
+            Node::Power(l, r) => op("power", &[l, r]),
+            Node::Sqrt(inner) => op("sqrt", &[inner]),
+            Node::Abs(inner) => op("abs", &[inner]),
+            Node::Floor(inner) => op("floor", &[inner]),
+            Node::Ceil(inner) => op("ceil", &[inner]),
+            Node::Round(inner) => op("round", &[inner]),
+            Node::Trunc(inner) => op("trunc", &[inner]),
+            Node::Negate(inner) => op("negate", &[inner]),
+            Node::Factorial(inner) => op("factorial", &[inner]),
+            Node::Greater(l, r) => op("greater", &[l, r]),
+            Node::Less(l, r) => op("less", &[l, r]),
+            Node::GreaterEqual(l, r) => op("greater_equal", &[l, r]),
+            Node::LessEqual(l, r) => op("less_equal", &[l, r]),
+            Node::Equal(l, r) => op("equal", &[l, r]),
+            Node::And(l, r) => op("and", &[l, r]),
+            Node::Or(l, r) => op("or", &[l, r]),
+            Node::Not(inner) => op("not", &[inner]),
+            Node::Equation(l, r) => op("equation", &[l, r]),
+            Node::Piecewise(arms) => {
+                let args: Vec<&Node> = arms.iter().flat_map(|(v, c)| [v, c]).collect();
+                op("piecewise", &args)
+            }
+            Node::Matrix(rows) => {
+                let mut value = op("matrix", &rows.iter().flatten().collect::<Vec<_>>())?;
+                value["cols"] = json!(rows.first().map_or(0, Vec::len));
+                Ok(value)
+            }
+            Node::Summation(var, start, end, body) => bound("summation", var, &[start, end, body]),
+            Node::Product(var, start, end, body) => bound("product", var, &[start, end, body]),
+            Node::SumOver(var, list, body) => bound("sum_over", var, &[list, body]),
+            Node::Integral(var, None, body) => bound("integral", var, &[body]),
+            Node::Integral(var, Some((lower, upper)), body) => {
+                bound("integral", var, &[lower, upper, body])
+            }
+            Node::Limit(var, point, direction, body) => {
+                let mut value = bound("limit", var, &[point, body])?;
+                value["direction"] = json!(match direction {
+                    LimitDirection::Both => "both",
+                    LimitDirection::Left => "left",
+                    LimitDirection::Right => "right",
+                });
+                Ok(value)
+            }
+            Node::Derivative(var, body) => bound("derivative", var, &[body]),
+            Node::Function(name, args) => {
+                let mut value = op("function", &args.iter().collect::<Vec<_>>())?;
+                value["name"] = json!(name);
+                Ok(value)
+            }
+        }
+    }
+
+    /// Build a tree from a JSON value in the `{"op", "args"}` form.
+    /// Errors name the path to the offending node, e.g. `$.args[1].args[0]`.
+    pub fn from_json_value(value: &Value) -> Result<Node, String> {
+        node_at(value, "$")
+    }
+}
+
+impl Serialize for Node {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json_value()
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Node, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Node::from_json_value(&value).map_err(D::Error::custom)
+    }
+}
+
+/// An exact integer that fits an `i64` as a JSON integer, any other
+/// rational as a `"p/q"` string, and a float as a JSON float.
+fn number_value(n: &ExactNum) -> Result<Value, String> {
+    match n {
+        ExactNum::Rational(r) if r.denom().is_one() => Ok(match r.numer().to_i64() {
+            Some(i) => json!(i),
+            None => json!(r.numer().to_string()),
+        }),
+        ExactNum::Rational(r) => Ok(json!(format!("{}/{}", r.numer(), r.denom()))),
+        ExactNum::Float(f) => Number::from_f64(*f)
+            .map(Value::Number)
+            .ok_or_else(|| format!("{} has no JSON form", f)),
+    }
+}
+
+fn node_at(value: &Value, path: &str) -> Result<Node, String> {
+    let object = value.as_object().ok_or_else(|| {
+        format!(
+            "{}: expected an {{\"op\": …}} object, found {}",
+            path, value
+        )
+    })?;
+    let op = object
+        .get("op")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("{}: missing string field \"op\"", path))?;
+    let fields: &[&str] = match op {
+        "num" => &["value"],
+        "variable" => &["name"],
+        "function" => &["name", "args"],
+        "matrix" => &["cols", "args"],
+        "summation" | "product" | "sum_over" | "integral" | "derivative" => &["var", "args"],
+        "limit" => &["var", "direction", "args"],
+        _ => &["args"],
+    };
+    if let Some(unknown) = object
+        .keys()
+        .find(|k| *k != "op" && !fields.contains(&k.as_str()))
+    {
+        return Err(format!(
+            "{}: unknown field \"{}\" for op \"{}\"",
+            path, unknown, op
+        ));
+    }
+
+    let string = |key: &str| -> Result<String, String> {
+        object
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| format!("{}: op \"{}\" needs a string field \"{}\"", path, op, key))
+    };
+    let args = || -> Result<Vec<Node>, String> {
+        object
+            .get("args")
+            .and_then(Value::as_array)
+            .ok_or_else(|| format!("{}: op \"{}\" needs an \"args\" array", path, op))?
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| node_at(arg, &format!("{}.args[{}]", path, i)))
+            .collect()
+    };
+    let arity = |args: Vec<Node>, n: usize| -> Result<Vec<Node>, String> {
+        match args.len() == n {
+            true => Ok(args),
+            false => Err(format!(
+                "{}: op \"{}\" takes {} argument{}, found {}",
+                path,
+                op,
+                n,
+                if n == 1 { "" } else { "s" },
+                args.len()
+            )),
+        }
+    };
+    let unary = |f: fn(Box<Node>) -> Node| -> Result<Node, String> {
+        let mut args = arity(args()?, 1)?;
+        Ok(f(Box::new(args.remove(0))))
+    };
+    let binary = |f: fn(Box<Node>, Box<Node>) -> Node| -> Result<Node, String> {
+        let mut args = arity(args()?, 2)?.into_iter().map(Box::new);
+        Ok(f(args.next().unwrap(), args.next().unwrap()))
+    };
+    let chain = |f: fn(Box<Node>, Box<Node>) -> Node| -> Result<Node, String> {
+        let args = args()?;
+        if args.len() < 2 {
+            return Err(format!(
+                "{}: op \"{}\" takes two or more arguments, found {}",
+                path,
+                op,
+                args.len()
+            ));
+        }
+        let mut args = args.into_iter();
+        let first = args.next().unwrap();
+        Ok(args.fold(first, |acc, arg| f(Box::new(acc), Box::new(arg))))
+    };
+
+    match op {
+        "num" => {
+            let value = object
+                .get("value")
+                .ok_or_else(|| format!("{}: op \"num\" needs a \"value\"", path))?;
+            number_from(value)
+                .map(Node::Num)
+                .map_err(|e| format!("{}: {}", path, e))
+        }
+        "variable" => Ok(Node::Variable(string("name")?)),
+        "add" => chain(Node::Add),
+        "multiply" => chain(Node::Multiply),
+        "subtract" => binary(Node::Subtract),
+        "divide" => binary(Node::Divide),
+        "power" => binary(Node::Power),
+        "sqrt" => unary(Node::Sqrt),
+        "abs" => unary(Node::Abs),
+        "floor" => unary(Node::Floor),
+        "ceil" => unary(Node::Ceil),
+        "round" => unary(Node::Round),
+        "trunc" => unary(Node::Trunc),
+        "negate" => unary(Node::Negate),
+        "factorial" => unary(Node::Factorial),
+        "greater" => binary(Node::Greater),
+        "less" => binary(Node::Less),
+        "greater_equal" => binary(Node::GreaterEqual),
+        "less_equal" => binary(Node::LessEqual),
+        "equal" => binary(Node::Equal),
+        "and" => binary(Node::And),
+        "or" => binary(Node::Or),
+        "not" => unary(Node::Not),
+        "equation" => binary(Node::Equation),
+        "piecewise" => {
+            let args = args()?;
+            if args.is_empty() || args.len() % 2 != 0 {
+                return Err(format!(
+                    "{}: op \"piecewise\" takes value, condition pairs, found {} arguments",
+                    path,
+                    args.len()
+                ));
+            }
+            let mut args = args.into_iter();
+            let mut arms = Vec::new();
+            while let (Some(value), Some(condition)) = (args.next(), args.next()) {
+                arms.push((value, condition));
+            }
+            Ok(Node::Piecewise(arms))
+        }
+        "matrix" => {
+            let cols = object
+                .get("cols")
+                .and_then(Value::as_u64)
+                .filter(|&c| c > 0)
+                .ok_or_else(|| {
+                    format!("{}: op \"matrix\" needs a positive integer \"cols\"", path)
+                })? as usize;
+            let entries = args()?;
+            if entries.is_empty() || entries.len() % cols != 0 {
+                return Err(format!(
+                    "{}: op \"matrix\" takes whole rows of {} entries, found {}",
+                    path,
+                    cols,
+                    entries.len()
+                ));
+            }
+            Ok(Node::Matrix(
+                entries.chunks(cols).map(<[Node]>::to_vec).collect(),
+            ))
+        }
+        "summation" | "product" => {
+            let var = string("var")?;
+            let mut args = arity(args()?, 3)?.into_iter().map(Box::new);
+            let (start, end, body) = (args.next(), args.next(), args.next());
+            let f = if op == "summation" {
+                Node::Summation
+            } else {
+                Node::Product
+            };
+            Ok(f(var, start.unwrap(), end.unwrap(), body.unwrap()))
+        }
+        "sum_over" => {
+            let var = string("var")?;
+            let mut args = arity(args()?, 2)?.into_iter().map(Box::new);
+            Ok(Node::SumOver(
+                var,
+                args.next().unwrap(),
+                args.next().unwrap(),
+            ))
+        }
+        "integral" => {
+            let var = string("var")?;
+            let mut args = args()?.into_iter().map(Box::new).collect::<Vec<_>>();
+            match args.len() {
+                1 => Ok(Node::Integral(var, None, args.remove(0))),
+                3 => {
+                    let body = args.remove(2);
+                    let upper = args.remove(1);
+                    Ok(Node::Integral(var, Some((args.remove(0), upper)), body))
+                }
+                n => Err(format!(
+                    "{}: op \"integral\" takes [body] or [lower, upper, body], found {} arguments",
+                    path, n
+                )),
+            }
+        }
+        "limit" => {
+            let var = string("var")?;
+            let direction = match object.get("direction").map(|d| d.as_str()) {
+                None | Some(Some("both")) => LimitDirection::Both,
+                Some(Some("left")) => LimitDirection::Left,
+                Some(Some("right")) => LimitDirection::Right,
+                Some(_) => {
+                    return Err(format!(
+                        "{}: \"direction\" must be \"both\", \"left\" or \"right\"",
+                        path
+                    ))
+                }
+            };
+            let mut args = arity(args()?, 2)?.into_iter().map(Box::new);
+            Ok(Node::Limit(
+                var,
+                args.next().unwrap(),
+                direction,
+                args.next().unwrap(),
+            ))
+        }
+        "derivative" => {
+            let var = string("var")?;
+            let mut args = arity(args()?, 1)?;
+            Ok(Node::Derivative(var, Box::new(args.remove(0))))
+        }
+        "function" => Ok(Node::Function(string("name")?, args()?)),
+        _ => Err(format!("{}: unknown op \"{}\"", path, op)),
+    }
+}
+
+fn number_from(value: &Value) -> Result<ExactNum, String> {
+    match value {
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Ok(ExactNum::integer(i))
+            } else if let Some(u) = n.as_u64() {
+                Ok(ExactNum::Rational(BigRational::from_integer(BigInt::from(
+                    u,
+                ))))
+            } else {
+                Ok(ExactNum::Float(n.as_f64().unwrap_or(f64::NAN)))
+            }
+        }
+        Value::String(s) => s
+            .trim()
+            .parse::<BigRational>()
+            .ok()
+            .map(ExactNum::Rational)
+            .ok_or_else(|| format!("\"{}\" is not an integer or a fraction p/q", s)),
+        other => Err(format!(
+            "a number value must be a number or a string, found {}",
+            other
+        )),
+    }
+}
@@ -0,0 +1,84 @@
+//! The mathematical constants expressions may name.
+//!
+//! A constant is a [`Node::Variable`](crate::Node::Variable) whose name is
+//! one of these, so it stays symbolic through parsing and simplification
+//! and prints back as written (`\pi`, `e`, `\tau`,
+//! `\operatorname{GoldenRatio}`). It becomes a float only when evaluated,
+//! or when simplification is asked to fold constants ([`SimplifyOptions::fold_constants`](crate::simplify::SimplifyOptions::fold_constants)).
+//! A binding of the same name in the environment wins over the constant.
+
+use std::f64::consts;
+
+/// A named constant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Constant {
+    /// π, the ratio of a circle's circumference to its diameter
+    Pi,
+    /// Euler's number, the base of the natural logarithm
+    E,
+    /// τ = 2π
+    Tau,
+    /// The golden ratio (1 + √5)/2. It is spelled out because `\phi` is
+    /// as often an angle or an unknown.
+    GoldenRatio,
+}
+
+impl Constant {
+    pub const ALL: [Constant; 4] = [
+        Constant::Pi,
+        Constant::E,
+        Constant::Tau,
+        Constant::GoldenRatio,
+    ];
+
+    /// The constant a variable named `name` stands for.
+    pub fn from_name(name: &str) -> Option<Constant> {
+        Constant::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// The constant spelled `word` in plain input (`PI`, `EULER`, `TAU`,
+    /// `GOLDENRATIO`), or by its own name.
+    pub fn from_word(word: &str) -> Option<Constant> {
+        match word {
+            "PI" => Some(Constant::Pi),
+            "EULER" => Some(Constant::E),
+            "TAU" => Some(Constant::Tau),
+            "GOLDENRATIO" => Some(Constant::GoldenRatio),
+            _ => Constant::from_name(word),
+        }
+    }
+
+    /// The variable name the constant is stored under.
+    pub fn name(self) -> &'static str {
+        match self {
+            Constant::Pi => "π",
+            Constant::E => "e",
+            Constant::Tau => "τ",
+            Constant::GoldenRatio => "GoldenRatio",
+        }
+    }
+
+    /// The LaTeX the constant prints as, which parses back to it.
+    pub fn latex(self) -> &'static str {
+        match self {
+            Constant::Pi => "\\pi",
+            Constant::E => "e",
+            Constant::Tau => "\\tau",
+            Constant::GoldenRatio => "\\operatorname{GoldenRatio}",
+        }
+    }
+
+    pub fn value(self) -> f64 {
+        match self {
+            Constant::Pi => consts::PI,
+            Constant::E => consts::E,
+            Constant::Tau => consts::TAU,
+            Constant::GoldenRatio => 1.618_033_988_749_895,
+        }
+    }
+}
+
+/// Whether a variable named `name` is a constant.
+pub fn is_constant(name: &str) -> bool {
+    Constant::from_name(name).is_some()
+}
@@ -1,18 +1,75 @@
+//! Bindings that expressions are evaluated and simplified in.
+//!
+//! An [`Environment`] is a stack of scopes. [`Environment::child`] starts a
+//! new scope on top of the current ones without copying them, so a
+//! summation index, the parameters of a user function or a temporary
+//! substitution is bound in the child and never reaches the caller's
+//! environment; lookups fall back from the innermost scope outwards.
+//! [`Environment::push_scope`]/[`Environment::pop_scope`] do the same in
+//! place.
+
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use crate::assumptions::Assumptions;
+use crate::domains::DomainPolicy;
 use crate::exact::ExactNum;
+use crate::functions::{FunctionHandler, FunctionRegistry, FUNCTION_REGISTRY};
+use crate::node::Node;
+use crate::rewrite::RuleSet;
+use crate::simplify::SimplifyOptions;
 
 #[derive(Serialize, Deserialize)]
 struct EnvironmentJson {
-    vars: HashMap<String, f64>,
+    vars: HashMap<String, VarJson>,
+}
+
+/// A `vars` entry: `"x": 2.5` or `"data": [1, 2, 3]`.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum VarJson {
+    Scalar(f64),
+    List(Vec<f64>),
+}
+
+/// A function defined in an environment, e.g. `f(x) = x^2 + 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFunction {
+    /// Parameter names, bound positionally to the arguments
+    pub params: Vec<String>,
+    /// Body expression in terms of the parameters
+    pub body: Node,
+}
+
+/// What a name is bound to in one scope.
+#[derive(Debug, Clone)]
+enum Binding {
+    Scalar(ExactNum),
+    List(Vec<ExactNum>),
+    /// Removed in this scope while an outer scope still binds it
+    Unbound,
+}
+
+/// One level of an [`Environment`].
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    bindings: HashMap<String, Binding>,
+    functions: HashMap<String, UserFunction>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Environment {
-    vars: HashMap<String, ExactNum>,
-    assumptions: Assumptions,
+    /// Outermost first; never empty. Scopes are shared with the
+    /// environments this one was made from until one of them writes.
+    scopes: Vec<Arc<Scope>>,
+    assumptions: Arc<Assumptions>,
+    /// Functions the application registered, looked up before the
+    /// built-in ones
+    registry: Arc<FunctionRegistry>,
+    domain_policy: DomainPolicy,
+    simplify_options: SimplifyOptions,
+    rules: Arc<RuleSet>,
 }
 
 impl Default for Environment {
@@ -25,9 +82,15 @@ impl Serialize for Environment {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let json = EnvironmentJson {
             vars: self
-                .vars
-                .iter()
-                .map(|(k, v)| (k.clone(), v.to_f64()))
+                .bound_names()
+                .filter_map(|name| match self.binding(name)? {
+                    Binding::Scalar(v) => Some((name.to_string(), VarJson::Scalar(v.to_f64()))),
+                    Binding::List(values) => {
+                        let values = values.iter().map(ExactNum::to_f64).collect();
+                        Some((name.to_string(), VarJson::List(values)))
+                    }
+                    Binding::Unbound => None,
+                })
                 .collect(),
         };
         json.serialize(serializer)
@@ -37,50 +100,302 @@ impl Serialize for Environment {
 impl<'de> Deserialize<'de> for Environment {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         let json = EnvironmentJson::deserialize(deserializer)?;
-        let vars = json
-            .vars
-            .into_iter()
-            .map(|(k, v)| (k, ExactNum::from_f64(v)))
-            .collect();
-        Ok(Environment {
-            vars,
-            assumptions: Assumptions::new(),
-        })
+        let mut env = Environment::new();
+        for (name, value) in json.vars {
+            match value {
+                VarJson::Scalar(v) => env.set(&name, v),
+                VarJson::List(values) => env.set_list(&name, &values),
+            }
+        }
+        Ok(env)
     }
 }
 
 impl Environment {
     pub fn new() -> Self {
-        Environment {
-            vars: HashMap::new(),
-            assumptions: Assumptions::new(),
-        }
+        Self::with_assumptions(Assumptions::new())
     }
 
     pub fn with_assumptions(assumptions: Assumptions) -> Self {
         Environment {
-            vars: HashMap::new(),
-            assumptions,
+            scopes: vec![Arc::new(Scope::default())],
+            assumptions: Arc::new(assumptions),
+            registry: Arc::new(FunctionRegistry::new()),
+            domain_policy: DomainPolicy::default(),
+            simplify_options: SimplifyOptions::default(),
+            rules: Arc::new(RuleSet::new()),
+        }
+    }
+
+    /// A new scope over this environment. What is bound or defined in the
+    /// child shadows this environment's bindings and is gone with the
+    /// child; nothing is copied to make it.
+    pub fn child(&self) -> Environment {
+        let mut child = self.clone();
+        child.push_scope();
+        child
+    }
+
+    /// Start a new innermost scope; later bindings go into it until
+    /// [`pop_scope`](Environment::pop_scope).
+    pub fn push_scope(&mut self) {
+        self.scopes.push(Arc::new(Scope::default()));
+    }
+
+    /// Drop the innermost scope and its bindings, uncovering the ones it
+    /// shadowed. The outermost scope is never dropped; whether one was.
+    pub fn pop_scope(&mut self) -> bool {
+        if self.scopes.len() == 1 {
+            return false;
         }
+        self.scopes.pop();
+        true
+    }
+
+    /// How many scopes deep this environment is; 1 for a new one.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    fn innermost(&mut self) -> &mut Scope {
+        Arc::make_mut(self.scopes.last_mut().expect("an environment has a scope"))
+    }
+
+    /// The binding `name` resolves to, innermost scope first.
+    fn binding(&self, name: &str) -> Option<&Binding> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.bindings.get(name))
+            .filter(|binding| !matches!(binding, Binding::Unbound))
+    }
+
+    /// Every name bound in some scope, once each.
+    fn bound_names(&self) -> impl Iterator<Item = &str> {
+        let mut seen = HashSet::new();
+        self.scopes
+            .iter()
+            .flat_map(|scope| scope.bindings.keys())
+            .map(String::as_str)
+            .filter(move |name| seen.insert(*name))
     }
 
     pub fn assumptions(&self) -> &Assumptions {
         &self.assumptions
     }
 
+    /// What evaluation does with `\\arcsin(2)`, `\\ln(-1)` and other
+    /// arguments outside a principal domain; NaN unless set.
+    pub fn domain_policy(&self) -> DomainPolicy {
+        self.domain_policy
+    }
+
+    pub fn set_domain_policy(&mut self, policy: DomainPolicy) {
+        self.domain_policy = policy;
+    }
+
+    /// Which identity rewrites simplification applies; the defaults unless
+    /// set.
+    pub fn simplify_options(&self) -> SimplifyOptions {
+        self.simplify_options
+    }
+
+    pub fn set_simplify_options(&mut self, options: SimplifyOptions) {
+        self.simplify_options = options;
+    }
+
+    /// Rewrite rules simplification applies after its own, to every
+    /// subexpression; none unless set.
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = Arc::new(rules);
+    }
+
     pub fn get(&self, var: &str) -> Option<f64> {
-        self.vars.get(var).map(|n| n.to_f64())
+        self.get_exact(var).map(|n| n.to_f64())
     }
 
     pub fn get_exact(&self, var: &str) -> Option<&ExactNum> {
-        self.vars.get(var)
+        match self.binding(var)? {
+            Binding::Scalar(value) => Some(value),
+            _ => None,
+        }
     }
 
     pub fn set(&mut self, var: &str, value: f64) {
-        self.vars.insert(var.to_string(), ExactNum::from_f64(value));
+        self.set_exact(var, ExactNum::from_f64(value));
     }
 
+    /// Bind `var` in the innermost scope, replacing any list binding.
     pub fn set_exact(&mut self, var: &str, value: ExactNum) {
-        self.vars.insert(var.to_string(), value);
+        self.innermost()
+            .bindings
+            .insert(var.to_string(), Binding::Scalar(value));
+    }
+
+    /// Bind `name` to a list of values, replacing any scalar binding. A
+    /// list is read by `\sum_{x \in name}` and spliced into the arguments
+    /// of a function call, so `\max(data)` is the largest element.
+    pub fn set_list(&mut self, name: &str, values: &[f64]) {
+        self.set_list_exact(
+            name,
+            values.iter().map(|v| ExactNum::from_f64(*v)).collect(),
+        );
+    }
+
+    pub fn set_list_exact(&mut self, name: &str, values: Vec<ExactNum>) {
+        self.innermost()
+            .bindings
+            .insert(name.to_string(), Binding::List(values));
+    }
+
+    pub fn get_list(&self, name: &str) -> Option<&[ExactNum]> {
+        match self.binding(name)? {
+            Binding::List(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Unbind the scalar or list `name`; whether it was bound. In a child
+    /// scope the name stays unbound until the scope ends, even when an
+    /// outer scope binds it.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let bound = self.binding(name).is_some();
+        let outer = self.scopes[..self.scopes.len() - 1]
+            .iter()
+            .any(|scope| scope.bindings.contains_key(name));
+        let innermost = self.innermost();
+        if outer {
+            innermost
+                .bindings
+                .insert(name.to_string(), Binding::Unbound);
+        } else {
+            innermost.bindings.remove(name);
+        }
+        bound
+    }
+
+    pub fn variable_names(&self) -> impl Iterator<Item = &str> {
+        self.bound_names()
+            .filter(|name| matches!(self.binding(name), Some(Binding::Scalar(_))))
+    }
+
+    pub fn list_names(&self) -> impl Iterator<Item = &str> {
+        self.bound_names()
+            .filter(|name| matches!(self.binding(name), Some(Binding::List(_))))
+    }
+
+    /// Define `name(params) = body`. Once defined, `name(…)` parses as an
+    /// application in [`crate::parser::parse_latex`] and evaluates by
+    /// binding the arguments to `params`. A body that calls `name` itself
+    /// is rejected, since evaluation would never terminate. The definition
+    /// belongs to the innermost scope.
+    pub fn define_function(
+        &mut self,
+        name: &str,
+        params: &[&str],
+        body: Node,
+    ) -> Result<(), String> {
+        if FUNCTION_REGISTRY.get(name).is_some() || self.registry.get(name).is_some() {
+            return Err(format!(
+                "'{}' is a built-in function and cannot be redefined",
+                name
+            ));
+        }
+        if params.is_empty() {
+            return Err(format!("Function '{}' needs at least one parameter", name));
+        }
+        if let Some((i, p)) = params
+            .iter()
+            .enumerate()
+            .find(|(i, p)| params[..*i].contains(p))
+        {
+            return Err(format!(
+                "Parameter '{}' appears twice in {} (position {})",
+                p,
+                name,
+                i + 1
+            ));
+        }
+        if body.contains_function(name) {
+            return Err(format!(
+                "Function '{}' cannot be defined in terms of itself",
+                name
+            ));
+        }
+        self.innermost().functions.insert(
+            name.to_string(),
+            UserFunction {
+                params: params.iter().map(|p| p.to_string()).collect(),
+                body,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_function(&self, name: &str) -> Option<&UserFunction> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.functions.get(name))
+    }
+
+    pub fn function_names(&self) -> impl Iterator<Item = &str> {
+        let mut seen = HashSet::new();
+        self.scopes
+            .iter()
+            .flat_map(|scope| scope.functions.keys())
+            .map(String::as_str)
+            .filter(move |name| seen.insert(*name))
+    }
+
+    /// Add a function implemented in Rust, such as `sinc` or a
+    /// domain-specific model, without patching the built-in registry. It
+    /// evaluates like a built-in one and parses as `name(…)` with this
+    /// environment, or as `\operatorname{name}(…)` without it; symbolic
+    /// calculus does not know it, so its derivatives evaluate by finite
+    /// differences. Built-in names cannot be replaced; registering a name
+    /// again replaces the earlier handler. Registrations are shared with
+    /// every child scope.
+    pub fn register_function(
+        &mut self,
+        name: &str,
+        function: Box<dyn FunctionHandler + Send + Sync>,
+    ) -> Result<(), String> {
+        if FUNCTION_REGISTRY.get(name).is_some() {
+            return Err(format!(
+                "'{}' is a built-in function and cannot be redefined",
+                name
+            ));
+        }
+        if name.is_empty() || !name.chars().all(char::is_alphabetic) {
+            return Err(format!(
+                "Function name '{}' must be letters only, so it parses back",
+                name
+            ));
+        }
+        Arc::make_mut(&mut self.registry).register_function(name, function);
+        Ok(())
+    }
+
+    /// A function registered with
+    /// [`register_function`](Environment::register_function).
+    pub fn registered_function(&self, name: &str) -> Option<&(dyn FunctionHandler + Send + Sync)> {
+        self.registry.get(name)
+    }
+
+    /// The handler `name` calls: a registered function, otherwise a
+    /// built-in one.
+    pub fn function_handler(&self, name: &str) -> Option<&(dyn FunctionHandler + Send + Sync)> {
+        self.registry
+            .get(name)
+            .or_else(|| FUNCTION_REGISTRY.get(name))
+    }
+
+    pub fn registered_function_names(&self) -> impl Iterator<Item = &str> {
+        self.registry.names()
     }
 }
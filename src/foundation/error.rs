@@ -0,0 +1,92 @@
+//! The error type of the evaluator, matrix, differentiation and integration
+//! APIs.
+//!
+//! Each variant carries the message these APIs have always reported, so
+//! `Display` (and the `String` an error converts into for code that still
+//! reports bare messages) reads as before; the variant says what kind of
+//! failure it was without matching on the text. Bare messages from modules
+//! not yet converted become [`ArithmaError::Eval`].
+
+use std::fmt;
+
+use crate::parser::ParseError;
+
+/// Why a computation failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArithmaError {
+    /// The input did not parse
+    Parse(ParseError),
+    /// The expression could not be evaluated, e.g. it has an unbound
+    /// variable
+    Eval(String),
+    /// A value is outside the domain of the operation: the square root of a
+    /// negative number, the inverse of a singular matrix
+    Domain(String),
+    /// Matrix or vector shapes do not fit the operation
+    DimensionMismatch(String),
+    /// A function was given the wrong number or kind of arguments
+    InvalidArgument(String),
+    /// The operation is supported in principle but not for this input
+    NotImplemented(String),
+    /// The integrand provably has no elementary antiderivative; the reason
+    /// comes from the Risch decision procedure
+    NonElementary(String),
+}
+
+impl ArithmaError {
+    /// The message, without the `NON_ELEMENTARY: ` tag `Display` puts on a
+    /// [`NonElementary`](ArithmaError::NonElementary) reason.
+    pub fn message(&self) -> &str {
+        match self {
+            ArithmaError::Parse(error) => &error.message,
+            ArithmaError::Eval(message)
+            | ArithmaError::Domain(message)
+            | ArithmaError::DimensionMismatch(message)
+            | ArithmaError::InvalidArgument(message)
+            | ArithmaError::NotImplemented(message)
+            | ArithmaError::NonElementary(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for ArithmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithmaError::NonElementary(reason) => write!(f, "NON_ELEMENTARY: {}", reason),
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+impl std::error::Error for ArithmaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ArithmaError::Parse(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for ArithmaError {
+    fn from(error: ParseError) -> Self {
+        ArithmaError::Parse(error)
+    }
+}
+
+impl From<String> for ArithmaError {
+    fn from(message: String) -> Self {
+        ArithmaError::Eval(message)
+    }
+}
+
+impl From<&str> for ArithmaError {
+    fn from(message: &str) -> Self {
+        ArithmaError::Eval(message.to_string())
+    }
+}
+
+impl From<ArithmaError> for String {
+    fn from(error: ArithmaError) -> String {
+        error.to_string()
+    }
+}
@@ -166,11 +166,7 @@ impl ExactNum {
     }
 
     fn bigint_to_exact(q: BigInt) -> Self {
-        if let Some(i) = q.to_i64() {
-            ExactNum::integer(i)
-        } else {
-            ExactNum::from_f64(q.to_f64().unwrap_or(f64::NAN))
-        }
+        ExactNum::Rational(BigRational::from_integer(q))
     }
 
     pub fn pow_f64(&self, exp: f64) -> Self {
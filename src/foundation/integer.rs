@@ -1,8 +1,10 @@
-//! Integer number-theory helpers (GCD/LCM, factorial, prime factorization, square-factor extraction).
+//! Integer number-theory helpers (GCD/LCM, factorial, binomial coefficients and permutations,
+//! primality, modular powers, prime factorization, square-factor extraction).
 //!
 //! All public APIs take and return [`ExactNum`]. Internally, integer algorithms run on
 //! [`BigInt`] end-to-end — there is no `usize`/`i64` cap on inputs for [`factorial`],
-//! [`binom`], [`gcd`], [`lcm`], or [`prime_factorize`].
+//! [`binom`], [`permutations`], [`gcd`], [`lcm`], [`is_prime`], [`powmod`], or
+//! [`prime_factorize`].
 
 use crate::exact::ExactNum;
 use crate::node::Node;
@@ -39,6 +41,16 @@ pub fn as_non_negative_integer(n: &ExactNum) -> Option<BigInt> {
     }
 }
 
+/// Integer of either sign as `BigInt`, when `n` is an exact whole number.
+fn as_integer(n: &ExactNum) -> Option<BigInt> {
+    match n {
+        ExactNum::Rational(r) if r.is_integer() => Some(r.numer().clone()),
+        ExactNum::Float(f) if *f < 0.0 => float_to_non_negative_bigint(-f).map(|m| -m),
+        ExactNum::Float(f) => float_to_non_negative_bigint(*f),
+        _ => None,
+    }
+}
+
 /// Parse a non-negative integer string into `ExactNum`.
 pub fn parse_non_negative_integer(s: &str) -> Option<ExactNum> {
     let n = s.trim().parse::<BigInt>().ok()?;
@@ -50,17 +62,17 @@ pub fn parse_non_negative_integer(s: &str) -> Option<ExactNum> {
 
 // GCD / LCM
 
-/// Greatest common divisor of two non-negative integer `ExactNum`s.
+/// Greatest common divisor of two integer `ExactNum`s, always non-negative.
 pub fn gcd(a: &ExactNum, b: &ExactNum) -> Option<ExactNum> {
-    let a = as_non_negative_integer(a)?;
-    let b = as_non_negative_integer(b)?;
+    let a = as_integer(a)?;
+    let b = as_integer(b)?;
     Some(bigint_to_exact(a.gcd(&b)))
 }
 
-/// Least common multiple of two non-negative integer `ExactNum`s.
+/// Least common multiple of two integer `ExactNum`s, always non-negative.
 pub fn lcm(a: &ExactNum, b: &ExactNum) -> Option<ExactNum> {
-    let a = as_non_negative_integer(a)?;
-    let b = as_non_negative_integer(b)?;
+    let a = as_integer(a)?.abs();
+    let b = as_integer(b)?.abs();
     if a.is_zero() || b.is_zero() {
         return Some(ExactNum::integer(0));
     }
@@ -116,6 +128,81 @@ pub fn binom(n: &ExactNum, k: &ExactNum) -> Option<ExactNum> {
     Some(bigint_to_exact(binomial_bigint(&n, &k)))
 }
 
+/// Exact number of k-permutations of n, P(n, k) = n!/(n − k)!; returns `0`
+/// when `k > n`.
+pub fn permutations(n: &ExactNum, k: &ExactNum) -> Option<ExactNum> {
+    let n = as_non_negative_integer(n)?;
+    let k = as_non_negative_integer(k)?;
+    if k > n {
+        return Some(ExactNum::zero());
+    }
+    let mut result = BigInt::one();
+    let mut factor = n.clone() - &k + 1;
+    while factor <= n {
+        result *= &factor;
+        factor += 1;
+    }
+    Some(bigint_to_exact(result))
+}
+
+// Primality and modular arithmetic
+
+/// Miller–Rabin witnesses that decide primality exactly below 3.3·10²⁴.
+const MILLER_RABIN_BASES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Whether the integer `n` is prime; `None` when `n` is not an integer.
+///
+/// Miller–Rabin with the first twelve primes as witnesses, which is exact for
+/// every `n` below 3.3·10²⁴. Beyond that a `true` means `n` is a strong
+/// probable prime to all twelve bases.
+pub fn is_prime(n: &ExactNum) -> Option<bool> {
+    let n = as_integer(n)?;
+    if n < BigInt::from(2u32) {
+        return Some(false);
+    }
+    for p in MILLER_RABIN_BASES {
+        let p = BigInt::from(p);
+        if n == p {
+            return Some(true);
+        }
+        if n.is_multiple_of(&p) {
+            return Some(false);
+        }
+    }
+    // n − 1 = d · 2^s with d odd
+    let n_minus_one = &n - 1u32;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+    let strong_probable_prime = |a: u32| {
+        let mut x = BigInt::from(a).modpow(&d, &n);
+        if x.is_one() || x == n_minus_one {
+            return true;
+        }
+        for _ in 1..s {
+            x = x.modpow(&BigInt::from(2u32), &n);
+            if x == n_minus_one {
+                return true;
+            }
+        }
+        false
+    };
+    Some(MILLER_RABIN_BASES.into_iter().all(strong_probable_prime))
+}
+
+/// `base^exponent mod modulus` for integers, without forming the power.
+///
+/// The exponent must be non-negative and the modulus nonzero. The result
+/// takes the sign of the modulus, as `\bmod` does.
+pub fn powmod(base: &ExactNum, exponent: &ExactNum, modulus: &ExactNum) -> Option<ExactNum> {
+    let base = as_integer(base)?;
+    let exponent = as_non_negative_integer(exponent)?;
+    let modulus = as_integer(modulus)?;
+    if modulus.is_zero() {
+        return None;
+    }
+    Some(bigint_to_exact(base.modpow(&exponent, &modulus)))
+}
+
 // Prime factorization
 
 /// Maximum trial divisor tried by [`prime_factorize`]. Inputs whose smallest
@@ -206,6 +293,27 @@ fn prime_factor_term(prime: &ExactNum, exponent: u32) -> Node {
     }
 }
 
+/// The prime factorization of the integer `n` as a product of powers,
+/// `360 → 2^3 · 3^2 · 5`, with a leading negation for negative `n`. `0`
+/// and `±1` are returned as they are. The product is not simplified,
+/// since simplifying would multiply it back out.
+pub fn prime_factorize_node(n: &ExactNum) -> Option<Node> {
+    let n = as_integer(n)?;
+    let factors = prime_factorize_bigint(n.abs())?;
+    let Some(product) = factors
+        .into_iter()
+        .map(|(prime, exponent)| prime_factor_term(&bigint_to_exact(prime), exponent))
+        .reduce(|acc, term| Node::Multiply(Box::new(acc), Box::new(term)))
+    else {
+        return Some(Node::Num(bigint_to_exact(n)));
+    };
+    Some(if n.is_negative() {
+        Node::Negate(Box::new(product))
+    } else {
+        product
+    })
+}
+
 /// Prime-factorize `n` and format as LaTeX (e.g. `2^{4} \cdot 3^{2} \cdot 5`).
 pub fn prime_factorize_latex(n: &ExactNum) -> Result<String, String> {
     let factors = prime_factorize(n).ok_or_else(|| {
@@ -225,7 +333,8 @@ pub fn prime_factorize_latex(n: &ExactNum) -> Result<String, String> {
 mod tests {
     use super::{
         as_non_negative_integer, bigint_to_exact, binom, extract_square_factors, factorial, gcd,
-        lcm, parse_non_negative_integer, prime_factorize, prime_factorize_latex,
+        is_prime, lcm, parse_non_negative_integer, powmod, prime_factorize, prime_factorize_latex,
+        prime_factorize_node,
     };
     use crate::ExactNum;
 
@@ -458,4 +567,64 @@ mod tests {
             Some((ExactNum::integer(10), ExactNum::integer(1)))
         );
     }
+
+    #[test]
+    fn test_gcd_lcm_of_negative_integers() {
+        assert_eq!(
+            gcd(&ExactNum::integer(-12), &ExactNum::integer(18)),
+            Some(ExactNum::integer(6))
+        );
+        assert_eq!(
+            lcm(&ExactNum::integer(-4), &ExactNum::integer(-6)),
+            Some(ExactNum::integer(12))
+        );
+        assert_eq!(gcd(&ExactNum::rational(1, 2), &ExactNum::integer(2)), None);
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let primes: Vec<i64> = (0..60)
+            .filter(|&n| is_prime(&ExactNum::integer(n)).unwrap())
+            .collect();
+        assert_eq!(
+            primes,
+            vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59]
+        );
+        assert_eq!(is_prime(&ExactNum::integer(-7)), Some(false));
+        // Mersenne prime 2^61 − 1, and the Carmichael number 561 = 3·11·17
+        assert_eq!(is_prime(&ExactNum::integer((1 << 61) - 1)), Some(true));
+        assert_eq!(is_prime(&ExactNum::integer(561)), Some(false));
+        // 2^89 − 1 is prime; 2^89 + 1 is divisible by 3
+        let m89 = parse_non_negative_integer("618970019642690137449562111").unwrap();
+        assert_eq!(is_prime(&m89), Some(true));
+        let p89 = parse_non_negative_integer("618970019642690137449562113").unwrap();
+        assert_eq!(is_prime(&p89), Some(false));
+        assert_eq!(is_prime(&ExactNum::rational(7, 2)), None);
+    }
+
+    #[test]
+    fn test_powmod() {
+        let int = ExactNum::integer;
+        assert_eq!(powmod(&int(2), &int(100), &int(7)), Some(int(2)));
+        assert_eq!(powmod(&int(-3), &int(3), &int(5)), Some(int(3)));
+        assert_eq!(powmod(&int(3), &int(2), &int(-5)), Some(int(-1)));
+        assert_eq!(powmod(&int(5), &int(0), &int(3)), Some(int(1)));
+        assert_eq!(powmod(&int(2), &int(-1), &int(7)), None);
+        assert_eq!(powmod(&int(2), &int(3), &int(0)), None);
+    }
+
+    #[test]
+    fn test_prime_factorize_node() {
+        let factored = |n: i64| {
+            prime_factorize_node(&ExactNum::integer(n))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(factored(360), "2^{3} \\cdot 3^{2} \\cdot 5");
+        assert_eq!(factored(-84), "-2^{2} \\cdot 3 \\cdot 7");
+        assert_eq!(factored(97), "97");
+        assert_eq!(factored(1), "1");
+        assert_eq!(factored(0), "0");
+        assert_eq!(prime_factorize_node(&ExactNum::rational(1, 2)), None);
+    }
 }
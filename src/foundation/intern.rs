@@ -0,0 +1,159 @@
+//! Hash-consed storage of expression trees.
+//!
+//! Machine-generated expressions repeat the same subtrees over and over: a
+//! quotient-rule derivative mentions its denominator three times, an
+//! expanded power mentions every power of `x` in many terms. A
+//! [`NodeArena`] stores each distinct subtree once and names it by a
+//! [`NodeId`], so two subtrees are equal exactly when their ids are, and
+//! counting or finding repeated subtrees is a hash lookup instead of a
+//! comparison of whole trees. Interning a tree takes time linear in its
+//! size.
+//!
+//! Numbers are only the same subtree when they are written the same way:
+//! `\frac{1}{2}` and `0.5` compare equal as [`Node`]s but intern apart, so
+//! [`NodeArena::get`] gives back the tree that was interned.
+//!
+//! [`crate::sharing`] interns its input to find repeated subtrees, and
+//! simplification interns the monomials of a sum to collect its like terms
+//! by id.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::exact::ExactNum;
+use crate::node::Node;
+
+/// A subtree stored in a [`NodeArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+/// Distinct subtrees of the trees interned into it, each stored once.
+#[derive(Debug, Default)]
+pub struct NodeArena {
+    entries: Vec<Entry>,
+    ids: HashMap<(Shell, Vec<NodeId>), NodeId>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    shell: Shell,
+    children: Vec<NodeId>,
+    size: usize,
+}
+
+impl NodeArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of `node`, adding it and any of its subtrees not yet in the
+    /// arena. Long `+`/`−` chains nest as deep as they have terms, so the
+    /// walk keeps its own stack instead of recursing.
+    pub fn intern(&mut self, node: &Node) -> NodeId {
+        let mut pending = vec![(node, false)];
+        let mut done: Vec<NodeId> = Vec::new();
+        while let Some((node, visited)) = pending.pop() {
+            let children = node.children();
+            if !visited {
+                pending.push((node, true));
+                pending.extend(children.into_iter().rev().map(|child| (child, false)));
+                continue;
+            }
+            let children = done.split_off(done.len() - children.len());
+            let id = self.insert(Shell::of(node), children);
+            done.push(id);
+        }
+        done.pop().expect("the root is interned last")
+    }
+
+    fn insert(&mut self, shell: Shell, children: Vec<NodeId>) -> NodeId {
+        let key = (shell, children);
+        if let Some(&id) = self.ids.get(&key) {
+            return id;
+        }
+        let (shell, children) = key;
+        let size = 1 + children.iter().map(|&c| self.size(c)).sum::<usize>();
+        let id = NodeId(u32::try_from(self.entries.len()).expect("fewer than 2³² subtrees"));
+        self.entries.push(Entry {
+            shell: shell.clone(),
+            children: children.clone(),
+            size,
+        });
+        self.ids.insert((shell, children), id);
+        id
+    }
+
+    /// The tree `id` names.
+    pub fn get(&self, id: NodeId) -> Node {
+        let entry = &self.entries[id.0 as usize];
+        let mut children = entry.children.iter();
+        entry
+            .shell
+            .0
+            .map_children(|_| self.get(*children.next().expect("an entry has an id per child")))
+    }
+
+    /// The ids of the children of `id`, in path order.
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.entries[id.0 as usize].children
+    }
+
+    /// How many nodes the tree `id` names has, counting repeats.
+    pub fn size(&self, id: NodeId) -> usize {
+        self.entries[id.0 as usize].size
+    }
+
+    /// How many distinct subtrees the arena holds.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A node with its children left out: what, besides its children, makes
+/// two subtrees the same.
+#[derive(Debug, Clone)]
+struct Shell(Node);
+
+impl Shell {
+    fn of(node: &Node) -> Self {
+        Shell(node.map_children(|_| Node::Num(ExactNum::zero())))
+    }
+}
+
+impl PartialEq for Shell {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Node::Num(ExactNum::Rational(a)), Node::Num(ExactNum::Rational(b))) => a == b,
+            (Node::Num(ExactNum::Float(a)), Node::Num(ExactNum::Float(b))) => {
+                a.to_bits() == b.to_bits()
+            }
+            (Node::Num(_), Node::Num(_)) => false,
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl Eq for Shell {}
+
+impl Hash for Shell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(&self.0).hash(state);
+        match &self.0 {
+            Node::Num(ExactNum::Rational(r)) => r.hash(state),
+            Node::Num(ExactNum::Float(f)) => f.to_bits().hash(state),
+            Node::Variable(name)
+            | Node::Function(name, _)
+            | Node::Summation(name, ..)
+            | Node::Product(name, ..)
+            | Node::SumOver(name, ..)
+            | Node::Integral(name, ..)
+            | Node::Limit(name, ..)
+            | Node::Derivative(name, _) => name.hash(state),
+            _ => {}
+        }
+    }
+}
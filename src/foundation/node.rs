@@ -2,7 +2,9 @@ use crate::exact::ExactNum;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// An expression tree. Serializes in the documented `{"op", "args"}`
+/// JSON form of [`ast_json`](crate::ast_json).
+#[derive(Debug, Clone, PartialEq)]
 pub enum Node {
     Num(ExactNum),
     Variable(String),
@@ -29,23 +31,83 @@ pub enum Node {
     LessEqual(Box<Node>, Box<Node>),
     Equal(Box<Node>, Box<Node>),
 
+    // Logical connectives, true as 1 and false as 0
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+
     // Equation (left side = right side)
     Equation(Box<Node>, Box<Node>),
 
     // Piecewise expressions
     Piecewise(Vec<(Node, Node)>),
 
+    // Matrix literal: rows of entries, all the same length
+    Matrix(Vec<Vec<Node>>),
+
     // Summation: index_var, start, end, body
     Summation(String, Box<Node>, Box<Node>, Box<Node>),
 
     // Product: index_var, start, end, body
     Product(String, Box<Node>, Box<Node>, Box<Node>),
 
+    // Sum over the elements of a list: index_var, list, body
+    SumOver(String, Box<Node>, Box<Node>),
+
+    // Integral: variable, optional (lower, upper) bounds, integrand
+    Integral(String, Option<(Box<Node>, Box<Node>)>, Box<Node>),
+
+    // Limit: variable, approach point, side, body
+    Limit(String, Box<Node>, LimitDirection, Box<Node>),
+
+    // Derivative the engine could not resolve: variable, expression
+    Derivative(String, Box<Node>),
+
     // Function calls
     Function(String, Vec<Node>), // For functions like sin, cos
 }
 
+/// Side from which a limit approaches its point: `x \to a` (both),
+/// `x \to a^{-}` (left), `x \to a^{+}` (right).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitDirection {
+    Both,
+    Left,
+    Right,
+}
+
+impl LimitDirection {
+    /// The superscript marking a one-sided approach: `^{-}`, `^{+}`, or
+    /// nothing.
+    pub fn latex_suffix(&self) -> &'static str {
+        match self {
+            LimitDirection::Both => "",
+            LimitDirection::Left => "^{-}",
+            LimitDirection::Right => "^{+}",
+        }
+    }
+}
+
 impl Node {
+    /// Serialize the tree to JSON in the `{"op", "args"}` form of
+    /// [`ast_json`](crate::ast_json), e.g. `{"op":"variable","name":"x"}`,
+    /// so a parsed expression can be cached or sent elsewhere and rebuilt
+    /// with [`Node::from_json`] instead of re-parsing LaTeX. Non-finite
+    /// floats have no JSON form and fail.
+    pub fn to_json(&self) -> Result<String, String> {
+        self.to_json_value()
+            .map(|value| value.to_string())
+            .map_err(|e| format!("Failed to serialize expression: {}", e))
+    }
+
+    /// Build a tree from JSON in the `{"op", "args"}` form, whether written
+    /// by [`Node::to_json`] or by another program.
+    pub fn from_json(json: &str) -> Result<Node, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid expression JSON: {}", e))?;
+        Node::from_json_value(&value).map_err(|e| format!("Invalid expression JSON: {}", e))
+    }
+
     pub fn contains_variable(&self, var: &str) -> bool {
         match self {
             Node::Num(_) => false,
@@ -59,9 +121,12 @@ impl Node {
             | Node::Less(l, r)
             | Node::GreaterEqual(l, r)
             | Node::LessEqual(l, r)
+            | Node::And(l, r)
+            | Node::Or(l, r)
             | Node::Equal(l, r)
             | Node::Equation(l, r) => l.contains_variable(var) || r.contains_variable(var),
             Node::Negate(inner)
+            | Node::Not(inner)
             | Node::Sqrt(inner)
             | Node::Abs(inner)
             | Node::Floor(inner)
@@ -73,6 +138,7 @@ impl Node {
             Node::Piecewise(cases) => cases
                 .iter()
                 .any(|(e, c)| e.contains_variable(var) || c.contains_variable(var)),
+            Node::Matrix(rows) => rows.iter().flatten().any(|e| e.contains_variable(var)),
             Node::Summation(idx, start, end, body) => {
                 if idx == var {
                     start.contains_variable(var) || end.contains_variable(var)
@@ -91,6 +157,74 @@ impl Node {
                         || body.contains_variable(var)
                 }
             }
+            Node::SumOver(idx, list, body) => {
+                list.contains_variable(var) || (idx != var && body.contains_variable(var))
+            }
+            // A definite integral binds its variable; an indefinite one
+            // is still a function of it
+            Node::Integral(v, bounds, body) => {
+                bounds
+                    .as_ref()
+                    .is_some_and(|(lo, hi)| lo.contains_variable(var) || hi.contains_variable(var))
+                    || ((v != var || bounds.is_none()) && body.contains_variable(var))
+            }
+            // The limit variable is bound
+            Node::Limit(v, point, _, body) => {
+                point.contains_variable(var) || (v != var && body.contains_variable(var))
+            }
+            // d/dx f is still a function of x
+            Node::Derivative(_, body) => body.contains_variable(var),
+        }
+    }
+
+    /// Whether `name` is applied anywhere in the tree.
+    pub fn contains_function(&self, name: &str) -> bool {
+        match self {
+            Node::Num(_) | Node::Variable(_) => false,
+            Node::Add(l, r)
+            | Node::Subtract(l, r)
+            | Node::Multiply(l, r)
+            | Node::Divide(l, r)
+            | Node::Power(l, r)
+            | Node::Greater(l, r)
+            | Node::Less(l, r)
+            | Node::GreaterEqual(l, r)
+            | Node::LessEqual(l, r)
+            | Node::And(l, r)
+            | Node::Or(l, r)
+            | Node::Equal(l, r)
+            | Node::Equation(l, r) => l.contains_function(name) || r.contains_function(name),
+            Node::Negate(inner)
+            | Node::Not(inner)
+            | Node::Sqrt(inner)
+            | Node::Abs(inner)
+            | Node::Floor(inner)
+            | Node::Ceil(inner)
+            | Node::Round(inner)
+            | Node::Trunc(inner)
+            | Node::Factorial(inner) => inner.contains_function(name),
+            Node::Function(f, args) => f == name || args.iter().any(|a| a.contains_function(name)),
+            Node::Piecewise(cases) => cases
+                .iter()
+                .any(|(e, c)| e.contains_function(name) || c.contains_function(name)),
+            Node::Matrix(rows) => rows.iter().flatten().any(|e| e.contains_function(name)),
+            Node::Summation(_, start, end, body) | Node::Product(_, start, end, body) => {
+                start.contains_function(name)
+                    || end.contains_function(name)
+                    || body.contains_function(name)
+            }
+            Node::SumOver(_, list, body) => {
+                list.contains_function(name) || body.contains_function(name)
+            }
+            Node::Integral(_, bounds, body) => {
+                bounds.as_ref().is_some_and(|(lo, hi)| {
+                    lo.contains_function(name) || hi.contains_function(name)
+                }) || body.contains_function(name)
+            }
+            Node::Limit(_, point, _, body) => {
+                point.contains_function(name) || body.contains_function(name)
+            }
+            Node::Derivative(_, body) => body.contains_function(name),
         }
     }
 
@@ -101,9 +235,13 @@ impl Node {
             | Node::Less(_, _)
             | Node::GreaterEqual(_, _)
             | Node::LessEqual(_, _)
-            | Node::Equal(_, _) => 1,
+            | Node::Equal(_, _)
+            | Node::And(_, _)
+            | Node::Or(_, _)
+            | Node::Not(_) => 1,
             Node::Add(_, _) | Node::Subtract(_, _) => 2,
             Node::Multiply(_, _) | Node::Divide(_, _) => 3,
+            _ if self.is_modulo() => 3,
             Node::Power(_, _) => 4,
             Node::Factorial(_) => 5,
             Node::Negate(_) => 5,
@@ -119,10 +257,14 @@ impl Node {
         f: &mut fmt::Formatter,
     ) -> fmt::Result {
         let child_prec = child.precedence();
+        // `a \bmod b` groups from the left at the level of a product, so
+        // on the right of either it keeps its parentheses
         let needs_parens = child_prec < parent_prec
             || (child_prec == parent_prec
                 && is_right
-                && matches!(self, Node::Subtract(_, _) | Node::Divide(_, _)));
+                && (matches!(self, Node::Subtract(_, _) | Node::Divide(_, _))
+                    || self.is_modulo()
+                    || child.is_modulo()));
 
         if needs_parens {
             write!(f, "({})", child)
@@ -131,6 +273,36 @@ impl Node {
         }
     }
 
+    /// How loosely a node binds among the logical connectives: `=`, then
+    /// `\lor`, `\land`, `\lnot`, and everything else as tight as a
+    /// comparison.
+    fn logic_level(&self) -> u8 {
+        match self {
+            Node::Equation(_, _) => 0,
+            Node::Or(_, _) => 1,
+            Node::And(_, _) => 2,
+            Node::Not(_) => 3,
+            _ => 4,
+        }
+    }
+
+    /// Write an operand of a connective or a comparison at `level`,
+    /// parenthesized when it binds more loosely (or as loosely, on the
+    /// right, since connectives group from the left).
+    fn fmt_logic_operand(
+        child: &Node,
+        level: u8,
+        is_right: bool,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        let child_level = child.logic_level();
+        if child_level < level || (is_right && child_level == level && level < 4) {
+            write!(f, "({})", child)
+        } else {
+            write!(f, "{}", child)
+        }
+    }
+
     /// Format a node as if it were a right child of an Add node at the given precedence.
     /// Used when we synthesize a positive version of a negative Multiply term.
     fn fmt_as_add_right_child(
@@ -152,7 +324,14 @@ impl Node {
         matches!(
             node,
             Node::Variable(_) | Node::Power(_, _) | Node::Sqrt(_) | Node::Function(_, _)
-        )
+        ) && !node.is_modulo()
+            // `3 \cdot 2^{2}`, not `32^{2}`
+            && !matches!(node, Node::Power(base, _) if matches!(**base, Node::Num(_)))
+    }
+
+    /// `a \bmod b`, which prints as an infix operator.
+    fn is_modulo(&self) -> bool {
+        matches!(self, Node::Function(name, args) if name == "mod" && args.len() == 2)
     }
 
     /// Is this expression *provably* free of `var`? Only node kinds this
@@ -170,27 +349,114 @@ impl Node {
             | Node::Multiply(l, r)
             | Node::Divide(l, r)
             | Node::Power(l, r) => l.is_provably_free_of(var) && r.is_provably_free_of(var),
-            Node::Negate(inner) | Node::Sqrt(inner) | Node::Abs(inner) => {
-                inner.is_provably_free_of(var)
-            }
+            Node::Negate(inner)
+            | Node::Sqrt(inner)
+            | Node::Abs(inner)
+            | Node::Floor(inner)
+            | Node::Ceil(inner)
+            | Node::Round(inner)
+            | Node::Trunc(inner) => inner.is_provably_free_of(var),
             Node::Function(_, args) => args.iter().all(|a| a.is_provably_free_of(var)),
             _ => false,
         }
     }
+
+    /// The terms of a chain of `+` and `-`, in order, each with whether it
+    /// is subtracted; a node that is not a sum is its only term. Walks the
+    /// chain with its own stack, so a sum of thousands of terms does not
+    /// recurse that deep.
+    pub(crate) fn sum_terms(&self) -> Vec<(&Node, bool)> {
+        let mut terms = Vec::new();
+        let mut pending = vec![(self, false)];
+        while let Some((node, negative)) = pending.pop() {
+            match node {
+                Node::Add(left, right) => {
+                    pending.push((right, negative));
+                    pending.push((left, negative));
+                }
+                Node::Subtract(left, right) => {
+                    pending.push((right, !negative));
+                    pending.push((left, negative));
+                }
+                term => terms.push((term, negative)),
+            }
+        }
+        terms
+    }
+
+    /// Whether the two trees are the same up to the order and grouping of
+    /// the operands of `+` and `\cdot`: `x + (y + 1)` and `(1 + y) + x`
+    /// are, `x - y` and `-y + x` are not. No simplification happens, so
+    /// `2x` and `x + x` are not either; for that, see
+    /// [`are_equivalent`](crate::verify::are_equivalent).
+    pub fn structurally_equal(&self, other: &Node) -> bool {
+        match (self, other) {
+            (Node::Add(..), Node::Add(..)) | (Node::Multiply(..), Node::Multiply(..)) => {
+                let mut left = Vec::new();
+                let mut right = Vec::new();
+                collect_operands(self, self, &mut left);
+                collect_operands(other, other, &mut right);
+                left.len() == right.len()
+                    && left.iter().all(|operand| {
+                        match right.iter().position(|o| operand.structurally_equal(o)) {
+                            Some(i) => {
+                                right.swap_remove(i);
+                                true
+                            }
+                            None => false,
+                        }
+                    })
+            }
+            _ => {
+                same_operator(self, other)
+                    && self
+                        .children()
+                        .iter()
+                        .zip(other.children())
+                        .all(|(a, b)| a.structurally_equal(b))
+            }
+        }
+    }
+}
+
+/// The operands of a chain of `op`s, `op` being a `+` or a `\cdot`.
+fn collect_operands<'a>(op: &Node, node: &'a Node, out: &mut Vec<&'a Node>) {
+    match (op, node) {
+        (Node::Add(..), Node::Add(l, r)) | (Node::Multiply(..), Node::Multiply(l, r)) => {
+            collect_operands(op, l, out);
+            collect_operands(op, r, out);
+        }
+        _ => out.push(node),
+    }
+}
+
+/// Whether two nodes have the same operator and everything but their
+/// children in common: the name of a function or bound variable, the side
+/// of a limit, the shape of a matrix.
+pub(crate) fn same_operator(a: &Node, b: &Node) -> bool {
+    match (a, b) {
+        (Node::Num(x), Node::Num(y)) => x == y,
+        (Node::Variable(x), Node::Variable(y)) => x == y,
+        (Node::Function(f, xs), Node::Function(g, ys)) => f == g && xs.len() == ys.len(),
+        (Node::Summation(i, ..), Node::Summation(j, ..))
+        | (Node::Product(i, ..), Node::Product(j, ..))
+        | (Node::SumOver(i, ..), Node::SumOver(j, ..))
+        | (Node::Derivative(i, _), Node::Derivative(j, _)) => i == j,
+        (Node::Integral(x, p, _), Node::Integral(y, q, _)) => x == y && p.is_some() == q.is_some(),
+        (Node::Limit(x, _, d, _), Node::Limit(y, _, e, _)) => x == y && d == e,
+        (Node::Piecewise(p), Node::Piecewise(q)) => p.len() == q.len(),
+        (Node::Matrix(p), Node::Matrix(q)) => {
+            p.len() == q.len() && p.iter().zip(q).all(|(r, s)| r.len() == s.len())
+        }
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
 }
 
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Node::Num(n) => write!(f, "{}", n),
-            Node::Variable(v) => {
-                if v.chars().count() == 1 {
-                    if let Some(latex) = crate::tokenizer::latex_name(v.chars().next().unwrap()) {
-                        return write!(f, "{}", latex);
-                    }
-                }
-                write!(f, "{}", v)
-            }
+            Node::Variable(v) => write!(f, "{}", crate::tokenizer::variable_latex(v)),
             Node::Add(left, right) => {
                 self.fmt_child(left, 2, false, f)?;
                 match right.as_ref() {
@@ -269,7 +535,10 @@ impl fmt::Display for Node {
                         | Node::Multiply(_, _)
                         | Node::Divide(_, _)
                         | Node::Negate(_)
-                );
+                ) || base.is_modulo()
+                    // `\frac{2}{3}^{n}` and `-2^{n}` read back as 2/3ⁿ and −2ⁿ
+                    || matches!(**base, Node::Num(ref n)
+                        if n.is_negative() || matches!(n, ExactNum::Rational(_)) && !n.is_integer());
                 if base_needs_parens {
                     write!(f, "({})", base)?;
                 } else {
@@ -284,7 +553,8 @@ impl fmt::Display for Node {
             Node::Round(operand) => write!(f, "\\round{{{}}}", operand),
             Node::Trunc(operand) => write!(f, "\\trunc{{{}}}", operand),
             Node::Negate(operand) => {
-                let needs_parens = matches!(**operand, Node::Add(_, _) | Node::Subtract(_, _));
+                let needs_parens = matches!(**operand, Node::Add(_, _) | Node::Subtract(_, _))
+                    || operand.is_modulo();
                 if needs_parens {
                     write!(f, "-({})", operand)
                 } else {
@@ -300,18 +570,52 @@ impl fmt::Display for Node {
                         | Node::Divide(_, _)
                         | Node::Power(_, _)
                         | Node::Negate(_)
-                );
+                ) || operand.is_modulo();
                 if needs_parens {
                     write!(f, "({})!", operand)
                 } else {
                     write!(f, "{}!", operand)
                 }
             }
-            Node::Greater(left, right) => write!(f, "{} > {}", left, right),
-            Node::Less(left, right) => write!(f, "{} < {}", left, right),
-            Node::GreaterEqual(left, right) => write!(f, "{} >= {}", left, right),
-            Node::LessEqual(left, right) => write!(f, "{} <= {}", left, right),
-            Node::Equal(left, right) => write!(f, "{} == {}", left, right),
+            Node::Greater(left, right) => {
+                Node::fmt_logic_operand(left, 4, false, f)?;
+                write!(f, " > ")?;
+                Node::fmt_logic_operand(right, 4, false, f)
+            }
+            Node::Less(left, right) => {
+                Node::fmt_logic_operand(left, 4, false, f)?;
+                write!(f, " < ")?;
+                Node::fmt_logic_operand(right, 4, false, f)
+            }
+            Node::GreaterEqual(left, right) => {
+                Node::fmt_logic_operand(left, 4, false, f)?;
+                write!(f, " >= ")?;
+                Node::fmt_logic_operand(right, 4, false, f)
+            }
+            Node::LessEqual(left, right) => {
+                Node::fmt_logic_operand(left, 4, false, f)?;
+                write!(f, " <= ")?;
+                Node::fmt_logic_operand(right, 4, false, f)
+            }
+            Node::Equal(left, right) => {
+                Node::fmt_logic_operand(left, 4, false, f)?;
+                write!(f, " == ")?;
+                Node::fmt_logic_operand(right, 4, false, f)
+            }
+            Node::And(left, right) => {
+                Node::fmt_logic_operand(left, 2, false, f)?;
+                write!(f, " \\land ")?;
+                Node::fmt_logic_operand(right, 2, true, f)
+            }
+            Node::Or(left, right) => {
+                Node::fmt_logic_operand(left, 1, false, f)?;
+                write!(f, " \\lor ")?;
+                Node::fmt_logic_operand(right, 1, true, f)
+            }
+            Node::Not(operand) => {
+                write!(f, "\\lnot ")?;
+                Node::fmt_logic_operand(operand, 3, false, f)
+            }
             Node::Equation(left, right) => write!(f, "{} = {}", left, right),
             Node::Piecewise(conditions) => {
                 let mut formatted_conditions = String::new();
@@ -320,6 +624,20 @@ impl fmt::Display for Node {
                 }
                 write!(f, "piecewise({})", formatted_conditions)
             }
+            Node::Matrix(rows) => {
+                let rows: Vec<String> = rows
+                    .iter()
+                    .map(|row| {
+                        let entries: Vec<String> = row.iter().map(|e| e.to_string()).collect();
+                        entries.join(" & ")
+                    })
+                    .collect();
+                write!(
+                    f,
+                    "\\begin{{pmatrix}} {} \\end{{pmatrix}}",
+                    rows.join(" \\\\ ")
+                )
+            }
             Node::Summation(index_var, start, end, body) => {
                 write!(
                     f,
@@ -334,13 +652,58 @@ impl fmt::Display for Node {
                     index_var, start, end, body
                 )
             }
+            Node::SumOver(index_var, list, body) => {
+                write!(f, "\\sum_{{{} \\in {}}}{{{}}}", index_var, list, body)
+            }
+            Node::Integral(var, bounds, body) => {
+                write!(f, "\\int")?;
+                if let Some((lower, upper)) = bounds {
+                    write!(f, "_{{{}}}^{{{}}}", lower, upper)?;
+                }
+                write!(f, " {{{}}} \\, d{}", body, var)
+            }
+            Node::Limit(var, point, direction, body) => {
+                write!(
+                    f,
+                    "\\lim_{{{} \\to {}{}}}{{{}}}",
+                    var,
+                    point,
+                    direction.latex_suffix(),
+                    body
+                )
+            }
+            Node::Derivative(var, body) => write!(
+                f,
+                "\\frac{{d}}{{d{}}}{{{}}}",
+                crate::tokenizer::variable_latex(var),
+                body
+            ),
+            Node::Function(name, args) if crate::vector::is_vector_function(name) => {
+                crate::vector::fmt_vector_function(name, args, f)
+            }
+            Node::Function(_, args) if self.is_modulo() => {
+                self.fmt_child(&args[0], 3, false, f)?;
+                write!(f, " \\bmod ")?;
+                self.fmt_child(&args[1], 3, true, f)
+            }
             Node::Function(name, args) => {
                 let formatted_args = args
                     .iter()
                     .map(|arg| format!("{}", arg))
                     .collect::<Vec<_>>()
                     .join(", ");
-                write!(f, "\\{}({})", name, formatted_args)
+                // Environment-defined functions are plain identifiers, so
+                // they print without a backslash and parse back as `f(x)`;
+                // a longer name is marked as one, `\\operatorname{area}(r)`
+                if crate::functions::FUNCTION_REGISTRY.get(name).is_some()
+                    && !crate::functions::OPERATOR_NAMES.contains(&name.as_str())
+                {
+                    write!(f, "\\{}({})", name, formatted_args)
+                } else if name.chars().count() > 1 {
+                    write!(f, "\\operatorname{{{}}}({})", name, formatted_args)
+                } else {
+                    write!(f, "{}({})", name, formatted_args)
+                }
             }
         }
     }
@@ -0,0 +1,117 @@
+//! Progress reporting for long numeric jobs.
+//!
+//! Large `\sum`/`\prod` ranges and adaptive quadrature can take seconds on
+//! a slow device. [`with_progress`] runs a computation with a callback
+//! installed for the current thread; the outermost such job inside it
+//! reports how far along it is. Nested jobs (the inner sum of a double
+//! sum, a quadrature inside a summand) stay silent so the reported
+//! fraction only ever moves forward. Without a callback, reporting costs
+//! one thread-local read per job.
+
+use std::cell::{Cell, RefCell};
+
+/// Work completed out of a known total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub completed: u64,
+    pub total: u64,
+}
+
+impl Progress {
+    /// Percent complete, 0 to 100; an empty job is complete.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            100.0 * self.completed as f64 / self.total as f64
+        }
+    }
+}
+
+type Callback = Box<dyn FnMut(Progress)>;
+
+thread_local! {
+    static CALLBACK: RefCell<Option<Callback>> = const { RefCell::new(None) };
+    static JOB_ACTIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `work` with `callback` receiving progress from the outermost long
+/// job it starts. The callback is called at most about a thousand times
+/// per job, and with `completed == total` once the job finishes.
+pub fn with_progress<T>(callback: impl FnMut(Progress) + 'static, work: impl FnOnce() -> T) -> T {
+    let previous = CALLBACK.with(|c| c.borrow_mut().replace(Box::new(callback)));
+    let result = work();
+    CALLBACK.with(|c| *c.borrow_mut() = previous);
+    result
+}
+
+/// One reporting job: `total` steps, advanced as they complete.
+pub(crate) struct Job {
+    /// Whether this job reports; only the outermost job does
+    reporting: bool,
+    completed: u64,
+    total: u64,
+    /// Completion in thousandths at the last report
+    last_permille: Option<u64>,
+}
+
+impl Job {
+    pub(crate) fn start(total: u64) -> Job {
+        let reporting = CALLBACK.with(|c| c.borrow().is_some()) && !JOB_ACTIVE.with(|a| a.get());
+        if reporting {
+            JOB_ACTIVE.with(|a| a.set(true));
+        }
+        let mut job = Job {
+            reporting,
+            completed: 0,
+            total,
+            last_permille: None,
+        };
+        job.report();
+        job
+    }
+
+    pub(crate) fn advance(&mut self, steps: u64) {
+        self.set(self.completed.saturating_add(steps));
+    }
+
+    /// Move to `completed` steps; a smaller count than already reported
+    /// is ignored.
+    pub(crate) fn set(&mut self, completed: u64) {
+        if completed > self.completed {
+            self.completed = completed.min(self.total);
+            self.report();
+        }
+    }
+
+    fn report(&mut self) {
+        if !self.reporting {
+            return;
+        }
+        let permille = match self.total {
+            0 => 1000,
+            total => (self.completed as u128 * 1000 / total as u128) as u64,
+        };
+        if self.last_permille == Some(permille) {
+            return;
+        }
+        self.last_permille = Some(permille);
+        let progress = Progress {
+            completed: self.completed,
+            total: self.total,
+        };
+        CALLBACK.with(|c| {
+            if let Some(callback) = c.borrow_mut().as_mut() {
+                callback(progress);
+            }
+        });
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        if self.reporting {
+            JOB_ACTIVE.with(|a| a.set(false));
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Floating-point special functions: erf/erfc, Γ and ln|Γ|, and the beta
+//! function.
+//!
+//! These are the numeric values behind the `\erf`, `\erfc`, `\Gamma`,
+//! `\lgamma` and `\Beta` registry functions and behind `x!` for
+//! non-integer `x`. Each is accurate to a few ulps over its domain (relative
+//! error below 1e-14 in the tests against published values); the exact
+//! integer cases (`n!`, Γ(n), B(m, n)) are computed exactly by the callers
+//! and never reach these.
+
+use crate::exact::ExactNum;
+use std::f64::consts::PI;
+
+const FRAC_2_SQRT_PI: f64 = std::f64::consts::FRAC_2_SQRT_PI;
+
+/// erf(x) = (2/√π)∫₀ˣ e^{−t²} dt (DLMF 7.2.1).
+pub fn erf(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x.abs() < 3.0 {
+        erf_series(x)
+    } else {
+        (1.0 - erfc_continued_fraction(x.abs())).copysign(x)
+    }
+}
+
+/// erfc(x) = 1 − erf(x), without the cancellation of computing it that way
+/// for large `x` (DLMF 7.2.2).
+pub fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x < 0.0 {
+        2.0 - erfc(-x)
+    } else if x < 2.0 {
+        1.0 - erf_series(x)
+    } else {
+        erfc_continued_fraction(x)
+    }
+}
+
+/// erf(x) = (2/√π)·e^{−x²}·Σ 2ⁿx^{2n+1}/(1·3·…·(2n+1)) (DLMF 7.6.2): every
+/// term is positive, so there is no cancellation for moderate |x|.
+fn erf_series(x: f64) -> f64 {
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 0.0;
+    while term.abs() > sum.abs() * f64::EPSILON {
+        n += 1.0;
+        term *= 2.0 * x2 / (2.0 * n + 1.0);
+        sum += term;
+    }
+    FRAC_2_SQRT_PI * (-x2).exp() * sum
+}
+
+/// erfc(x) for x ≥ 2 from its continued fraction (DLMF 7.9.2), evaluated
+/// with the modified Lentz method.
+fn erfc_continued_fraction(x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    // erfc(x) = (e^{−x²}/√π) · 1/(x + (1/2)/(x + 1/(x + (3/2)/(x + …))))
+    let mut f = x;
+    let mut c = x;
+    let mut d = 0.0;
+    for k in 1..500 {
+        let a = k as f64 / 2.0;
+        d = x + a * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = x + a / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = c * d;
+        f *= delta;
+        if (delta - 1.0).abs() < f64::EPSILON {
+            break;
+        }
+    }
+    (-x * x).exp() / (PI.sqrt() * f)
+}
+
+// Lanczos approximation with Godfrey's coefficients for g = 671/128
+// (Numerical Recipes, 3rd ed., §6.1): relative error below 1e-15 for x > 0.
+const LANCZOS_G: f64 = 5.242_187_5;
+const LANCZOS_SERIES_START: f64 = 0.999_999_999_999_997_1;
+const LANCZOS_COEFFICIENTS: [f64; 14] = [
+    57.156_235_665_862_92,
+    -59.597_960_355_475_49,
+    14.136_097_974_741_747,
+    -0.491_913_816_097_620_2,
+    3.399_464_998_481_189e-5,
+    4.652_362_892_704_858e-5,
+    -9.837_447_530_487_956e-5,
+    1.580_887_032_249_125e-4,
+    -2.102_644_417_241_049e-4,
+    2.174_396_181_152_126_4e-4,
+    -1.643_181_065_367_639e-4,
+    8.441_822_398_385_274e-5,
+    -2.619_083_840_158_141e-5,
+    3.689_918_265_953_162_5e-6,
+];
+const SQRT_2PI: f64 = 2.506_628_274_631_000_5;
+
+/// The Lanczos sum divided by `x`, and `t = x + g`, for Γ(x) with x > 0.
+fn lanczos(x: f64) -> (f64, f64) {
+    let mut sum = LANCZOS_SERIES_START;
+    for (j, c) in LANCZOS_COEFFICIENTS.iter().enumerate() {
+        sum += c / (x + (j + 1) as f64);
+    }
+    (SQRT_2PI * sum / x, x + LANCZOS_G)
+}
+
+/// Γ(x), with the reflection formula for negative x (DLMF 5.5.3). NaN at
+/// the poles 0, −1, −2, …; infinite past x ≈ 171.6.
+pub fn gamma(x: f64) -> f64 {
+    if x.is_nan() || (x <= 0.0 && x.fract() == 0.0) {
+        return f64::NAN;
+    }
+    if x < 0.0 {
+        return PI / ((PI * x).sin() * gamma(1.0 - x));
+    }
+    if x > 171.7 {
+        return f64::INFINITY;
+    }
+    let (sum, t) = lanczos(x);
+    // t^{x+½} split in two so it does not overflow before e^{−t} scales it.
+    let half_power = t.powf((x + 0.5) / 2.0);
+    half_power * (-t).exp() * half_power * sum
+}
+
+/// ln|Γ(x)| (DLMF 5.5.3 for negative x). Infinite at the poles.
+pub fn ln_gamma(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x <= 0.0 && x.fract() == 0.0 {
+        return f64::INFINITY;
+    }
+    if x < 0.0 {
+        return (PI / (PI * x).sin().abs()).ln() - ln_gamma(1.0 - x);
+    }
+    let (sum, t) = lanczos(x);
+    (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// B(a, b) = Γ(a)Γ(b)/Γ(a + b) (DLMF 5.12.1), through ln Γ when both
+/// arguments are positive so large ones do not overflow Γ.
+pub fn beta(a: f64, b: f64) -> f64 {
+    if a > 0.0 && b > 0.0 {
+        (ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b)).exp()
+    } else {
+        gamma(a) * gamma(b) / gamma(a + b)
+    }
+}
+
+/// x! for any real `x` that is not a negative integer: exact for a
+/// non-negative integer, Γ(x + 1) otherwise. `None` at the poles.
+pub fn factorial(x: &ExactNum) -> Option<ExactNum> {
+    if let Some(exact) = crate::integer::factorial(x) {
+        return Some(exact);
+    }
+    if x.is_integer() {
+        return None;
+    }
+    let value = gamma(x.to_f64() + 1.0);
+    value.is_finite().then_some(ExactNum::Float(value))
+}
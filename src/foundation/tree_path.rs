@@ -0,0 +1,203 @@
+//! Addressing and editing subtrees by path.
+//!
+//! A path is the list of child indices from the root down, so `[1, 0]` is
+//! the first child of the root's second child and `[]` is the root. The
+//! children of a node are numbered in the order of its `args` in the JSON
+//! AST ([`ast_json`](crate::ast_json)): `[left, right]` for a binary
+//! operator, `[start, end, body]` for a sum, `[lower, upper, body]` for a
+//! definite integral, `[value, condition, …]` for a piecewise expression,
+//! and the entries row by row for a matrix.
+//! An equation editor can replace the subtree under the cursor with
+//! [`Node::replace_at`] instead of re-parsing the whole input, and send
+//! only what changed with [`diff_trees`].
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::node::Node;
+
+/// One subtree replacement: the node at `path` goes from `old` to `new`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeEdit {
+    pub path: Vec<usize>,
+    pub old: Node,
+    pub new: Node,
+}
+
+/// `path: old → new`, with the path as `[1, 0]`.
+impl fmt::Display for TreeEdit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {} → {}", self.path, self.old, self.new)
+    }
+}
+
+impl Node {
+    /// Direct children, in path order.
+    pub fn children(&self) -> Vec<&Node> {
+        match self {
+            Node::Num(_) | Node::Variable(_) => vec![],
+            Node::Add(l, r)
+            | Node::Subtract(l, r)
+            | Node::Multiply(l, r)
+            | Node::Divide(l, r)
+            | Node::Power(l, r)
+            | Node::Greater(l, r)
+            | Node::Less(l, r)
+            | Node::GreaterEqual(l, r)
+            | Node::LessEqual(l, r)
+            | Node::And(l, r)
+            | Node::Or(l, r)
+            | Node::Equal(l, r)
+            | Node::Equation(l, r) => vec![l, r],
+            Node::Sqrt(inner)
+            | Node::Abs(inner)
+            | Node::Floor(inner)
+            | Node::Ceil(inner)
+            | Node::Round(inner)
+            | Node::Trunc(inner)
+            | Node::Negate(inner)
+            | Node::Not(inner)
+            | Node::Factorial(inner)
+            | Node::Derivative(_, inner) => vec![inner],
+            Node::Piecewise(arms) => arms.iter().flat_map(|(v, c)| [v, c]).collect(),
+            Node::Matrix(rows) => rows.iter().flatten().collect(),
+            Node::Summation(_, start, end, body) | Node::Product(_, start, end, body) => {
+                vec![start, end, body]
+            }
+            Node::SumOver(_, list, body) => vec![list, body],
+            Node::Integral(_, None, body) => vec![body],
+            Node::Integral(_, Some((lower, upper)), body) => vec![lower, upper, body],
+            Node::Limit(_, point, _, body) => vec![point, body],
+            Node::Function(_, args) => args.iter().collect(),
+        }
+    }
+
+    /// Direct children, mutably, in path order.
+    pub fn children_mut(&mut self) -> Vec<&mut Node> {
+        match self {
+            Node::Num(_) | Node::Variable(_) => vec![],
+            Node::Add(l, r)
+            | Node::Subtract(l, r)
+            | Node::Multiply(l, r)
+            | Node::Divide(l, r)
+            | Node::Power(l, r)
+            | Node::Greater(l, r)
+            | Node::Less(l, r)
+            | Node::GreaterEqual(l, r)
+            | Node::LessEqual(l, r)
+            | Node::And(l, r)
+            | Node::Or(l, r)
+            | Node::Equal(l, r)
+            | Node::Equation(l, r) => vec![l, r],
+            Node::Sqrt(inner)
+            | Node::Abs(inner)
+            | Node::Floor(inner)
+            | Node::Ceil(inner)
+            | Node::Round(inner)
+            | Node::Trunc(inner)
+            | Node::Negate(inner)
+            | Node::Not(inner)
+            | Node::Factorial(inner)
+            | Node::Derivative(_, inner) => vec![inner],
+            Node::Piecewise(arms) => arms.iter_mut().flat_map(|(v, c)| [v, c]).collect(),
+            Node::Matrix(rows) => rows.iter_mut().flatten().collect(),
+            Node::Summation(_, start, end, body) | Node::Product(_, start, end, body) => {
+                vec![start, end, body]
+            }
+            Node::SumOver(_, list, body) => vec![list, body],
+            Node::Integral(_, None, body) => vec![body],
+            Node::Integral(_, Some((lower, upper)), body) => vec![lower, upper, body],
+            Node::Limit(_, point, _, body) => vec![point, body],
+            Node::Function(_, args) => args.iter_mut().collect(),
+        }
+    }
+
+    /// The subtree at `path`, or `None` when the path leaves the tree.
+    pub fn get_at(&self, path: &[usize]) -> Option<&Node> {
+        path.iter()
+            .try_fold(self, |node, &i| node.children().get(i).copied())
+    }
+
+    /// The subtree at `path`, mutably.
+    pub fn get_at_mut(&mut self, path: &[usize]) -> Option<&mut Node> {
+        let Some((&first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        self.children_mut().into_iter().nth(first)?.get_at_mut(rest)
+    }
+
+    /// Put `subtree` at `path` and return the subtree it replaces.
+    pub fn replace_at(&mut self, path: &[usize], subtree: Node) -> Result<Node, String> {
+        if self.get_at(path).is_none() {
+            return Err(format!("No subtree at path {:?} in {}", path, self));
+        }
+        let target = self.get_at_mut(path).expect("the path was just checked");
+        Ok(std::mem::replace(target, subtree))
+    }
+}
+
+/// The smallest replacements that turn `old` into `new`: nodes of the same
+/// kind (same operator, variable, function name and number of children)
+/// are compared child by child, and any other difference replaces the
+/// whole subtree. Paths refer to both trees, since no edit changes the
+/// shape above it; an empty list means the trees are equal.
+pub fn diff_trees(old: &Node, new: &Node) -> Vec<TreeEdit> {
+    let mut edits = Vec::new();
+    diff_into(old, new, &mut Vec::new(), &mut edits);
+    edits
+}
+
+/// Apply edits from [`diff_trees`] to `tree`, checking that each replaced
+/// subtree is still the one the edit expects.
+pub fn apply_edits(tree: &Node, edits: &[TreeEdit]) -> Result<Node, String> {
+    let mut tree = tree.clone();
+    for edit in edits {
+        let old = tree.replace_at(&edit.path, edit.new.clone())?;
+        if old != edit.old {
+            return Err(format!(
+                "The subtree at path {:?} is {}, not {}",
+                edit.path, old, edit.old
+            ));
+        }
+    }
+    Ok(tree)
+}
+
+fn diff_into(old: &Node, new: &Node, path: &mut Vec<usize>, edits: &mut Vec<TreeEdit>) {
+    if old == new {
+        return;
+    }
+    let (old_children, new_children) = (old.children(), new.children());
+    if old_children.is_empty() || !same_kind(old, new) || old_children.len() != new_children.len() {
+        edits.push(TreeEdit {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        });
+        return;
+    }
+    for (i, (o, n)) in old_children.into_iter().zip(new_children).enumerate() {
+        path.push(i);
+        diff_into(o, n, path, edits);
+        path.pop();
+    }
+}
+
+/// Whether two nodes agree on everything except their children.
+fn same_kind(a: &Node, b: &Node) -> bool {
+    if std::mem::discriminant(a) != std::mem::discriminant(b) {
+        return false;
+    }
+    match (a, b) {
+        (Node::Summation(i, ..), Node::Summation(j, ..))
+        | (Node::Product(i, ..), Node::Product(j, ..))
+        | (Node::SumOver(i, ..), Node::SumOver(j, ..))
+        | (Node::Integral(i, ..), Node::Integral(j, ..))
+        | (Node::Derivative(i, _), Node::Derivative(j, _))
+        | (Node::Function(i, _), Node::Function(j, _)) => i == j,
+        (Node::Limit(i, _, d, _), Node::Limit(j, _, e, _)) => i == j && d == e,
+        (Node::Matrix(i), Node::Matrix(j)) => i.len() == j.len(),
+        _ => true,
+    }
+}
@@ -0,0 +1,128 @@
+//! Traversals of an expression tree, written once.
+//!
+//! A pass over a [`Node`] rarely cares about most of its variants: counting
+//! variables only looks at [`Node::Variable`], substituting only replaces
+//! it. [`Node::visit`] and [`Node::fold`] walk every subtree, and
+//! [`Node::map_children`] rebuilds a node from new children, so such a pass
+//! handles the variants it cares about and leaves the rest of the tree to
+//! these. Children are in path order, as in [`tree_path`](crate::tree_path).
+//!
+//! Σ, Π, a sum over a list, a definite integral and a limit bind a variable
+//! in their body, which is always their last child; [`Node::bound_variable`]
+//! names it, for passes that must respect the scope.
+
+use std::convert::Infallible;
+
+use crate::node::Node;
+
+impl Node {
+    /// Call `f` on this node and then on every node below it, parents
+    /// before children.
+    pub fn visit(&self, f: &mut impl FnMut(&Node)) {
+        f(self);
+        for child in self.children() {
+            child.visit(f);
+        }
+    }
+
+    /// Accumulate over this node and every node below it, in the order of
+    /// [`visit`](Self::visit): `node.fold(0, &mut |n, _| n + 1)` counts the
+    /// nodes of the tree.
+    pub fn fold<T>(&self, init: T, f: &mut impl FnMut(T, &Node) -> T) -> T {
+        let acc = f(init, self);
+        self.children()
+            .into_iter()
+            .fold(acc, |acc, child| child.fold(acc, f))
+    }
+
+    /// This node with each child replaced by `f` of it; a leaf is cloned.
+    pub fn map_children(&self, mut f: impl FnMut(&Node) -> Node) -> Node {
+        let Ok(node) = self.try_map_children(|child| Ok::<_, Infallible>(f(child)));
+        node
+    }
+
+    /// [`map_children`](Self::map_children) with a fallible `f`, stopping at
+    /// the first error.
+    pub fn try_map_children<E>(
+        &self,
+        mut f: impl FnMut(&Node) -> Result<Node, E>,
+    ) -> Result<Node, E> {
+        let mut map = |child: &Node| f(child).map(Box::new);
+        Ok(match self {
+            Node::Num(_) | Node::Variable(_) => self.clone(),
+            Node::Add(l, r) => Node::Add(map(l)?, map(r)?),
+            Node::Subtract(l, r) => Node::Subtract(map(l)?, map(r)?),
+            Node::Multiply(l, r) => Node::Multiply(map(l)?, map(r)?),
+            Node::Divide(l, r) => Node::Divide(map(l)?, map(r)?),
+            Node::Power(l, r) => Node::Power(map(l)?, map(r)?),
+            Node::Greater(l, r) => Node::Greater(map(l)?, map(r)?),
+            Node::Less(l, r) => Node::Less(map(l)?, map(r)?),
+            Node::GreaterEqual(l, r) => Node::GreaterEqual(map(l)?, map(r)?),
+            Node::LessEqual(l, r) => Node::LessEqual(map(l)?, map(r)?),
+            Node::Equal(l, r) => Node::Equal(map(l)?, map(r)?),
+            Node::And(l, r) => Node::And(map(l)?, map(r)?),
+            Node::Or(l, r) => Node::Or(map(l)?, map(r)?),
+            Node::Equation(l, r) => Node::Equation(map(l)?, map(r)?),
+            Node::Sqrt(inner) => Node::Sqrt(map(inner)?),
+            Node::Abs(inner) => Node::Abs(map(inner)?),
+            Node::Floor(inner) => Node::Floor(map(inner)?),
+            Node::Ceil(inner) => Node::Ceil(map(inner)?),
+            Node::Round(inner) => Node::Round(map(inner)?),
+            Node::Trunc(inner) => Node::Trunc(map(inner)?),
+            Node::Negate(inner) => Node::Negate(map(inner)?),
+            Node::Not(inner) => Node::Not(map(inner)?),
+            Node::Factorial(inner) => Node::Factorial(map(inner)?),
+            Node::Derivative(var, inner) => Node::Derivative(var.clone(), map(inner)?),
+            Node::Piecewise(arms) => Node::Piecewise(
+                arms.iter()
+                    .map(|(value, condition)| Ok((*map(value)?, *map(condition)?)))
+                    .collect::<Result<_, E>>()?,
+            ),
+            Node::Matrix(rows) => Node::Matrix(
+                rows.iter()
+                    .map(|row| row.iter().map(|entry| map(entry).map(|e| *e)).collect())
+                    .collect::<Result<_, E>>()?,
+            ),
+            Node::Summation(index, start, end, body) => {
+                Node::Summation(index.clone(), map(start)?, map(end)?, map(body)?)
+            }
+            Node::Product(index, start, end, body) => {
+                Node::Product(index.clone(), map(start)?, map(end)?, map(body)?)
+            }
+            Node::SumOver(index, list, body) => {
+                Node::SumOver(index.clone(), map(list)?, map(body)?)
+            }
+            Node::Integral(var, bounds, body) => {
+                let bounds = match bounds {
+                    Some((lower, upper)) => Some((map(lower)?, map(upper)?)),
+                    None => None,
+                };
+                Node::Integral(var.clone(), bounds, map(body)?)
+            }
+            Node::Limit(var, point, direction, body) => {
+                Node::Limit(var.clone(), map(point)?, *direction, map(body)?)
+            }
+            Node::Function(name, args) => Node::Function(
+                name.clone(),
+                args.iter()
+                    .map(|arg| map(arg).map(|a| *a))
+                    .collect::<Result<_, E>>()?,
+            ),
+        })
+    }
+
+    /// The variable this node binds in its body, its last child: the index
+    /// of Σ, Π and a sum over a list, the variable of a definite integral
+    /// or a limit. An indefinite integral and a derivative are functions of
+    /// their variable and bind nothing.
+    pub fn bound_variable(&self) -> Option<&str> {
+        match self {
+            Node::Summation(var, ..)
+            | Node::Product(var, ..)
+            | Node::SumOver(var, ..)
+            | Node::Integral(var, Some(_), _)
+            | Node::Limit(var, ..) => Some(var),
+            _ => None,
+        }
+    }
+}
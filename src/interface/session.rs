@@ -0,0 +1,234 @@
+//! Interactive sessions that remember assignments.
+//!
+//! A [`Session`] reads one input at a time. An assignment such as `x = 5`
+//! or `y = x^2 + 1` binds the name instead of being kept as an equation,
+//! and later inputs see the binding: `x + 2` gives `7`. Names whose value
+//! is a number live in the session's [`Environment`]; names bound to an
+//! expression that still has free variables are substituted into later
+//! inputs before simplification.
+//!
+//! `ans` names the previous result, as on a calculator: the value of the
+//! last input, or of the last assignment.
+
+use std::fmt;
+
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::exact::ExactNum;
+use crate::node::Node;
+use crate::parser::parse_latex_raw_with_env;
+use crate::simplify::Simplifiable;
+use crate::substitute::substitute_variable;
+
+/// What a name is bound to in a [`Session`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    Value(ExactNum),
+    List(Vec<ExactNum>),
+    /// An expression with free variables, such as `y = x + 1` before `x`
+    /// is bound
+    Expression(Node),
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Value(v) => write!(f, "{}", Node::Num(v.clone())),
+            Binding::List(values) => {
+                let items: Vec<String> = values
+                    .iter()
+                    .map(|v| Node::Num(v.clone()).to_string())
+                    .collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            Binding::Expression(expr) => write!(f, "{}", expr),
+        }
+    }
+}
+
+/// Result of [`Session::execute`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionOutcome {
+    /// The input was an assignment to `name`
+    Assigned { name: String, binding: Binding },
+    /// Any other input, simplified with the session's bindings applied
+    Evaluated(Node),
+}
+
+/// The name of the previous result.
+pub const ANS: &str = "ans";
+
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    env: Environment,
+    /// Symbolic bindings, in assignment order
+    expressions: Vec<(String, Node)>,
+    /// The previous result, read as [`ANS`]
+    ans: Option<Node>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Session::default()
+    }
+
+    /// A session starting from the bindings, functions and settings in `env`.
+    pub fn with_environment(env: Environment) -> Self {
+        Session {
+            env,
+            expressions: Vec::new(),
+            ans: None,
+        }
+    }
+
+    /// The numeric bindings; pass this to evaluation done outside the session.
+    pub fn environment(&self) -> &Environment {
+        &self.env
+    }
+
+    /// Run one input. `name = rhs` is an assignment when `name` is a plain
+    /// variable that `rhs` does not mention, or that is already bound (so
+    /// `n = n + 1` increments). Other equations are simplified like any
+    /// other input.
+    pub fn execute(&mut self, latex: &str) -> Result<SessionOutcome, String> {
+        let expr = parse_latex_raw_with_env(latex, &self.env)?;
+        if let Node::Equation(lhs, rhs) = &expr {
+            if let Node::Variable(name) = lhs.as_ref() {
+                if !rhs.contains_variable(name) || self.is_bound(name) {
+                    let binding = self.assign(name, rhs)?;
+                    match &binding {
+                        Binding::Value(value) => self.ans = Some(Node::Num(value.clone())),
+                        Binding::Expression(expr) => self.ans = Some(expr.clone()),
+                        Binding::List(_) => {}
+                    }
+                    return Ok(SessionOutcome::Assigned {
+                        name: name.clone(),
+                        binding,
+                    });
+                }
+            }
+        }
+        let result = self.resolve(&expr)?.simplify(&self.env)?;
+        self.ans = Some(result.clone());
+        Ok(SessionOutcome::Evaluated(result))
+    }
+
+    /// The previous result, if there has been one.
+    pub fn ans(&self) -> Option<&Node> {
+        self.ans.as_ref()
+    }
+
+    /// Make `value` the previous result, for a result computed outside
+    /// [`execute`](Self::execute).
+    pub fn set_ans(&mut self, value: Node) {
+        self.ans = Some(value);
+    }
+
+    /// Bind `name` to `expr` with the current bindings applied: to its
+    /// value when that is a finite number, otherwise to the simplified
+    /// expression.
+    pub fn assign(&mut self, name: &str, expr: &Node) -> Result<Binding, String> {
+        // Assigning a constant would be silently ignored by some
+        // operations and honoured by others
+        if crate::constants::is_constant(name) {
+            return Err(format!("'{}' is a constant and cannot be assigned", name));
+        }
+        if name == ANS {
+            return Err(format!(
+                "'{}' is the previous result and cannot be assigned",
+                ANS
+            ));
+        }
+        let resolved = self.resolve(expr)?.simplify(&self.env)?;
+        if resolved.contains_variable(name) {
+            return Err(format!("'{}' cannot be defined in terms of itself", name));
+        }
+        self.clear(name);
+        match Evaluator::evaluate_exact(&resolved, &self.env) {
+            Ok(value) if value.to_f64().is_finite() => {
+                self.env.set_exact(name, value.clone());
+                Ok(Binding::Value(value))
+            }
+            _ => {
+                self.expressions.push((name.to_string(), resolved.clone()));
+                Ok(Binding::Expression(resolved))
+            }
+        }
+    }
+
+    /// Substitute the previous result for [`ANS`] and every bound name in
+    /// `expr`: expressions first (they may mention names bound to values),
+    /// then values. Lists stay as variables for the evaluator to read.
+    pub fn resolve(&self, expr: &Node) -> Result<Node, String> {
+        let mut resolved = match &self.ans {
+            Some(ans) if expr.contains_variable(ANS) => substitute_variable(expr, ANS, ans)?,
+            _ => expr.clone(),
+        };
+        // An expression binding can mention a name bound after it, but
+        // never (directly or not) itself, so each pass removes a level
+        for _ in 0..=self.expressions.len() {
+            let Some((name, value)) = self
+                .expressions
+                .iter()
+                .find(|(name, _)| resolved.contains_variable(name))
+            else {
+                break;
+            };
+            resolved = substitute_variable(&resolved, name, value)?;
+        }
+        for name in self.env.variable_names() {
+            if resolved.contains_variable(name) {
+                let value = Node::Num(self.env.get_exact(name).expect("listed name").clone());
+                resolved = substitute_variable(&resolved, name, &value)?;
+            }
+        }
+        Ok(resolved)
+    }
+
+    pub fn is_bound(&self, name: &str) -> bool {
+        self.env.get_exact(name).is_some()
+            || self.env.get_list(name).is_some()
+            || self.expressions.iter().any(|(n, _)| n == name)
+    }
+
+    /// Every bound name with its binding, sorted by name.
+    pub fn bindings(&self) -> Vec<(String, Binding)> {
+        let values = self.env.variable_names().map(|name| {
+            let value = self.env.get_exact(name).expect("listed name").clone();
+            (name.to_string(), Binding::Value(value))
+        });
+        let lists = self.env.list_names().map(|name| {
+            let values = self.env.get_list(name).expect("listed name").to_vec();
+            (name.to_string(), Binding::List(values))
+        });
+        let expressions = self
+            .expressions
+            .iter()
+            .map(|(name, expr)| (name.clone(), Binding::Expression(expr.clone())));
+        let mut all: Vec<(String, Binding)> = values.chain(lists).chain(expressions).collect();
+        all.sort_by(|a, b| a.0.cmp(&b.0));
+        all
+    }
+
+    /// Unbind `name`; whether it was bound.
+    pub fn clear(&mut self, name: &str) -> bool {
+        let before = self.expressions.len();
+        self.expressions.retain(|(n, _)| n != name);
+        self.env.remove(name) | (self.expressions.len() != before)
+    }
+
+    /// Unbind every variable and list. Defined functions and settings such
+    /// as assumptions stay.
+    pub fn clear_all(&mut self) {
+        let names: Vec<String> = self
+            .env
+            .variable_names()
+            .chain(self.env.list_names())
+            .map(str::to_string)
+            .collect();
+        for name in names {
+            self.env.remove(&name);
+        }
+        self.expressions.clear();
+    }
+}
@@ -1,19 +1,28 @@
+use crate::asciimath::parse_asciimath;
 use crate::composition::compose_latex;
-use crate::derivative::differentiate_latex;
+use crate::derivative::{
+    differentiate, differentiate_latex, differentiate_many, partial_derivative,
+};
 use crate::environment::Environment;
+use crate::error::ArithmaError;
 use crate::evaluator::Evaluator;
 use crate::exact::ExactNum;
 use crate::expression::extract_variable;
-use crate::integration::{definite_integral_latex, integrate_latex};
+use crate::incremental::IncrementalParser;
+use crate::inequality::{Interval, SolutionSet};
+use crate::integration::{definite_integral_latex, integrate_latex, integrate_many};
+use crate::ivp::{solve_ivp_latex, OdeMethod, OdeOptions};
 use crate::limits::limit_latex;
 use crate::matrix::parse_latex_matrix;
 use crate::node::Node;
-use crate::ode::solve_ode_latex;
-use crate::parser::{build_expression_tree, parse_latex, parse_latex_raw};
+use crate::ode::{solve_ode_equation_latex, solve_ode_latex};
+use crate::parser::{parse_latex, parse_latex_raw, parse_latex_raw_with_env};
 use crate::series::taylor_series_latex;
 use crate::simplify::Simplifiable;
 use crate::substitute::substitute_latex;
-use crate::tokenizer::Tokenizer;
+use crate::tree_path::diff_trees;
+use crate::verify::are_equivalent;
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 /// Canonical LaTeX from parse only (no simplification).
@@ -22,9 +31,112 @@ use wasm_bindgen::prelude::*;
 pub fn format_latex_js(latex_expr: &str) -> Result<String, JsValue> {
     parse_latex_raw(latex_expr)
         .map(|node| format!("{node}"))
+        .map_err(|e| JsValue::from_str(&e.render()))
+}
+
+/// Parse LaTeX (no simplification) into the JSON form of its expression
+/// tree ([`Node::to_json`]), so the frontend can cache it or send it over
+/// the wire and later rehydrate it without re-parsing.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn parse_latex_to_json_js(latex_expr: &str) -> Result<String, JsValue> {
+    parse_latex_raw(latex_expr)
+        .map_err(|e| e.render())
+        .and_then(|node| node.to_json())
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// LaTeX for AsciiMath input (`sqrt x`, `root(3)(x)`, `(x+1)/(x-1)`), to
+/// pass to the other bindings.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn asciimath_to_latex_js(asciimath: &str) -> Result<String, JsValue> {
+    parse_asciimath(asciimath)
+        .map(|node| node.to_latex())
+        .map_err(|e| JsValue::from_str(&e.render()))
+}
+
+/// Canonical LaTeX for an expression tree from [`parse_latex_to_json_js`].
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn json_to_latex_js(ast_json: &str) -> Result<String, JsValue> {
+    Node::from_json(ast_json)
+        .map(|node| format!("{node}"))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Evaluate a cached expression tree against a JSON environment.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn evaluate_json_js(ast_json: &str, env_json: &str) -> Result<f64, JsValue> {
+    let env: Environment = serde_json::from_str(env_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse environment: {}", e)))?;
+    let node = Node::from_json(ast_json).map_err(|e| JsValue::from_str(&e))?;
+    Evaluator::evaluate(&node, &env).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Replace the subtree at `path_json` (a JSON array of child indices) in a
+/// cached expression tree, returning the edited tree as JSON. Lets an
+/// equation editor change the part under the cursor without re-parsing.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn replace_at_path_js(
+    ast_json: &str,
+    path_json: &str,
+    subtree_json: &str,
+) -> Result<String, JsValue> {
+    let path: Vec<usize> = serde_json::from_str(path_json).map_err(|e| {
+        JsValue::from_str(&format!("Expected a JSON array of child indices: {}", e))
+    })?;
+    let mut node = Node::from_json(ast_json).map_err(|e| JsValue::from_str(&e))?;
+    let subtree = Node::from_json(subtree_json).map_err(|e| JsValue::from_str(&e))?;
+    node.replace_at(&path, subtree)
+        .and_then(|_| node.to_json())
         .map_err(|e| JsValue::from_str(&e))
 }
 
+/// The subtree replacements turning one cached tree into another, as a
+/// JSON array of `{"path": […], "old": …, "new": …}`.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn diff_trees_js(old_json: &str, new_json: &str) -> Result<String, JsValue> {
+    let old = Node::from_json(old_json).map_err(|e| JsValue::from_str(&e))?;
+    let new = Node::from_json(new_json).map_err(|e| JsValue::from_str(&e))?;
+    serde_json::to_string(&diff_trees(&old, &new)).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+extern "C" {
+    /// A JS function `(completed, total) => void`.
+    #[derive(Clone)]
+    pub type ProgressCallback;
+
+    #[wasm_bindgen(method, js_name = call)]
+    fn call_with(this: &ProgressCallback, this_arg: &JsValue, completed: f64, total: f64);
+}
+
+/// Evaluate LaTeX against a JSON environment, calling
+/// `on_progress(completed, total)` as the outermost large sum, product or
+/// numeric integral advances, so a UI can draw a progress bar.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn evaluate_with_progress_js(
+    latex_expr: &str,
+    env_json: &str,
+    on_progress: &ProgressCallback,
+) -> Result<f64, JsValue> {
+    let env: Environment = serde_json::from_str(env_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse environment: {}", e)))?;
+    let node = parse_latex(latex_expr, &env).map_err(|e| JsValue::from_str(&e.render()))?;
+    let callback = on_progress.clone();
+    crate::progress::with_progress(
+        move |p| callback.call_with(&JsValue::NULL, p.completed as f64, p.total as f64),
+        || Evaluator::evaluate(&node, &env),
+    )
+    .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
 /// Simplify LaTeX. Returns unsimplified output if simplification fails.
 #[allow(unexpected_cfgs)]
 #[wasm_bindgen]
@@ -32,16 +144,14 @@ pub fn simplify_latex_js(latex_expr: &str) -> Result<String, JsValue> {
     let env = Environment::new();
     parse_latex(latex_expr, &env)
         .map(|node| format!("{node}"))
-        .map_err(|e| JsValue::from_str(&e))
+        .map_err(|e| JsValue::from_str(&e.render()))
 }
 
 #[allow(unexpected_cfgs)]
 #[wasm_bindgen]
 pub fn polynomial_factor_js(latex_expr: &str, var_name: &str) -> Result<String, JsValue> {
-    let mut tokenizer = Tokenizer::new(latex_expr);
-    let tokens = tokenizer.tokenize();
-    let expr = build_expression_tree(tokens)
-        .map_err(|e| JsValue::from_str(&format!("Error parsing LaTeX: {}", e)))?;
+    let expr = parse_latex_raw(latex_expr)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing LaTeX: {}", e.render())))?;
     let poly = crate::polynomial::Polynomial::from_node(&expr, var_name)
         .map_err(|e| JsValue::from_str(&format!("Not a polynomial: {}", e)))?;
     let factors = poly.square_free_decomposition();
@@ -128,26 +238,102 @@ pub fn limit_js(latex_expr: &str, var_name: &str, point: f64) -> Result<String,
 #[allow(unexpected_cfgs)]
 #[wasm_bindgen]
 pub fn evaluate_latex_expression_js(latex_expr: &str, env_json: &str) -> Result<String, JsValue> {
-    // Deserialize the environment
-    let env: Environment = serde_json::from_str(env_json)
-        .map_err(|e| JsValue::from_str(&format!("Failed to parse environment: {}", e)))?;
+    let env = environment_from_json(env_json)?;
+    Ok(match evaluate_latex(latex_expr, &env)? {
+        // Integers past 2^53 are exact in the tree but would round in f64
+        // (2^{200}, 100!): print them from the big rational instead.
+        Evaluated::Value {
+            result,
+            value: Some(value),
+            ..
+        } if !is_wide_integer(&result) => value.to_string(),
+        Evaluated::Value { result, .. } | Evaluated::Unsolved(result) => result.to_string(),
+        Evaluated::Check {
+            left,
+            right,
+            holds: true,
+        } => format!("Equation is true: {} = {}", left, right),
+        Evaluated::Check { left, right, .. } => format!("Equation is false: {} ≠ {}", left, right),
+        Evaluated::Solutions { var, solutions } => solutions
+            .iter()
+            .map(|s| format!("{} = {}", var, s))
+            .collect::<Vec<_>>()
+            .join(", "),
+    })
+}
 
-    // Special case for matrix multiplication with \cdot
-    if latex_expr.contains("\\begin{pmatrix}")
-        && latex_expr.contains("\\cdot")
-        && latex_expr.contains("\\end{pmatrix}")
-    {
-        // Try to split the expression by \cdot
-        let parts: Vec<&str> = latex_expr.split("\\cdot").collect();
-        if parts.len() == 2 {
-            let matrix_a = parts[0].trim();
-            let matrix_b = parts[1].trim();
+/// [`evaluate_latex_expression_js`] as a [`StructuredResult`] in JSON: a
+/// `number` or `expression`, or an `equation` that was checked (`holds`) or
+/// solved (`solutions`).
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn evaluate_latex_expression_json(latex_expr: &str, env_json: &str) -> Result<String, JsValue> {
+    let env = environment_from_json(env_json)?;
+    let result = match evaluate_latex(latex_expr, &env)? {
+        Evaluated::Value {
+            parsed,
+            result,
+            value,
+        } => StructuredResult::of(&result, value, parsed.iter().collect()),
+        Evaluated::Unsolved(equation) => StructuredResult::of(&equation, None, Vec::new()),
+        Evaluated::Check { left, right, holds } => StructuredResult {
+            kind: ResultKind::Equation,
+            value: None,
+            latex: format!("{} {} {}", left, if holds { "=" } else { "\\neq" }, right),
+            holds: Some(holds),
+            solutions: Vec::new(),
+            steps: Vec::new(),
+        },
+        Evaluated::Solutions { var, solutions } => StructuredResult::solutions(&var, &solutions),
+    };
+    structured_json(&result)
+}
+
+fn environment_from_json(env_json: &str) -> Result<Environment, JsValue> {
+    serde_json::from_str(env_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse environment: {}", e)))
+}
 
-            // Try to perform matrix multiplication
-            return matrix_multiply_js(matrix_a, matrix_b, env_json);
+/// An integer too wide for an f64 mantissa.
+fn is_wide_integer(node: &Node) -> bool {
+    matches!(node, Node::Num(n) if n.to_rational()
+        .is_some_and(|r| r.is_integer() && r.numer().bits() > f64::MANTISSA_DIGITS as u64))
+}
+
+/// What [`evaluate_latex`] found, before it is reported as text or as a
+/// [`StructuredResult`].
+enum Evaluated {
+    /// The simplified expression with its value, if it has one; `parsed` is
+    /// the input before simplification
+    Value {
+        parsed: Option<Node>,
+        result: Node,
+        value: Option<f64>,
+    },
+    /// An equation whose sides both evaluate
+    Check { left: f64, right: f64, holds: bool },
+    /// An equation solved for its variable
+    Solutions {
+        var: String,
+        solutions: Vec<ExactNum>,
+    },
+    /// An equation that could be neither checked nor solved
+    Unsolved(Node),
+}
+
+impl Evaluated {
+    fn sum(total: i64) -> Self {
+        Evaluated::Value {
+            parsed: None,
+            result: Node::Num(ExactNum::integer(total)),
+            value: Some(total as f64),
         }
     }
+}
 
+/// Evaluate `latex_expr` in `env`: check or solve an equation, otherwise
+/// simplify and evaluate what can be.
+fn evaluate_latex(latex_expr: &str, env: &Environment) -> Result<Evaluated, JsValue> {
     // Special case for common summation notations in the frontend that might cause parsing issues
     if latex_expr.contains("\\sum_") && latex_expr.contains("^") {
         // Check for common patterns like \sum_{i=1}^3i^2 - unbraced with exponent
@@ -183,7 +369,7 @@ pub fn evaluate_latex_expression_js(latex_expr: &str, env_json: &str) -> Result<
                 for i in start..=end {
                     sum += i.pow(exponent as u32);
                 }
-                return Ok(sum.to_string());
+                return Ok(Evaluated::sum(sum));
             }
         }
     }
@@ -219,47 +405,40 @@ pub fn evaluate_latex_expression_js(latex_expr: &str, env_json: &str) -> Result<
                 for i in start..=end {
                     product *= i.pow(exponent as u32);
                 }
-                return Ok(product.to_string());
+                return Ok(Evaluated::sum(product));
             }
         }
     }
 
-    // Create an instance of the Tokenizer
-    let mut tokenizer = Tokenizer::new(latex_expr); // Pass input as a reference
-
-    // Tokenize and parse the input
-    let tokens = tokenizer.tokenize(); // Call the instance method on tokenizer
-    let parsed_expr = build_expression_tree(tokens)
-        .map_err(|e| JsValue::from_str(&format!("Error parsing LaTeX: {}", e)))?;
+    let parsed_expr = parse_latex_raw(latex_expr)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing LaTeX: {}", e.render())))?;
 
     // Check if it's an equation that we need to solve
     if let Node::Equation(left, right) = &parsed_expr {
         // First try to evaluate both sides
-        let env_clone = env.clone();
         match (
-            Evaluator::evaluate(left, &env_clone),
-            Evaluator::evaluate(right, &env_clone),
+            Evaluator::evaluate(left, env),
+            Evaluator::evaluate(right, env),
         ) {
-            (Ok(left_val), Ok(right_val)) => {
-                if (left_val - right_val).abs() < 1e-9 {
-                    return Ok(format!("Equation is true: {} = {}", left_val, right_val));
-                } else {
-                    return Ok(format!("Equation is false: {} ≠ {}", left_val, right_val));
-                }
+            (Ok(left), Ok(right)) => {
+                return Ok(Evaluated::Check {
+                    left,
+                    right,
+                    holds: (left - right).abs() < 1e-9,
+                });
             }
             _ => {
                 if let Some(var_name) = extract_variable(latex_expr) {
                     match crate::expression::solve_for_variable_exact(&parsed_expr, &var_name) {
                         Ok(solutions) => {
-                            let parts: Vec<String> = solutions
-                                .iter()
-                                .map(|s| format!("{} = {}", var_name, s))
-                                .collect();
-                            return Ok(parts.join(", "));
+                            return Ok(Evaluated::Solutions {
+                                var: var_name,
+                                solutions,
+                            });
                         }
                         Err(e) => {
                             if e.contains("summation") || e.contains("function") {
-                                return Ok(format!("{}", parsed_expr));
+                                return Ok(Evaluated::Unsolved(parsed_expr));
                             }
                             return Err(JsValue::from_str(&format!(
                                 "Error solving equation: {}",
@@ -274,14 +453,183 @@ pub fn evaluate_latex_expression_js(latex_expr: &str, env_json: &str) -> Result<
 
     // Always simplify the expression first
     let simplified_expr = parsed_expr
-        .simplify(&env)
+        .simplify(env)
         .map_err(|e| JsValue::from_str(&format!("Error simplifying expression: {}", e)))?;
 
-    // Try to evaluate the simplified expression
-    match Evaluator::evaluate(&simplified_expr, &env) {
-        Ok(result) => Ok(result.to_string()), // Return fully evaluated result if possible
-        Err(_) => Ok(simplified_expr.to_string()), // If evaluation fails, return the simplified expression
+    let value = Evaluator::evaluate(&simplified_expr, env).ok();
+    Ok(Evaluated::Value {
+        parsed: Some(parsed_expr),
+        result: simplified_expr,
+        value,
+    })
+}
+
+/// What a [`StructuredResult`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultKind {
+    Number,
+    Expression,
+    Equation,
+    Inequality,
+}
+
+/// The result of a `*_json` binding: what the `*_js` binding of the same
+/// operation reports as a string, in fields a frontend renders without
+/// parsing it.
+#[derive(Debug, Serialize)]
+pub struct StructuredResult {
+    pub kind: ResultKind,
+    /// The value of a `number`, `null` for anything else
+    pub value: Option<f64>,
+    /// The result as LaTeX: an equation that was checked reads `a = b` or
+    /// `a \neq b`, one that was solved `x = s_1, x = s_2`, a solved
+    /// inequality `x > 3`
+    pub latex: String,
+    /// Whether a checked equation holds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub holds: Option<bool>,
+    /// The solutions of a solved equation, or the intervals solving an
+    /// inequality, as LaTeX
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub solutions: Vec<String>,
+    /// The forms the result went through as LaTeX, the input first, when
+    /// they differ from it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<String>,
+}
+
+impl StructuredResult {
+    /// `result`, a `number` when `value` is finite, after `steps`.
+    fn of(result: &Node, value: Option<f64>, steps: Vec<&Node>) -> Self {
+        let latex = result.to_string();
+        let mut forms: Vec<String> = Vec::new();
+        for step in steps.into_iter().map(Node::to_string) {
+            if step != latex && forms.last() != Some(&step) {
+                forms.push(step);
+            }
+        }
+        let value = value.filter(|v| v.is_finite());
+        let kind = match (result, value) {
+            (Node::Equation(..), _) => ResultKind::Equation,
+            (_, Some(_)) => ResultKind::Number,
+            (_, None) => ResultKind::Expression,
+        };
+        StructuredResult {
+            kind,
+            value: value.filter(|_| kind == ResultKind::Number),
+            latex,
+            holds: None,
+            solutions: Vec::new(),
+            steps: forms,
+        }
+    }
+
+    fn solutions(var: &str, solutions: &[ExactNum]) -> Self {
+        let solutions: Vec<String> = solutions.iter().map(ExactNum::to_string).collect();
+        StructuredResult {
+            kind: ResultKind::Equation,
+            value: None,
+            latex: solutions
+                .iter()
+                .map(|s| format!("{} = {}", var, s))
+                .collect::<Vec<_>>()
+                .join(", "),
+            holds: None,
+            solutions,
+            steps: Vec::new(),
+        }
+    }
+
+    fn solution_set(var: &str, set: &SolutionSet) -> Self {
+        StructuredResult {
+            kind: ResultKind::Inequality,
+            value: None,
+            latex: set.to_latex_in(var),
+            holds: None,
+            solutions: set.intervals.iter().map(Interval::to_latex).collect(),
+            steps: Vec::new(),
+        }
+    }
+}
+
+fn is_inequality(expr: &Node) -> bool {
+    matches!(
+        expr,
+        Node::Greater(..) | Node::GreaterEqual(..) | Node::Less(..) | Node::LessEqual(..)
+    )
+}
+
+fn structured_json(result: &StructuredResult) -> Result<String, JsValue> {
+    serde_json::to_string(result).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// [`simplify_latex_js`] as a [`StructuredResult`] in JSON.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn simplify_latex_json(latex_expr: &str) -> Result<String, JsValue> {
+    let env = Environment::new();
+    let parsed =
+        parse_latex_raw_with_env(latex_expr, &env).map_err(|e| JsValue::from_str(&e.render()))?;
+    let result = parsed.simplify(&env).unwrap_or_else(|_| parsed.clone());
+    let value = Evaluator::evaluate(&result, &env).ok();
+    structured_json(&StructuredResult::of(&result, value, vec![&parsed]))
+}
+
+/// [`differentiate_expression_js`] as a [`StructuredResult`] in JSON; the
+/// steps are the input and the derivative before simplification.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn differentiate_expression_json(latex_expr: &str, var_name: &str) -> Result<String, JsValue> {
+    let expr = parse_latex_raw(latex_expr)
+        .map_err(ArithmaError::from)
+        .map_err(differentiation_error)?;
+    let derivative = differentiate(&expr, var_name).map_err(differentiation_error)?;
+    let env = Environment::new();
+    let result = derivative
+        .simplify(&env)
+        .unwrap_or_else(|_| derivative.clone());
+    let value = Evaluator::evaluate(&result, &env).ok();
+    structured_json(&StructuredResult::of(
+        &result,
+        value,
+        vec![&expr, &derivative],
+    ))
+}
+
+/// [`integrate_expression_js`] as a [`StructuredResult`] in JSON: always an
+/// `expression` ending in `+ C`; the steps are the input and the
+/// antiderivative before simplification.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn integrate_expression_json(latex_expr: &str, var_name: &str) -> Result<String, JsValue> {
+    let expr = parse_latex_raw(latex_expr)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing LaTeX: {}", e.render())))?;
+    let integral = crate::integration::integrate(&expr, var_name)
+        .map_err(|e| JsValue::from_str(&format!("Error in integration: {}", e)))?;
+    let result = integral
+        .simplify(&Environment::new())
+        .unwrap_or_else(|_| integral.clone());
+    let mut structured = StructuredResult::of(&result, None, vec![&expr, &integral]);
+    structured.latex = format!("{} + C", structured.latex);
+    structured_json(&structured)
+}
+
+/// [`solve_js`] as a [`StructuredResult`] in JSON: an `equation` with its
+/// `solutions`, or an `inequality` with its intervals.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn solve_json(latex_equation: &str, var_name: &str) -> Result<String, JsValue> {
+    let expr = parse_latex_raw(latex_equation)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing: {}", e.render())))?;
+    if is_inequality(&expr) {
+        let set = crate::solve_inequality_set(&expr, var_name)
+            .map_err(|e| JsValue::from_str(&format!("Error solving: {}", e)))?;
+        return structured_json(&StructuredResult::solution_set(var_name, &set));
     }
+    let solutions = crate::expression::solve_for_variable_exact(&expr, var_name)
+        .map_err(|e| JsValue::from_str(&format!("Error solving: {}", e)))?;
+    structured_json(&StructuredResult::solutions(var_name, &solutions))
 }
 
 #[allow(unexpected_cfgs)]
@@ -444,18 +792,98 @@ pub fn solve_linear_system_js(
     }
 }
 
+/// The simplified derivative of `latex_expr` with respect to `var_name`, as
+/// LaTeX.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn differentiate_expression_js(latex_expr: &str, var_name: &str) -> Result<String, JsValue> {
+    differentiate_latex(latex_expr, var_name).map_err(differentiation_error)
+}
+
+/// Same as [`differentiate_expression_js`], under its older name.
 #[allow(unexpected_cfgs)]
 #[wasm_bindgen]
 pub fn differentiate_js(latex_expr: &str, var_name: &str) -> Result<String, JsValue> {
-    match differentiate_latex(latex_expr, var_name) {
-        Ok(result) => Ok(result),
-        Err(e) => Err(JsValue::from_str(&format!(
-            "Error in differentiation: {}",
-            e
-        ))),
+    differentiate_expression_js(latex_expr, var_name)
+}
+
+/// The simplified partial derivative of `latex_expr` with respect to
+/// `var_name`, every other variable held constant, as LaTeX.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn partial_derivative_js(latex_expr: &str, var_name: &str) -> Result<String, JsValue> {
+    let expr = parse_latex_raw(latex_expr).map_err(ArithmaError::from);
+    let derivative = expr
+        .and_then(|expr| partial_derivative(&expr, var_name))
+        .map_err(differentiation_error)?;
+    let simplified = derivative
+        .simplify(&Environment::new())
+        .unwrap_or(derivative);
+    Ok(simplified.to_string())
+}
+
+/// A parse error with the input marked, otherwise the message.
+fn differentiation_error(error: ArithmaError) -> JsValue {
+    match error {
+        ArithmaError::Parse(e) => JsValue::from_str(&e.render()),
+        e => JsValue::from_str(&format!("Error in differentiation: {}", e)),
     }
 }
 
+/// One entry of a batch result: `{"latex": …}` or `{"error": …}`.
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BatchEntry {
+    Latex(String),
+    Error(String),
+}
+
+/// A batch calculus operation such as [`differentiate_many`].
+type BatchOp = fn(&[Node], &str) -> Vec<Result<Node, ArithmaError>>;
+
+/// Parse a JSON array of LaTeX strings, run `op` over the ones that parse,
+/// and report per-entry results in input order.
+fn run_batch(latex_exprs_json: &str, var_name: &str, op: BatchOp) -> Result<String, JsValue> {
+    let inputs: Vec<String> = serde_json::from_str(latex_exprs_json).map_err(|e| {
+        JsValue::from_str(&format!("Expected a JSON array of LaTeX strings: {}", e))
+    })?;
+    let parsed: Vec<Result<Node, String>> = inputs
+        .iter()
+        .map(|l| parse_latex_raw(l).map_err(|e| e.render()))
+        .collect();
+    let exprs: Vec<Node> = parsed.iter().filter_map(|p| p.clone().ok()).collect();
+    let mut results = op(&exprs, var_name).into_iter();
+    let entries: Vec<BatchEntry> = parsed
+        .into_iter()
+        .map(|p| {
+            match p.and_then(|_| {
+                let result = results.next().expect("one result per parsed input");
+                result.map_err(String::from)
+            }) {
+                Ok(node) => BatchEntry::Latex(node.to_string()),
+                Err(e) => BatchEntry::Error(e),
+            }
+        })
+        .collect();
+    serde_json::to_string(&entries).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Differentiate a JSON array of LaTeX expressions in one call. Returns a
+/// JSON array of `{"latex": …}` / `{"error": …}` entries in input order.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn differentiate_many_js(latex_exprs_json: &str, var_name: &str) -> Result<String, JsValue> {
+    run_batch(latex_exprs_json, var_name, differentiate_many)
+}
+
+/// Integrate a JSON array of LaTeX expressions in one call (antiderivatives
+/// without `+ C`), in the same result format as [`differentiate_many_js`].
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn integrate_many_js(latex_exprs_json: &str, var_name: &str) -> Result<String, JsValue> {
+    run_batch(latex_exprs_json, var_name, integrate_many)
+}
+
 #[allow(unexpected_cfgs)]
 #[wasm_bindgen]
 pub fn substitute_js(
@@ -473,10 +901,13 @@ pub fn substitute_js(
 #[allow(unexpected_cfgs)]
 #[wasm_bindgen]
 pub fn solve_js(latex_equation: &str, var_name: &str) -> Result<String, JsValue> {
-    let mut tokenizer = Tokenizer::new(latex_equation);
-    let tokens = tokenizer.tokenize();
-    let expr = build_expression_tree(tokens)
-        .map_err(|e| JsValue::from_str(&format!("Error parsing: {}", e)))?;
+    let expr = parse_latex_raw(latex_equation)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing: {}", e.render())))?;
+    if is_inequality(&expr) {
+        return crate::solve_inequality_set(&expr, var_name)
+            .map(|set| set.to_latex_in(var_name))
+            .map_err(|e| JsValue::from_str(&format!("Error solving: {}", e)));
+    }
     match crate::expression::solve_for_variable_exact(&expr, var_name) {
         Ok(solutions) => {
             let parts: Vec<String> = solutions.iter().map(|s| format!("{}", s)).collect();
@@ -489,11 +920,8 @@ pub fn solve_js(latex_equation: &str, var_name: &str) -> Result<String, JsValue>
 #[allow(unexpected_cfgs)]
 #[wasm_bindgen]
 pub fn partial_fractions_js(latex_expr: &str, var_name: &str) -> Result<String, JsValue> {
-    // Parse the expression and extract numerator/denominator from a Divide node
-    let mut tokenizer = Tokenizer::new(latex_expr);
-    let tokens = tokenizer.tokenize();
-    let expr = build_expression_tree(tokens)
-        .map_err(|e| JsValue::from_str(&format!("Error parsing: {}", e)))?;
+    let expr = parse_latex_raw(latex_expr)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing: {}", e.render())))?;
     match expr {
         Node::Divide(num, den) => {
             let num_str = format!("{}", num);
@@ -516,27 +944,11 @@ pub fn partial_fractions_js(latex_expr: &str, var_name: &str) -> Result<String,
 #[wasm_bindgen]
 pub fn equivalent_js(expr1: &str, expr2: &str) -> Result<String, JsValue> {
     let env = Environment::new();
-    let mut t1 = Tokenizer::new(expr1);
-    let mut t2 = Tokenizer::new(expr2);
-    let e1 = build_expression_tree(t1.tokenize())
-        .map_err(|e| JsValue::from_str(&format!("Error parsing expr1: {}", e)))?;
-    let e2 = build_expression_tree(t2.tokenize())
-        .map_err(|e| JsValue::from_str(&format!("Error parsing expr2: {}", e)))?;
-    let s1 = e1
-        .simplify(&env)
-        .map_err(|e| JsValue::from_str(&format!("Error simplifying expr1: {}", e)))?;
-    let s2 = e2
-        .simplify(&env)
-        .map_err(|e| JsValue::from_str(&format!("Error simplifying expr2: {}", e)))?;
-    let diff = Node::Subtract(Box::new(s1), Box::new(s2));
-    let diff_simplified = diff
-        .simplify(&env)
-        .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-    if diff_simplified == Node::Num(ExactNum::zero()) {
-        Ok("true".to_string())
-    } else {
-        Ok("false".to_string())
-    }
+    let e1 = parse_latex_raw(expr1)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing expr1: {}", e.render())))?;
+    let e2 = parse_latex_raw(expr2)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing expr2: {}", e.render())))?;
+    Ok(are_equivalent(&e1, &e2, &env).to_string())
 }
 
 #[allow(unexpected_cfgs)]
@@ -547,3 +959,815 @@ pub fn solve_ode_js(rhs_latex: &str, indep_var: &str, dep_var: &str) -> Result<S
         Err(e) => Err(JsValue::from_str(&format!("Error solving ODE: {}", e))),
     }
 }
+
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn solve_ode_equation_js(
+    equation_latex: &str,
+    indep_var: &str,
+    dep_var: &str,
+) -> Result<String, JsValue> {
+    solve_ode_equation_latex(equation_latex, indep_var, dep_var)
+        .map_err(|e| JsValue::from_str(&format!("Error solving ODE: {}", e)))
+}
+
+/// A numeric solution of `y' = f(t, y)` sampled at evenly spaced times,
+/// ready to plot.
+#[derive(Debug, Serialize)]
+pub struct IvpTrajectory {
+    pub t: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Steps the adaptive method threw away for being too inaccurate
+    pub rejected_steps: usize,
+}
+
+/// Solve `y' = f(t, y)`, `y(t0) = y0` up to `t_end` with the method named
+/// `"rk4"`, `"rk45"` or `"implicit_euler"`, then sample the solution at
+/// `samples` evenly spaced times.
+#[allow(clippy::too_many_arguments)]
+pub fn ivp_trajectory(
+    rhs_latex: &str,
+    indep_var: &str,
+    dep_var: &str,
+    t0: f64,
+    y0: f64,
+    t_end: f64,
+    method: &str,
+    samples: usize,
+) -> Result<IvpTrajectory, String> {
+    let method = match method {
+        "rk4" => OdeMethod::Rk4,
+        "rk45" | "" => OdeMethod::Rk45,
+        "implicit_euler" => OdeMethod::ImplicitEuler,
+        other => return Err(format!("Unknown ODE method '{}'", other)),
+    };
+    let solution = solve_ivp_latex(
+        rhs_latex,
+        indep_var,
+        dep_var,
+        t0,
+        y0,
+        t_end,
+        &OdeOptions::with_method(method),
+    )?;
+    let (t, y) = solution
+        .sample(samples)
+        .into_iter()
+        .map(|(t, y)| (t, y[0]))
+        .unzip();
+    Ok(IvpTrajectory {
+        t,
+        y,
+        rejected_steps: solution.rejected_steps,
+    })
+}
+
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn solve_ivp_js(
+    rhs_latex: &str,
+    indep_var: &str,
+    dep_var: &str,
+    t0: f64,
+    y0: f64,
+    t_end: f64,
+    method: &str,
+    samples: usize,
+) -> Result<String, JsValue> {
+    let data = ivp_trajectory(
+        rhs_latex, indep_var, dep_var, t0, y0, t_end, method, samples,
+    )
+    .map_err(|e| JsValue::from_str(&format!("Error solving IVP: {}", e)))?;
+    serde_json::to_string(&data).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Samples of f, f′, and the accumulated integral of f on one shared
+/// grid — the data behind the classic three-curve calculus plot.
+/// Non-finite values (poles, domain gaps) serialize as `null`.
+#[derive(Debug, Serialize)]
+pub struct CalculusPlotData {
+    pub x: Vec<f64>,
+    pub f: Vec<Option<f64>>,
+    pub derivative: Vec<Option<f64>>,
+    /// ∫f from the start of the run of defined samples each point lies in:
+    /// from `x_min` until f is first undefined, then afresh after each gap.
+    pub integral: Vec<Option<f64>>,
+    /// Symbolic f′ that produced `derivative`, or `None` when it fell back
+    /// to central differences.
+    pub derivative_latex: Option<String>,
+}
+
+/// Sample f, f′, and ∫f over `[x_min, x_max]` at `samples` evenly spaced
+/// points. The expression is parsed and differentiated once, and f and f′
+/// are [compiled](Evaluator::compile) once for all the samples. The
+/// integral is the cumulative trapezoid rule; it cannot be carried across
+/// a pole, so it is `null` where f is and starts again from 0 at the
+/// first defined sample after each gap.
+pub fn calculus_plot_data(
+    latex_expr: &str,
+    var_name: &str,
+    x_min: f64,
+    x_max: f64,
+    samples: usize,
+) -> Result<CalculusPlotData, String> {
+    if samples < 2 {
+        return Err("At least 2 samples are required".to_string());
+    }
+    if !(x_min.is_finite() && x_max.is_finite() && x_min < x_max) {
+        return Err(format!("Invalid plot range [{}, {}]", x_min, x_max));
+    }
+    let expr = parse_latex_raw(latex_expr)?;
+    let env = Environment::new();
+    let derivative = differentiate(&expr, var_name)
+        .ok()
+        .map(|d| d.simplify(&env).unwrap_or(d));
+    sample_calculus_plot(&expr, derivative, var_name, x_min, x_max, samples)
+}
+
+/// [`calculus_plot_data`] for an expression already parsed and
+/// differentiated; `derivative` is `None` when differentiation failed.
+fn sample_calculus_plot(
+    expr: &Node,
+    derivative: Option<Node>,
+    var_name: &str,
+    x_min: f64,
+    x_max: f64,
+    samples: usize,
+) -> Result<CalculusPlotData, String> {
+    let compiled_f = Evaluator::compile(expr, &[var_name])?;
+    let compiled_derivative = derivative
+        .as_ref()
+        .and_then(|d| Evaluator::compile(d, &[var_name]).ok());
+    let finite = |v: f64| Some(v).filter(|v| v.is_finite());
+    let f_at = |x: f64| finite(compiled_f.eval(&[x]));
+
+    let step = (x_max - x_min) / (samples - 1) as f64;
+    let x: Vec<f64> = (0..samples).map(|i| x_min + step * i as f64).collect();
+    let f: Vec<Option<f64>> = x.iter().map(|&xi| f_at(xi)).collect();
+    let derivative_values: Vec<Option<f64>> = match &compiled_derivative {
+        Some(d) => x.iter().map(|&xi| finite(d.eval(&[xi]))).collect(),
+        None => {
+            let h = step.min(1e-4);
+            x.iter()
+                .map(|&xi| Some((f_at(xi + h)? - f_at(xi - h)?) / (2.0 * h)))
+                .collect()
+        }
+    };
+
+    let mut integral = Vec::with_capacity(samples);
+    let mut accumulated: Option<f64> = None;
+    for i in 0..samples {
+        accumulated = match (accumulated, f[i]) {
+            (_, None) => None,
+            (None, Some(_)) => Some(0.0),
+            (Some(total), Some(b)) => f[i - 1].map(|a| total + 0.5 * step * (a + b)),
+        };
+        integral.push(accumulated);
+    }
+
+    Ok(CalculusPlotData {
+        x,
+        f,
+        derivative: derivative_values,
+        integral,
+        derivative_latex: derivative
+            .filter(|_| compiled_derivative.is_some())
+            .map(|d| format!("{}", d)),
+    })
+}
+
+/// JSON-serialized [`calculus_plot_data`] for calculus frontends:
+/// `{x, f, derivative, integral, derivative_latex}`.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn calculus_plot_js(
+    latex_expr: &str,
+    var_name: &str,
+    x_min: f64,
+    x_max: f64,
+    samples: usize,
+) -> Result<String, JsValue> {
+    let data = calculus_plot_data(latex_expr, var_name, x_min, x_max, samples)
+        .map_err(|e| JsValue::from_str(&format!("Error sampling plot: {}", e)))?;
+    serde_json::to_string(&data).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// JSON-serialized [`plot::plot`](crate::plot::plot) of `latex_expr` over
+/// `[x_min, x_max]`: `{segments, discontinuities, undefined}`.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn plot_js(
+    latex_expr: &str,
+    var_name: &str,
+    x_min: f64,
+    x_max: f64,
+    samples: usize,
+) -> Result<String, JsValue> {
+    let expr = parse_latex_raw(latex_expr)
+        .map_err(|e| JsValue::from_str(&format!("Error parsing expression: {}", e.render())))?;
+    let data = crate::plot::plot(&expr, var_name, x_min, x_max, samples)
+        .map_err(|e| JsValue::from_str(&format!("Error sampling plot: {}", e)))?;
+    serde_json::to_string(&data).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// The points of a slope field: `nx` columns by `ny` rows spanning
+/// `[x_min, x_max] × [y_min, y_max]`, edges included.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SlopeFieldGrid {
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+    pub nx: usize,
+    pub ny: usize,
+}
+
+/// The direction field of `y' = f(x, y)`: the slope at each grid point and
+/// the unit vector along it, so a frontend only has to draw the segments.
+/// Row `j` holds the points `(x[i], y[j])`; where f is undefined both are
+/// `null`.
+#[derive(Debug, Serialize)]
+pub struct SlopeFieldData {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub slopes: Vec<Vec<Option<f64>>>,
+    /// `(1, f) / √(1 + f²)`
+    pub directions: Vec<Vec<Option<(f64, f64)>>>,
+}
+
+/// Sample `f(x, y)` over `grid`, with `x_var` and `y_var` naming the
+/// independent and dependent variables. The expression is parsed once
+/// and evaluated at every point.
+pub fn slope_field_data(
+    latex_rhs: &str,
+    x_var: &str,
+    y_var: &str,
+    grid: &SlopeFieldGrid,
+) -> Result<SlopeFieldData, String> {
+    let axis = |min: f64, max: f64, n: usize, name: &str| -> Result<Vec<f64>, String> {
+        if n < 2 {
+            return Err(format!("At least 2 {} samples are required", name));
+        }
+        if !(min.is_finite() && max.is_finite() && min < max) {
+            return Err(format!("Invalid {} range [{}, {}]", name, min, max));
+        }
+        let step = (max - min) / (n - 1) as f64;
+        Ok((0..n).map(|i| min + step * i as f64).collect())
+    };
+    let x = axis(grid.x_min, grid.x_max, grid.nx, x_var)?;
+    let y = axis(grid.y_min, grid.y_max, grid.ny, y_var)?;
+    let rhs = parse_latex_raw(latex_rhs)?;
+
+    let mut point_env = Environment::new();
+    let mut slopes = Vec::with_capacity(y.len());
+    for &yj in &y {
+        point_env.set(y_var, yj);
+        let row: Vec<Option<f64>> = x
+            .iter()
+            .map(|&xi| {
+                point_env.set(x_var, xi);
+                Evaluator::evaluate(&rhs, &point_env)
+                    .ok()
+                    .filter(|v| v.is_finite())
+            })
+            .collect();
+        slopes.push(row);
+    }
+    let directions = slopes
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|slope| {
+                    slope.map(|m| {
+                        let length = m.hypot(1.0);
+                        (1.0 / length, m / length)
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(SlopeFieldData {
+        x,
+        y,
+        slopes,
+        directions,
+    })
+}
+
+/// JSON-serialized [`slope_field_data`] for differential-equation
+/// frontends: `{x, y, slopes, directions}`. `grid_json` is a
+/// [`SlopeFieldGrid`], e.g.
+/// `{"x_min":-2,"x_max":2,"y_min":-2,"y_max":2,"nx":21,"ny":21}`.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn slope_field_js(
+    latex_rhs: &str,
+    x_var: &str,
+    y_var: &str,
+    grid_json: &str,
+) -> Result<String, JsValue> {
+    let grid: SlopeFieldGrid = serde_json::from_str(grid_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grid: {}", e)))?;
+    let data = slope_field_data(latex_rhs, x_var, y_var, &grid)
+        .map_err(|e| JsValue::from_str(&format!("Error sampling slope field: {}", e)))?;
+    serde_json::to_string(&data).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// One evaluated input in a [`SessionHandle`], referable later as its
+/// `label` (`%1`, `%2`, …).
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionEntry {
+    pub label: String,
+    pub input: String,
+    /// The parsed input with earlier `%n` references already substituted
+    #[serde(skip)]
+    pub expr: Node,
+    /// The simplified result that later `%n` references stand for
+    #[serde(skip)]
+    pub result: Node,
+    pub result_latex: String,
+    /// Numeric value when the result evaluates to a finite number
+    pub value: Option<f64>,
+}
+
+/// A notebook-style session: variables set once persist, and every result
+/// is kept as `%n` so later inputs can build on it (`%1 + 3`) without the
+/// frontend re-sending the earlier LaTeX.
+#[wasm_bindgen]
+#[derive(Debug, Default)]
+pub struct SessionHandle {
+    env: Environment,
+    history: Vec<SessionEntry>,
+}
+
+impl SessionHandle {
+    /// Parse `latex`, replace `%n` with the stored results, simplify, and
+    /// record the outcome as the next entry.
+    pub fn evaluate_entry(&mut self, latex: &str) -> Result<&SessionEntry, String> {
+        let parsed = parse_latex_raw(latex)?;
+        let expr = self.resolve_references(&parsed)?;
+        let result = expr.simplify(&self.env)?;
+        let value = Evaluator::evaluate(&result, &self.env)
+            .ok()
+            .filter(|v| v.is_finite());
+        self.history.push(SessionEntry {
+            label: format!("%{}", self.history.len() + 1),
+            input: latex.to_string(),
+            result_latex: result.to_string(),
+            expr,
+            result,
+            value,
+        });
+        Ok(self.history.last().expect("entry just pushed"))
+    }
+
+    /// The entry labelled `%n`, if any.
+    pub fn entry(&self, label: &str) -> Option<&SessionEntry> {
+        self.history.iter().find(|e| e.label == label)
+    }
+
+    pub fn entries(&self) -> &[SessionEntry] {
+        &self.history
+    }
+
+    fn resolve_references(&self, expr: &Node) -> Result<Node, String> {
+        let mut labels = Vec::new();
+        collect_history_refs(expr, &mut labels);
+        let mut resolved = expr.clone();
+        for label in labels {
+            let entry = self
+                .entry(&label)
+                .ok_or_else(|| format!("Unknown history reference {}", label))?;
+            resolved = crate::substitute::substitute_variable(&resolved, &label, &entry.result)?;
+        }
+        Ok(resolved)
+    }
+}
+
+fn collect_history_refs(node: &Node, out: &mut Vec<String>) {
+    match node {
+        Node::Variable(v) if v.starts_with('%') => {
+            if !out.contains(v) {
+                out.push(v.clone());
+            }
+        }
+        Node::Add(a, b)
+        | Node::Subtract(a, b)
+        | Node::Multiply(a, b)
+        | Node::Divide(a, b)
+        | Node::Power(a, b)
+        | Node::Greater(a, b)
+        | Node::Less(a, b)
+        | Node::GreaterEqual(a, b)
+        | Node::LessEqual(a, b)
+        | Node::And(a, b)
+        | Node::Or(a, b)
+        | Node::Equal(a, b)
+        | Node::Equation(a, b) => {
+            collect_history_refs(a, out);
+            collect_history_refs(b, out);
+        }
+        Node::Sqrt(a)
+        | Node::Abs(a)
+        | Node::Floor(a)
+        | Node::Ceil(a)
+        | Node::Round(a)
+        | Node::Trunc(a)
+        | Node::Negate(a)
+        | Node::Not(a)
+        | Node::Factorial(a) => collect_history_refs(a, out),
+        Node::Summation(_, start, end, body) | Node::Product(_, start, end, body) => {
+            collect_history_refs(start, out);
+            collect_history_refs(end, out);
+            collect_history_refs(body, out);
+        }
+        Node::Integral(_, bounds, body) => {
+            if let Some((lower, upper)) = bounds {
+                collect_history_refs(lower, out);
+                collect_history_refs(upper, out);
+            }
+            collect_history_refs(body, out);
+        }
+        Node::Derivative(_, body) => collect_history_refs(body, out),
+        Node::Limit(_, point, _, body) | Node::SumOver(_, point, body) => {
+            collect_history_refs(point, out);
+            collect_history_refs(body, out);
+        }
+        Node::Piecewise(cases) => {
+            for (value, condition) in cases {
+                collect_history_refs(value, out);
+                collect_history_refs(condition, out);
+            }
+        }
+        Node::Matrix(rows) => {
+            for entry in rows.iter().flatten() {
+                collect_history_refs(entry, out);
+            }
+        }
+        Node::Function(_, args) => {
+            for arg in args {
+                collect_history_refs(arg, out);
+            }
+        }
+        Node::Num(_) | Node::Variable(_) => {}
+    }
+}
+
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+impl SessionHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SessionHandle {
+        SessionHandle::default()
+    }
+
+    /// Evaluate one input; returns the new entry as JSON
+    /// `{label, input, result_latex, value}`.
+    pub fn evaluate(&mut self, latex: &str) -> Result<String, JsValue> {
+        let entry = self
+            .evaluate_entry(latex)
+            .map_err(|e| JsValue::from_str(&format!("Error evaluating: {}", e)))?;
+        serde_json::to_string(entry).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Bind a variable for all later inputs in this session.
+    pub fn set_variable(&mut self, name: &str, value: f64) {
+        self.env.set(name, value);
+    }
+
+    /// All entries so far as a JSON array.
+    pub fn history_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.history).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Number of entries (the label of the latest is `%len`).
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Forget the history; variables stay bound.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// An [`IncrementalParser`] behind an input field: call `parse` after
+/// every edit, and only the changed end of the input is tokenized again.
+#[wasm_bindgen]
+#[derive(Debug, Default)]
+pub struct IncrementalParserHandle {
+    parser: IncrementalParser,
+}
+
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+impl IncrementalParserHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> IncrementalParserHandle {
+        IncrementalParserHandle::default()
+    }
+
+    /// The whole input as the JSON expression tree of
+    /// [`parse_latex_to_json_js`].
+    pub fn parse(&mut self, latex: &str) -> Result<String, JsValue> {
+        self.parser
+            .parse(latex)
+            .map_err(|e| e.render())
+            .and_then(|node| node.to_json())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Forget the previous input.
+    pub fn reset(&mut self) {
+        self.parser.reset();
+    }
+}
+
+/// Request for [`run_operation_js`]. Only `operation` and `latex` are
+/// required; `env` uses the same `{"vars": {…}}` shape as the other
+/// bindings.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OperationRequest {
+    pub operation: String,
+    pub latex: String,
+    /// Variable to operate on; inferred when the expression has only one
+    #[serde(default)]
+    pub variable: Option<String>,
+    /// Several variables, for successive (mixed) differentiation
+    #[serde(default)]
+    pub variables: Vec<String>,
+    #[serde(default)]
+    pub options: OperationOptions,
+    #[serde(default)]
+    pub env: Environment,
+}
+
+/// Operation-specific settings; each operation reads the ones it needs.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OperationOptions {
+    /// Lower bound of a definite integral
+    pub lower: Option<f64>,
+    /// Upper bound of a definite integral
+    pub upper: Option<f64>,
+    /// Limit point
+    pub point: Option<f64>,
+    /// Taylor expansion center (default 0)
+    pub center: Option<f64>,
+    /// Taylor order (default 5)
+    pub order: Option<usize>,
+    /// LaTeX value to substitute for the variable
+    pub value: Option<String>,
+}
+
+/// Result of [`run_operation_js`]: `ok` with `latex` (and `value` when the
+/// result is a number, `results` when there are several), or `ok: false`
+/// with `error`.
+#[derive(Debug, Serialize)]
+pub struct OperationResponse {
+    pub ok: bool,
+    pub operation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub results: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct OperationOutput {
+    latex: Option<String>,
+    value: Option<f64>,
+    results: Vec<String>,
+}
+
+impl OperationOutput {
+    fn latex(latex: String) -> Self {
+        OperationOutput {
+            latex: Some(latex),
+            ..Default::default()
+        }
+    }
+}
+
+/// Operations accepted by [`run_operation_js`].
+pub const OPERATIONS: &[&str] = &[
+    "format",
+    "simplify",
+    "evaluate",
+    "differentiate",
+    "integrate",
+    "limit",
+    "taylor",
+    "factor",
+    "solve",
+    "substitute",
+    "partial_fractions",
+];
+
+/// Single entry point for the frontend: parse `request.latex` and run
+/// `request.operation` on it. New operations are added here rather than as
+/// new exported functions. Always returns an [`OperationResponse`] as JSON;
+/// failures, including a malformed request, come back as `ok: false`.
+#[allow(unexpected_cfgs)]
+#[wasm_bindgen]
+pub fn run_operation_js(request_json: &str) -> String {
+    let response = match serde_json::from_str::<OperationRequest>(request_json) {
+        Ok(request) => {
+            let result = run_operation(&request);
+            operation_response(request.operation, result)
+        }
+        Err(e) => operation_response(String::new(), Err(format!("Invalid request: {}", e))),
+    };
+    serde_json::to_string(&response).expect("operation responses always serialize")
+}
+
+fn operation_response(
+    operation: String,
+    result: Result<OperationOutput, String>,
+) -> OperationResponse {
+    match result {
+        Ok(out) => OperationResponse {
+            ok: true,
+            operation,
+            latex: out.latex,
+            value: out.value.filter(|v| v.is_finite()),
+            results: out.results,
+            error: None,
+        },
+        Err(e) => OperationResponse {
+            ok: false,
+            operation,
+            latex: None,
+            value: None,
+            results: Vec::new(),
+            error: Some(e),
+        },
+    }
+}
+
+fn run_operation(request: &OperationRequest) -> Result<OperationOutput, String> {
+    let options = &request.options;
+    let expr = parse_latex_raw(&request.latex).map_err(|e| e.render())?;
+    let variable = || operation_variable(request, &expr);
+    let simplified = |node: Node| node.simplify(&request.env).unwrap_or(node);
+
+    match request.operation.as_str() {
+        "format" => Ok(OperationOutput::latex(expr.to_string())),
+        "simplify" => Ok(OperationOutput::latex(simplified(expr).to_string())),
+        "evaluate" => {
+            let result = simplified(expr);
+            let value = Evaluator::evaluate(&result, &request.env).ok();
+            Ok(OperationOutput {
+                latex: Some(result.to_string()),
+                value,
+                results: Vec::new(),
+            })
+        }
+        "differentiate" => {
+            let vars = if request.variables.is_empty() {
+                vec![variable()?]
+            } else {
+                request.variables.clone()
+            };
+            let derivative = vars
+                .iter()
+                .try_fold(expr, |acc, var| differentiate(&acc, var).map(&simplified))?;
+            Ok(OperationOutput::latex(derivative.to_string()))
+        }
+        "integrate" => {
+            let var = variable()?;
+            match (options.lower, options.upper) {
+                (Some(lower), Some(upper)) => {
+                    let value = crate::integration::definite_integral(&expr, &var, lower, upper)?;
+                    Ok(OperationOutput {
+                        latex: Some(value.to_string()),
+                        value: Some(value),
+                        results: Vec::new(),
+                    })
+                }
+                (None, None) => {
+                    let integral = simplified(crate::integration::integrate(&expr, &var)?);
+                    Ok(OperationOutput::latex(format!("{} + C", integral)))
+                }
+                _ => Err(
+                    "A definite integral needs both options.lower and options.upper".to_string(),
+                ),
+            }
+        }
+        "limit" => {
+            let point = options
+                .point
+                .ok_or("A limit needs options.point".to_string())?;
+            limit_latex(&request.latex, &variable()?, point).map(OperationOutput::latex)
+        }
+        "taylor" => taylor_series_latex(
+            &request.latex,
+            &variable()?,
+            options.center.unwrap_or(0.0),
+            options.order.unwrap_or(5),
+        )
+        .map(OperationOutput::latex),
+        "factor" => crate::factor::factor(&expr, &variable()?)
+            .map(|node| OperationOutput::latex(node.to_string())),
+        "solve" => {
+            let var = variable()?;
+            let solved = crate::expression::solve_full(&expr, &var)?;
+            if solved.solutions.is_empty() {
+                if let Some(reason) = solved.impossibility_reason {
+                    return Err(reason);
+                }
+            }
+            let results: Vec<String> = solved
+                .solutions
+                .iter()
+                .chain(&solved.complex_solutions)
+                .map(|s| s.to_string())
+                .collect();
+            Ok(OperationOutput {
+                latex: Some(
+                    results
+                        .iter()
+                        .map(|s| format!("{} = {}", var, s))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                value: None,
+                results,
+            })
+        }
+        "substitute" => {
+            let value = options
+                .value
+                .clone()
+                .ok_or("Substitution needs options.value".to_string())?;
+            substitute_latex(&request.latex, &[(variable()?, value)]).map(OperationOutput::latex)
+        }
+        "partial_fractions" => match &expr {
+            Node::Divide(num, den) => {
+                crate::partial_fractions_latex(&num.to_string(), &den.to_string(), &variable()?)
+                    .map(OperationOutput::latex)
+            }
+            _ => Err("Expression must be a fraction (numerator/denominator)".to_string()),
+        },
+        other => Err(format!(
+            "Unknown operation '{}'; expected one of: {}",
+            other,
+            OPERATIONS.join(", ")
+        )),
+    }
+}
+
+/// `request.variable`, else the first of `request.variables`, else the
+/// expression's only free variable (or `x` among several).
+fn operation_variable(request: &OperationRequest, expr: &Node) -> Result<String, String> {
+    if let Some(var) = request
+        .variable
+        .clone()
+        .or_else(|| request.variables.first().cloned())
+    {
+        return Ok(var);
+    }
+    let free: Vec<String> = crate::status::free_variables(&[expr])
+        .into_iter()
+        .filter(|v| !crate::constants::is_constant(v))
+        .collect();
+    match free.as_slice() {
+        [only] => Ok(only.clone()),
+        vars if vars.iter().any(|v| v == "x") => Ok("x".to_string()),
+        [] => Err(format!("'{}' needs a variable", request.operation)),
+        _ => Err(format!(
+            "'{}' needs a variable: the expression has {}",
+            request.operation,
+            free.join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uncompilable_derivative_falls_back_without_latex() {
+        let x = || Node::Variable("x".to_string());
+        let expr = Node::Power(Box::new(x()), Box::new(Node::Num(ExactNum::integer(2))));
+        // No compiled expression can call a function the registry lacks
+        let derivative = Node::Function("unknownfn".to_string(), vec![x()]);
+        let data = sample_calculus_plot(&expr, Some(derivative), "x", 0.0, 2.0, 5).unwrap();
+        assert_eq!(data.derivative_latex, None);
+        for (xi, slope) in data.x.iter().zip(&data.derivative) {
+            assert!((slope.unwrap() - 2.0 * xi).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,822 @@
+//! AsciiMath input, as emitted by editors and note-taking tools:
+//! `(x+1)/(x-1)`, `sqrt x`, `root(3)(x)`, `sum_(k=1)^n k^2`.
+//!
+//! AsciiMath is a notation for display, so its grammar is loose; this
+//! front-end reads it the way the rendered formula reads and builds the
+//! same trees as the LaTeX parser:
+//!
+//! - `/` takes the simple expressions on either side and drops their
+//!   brackets: `1/2 x` is ½·x and `(x+1)/(x-1)` a single fraction;
+//! - `^` and `_` do the same, and `^` groups to the right;
+//! - a function applies to the simple expression after it, with its own
+//!   power: `sin x^2` is sin(x²) while `sin(x)^2` and `sin^2 x` are
+//!   sin(x)²; `log_2 x` is ln x / ln 2;
+//! - a word that is not a known symbol is a run of one-letter variables,
+//!   as AsciiMath renders it: `xy` is x·y;
+//! - `*`, `**`, `xx` and `cdot` multiply and `-:` divides;
+//! - `|x|`, `|__x__|` and `|~x~|` are the absolute value, floor and
+//!   ceiling, and `((a, b), (c, d))` or `[[a, b], [c, d]]` a matrix;
+//! - `int_a^b f dx`, `lim_(x->a) f` (`a^+` and `a^-` for one side),
+//!   `sum_(k=a)^b f`, `prod_(k=a)^b f` and `d/dx f` are the operators.
+//!
+//! `oo` is ∞, and Greek letters are spelled out (`theta`, `Gamma`).
+
+use std::ops::Range;
+
+use crate::exact::ExactNum;
+use crate::limits::INFINITY;
+use crate::node::{LimitDirection, Node};
+use crate::parser::{parse_number, ParseError, ParseLimit, ParserOptions};
+use crate::tokenizer::normalize_var;
+
+/// Parse AsciiMath into an expression tree.
+pub fn parse_asciimath(input: &str) -> Result<Node, ParseError> {
+    let options = ParserOptions::default();
+    let tokens = tokenize(input)?;
+    if tokens.len() > options.max_tokens {
+        return Err(ParseError {
+            limit: Some(ParseLimit::Tokens),
+            ..ParseError::new(format!("Input has more than {} tokens", options.max_tokens))
+        });
+    }
+    let mut parser = AsciiMathParser {
+        tokens,
+        pos: 0,
+        source: input,
+        depth: 0,
+        max_depth: options.max_expression_depth,
+        abs_depth: 0,
+        stop_at_differential: false,
+    };
+    if parser.tokens.is_empty() {
+        return Err(parser.error("Empty expression"));
+    }
+    let node = parser.parse_expression()?;
+    if let Some(token) = parser.peek() {
+        let text = token.text.clone();
+        return Err(parser.error(format!("Unexpected '{}'", text)));
+    }
+    Ok(node)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Name,
+    Symbol,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: Kind,
+    text: String,
+    span: Range<usize>,
+}
+
+/// Operator spellings, matched longest first, and what they stand for.
+const SYMBOLS: &[(&str, &str)] = &[
+    ("|__", "|__"),
+    ("__|", "__|"),
+    ("(:", "("),
+    (":)", ")"),
+    ("{:", "("),
+    (":}", ")"),
+    ("|~", "|~"),
+    ("~|", "~|"),
+    ("->", "->"),
+    ("<=", "<="),
+    (">=", ">="),
+    ("-:", "-:"),
+    ("//", "/"),
+    ("**", "*"),
+    ("+", "+"),
+    ("-", "-"),
+    ("*", "*"),
+    ("/", "/"),
+    ("^", "^"),
+    ("_", "_"),
+    ("(", "("),
+    (")", ")"),
+    ("[", "("),
+    ("]", ")"),
+    ("{", "("),
+    ("}", ")"),
+    ("|", "|"),
+    (",", ","),
+    ("=", "="),
+    ("<", "<"),
+    (">", ">"),
+    ("!", "!"),
+];
+
+/// Functions applied to the simple expression after them.
+const FUNCTIONS: &[&str] = &[
+    "sin",
+    "cos",
+    "tan",
+    "sec",
+    "csc",
+    "cot",
+    "arcsin",
+    "arccos",
+    "arctan",
+    "sinh",
+    "cosh",
+    "tanh",
+    "sech",
+    "csch",
+    "coth",
+    "exp",
+    "ln",
+    "log",
+    "det",
+    "gcd",
+    "lcm",
+    "min",
+    "max",
+    "isprime",
+    "powmod",
+    "factorint",
+];
+
+/// Words read as one symbol rather than as a run of letters.
+const WORDS: &[&str] = &[
+    "sqrt",
+    "root",
+    "frac",
+    "abs",
+    "floor",
+    "ceil",
+    "vec",
+    "sum",
+    "prod",
+    "int",
+    "lim",
+    "xx",
+    "cdot",
+    "rarr",
+    "oo",
+    "and",
+    "or",
+    "not",
+    "alpha",
+    "beta",
+    "gamma",
+    "Gamma",
+    "delta",
+    "Delta",
+    "epsilon",
+    "varepsilon",
+    "zeta",
+    "eta",
+    "theta",
+    "Theta",
+    "vartheta",
+    "iota",
+    "kappa",
+    "lambda",
+    "Lambda",
+    "mu",
+    "nu",
+    "xi",
+    "Xi",
+    "pi",
+    "Pi",
+    "rho",
+    "sigma",
+    "Sigma",
+    "tau",
+    "upsilon",
+    "phi",
+    "Phi",
+    "varphi",
+    "chi",
+    "psi",
+    "Psi",
+    "omega",
+    "Omega",
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        let start = input.len() - rest.len();
+        let (kind, text, len) = if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        } else if c.is_ascii_digit() || c == '.' {
+            let len = rest
+                .find(|d: char| !(d.is_ascii_digit() || d == '.'))
+                .unwrap_or(rest.len());
+            (Kind::Number, &rest[..len], len)
+        } else if c.is_alphabetic() {
+            // The longest known word here, else a single letter
+            let word = FUNCTIONS
+                .iter()
+                .chain(WORDS)
+                .filter(|w| rest.starts_with(*w))
+                .max_by_key(|w| w.len());
+            match word {
+                Some(word) => (Kind::Name, *word, word.len()),
+                None => (Kind::Name, &rest[..c.len_utf8()], c.len_utf8()),
+            }
+        } else if let Some((spelling, symbol)) = SYMBOLS.iter().find(|(s, _)| rest.starts_with(s)) {
+            (Kind::Symbol, *symbol, spelling.len())
+        } else {
+            return Err(ParseError {
+                span: Some(start..start + c.len_utf8()),
+                source: Some(input.to_string()),
+                ..ParseError::new(format!("Unknown character '{}'", c))
+            });
+        };
+        tokens.push(Token {
+            kind,
+            text: text.to_string(),
+            span: start..start + len,
+        });
+        rest = &rest[len..];
+    }
+    Ok(tokens)
+}
+
+struct AsciiMathParser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    source: &'a str,
+    depth: usize,
+    max_depth: usize,
+    /// Open `|…|` groups, inside which `|` closes rather than opens
+    abs_depth: usize,
+    /// Inside an integral, where `d x` ends the integrand
+    stop_at_differential: bool,
+}
+
+impl AsciiMathParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn at(&self, kind: Kind, text: &str) -> bool {
+        self.peek()
+            .is_some_and(|t| t.kind == kind && t.text == text)
+    }
+
+    fn at_symbol(&self, symbol: &str) -> bool {
+        self.at(Kind::Symbol, symbol)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.at_symbol(symbol);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_name(&mut self, name: &str) -> bool {
+        let found = self.at(Kind::Name, name);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected '{}'", symbol)))
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        let span = match self.peek() {
+            Some(token) => token.span.clone(),
+            None => self.source.len()..self.source.len(),
+        };
+        ParseError {
+            token_index: Some(self.pos),
+            span: Some(span),
+            source: Some(self.source.to_string()),
+            ..ParseError::new(message)
+        }
+    }
+
+    /// Run `parse` one level deeper, failing past the depth limit.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Node, ParseError>,
+    ) -> Result<Node, ParseError> {
+        if self.depth >= self.max_depth {
+            return Err(ParseError {
+                limit: Some(ParseLimit::ExpressionDepth),
+                ..self.error(format!(
+                    "Expression nested more than {} levels deep",
+                    self.max_depth
+                ))
+            });
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_expression(&mut self) -> Result<Node, ParseError> {
+        let lhs = self.parse_or()?;
+        if self.eat_symbol("=") {
+            let rhs = self.parse_or()?;
+            return Ok(Node::Equation(Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_or(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_and()?;
+        while self.eat_name("or") {
+            node = Node::Or(Box::new(node), Box::new(self.parse_and()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_not()?;
+        while self.eat_name("and") {
+            node = Node::And(Box::new(node), Box::new(self.parse_not()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_not(&mut self) -> Result<Node, ParseError> {
+        if self.eat_name("not") {
+            return self.nested(|p| Ok(Node::Not(Box::new(p.parse_not()?))));
+        }
+        self.parse_relation()
+    }
+
+    fn parse_relation(&mut self) -> Result<Node, ParseError> {
+        let lhs = self.parse_sum()?;
+        let relation: fn(Box<Node>, Box<Node>) -> Node = match self.peek() {
+            Some(t) if t.kind == Kind::Symbol => match t.text.as_str() {
+                "<" => Node::Less,
+                ">" => Node::Greater,
+                "<=" => Node::LessEqual,
+                ">=" => Node::GreaterEqual,
+                _ => return Ok(lhs),
+            },
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.parse_sum()?;
+        Ok(relation(Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn parse_sum(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_product()?;
+        loop {
+            if self.eat_symbol("+") {
+                node = Node::Add(Box::new(node), Box::new(self.parse_product()?));
+            } else if self.eat_symbol("-") {
+                node = Node::Subtract(Box::new(node), Box::new(self.parse_product()?));
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_unary()?;
+        loop {
+            if self.eat_symbol("*") || self.eat_name("xx") || self.eat_name("cdot") {
+                node = Node::Multiply(Box::new(node), Box::new(self.parse_unary()?));
+            } else if self.eat_symbol("-:") {
+                node = Node::Divide(Box::new(node), Box::new(self.parse_unary()?));
+            } else if self.starts_factor() {
+                node = Node::Multiply(Box::new(node), Box::new(self.parse_fraction()?));
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn starts_factor(&self) -> bool {
+        if self.at_differential() {
+            return false;
+        }
+        match self.peek() {
+            Some(t) => match t.kind {
+                Kind::Number => true,
+                Kind::Name => !matches!(t.text.as_str(), "and" | "or" | "not" | "xx" | "cdot"),
+                Kind::Symbol => match t.text.as_str() {
+                    "(" | "|__" | "|~" => true,
+                    "|" => self.abs_depth == 0,
+                    _ => false,
+                },
+            },
+            None => false,
+        }
+    }
+
+    /// `d x` at the end of an integrand.
+    fn at_differential(&self) -> bool {
+        self.stop_at_differential
+            && self.at(Kind::Name, "d")
+            && self
+                .peek_at(1)
+                .is_some_and(|t| t.kind == Kind::Name && t.text.chars().count() == 1)
+    }
+
+    fn parse_unary(&mut self) -> Result<Node, ParseError> {
+        if self.eat_symbol("-") {
+            return self.nested(|p| Ok(Node::Negate(Box::new(p.parse_unary()?))));
+        }
+        if self.eat_symbol("+") {
+            return self.nested(|p| p.parse_unary());
+        }
+        self.parse_fraction()
+    }
+
+    /// Simple expressions joined by `/`, grouping from the left.
+    fn parse_fraction(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_scripted()?;
+        while self.eat_symbol("/") {
+            let denominator = self.parse_scripted()?;
+            node = Node::Divide(Box::new(node), Box::new(denominator));
+        }
+        Ok(node)
+    }
+
+    /// A simple expression with its subscript and power.
+    fn parse_scripted(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_simple()?;
+        if self.at_symbol("_") {
+            let Node::Variable(name) = &node else {
+                return Err(self.error("Only a variable takes a subscript"));
+            };
+            self.pos += 1;
+            let subscript = match self.parse_simple()? {
+                Node::Variable(v) => v,
+                Node::Num(n) if n.is_integer() => n.to_string(),
+                _ => return Err(self.error("A subscript must be a name or a whole number")),
+            };
+            node = Node::Variable(format!("{}_{}", name, subscript));
+        }
+        if self.eat_symbol("^") {
+            let exponent = self.nested(|p| p.parse_exponent())?;
+            node = Node::Power(Box::new(node), Box::new(exponent));
+        }
+        Ok(node)
+    }
+
+    /// An exponent: a simple expression, negated by a leading `-`, with
+    /// its own power (`2^3^4` is 2^(3^4)).
+    fn parse_exponent(&mut self) -> Result<Node, ParseError> {
+        if self.eat_symbol("-") {
+            return Ok(Node::Negate(Box::new(self.parse_exponent()?)));
+        }
+        let base = self.parse_simple()?;
+        if self.eat_symbol("^") {
+            let exponent = self.nested(|p| p.parse_exponent())?;
+            return Ok(Node::Power(Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn parse_simple(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.nested(|p| p.parse_atom())?;
+        while self.eat_symbol("!") {
+            node = Node::Factorial(Box::new(node));
+        }
+        Ok(node)
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, ParseError> {
+        let Some(token) = self.peek().cloned() else {
+            return Err(self.error("Unexpected end of input"));
+        };
+        match token.kind {
+            Kind::Number => {
+                let node = parse_number(&token.text).map_err(|e| self.error(e))?;
+                self.pos += 1;
+                Ok(node)
+            }
+            Kind::Symbol => match token.text.as_str() {
+                "(" => self.parse_group(),
+                "|" => self.parse_delimited("|", "|", Node::Abs),
+                "|__" => self.parse_delimited("|__", "__|", Node::Floor),
+                "|~" => self.parse_delimited("|~", "~|", Node::Ceil),
+                other => Err(self.error(format!("Unexpected '{}'", other))),
+            },
+            Kind::Name => self.parse_name(&token.text),
+        }
+    }
+
+    /// `|x|`, `|__x__|` or `|~x~|`.
+    fn parse_delimited(
+        &mut self,
+        open: &str,
+        close: &str,
+        node: fn(Box<Node>) -> Node,
+    ) -> Result<Node, ParseError> {
+        self.expect(open)?;
+        self.abs_depth += 1;
+        let inner = self.parse_sum();
+        self.abs_depth -= 1;
+        let inner = inner?;
+        self.expect(close)?;
+        Ok(node(Box::new(inner)))
+    }
+
+    /// A bracketed expression, or a matrix written as bracketed rows.
+    fn parse_group(&mut self) -> Result<Node, ParseError> {
+        if let Some(rows) = self.parse_matrix_rows()? {
+            return Ok(Node::Matrix(rows));
+        }
+        let items = self.parse_items()?;
+        match <[Node; 1]>::try_from(items) {
+            Ok([inner]) => Ok(inner),
+            Err(_) => Err(self.error_before("A bracketed list is only allowed as arguments")),
+        }
+    }
+
+    /// An error at the token just read.
+    fn error_before(&self, message: &str) -> ParseError {
+        let mut error = self.error(message);
+        if self.pos > 0 {
+            error.span = Some(self.tokens[self.pos - 1].span.clone());
+            error.token_index = Some(self.pos - 1);
+        }
+        error
+    }
+
+    /// `(a, b, …)`: the comma-separated expressions in brackets.
+    fn parse_items(&mut self) -> Result<Vec<Node>, ParseError> {
+        self.expect("(")?;
+        let saved = (self.abs_depth, self.stop_at_differential);
+        (self.abs_depth, self.stop_at_differential) = (0, false);
+        let mut items = Vec::new();
+        let result = loop {
+            match self.nested(|p| p.parse_expression()) {
+                Ok(item) => items.push(item),
+                Err(e) => break Err(e),
+            }
+            if self.eat_symbol(")") {
+                break Ok(items);
+            }
+            if !self.eat_symbol(",") {
+                break Err(self.error("Expected ',' or a closing bracket"));
+            }
+        };
+        (self.abs_depth, self.stop_at_differential) = saved;
+        result
+    }
+
+    /// `((a, b), (c, d))`: two or more bracketed rows of one length,
+    /// separated by commas. `None`, with nothing read, otherwise.
+    fn parse_matrix_rows(&mut self) -> Result<Option<Vec<Vec<Node>>>, ParseError> {
+        let mut at = self.pos + 1;
+        let mut row_count = 0;
+        loop {
+            if self.tokens.get(at).is_none_or(|t| t.text != "(") {
+                return Ok(None);
+            }
+            let Some(close) = self.matching_close(at) else {
+                return Ok(None);
+            };
+            row_count += 1;
+            match self.tokens.get(close + 1).map(|t| t.text.as_str()) {
+                Some(",") => at = close + 2,
+                Some(")") if row_count >= 2 => break,
+                _ => return Ok(None),
+            }
+        }
+        self.expect("(")?;
+        let mut rows: Vec<Vec<Node>> = Vec::new();
+        loop {
+            let row = self.parse_items()?;
+            if rows.first().is_some_and(|first| first.len() != row.len()) {
+                return Err(self.error_before("Matrix rows differ in length"));
+            }
+            rows.push(row);
+            if self.eat_symbol(")") {
+                return Ok(Some(rows));
+            }
+            self.expect(",")?;
+        }
+    }
+
+    /// The index of the bracket closing the one at `open`.
+    fn matching_close(&self, open: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        for (i, token) in self.tokens.iter().enumerate().skip(open) {
+            if token.kind != Kind::Symbol {
+                continue;
+            }
+            match token.text.as_str() {
+                "(" => depth += 1,
+                ")" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn parse_name(&mut self, name: &str) -> Result<Node, ParseError> {
+        self.pos += 1;
+        let one = || Box::new(Node::Num(ExactNum::one()));
+        match name {
+            "sqrt" => Ok(Node::Function(
+                "sqrt".to_string(),
+                vec![self.parse_argument()?],
+            )),
+            "root" => {
+                let index = self.parse_simple()?;
+                let radicand = self.parse_simple()?;
+                Ok(Node::Power(
+                    Box::new(radicand),
+                    Box::new(Node::Divide(one(), Box::new(index))),
+                ))
+            }
+            "frac" => {
+                let numerator = self.parse_simple()?;
+                let denominator = self.parse_simple()?;
+                Ok(Node::Divide(Box::new(numerator), Box::new(denominator)))
+            }
+            "abs" => Ok(Node::Abs(Box::new(self.parse_argument()?))),
+            "floor" => Ok(Node::Floor(Box::new(self.parse_argument()?))),
+            "ceil" => Ok(Node::Ceil(Box::new(self.parse_argument()?))),
+            "vec" => Ok(Node::Function(
+                "vec".to_string(),
+                vec![self.parse_simple()?],
+            )),
+            "sum" | "prod" => self.parse_big_operator(name),
+            "int" => self.parse_integral(),
+            "lim" => self.parse_limit(),
+            "oo" => Ok(Node::Variable(INFINITY.to_string())),
+            "d" if self.at_symbol("/") && self.is_derivative_operator() => {
+                self.pos += 2;
+                let var = self.peek().map(|t| t.text.clone()).unwrap_or_default();
+                self.pos += 1;
+                let body = self.nested(|p| p.parse_product())?;
+                Ok(Node::Derivative(normalize_var(&var), Box::new(body)))
+            }
+            _ if FUNCTIONS.contains(&name) => self.parse_function(name),
+            _ if matches!(name, "and" | "or" | "not" | "xx" | "cdot" | "rarr") => {
+                Err(self.error_before(&format!("Unexpected '{}'", name)))
+            }
+            _ => Ok(Node::Variable(normalize_var(name))),
+        }
+    }
+
+    /// After `d`: `/ d x`, the operator of `d/dx f`.
+    fn is_derivative_operator(&self) -> bool {
+        self.peek_at(1).is_some_and(|t| t.text == "d")
+            && self
+                .peek_at(2)
+                .is_some_and(|t| t.kind == Kind::Name && t.text.chars().count() == 1)
+    }
+
+    /// The argument of a one-argument function: brackets are dropped, and
+    /// a bare argument keeps its power (`sqrt x^2` is √(x²)).
+    fn parse_argument(&mut self) -> Result<Node, ParseError> {
+        if self.at_symbol("(") {
+            self.parse_simple()
+        } else {
+            self.nested(|p| p.parse_scripted())
+        }
+    }
+
+    fn parse_function(&mut self, name: &str) -> Result<Node, ParseError> {
+        // `log_b x` is ln x / ln b
+        if name == "log" && self.eat_symbol("_") {
+            let base = self.parse_simple()?;
+            let argument = self.parse_argument()?;
+            let ln = |n: Node| Box::new(Node::Function("ln".to_string(), vec![n]));
+            return Ok(Node::Divide(ln(argument), ln(base)));
+        }
+        // `sin^2 x` is sin(x)^2
+        let power = if self.eat_symbol("^") {
+            Some(self.nested(|p| p.parse_exponent())?)
+        } else {
+            None
+        };
+        let args = if self.at_symbol("(") {
+            self.parse_items()?
+        } else {
+            vec![self.parse_argument()?]
+        };
+        let call = Node::Function(name.to_string(), args);
+        Ok(match power {
+            Some(exponent) => Node::Power(Box::new(call), Box::new(exponent)),
+            None => call,
+        })
+    }
+
+    /// `sum_(k=a)^b f` and `prod_(k=a)^b f`.
+    fn parse_big_operator(&mut self, name: &str) -> Result<Node, ParseError> {
+        self.expect("_")?;
+        let (index, start) = match self.parse_simple()? {
+            Node::Equation(lhs, rhs) => match *lhs {
+                Node::Variable(index) => (index, rhs),
+                _ => return Err(self.error_before("Expected the index, as in sum_(k=1)^n")),
+            },
+            _ => return Err(self.error_before("Expected the index, as in sum_(k=1)^n")),
+        };
+        self.expect("^")?;
+        let end = Box::new(self.parse_simple()?);
+        let body = Box::new(self.nested(|p| p.parse_product())?);
+        Ok(if name == "sum" {
+            Node::Summation(index, start, end, body)
+        } else {
+            Node::Product(index, start, end, body)
+        })
+    }
+
+    /// `int f dx` or `int_a^b f dx`.
+    fn parse_integral(&mut self) -> Result<Node, ParseError> {
+        let bounds = if self.eat_symbol("_") {
+            let lower = self.parse_simple()?;
+            self.expect("^")?;
+            let upper = self.parse_simple()?;
+            Some((Box::new(lower), Box::new(upper)))
+        } else {
+            None
+        };
+        let outer = self.stop_at_differential;
+        self.stop_at_differential = true;
+        let body = self.nested(|p| p.parse_sum());
+        self.stop_at_differential = outer;
+        let body = body?;
+        if !self.eat_name("d") {
+            return Err(self.error("Expected the differential, as in int x dx"));
+        }
+        let var = self.peek().map(|t| t.text.clone()).unwrap_or_default();
+        self.pos += 1;
+        Ok(Node::Integral(normalize_var(&var), bounds, Box::new(body)))
+    }
+
+    /// `lim_(x->a) f`, with `a^+` or `a^-` for a one-sided limit.
+    fn parse_limit(&mut self) -> Result<Node, ParseError> {
+        self.expect("_")?;
+        self.expect("(")?;
+        let var = match self.peek() {
+            Some(t) if t.kind == Kind::Name => normalize_var(&t.text.clone()),
+            _ => return Err(self.error("Expected the variable, as in lim_(x->0)")),
+        };
+        self.pos += 1;
+        if !(self.eat_symbol("->") || self.eat_name("rarr")) {
+            return Err(self.error("Expected '->'"));
+        }
+        // A trailing `^+` or `^-` is the side, not a power
+        let close = self
+            .matching_close(self.pos - 3)
+            .unwrap_or(self.tokens.len());
+        let side = match (self.tokens.get(close - 2), self.tokens.get(close - 1)) {
+            (Some(caret), Some(sign)) if caret.text == "^" && close >= self.pos + 3 => {
+                match sign.text.as_str() {
+                    "+" => Some(LimitDirection::Right),
+                    "-" => Some(LimitDirection::Left),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        let point = if side.is_some() {
+            let mut point_parser = AsciiMathParser {
+                tokens: self.tokens[self.pos..close - 2].to_vec(),
+                pos: 0,
+                source: self.source,
+                depth: self.depth,
+                max_depth: self.max_depth,
+                abs_depth: 0,
+                stop_at_differential: false,
+            };
+            let point = point_parser.parse_sum()?;
+            if point_parser.pos < point_parser.tokens.len() {
+                return Err(point_parser.error("Unexpected token in the limit point"));
+            }
+            self.pos = close - 2 + 2;
+            point
+        } else {
+            self.nested(|p| p.parse_sum())?
+        };
+        self.expect(")")?;
+        let body = self.nested(|p| p.parse_product())?;
+        Ok(Node::Limit(
+            var,
+            Box::new(point),
+            side.unwrap_or(LimitDirection::Both),
+            Box::new(body),
+        ))
+    }
+}
@@ -0,0 +1,165 @@
+//! Principal domains and branches of the multivalued built-in functions.
+//!
+//! Each inverse trigonometric, inverse hyperbolic and logarithmic function
+//! evaluates on one branch: [`principal_branch`] gives the real arguments
+//! it accepts and the values it returns. What numeric evaluation does with
+//! an argument outside that domain is a [`DomainPolicy`], read from the
+//! [`Environment`](crate::environment::Environment) by the evaluator and
+//! applied the same way by [`call_function`](crate::functions::call_function).
+
+use crate::exact::ExactNum;
+use crate::function_meta::canonical_function_name;
+
+/// What evaluation does with an argument outside a function's real
+/// principal domain, such as `\arcsin(2)` or `\ln(-1)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DomainPolicy {
+    /// The value is NaN, which numeric callers (plot sampling, identity
+    /// checks) already treat as "undefined here"
+    #[default]
+    Nan,
+    /// Evaluation fails with a message naming the domain
+    Error,
+}
+
+/// The branch a multivalued function evaluates on.
+#[derive(Clone, Copy, Debug)]
+pub struct PrincipalBranch {
+    /// Canonical function name (`arcsin`, not `asin`)
+    pub name: &'static str,
+    /// Real arguments with a real value, in interval notation
+    pub domain: &'static str,
+    /// Values the branch takes
+    pub range: &'static str,
+    in_domain: fn(f64) -> bool,
+}
+
+impl PrincipalBranch {
+    /// Whether `x` is in the real domain.
+    pub fn contains(&self, x: f64) -> bool {
+        (self.in_domain)(x)
+    }
+}
+
+const BRANCHES: [PrincipalBranch; 15] = [
+    // Inverse circular trigonometric
+    PrincipalBranch {
+        name: "arcsin",
+        domain: "[-1, 1]",
+        range: "[-π/2, π/2]",
+        in_domain: |x| x.abs() <= 1.0,
+    },
+    PrincipalBranch {
+        name: "arccos",
+        domain: "[-1, 1]",
+        range: "[0, π]",
+        in_domain: |x| x.abs() <= 1.0,
+    },
+    PrincipalBranch {
+        name: "arctan",
+        domain: "(-∞, ∞)",
+        range: "(-π/2, π/2)",
+        in_domain: |x| !x.is_nan(),
+    },
+    // Inverse reciprocal trigonometric
+    PrincipalBranch {
+        name: "arccsc",
+        domain: "(-∞, -1] ∪ [1, ∞)",
+        range: "[-π/2, 0) ∪ (0, π/2]",
+        in_domain: |x| x.abs() >= 1.0,
+    },
+    PrincipalBranch {
+        name: "arcsec",
+        domain: "(-∞, -1] ∪ [1, ∞)",
+        range: "[0, π/2) ∪ (π/2, π]",
+        in_domain: |x| x.abs() >= 1.0,
+    },
+    PrincipalBranch {
+        name: "arccot",
+        domain: "(-∞, ∞)",
+        range: "(-π/2, π/2]",
+        in_domain: |x| !x.is_nan(),
+    },
+    // Inverse hyperbolic
+    PrincipalBranch {
+        name: "arcsinh",
+        domain: "(-∞, ∞)",
+        range: "(-∞, ∞)",
+        in_domain: |x| !x.is_nan(),
+    },
+    PrincipalBranch {
+        name: "arccosh",
+        domain: "[1, ∞)",
+        range: "[0, ∞)",
+        in_domain: |x| x >= 1.0,
+    },
+    PrincipalBranch {
+        name: "arctanh",
+        domain: "(-1, 1)",
+        range: "(-∞, ∞)",
+        in_domain: |x| x.abs() < 1.0,
+    },
+    // Inverse reciprocal hyperbolic
+    PrincipalBranch {
+        name: "arccsch",
+        domain: "(-∞, 0) ∪ (0, ∞)",
+        range: "(-∞, 0) ∪ (0, ∞)",
+        in_domain: |x| x != 0.0 && !x.is_nan(),
+    },
+    PrincipalBranch {
+        name: "arcsech",
+        domain: "(0, 1]",
+        range: "[0, ∞)",
+        in_domain: |x| x > 0.0 && x <= 1.0,
+    },
+    PrincipalBranch {
+        name: "arccoth",
+        domain: "(-∞, -1) ∪ (1, ∞)",
+        range: "(-∞, 0) ∪ (0, ∞)",
+        in_domain: |x| x.abs() > 1.0,
+    },
+    // Logarithmic
+    PrincipalBranch {
+        name: "log",
+        domain: "(0, ∞)",
+        range: "(-∞, ∞)",
+        in_domain: |x| x > 0.0,
+    },
+    PrincipalBranch {
+        name: "ln",
+        domain: "(0, ∞)",
+        range: "(-∞, ∞)",
+        in_domain: |x| x > 0.0,
+    },
+    PrincipalBranch {
+        name: "lg",
+        domain: "(0, ∞)",
+        range: "(-∞, ∞)",
+        in_domain: |x| x > 0.0,
+    },
+];
+
+/// The principal branch of `name` (aliases such as `asin` included), or
+/// `None` for a function that is single-valued on all reals.
+pub fn principal_branch(name: &str) -> Option<&'static PrincipalBranch> {
+    let name = canonical_function_name(name);
+    BRANCHES.iter().find(|branch| branch.name == name)
+}
+
+impl DomainPolicy {
+    /// The result for `name(x)` when `x` is outside the principal domain,
+    /// or `None` when it is inside and the function should be called.
+    pub fn check(&self, name: &str, x: &ExactNum) -> Option<Result<ExactNum, String>> {
+        let branch = principal_branch(name)?;
+        if branch.contains(x.to_f64()) {
+            return None;
+        }
+        Some(match self {
+            DomainPolicy::Nan => Ok(ExactNum::Float(f64::NAN)),
+            DomainPolicy::Error => Err(format!(
+                "\\{}({}) is undefined: its principal domain is {}",
+                branch.name, x, branch.domain
+            )),
+        })
+    }
+}
@@ -0,0 +1,89 @@
+//! Grammar for LaTeX commands arithma does not know.
+//!
+//! A tool embedding arithma can teach the parser its own notation, say a
+//! chemistry package reading `\ce{H_2O}`, without forking it. Each
+//! [`ParserExtension`] is offered the commands of the input in turn, in the
+//! order they were registered in [`ParserExtensions`]: the first one to
+//! return `Some` parses the command, and one returning `None` passes it on
+//! to the next. A command no extension takes is parsed by the built-in
+//! grammar as usual, so an unknown `\foo` is still the variable `foo`.
+//!
+//! Extensions are consulted before the built-in grammar and may take over a
+//! command arithma knows. Only tokens written as commands are offered: the
+//! `ce` of `\ce{x}` is, the `ce` of `ce{x}` is not.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::node::Node;
+use crate::parser::{CommandInput, ParseError};
+
+/// Handler for LaTeX commands, registered in [`ParserExtensions`].
+pub trait ParserExtension: Send + Sync {
+    /// Parse `\command` (`command` is the name without the backslash),
+    /// whose token has just been read; `input` holds the tokens after it.
+    /// `None` declines the command, and the tokens read meanwhile are put
+    /// back for the next extension.
+    fn parse_command(
+        &self,
+        command: &str,
+        input: &mut CommandInput<'_, '_>,
+    ) -> Option<Result<Node, ParseError>>;
+}
+
+/// An ordered chain of [`ParserExtension`]s, passed to
+/// [`parse_latex_raw_with_extensions`](crate::parser::parse_latex_raw_with_extensions).
+#[derive(Clone, Default)]
+pub struct ParserExtensions {
+    handlers: Vec<Arc<dyn ParserExtension>>,
+}
+
+impl ParserExtensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `extension` after those already registered.
+    pub fn register(&mut self, extension: impl ParserExtension + 'static) {
+        self.handlers.push(Arc::new(extension));
+    }
+
+    /// [`register`](Self::register), for building a chain in one expression.
+    pub fn with(mut self, extension: impl ParserExtension + 'static) -> Self {
+        self.register(extension);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// The first extension's answer for `command`, rewinding `input` after
+    /// each one that declines.
+    pub(crate) fn parse_command(
+        &self,
+        command: &str,
+        input: &mut CommandInput<'_, '_>,
+    ) -> Option<Result<Node, ParseError>> {
+        let start = input.position();
+        self.handlers.iter().find_map(|handler| {
+            let parsed = handler.parse_command(command, input);
+            if parsed.is_none() {
+                input.rewind(start);
+            }
+            parsed
+        })
+    }
+}
+
+impl fmt::Debug for ParserExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParserExtensions")
+            .field("handlers", &self.handlers.len())
+            .finish()
+    }
+}
@@ -0,0 +1,401 @@
+//! Output forms of an expression besides `Display`.
+//!
+//! `Display` is the tidied reading form and is not meant to be parsed.
+//! `Node::to_latex` writes LaTeX that parses back to the same tree, and
+//! [`Node::to_plain_string`] writes plain infix text with the same
+//! guarantee for the plain input syntax: `3*x^2 + sqrt(x)/2`,
+//! `sum(k^2, k, 1, n)`, `pi`, `inf`. Both have a `_with` variant taking
+//! [`FormatOptions`], which choose decimal or exact numbers, the number of
+//! significant digits, and the multiplication sign.
+
+use crate::exact::ExactNum;
+use crate::limits::INFINITY;
+use crate::node::{LimitDirection, Node};
+use crate::tokenizer::latex_name;
+
+/// How non-integer numbers are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberStyle {
+    /// Rationals as fractions, floats as decimals
+    #[default]
+    Exact,
+    /// Every non-integer as a decimal
+    Decimal,
+}
+
+/// The sign written between factors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MulSymbol {
+    /// `\cdot` in LaTeX, `*` in plain text
+    #[default]
+    Auto,
+    /// `\cdot`, or `·` in plain text
+    Cdot,
+    /// `\times`, or `×` in plain text
+    Times,
+    /// `*` in both
+    Asterisk,
+}
+
+impl MulSymbol {
+    pub(crate) fn latex(self) -> &'static str {
+        match self {
+            MulSymbol::Auto | MulSymbol::Cdot => " \\cdot ",
+            MulSymbol::Times => " \\times ",
+            MulSymbol::Asterisk => " * ",
+        }
+    }
+
+    fn plain(self) -> &'static str {
+        match self {
+            MulSymbol::Auto | MulSymbol::Asterisk => "*",
+            MulSymbol::Cdot => "·",
+            MulSymbol::Times => "×",
+        }
+    }
+}
+
+/// Choices for [`Node::to_latex_with`] and [`Node::to_plain_string_with`].
+/// The default writes exact numbers with every digit a float has.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub numbers: NumberStyle,
+    /// Significant digits of a decimal; `None` writes the shortest form
+    /// that reads back as the same float
+    pub precision: Option<usize>,
+    pub multiplication: MulSymbol,
+}
+
+impl FormatOptions {
+    /// The digits to write for `n`, or `None` when it keeps its exact
+    /// spelling: integers always, fractions unless decimals are asked for,
+    /// and `π` and `e` unless decimals or a precision are.
+    pub(crate) fn decimal(&self, n: &ExactNum) -> Option<String> {
+        match n {
+            ExactNum::Rational(r) if r.is_integer() => None,
+            ExactNum::Rational(_) if self.numbers == NumberStyle::Exact => None,
+            ExactNum::Float(v)
+                if self.numbers == NumberStyle::Exact
+                    && (self.precision.is_none() || named_constant(*v).is_some()) =>
+            {
+                None
+            }
+            _ => Some(self.digits(n.to_f64())),
+        }
+    }
+
+    /// [`FormatOptions::decimal`] for a number literal, or for a quotient
+    /// of integer literals as the parser reads `\frac{1}{3}`.
+    pub(crate) fn decimal_node(&self, node: &Node) -> Option<String> {
+        match node {
+            Node::Num(n) => self.decimal(n),
+            Node::Divide(l, r) if self.numbers == NumberStyle::Decimal => match (&**l, &**r) {
+                (Node::Num(a), Node::Num(b))
+                    if a.is_integer() && b.is_integer() && !b.is_zero() =>
+                {
+                    Some(self.digits(a.to_f64() / b.to_f64()))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn digits(&self, v: f64) -> String {
+        match self.precision {
+            Some(digits) if v.is_finite() && v != 0.0 => {
+                let rounded: f64 = format!("{:.*e}", digits.max(1) - 1, v).parse().unwrap_or(v);
+                rounded.to_string()
+            }
+            _ => v.to_string(),
+        }
+    }
+}
+
+fn named_constant(v: f64) -> Option<&'static str> {
+    if (v - std::f64::consts::PI).abs() < 1e-15 {
+        Some("pi")
+    } else if (v - std::f64::consts::E).abs() < 1e-15 {
+        Some("e")
+    } else {
+        None
+    }
+}
+
+impl Node {
+    /// Plain infix text that the plain input syntax reads back as the same
+    /// tree: `*` between factors, `/` for division, `^` for powers,
+    /// brackets only where precedence or a leading minus needs them, and
+    /// named calls for everything LaTeX writes as notation (`sqrt(x)`,
+    /// `abs(x)`, `sum(body, k, a, b)`, `integrate(body, x, a, b)`,
+    /// `limit(body, x, a)`, `diff(body, x)`).
+    pub fn to_plain_string(&self) -> String {
+        self.to_plain_string_with(&FormatOptions::default())
+    }
+
+    /// [`Node::to_plain_string`] with numbers and products written as
+    /// `options` asks.
+    pub fn to_plain_string_with(&self, options: &FormatOptions) -> String {
+        let mut out = String::new();
+        write_node(self, options, &mut out);
+        out
+    }
+}
+
+// Binding strength for parenthesization, loosest first. Unlike in LaTeX,
+// a quotient and a fraction literal bind like a product.
+const EQUATION: u8 = 0;
+const OR: u8 = 1;
+const AND: u8 = 2;
+const NOT: u8 = 3;
+const RELATION: u8 = 4;
+const SUM: u8 = 5;
+const NEGATION: u8 = 6;
+const PRODUCT: u8 = 7;
+const POWER: u8 = 9;
+const FACTORIAL: u8 = 10;
+const ATOM: u8 = 12;
+
+fn precedence(node: &Node, options: &FormatOptions) -> u8 {
+    match node {
+        Node::Equation(_, _) => EQUATION,
+        Node::Greater(_, _)
+        | Node::Less(_, _)
+        | Node::GreaterEqual(_, _)
+        | Node::LessEqual(_, _)
+        | Node::Equal(_, _) => RELATION,
+        Node::Or(_, _) => OR,
+        Node::And(_, _) => AND,
+        Node::Not(_) => NOT,
+        Node::Add(_, _) | Node::Subtract(_, _) => SUM,
+        Node::Negate(_) => NEGATION,
+        _ if options.decimal_node(node).is_some() => {
+            if leading_minus(node) {
+                NEGATION
+            } else {
+                ATOM
+            }
+        }
+        Node::Num(n) if n.is_negative() => NEGATION,
+        Node::Num(ExactNum::Rational(r)) if !r.is_integer() => PRODUCT,
+        Node::Multiply(_, _) | Node::Divide(_, _) => PRODUCT,
+        Node::Power(_, _) => POWER,
+        Node::Factorial(_) => FACTORIAL,
+        _ => ATOM,
+    }
+}
+
+/// Whether the text starts with `-`, so that it needs brackets anywhere
+/// but the front of an expression.
+fn leading_minus(node: &Node) -> bool {
+    match node {
+        Node::Negate(_) => true,
+        Node::Num(n) => n.is_negative(),
+        Node::Add(l, _) | Node::Subtract(l, _) | Node::Multiply(l, _) | Node::Divide(l, _) => {
+            leading_minus(l)
+        }
+        _ => false,
+    }
+}
+
+fn write_operand(node: &Node, bracket: bool, options: &FormatOptions, out: &mut String) {
+    if bracket {
+        out.push('(');
+        write_node(node, options, out);
+        out.push(')');
+    } else {
+        write_node(node, options, out);
+    }
+}
+
+/// `l op r` for an operator grouping from the left at `prec`.
+fn write_binary(l: &Node, op: &str, r: &Node, prec: u8, options: &FormatOptions, out: &mut String) {
+    write_operand(l, precedence(l, options) < prec, options, out);
+    out.push_str(op);
+    // A leading minus only needs brackets after an arithmetic operator
+    write_operand(
+        r,
+        precedence(r, options) <= prec || prec >= SUM && leading_minus(r),
+        options,
+        out,
+    );
+}
+
+fn write_call(
+    name: &str,
+    args: &[&Node],
+    extra: &[&str],
+    options: &FormatOptions,
+    out: &mut String,
+) {
+    out.push_str(name);
+    out.push('(');
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_node(arg, options, out);
+    }
+    for word in extra {
+        out.push_str(", ");
+        out.push_str(word);
+    }
+    out.push(')');
+}
+
+/// A variable name in ASCII: Greek letters by name, `∞` as `inf`.
+fn variable_plain(name: &str) -> String {
+    if name == INFINITY {
+        return "inf".to_string();
+    }
+    name.chars()
+        .map(|c| match latex_name(c) {
+            Some(latex) => latex.trim_start_matches('\\').to_string(),
+            None => c.to_string(),
+        })
+        .collect()
+}
+
+fn write_number(n: &ExactNum, out: &mut String) {
+    match n {
+        ExactNum::Rational(r) if !r.is_integer() => {
+            out.push_str(&format!("{}/{}", r.numer(), r.denom()));
+        }
+        ExactNum::Rational(r) => out.push_str(&r.numer().to_string()),
+        ExactNum::Float(v) => match named_constant(*v) {
+            Some(name) => out.push_str(name),
+            None => out.push_str(&v.to_string()),
+        },
+    }
+}
+
+fn write_node(node: &Node, options: &FormatOptions, out: &mut String) {
+    if let Some(digits) = options.decimal_node(node) {
+        out.push_str(&digits);
+        return;
+    }
+    let prec = |n: &Node| precedence(n, options);
+    match node {
+        Node::Num(n) => write_number(n, out),
+        Node::Variable(v) => out.push_str(&variable_plain(v)),
+        Node::Add(l, r) => {
+            write_operand(l, prec(l) < SUM, options, out);
+            out.push_str(" + ");
+            write_operand(r, prec(r) < SUM || leading_minus(r), options, out);
+        }
+        Node::Subtract(l, r) => write_binary(l, " - ", r, SUM, options, out),
+        Node::Multiply(l, r) => {
+            // Unary minus binds tighter than multiplication: `-2*x`
+            write_operand(l, prec(l) < NEGATION, options, out);
+            out.push_str(options.multiplication.plain());
+            write_operand(r, prec(r) <= PRODUCT || leading_minus(r), options, out);
+        }
+        Node::Divide(l, r) => {
+            write_operand(l, prec(l) < NEGATION, options, out);
+            out.push('/');
+            write_operand(r, prec(r) <= PRODUCT || leading_minus(r), options, out);
+        }
+        Node::Power(base, exp) => {
+            write_operand(base, prec(base) < ATOM || leading_minus(base), options, out);
+            out.push('^');
+            write_operand(exp, prec(exp) < ATOM || leading_minus(exp), options, out);
+        }
+        Node::Negate(inner) => {
+            // `-x*y` reads as (−x)·y, so a negated product keeps brackets
+            out.push('-');
+            write_operand(
+                inner,
+                prec(inner) <= PRODUCT || leading_minus(inner),
+                options,
+                out,
+            );
+        }
+        Node::Factorial(inner) => {
+            write_operand(
+                inner,
+                prec(inner) < ATOM || leading_minus(inner),
+                options,
+                out,
+            );
+            out.push('!');
+        }
+        Node::Sqrt(inner) => write_call("sqrt", &[inner], &[], options, out),
+        Node::Abs(inner) => write_call("abs", &[inner], &[], options, out),
+        Node::Floor(inner) => write_call("floor", &[inner], &[], options, out),
+        Node::Ceil(inner) => write_call("ceil", &[inner], &[], options, out),
+        Node::Round(inner) => write_call("round", &[inner], &[], options, out),
+        Node::Trunc(inner) => write_call("trunc", &[inner], &[], options, out),
+        Node::Greater(l, r) => write_binary(l, " > ", r, RELATION, options, out),
+        Node::Less(l, r) => write_binary(l, " < ", r, RELATION, options, out),
+        Node::GreaterEqual(l, r) => write_binary(l, " >= ", r, RELATION, options, out),
+        Node::LessEqual(l, r) => write_binary(l, " <= ", r, RELATION, options, out),
+        Node::Equal(l, r) => write_binary(l, " == ", r, RELATION, options, out),
+        Node::Equation(l, r) => write_binary(l, " = ", r, EQUATION, options, out),
+        Node::And(l, r) => write_binary(l, " and ", r, AND, options, out),
+        Node::Or(l, r) => write_binary(l, " or ", r, OR, options, out),
+        Node::Not(inner) => {
+            out.push_str("not ");
+            write_operand(inner, prec(inner) < NOT, options, out);
+        }
+        Node::Piecewise(cases) => {
+            let args: Vec<&Node> = cases.iter().flat_map(|(e, c)| [e, c]).collect();
+            write_call("piecewise", &args, &[], options, out);
+        }
+        Node::Matrix(rows) => {
+            out.push('[');
+            for (i, row) in rows.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                out.push('[');
+                for (j, entry) in row.iter().enumerate() {
+                    if j > 0 {
+                        out.push_str(", ");
+                    }
+                    write_node(entry, options, out);
+                }
+                out.push(']');
+            }
+            out.push(']');
+        }
+        Node::Summation(index, start, end, body) | Node::Product(index, start, end, body) => {
+            let name = if matches!(node, Node::Summation(..)) {
+                "sum"
+            } else {
+                "prod"
+            };
+            let index = Node::Variable(index.clone());
+            write_call(name, &[body, &index, start, end], &[], options, out);
+        }
+        Node::SumOver(index, list, body) => {
+            let index = Node::Variable(index.clone());
+            write_call("sum", &[body, &index, list], &[], options, out);
+        }
+        Node::Integral(var, bounds, body) => {
+            let var = Node::Variable(var.clone());
+            match bounds {
+                Some((lower, upper)) => {
+                    write_call("integrate", &[body, &var, lower, upper], &[], options, out)
+                }
+                None => write_call("integrate", &[body, &var], &[], options, out),
+            }
+        }
+        Node::Limit(var, point, direction, body) => {
+            let var = Node::Variable(var.clone());
+            let side: &[&str] = match direction {
+                LimitDirection::Both => &[],
+                LimitDirection::Left => &["left"],
+                LimitDirection::Right => &["right"],
+            };
+            write_call("limit", &[body, &var, point], side, options, out);
+        }
+        Node::Derivative(var, body) => {
+            let var = Node::Variable(var.clone());
+            write_call("diff", &[body, &var], &[], options, out);
+        }
+        Node::Function(name, args) => {
+            let args: Vec<&Node> = args.iter().collect();
+            write_call(name, &args, &[], options, out);
+        }
+    }
+}
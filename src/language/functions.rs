@@ -1,8 +1,13 @@
 use lazy_static::lazy_static;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
+use crate::domains::DomainPolicy;
 use crate::exact::ExactNum;
-use crate::integer::{binom, factorial, gcd, lcm};
+use crate::integer::{binom, gcd, is_prime, lcm, permutations, powmod};
+use crate::node::Node;
+use crate::special;
 
 // Define a trait for function handlers
 pub trait FunctionHandler {
@@ -10,11 +15,23 @@ pub trait FunctionHandler {
 
     // New method to return the number of arguments the function requires
     fn get_arg_count(&self) -> Option<usize>; // None for variable arguments
+
+    /// The partial derivative in argument `index`, as an expression in
+    /// `args`. `differentiate` applies the chain rule over the arguments,
+    /// so a function that implements this is differentiable without a rule
+    /// of its own there. `None` when there is no symbolic derivative.
+    fn partial_derivative(&self, _args: &[Node], _index: usize) -> Option<Node> {
+        None
+    }
 }
 
-// Define the function registry that holds all functions
+/// Functions by name: the built-in [`FUNCTION_REGISTRY`], or the ones an
+/// application registers on an [`Environment`](crate::Environment) with
+/// [`register_function`](crate::Environment::register_function). Cloning
+/// shares the handlers.
+#[derive(Clone)]
 pub struct FunctionRegistry {
-    functions: HashMap<String, Box<dyn FunctionHandler + Send + Sync>>,
+    functions: HashMap<String, Arc<dyn FunctionHandler + Send + Sync>>,
 }
 
 impl Default for FunctionRegistry {
@@ -23,8 +40,15 @@ impl Default for FunctionRegistry {
     }
 }
 
+impl fmt::Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&str> = self.names().collect();
+        names.sort_unstable();
+        f.debug_set().entries(names).finish()
+    }
+}
+
 impl FunctionRegistry {
-    // Create a new function registry (using lazy_static to ensure it's a singleton)
     pub fn new() -> Self {
         Self {
             functions: HashMap::new(),
@@ -36,23 +60,62 @@ impl FunctionRegistry {
         name: &str,
         function: Box<dyn FunctionHandler + Send + Sync>,
     ) {
-        self.functions.insert(name.to_string(), function);
+        self.functions.insert(name.to_string(), Arc::from(function));
     }
 
     pub fn get(&self, name: &str) -> Option<&(dyn FunctionHandler + Send + Sync)> {
         self.functions.get(name).map(|v| &**v)
     }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
 }
 
-// Function to retrieve and call a function from the registry
+// Function to retrieve and call a function from the registry, with the
+// default policy for arguments outside a principal domain
 pub fn call_function(name: &str, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+    call_function_with_policy(name, args, DomainPolicy::default())
+}
+
+/// The error for a call of `name` that is neither built in nor defined in
+/// the environment, such as `\\operatorname{foo}(x)` before `foo` is
+/// defined.
+pub fn undefined_function(name: &str) -> String {
+    format!(
+        "Unknown function '{}': it is not built in; define it in the environment first",
+        name
+    )
+}
+
+/// [`call_function`] with an explicit [`DomainPolicy`]: an argument outside
+/// the function's principal domain gives NaN or an error instead of
+/// reaching the handler.
+pub fn call_function_with_policy(
+    name: &str,
+    args: Vec<ExactNum>,
+    policy: DomainPolicy,
+) -> Result<ExactNum, String> {
+    if let [x] = args.as_slice() {
+        if let Some(result) = policy.check(name, x) {
+            return result;
+        }
+    }
     if let Some(function) = FUNCTION_REGISTRY.get(name) {
         function.call(args)
     } else {
-        Err(format!("Unknown function: {}", name))
+        Err(undefined_function(name))
     }
 }
 
+/// Registered functions with no LaTeX command of their own, written as
+/// `\operatorname{…}` (which reads back as the same function)
+pub(crate) const OPERATOR_NAMES: [&str; 4] = ["lcm", "isprime", "powmod", "factorint"];
+
 fn arg_f64(args: &[ExactNum], i: usize) -> f64 {
     args[i].to_f64()
 }
@@ -69,12 +132,20 @@ lazy_static! {
         registry.register_function("ceil", Box::new(CeilFunction));
         registry.register_function("round", Box::new(RoundFunction));
         registry.register_function("trunc", Box::new(TruncFunction));
+        registry.register_function("sgn", Box::new(SgnFunction));
+        registry.register_function("sign", Box::new(SgnFunction));
+        registry.register_function("mod", Box::new(ModFunction));
 
         // Integer arithmetic
         registry.register_function("gcd", Box::new(GcdFunction));
         registry.register_function("lcm", Box::new(LcmFunction));
+        registry.register_function("isprime", Box::new(IsPrimeFunction));
+        registry.register_function("powmod", Box::new(PowModFunction));
+        registry.register_function("factorint", Box::new(FactorIntFunction));
         registry.register_function("factorial", Box::new(FactorialFunction));
         registry.register_function("binom", Box::new(BinomFunction));
+        registry.register_function("nCr", Box::new(BinomFunction));
+        registry.register_function("nPr", Box::new(PermutationsFunction));
 
         // Circular trigonometric
         registry.register_function("sin", Box::new(SinFunction));
@@ -93,6 +164,7 @@ lazy_static! {
         registry.register_function("asin", Box::new(ArcsinFunction));
         registry.register_function("acos", Box::new(ArccosFunction));
         registry.register_function("atan", Box::new(ArctanFunction));
+        registry.register_function("atan2", Box::new(Atan2Function));
 
         // Inverse reciprocal trigonometric
         registry.register_function("arccsc", Box::new(ArccscFunction));
@@ -128,13 +200,19 @@ lazy_static! {
         registry.register_function("lg", Box::new(LgFunction));
         registry.register_function("exp", Box::new(ExpFunction));
 
-        // Special functions (non-elementary antiderivatives): erf, Ei, li.
-        // Symbolic-only for now: they parse, print, and differentiate exactly;
-        // numeric evaluation is not yet implemented and says so rather than
-        // approximating silently.
+        // Special functions. erf, Ei and li arise as non-elementary
+        // antiderivatives; Ei and li are symbolic-only: they parse, print,
+        // and differentiate exactly, but numeric evaluation is not yet
+        // implemented and says so rather than approximating silently.
         registry.register_function("erf", Box::new(ErfFunction));
+        registry.register_function("erfc", Box::new(ErfcFunction));
         registry.register_function("Ei", Box::new(EiFunction));
         registry.register_function("li", Box::new(LiFunction));
+        // Γ is written \Gamma(x); the parser maps a Γ applied to
+        // parentheses to this name.
+        registry.register_function("Gamma", Box::new(GammaFunction));
+        registry.register_function("lgamma", Box::new(LgammaFunction));
+        registry.register_function("Beta", Box::new(BetaFunction));
 
         registry.register_function("frac", Box::new(FracFunction));
         registry.register_function("sqrt", Box::new(SqrtFunction));
@@ -149,7 +227,6 @@ lazy_static! {
         registry.register_function("liminf", Box::new(LimInfFunction)); // TODO: Implement Fully
         registry.register_function("limsup", Box::new(LimSupFunction)); // TODO: Implement Fully
         registry.register_function("arg", Box::new(ArgFunction)); // TODO: Implement Fully
-        registry.register_function("lim", Box::new(LimFunction)); // TODO: Implement Fully
 
         registry
     };
@@ -226,6 +303,65 @@ impl FunctionHandler for TruncFunction {
     }
 }
 
+/// The sign of x: −1, 0 or 1.
+pub struct SgnFunction;
+impl FunctionHandler for SgnFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 1 {
+            return Err("\\sgn requires exactly one argument.".to_string());
+        }
+        let x = &args[0];
+        if x.to_f64().is_nan() {
+            return Err("\\sgn of an undefined value.".to_string());
+        }
+        Ok(if x.is_zero() {
+            ExactNum::zero()
+        } else if x.is_negative() {
+            ExactNum::integer(-1)
+        } else {
+            ExactNum::one()
+        })
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// a mod b = a − b⌊a/b⌋, taking the sign of b, as `a \bmod b` or
+/// `\mod(a, b)`.
+pub struct ModFunction;
+impl FunctionHandler for ModFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 2 {
+            return Err("\\bmod requires exactly two operands.".to_string());
+        }
+        let (a, b) = (&args[0], &args[1]);
+        if b.is_zero() {
+            return Err("\\bmod by zero is undefined.".to_string());
+        }
+        Ok(a.clone() - b.clone() * (a.clone() / b.clone()).floor())
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    // ∂/∂a = 1 and ∂/∂b = −⌊a/b⌋, away from the jumps where a/b is an
+    // integer
+    fn partial_derivative(&self, args: &[Node], index: usize) -> Option<Node> {
+        let [a, b] = args else { return None };
+        match index {
+            0 => Some(Node::Num(ExactNum::one())),
+            1 => Some(Node::Negate(Box::new(Node::Floor(Box::new(Node::Divide(
+                Box::new(a.clone()),
+                Box::new(b.clone()),
+            )))))),
+            _ => None,
+        }
+    }
+}
+
 // Integer arithmetic
 pub struct GcdFunction;
 impl FunctionHandler for GcdFunction {
@@ -235,8 +371,8 @@ impl FunctionHandler for GcdFunction {
         }
         let mut result = args[0].clone();
         for arg in &args[1..] {
-            result = gcd(&result, arg)
-                .ok_or_else(|| "\\gcd requires non-negative integer arguments.".to_string())?;
+            result =
+                gcd(&result, arg).ok_or_else(|| "\\gcd requires integer arguments.".to_string())?;
         }
         Ok(result)
     }
@@ -254,8 +390,8 @@ impl FunctionHandler for LcmFunction {
         }
         let mut result = args[0].clone();
         for arg in &args[1..] {
-            result = lcm(&result, arg)
-                .ok_or_else(|| "\\lcm requires non-negative integer arguments.".to_string())?;
+            result =
+                lcm(&result, arg).ok_or_else(|| "\\lcm requires integer arguments.".to_string())?;
         }
         Ok(result)
     }
@@ -265,14 +401,70 @@ impl FunctionHandler for LcmFunction {
     }
 }
 
+/// 1 when the argument is prime, 0 otherwise, as relations evaluate.
+pub struct IsPrimeFunction;
+impl FunctionHandler for IsPrimeFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 1 {
+            return Err("\\isprime requires exactly one argument.".to_string());
+        }
+        match is_prime(&args[0]) {
+            Some(prime) => Ok(ExactNum::integer(prime as i64)),
+            None => Err("\\isprime requires an integer argument.".to_string()),
+        }
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// powmod(b, e, m) = b^e mod m, computed without the full power.
+pub struct PowModFunction;
+impl FunctionHandler for PowModFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 3 {
+            return Err("\\powmod requires exactly three arguments.".to_string());
+        }
+        powmod(&args[0], &args[1], &args[2]).ok_or_else(|| {
+            "\\powmod requires integers, a non-negative exponent and a nonzero modulus.".to_string()
+        })
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(3)
+    }
+}
+
+/// The prime factorization of an integer. Its value is the integer itself;
+/// `simplify` writes out the product of prime powers.
+pub struct FactorIntFunction;
+impl FunctionHandler for FactorIntFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 1 {
+            return Err("\\factorint requires exactly one argument.".to_string());
+        }
+        if args[0].to_rational().is_some_and(|r| r.is_integer()) {
+            Ok(args[0].clone())
+        } else {
+            Err("\\factorint requires an integer argument.".to_string())
+        }
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
 pub struct FactorialFunction;
 impl FunctionHandler for FactorialFunction {
     fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
         if args.len() != 1 {
             return Err("\\factorial requires exactly one argument.".to_string());
         }
-        factorial(&args[0])
-            .ok_or_else(|| "\\factorial requires a non-negative integer.".to_string())
+        special::factorial(&args[0]).ok_or_else(|| {
+            "\\factorial requires a non-negative integer or a non-integer real.".to_string()
+        })
     }
 
     fn get_arg_count(&self) -> Option<usize> {
@@ -295,6 +487,22 @@ impl FunctionHandler for BinomFunction {
     }
 }
 
+/// P(n, k) = n!/(n − k)!, the ordered selections of k from n.
+pub struct PermutationsFunction;
+impl FunctionHandler for PermutationsFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 2 {
+            return Err("\\nPr requires exactly two arguments.".to_string());
+        }
+        permutations(&args[0], &args[1])
+            .ok_or_else(|| "\\nPr requires non-negative integer arguments.".to_string())
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
 // Circular trigonometric
 pub struct SinFunction;
 impl FunctionHandler for SinFunction {
@@ -436,6 +644,39 @@ impl FunctionHandler for ArctanFunction {
     }
 }
 
+/// `\atan2(y, x)`: the angle of the point (x, y), in (-π, π].
+pub struct Atan2Function;
+impl FunctionHandler for Atan2Function {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 2 {
+            return Err("\\atan2 requires exactly two arguments.".to_string());
+        }
+        Ok(ExactNum::Float(arg_f64(&args, 0).atan2(arg_f64(&args, 1))))
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    // ∂/∂y = x/(x² + y²), ∂/∂x = −y/(x² + y²)
+    fn partial_derivative(&self, args: &[Node], index: usize) -> Option<Node> {
+        let [y, x] = args else { return None };
+        let square = |n: &Node| {
+            Node::Power(
+                Box::new(n.clone()),
+                Box::new(Node::Num(ExactNum::integer(2))),
+            )
+        };
+        let radius_squared = Node::Add(Box::new(square(x)), Box::new(square(y)));
+        let numerator = match index {
+            0 => x.clone(),
+            1 => Node::Negate(Box::new(y.clone())),
+            _ => return None,
+        };
+        Some(Node::Divide(Box::new(numerator), Box::new(radius_squared)))
+    }
+}
+
 // Inverse reciprocal trigonometric
 pub struct ArccscFunction;
 impl FunctionHandler for ArccscFunction {
@@ -753,20 +994,132 @@ impl FunctionHandler for LgFunction {
 }
 
 /// The error function erf(x) = (2/√π)∫₀ˣ e^{-t²} dt (DLMF 7.2.1).
-/// Kept symbolic: no f64 approximation is offered until one with a stated
-/// error bound lands; an honest refusal beats a silent approximation.
 pub struct ErfFunction;
 impl FunctionHandler for ErfFunction {
     fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
         if args.len() != 1 {
             return Err("\\erf requires exactly one argument.".to_string());
         }
-        Err("Numeric evaluation of erf is not implemented; the value is kept symbolic.".to_string())
+        Ok(ExactNum::Float(special::erf(arg_f64(&args, 0))))
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// The complementary error function erfc(x) = 1 − erf(x) (DLMF 7.2.2).
+pub struct ErfcFunction;
+impl FunctionHandler for ErfcFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 1 {
+            return Err("\\erfc requires exactly one argument.".to_string());
+        }
+        Ok(ExactNum::Float(special::erfc(arg_f64(&args, 0))))
     }
 
     fn get_arg_count(&self) -> Option<usize> {
         Some(1)
     }
+
+    // d/dx erfc(x) = −(2/√π)·e^{−x²}
+    fn partial_derivative(&self, args: &[Node], index: usize) -> Option<Node> {
+        let ([x], 0) = (args, index) else { return None };
+        let coefficient = Node::Divide(
+            Box::new(Node::Num(ExactNum::two())),
+            Box::new(Node::Sqrt(Box::new(Node::Variable("π".to_string())))),
+        );
+        let gaussian = Node::Function(
+            "exp".to_string(),
+            vec![Node::Negate(Box::new(Node::Power(
+                Box::new(x.clone()),
+                Box::new(Node::Num(ExactNum::two())),
+            )))],
+        );
+        Some(Node::Negate(Box::new(Node::Multiply(
+            Box::new(coefficient),
+            Box::new(gaussian),
+        ))))
+    }
+}
+
+/// The gamma function Γ(x) (DLMF 5.2.1): exactly (n − 1)! at a positive
+/// integer n, undefined at 0, −1, −2, ….
+pub struct GammaFunction;
+impl FunctionHandler for GammaFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 1 {
+            return Err("\\Gamma requires exactly one argument.".to_string());
+        }
+        let x = &args[0];
+        if x.is_integer() {
+            return if x.is_negative() || x.is_zero() {
+                Err("\\Gamma is undefined at 0 and the negative integers.".to_string())
+            } else {
+                special::factorial(&(x.clone() - ExactNum::one()))
+                    .ok_or_else(|| "\\Gamma argument is too large.".to_string())
+            };
+        }
+        Ok(ExactNum::Float(special::gamma(x.to_f64())))
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// ln|Γ(x)| (DLMF 5.5.3), finite where Γ itself overflows.
+pub struct LgammaFunction;
+impl FunctionHandler for LgammaFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 1 {
+            return Err("\\lgamma requires exactly one argument.".to_string());
+        }
+        let value = special::ln_gamma(arg_f64(&args, 0));
+        if value.is_infinite() {
+            return Err("\\lgamma is undefined at 0 and the negative integers.".to_string());
+        }
+        Ok(ExactNum::Float(value))
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// The beta function B(a, b) = Γ(a)Γ(b)/Γ(a + b) (DLMF 5.12.1): exact
+/// when both arguments are positive integers.
+pub struct BetaFunction;
+impl FunctionHandler for BetaFunction {
+    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
+        if args.len() != 2 {
+            return Err("\\Beta requires exactly two arguments.".to_string());
+        }
+        let (a, b) = (&args[0], &args[1]);
+        let positive_integer = |n: &ExactNum| n.is_integer() && !n.is_negative() && !n.is_zero();
+        if positive_integer(a) && positive_integer(b) {
+            let one = ExactNum::one();
+            let fact = |n: ExactNum| special::factorial(&n);
+            if let (Some(fa), Some(fb), Some(fab)) = (
+                fact(a.clone() - one.clone()),
+                fact(b.clone() - one.clone()),
+                fact(a.clone() + b.clone() - one),
+            ) {
+                return Ok(fa * fb / fab);
+            }
+        }
+        let value = special::beta(a.to_f64(), b.to_f64());
+        if !value.is_finite() {
+            return Err(
+                "\\Beta is undefined where a, b or a + b is 0 or a negative integer.".to_string(),
+            );
+        }
+        Ok(ExactNum::Float(value))
+    }
+
+    fn get_arg_count(&self) -> Option<usize> {
+        Some(2)
+    }
 }
 
 /// The exponential integral Ei(x) = −∫_{−x}^∞ e^{−t}/t dt (DLMF 6.2.5).
@@ -873,18 +1226,19 @@ impl FunctionHandler for MaxFunction {
     }
 }
 
-// Determinant (currently treated as product)
+/// `\det` of a number, read as a 1×1 matrix. A matrix argument is taken
+/// by the simplifier and evaluator before it gets here.
 pub struct DetFunction;
 impl FunctionHandler for DetFunction {
     fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
-        if args.is_empty() {
-            return Err("\\det requires at least one argument.".to_string());
+        if args.len() != 1 {
+            return Err("\\det requires exactly one argument, a matrix.".to_string());
         }
-        Ok(args.into_iter().fold(ExactNum::one(), |a, b| a * b))
+        Ok(args[0].clone())
     }
 
     fn get_arg_count(&self) -> Option<usize> {
-        None // Variable number of arguments
+        Some(1)
     }
 }
 
@@ -1036,18 +1390,3 @@ impl FunctionHandler for LimSupFunction {
         None // Variable number of arguments
     }
 }
-
-pub struct LimFunction;
-impl FunctionHandler for LimFunction {
-    fn call(&self, args: Vec<ExactNum>) -> Result<ExactNum, String> {
-        if args.len() != 2 {
-            return Err("\\lim requires exactly two arguments: the function value and the point to evaluate at.".to_string());
-        }
-
-        Ok(args[0].clone()) // Just return the function value for now (as a placeholder)
-    }
-
-    fn get_arg_count(&self) -> Option<usize> {
-        Some(2) // Requires two arguments: function and the point
-    }
-}
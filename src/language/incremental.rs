@@ -0,0 +1,153 @@
+//! Re-parsing an expression as it is typed.
+//!
+//! An editor re-parses after every keystroke, and almost every keystroke
+//! only changes the end of the input. [`IncrementalParser`] keeps the
+//! tokens of the previous input together with the tokenizer state before
+//! each of them, so a new input is only tokenized from the last point
+//! whose tokens cannot have changed: the hundreds of entries of a long
+//! vector are not read again when its closing `\end{pmatrix}` is typed. The tree is then
+//! rebuilt from the tokens, and reused as is when they did not change
+//! (an edit inside whitespace).
+//!
+//! The result is always the one [`parse_latex_raw_with_options`] gives for
+//! the same input, errors and their spans included.
+//!
+//! [`parse_latex_raw_with_options`]: crate::parser::parse_latex_raw_with_options
+
+use std::ops::Range;
+
+use crate::node::Node;
+use crate::parser::{parse_tokenized, ParseError, ParserOptions};
+use crate::tokenizer::{Tokenizer, TokenizerCheckpoint};
+
+/// A parser that remembers its last input. See the [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalParser {
+    options: ParserOptions,
+    source: String,
+    tokens: Vec<String>,
+    spans: Vec<Range<usize>>,
+    errors: Vec<String>,
+    error_spans: Vec<Range<usize>>,
+    /// Tokenizer state before each step over `source`, in input order
+    checkpoints: Vec<TokenizerCheckpoint>,
+    result: Option<Result<Node, ParseError>>,
+    reused_tokens: usize,
+}
+
+impl IncrementalParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A parser applying `options` to every input, as
+    /// [`parse_latex_raw_with_options`](crate::parser::parse_latex_raw_with_options) does.
+    pub fn with_options(options: ParserOptions) -> Self {
+        IncrementalParser {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Parse `latex` (no simplification), reusing what it shares with the
+    /// previous input.
+    pub fn parse(&mut self, latex: &str) -> Result<Node, ParseError> {
+        if let Some(result) = self.result.as_ref().filter(|_| latex == self.source) {
+            self.reused_tokens = self.tokens.len();
+            return result.clone();
+        }
+
+        let index = self.resume_index(common_prefix(&self.source, latex));
+        let checkpoint = match index {
+            Some(i) => self.checkpoints[i].clone(),
+            None => Tokenizer::initial_checkpoint(),
+        };
+        self.checkpoints.truncate(index.unwrap_or(0));
+
+        let previous = std::mem::take(&mut self.tokens);
+        let mut tokens = previous[..checkpoint.tokens].to_vec();
+        let mut spans = std::mem::take(&mut self.spans);
+        spans.truncate(checkpoint.tokens);
+        if let Some((token, span)) = &checkpoint.last {
+            *tokens.last_mut().expect("the checkpoint follows a token") = token.clone();
+            *spans.last_mut().expect("one span per token") = span.clone();
+        }
+        let mut errors = std::mem::take(&mut self.errors);
+        let mut error_spans = std::mem::take(&mut self.error_spans);
+        errors.truncate(checkpoint.errors);
+        error_spans.truncate(checkpoint.errors);
+        self.reused_tokens = checkpoint.tokens;
+
+        let (tokens, tokenizer) = Tokenizer::resume(
+            latex,
+            &checkpoint,
+            tokens,
+            spans,
+            (errors, error_spans),
+            &mut self.checkpoints,
+        );
+        let result = match self.result.take() {
+            Some(Ok(node)) if tokens == previous && tokenizer.errors.is_empty() => Ok(node),
+            _ => parse_tokenized(latex, &tokens, &tokenizer, None, &self.options, None),
+        };
+
+        self.source = latex.to_string();
+        self.tokens = tokens;
+        self.spans = tokenizer.spans;
+        self.errors = tokenizer.errors;
+        self.error_spans = tokenizer.error_spans;
+        self.result = Some(result.clone());
+        result
+    }
+
+    /// The input last parsed.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The tokens of the input last parsed.
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    /// How many tokens the last [`parse`](Self::parse) carried over from
+    /// the input before instead of tokenizing again.
+    pub fn reused_tokens(&self) -> usize {
+        self.reused_tokens
+    }
+
+    /// Forget the previous input, so the next parse starts from scratch.
+    pub fn reset(&mut self) {
+        *self = Self::with_options(self.options);
+    }
+
+    /// The last checkpoint to resume from when the new input agrees with
+    /// the old one on its first `prefix` bytes. Besides having read no
+    /// further than that, it must come before no step that rewrote an
+    /// earlier token than its own last one: a step may replace the token
+    /// it follows (`1.2\overline{3}` replaces `1.2`), so a checkpoint
+    /// holding more tokens than some later one cannot be restored.
+    fn resume_index(&self, prefix: usize) -> Option<usize> {
+        let end = self
+            .checkpoints
+            .partition_point(|checkpoint| checkpoint.horizon <= prefix);
+        let mut fewest_after = usize::MAX;
+        for checkpoint in &self.checkpoints[end..] {
+            fewest_after = fewest_after.min(checkpoint.tokens);
+        }
+        (0..end).rev().find(|&i| {
+            let checkpoint = &self.checkpoints[i];
+            fewest_after = fewest_after.min(checkpoint.tokens);
+            checkpoint.tokens <= fewest_after
+        })
+    }
+}
+
+/// Length in bytes of the longest common prefix of `a` and `b`, on a
+/// character boundary of both.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or(a.len().min(b.len()), |((i, _), _)| i)
+}
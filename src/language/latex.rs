@@ -0,0 +1,428 @@
+use crate::exact::ExactNum;
+use crate::format::FormatOptions;
+use crate::functions::{FUNCTION_REGISTRY, OPERATOR_NAMES};
+use crate::node::Node;
+use crate::tokenizer::variable_latex;
+use crate::vector;
+
+// Binding strength for parenthesization, loosest first. Division prints
+// as \frac and every bracketed form (|x|, ⌊x⌋, f(x)) is an atom.
+const EQUATION: u8 = 0;
+const OR: u8 = 1;
+const AND: u8 = 2;
+const NOT: u8 = 3;
+const RELATION: u8 = 4;
+const SUM: u8 = 5;
+const NEGATION: u8 = 6;
+const PRODUCT: u8 = 7;
+const BIG_OPERATOR: u8 = 8;
+const POWER: u8 = 9;
+const FACTORIAL: u8 = 10;
+const ATOM: u8 = 12;
+
+impl Node {
+    /// Render as LaTeX that parses back to the same tree: `\frac{a}{b}`
+    /// for division, `\cdot` between factors (juxtaposition only for an
+    /// integer coefficient, as in `2x`), `\left(…\right)` only where
+    /// precedence or a leading minus requires it, `\sqrt{…}`,
+    /// `\left|…\right|`, `\sum_{k=a}^{b}{…}`, `\int_{a}^{b} … \, dx`, and
+    /// `\frac{d}{dx}…`.
+    ///
+    /// Unlike `Display`, which tidies for reading (`x·2` shows as `2x`,
+    /// `a + (−b)` as `a − b`), this keeps the tree's exact shape, so
+    /// `parse_latex_raw(&e.to_latex())` reproduces `e` for anything the
+    /// parser itself produces. Rational literals print as `\frac{p}{q}`
+    /// and reparse as a division.
+    pub fn to_latex(&self) -> String {
+        self.to_latex_with(&FormatOptions::default())
+    }
+
+    /// [`Node::to_latex`] with numbers and products written as `options`
+    /// asks. Decimal numbers parse back as floats, so the round trip
+    /// holds only for the default options.
+    pub fn to_latex_with(&self, options: &FormatOptions) -> String {
+        let mut out = Latex {
+            text: String::new(),
+            options,
+        };
+        write_node(self, &mut out);
+        out.text
+    }
+}
+
+/// LaTeX written so far, and the choices it is written with.
+struct Latex<'a> {
+    text: String,
+    options: &'a FormatOptions,
+}
+
+impl Latex<'_> {
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.text.push_str(s);
+    }
+}
+
+fn precedence(node: &Node) -> u8 {
+    match node {
+        Node::Equation(_, _) => EQUATION,
+        Node::Greater(_, _)
+        | Node::Less(_, _)
+        | Node::GreaterEqual(_, _)
+        | Node::LessEqual(_, _)
+        | Node::Equal(_, _) => RELATION,
+        Node::Or(_, _) => OR,
+        Node::And(_, _) => AND,
+        Node::Not(_) => NOT,
+        Node::Add(_, _) | Node::Subtract(_, _) => SUM,
+        Node::Negate(_) => NEGATION,
+        Node::Num(n) if n.is_negative() => NEGATION,
+        Node::Multiply(_, _) => PRODUCT,
+        Node::Summation(..)
+        | Node::Product(..)
+        | Node::SumOver(..)
+        | Node::Integral(..)
+        | Node::Limit(..)
+        | Node::Derivative(..) => BIG_OPERATOR,
+        Node::Power(_, _) => POWER,
+        Node::Factorial(_) => FACTORIAL,
+        Node::Function(name, _) if matches!(name.as_str(), vector::DOT | vector::CROSS) => PRODUCT,
+        _ if is_modulo(node) => PRODUCT,
+        _ => ATOM,
+    }
+}
+
+/// Whether the rendering starts with `-`. Such an operand is bracketed
+/// anywhere but the front of an expression: `a + (-b)`, `2 \cdot (-x)`.
+fn leading_minus(node: &Node) -> bool {
+    match node {
+        Node::Negate(_) => true,
+        Node::Num(n) => n.is_negative(),
+        Node::Add(l, _) | Node::Subtract(l, _) | Node::Multiply(l, _) => leading_minus(l),
+        _ => false,
+    }
+}
+
+/// Atoms that read as one symbol when written after an exponent-free
+/// base: `x^{2}` is fine, `\frac{1}{2}^{2}` and `3!^{2}` are not.
+fn is_power_base(node: &Node) -> bool {
+    match node {
+        Node::Num(n) => n.is_integer() && !n.is_negative() || matches!(n, ExactNum::Float(_)),
+        Node::Divide(_, _) | Node::Floor(_) | Node::Ceil(_) => false,
+        other => precedence(other) == ATOM,
+    }
+}
+
+/// `a \bmod b`, written infix at the level of a product. It is not
+/// associative with `\cdot`, so a product keeps it bracketed on the right.
+fn is_modulo(node: &Node) -> bool {
+    matches!(node, Node::Function(name, args) if name == "mod" && args.len() == 2)
+}
+
+/// Right-hand factors that may follow an integer coefficient directly.
+fn is_juxtaposable(node: &Node) -> bool {
+    match node {
+        _ if is_modulo(node) => false,
+        Node::Variable(_) | Node::Function(_, _) | Node::Sqrt(_) | Node::Abs(_) => true,
+        Node::Power(base, _) => matches!(**base, Node::Variable(_) | Node::Function(_, _)),
+        _ => false,
+    }
+}
+
+fn write_operand(node: &Node, bracket: bool, out: &mut Latex) {
+    if bracket {
+        out.push_str("\\left(");
+        write_node(node, out);
+        out.push_str("\\right)");
+    } else {
+        write_node(node, out);
+    }
+}
+
+fn write_binary(l: &Node, op: &str, r: &Node, prec: u8, out: &mut Latex) {
+    write_operand(l, precedence(l) < prec, out);
+    out.push_str(op);
+    write_operand(r, precedence(r) < prec || leading_minus(r), out);
+}
+
+fn write_args(args: &[Node], out: &mut Latex) {
+    out.push_str("\\left(");
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_node(arg, out);
+    }
+    out.push_str("\\right)");
+}
+
+fn write_number(n: &ExactNum, out: &mut Latex) {
+    match n {
+        ExactNum::Rational(r) if !r.is_integer() => {
+            if n.is_negative() {
+                out.push('-');
+            }
+            out.push_str(&format!(
+                "\\frac{{{}}}{{{}}}",
+                r.numer().magnitude(),
+                r.denom()
+            ));
+        }
+        // Integers print as digits; floats share Display's e and π spelling
+        _ => out.push_str(&n.to_string()),
+    }
+}
+
+fn write_node(node: &Node, out: &mut Latex) {
+    if let Some(digits) = out.options.decimal_node(node) {
+        out.push_str(&digits);
+        return;
+    }
+    match node {
+        Node::Num(n) => write_number(n, out),
+        Node::Variable(v) => out.push_str(&variable_latex(v)),
+        Node::Add(l, r) => write_binary(l, " + ", r, SUM, out),
+        Node::Subtract(l, r) => {
+            write_operand(l, precedence(l) < SUM, out);
+            out.push_str(" - ");
+            write_operand(r, precedence(r) <= SUM || leading_minus(r), out);
+        }
+        Node::Multiply(l, r) => {
+            // Unary minus binds tighter than multiplication, so a negated
+            // left factor needs no brackets: `-2x` is (−2)·x.
+            let coefficient = match &**l {
+                Node::Num(n) => n.is_integer(),
+                Node::Negate(inner) => matches!(&**inner, Node::Num(n) if n.is_integer()),
+                _ => false,
+            };
+            write_operand(l, precedence(l) < NEGATION, out);
+            if !(coefficient && is_juxtaposable(r)) {
+                out.push_str(out.options.multiplication.latex());
+            }
+            write_operand(
+                r,
+                precedence(r) < PRODUCT || leading_minus(r) || is_modulo(r),
+                out,
+            );
+        }
+        Node::Divide(l, r) => {
+            out.push_str("\\frac{");
+            write_node(l, out);
+            out.push_str("}{");
+            write_node(r, out);
+            out.push('}');
+        }
+        Node::Power(base, exp) => {
+            write_operand(base, !is_power_base(base), out);
+            out.push_str("^{");
+            write_node(exp, out);
+            out.push('}');
+        }
+        Node::Negate(inner) => {
+            out.push('-');
+            write_operand(
+                inner,
+                precedence(inner) <= NEGATION || leading_minus(inner) || is_modulo(inner),
+                out,
+            );
+        }
+        Node::Factorial(inner) => {
+            write_operand(inner, !is_power_base(inner), out);
+            out.push('!');
+        }
+        Node::Sqrt(inner) => {
+            out.push_str("\\sqrt{");
+            write_node(inner, out);
+            out.push('}');
+        }
+        Node::Abs(inner) => {
+            out.push_str("\\left|");
+            write_node(inner, out);
+            out.push_str("\\right|");
+        }
+        Node::Floor(inner) => {
+            out.push_str("\\lfloor ");
+            write_node(inner, out);
+            out.push_str(" \\rfloor");
+        }
+        Node::Ceil(inner) => {
+            out.push_str("\\lceil ");
+            write_node(inner, out);
+            out.push_str(" \\rceil");
+        }
+        Node::Round(inner) => {
+            out.push_str("\\operatorname{round}");
+            write_args(std::slice::from_ref(inner), out);
+        }
+        Node::Trunc(inner) => {
+            out.push_str("\\operatorname{trunc}");
+            write_args(std::slice::from_ref(inner), out);
+        }
+        Node::Greater(l, r) => write_binary(l, " > ", r, RELATION + 1, out),
+        Node::Less(l, r) => write_binary(l, " < ", r, RELATION + 1, out),
+        Node::GreaterEqual(l, r) => write_binary(l, " \\geq ", r, RELATION + 1, out),
+        Node::LessEqual(l, r) => write_binary(l, " \\leq ", r, RELATION + 1, out),
+        Node::Equal(l, r) => write_binary(l, " == ", r, RELATION + 1, out),
+        Node::Equation(l, r) => write_binary(l, " = ", r, EQUATION + 1, out),
+        Node::And(l, r) => {
+            write_operand(l, precedence(l) < AND, out);
+            out.push_str(" \\land ");
+            write_operand(r, precedence(r) <= AND, out);
+        }
+        Node::Or(l, r) => {
+            write_operand(l, precedence(l) < OR, out);
+            out.push_str(" \\lor ");
+            write_operand(r, precedence(r) <= OR, out);
+        }
+        Node::Not(inner) => {
+            out.push_str("\\lnot ");
+            write_operand(inner, precedence(inner) < NOT, out);
+        }
+        Node::Piecewise(cases) => {
+            out.push_str("\\begin{cases} ");
+            for (i, (expr, cond)) in cases.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(" \\\\ ");
+                }
+                write_node(expr, out);
+                out.push_str(" & \\text{if } ");
+                write_node(cond, out);
+            }
+            out.push_str(" \\end{cases}");
+        }
+        Node::Matrix(rows) => {
+            out.push_str("\\begin{pmatrix} ");
+            for (i, row) in rows.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(" \\\\ ");
+                }
+                for (j, entry) in row.iter().enumerate() {
+                    if j > 0 {
+                        out.push_str(" & ");
+                    }
+                    write_node(entry, out);
+                }
+            }
+            out.push_str(" \\end{pmatrix}");
+        }
+        Node::Summation(index, start, end, body) | Node::Product(index, start, end, body) => {
+            out.push_str(if matches!(node, Node::Summation(..)) {
+                "\\sum_{"
+            } else {
+                "\\prod_{"
+            });
+            out.push_str(index);
+            out.push('=');
+            write_node(start, out);
+            out.push_str("}^{");
+            write_node(end, out);
+            out.push_str("}{");
+            write_node(body, out);
+            out.push('}');
+        }
+        Node::SumOver(index, list, body) => {
+            out.push_str("\\sum_{");
+            out.push_str(index);
+            out.push_str(" \\in ");
+            write_node(list, out);
+            out.push_str("}{");
+            write_node(body, out);
+            out.push('}');
+        }
+        Node::Integral(var, bounds, body) => {
+            out.push_str("\\int");
+            if let Some((lower, upper)) = bounds {
+                out.push_str("_{");
+                write_node(lower, out);
+                out.push_str("}^{");
+                write_node(upper, out);
+                out.push('}');
+            }
+            out.push(' ');
+            write_node(body, out);
+            out.push_str(" \\, d");
+            out.push_str(var);
+        }
+        Node::Limit(var, point, direction, body) => {
+            out.push_str("\\lim_{");
+            out.push_str(var);
+            out.push_str(" \\to ");
+            write_node(point, out);
+            out.push_str(direction.latex_suffix());
+            out.push_str("}{");
+            write_node(body, out);
+            out.push('}');
+        }
+        Node::Derivative(var, body) => {
+            out.push_str("\\frac{d}{d");
+            out.push_str(&variable_latex(var));
+            out.push('}');
+            write_operand(body, precedence(body) < PRODUCT || leading_minus(body), out);
+        }
+        Node::Function(name, args) => match (name.as_str(), args.as_slice()) {
+            ("binom", [n, k]) => {
+                out.push_str("\\binom{");
+                write_node(n, out);
+                out.push_str("}{");
+                write_node(k, out);
+                out.push('}');
+            }
+            ("sqrt", [x]) => {
+                out.push_str("\\sqrt{");
+                write_node(x, out);
+                out.push('}');
+            }
+            ("mod", [l, r]) => {
+                write_operand(l, precedence(l) < PRODUCT, out);
+                out.push_str(" \\bmod ");
+                write_operand(r, precedence(r) <= PRODUCT || leading_minus(r), out);
+            }
+            (vector::DOT | vector::CROSS, [l, r]) => {
+                let op = if name == vector::DOT {
+                    " \\cdot "
+                } else {
+                    " \\times "
+                };
+                // Products group from the left, so a product on the right
+                // keeps its brackets
+                write_operand(l, precedence(l) < PRODUCT, out);
+                out.push_str(op);
+                write_operand(r, precedence(r) <= PRODUCT || leading_minus(r), out);
+            }
+            (vector::VECTOR_NAME, [Node::Variable(v)]) => {
+                out.push_str("\\vec{");
+                out.push_str(&variable_latex(v));
+                out.push('}');
+            }
+            (vector::VECTOR_LITERAL, components) if !components.is_empty() => {
+                out.push_str("\\begin{pmatrix} ");
+                for (i, c) in components.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(" \\\\ ");
+                    }
+                    write_node(c, out);
+                }
+                out.push_str(" \\end{pmatrix}");
+            }
+            _ => {
+                // Environment-defined functions are plain identifiers, and
+                // longer names are marked so they read back as one name
+                if FUNCTION_REGISTRY.get(name).is_some() && !OPERATOR_NAMES.contains(&name.as_str())
+                {
+                    out.push('\\');
+                    out.push_str(name);
+                } else if name.chars().count() > 1 {
+                    out.push_str("\\operatorname{");
+                    out.push_str(name);
+                    out.push('}');
+                } else {
+                    out.push_str(name);
+                }
+                write_args(args, out);
+            }
+        },
+    }
+}
@@ -1,589 +1,1614 @@
+use crate::constants::Constant;
+use crate::environment::Environment;
 use crate::exact::ExactNum;
+use crate::extension::ParserExtensions;
 use crate::functions::FUNCTION_REGISTRY;
-use crate::node::Node;
+use crate::node::{LimitDirection, Node};
 use crate::simplify::Simplifiable;
+use crate::tokenizer::{
+    is_history_ref, is_subscripted_identifier, MATRIX_BEGIN_PREFIX, MATRIX_END_PREFIX, MATRIX_ROW,
+    OPERATOR_PREFIX, VECTOR_PREFIX,
+};
+use crate::vector;
 use num_bigint::BigInt;
 use num_rational::BigRational;
+use std::fmt;
+use std::ops::Range;
+
+/// A parse failure, located in the token stream and, when parsed from
+/// LaTeX, in the source text. `Display` gives the bare message;
+/// [`ParseError::render`] adds the offending input with a caret.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    /// Index of the offending token, or the token count for an unexpected
+    /// end of input. `None` for errors raised by the tokenizer.
+    pub token_index: Option<usize>,
+    /// Byte range of the offending input within `source`
+    pub span: Option<Range<usize>>,
+    /// The LaTeX being parsed, when the tokens came from a string
+    pub source: Option<String>,
+    /// Set when the input was rejected for exceeding a [`ParserOptions`]
+    /// limit rather than for being malformed
+    pub limit: Option<ParseLimit>,
+}
 
-pub fn shunting_yard(tokens: Vec<String>) -> Result<Vec<String>, String> {
-    log::debug!("Starting Shunting Yard with tokens: {:?}", tokens);
+/// Which [`ParserOptions`] limit an input exceeded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseLimit {
+    Tokens,
+    BraceDepth,
+    FunctionNesting,
+    ExpressionDepth,
+}
 
-    let mut output_queue: Vec<String> = Vec::new();
-    let mut operator_stack: Vec<String> = Vec::new();
+/// Bounds on the input a parse will accept. Inputs past a bound are
+/// rejected with a [`ParseError`] carrying the [`ParseLimit`], before the
+/// recursive descent can exhaust the stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Most tokens after tokenizing
+    pub max_tokens: usize,
+    /// Deepest nesting of `(`, `{`, `|…|`, `\lfloor` and `\lceil` groups;
+    /// checked over the tokens before parsing starts
+    pub max_brace_depth: usize,
+    /// Deepest nesting of function calls inside function arguments
+    pub max_function_nesting: usize,
+    /// Deepest recursion of the grammar: each group, function argument,
+    /// unary minus or right-nested `^` is one level
+    pub max_expression_depth: usize,
+}
 
-    for token in tokens {
-        log::debug!("Processing token: {}", token);
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            max_tokens: 10_000,
+            max_brace_depth: 64,
+            max_function_nesting: 32,
+            max_expression_depth: 100,
+        }
+    }
+}
 
-        if token.parse::<f64>().is_ok()
-            && token.starts_with(|c: char| c.is_ascii_digit() || c == '.')
-        {
-            log::debug!("Token is a number: {}", token);
-            output_queue.push(token);
-        } else if token.starts_with(INDEXED_ATOM_PREFIX) {
-            // Pre-parsed \sum/\prod atom: a plain operand.
-            output_queue.push(token);
-        } else if token == "NEG" {
-            log::debug!("Unary minus detected, pushing to operator stack");
-            operator_stack.push(token);
-        } else if token == "FACT" {
-            output_queue.push(token);
-        } else if token == "ABS_START" {
-            operator_stack.push(token);
-        } else if token == "ABS_END" {
-            while let Some(op) = operator_stack.pop() {
-                if op == "ABS_START" {
-                    break;
-                }
-                output_queue.push(op);
-            }
-            output_queue.push("ABS".to_string());
-        } else if token == "FLOOR_START" {
-            operator_stack.push(token);
-        } else if token == "FLOOR_END" {
-            while let Some(op) = operator_stack.pop() {
-                if op == "FLOOR_START" {
-                    break;
-                }
-                output_queue.push(op);
-            }
-            output_queue.push("FLOOR".to_string());
-        } else if token == "CEIL_START" {
-            operator_stack.push(token);
-        } else if token == "CEIL_END" {
-            while let Some(op) = operator_stack.pop() {
-                if op == "CEIL_START" {
-                    break;
-                }
-                output_queue.push(op);
-            }
-            output_queue.push("CEIL".to_string());
-        } else if token == ">"
-            || token == "<"
-            || token == ">="
-            || token == "<="
-            || token == "=="
-            || token == "="
-            || "+-*/^".contains(token.as_str())
-        {
-            while let Some(top) = operator_stack.last() {
-                if get_precedence(top) >= get_precedence(&token) {
-                    output_queue.push(operator_stack.pop().unwrap());
-                } else {
-                    break;
-                }
-            }
-            operator_stack.push(token);
-        } else if token == "(" || token == "{" {
-            operator_stack.push(token);
-        } else if token == ")" || token == "}" {
-            while let Some(top) = operator_stack.pop() {
-                if top == "(" || top == "{" {
-                    break;
-                }
-                output_queue.push(top);
-            }
-            if let Some(top) = operator_stack.pop_if(|top| FUNCTION_REGISTRY.get(top).is_some()) {
-                output_queue.push(top);
-            }
-        } else if let Some(_function) = FUNCTION_REGISTRY.get(&token) {
-            log::debug!("Function detected: {}", token);
-            operator_stack.push(token);
-        } else if token.chars().all(|c| c.is_alphabetic()) {
-            log::debug!("Variable detected: {}", token);
-            output_queue.push(token);
-        } else {
-            return Err(format!("Unknown token '{}'", token));
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ParseError {
+            message: message.into(),
+            token_index: None,
+            span: None,
+            source: None,
+            limit: None,
         }
+    }
 
-        log::debug!("Current output queue: {:?}", output_queue);
-        log::debug!("Current operator stack: {:?}", operator_stack);
+    fn over_limit(limit: ParseLimit, message: impl Into<String>, index: usize) -> Self {
+        ParseError {
+            limit: Some(limit),
+            ..ParseError::at_token(message, index)
+        }
     }
 
-    // Pop all remaining operators to the output queue
-    while let Some(op) = operator_stack.pop() {
-        if op == "(" || op == ")" || op == "{" || op == "}" {
-            return Err("Mismatched parentheses or braces".to_string());
+    fn at_token(message: impl Into<String>, index: usize) -> Self {
+        ParseError {
+            token_index: Some(index),
+            ..ParseError::new(message)
         }
-        output_queue.push(op);
     }
 
-    log::debug!("Final RPN output: {:?}", output_queue);
-    Ok(output_queue)
-}
+    fn context(mut self, context: impl fmt::Display) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
 
-pub(crate) fn get_precedence(op: &str) -> i32 {
-    match op {
-        "^" => 5,                            // Exponentiation
-        "NEG" => 4,                          // Unary minus (binds tighter than *, looser than ^)
-        "*" | "/" => 3,                      // Multiplication and Division
-        "+" | "-" => 2,                      // Addition and Subtraction
-        ">" | "<" | ">=" | "<=" | "==" => 1, // Inequality operators
-        "=" => 0,                            // Equation has lowest precedence
-        _ => 0,
+    /// Attach the source, resolving the token index to the byte range
+    /// the tokenizer recorded for it (the end of the input past the last
+    /// token).
+    fn locate(mut self, source: &str, spans: &[Range<usize>]) -> Self {
+        if self.span.is_none() {
+            self.span = self.token_index.map(|index| {
+                spans
+                    .get(index)
+                    .cloned()
+                    .unwrap_or(source.len()..source.len())
+            });
+        }
+        self.source = Some(source.to_string());
+        self
     }
-}
 
-/// Marks a pre-parsed `\sum`/`\prod` atom in the token stream. A private-use
-/// character the tokenizer can never emit, so placeholders cannot collide
-/// with user input.
-const INDEXED_ATOM_PREFIX: char = '\u{E000}';
+    /// The span in characters rather than bytes, for display columns.
+    pub fn char_span(&self) -> Option<Range<usize>> {
+        let (source, span) = (self.source.as_deref()?, self.span.clone()?);
+        let start = source[..span.start].chars().count();
+        Some(start..start + source[span].chars().count())
+    }
 
-pub fn build_expression_tree(tokens: Vec<String>) -> Result<Node, String> {
-    let mut indexed_atoms: Vec<Node> = Vec::new();
-    build_expression_tree_inner(tokens, &mut indexed_atoms)
+    /// The message followed by the input with the offending part marked:
+    ///
+    /// ```text
+    /// Unknown token '_'
+    ///   x + _
+    ///       ^
+    /// ```
+    pub fn render(&self) -> String {
+        match (&self.source, self.char_span()) {
+            (Some(source), Some(span)) => format!(
+                "{}\n  {}\n  {}{}",
+                self.message,
+                source,
+                " ".repeat(span.start),
+                "^".repeat(span.len().max(1))
+            ),
+            _ => self.message.clone(),
+        }
+    }
 }
 
-fn build_expression_tree_inner(
-    tokens: Vec<String>,
-    indexed_atoms: &mut Vec<Node>,
-) -> Result<Node, String> {
-    log::debug!("Building expression tree from tokens: {:?}", tokens);
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
 
-    // \sum and \prod parse as expression ATOMS: each construct is parsed
-    // into a Node here and its token span replaced by a placeholder
-    // operand, so indexed notation composes with the surrounding grammar
-    // (2·Σk, 1 + Σk², Σ − Σ, nested sums). Rightmost-first, so inner
-    // constructs resolve before the outer ones whose bodies contain them.
-    // (Previously a whole-expression special case silently discarded
-    // everything around the Σ/Π — the worst failure class for a CAS.)
-    let mut tokens = tokens;
-    while let Some(pos) = tokens.iter().rposition(|t| t == "sum" || t == "prod") {
-        let kind = if tokens[pos] == "sum" {
-            IndexedNotation::Sum
-        } else {
-            IndexedNotation::Prod
-        };
-        let (node, end) = parse_indexed_at(&tokens, pos, kind, indexed_atoms)?;
-        let placeholder = format!("{}{}", INDEXED_ATOM_PREFIX, indexed_atoms.len());
-        indexed_atoms.push(node);
-        tokens.splice(pos..end, [placeholder]);
-    }
-
-    // Argument-separator commas have done their tokenizer-side job
-    // (opening a unary-minus context so \max(2, -1) keeps its sign); the
-    // expression builder separates arguments by operand adjacency, so the
-    // separator itself is dropped here.
-    let tokens: Vec<String> = tokens.into_iter().filter(|t| t != ",").collect();
-
-    let rpn = shunting_yard(tokens)?;
-
-    let mut stack: Vec<Node> = Vec::new();
-
-    for token in rpn {
-        log::debug!("Processing token: {}", token);
-
-        if let Some(idx_str) = token.strip_prefix(INDEXED_ATOM_PREFIX) {
-            let atom = idx_str
-                .parse::<usize>()
-                .ok()
-                .and_then(|idx| indexed_atoms.get(idx).cloned())
-                .ok_or_else(|| "Invalid indexed-notation placeholder".to_string())?;
-            stack.push(atom);
-        } else if token.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
-            if !token.contains('.') {
-                if let Ok(n) = token.parse::<BigInt>() {
-                    log::debug!("Pushing integer: {}", n);
-                    stack.push(Node::Num(ExactNum::Rational(BigRational::from_integer(n))));
-                } else if let Ok(num) = token.parse::<f64>() {
-                    stack.push(Node::Num(ExactNum::from_f64(num)));
-                }
-            } else if let Ok(num) = token.parse::<f64>() {
-                log::debug!("Pushing number: {}", num);
-                stack.push(Node::Num(ExactNum::from_f64(num)));
-            }
-        } else if token == "ABS" {
-            let operand = stack
-                .pop()
-                .ok_or_else(|| "Not enough operands for ABS".to_string())?;
-            stack.push(Node::Abs(Box::new(operand))); // Handle absolute value
-        } else if token == "FLOOR" {
-            let operand = stack
-                .pop()
-                .ok_or_else(|| "Not enough operands for floor".to_string())?;
-            stack.push(Node::Floor(Box::new(operand)));
-        } else if token == "CEIL" {
-            let operand = stack
-                .pop()
-                .ok_or_else(|| "Not enough operands for ceil".to_string())?;
-            stack.push(Node::Ceil(Box::new(operand)));
-        } else if token == "NEG" {
-            // Handle unary minus by applying it to the top of the stack
-            let operand = stack
-                .pop()
-                .ok_or_else(|| "Not enough operands for unary minus".to_string())?;
-            stack.push(Node::Negate(Box::new(operand)));
-        } else if token == "FACT" {
-            let operand = stack
-                .pop()
-                .ok_or_else(|| "Not enough operands for factorial".to_string())?;
-            stack.push(Node::Factorial(Box::new(operand)));
-        } else if "+-*/^".contains(&token) {
-            // Binary operators require two operands
-            let right = stack
-                .pop()
-                .ok_or_else(|| format!("Not enough operands for operator '{}'", token))?;
-            let left = stack
-                .pop()
-                .ok_or_else(|| format!("Not enough operands for operator '{}'", token))?;
-
-            let node = match token.as_str() {
-                "+" => Node::Add(Box::new(left), Box::new(right)),
-                "-" => Node::Subtract(Box::new(left), Box::new(right)),
-                "*" => Node::Multiply(Box::new(left), Box::new(right)),
-                "/" => Node::Divide(Box::new(left), Box::new(right)),
-                "^" => Node::Power(Box::new(left), Box::new(right)),
-                _ => return Err(format!("Unknown operator '{}'", token)),
-            };
+impl std::error::Error for ParseError {}
 
-            log::debug!("Pushing node: {:?}", node);
-            stack.push(node);
-        } else if token == ">"
-            || token == "<"
-            || token == ">="
-            || token == "<="
-            || token == "=="
-            || token == "="
-        {
-            let right = stack
-                .pop()
-                .ok_or_else(|| format!("Not enough operands for operator '{}'", token))?;
-            let left = stack
-                .pop()
-                .ok_or_else(|| format!("Not enough operands for operator '{}'", token))?;
-
-            let node = match token.as_str() {
-                ">" => Node::Greater(Box::new(left), Box::new(right)),
-                "<" => Node::Less(Box::new(left), Box::new(right)),
-                ">=" => Node::GreaterEqual(Box::new(left), Box::new(right)),
-                "<=" => Node::LessEqual(Box::new(left), Box::new(right)),
-                "==" => Node::Equal(Box::new(left), Box::new(right)), // For equality comparison
-                "=" => Node::Equation(Box::new(left), Box::new(right)), // For equation
-                _ => return Err(format!("Unknown operator '{}'", token)),
-            };
+impl From<ParseError> for String {
+    fn from(error: ParseError) -> String {
+        error.message
+    }
+}
 
-            stack.push(node);
-        } else if let Some(function) = FUNCTION_REGISTRY.get(&token) {
-            let arg_count = function.get_arg_count();
-
-            if let Some(count) = arg_count {
-                // Fixed-argument function
-                let mut args = Vec::new();
-                for _ in 0..count {
-                    let arg = stack
-                        .pop()
-                        .ok_or_else(|| format!("Not enough operands for function {}", token))?;
-                    args.push(arg);
-                }
-                args.reverse();
-                stack.push(Node::Function(token.clone(), args));
-            } else {
-                // Variable-argument function: collect all remaining stack items as arguments
-                let mut args: Vec<Node> = std::mem::take(&mut stack);
-                args.reverse();
-                stack.push(Node::Function(token.clone(), args));
-            }
-        } else if token.chars().all(|c| c.is_alphabetic()) {
-            // Handle variables directly (e.g., `x`, `y`)
-            if token == "e" || token == "EULER" {
-                stack.push(Node::Variable("e".to_string()));
-            } else if token == "\\pi" || token == "PI" || token == "π" {
-                stack.push(Node::Variable("π".to_string()));
-            } else {
-                log::debug!("Pushing variable: {}", token);
-                stack.push(Node::Variable(token));
-            }
-        } else {
-            return Err(format!("Unknown token '{}'", token));
-        }
+// Binding powers, loosest first. Each infix operator has a left and a
+// right power; left-associative operators bind their right operand one
+// step tighter, `^` (right-associative) one step looser. Unary minus sits
+// between products and powers: `-x^2` is −(x²) but `-2x` is (−2)·x.
+// Logical not takes a whole comparison: `\lnot x > 0` is ¬(x > 0).
+const EQUATION_BP: (u8, u8) = (1, 2);
+const OR_BP: (u8, u8) = (3, 4);
+const AND_BP: (u8, u8) = (5, 6);
+const NOT_BP: u8 = 7;
+const RELATION_BP: (u8, u8) = (9, 10);
+const SUM_BP: (u8, u8) = (11, 12);
+const PRODUCT_BP: (u8, u8) = (13, 14);
+const NEGATION_BP: u8 = 15;
+const POWER_BP: (u8, u8) = (17, 16);
+const FACTORIAL_BP: u8 = 19;
+
+fn infix_binding_power(token: &str) -> Option<(u8, u8)> {
+    match token {
+        "=" => Some(EQUATION_BP),
+        "||" => Some(OR_BP),
+        "&&" => Some(AND_BP),
+        ">" | "<" | ">=" | "<=" | "==" => Some(RELATION_BP),
+        "+" | "-" => Some(SUM_BP),
+        "*" | "CROSS" | "MOD" | "/" => Some(PRODUCT_BP),
+        "^" => Some(POWER_BP),
+        _ => None,
+    }
+}
 
-        log::debug!("Current stack state: {:?}", stack);
+/// An explicit `\cdot` or `\times` between two vectors is their dot or
+/// cross product; anything else is [`binary_node`].
+fn product_node(op: &str, left: Node, right: Node) -> Node {
+    let product = match op {
+        "*" => vector::DOT,
+        "CROSS" => vector::CROSS,
+        _ => return binary_node(op, left, right),
+    };
+    if vector::is_vector_expression(&left) && vector::is_vector_expression(&right) {
+        Node::Function(product.to_string(), vec![left, right])
+    } else {
+        binary_node(op, left, right)
     }
+}
 
-    // The final expression tree should be a single node on the stack
-    if stack.len() != 1 {
-        return Err("The expression did not resolve into a single tree.".to_string());
+fn binary_node(op: &str, left: Node, right: Node) -> Node {
+    let (l, r) = (Box::new(left), Box::new(right));
+    match op {
+        "=" => Node::Equation(l, r),
+        ">" => Node::Greater(l, r),
+        "<" => Node::Less(l, r),
+        ">=" => Node::GreaterEqual(l, r),
+        "<=" => Node::LessEqual(l, r),
+        "==" => Node::Equal(l, r),
+        "&&" => Node::And(l, r),
+        "||" => Node::Or(l, r),
+        "+" => Node::Add(l, r),
+        "-" => Node::Subtract(l, r),
+        "*" | "CROSS" => Node::Multiply(l, r),
+        "/" => Node::Divide(l, r),
+        "^" => Node::Power(l, r),
+        "MOD" => Node::Function("mod".to_string(), vec![*l, *r]),
+        _ => unreachable!("not an infix operator: {op}"),
     }
+}
 
-    log::debug!("Final expression tree: {:?}", stack[0]);
-    Ok(stack.pop().unwrap())
+fn is_number_token(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit() || c == '.')
 }
 
-/// Parse a LaTeX expression string into a Node AST (no simplification)
-pub fn parse_latex_raw(latex: &str) -> Result<Node, String> {
-    let mut tokenizer = crate::tokenizer::Tokenizer::new(latex);
-    let tokens = tokenizer.tokenize();
-    if let Some(err) = tokenizer.errors.into_iter().next() {
-        return Err(err);
-    }
-    build_expression_tree(tokens)
+fn is_identifier_token(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_alphabetic())
+        || is_subscripted_identifier(token)
+        || is_history_ref(token)
 }
 
-/// Parse a LaTeX expression string into a Node AST and simplify using the environment.
-/// If simplification fails, returns the parsed (unsimplified) expression.
-pub fn parse_latex(latex: &str, env: &crate::environment::Environment) -> Result<Node, String> {
-    let expr = parse_latex_raw(latex)?;
-    Ok(expr.simplify(env).unwrap_or(expr))
+/// Tokens that, directly after a complete operand, continue it as an
+/// implicit product (`x^{2}y`). A bare number does not: `2 3` is an error
+/// rather than 6.
+fn continues_product(token: &str) -> bool {
+    matches!(
+        token,
+        "(" | "{"
+            | "ABS_START"
+            | "FLOOR_START"
+            | "CEIL_START"
+            | "sum"
+            | "prod"
+            | "int"
+            | "lim"
+            | "DERIV"
+    ) || token.starts_with(MATRIX_BEGIN_PREFIX)
+        || token.starts_with(VECTOR_PREFIX)
+        || token.starts_with(OPERATOR_PREFIX)
+        || is_identifier_token(token)
 }
 
-enum IndexedNotation {
-    Sum,
-    Prod,
+/// Tokens that begin a self-delimiting construct which ends an
+/// unbracketed function argument: `\sin x \cos x` is sin(x)·cos(x).
+fn starts_new_factor(token: &str) -> bool {
+    FUNCTION_REGISTRY.get(token).is_some()
+        || matches!(token, "sum" | "prod" | "int" | "lim" | "DERIV")
 }
 
-/// Parse indexed notation like \sum_{i=1}^{n} i or \prod_{k=1}^{n} k.
-/// Parse one `\sum`/`\prod` construct beginning at `op_pos`. Returns the
-/// node and the index one past the last token the construct consumed, so
-/// the caller can splice the span out and let the surrounding expression
-/// grammar see the construct as a single operand. Bounds and body parse
-/// through `build_expression_tree_inner` with the shared atom table, so
-/// nested constructs (already replaced by placeholders) resolve correctly.
-fn parse_indexed_at(
-    tokens: &[String],
-    op_pos: usize,
-    kind: IndexedNotation,
-    indexed_atoms: &mut Vec<Node>,
-) -> Result<(Node, usize), String> {
-    let (op_token, op_label) = match kind {
-        IndexedNotation::Sum => ("sum", "summation"),
-        IndexedNotation::Prod => ("prod", "product"),
+/// The differential closing an integrand at `tokens[i]`: `dx` as one
+/// token, or `d` followed by the variable (`d x`, `\mathrm{d}x`, which
+/// the tokenizer joins with an implicit `*`). Returns the token count and
+/// the variable.
+fn differential_at(tokens: &[String], i: usize) -> Option<(usize, String)> {
+    let is_variable = |t: &str| t.chars().count() == 1 && is_identifier_token(t);
+    let token = tokens[i].as_str();
+    if let Some(var) = token.strip_prefix('d').filter(|v| is_variable(v)) {
+        return Some((1, var.to_string()));
+    }
+    if token != "d" {
+        return None;
+    }
+    match tokens.get(i + 1).map(String::as_str) {
+        Some("*") => tokens
+            .get(i + 2)
+            .filter(|v| is_variable(v))
+            .map(|v| (3, v.clone())),
+        Some(v) if is_variable(v) => Some((2, v.to_string())),
+        _ => None,
+    }
+}
+
+/// Where a limit point ends and which side it approaches from: a
+/// trailing `^+`, `^-`, `^{+}` or `^{-}` marks a one-sided limit.
+fn one_sided_suffix(point: &[String]) -> (usize, LimitDirection) {
+    let tail: Vec<&str> = point
+        .iter()
+        .rev()
+        .take(4)
+        .rev()
+        .map(String::as_str)
+        .collect();
+    let side = |sign: &str| match sign {
+        "+" => Some(LimitDirection::Right),
+        "-" | "NEG" => Some(LimitDirection::Left),
+        _ => None,
     };
-    debug_assert_eq!(tokens[op_pos], op_token);
+    match tail.as_slice() {
+        [.., "^", "{", sign, "}"] => side(sign).map(|d| (point.len() - 4, d)),
+        [.., "^", sign] => side(sign).map(|d| (point.len() - 2, d)),
+        _ => None,
+    }
+    .unwrap_or((point.len(), LimitDirection::Both))
+}
 
-    // Check for underscore after operator
-    if op_pos + 1 >= tokens.len() || tokens[op_pos + 1] != "_" {
-        return Err(format!("Expected '_' after '{op_token}'"));
+fn closing_delimiter(open: &str) -> &'static str {
+    match open {
+        "(" => ")",
+        "{" => "}",
+        "ABS_START" => "ABS_END",
+        "FLOOR_START" => "FLOOR_END",
+        "CEIL_START" => "CEIL_END",
+        _ => unreachable!("not an opening delimiter: {open}"),
     }
+}
+
+/// Pratt parser over the tokenizer's output. Every construct — groups,
+/// bars, function calls, `\sum`/`\prod`/`\int`, determinants, and applications
+/// of functions defined in the environment — is parsed in place by the
+/// grammar, so each composes with whatever surrounds it.
+struct Parser<'a> {
+    tokens: &'a [String],
+    pos: usize,
+    env: Option<&'a Environment>,
+    options: ParserOptions,
+    expression_depth: usize,
+    function_nesting: usize,
+    extensions: Option<&'a ParserExtensions>,
+    /// The LaTeX the tokens were read from and their spans, which tell
+    /// commands (`\ce`) from identifiers (`ce`)
+    source: Option<(&'a str, &'a [Range<usize>])>,
+}
 
-    // Check for opening brace for lower bound
-    if op_pos + 2 >= tokens.len() || tokens[op_pos + 2] != "{" {
-        return Err("Expected '{' after '_'".to_string());
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [String], env: Option<&'a Environment>, options: ParserOptions) -> Self {
+        Parser {
+            tokens,
+            pos: 0,
+            env,
+            options,
+            expression_depth: 0,
+            function_nesting: 0,
+            extensions: None,
+            source: None,
+        }
     }
 
-    // Extract the index variable and starting value
-    // Format is typically: i = 1
-    let mut lower_bound_tokens = Vec::new();
-    let mut i = op_pos + 3;
-    let mut brace_count = 1;
+    fn with_source(mut self, latex: &'a str, spans: &'a [Range<usize>]) -> Self {
+        self.source = Some((latex, spans));
+        self
+    }
 
-    // Extract the index variable
-    let index_var = if i < tokens.len() && tokens[i].chars().all(|c| c.is_alphabetic()) {
-        let var = tokens[i].clone();
-        i += 1;
-        var
-    } else {
-        return Err("Expected index variable after '{'".to_string());
-    };
+    fn with_extensions(mut self, extensions: Option<&'a ParserExtensions>) -> Self {
+        self.extensions = extensions.filter(|extensions| !extensions.is_empty());
+        self
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&'a str> {
+        self.tokens.get(self.pos + offset).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, token: &str, error: impl FnOnce() -> String) -> Result<(), ParseError> {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(error()))
+        }
+    }
+
+    /// An error at the current token (the end of input if none is left).
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::at_token(message, self.pos)
+    }
+
+    /// An error at the token just consumed.
+    fn error_at_previous(&self, message: impl Into<String>) -> ParseError {
+        ParseError::at_token(message, self.pos.saturating_sub(1))
+    }
+
+    /// Parse the whole token stream as one expression.
+    fn parse_complete(&mut self) -> Result<Node, ParseError> {
+        if self.tokens.is_empty() {
+            return Err(self.error("The expression did not resolve into a single tree."));
+        }
+        let node = self.parse_expression(0)?;
+        match self.peek() {
+            None => Ok(node),
+            Some(")" | "}") => Err(self.error("Mismatched parentheses or braces")),
+            Some(",") => Err(self.error("Unexpected ',' outside a function's argument list")),
+            Some(_) => Err(self.error(format!("Unexpected token '{}'", self.written(self.pos)))),
+        }
+    }
+
+    /// Parse operators binding at least as tightly as `min_bp`.
+    fn parse_expression(&mut self, min_bp: u8) -> Result<Node, ParseError> {
+        self.parse_expression_until(min_bp, false)
+    }
 
-    // Expect equals sign
-    if i >= tokens.len() || tokens[i] != "=" {
-        return Err("Expected '=' after index variable".to_string());
+    /// With `stop_at_new_factor`, a product stops before a function or
+    /// big operator: the unbracketed argument of `\sin x \cos x`.
+    fn parse_expression_until(
+        &mut self,
+        min_bp: u8,
+        stop_at_new_factor: bool,
+    ) -> Result<Node, ParseError> {
+        if self.expression_depth >= self.options.max_expression_depth {
+            return Err(ParseError::over_limit(
+                ParseLimit::ExpressionDepth,
+                format!(
+                    "Expression nested more than {} levels deep",
+                    self.options.max_expression_depth
+                ),
+                self.pos,
+            ));
+        }
+        self.expression_depth += 1;
+        let result = self.parse_operators(min_bp, stop_at_new_factor);
+        self.expression_depth -= 1;
+        result
     }
-    i += 1;
 
-    // Extract the start value
-    while i < tokens.len() && brace_count > 0 {
-        if tokens[i] == "{" {
-            brace_count += 1;
-        } else if tokens[i] == "}" {
-            brace_count -= 1;
-            if brace_count == 0 {
+    fn parse_operators(
+        &mut self,
+        min_bp: u8,
+        stop_at_new_factor: bool,
+    ) -> Result<Node, ParseError> {
+        let mut lhs = self.parse_prefix()?;
+        while let Some(token) = self.peek() {
+            if token == "FACT" {
+                if FACTORIAL_BP < min_bp {
+                    break;
+                }
+                self.pos += 1;
+                lhs = Node::Factorial(Box::new(lhs));
+                continue;
+            }
+
+            let (op, (l_bp, r_bp), explicit) = match infix_binding_power(token) {
+                Some(bp) => (token, bp, true),
+                None if continues_product(token) => ("*", PRODUCT_BP, false),
+                None => break,
+            };
+            if l_bp < min_bp {
                 break;
             }
+            if matches!(op, "*" | "CROSS") && stop_at_new_factor {
+                let factor = if explicit {
+                    self.peek_at(1)
+                } else {
+                    Some(token)
+                };
+                if factor.is_some_and(starts_new_factor) {
+                    break;
+                }
+            }
+            if explicit {
+                self.pos += 1;
+            }
+            if matches!(self.peek(), None | Some(")" | "}" | ",")) {
+                return Err(self.error(format!("Not enough operands for operator '{}'", op)));
+            }
+            let rhs = self.parse_expression(r_bp)?;
+            lhs = match explicit {
+                true => product_node(op, lhs, rhs),
+                false => binary_node(op, lhs, rhs),
+            };
         }
-        lower_bound_tokens.push(tokens[i].clone());
-        i += 1;
+        Ok(lhs)
     }
 
-    if brace_count > 0 {
-        return Err("Unclosed lower bound brace".to_string());
+    fn parse_prefix(&mut self) -> Result<Node, ParseError> {
+        let Some(token) = self.next() else {
+            return Err(self.error("Unexpected end of expression"));
+        };
+        log::debug!("Parsing operand starting at: {}", token);
+        if let Some(parsed) = self.parse_extension_command(token) {
+            return parsed;
+        }
+
+        match token {
+            "NEG" => {
+                if self.peek().is_none() {
+                    return Err(self.error("Not enough operands for unary minus"));
+                }
+                let operand = self.parse_expression(NEGATION_BP)?;
+                Ok(Node::Negate(Box::new(operand)))
+            }
+            "NOT" => {
+                if self.peek().is_none() {
+                    return Err(self.error("Not enough operands for logical not"));
+                }
+                let operand = self.parse_expression(NOT_BP)?;
+                Ok(Node::Not(Box::new(operand)))
+            }
+            "(" | "{" => {
+                let closer = closing_delimiter(token);
+                if self.peek() == Some(closer) {
+                    // A synthesized group is a brace argument: `\frac{1}{}`
+                    let group = if self.source.is_some() && self.source_text(self.pos - 1).is_none()
+                    {
+                        "{}".to_string()
+                    } else {
+                        format!("{}{}", token, closer)
+                    };
+                    return Err(self.error(format!("Expected an expression inside '{}'", group)));
+                }
+                let inner = self.parse_expression(0)?;
+                self.expect(closer, || "Mismatched parentheses or braces".to_string())?;
+                Ok(inner)
+            }
+            "ABS_START" if self.barred_matrix_follows() => {
+                let begin = self.next().expect("matrix environment follows");
+                let node = self.parse_barred_matrix(&begin[MATRIX_BEGIN_PREFIX.len()..])?;
+                self.expect("ABS_END", || "Unclosed absolute value".to_string())?;
+                Ok(node)
+            }
+            "ABS_START" | "FLOOR_START" | "CEIL_START" => {
+                let inner = self.parse_expression(0)?;
+                let (node, name) = match token {
+                    "ABS_START" => (Node::Abs(Box::new(inner)), "absolute value"),
+                    "FLOOR_START" => (Node::Floor(Box::new(inner)), "\\lfloor"),
+                    _ => (Node::Ceil(Box::new(inner)), "\\lceil"),
+                };
+                self.expect(closing_delimiter(token), || format!("Unclosed {}", name))?;
+                Ok(node)
+            }
+            "sum" => self.parse_indexed(IndexedNotation::Sum),
+            "prod" => self.parse_indexed(IndexedNotation::Prod),
+            "int" => self.parse_integral(),
+            "lim" => self.parse_limit(),
+            "DERIV" => self.parse_derivative(),
+            "infty" => Ok(Node::Variable(crate::limits::INFINITY.to_string())),
+            "FACT" => Err(self.error_at_previous("Not enough operands for factorial")),
+            ")" | "}" => Err(self.error_at_previous("Mismatched parentheses or braces")),
+            // `\mod(a, b)`: the function form of `a \bmod b`
+            "MOD" if self.peek() == Some("(") => self.parse_function_call("mod"),
+            _ if infix_binding_power(token).is_some() => {
+                Err(self.error_at_previous(format!("Not enough operands for operator '{}'", token)))
+            }
+            _ if token.starts_with(MATRIX_BEGIN_PREFIX) => {
+                let env_name = &token[MATRIX_BEGIN_PREFIX.len()..];
+                if env_name != "vmatrix" {
+                    return self.parse_matrix_literal(env_name);
+                }
+                self.parse_determinant(env_name)
+            }
+            _ if token.starts_with(VECTOR_PREFIX) => Ok(Node::Function(
+                vector::VECTOR_NAME.to_string(),
+                vec![Node::Variable(token[VECTOR_PREFIX.len()..].to_string())],
+            )),
+            _ if token.starts_with(OPERATOR_PREFIX) => {
+                self.parse_operator_call(&token[OPERATOR_PREFIX.len()..])
+            }
+            _ if is_number_token(token) => {
+                parse_number(token).map_err(|e| self.error_at_previous(e))
+            }
+            _ if FUNCTION_REGISTRY.get(token).is_some() => self.parse_function_call(token),
+            _ if is_identifier_token(token) => self.parse_identifier(token),
+            _ => {
+                Err(self
+                    .error_at_previous(format!("Unknown token '{}'", self.written(self.pos - 1))))
+            }
+        }
     }
 
-    // Check for caret after lower bound
-    i += 1; // Move past closing brace
-    if i >= tokens.len() || tokens[i] != "^" {
-        return Err("Expected '^' after lower bound".to_string());
+    /// The command just read, parsed by the first extension that takes it.
+    fn parse_extension_command(&mut self, token: &'a str) -> Option<Result<Node, ParseError>> {
+        let extensions = self.extensions?;
+        if !self.is_command(self.pos - 1, token) {
+            return None;
+        }
+        let mut input = CommandInput {
+            parser: self,
+            command: token,
+        };
+        extensions.parse_command(token, &mut input)
     }
-    i += 1;
 
-    // Check for opening brace for upper bound
-    // Some users might omit braces for single-token values (like \sum_{i=1}^3)
-    let has_upper_brace = i < tokens.len() && tokens[i] == "{";
-    if has_upper_brace {
-        i += 1;
-    } else if i >= tokens.len() {
-        return Err("Expected upper bound after '^'".to_string());
+    /// `tokens[index]` as it was written, for messages: `\\` rather than
+    /// the row separator the tokenizer turns it into.
+    fn written(&self, index: usize) -> &'a str {
+        self.source_text(index)
+            .unwrap_or(self.tokens[index].as_str())
     }
 
-    // Extract upper bound tokens
-    let mut upper_bound_tokens = Vec::new();
+    /// The input `tokens[index]` was read from, when it has a span of its
+    /// own. Tokens synthesized from a larger construct (`\frac{a}{b}` →
+    /// `( a ) / ( b )`) share its span and have none; an implicit `*`
+    /// shares the span of the operand it was inserted before, which keeps
+    /// its own.
+    fn source_text(&self, index: usize) -> Option<&'a str> {
+        let (latex, spans) = self.source?;
+        let span = spans.get(index)?;
+        let implicit = |i: usize| self.tokens[i] == "*" && i != index;
+        let shares_span = |other: Option<usize>| {
+            other.is_some_and(|i| spans.get(i) == Some(span) && !implicit(i))
+        };
+        if shares_span(index.checked_sub(1)) || shares_span(Some(index + 1)) {
+            None
+        } else {
+            Some(&latex[span.clone()])
+        }
+    }
 
-    if has_upper_brace {
-        // If we have braces, collect all tokens until the closing brace
-        brace_count = 1;
+    /// Whether `tokens[index]` was written as `\token`. Without the source
+    /// every token may be a command.
+    fn is_command(&self, index: usize, token: &str) -> bool {
+        let Some((latex, spans)) = self.source else {
+            return true;
+        };
+        spans
+            .get(index)
+            .and_then(|span| latex[span.start..].strip_prefix('\\'))
+            .and_then(|rest| rest.strip_prefix(token))
+            .is_some_and(|rest| !rest.starts_with(char::is_alphabetic))
+    }
 
-        while i < tokens.len() && brace_count > 0 {
-            if tokens[i] == "{" {
-                brace_count += 1;
-            } else if tokens[i] == "}" {
-                brace_count -= 1;
-                if brace_count == 0 {
-                    break;
+    /// Arguments of a built-in function: a delimited, comma-separated list
+    /// (`\gcd(4, 6)`), successive brace groups up to the arity
+    /// (`\binom{n}{k}`), or, without delimiters, the product that follows
+    /// (`\sin 2x`, `\ln|x|`).
+    fn parse_function_call(&mut self, name: &str) -> Result<Node, ParseError> {
+        self.nested_call(|parser| parser.parse_function_arguments(name))
+    }
+
+    /// `\operatorname{name}` (or a registered function's `name`) applied to
+    /// its arguments: with the arity of a function of that name defined in
+    /// the environment, otherwise like a built-in function, checking the
+    /// arity of a registered one. Whether `name` is defined is only checked
+    /// at evaluation.
+    fn parse_operator_call(&mut self, name: &str) -> Result<Node, ParseError> {
+        if let Some(function) = self.env.and_then(|env| env.get_function(name)) {
+            let arity = function.params.len();
+            return self.nested_call(|parser| parser.parse_application(name, arity));
+        }
+        let name_index = self.pos - 1;
+        if self.next_is_implicit_call() {
+            self.pos += 1;
+        }
+        let arity = self
+            .env
+            .and_then(|env| env.registered_function(name))
+            .and_then(|function| function.get_arg_count());
+        let call = self.parse_function_call(name)?;
+        match &call {
+            Node::Function(_, args) if args.is_empty() => Err(self.error_at_previous(format!(
+                "\\operatorname{{{name}}} needs an argument, as \\operatorname{{{name}}}(x)"
+            ))),
+            Node::Function(_, args) if arity.is_some_and(|n| n != args.len()) => {
+                Err(ParseError::at_token(
+                    format!(
+                        "{name} takes {} argument(s) but {} were given",
+                        arity.unwrap_or_default(),
+                        args.len()
+                    ),
+                    name_index,
+                ))
+            }
+            _ => Ok(call),
+        }
+    }
+
+    /// Whether the next tokens are the `*` the tokenizer put before a `(`.
+    fn next_is_implicit_call(&self) -> bool {
+        self.peek() == Some("*") && self.peek_at(1) == Some("(")
+    }
+
+    /// Run `parse` one function-nesting level deeper.
+    fn nested_call(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Node, ParseError>,
+    ) -> Result<Node, ParseError> {
+        if self.function_nesting >= self.options.max_function_nesting {
+            return Err(ParseError::over_limit(
+                ParseLimit::FunctionNesting,
+                format!(
+                    "Function calls nested more than {} deep",
+                    self.options.max_function_nesting
+                ),
+                self.pos.saturating_sub(1),
+            ));
+        }
+        self.function_nesting += 1;
+        let result = parse(self);
+        self.function_nesting -= 1;
+        result
+    }
+
+    fn parse_function_arguments(&mut self, name: &str) -> Result<Node, ParseError> {
+        let name_index = self.pos - 1;
+        let arity = FUNCTION_REGISTRY
+            .get(name)
+            .and_then(|function| function.get_arg_count());
+        let mut args = Vec::new();
+        match self.peek() {
+            Some("(" | "{") => {
+                self.parse_argument_list(name, &mut args)?;
+                while arity.is_some_and(|n| args.len() < n) && self.peek() == Some("{") {
+                    self.parse_argument_list(name, &mut args)?;
                 }
             }
-            upper_bound_tokens.push(tokens[i].clone());
-            i += 1;
+            _ if arity == Some(0) => {}
+            None => {}
+            Some(_) => args.push(self.parse_expression_until(PRODUCT_BP.0, true)?),
         }
 
-        if brace_count > 0 {
-            return Err("Unclosed upper bound brace".to_string());
+        match arity {
+            Some(n) if args.len() < n => Err(ParseError::at_token(
+                format!("Not enough operands for function {}", name),
+                name_index,
+            )),
+            Some(n) if args.len() > n => Err(ParseError::at_token(
+                format!("Too many arguments for function {}", name),
+                name_index,
+            )),
+            _ => Ok(Node::Function(name.to_string(), args)),
         }
-        i += 1; // Move past the closing brace
-    } else {
-        // If no braces, take just one token as the upper bound
-        upper_bound_tokens.push(tokens[i].clone());
-        i += 1;
-    }
-
-    // Extract the body expression (summand or multiplicand)
-    // The expression can be a single token or surrounded by braces
-    let mut body_tokens = Vec::new();
-
-    // If there's a brace, collect all tokens until closing brace
-    if i < tokens.len() && tokens[i] == "{" {
-        i += 1;
-        brace_count = 1;
-
-        while i < tokens.len() && brace_count > 0 {
-            if tokens[i] == "{" {
-                brace_count += 1;
-            } else if tokens[i] == "}" {
-                brace_count -= 1;
-                if brace_count == 0 {
-                    break;
+    }
+
+    fn parse_argument_list(&mut self, name: &str, args: &mut Vec<Node>) -> Result<(), ParseError> {
+        let open = self
+            .next()
+            .expect("caller checked for an opening delimiter");
+        let close = closing_delimiter(open);
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(());
+        }
+        let unclosed = || format!("Unclosed argument list in \\{name}: expected '{close}'");
+        loop {
+            match self.peek() {
+                None => return Err(self.error(unclosed())),
+                Some(t) if t == "," || t == close => {
+                    return Err(self.error(format!("Empty argument in \\{name}")))
+                }
+                _ => {}
+            }
+            args.push(self.parse_expression(0)?);
+            match self.next() {
+                Some(",") => continue,
+                Some(t) if t == close => return Ok(()),
+                None => return Err(self.error(unclosed())),
+                Some(_) => {
+                    return Err(self.error_at_previous(format!(
+                        "Mismatched parentheses or braces in \\{name}: expected ',' or '{close}'"
+                    )))
                 }
             }
-            body_tokens.push(tokens[i].clone());
-            i += 1;
         }
+    }
 
-        if brace_count > 0 {
-            return Err("Unclosed body brace".to_string());
+    fn parse_identifier(&mut self, token: &str) -> Result<Node, ParseError> {
+        if let Some(env) = self.env {
+            if let Some(function) = env.get_function(token) {
+                let arity = function.params.len();
+                return self.nested_call(|parser| parser.parse_application(token, arity));
+            }
+            if env.registered_function(token).is_some() && self.opens_call() {
+                return self.parse_operator_call(token);
+            }
+            if self.opens_call() {
+                if let Some(name) = env
+                    .function_names()
+                    .find(|name| token.len() > name.len() && token.ends_with(name))
+                {
+                    let prefix = &token[..token.len() - name.len()];
+                    return Err(self.error_at_previous(format!(
+                        "'{token}(…)' reads as the variable {token} times (…); \
+                         to apply the function {name}, write {prefix} \\cdot {name}(…)"
+                    )));
+                }
+            }
         }
-        // Move past the closing brace
-        if i < tokens.len() {
-            i += 1;
+
+        // \Gamma(x) is the gamma function; a bare Γ stays a variable.
+        if token == "Γ" && self.opens_call() {
+            if self.next_is_implicit_call() {
+                self.pos += 1;
+            }
+            return self.parse_function_call("Gamma");
         }
-    } else {
-        // If there's no brace, extract the indexed body with advanced balancing
-        parse_unbraced_indexed_body(&tokens[i..], &mut body_tokens, &mut i);
+
+        Ok(match Constant::from_word(token) {
+            Some(constant) => Node::Variable(constant.name().to_string()),
+            None => Node::Variable(token.to_string()),
+        })
+    }
+
+    /// Whether the next tokens open an argument list. The tokenizer reads
+    /// `f(` as `f·(`, so the implicit `*` is skipped too.
+    fn opens_call(&self) -> bool {
+        match self.peek() {
+            Some("(") => true,
+            Some("*") => self.peek_at(1) == Some("("),
+            _ => false,
+        }
+    }
+
+    /// `f(a, b)` for a function defined in the environment. A defined
+    /// name without parentheses and a wrong argument count are errors
+    /// rather than a silent fallback to multiplication.
+    fn parse_application(&mut self, name: &str, arity: usize) -> Result<Node, ParseError> {
+        let name_index = self.pos - 1;
+        if !self.opens_call() {
+            return Err(ParseError::at_token(
+                format!("'{name}' is a function defined in the environment; apply it with parentheses, as {name}(…)"),
+                name_index,
+            ));
+        }
+        if self.next() == Some("*") {
+            self.pos += 1;
+        }
+
+        let mut args = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error(format!("Unclosed parenthesis in {name}(…)"))),
+                Some("," | ")") => return Err(self.error(format!("Empty argument in {name}(…)"))),
+                _ => {}
+            }
+            args.push(self.parse_expression(0)?);
+            match self.next() {
+                Some(",") => continue,
+                Some(")") => break,
+                _ => return Err(self.error(format!("Unclosed parenthesis in {name}(…)"))),
+            }
+        }
+
+        if args.len() != arity {
+            return Err(ParseError::at_token(
+                format!(
+                    "{name} takes {arity} argument{} but {} {} given",
+                    if arity == 1 { "" } else { "s" },
+                    args.len(),
+                    if args.len() == 1 { "was" } else { "were" },
+                ),
+                name_index,
+            ));
+        }
+        Ok(Node::Function(name.to_string(), args))
     }
 
-    // Parse the start, end, and body expressions with better error handling
-    let start_expr = build_expression_tree_inner(lower_bound_tokens, indexed_atoms)
-        .map_err(|e| format!("Error in {op_label} lower bound: {e}"))?;
+    /// Whether the bars just opened enclose exactly a matrix environment:
+    /// `|\begin{pmatrix}…\end{pmatrix}|` is a determinant.
+    fn barred_matrix_follows(&self) -> bool {
+        if !self
+            .peek()
+            .is_some_and(|t| t.starts_with(MATRIX_BEGIN_PREFIX))
+        {
+            return false;
+        }
+        let rest = &self.tokens[self.pos..];
+        rest.iter()
+            .position(|t| t.starts_with(MATRIX_END_PREFIX))
+            .is_some_and(|end| rest.get(end + 1).map(String::as_str) == Some("ABS_END"))
+    }
 
-    let end_expr = build_expression_tree_inner(upper_bound_tokens, indexed_atoms)
-        .map_err(|e| format!("Error in {op_label} upper bound: {e}"))?;
+    /// Rows of the matrix environment just opened, as the determinant
+    /// `det` of that matrix.
+    fn parse_determinant(&mut self, env_name: &str) -> Result<Node, ParseError> {
+        let begin_index = self.pos - 1;
+        let rows = self.parse_matrix_rows(env_name)?;
+        determinant_of_rows(rows, begin_index)
+    }
 
-    // Debug logging for body tokens
-    log::debug!("Body tokens for {op_label}: {:?}", body_tokens);
+    /// The matrix environment just opened inside bars: the norm of a
+    /// column or row vector, `|\begin{pmatrix} 3 \\ 4 \end{pmatrix}|`,
+    /// and otherwise a determinant.
+    fn parse_barred_matrix(&mut self, env_name: &str) -> Result<Node, ParseError> {
+        let begin_index = self.pos - 1;
+        let rows = self.parse_matrix_rows(env_name)?;
+        let square = rows.iter().all(|row| row.len() == rows.len());
+        match vector_components(&rows) {
+            Some(components) if !square && env_name != "vmatrix" => Ok(Node::Abs(Box::new(
+                Node::Function(vector::VECTOR_LITERAL.to_string(), components),
+            ))),
+            _ => determinant_of_rows(rows, begin_index),
+        }
+    }
 
-    let body_expr = build_expression_tree_inner(body_tokens, indexed_atoms)
-        .map_err(|e| format!("Error in {op_label} body: {e}"))?;
+    /// The matrix environment just opened: a single column of two or more
+    /// entries is a vector literal, anything else (rows included, so
+    /// their orientation survives) a [`Node::Matrix`].
+    fn parse_matrix_literal(&mut self, env_name: &str) -> Result<Node, ParseError> {
+        let begin_index = self.pos - 1;
+        let rows = self.parse_matrix_rows(env_name)?;
+        if let Some(components) = column_components(&rows) {
+            if components.is_empty() {
+                return Err(ParseError::at_token("Empty vector", begin_index));
+            }
+            return Ok(Node::Function(
+                vector::VECTOR_LITERAL.to_string(),
+                components,
+            ));
+        }
+        let cols = rows[0].len();
+        if let Some(row) = rows.iter().find(|row| row.len() != cols) {
+            return Err(ParseError::at_token(
+                format!(
+                    "Matrix rows must have the same length, got {} and {} entries",
+                    cols,
+                    row.len()
+                ),
+                begin_index,
+            ));
+        }
+        Ok(Node::Matrix(rows))
+    }
 
-    let indexed_node = match kind {
-        IndexedNotation::Sum => Node::Summation(
-            index_var,
-            Box::new(start_expr),
-            Box::new(end_expr),
-            Box::new(body_expr),
-        ),
-        IndexedNotation::Prod => Node::Product(
-            index_var,
+    /// Rows of the matrix environment just opened, up to and including its
+    /// `\end`.
+    fn parse_matrix_rows(&mut self, env_name: &str) -> Result<Vec<Vec<Node>>, ParseError> {
+        let mut rows: Vec<Vec<Node>> = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error(format!("Missing \\end{{{}}}", env_name))),
+                Some(t) if t.starts_with(MATRIX_END_PREFIX) => {
+                    let end_name = &t[MATRIX_END_PREFIX.len()..];
+                    if end_name != env_name {
+                        return Err(self.error(format!(
+                            "\\begin{{{}}} closed by \\end{{{}}}",
+                            env_name, end_name
+                        )));
+                    }
+                    self.pos += 1;
+                    break;
+                }
+                // An empty row, e.g. a trailing \\ before \end
+                Some(MATRIX_ROW) => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let mut row = Vec::new();
+            loop {
+                match self.peek() {
+                    None => return Err(self.error(format!("Missing \\end{{{}}}", env_name))),
+                    Some("&" | MATRIX_ROW) => return Err(self.error("Empty matrix entry")),
+                    Some(t) if t.starts_with(MATRIX_END_PREFIX) => {
+                        return Err(self.error("Empty matrix entry"))
+                    }
+                    _ => {}
+                }
+                row.push(self.parse_expression(0)?);
+                match self.peek() {
+                    Some("&") => self.pos += 1,
+                    Some(MATRIX_ROW) => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(t) if t.starts_with(MATRIX_END_PREFIX) => break,
+                    None => return Err(self.error(format!("Missing \\end{{{}}}", env_name))),
+                    Some(_) => {
+                        return Err(self.error(format!(
+                            "Unexpected token '{}' in matrix",
+                            self.written(self.pos)
+                        )))
+                    }
+                }
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// `\sum_{k=a}^{b} body` or `\prod_{k=a}^{b} body`, with the operator
+    /// token already consumed. The upper bound may be a single unbraced
+    /// operand (`^n`) and may come first (`\sum^{n}_{k=1}`); an unbraced
+    /// body extends over the product that follows, so
+    /// `\sum_{k=1}^{n} k^2 + 1` is (Σk²) + 1. The body is parsed like any
+    /// other operand, so it may hold further sums.
+    fn parse_indexed(&mut self, kind: IndexedNotation) -> Result<Node, ParseError> {
+        let (op_token, op_label) = match kind {
+            IndexedNotation::Sum => ("sum", "summation"),
+            IndexedNotation::Prod => ("prod", "product"),
+        };
+
+        let upper_first = if self.peek() == Some("^") {
+            self.pos += 1;
+            Some(self.parse_indexed_upper(op_label)?)
+        } else {
+            None
+        };
+        self.expect("_", || format!("Expected '_' after '{op_token}'"))?;
+        self.expect("{", || "Expected '{' after '_'".to_string())?;
+        let index_pos = self.pos;
+        let index_var = match self.peek() {
+            Some(t) if t.chars().all(|c| c.is_alphabetic()) => {
+                self.pos += 1;
+                t.to_string()
+            }
+            _ => return Err(self.error("Expected index variable after '{'")),
+        };
+        if self.peek() == Some("in") {
+            return self.parse_sum_over(kind, index_var);
+        }
+        self.expect("=", || "Expected '=' after index variable".to_string())?;
+        let start_expr = self
+            .parse_expression(0)
+            .map_err(|e| e.context(format!("Error in {op_label} lower bound")))?;
+        self.expect("}", || "Unclosed lower bound brace".to_string())?;
+
+        let end_expr = match upper_first {
+            Some(end) => end,
+            None => {
+                self.expect("^", || "Expected '^' after lower bound".to_string())?;
+                self.parse_indexed_upper(op_label)?
+            }
+        };
+
+        if start_expr.contains_variable(&index_var) || end_expr.contains_variable(&index_var) {
+            return Err(ParseError::at_token(
+                format!("The {op_label} bounds cannot depend on its index '{index_var}'"),
+                index_pos,
+            ));
+        }
+
+        let body_expr = self.parse_indexed_body(op_label)?;
+
+        let (start, end, body) = (
             Box::new(start_expr),
             Box::new(end_expr),
             Box::new(body_expr),
-        ),
-    };
-
-    // Tokens beyond `i` are NOT this construct's concern: the caller
-    // splices the consumed span out and the expression grammar handles
-    // the rest. (The old code returned the bare node here, silently
-    // discarding surrounding context — 2·Σk evaluated to Σk.)
-    Ok((indexed_node, i))
-}
+        );
+        Ok(match kind {
+            IndexedNotation::Sum => Node::Summation(index_var, start, end, body),
+            IndexedNotation::Prod => Node::Product(index_var, start, end, body),
+        })
+    }
 
-/// Parse an unbraced indexed-notation body, handling cases like i^2, i*j, etc.
-fn parse_unbraced_indexed_body(tokens: &[String], body_tokens: &mut Vec<String>, pos: &mut usize) {
-    let mut i = 0;
-    let mut paren_depth: i32 = 0;
+    /// The upper bound of a `\sum` or `\prod`, just after its `^`.
+    fn parse_indexed_upper(&mut self, op_label: &str) -> Result<Node, ParseError> {
+        match self.peek() {
+            None => Err(self.error("Expected upper bound after '^'")),
+            Some("{") => {
+                self.pos += 1;
+                let end = self
+                    .parse_expression(0)
+                    .map_err(|e| e.context(format!("Error in {op_label} upper bound")))?;
+                self.expect("}", || "Unclosed upper bound brace".to_string())?;
+                Ok(end)
+            }
+            Some(_) => {
+                let end = self
+                    .parse_prefix()
+                    .map_err(|e| e.context(format!("Error in {op_label} upper bound")))?;
+                // The tokenizer joins an unbraced bound to the body with
+                // an implicit product: `^n k`
+                if self.peek() == Some("*") {
+                    self.pos += 1;
+                }
+                Ok(end)
+            }
+        }
+    }
 
-    while i < tokens.len() {
-        match tokens[i].as_str() {
-            // Paired delimiters all contribute to depth, so the top-level
-            // +/- break below cannot split inside |k−3| or ⌊k/2⌋.
-            "(" | "{" | "ABS_START" | "FLOOR_START" | "CEIL_START" => {
-                paren_depth += 1;
-                body_tokens.push(tokens[i].clone());
+    /// `\sum_{x \in data} body`, positioned at `\in`: a sum over the
+    /// elements of a list bound in the environment.
+    fn parse_sum_over(
+        &mut self,
+        kind: IndexedNotation,
+        index_var: String,
+    ) -> Result<Node, ParseError> {
+        if matches!(kind, IndexedNotation::Prod) {
+            return Err(self.error("\\prod over a list is not supported; use \\prod_{k=a}^{b}"));
+        }
+        self.pos += 1;
+        // `\in data` tokenizes with an implicit product before the name
+        if self.peek() == Some("*") {
+            self.pos += 1;
+        }
+        let list = match self.peek() {
+            Some(t) if t.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                self.pos += 1;
+                t.to_string()
             }
-            ")" | "}" | "ABS_END" | "FLOOR_END" | "CEIL_END" => {
-                if paren_depth > 0 {
-                    paren_depth -= 1;
-                    body_tokens.push(tokens[i].clone());
-                } else {
-                    break;
+            _ => return Err(self.error("Expected a list name after '\\in'")),
+        };
+        self.expect("}", || "Unclosed index brace".to_string())?;
+        let body = self.parse_indexed_body("summation")?;
+        Ok(Node::SumOver(
+            index_var,
+            Box::new(Node::Variable(list)),
+            Box::new(body),
+        ))
+    }
+
+    /// The body of a `\sum` or `\prod`: a braced group, or everything up
+    /// to the next `+` or `-`.
+    fn parse_indexed_body(&mut self, op_label: &str) -> Result<Node, ParseError> {
+        if self.peek() == Some("{") {
+            self.pos += 1;
+            let body = self
+                .parse_expression(0)
+                .map_err(|e| e.context(format!("Error in {op_label} body")))?;
+            self.expect("}", || "Unclosed body brace".to_string())?;
+            Ok(body)
+        } else {
+            self.parse_expression(PRODUCT_BP.0)
+                .map_err(|e| e.context(format!("Error in {op_label} body")))
+        }
+    }
+
+    /// `\int_{a}^{b} f \, dx` or `\int f \, dx`, with `\int` already
+    /// consumed. Bounds are a braced group or a single operand; the
+    /// integrand runs up to its differential, and a nested `\int` claims
+    /// the first differential after it, so `\int \int xy \, dx \, dy`
+    /// integrates over x first. `\int dx` integrates 1.
+    fn parse_integral(&mut self) -> Result<Node, ParseError> {
+        let int_index = self.pos - 1;
+        let (mut lower, mut upper) = (None, None);
+        while let Some(script @ ("_" | "^")) = self.peek() {
+            self.pos += 1;
+            let bound = match self.peek() {
+                None => return Err(self.error(format!("Expected a bound after '{}'", script))),
+                Some("{") => {
+                    self.pos += 1;
+                    let bound = self.parse_expression(0)?;
+                    self.expect("}", || "Unclosed integral bound brace".to_string())?;
+                    bound
                 }
+                Some(_) => self.parse_prefix()?,
+            };
+            let slot = if script == "_" {
+                &mut lower
+            } else {
+                &mut upper
+            };
+            if slot.replace(Box::new(bound)).is_some() {
+                return Err(
+                    self.error_at_previous(format!("Duplicate '{}' bound on \\int", script))
+                );
             }
-            "=" | "sum" | "prod" | ">" | "<" | ">=" | "<=" => break,
-            "+" | "-" if paren_depth == 0 && !body_tokens.is_empty() => break,
+        }
+        let bounds = match (lower, upper) {
+            (Some(lower), Some(upper)) => Some((lower, upper)),
+            (None, None) => None,
             _ => {
-                body_tokens.push(tokens[i].clone());
+                return Err(ParseError::at_token(
+                    "A definite integral needs both a lower and an upper bound",
+                    int_index,
+                ))
+            }
+        };
+        // An unbraced upper bound is joined to the integrand by an
+        // implicit product
+        if bounds.is_some() && self.peek() == Some("*") {
+            self.pos += 1;
+        }
+
+        let Some((end, differential_len, var)) = self.find_differential() else {
+            return Err(ParseError::at_token(
+                "Expected a differential such as 'dx' to close \\int",
+                int_index,
+            ));
+        };
+        let mut body_end = end;
+        if body_end > self.pos && self.tokens[body_end - 1] == "*" {
+            body_end -= 1;
+        }
+        let body = if body_end == self.pos {
+            Node::Num(ExactNum::one())
+        } else {
+            // Parse the integrand as if the input stopped at the differential
+            let all = self.tokens;
+            self.tokens = &all[..body_end];
+            let body = self.parse_expression(0);
+            let stopped_early = self.pos < body_end;
+            self.tokens = all;
+            let body = body.map_err(|e| e.context("Error in integrand"))?;
+            if stopped_early {
+                return Err(self.error(format!(
+                    "Unexpected token '{}' in integrand",
+                    self.written(self.pos)
+                )));
+            }
+            body
+        };
+        self.pos = end + differential_len;
+        Ok(Node::Integral(var, bounds, Box::new(body)))
+    }
+
+    /// `\\lim_{x \\to a} body`, with `\\lim` already consumed. The arrow
+    /// may be `\\to`, `\\rightarrow` or `\\longrightarrow`; the point may
+    /// be `\\infty` and may end in `^+` or `^-` (braced or not) for a
+    /// one-sided limit. The body extends like a `\\sum` body, so
+    /// `\\lim_{x \\to 0} \\frac{\\sin x}{x} + 1` is (lim …) + 1.
+    fn parse_limit(&mut self) -> Result<Node, ParseError> {
+        if self.peek() == Some("limits") {
+            self.pos += 1;
+        }
+        self.expect("_", || "Expected '_{x \\to a}' after \\lim".to_string())?;
+        self.expect("{", || "Expected '{' after '_'".to_string())?;
+        let var = match self.peek() {
+            Some(t) if is_identifier_token(t) && !matches!(t, "to" | "infty") => {
+                self.pos += 1;
+                t.to_string()
+            }
+            _ => return Err(self.error("Expected the limit variable after '{'")),
+        };
+        if !matches!(self.next(), Some("to" | "rightarrow" | "longrightarrow")) {
+            return Err(self.error_at_previous("Expected '\\to' after the limit variable"));
+        }
+
+        let Some(close) = self.matching_brace() else {
+            return Err(self.error("Unclosed limit subscript brace"));
+        };
+        let (point_end, direction) = one_sided_suffix(&self.tokens[self.pos..close]);
+        let point_end = self.pos + point_end;
+        if point_end == self.pos {
+            return Err(self.error("Expected the point after '\\to'"));
+        }
+        // A sign directly after the arrow is unary: `x \to -\infty`
+        let negate = match self.peek() {
+            Some("-") => {
+                self.pos += 1;
+                true
             }
+            Some("+") => {
+                self.pos += 1;
+                false
+            }
+            _ => false,
+        };
+        // Parse the point as if the input stopped before the suffix
+        let all = self.tokens;
+        self.tokens = &all[..point_end];
+        let point = self.parse_expression(0);
+        let stopped_early = self.pos < point_end;
+        self.tokens = all;
+        let point = point.map_err(|e| e.context("Error in limit point"))?;
+        if stopped_early {
+            return Err(self.error(format!(
+                "Unexpected token '{}' in limit point",
+                self.written(self.pos)
+            )));
         }
-        i += 1;
+        let point = if negate {
+            Node::Negate(Box::new(point))
+        } else {
+            point
+        };
+        self.pos = close + 1;
+
+        let body = if self.peek() == Some("{") {
+            self.pos += 1;
+            let body = self
+                .parse_expression(0)
+                .map_err(|e| e.context("Error in limit body"))?;
+            self.expect("}", || "Unclosed body brace".to_string())?;
+            body
+        } else {
+            self.parse_expression(PRODUCT_BP.0)
+                .map_err(|e| e.context("Error in limit body"))?
+        };
+        Ok(Node::Limit(var, Box::new(point), direction, Box::new(body)))
     }
 
-    *pos += i;
+    /// `\\frac{d}{dx} body`, which the tokenizer turns into `DERIV { x }`.
+    /// The body extends like a `\\sum` body, so `\\frac{d}{dx} x^2 + 1`
+    /// is (d/dx x²) + 1.
+    fn parse_derivative(&mut self) -> Result<Node, ParseError> {
+        self.expect("{", || "Expected the differentiation variable".to_string())?;
+        let var = match self.next() {
+            Some(t) if is_identifier_token(t) => t.to_string(),
+            _ => return Err(self.error_at_previous("Expected the differentiation variable")),
+        };
+        self.expect("}", || {
+            "Expected '}' after the differentiation variable".to_string()
+        })?;
+        if self.peek().is_none() {
+            return Err(self.error(format!(
+                "\\frac{{d}}{{d{}}} needs an expression to differentiate",
+                var
+            )));
+        }
+        let body = self.parse_indexed_body("derivative")?;
+        Ok(Node::Derivative(var, Box::new(body)))
+    }
 
-    // Handle the case where we have a power operation that needs to be rewritten for RPN
-    if body_tokens.len() >= 2 && body_tokens.contains(&"^".to_string()) {
-        // For case like "i^2", ensure we have a valid RPN expression
-        // Sometimes, the ^ operator can be problematic in RPN evaluation
+    /// Index of the `}` closing the group the current token is in.
+    fn matching_brace(&self) -> Option<usize> {
+        let mut depth = 0usize;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.pos) {
+            match token.as_str() {
+                "{" => depth += 1,
+                "}" if depth == 0 => return Some(i),
+                "}" => depth -= 1,
+                _ => {}
+            }
+        }
+        None
+    }
 
-        // Add explicit parentheses for power operations to ensure correct precedence
-        if body_tokens.len() == 3 && body_tokens[1] == "^" {
-            // Format is: var ^ exponent
-            let var = body_tokens[0].clone();
-            let exponent = body_tokens[2].clone();
+    /// Position, length and variable of the differential that closes the
+    /// integrand starting at the current token: the first one at the same
+    /// bracket depth not claimed by a nested `\int`.
+    fn find_differential(&self) -> Option<(usize, usize, String)> {
+        let (mut depth, mut nested) = (0usize, 0usize);
+        let mut i = self.pos;
+        while i < self.tokens.len() {
+            match self.tokens[i].as_str() {
+                "(" | "{" | "ABS_START" | "FLOOR_START" | "CEIL_START" => depth += 1,
+                ")" | "}" | "ABS_END" | "FLOOR_END" | "CEIL_END" => {
+                    depth = depth.checked_sub(1)?;
+                }
+                "int" if depth == 0 => nested += 1,
+                _ if depth == 0 => {
+                    if let Some((len, var)) = differential_at(self.tokens, i) {
+                        if nested == 0 {
+                            return Some((i, len, var));
+                        }
+                        nested -= 1;
+                        i += len;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+}
 
-            // If it's a numeric exponent, we can special-case it for better handling
-            if let Ok(exp_val) = exponent.parse::<i64>() {
-                if exp_val == 2 {
-                    // For squaring, use explicit multiplication: i*i
-                    body_tokens.clear();
-                    body_tokens.push(var.clone());
-                    body_tokens.push("*".to_string());
-                    body_tokens.push(var);
-                } else if exp_val > 2 && exp_val <= 5 {
-                    // For small powers, use explicit multiplication chain
-                    body_tokens.clear();
-                    body_tokens.push("(".to_string());
+pub(crate) fn parse_number(token: &str) -> Result<Node, String> {
+    if !token.contains('.') {
+        if let Ok(n) = token.parse::<BigInt>() {
+            return Ok(Node::Num(ExactNum::Rational(BigRational::from_integer(n))));
+        }
+    }
+    token
+        .parse::<f64>()
+        .map(|num| Node::Num(ExactNum::from_f64(num)))
+        .map_err(|_| format!("Invalid number '{}'", token))
+}
 
-                    // Add the first factor
-                    body_tokens.push(var.clone());
+/// The tokens after a LaTeX command, as a
+/// [`ParserExtension`](crate::extension::ParserExtension) reads them.
+/// Errors made with [`CommandInput::error`] point into the source like the
+/// parser's own.
+pub struct CommandInput<'p, 'a> {
+    parser: &'p mut Parser<'a>,
+    command: &'a str,
+}
 
-                    // Add the remaining factors
-                    for _ in 1..exp_val {
-                        body_tokens.push("*".to_string());
-                        body_tokens.push(var.clone());
+impl<'a> CommandInput<'_, 'a> {
+    /// The command being parsed, without the backslash.
+    pub fn command(&self) -> &'a str {
+        self.command
+    }
+
+    pub fn peek_token(&self) -> Option<&'a str> {
+        self.parser.peek()
+    }
+
+    pub fn next_token(&mut self) -> Option<&'a str> {
+        self.parser.next()
+    }
+
+    /// The tokens of the brace group that follows, unparsed: `H_2O` in
+    /// `\ce{H_2O}` gives `["H_2", "*", "O"]`, with the implicit products
+    /// the tokenizer inserts.
+    pub fn group_tokens(&mut self) -> Result<Vec<&'a str>, ParseError> {
+        self.open_group()?;
+        let mut tokens = Vec::new();
+        let mut depth = 0usize;
+        loop {
+            match self.parser.next() {
+                None => return Err(self.error(format!("Unclosed argument of \\{}", self.command))),
+                Some("}") if depth == 0 => return Ok(tokens),
+                Some(token) => {
+                    match token {
+                        "{" => depth += 1,
+                        "}" => depth -= 1,
+                        _ => {}
                     }
+                    tokens.push(token);
+                }
+            }
+        }
+    }
+
+    /// The brace group that follows, parsed as an expression.
+    pub fn parse_group(&mut self) -> Result<Node, ParseError> {
+        self.open_group()?;
+        let command = self.command;
+        self.parser.nested_call(|parser| {
+            let inner = parser.parse_expression(0)?;
+            parser.expect("}", || format!("Unclosed argument of \\{}", command))?;
+            Ok(inner)
+        })
+    }
 
-                    body_tokens.push(")".to_string());
+    /// An error at the next token (the end of input if none is left).
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        self.parser.error(message)
+    }
+
+    /// Read the `{` of a group, and the implicit `*` the tokenizer puts
+    /// between a command and its braces.
+    fn open_group(&mut self) -> Result<(), ParseError> {
+        if self.parser.peek() == Some("*") && self.parser.peek_at(1) == Some("{") {
+            self.parser.pos += 1;
+        }
+        let command = self.command;
+        self.parser
+            .expect("{", || format!("Expected '{{' after \\{}", command))
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.parser.pos
+    }
+
+    pub(crate) fn rewind(&mut self, position: usize) {
+        self.parser.pos = position;
+    }
+}
+
+/// Build the expression tree for a token stream from [`Tokenizer`].
+/// Errors carry the offending token's index but no source span; parse
+/// from LaTeX with [`parse_latex_raw`] to get one.
+///
+/// [`Tokenizer`]: crate::tokenizer::Tokenizer
+pub fn build_expression_tree(tokens: Vec<String>) -> Result<Node, ParseError> {
+    build_expression_tree_with_options(tokens, &ParserOptions::default())
+}
+
+/// [`build_expression_tree`] under the given limits.
+pub fn build_expression_tree_with_options(
+    tokens: Vec<String>,
+    options: &ParserOptions,
+) -> Result<Node, ParseError> {
+    log::debug!("Building expression tree from tokens: {:?}", tokens);
+    check_limits(&tokens, options)?;
+    Parser::new(&tokens, None, *options).parse_complete()
+}
+
+/// Reject a token stream past `options`' token count or brace depth
+/// before any recursion starts.
+fn check_limits(tokens: &[String], options: &ParserOptions) -> Result<(), ParseError> {
+    if tokens.len() > options.max_tokens {
+        return Err(ParseError::over_limit(
+            ParseLimit::Tokens,
+            format!(
+                "Expression has {} tokens; the limit is {}",
+                tokens.len(),
+                options.max_tokens
+            ),
+            options.max_tokens,
+        ));
+    }
+    let mut depth = 0usize;
+    for (index, token) in tokens.iter().enumerate() {
+        match token.as_str() {
+            "(" | "{" | "ABS_START" | "FLOOR_START" | "CEIL_START" => {
+                depth += 1;
+                if depth > options.max_brace_depth {
+                    return Err(ParseError::over_limit(
+                        ParseLimit::BraceDepth,
+                        format!("Groups nested more than {} deep", options.max_brace_depth),
+                        index,
+                    ));
                 }
-                // For higher powers or non-numeric exponents, leave as is
             }
+            ")" | "}" | "ABS_END" | "FLOOR_END" | "CEIL_END" => depth = depth.saturating_sub(1),
+            _ => {}
         }
     }
+    Ok(())
+}
+
+/// Tokenize and parse `latex`, locating any error in the source.
+fn parse_source(latex: &str, env: Option<&Environment>) -> Result<Node, ParseError> {
+    parse_source_with_options(latex, env, &ParserOptions::default(), None)
+}
+
+fn parse_source_with_options(
+    latex: &str,
+    env: Option<&Environment>,
+    options: &ParserOptions,
+    extensions: Option<&ParserExtensions>,
+) -> Result<Node, ParseError> {
+    let mut tokenizer = crate::tokenizer::Tokenizer::new(latex);
+    let tokens = tokenizer.tokenize();
+    parse_tokenized(latex, &tokens, &tokenizer, env, options, extensions)
+}
+
+/// Parse the `tokens` that `tokenizer` read from `latex`, or report its
+/// first error.
+pub(crate) fn parse_tokenized(
+    latex: &str,
+    tokens: &[String],
+    tokenizer: &crate::tokenizer::Tokenizer<'_>,
+    env: Option<&Environment>,
+    options: &ParserOptions,
+    extensions: Option<&ParserExtensions>,
+) -> Result<Node, ParseError> {
+    if let Some(message) = tokenizer.errors.first() {
+        let error = ParseError {
+            span: tokenizer.error_spans.first().cloned(),
+            ..ParseError::new(message.clone())
+        };
+        return Err(error.locate(latex, &tokenizer.spans));
+    }
+    check_limits(tokens, options).map_err(|e| e.locate(latex, &tokenizer.spans))?;
+    Parser::new(tokens, env, *options)
+        .with_source(latex, &tokenizer.spans)
+        .with_extensions(extensions)
+        .parse_complete()
+        .map_err(|e| e.locate(latex, &tokenizer.spans))
+}
+
+/// Parse a LaTeX expression string into a Node AST (no simplification)
+pub fn parse_latex_raw(latex: &str) -> Result<Node, ParseError> {
+    parse_source(latex, None)
+}
+
+/// Parse a LaTeX expression string into a Node AST (no simplification)
+/// under the given limits, for input from untrusted sources.
+/// [`parse_latex_raw`] applies [`ParserOptions::default`].
+pub fn parse_latex_raw_with_options(
+    latex: &str,
+    options: &ParserOptions,
+) -> Result<Node, ParseError> {
+    parse_source_with_options(latex, None, options, None)
+}
+
+/// Parse a LaTeX expression string into a Node AST (no simplification),
+/// handing the commands it contains to `extensions` first. See
+/// [`extension`](crate::extension).
+pub fn parse_latex_raw_with_extensions(
+    latex: &str,
+    extensions: &ParserExtensions,
+) -> Result<Node, ParseError> {
+    parse_source_with_options(latex, None, &ParserOptions::default(), Some(extensions))
+}
+
+/// Parse a LaTeX expression string into a Node AST (no simplification),
+/// reading `f(…)` as an application when `f` is a function defined in
+/// `env`. Undefined identifiers keep implicit multiplication.
+pub fn parse_latex_raw_with_env(latex: &str, env: &Environment) -> Result<Node, ParseError> {
+    parse_source(latex, Some(env))
+}
+
+/// Parse a LaTeX expression string into a Node AST and simplify using the environment.
+/// If simplification fails, returns the parsed (unsimplified) expression.
+pub fn parse_latex(latex: &str, env: &Environment) -> Result<Node, ParseError> {
+    let expr = parse_latex_raw_with_env(latex, env)?;
+    Ok(expr.simplify(env).unwrap_or(expr))
+}
+
+/// The determinant of parsed matrix rows; errors point at the `\begin`.
+fn determinant_of_rows(rows: Vec<Vec<Node>>, begin_index: usize) -> Result<Node, ParseError> {
+    let n = rows.len();
+    if n == 0 {
+        return Err(ParseError::at_token(
+            "Cannot take the determinant of an empty matrix",
+            begin_index,
+        ));
+    }
+    if let Some(row) = rows.iter().find(|row| row.len() != n) {
+        return Err(ParseError::at_token(
+            format!(
+                "Determinant requires a square matrix, got {}×{}",
+                n,
+                row.len()
+            ),
+            begin_index,
+        ));
+    }
+    Ok(Node::Function(
+        crate::matrix::DETERMINANT.to_string(),
+        vec![Node::Matrix(rows)],
+    ))
+}
+
+/// The entries of a single row or a single column, in order.
+fn vector_components(rows: &[Vec<Node>]) -> Option<Vec<Node>> {
+    (rows.len() == 1 || rows.iter().all(|row| row.len() == 1))
+        .then(|| rows.iter().flatten().cloned().collect())
+}
+
+/// The entries of a single column, when there is more than one.
+fn column_components(rows: &[Vec<Node>]) -> Option<Vec<Node>> {
+    (rows.len() != 1 && rows.iter().all(|row| row.len() == 1))
+        .then(|| rows.iter().flatten().cloned().collect())
+}
+
+enum IndexedNotation {
+    Sum,
+    Prod,
 }
 
 #[cfg(test)]
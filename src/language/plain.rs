@@ -0,0 +1,530 @@
+//! Plain infix input: `sin(x)^2 + 3*x`, with no backslashes or braces.
+//!
+//! The syntax is the one [`Node::to_plain_string`] writes, and produces
+//! the same trees as the LaTeX parser: `3*x^2 - sqrt(x)/2` and
+//! `3x^{2} - \frac{\sqrt{x}}{2}` parse alike. Powers are `^` or `**`,
+//! factors may be juxtaposed (`2x`, `2(x + 1)`), and unary minus binds
+//! tighter than a product but looser than a power (`-2*x` is (−2)·x,
+//! `-x^2` is −(x²)).
+//!
+//! Names are whole words: `rate*time` is two variables, not eight. A
+//! longer name followed by `(` is a call, while a single letter followed
+//! by `(` is a product, so `x(y + 1)` is x·(y + 1). Greek letters are
+//! spelled out (`theta_1`), `pi` and `e` are the constants and `inf` is
+//! ∞. Notation that LaTeX writes with symbols is written as a call:
+//! `abs(x)`, `floor(x)`, `ceil(x)`, `sum(body, k, a, b)`,
+//! `prod(body, k, a, b)`, `integrate(body, x)` or
+//! `integrate(body, x, a, b)`, `limit(body, x, a)` with an optional
+//! `left` or `right`, `diff(body, x)` and `piecewise(value, condition, …)`.
+//! `[[1, 2], [3, 4]]` is a matrix and `[1, 2, 3]` a vector.
+
+use std::ops::Range;
+
+use crate::functions::FUNCTION_REGISTRY;
+use crate::limits::INFINITY;
+use crate::node::{LimitDirection, Node};
+use crate::parser::{parse_number, ParseError, ParseLimit, ParserOptions};
+use crate::tokenizer::normalize_var;
+use crate::vector;
+
+/// Parse plain infix text into an expression tree.
+pub fn parse_plain(input: &str) -> Result<Node, ParseError> {
+    let options = ParserOptions::default();
+    let tokens = tokenize(input)?;
+    if tokens.len() > options.max_tokens {
+        return Err(ParseError {
+            limit: Some(ParseLimit::Tokens),
+            ..ParseError::new(format!("Input has more than {} tokens", options.max_tokens))
+        });
+    }
+    let mut parser = PlainParser {
+        tokens,
+        pos: 0,
+        source: input,
+        depth: 0,
+        max_depth: options.max_expression_depth,
+    };
+    if parser.tokens.is_empty() {
+        return Err(parser.error("Empty expression"));
+    }
+    let node = parser.parse_equation()?;
+    if parser.pos < parser.tokens.len() {
+        let unexpected = parser.tokens[parser.pos].text.clone();
+        return Err(parser.error(format!("Unexpected '{}'", unexpected)));
+    }
+    Ok(node)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Name,
+    Symbol,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: Kind,
+    text: String,
+    span: Range<usize>,
+}
+
+/// Two-character operators, then one-character ones, with the Unicode
+/// spellings a pasted expression may contain.
+const SYMBOLS: &[(&str, &str)] = &[
+    ("**", "^"),
+    ("<=", "<="),
+    (">=", ">="),
+    ("==", "=="),
+    ("+", "+"),
+    ("-", "-"),
+    ("−", "-"),
+    ("*", "*"),
+    ("·", "*"),
+    ("×", "*"),
+    ("/", "/"),
+    ("^", "^"),
+    ("!", "!"),
+    ("(", "("),
+    (")", ")"),
+    ("[", "["),
+    ("]", "]"),
+    ("|", "|"),
+    (",", ","),
+    ("=", "="),
+    ("<", "<"),
+    (">", ">"),
+    ("≤", "<="),
+    ("≥", ">="),
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        let start = input.len() - rest.len();
+        let len = if c.is_whitespace() {
+            c.len_utf8()
+        } else if c.is_ascii_digit() || c == '.' {
+            let len = rest
+                .find(|d: char| !(d.is_ascii_digit() || d == '.'))
+                .unwrap_or(rest.len());
+            push(&mut tokens, Kind::Number, &rest[..len], start);
+            len
+        } else if c == '∞' {
+            push(&mut tokens, Kind::Name, "inf", start);
+            c.len_utf8()
+        } else if c.is_alphabetic() {
+            let len = rest
+                .find(|d: char| !(d.is_alphanumeric() || d == '_'))
+                .unwrap_or(rest.len());
+            push(&mut tokens, Kind::Name, &rest[..len], start);
+            len
+        } else if let Some((spelling, symbol)) = SYMBOLS.iter().find(|(s, _)| rest.starts_with(s)) {
+            push(&mut tokens, Kind::Symbol, symbol, start);
+            tokens.last_mut().unwrap().span = start..start + spelling.len();
+            spelling.len()
+        } else {
+            return Err(ParseError {
+                span: Some(start..start + c.len_utf8()),
+                source: Some(input.to_string()),
+                ..ParseError::new(format!("Unknown character '{}'", c))
+            });
+        };
+        rest = &rest[len..];
+    }
+    Ok(tokens)
+}
+
+fn push(tokens: &mut Vec<Token>, kind: Kind, text: &str, start: usize) {
+    tokens.push(Token {
+        kind,
+        text: text.to_string(),
+        span: start..start + text.len(),
+    });
+}
+
+/// Words that are operators rather than names.
+const KEYWORDS: [&str; 4] = ["and", "or", "not", "mod"];
+
+struct PlainParser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    source: &'a str,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl PlainParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn at(&self, kind: Kind, text: &str) -> bool {
+        self.peek()
+            .is_some_and(|t| t.kind == kind && t.text == text)
+    }
+
+    fn at_symbol(&self, symbol: &str) -> bool {
+        self.at(Kind::Symbol, symbol)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.at_symbol(symbol);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, word: &str) -> bool {
+        let found = self.at(Kind::Name, word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected '{}'", symbol)))
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        let span = match self.peek() {
+            Some(token) => token.span.clone(),
+            None => self.source.len()..self.source.len(),
+        };
+        ParseError {
+            token_index: Some(self.pos),
+            span: Some(span),
+            source: Some(self.source.to_string()),
+            ..ParseError::new(message)
+        }
+    }
+
+    fn parse_equation(&mut self) -> Result<Node, ParseError> {
+        let lhs = self.parse_or()?;
+        if self.eat_symbol("=") {
+            let rhs = self.parse_or()?;
+            return Ok(Node::Equation(Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_or(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_and()?;
+        while self.eat_keyword("or") {
+            node = Node::Or(Box::new(node), Box::new(self.parse_and()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_not()?;
+        while self.eat_keyword("and") {
+            node = Node::And(Box::new(node), Box::new(self.parse_not()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_not(&mut self) -> Result<Node, ParseError> {
+        if self.eat_keyword("not") {
+            return self.nested(|p| Ok(Node::Not(Box::new(p.parse_not()?))));
+        }
+        self.parse_relation()
+    }
+
+    fn parse_relation(&mut self) -> Result<Node, ParseError> {
+        let lhs = self.parse_sum()?;
+        let relation: fn(Box<Node>, Box<Node>) -> Node = match self.peek() {
+            Some(t) if t.kind == Kind::Symbol => match t.text.as_str() {
+                "<" => Node::Less,
+                ">" => Node::Greater,
+                "<=" => Node::LessEqual,
+                ">=" => Node::GreaterEqual,
+                "==" => Node::Equal,
+                _ => return Ok(lhs),
+            },
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.parse_sum()?;
+        Ok(relation(Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn parse_sum(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_product()?;
+        loop {
+            if self.eat_symbol("+") {
+                node = Node::Add(Box::new(node), Box::new(self.parse_product()?));
+            } else if self.eat_symbol("-") {
+                node = Node::Subtract(Box::new(node), Box::new(self.parse_product()?));
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_unary()?;
+        loop {
+            if self.eat_symbol("*") {
+                node = Node::Multiply(Box::new(node), Box::new(self.parse_unary()?));
+            } else if self.eat_symbol("/") {
+                node = Node::Divide(Box::new(node), Box::new(self.parse_unary()?));
+            } else if self.eat_keyword("mod") {
+                let rhs = self.parse_unary()?;
+                node = Node::Function("mod".to_string(), vec![node, rhs]);
+            } else if self.starts_factor() {
+                // Juxtaposition: `2x`, `2(x + 1)`, `x y`
+                node = Node::Multiply(Box::new(node), Box::new(self.parse_power()?));
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn starts_factor(&self) -> bool {
+        match self.peek() {
+            Some(t) => match t.kind {
+                Kind::Number => true,
+                Kind::Name => !KEYWORDS.contains(&t.text.as_str()),
+                Kind::Symbol => t.text == "(",
+            },
+            None => false,
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Node, ParseError> {
+        if self.eat_symbol("-") {
+            return self.nested(|p| Ok(Node::Negate(Box::new(p.parse_unary()?))));
+        }
+        if self.eat_symbol("+") {
+            return self.nested(|p| p.parse_unary());
+        }
+        self.parse_power()
+    }
+
+    fn parse_power(&mut self) -> Result<Node, ParseError> {
+        let base = self.parse_postfix()?;
+        if self.eat_symbol("^") {
+            // Right-associative, and the exponent may be negated: `x^-1`
+            let exponent = self.nested(|p| p.parse_unary())?;
+            return Ok(Node::Power(Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn parse_postfix(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_primary()?;
+        while self.eat_symbol("!") {
+            node = Node::Factorial(Box::new(node));
+        }
+        Ok(node)
+    }
+
+    /// Run `parse` one level deeper, failing past the depth limit.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Node, ParseError>,
+    ) -> Result<Node, ParseError> {
+        if self.depth >= self.max_depth {
+            return Err(ParseError {
+                limit: Some(ParseLimit::ExpressionDepth),
+                ..self.error(format!(
+                    "Expression nested more than {} levels deep",
+                    self.max_depth
+                ))
+            });
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_primary(&mut self) -> Result<Node, ParseError> {
+        let Some(token) = self.peek().cloned() else {
+            return Err(self.error("Unexpected end of input"));
+        };
+        match (token.kind, token.text.as_str()) {
+            (Kind::Number, text) => {
+                let node = parse_number(text).map_err(|e| self.error(e))?;
+                self.pos += 1;
+                Ok(node)
+            }
+            (Kind::Symbol, "(") => {
+                self.pos += 1;
+                let inner = self.nested(|p| p.parse_equation())?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            (Kind::Symbol, "|") => {
+                self.pos += 1;
+                let inner = self.nested(|p| p.parse_sum())?;
+                self.expect("|")?;
+                Ok(Node::Abs(Box::new(inner)))
+            }
+            (Kind::Symbol, "[") => self.parse_list(),
+            (Kind::Name, name) if !KEYWORDS.contains(&name) || name == "mod" => {
+                self.pos += 1;
+                let call = name.chars().count() > 1 && self.at_symbol("(");
+                if call {
+                    return self.parse_call(name);
+                }
+                // `inf` and `gamma` are functions too, but as words they
+                // name ∞ and γ
+                let variable = variable_name(name);
+                if variable == name && name.len() > 1 && FUNCTION_REGISTRY.get(name).is_some() {
+                    return Err(self.error(format!("Expected '(' after '{}'", name)));
+                }
+                Ok(Node::Variable(variable))
+            }
+            _ => Err(self.error(format!("Unexpected '{}'", token.text))),
+        }
+    }
+
+    /// The arguments of a call, after its name.
+    fn parse_args(&mut self) -> Result<Vec<Node>, ParseError> {
+        self.expect("(")?;
+        let mut args = Vec::new();
+        if !self.eat_symbol(")") {
+            loop {
+                args.push(self.nested(|p| p.parse_equation())?);
+                if self.eat_symbol(")") {
+                    break;
+                }
+                if !self.eat_symbol(",") {
+                    return Err(self.error("Expected ',' or ')'"));
+                }
+            }
+        }
+        Ok(args)
+    }
+
+    fn parse_call(&mut self, name: &str) -> Result<Node, ParseError> {
+        let start = self.pos - 1;
+        let args = self.parse_args()?;
+        let arity_error = |expected: &str| {
+            let mut error = self.error(format!("{} takes {}", name, expected));
+            error.span = Some(self.tokens[start].span.clone());
+            error.token_index = Some(start);
+            error
+        };
+        let variable = |node: &Node, error: ParseError| match node {
+            Node::Variable(v) => Ok(v.clone()),
+            _ => Err(error),
+        };
+        let boxed = |node: &Node| Box::new(node.clone());
+        Ok(match (name, args.as_slice()) {
+            ("abs", [x]) => Node::Abs(boxed(x)),
+            ("floor", [x]) => Node::Floor(boxed(x)),
+            ("ceil", [x]) => Node::Ceil(boxed(x)),
+            ("abs" | "floor" | "ceil", _) => return Err(arity_error("one argument")),
+            ("sum" | "prod", [body, index, start, end]) => {
+                let index = variable(index, arity_error("a variable as its second argument"))?;
+                let (start, end, body) = (boxed(start), boxed(end), boxed(body));
+                if name == "sum" {
+                    Node::Summation(index, start, end, body)
+                } else {
+                    Node::Product(index, start, end, body)
+                }
+            }
+            ("sum", [body, index, list]) => {
+                let index = variable(index, arity_error("a variable as its second argument"))?;
+                Node::SumOver(index, boxed(list), boxed(body))
+            }
+            ("sum" | "prod", _) => return Err(arity_error("(body, index, from, to)")),
+            ("integrate", [body, var]) => {
+                let var = variable(var, arity_error("a variable as its second argument"))?;
+                Node::Integral(var, None, boxed(body))
+            }
+            ("integrate", [body, var, lower, upper]) => {
+                let var = variable(var, arity_error("a variable as its second argument"))?;
+                Node::Integral(var, Some((boxed(lower), boxed(upper))), boxed(body))
+            }
+            ("integrate", _) => return Err(arity_error("(body, x) or (body, x, from, to)")),
+            ("limit", [body, var, point, rest @ ..]) if rest.len() <= 1 => {
+                let var = variable(var, arity_error("a variable as its second argument"))?;
+                let direction = match rest {
+                    [] => LimitDirection::Both,
+                    [Node::Variable(side)] if side == "left" => LimitDirection::Left,
+                    [Node::Variable(side)] if side == "right" => LimitDirection::Right,
+                    _ => return Err(arity_error("left or right as its fourth argument")),
+                };
+                Node::Limit(var, boxed(point), direction, boxed(body))
+            }
+            ("limit", _) => return Err(arity_error("(body, x, point)")),
+            ("diff", [body, var]) => {
+                let var = variable(var, arity_error("a variable as its second argument"))?;
+                Node::Derivative(var, boxed(body))
+            }
+            ("diff", _) => return Err(arity_error("(body, x)")),
+            ("piecewise", cases) if !cases.is_empty() && cases.len() % 2 == 0 => Node::Piecewise(
+                cases
+                    .chunks(2)
+                    .map(|pair| (pair[0].clone(), pair[1].clone()))
+                    .collect(),
+            ),
+            ("piecewise", _) => return Err(arity_error("(value, condition, …) pairs")),
+            _ => Node::Function(name.to_string(), args),
+        })
+    }
+
+    /// `[[a, b], [c, d]]` as a matrix, `[a, b]` as a vector.
+    fn parse_list(&mut self) -> Result<Node, ParseError> {
+        self.expect("[")?;
+        if self.at_symbol("[") {
+            let mut rows = Vec::new();
+            loop {
+                self.expect("[")?;
+                let mut row = Vec::new();
+                loop {
+                    row.push(self.nested(|p| p.parse_equation())?);
+                    if self.eat_symbol("]") {
+                        break;
+                    }
+                    self.expect(",")?;
+                }
+                if rows
+                    .first()
+                    .is_some_and(|first: &Vec<Node>| first.len() != row.len())
+                {
+                    return Err(self.error("Matrix rows differ in length"));
+                }
+                rows.push(row);
+                if self.eat_symbol("]") {
+                    return Ok(Node::Matrix(rows));
+                }
+                self.expect(",")?;
+            }
+        }
+        let mut components = Vec::new();
+        loop {
+            components.push(self.nested(|p| p.parse_equation())?);
+            if self.eat_symbol("]") {
+                return Ok(Node::Function(
+                    vector::VECTOR_LITERAL.to_string(),
+                    components,
+                ));
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+/// A name as the LaTeX parser stores it: Greek letters as symbols on
+/// either side of a subscript (`theta_1` is `θ_1`), `inf` as ∞.
+fn variable_name(name: &str) -> String {
+    if matches!(name, "inf" | "infinity") {
+        return INFINITY.to_string();
+    }
+    match name.split_once('_') {
+        Some((base, subscript)) => format!("{}_{}", normalize_var(base), normalize_var(subscript)),
+        None => normalize_var(name),
+    }
+}
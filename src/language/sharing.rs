@@ -0,0 +1,302 @@
+//! Let-style rendering of large results with repeated subexpressions.
+//!
+//! Machine-generated expressions (quotient-rule derivatives, expanded
+//! series) tend to repeat the same subtree many times. [`share_subexpressions`]
+//! pulls each repeated subtree out into a named definition so the result
+//! reads as
+//!
+//! ```text
+//! u = x^{2} + 1
+//! result = \frac{2x \cdot u - x^{2} \cdot 2x}{u^{2}}
+//! ```
+//!
+//! or, as LaTeX, an `aligned` block. Only rendering changes: [`SharedExpression::expand`]
+//! substitutes the definitions back and gives the original tree.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::intern::{NodeArena, NodeId};
+use crate::node::Node;
+
+/// Tuning for [`share_subexpressions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharingOptions {
+    /// Smallest subtree (in nodes) worth naming; `x^2` is 3 nodes
+    pub min_size: usize,
+    /// Fewest occurrences worth naming
+    pub min_occurrences: usize,
+}
+
+impl Default for SharingOptions {
+    fn default() -> Self {
+        SharingOptions {
+            min_size: 4,
+            min_occurrences: 2,
+        }
+    }
+}
+
+/// An expression with its shared subtrees pulled out into definitions.
+/// Each definition may refer to the ones before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedExpression {
+    pub definitions: Vec<(String, Node)>,
+    pub result: Node,
+}
+
+/// Names tried in order before falling back to `u_1`, `u_2`, …
+const NAMES: [&str; 6] = ["u", "v", "w", "p", "q", "r"];
+
+/// Name repeated subtrees of `expr` with the default options.
+pub fn share_subexpressions(expr: &Node) -> SharedExpression {
+    share_subexpressions_with(expr, SharingOptions::default())
+}
+
+/// Name every subtree of at least `min_size` nodes that occurs at least
+/// `min_occurrences` times, largest first, so a definition can itself use
+/// smaller shared pieces. Subtrees under a `\sum`, `\prod`, `\int`, `\lim`,
+/// or `\frac{d}{dx}` body mention the bound variable and are left in place.
+pub fn share_subexpressions_with(expr: &Node, options: SharingOptions) -> SharedExpression {
+    let mut taken = Vec::new();
+    collect_names(expr, &mut taken);
+
+    let mut definitions: Vec<(String, Node)> = Vec::new();
+    let mut result = expr.clone();
+    loop {
+        // Interned afresh each round: a round rewrites the trees, and
+        // hash-consing keeps the tally linear in their size.
+        let mut arena = NodeArena::new();
+        let mut tally = Tally::default();
+        let result_id = arena.intern(&result);
+        tally.count(&result, result_id, &arena, options.min_size);
+        for (_, def) in &definitions {
+            let def_id = arena.intern(def);
+            tally.count_below_root(def, def_id, &arena, options.min_size);
+        }
+        let best = tally
+            .counts
+            .into_iter()
+            .filter(|(_, _, n)| *n >= options.min_occurrences.max(2))
+            .max_by_key(|(_, size, _)| *size);
+        let Some((shared_id, _, _)) = best else { break };
+        let shared = arena.get(shared_id);
+
+        let name = fresh_name(&taken);
+        taken.push(name.clone());
+        let var = Node::Variable(name.clone());
+        result = replace(&result, result_id, shared_id, &var, &arena);
+        for (_, def) in definitions.iter_mut() {
+            let def_id = arena.intern(def);
+            *def = replace_below_root(def, def_id, shared_id, &var, &arena);
+        }
+        definitions.push((name, shared));
+    }
+
+    SharedExpression {
+        definitions: dependency_order(definitions),
+        result,
+    }
+}
+
+impl SharedExpression {
+    /// Substitute every definition back, giving the unshared tree.
+    pub fn expand(&self) -> Node {
+        let mut node = self.result.clone();
+        for (name, def) in self.definitions.iter().rev() {
+            node = expand_name(&node, name, def);
+        }
+        node
+    }
+
+    /// An `aligned` block with one `name &= …` row per definition and a
+    /// final `result &= …` row; just the expression when nothing is shared.
+    pub fn to_latex(&self) -> String {
+        if self.definitions.is_empty() {
+            return self.result.to_latex();
+        }
+        let mut rows: Vec<String> = self
+            .definitions
+            .iter()
+            .map(|(name, def)| format!("{} &= {}", name, def.to_latex()))
+            .collect();
+        rows.push(format!("\\text{{result}} &= {}", self.result.to_latex()));
+        format!(
+            "\\begin{{aligned}}\n{}\n\\end{{aligned}}",
+            rows.join(" \\\\\n")
+        )
+    }
+}
+
+/// One `name = …` line per definition, then `result = …`.
+impl fmt::Display for SharedExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.definitions.is_empty() {
+            return write!(f, "{}", self.result);
+        }
+        for (name, def) in &self.definitions {
+            writeln!(f, "{} = {}", name, def)?;
+        }
+        write!(f, "result = {}", self.result)
+    }
+}
+
+fn fresh_name(taken: &[String]) -> String {
+    NAMES
+        .iter()
+        .map(|n| n.to_string())
+        .chain((1..).map(|k| format!("u_{}", k)))
+        .find(|n| !taken.contains(n))
+        .expect("the name sequence is unbounded")
+}
+
+fn collect_names(node: &Node, out: &mut Vec<String>) {
+    node.visit(&mut |n| match n {
+        Node::Variable(v) => out.push(v.clone()),
+        Node::Summation(idx, ..)
+        | Node::Product(idx, ..)
+        | Node::SumOver(idx, ..)
+        | Node::Integral(idx, ..)
+        | Node::Limit(idx, ..)
+        | Node::Derivative(idx, _) => out.push(idx.clone()),
+        _ => {}
+    });
+}
+
+/// How many of the children of `node` (in path order) may be named: all
+/// but the body of a binder, which may mention the bound variable and so
+/// cannot move out of scope.
+fn shareable(node: &Node) -> usize {
+    let children = node.children().len();
+    match node {
+        Node::Summation(..)
+        | Node::Product(..)
+        | Node::SumOver(..)
+        | Node::Integral(..)
+        | Node::Limit(..)
+        | Node::Derivative(..) => children - 1,
+        _ => children,
+    }
+}
+
+/// Rebuild `node` with `f` applied to each shareable child and its id.
+fn map_shareable(
+    node: &Node,
+    id: NodeId,
+    arena: &NodeArena,
+    mut f: impl FnMut(&Node, NodeId) -> Node,
+) -> Node {
+    let shareable = shareable(node);
+    let mut ids = arena.children(id).iter().enumerate();
+    node.map_children(|child| match ids.next() {
+        Some((i, &child_id)) if i < shareable => f(child, child_id),
+        _ => child.clone(),
+    })
+}
+
+/// Subtrees of at least `min_size` nodes as (id, size, count), in the
+/// order they were first seen.
+#[derive(Default)]
+struct Tally {
+    counts: Vec<(NodeId, usize, usize)>,
+    positions: HashMap<NodeId, usize>,
+    sizes: HashMap<NodeId, usize>,
+}
+
+impl Tally {
+    fn count(&mut self, node: &Node, id: NodeId, arena: &NodeArena, min_size: usize) {
+        let n = self.size(node, id, arena);
+        if n >= min_size {
+            match self.positions.get(&id) {
+                Some(&i) => self.counts[i].2 += 1,
+                None => {
+                    self.positions.insert(id, self.counts.len());
+                    self.counts.push((id, n, 1));
+                }
+            }
+        }
+        self.count_below_root(node, id, arena, min_size);
+    }
+
+    /// A definition's own root is already named, so only its parts count.
+    fn count_below_root(&mut self, node: &Node, id: NodeId, arena: &NodeArena, min_size: usize) {
+        let children = node.children();
+        for (child, &child_id) in children
+            .into_iter()
+            .zip(arena.children(id))
+            .take(shareable(node))
+        {
+            self.count(child, child_id, arena, min_size);
+        }
+    }
+
+    /// Nodes in the shareable part of the tree.
+    fn size(&mut self, node: &Node, id: NodeId, arena: &NodeArena) -> usize {
+        if let Some(&n) = self.sizes.get(&id) {
+            return n;
+        }
+        let children = node.children();
+        let n = 1 + children
+            .into_iter()
+            .zip(arena.children(id))
+            .take(shareable(node))
+            .map(|(child, &child_id)| self.size(child, child_id, arena))
+            .sum::<usize>();
+        self.sizes.insert(id, n);
+        n
+    }
+}
+
+fn replace(node: &Node, id: NodeId, target: NodeId, with: &Node, arena: &NodeArena) -> Node {
+    if id == target {
+        return with.clone();
+    }
+    replace_below_root(node, id, target, with, arena)
+}
+
+fn replace_below_root(
+    node: &Node,
+    id: NodeId,
+    target: NodeId,
+    with: &Node,
+    arena: &NodeArena,
+) -> Node {
+    map_shareable(node, id, arena, |child, child_id| {
+        replace(child, child_id, target, with, arena)
+    })
+}
+
+/// `node` with the shareable occurrences of the variable `name` replaced
+/// by `def`.
+fn expand_name(node: &Node, name: &str, def: &Node) -> Node {
+    if matches!(node, Node::Variable(v) if v == name) {
+        return def.clone();
+    }
+    let shareable = shareable(node);
+    let mut index = 0;
+    node.map_children(|child| {
+        index += 1;
+        if index <= shareable {
+            expand_name(child, name, def)
+        } else {
+            child.clone()
+        }
+    })
+}
+
+/// Order definitions so each comes after every definition it mentions.
+fn dependency_order(mut pending: Vec<(String, Node)>) -> Vec<(String, Node)> {
+    let mut ordered: Vec<(String, Node)> = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|(_, def)| {
+                pending
+                    .iter()
+                    .all(|(other, _)| !def.contains_variable(other))
+            })
+            .unwrap_or(0);
+        ordered.push(pending.remove(ready));
+    }
+    ordered
+}
@@ -1,6 +1,10 @@
+use std::cell::Cell;
 use std::iter::Peekable;
+use std::ops::Range;
+use std::rc::Rc;
 use std::str::Chars;
 
+use crate::constants::Constant;
 use crate::exact::ExactNum;
 use crate::function_meta::{inverse_from_minus_one_power, is_log_or_exp, is_trig_or_hyperbolic};
 use crate::functions::FUNCTION_REGISTRY;
@@ -112,19 +116,66 @@ fn push_reduced_rational_tokens(tokens: &mut Vec<String>, r: &BigRational) {
     tokens.push(")".to_string());
 }
 
+/// The variable of a Leibniz operator `\\frac{d}{dx}` or
+/// `\\frac{\\partial}{\\partial x}`, given the fraction's numerator and
+/// denominator; `None` for any other fraction. The variable may be any
+/// identifier (`d\\theta`, `dx_{1}`).
+fn leibniz_variable(numer: &str, denom: &str) -> Option<Result<String, String>> {
+    let rest = match numer {
+        "d" => denom.strip_prefix('d').filter(|rest| !rest.is_empty())?,
+        "\\partial" => denom.strip_prefix("\\partial")?,
+        _ => return None,
+    };
+    let tokens = Tokenizer::new(rest.trim()).tokenize();
+    match tokens.as_slice() {
+        [var] if is_variable_token(var) => Some(Ok(var.clone())),
+        // `\frac{d}{d+1}` and the like are ordinary fractions
+        _ if numer == "d" => None,
+        _ => Some(Err(format!(
+            "\\frac{{\\partial}}{{{}}} needs a single variable after \\partial",
+            denom
+        ))),
+    }
+}
+
+/// A history reference `%n` (session result label, n ≥ 1).
+pub(crate) fn is_history_ref(token: &str) -> bool {
+    token
+        .strip_prefix('%')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
 fn is_variable_token(token: &str) -> bool {
+    if is_history_ref(token) || is_subscripted_identifier(token) {
+        return true;
+    }
     !token.is_empty()
         && token.chars().all(|c| c.is_alphabetic())
         && FUNCTION_REGISTRY.get(token).is_none()
         && token != "NEG"
+        && token != "DERIV"
+        && token != "CROSS"
+        && token != "MOD"
+        && token != "NOT"
         && token != "sum"
         && !matches!(
             token,
-            "int" | "prod" | "oint" | "iint" | "iiint" | "lim" | "nabla" | "infty"
+            "int"
+                | "prod"
+                | "oint"
+                | "iint"
+                | "iiint"
+                | "lim"
+                | "nabla"
+                | "infty"
+                | "to"
+                | "rightarrow"
+                | "longrightarrow"
         )
 }
 
-/// True when `tokens` ends with `}` closing a `_{…}` or `^{…}` script group.
+/// True when `tokens` ends with `}` closing a `_{…}` or `^{…}` script group,
+/// or the variable group of a `\\frac{d}{dx}` operator (`DERIV {x}`).
 fn closes_script_bound(tokens: &[String]) -> bool {
     if tokens.last().map(|t| t.as_str()) != Some("}") {
         return false;
@@ -136,7 +187,7 @@ fn closes_script_bound(tokens: &[String]) -> bool {
             "{" => {
                 depth -= 1;
                 if depth == 0 {
-                    return i > 0 && matches!(tokens[i - 1].as_str(), "^" | "_");
+                    return i > 0 && matches!(tokens[i - 1].as_str(), "^" | "_" | "DERIV");
                 }
             }
             _ => {}
@@ -166,9 +217,40 @@ fn needs_implicit_mul_after_token(last: &str, tokens: &[String]) -> bool {
     if last == "}" && closes_script_bound(tokens) {
         return false;
     }
-    last == ")" || last == "}" || is_decimal_literal(last) || is_variable_token(last)
+    last == ")"
+        || last == "}"
+        || is_decimal_literal(last)
+        || is_variable_token(last)
+        || last.starts_with(VECTOR_PREFIX)
 }
 
+/// Token prefixes marking `\begin{env}` / `\end{env}` of a matrix
+/// environment. The `:` keeps them disjoint from every variable token.
+pub(crate) const MATRIX_BEGIN_PREFIX: &str = "MATRIX_BEGIN:";
+pub(crate) const MATRIX_END_PREFIX: &str = "MATRIX_END:";
+/// Row separator (`\\`) inside a matrix environment.
+pub(crate) const MATRIX_ROW: &str = "MATRIX_ROW";
+/// Token prefix of a vector name: `\vec{a}` is `VEC:a`.
+pub(crate) const VECTOR_PREFIX: &str = "VEC:";
+/// Token prefix of a function named with `\operatorname` that is not
+/// built in: `\operatorname{foo}` is `OPERATOR:foo`.
+pub(crate) const OPERATOR_PREFIX: &str = "OPERATOR:";
+
+/// Synthesized tokens that are spelled like a subscripted name.
+const MARKER_TOKENS: [&str; 7] = [
+    "ABS_START",
+    "ABS_END",
+    "FLOOR_START",
+    "FLOOR_END",
+    "CEIL_START",
+    "CEIL_END",
+    MATRIX_ROW,
+];
+
+/// Matrix environments the tokenizer accepts. `Vmatrix` (a norm) is not
+/// among them: reading it as anything else would be a wrong value.
+const MATRIX_ENVIRONMENTS: &[&str] = &["matrix", "pmatrix", "bmatrix", "Bmatrix", "vmatrix"];
+
 fn greek_letter(name: &str) -> Option<char> {
     match name {
         "pi" => Some('π'),
@@ -207,6 +289,40 @@ fn greek_letter(name: &str) -> Option<char> {
     }
 }
 
+/// A name with a subscript, `x_1`, `θ_0` or `v_max`: a letter run, `_`,
+/// and a run of letters and digits. `x_1` and `x_{1}` both tokenize to
+/// this form, and it is the name a `Node::Variable` carries.
+pub fn is_subscripted_identifier(token: &str) -> bool {
+    !MARKER_TOKENS.contains(&token)
+        && token.split_once('_').is_some_and(|(name, subscript)| {
+            !name.is_empty()
+                && name.chars().all(char::is_alphabetic)
+                && !subscript.is_empty()
+                && subscript.chars().all(char::is_alphanumeric)
+        })
+}
+
+/// LaTeX for a variable name: Greek letters as commands and a subscript
+/// in braces, so `θ_0` is `\theta_{0}`. A constant prints as it is read.
+pub fn variable_latex(name: &str) -> String {
+    if let Some(constant) = Constant::from_name(name) {
+        return constant.latex().to_string();
+    }
+    let part = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next().and_then(latex_name), chars.next()) {
+            (Some(latex), None) => latex.to_string(),
+            _ => s.to_string(),
+        }
+    };
+    match name.split_once('_') {
+        Some((base, subscript)) if is_subscripted_identifier(name) => {
+            format!("{}_{{{}}}", part(base), part(subscript))
+        }
+        _ => part(name),
+    }
+}
+
 pub fn latex_name(c: char) -> Option<&'static str> {
     match c {
         'π' => Some("\\pi"),
@@ -241,6 +357,7 @@ pub fn latex_name(c: char) -> Option<&'static str> {
         'Φ' => Some("\\Phi"),
         'Ψ' => Some("\\Psi"),
         'Ω' => Some("\\Omega"),
+        '∞' => Some("\\infty"),
         _ => None,
     }
 }
@@ -257,35 +374,167 @@ pub fn normalize_var(name: &str) -> String {
     name.to_string()
 }
 
-pub struct Tokenizer<'a> {
+/// A peekable character stream that knows the byte offset of the next
+/// character, so tokens can be traced back to the input.
+#[derive(Clone)]
+struct SourceChars<'a> {
     chars: Peekable<Chars<'a>>,
+    offset: usize,
+    /// How many bytes of input anything has read or peeked at, lookahead
+    /// clones included; one past the end once the end has been seen.
+    horizon: Rc<Cell<usize>>,
+}
+
+impl<'a> SourceChars<'a> {
+    fn new(input: &'a str) -> Self {
+        Self::starting_at(input, 0, 0)
+    }
+
+    /// The characters of `input` from byte `offset` on, as if everything
+    /// before had been read and `horizon` bytes seen.
+    fn starting_at(input: &'a str, offset: usize, horizon: usize) -> Self {
+        SourceChars {
+            chars: input[offset..].chars().peekable(),
+            offset,
+            horizon: Rc::new(Cell::new(horizon)),
+        }
+    }
+
+    fn peek(&mut self) -> Option<&char> {
+        let seen = self.offset + self.chars.peek().map_or(1, |c| c.len_utf8());
+        self.see(seen);
+        self.chars.peek()
+    }
+
+    fn see(&self, end: usize) {
+        self.horizon.set(self.horizon.get().max(end));
+    }
+}
+
+impl Iterator for SourceChars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let Some(c) = self.chars.next() else {
+            self.see(self.offset + 1);
+            return None;
+        };
+        self.offset += c.len_utf8();
+        self.see(self.offset);
+        Some(c)
+    }
+}
+
+/// Tokenizer state between two top-level steps, enough to carry on over
+/// any input that agrees with the tokenized one on its first `horizon`
+/// bytes (see [`IncrementalParser`](crate::incremental::IncrementalParser)).
+#[derive(Clone, Debug)]
+pub(crate) struct TokenizerCheckpoint {
+    /// Byte offset of the next character
+    pub(crate) offset: usize,
+    /// Bytes read or peeked at so far
+    pub(crate) horizon: usize,
+    pub(crate) tokens: usize,
+    pub(crate) errors: usize,
+    /// The last token and its span, which the next step may rewrite
+    /// (`1.2\overline{3}` replaces `1.2`)
+    pub(crate) last: Option<(String, Range<usize>)>,
+    bare_abs_depth: u32,
+}
+
+pub struct Tokenizer<'a> {
+    chars: SourceChars<'a>,
     pub errors: Vec<String>,
+    /// Byte range of the input each token came from, parallel to the
+    /// output of [`Tokenizer::tokenize`]. Tokens synthesized from one
+    /// construct (`\frac{a}{b}` → `( a ) / ( b )`) share its range.
+    pub spans: Vec<Range<usize>>,
+    /// Byte range of the construct that raised each entry of `errors`
+    pub error_spans: Vec<Range<usize>>,
 }
 
 impl<'a> Tokenizer<'a> {
     /// Create a new instance of Tokenizer with input expression
     pub fn new(input: &'a str) -> Self {
         Self {
-            chars: input.chars().peekable(),
+            chars: SourceChars::new(input),
             errors: Vec::new(),
+            spans: Vec::new(),
+            error_spans: Vec::new(),
         }
     }
 
     /// Tokenize the input string into individual tokens
     pub fn tokenize(&mut self) -> Vec<String> {
-        let mut tokens = Vec::new();
-        let mut current_token = String::new();
-        let mut last_token: Option<String> = None;
+        self.run(Vec::new(), 0, None)
+    }
+
+    /// Tokenize `input` from `checkpoint` on, given the `tokens`, `spans`
+    /// and `errors` of an earlier input up to that checkpoint (already cut
+    /// back to it), recording a checkpoint before every step.
+    pub(crate) fn resume(
+        input: &'a str,
+        checkpoint: &TokenizerCheckpoint,
+        tokens: Vec<String>,
+        spans: Vec<Range<usize>>,
+        errors: (Vec<String>, Vec<Range<usize>>),
+        checkpoints: &mut Vec<TokenizerCheckpoint>,
+    ) -> (Vec<String>, Self) {
+        let mut tokenizer = Self {
+            chars: SourceChars::starting_at(input, checkpoint.offset, checkpoint.horizon),
+            errors: errors.0,
+            spans,
+            error_spans: errors.1,
+        };
+        let tokens = tokenizer.run(tokens, checkpoint.bare_abs_depth, Some(checkpoints));
+        (tokens, tokenizer)
+    }
+
+    /// The checkpoint at the very start of any input.
+    pub(crate) fn initial_checkpoint() -> TokenizerCheckpoint {
+        TokenizerCheckpoint {
+            offset: 0,
+            horizon: 0,
+            tokens: 0,
+            errors: 0,
+            last: None,
+            bare_abs_depth: 0,
+        }
+    }
+
+    fn run(
+        &mut self,
+        mut tokens: Vec<String>,
         // Bare '|' is ambiguous (opener and closer look identical). Depth
         // plus token position disambiguates: at depth 0, or wherever an
         // operand is expected (after an operator or an opener), a bare '|'
         // opens; otherwise it closes. This reads |x|, 2|x|, ||x||, |x+|y||,
         // and |a|b|c| all according to convention. Pathological cases can
         // always use the explicit \left| ... \right| form.
-        let mut bare_abs_depth: u32 = 0;
-
-        while let Some(c) = self.chars.next() {
-            if c.is_whitespace() {
+        mut bare_abs_depth: u32,
+        mut checkpoints: Option<&mut Vec<TokenizerCheckpoint>>,
+    ) -> Vec<String> {
+        let mut current_token = String::new();
+        let mut last_token: Option<String> = tokens.last().cloned();
+
+        loop {
+            let start = self.chars.offset;
+            if let Some(checkpoints) = checkpoints.as_deref_mut() {
+                checkpoints.push(TokenizerCheckpoint {
+                    offset: start,
+                    horizon: self.chars.horizon.get(),
+                    tokens: tokens.len(),
+                    errors: self.errors.len(),
+                    last: tokens.last().cloned().zip(self.spans.last().cloned()),
+                    bare_abs_depth,
+                });
+            }
+            let pushed_before = tokens.len();
+            let Some(c) = self.chars.next() else {
+                break;
+            };
+            // `~` is LaTeX's non-breaking space
+            if c.is_whitespace() || c == '~' {
                 continue; // Skip whitespace
             }
 
@@ -300,7 +549,12 @@ impl<'a> Tokenizer<'a> {
             }
             // Handle LaTeX commands
             else if c == '\\' {
-                self.tokenize_latex_commands(&mut tokens, &mut current_token);
+                // `\|` and `\Vert` are norm bars, read like bare `|`
+                if self.consume_bar_command() {
+                    push_bar(&mut tokens, last_token.as_deref(), &mut bare_abs_depth);
+                } else {
+                    self.tokenize_latex_commands(&mut tokens, &mut current_token);
+                }
             }
             // Handle operators and parentheses
             else if "+*/(){}".contains(c) {
@@ -341,14 +595,25 @@ impl<'a> Tokenizer<'a> {
             }
             // Handle alphabetic variables like x, y, etc.
             else if c.is_alphabetic() {
+                let after_operand = !token_expects_operand(last_token.as_deref());
+                let mut implicit_mul = false;
                 if let Some(last) = last_token.as_ref() {
                     if needs_implicit_mul_after_token(last, &tokens) {
                         tokens.push("*".to_string());
+                        implicit_mul = true;
                     }
                 }
                 current_token.push(c);
                 self.tokenize_variable_or_function(&mut tokens, &mut current_token);
                 current_token.clear();
+                // `a mod b` without the backslash, as typed in the REPL
+                if after_operand && tokens.last().is_some_and(|t| t == "mod") {
+                    tokens.pop();
+                    if implicit_mul {
+                        tokens.pop();
+                    }
+                    tokens.push("MOD".to_string());
+                }
             }
             // Special handling for minus '-'
             else if c == '-' {
@@ -357,8 +622,8 @@ impl<'a> Tokenizer<'a> {
             // Argument separator. Previously dropped silently, which
             // merged function arguments: \max(2, -1) tokenized as
             // max(2 - 1) — a wrong VALUE, not an error. The token opens a
-            // unary-minus context; the parser discards it after
-            // shunting-yard sees operand adjacency.
+            // unary-minus context; the parser splits argument lists on
+            // it.
             else if c == ',' {
                 if !current_token.is_empty() {
                     tokens.push(current_token.clone());
@@ -366,13 +631,41 @@ impl<'a> Tokenizer<'a> {
                 }
                 tokens.push(",".to_string());
             }
-            // Postfix factorial: 5!, (n+1)!
+            // Postfix factorial: 5!, (n+1)!; where an operand is expected,
+            // logical not: !(x > 0)
             else if c == '!' {
                 if !current_token.is_empty() {
                     tokens.push(current_token.clone());
                     current_token.clear();
                 }
-                tokens.push("FACT".to_string());
+                if token_expects_operand(last_token.as_deref()) {
+                    tokens.push("NOT".to_string());
+                } else {
+                    tokens.push("FACT".to_string());
+                }
+            }
+            // History reference: %1, %2, … names an earlier session result.
+            // A '%' without digits is kept as its own token so the parser
+            // rejects it instead of the character vanishing.
+            else if c == '%' {
+                if !current_token.is_empty() {
+                    tokens.push(current_token.clone());
+                    current_token.clear();
+                }
+                if let Some(last) = last_token.as_ref() {
+                    if needs_implicit_mul_after_token(last, &tokens) {
+                        tokens.push("*".to_string());
+                    }
+                }
+                let mut label = String::from("%");
+                while let Some(&d) = self.chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    label.push(d);
+                    self.chars.next();
+                }
+                tokens.push(label);
             }
             // Bare absolute value bars: |x|. Previously these characters
             // were silently DROPPED (|x| tokenized as x) — and since the
@@ -384,22 +677,38 @@ impl<'a> Tokenizer<'a> {
                     current_token.clear();
                 }
                 let expects_operand = token_expects_operand(last_token.as_deref());
-                if bare_abs_depth > 0 && !expects_operand {
-                    tokens.push("ABS_END".to_string());
-                    bare_abs_depth -= 1;
+                // Logical or: `||` after an operand, outside any bars
+                if bare_abs_depth == 0 && !expects_operand && self.chars.peek() == Some(&'|') {
+                    self.chars.next();
+                    tokens.push("||".to_string());
                 } else {
-                    // Implicit multiplication: 2|x|, x|y|, (a+b)|x|, |x||y|
-                    if let Some(last) = last_token.as_ref() {
-                        if last == "ABS_END" || needs_implicit_mul_after_token(last, &tokens) {
-                            tokens.push("*".to_string());
-                        }
-                    }
-                    tokens.push("ABS_START".to_string());
-                    bare_abs_depth += 1;
+                    push_bar(&mut tokens, last_token.as_deref(), &mut bare_abs_depth);
+                }
+            }
+            // Anything else has no meaning here; skipping it would parse
+            // `1 @ 2` as if it were `1 2`
+            else {
+                self.errors.push(format!("Unexpected character '{}'", c));
+            }
+
+            // A subscript names a new variable: x_1, \theta_{0}, \text{v}_{max}
+            if tokens.len() > pushed_before
+                && tokens
+                    .last()
+                    .is_some_and(|t| is_variable_token(t) && !t.contains('_'))
+            {
+                if let Some(subscript) = self.read_subscript() {
+                    let name = tokens.last_mut().expect("checked above");
+                    name.push('_');
+                    name.push_str(&subscript);
                 }
             }
 
             last_token = tokens.last().cloned();
+            let span = start..self.chars.offset;
+            self.spans.truncate(tokens.len());
+            self.spans.resize(tokens.len(), span.clone());
+            self.error_spans.resize(self.errors.len(), span);
         }
 
         tokens
@@ -433,6 +742,20 @@ impl<'a> Tokenizer<'a> {
                 current_token.clear();
                 return;
             }
+            if next_char == '\\' {
+                self.chars.next(); // consume the second backslash
+                tokens.push(MATRIX_ROW.to_string());
+                current_token.clear();
+                return;
+            }
+            // `\{`, `\#`, …: kept whole so the parser can name what it
+            // rejects
+            if !next_char.is_alphabetic() {
+                self.chars.next();
+                tokens.push(format!("\\{}", next_char));
+                current_token.clear();
+                return;
+            }
         }
 
         while let Some(&next_char) = self.chars.peek() {
@@ -443,6 +766,14 @@ impl<'a> Tokenizer<'a> {
                 break;
             }
         }
+        // A registered function name may end in a digit: `\atan2`
+        if let Some(&digit) = self.chars.peek().filter(|c| c.is_ascii_digit()) {
+            let candidate = format!("{}{}", &current_token[1..], digit);
+            if FUNCTION_REGISTRY.get(&candidate).is_some() {
+                current_token.push(digit);
+                self.chars.next();
+            }
+        }
 
         let stripped_token = current_token.trim_start_matches('\\').to_string();
 
@@ -451,7 +782,19 @@ impl<'a> Tokenizer<'a> {
             let needs_mul = needs_implicit_mul_after_token(last, tokens);
             let is_value_producing = is_trig_or_hyperbolic(&stripped_token)
                 || is_log_or_exp(&stripped_token)
-                || matches!(stripped_token.as_str(), "sqrt" | "frac" | "binom")
+                || matches!(
+                    stripped_token.as_str(),
+                    "sqrt"
+                        | "frac"
+                        | "binom"
+                        | "begin"
+                        | "mathrm"
+                        | "mathit"
+                        | "text"
+                        | "textrm"
+                        | "textit"
+                        | "vec"
+                )
                 || greek_letter(&stripped_token).is_some();
             if needs_mul && is_value_producing {
                 tokens.push("*".to_string());
@@ -495,21 +838,94 @@ impl<'a> Tokenizer<'a> {
         }
 
         match stripped_token.as_str() {
-            "mathrm" => {
-                if let Some('{') = self.chars.peek() {
-                    self.chars.next();
-                    if let Some('e') = self.chars.peek() {
-                        tokens.push("e".to_string());
-                        self.chars.next();
-                        if let Some('}') = self.chars.peek() {
+            // Named identifiers: \text{rate}, \mathit{speed}, \mathrm{e}.
+            // The braced name becomes one variable token, so multi-letter
+            // names never depend on how bare letters happen to group.
+            // \operatorname{atan} reads as the built-in function,
+            // \operatorname{GoldenRatio} as the constant and
+            // \operatorname{foo} as a function the parser resolves.
+            "mathrm" | "mathit" | "text" | "textrm" | "textit" | "operatorname" => {
+                self.skip_whitespace_chars();
+                if self.chars.peek() != Some(&'{') {
+                    self.errors
+                        .push(format!("\\{} requires a braced name.", stripped_token));
+                    return;
+                }
+                self.chars.next();
+                let Some(name) = self.consume_brace_group() else {
+                    self.errors
+                        .push(format!("\\{}: unclosed name.", stripped_token));
+                    return;
+                };
+                let name = name.trim();
+                if name.is_empty() || !name.chars().all(char::is_alphabetic) {
+                    self.errors.push(format!(
+                        "\\{}{{{}}}: identifier names must be letters only.",
+                        stripped_token, name
+                    ));
+                    return;
+                }
+                if stripped_token == "operatorname"
+                    && FUNCTION_REGISTRY.get(name).is_none()
+                    && Constant::from_name(name).is_none()
+                {
+                    tokens.push(format!("{}{}", OPERATOR_PREFIX, name));
+                } else {
+                    tokens.push(name.to_string());
+                }
+            }
+            "cdot" => {
+                tokens.push("*".to_string());
+            }
+            // `a \bmod b`; after an operator, `\mod(a, b)` in function form
+            "bmod" | "mod" => {
+                tokens.push("MOD".to_string());
+            }
+            // Logical connectives, the same tokens as `&&`, `||` and `!`
+            "land" | "wedge" => {
+                tokens.push("&&".to_string());
+            }
+            "lor" | "vee" => {
+                tokens.push("||".to_string());
+            }
+            "lnot" | "neg" => {
+                tokens.push("NOT".to_string());
+            }
+            // A product like `*`, or the cross product between vectors
+            "times" => {
+                tokens.push("CROSS".to_string());
+            }
+            // \vec{a}, \vec a, \vec{\omega}: one token naming the vector
+            "vec" => {
+                self.skip_whitespace_chars();
+                let name = match self.chars.next() {
+                    Some('{') => self.consume_brace_group(),
+                    Some('\\') => {
+                        let mut command = String::from("\\");
+                        while let Some(c) = self.chars.peek().filter(|c| c.is_alphabetic()) {
+                            command.push(*c);
                             self.chars.next();
                         }
+                        Some(command)
                     }
+                    Some(c) if c.is_alphabetic() => Some(c.to_string()),
+                    _ => None,
+                };
+                let name =
+                    name.map(|n| n.trim().to_string())
+                        .and_then(|n| match n.strip_prefix('\\') {
+                            Some(command) => greek_letter(command).map(String::from),
+                            None => {
+                                (!n.is_empty() && n.chars().all(char::is_alphabetic)).then_some(n)
+                            }
+                        });
+                match name {
+                    Some(name) => tokens.push(format!("{}{}", VECTOR_PREFIX, name)),
+                    None => self
+                        .errors
+                        .push("\\vec needs a name, e.g. \\vec{a}.".to_string()),
                 }
             }
-            "cdot" | "times" => {
-                tokens.push("*".to_string());
-            }
             "div" => {
                 tokens.push("/".to_string());
             }
@@ -567,6 +983,10 @@ impl<'a> Tokenizer<'a> {
             }
             "frac" => {
                 current_token.clear();
+                // A fraction as a script (`x^\frac{1}{2}`, `\sum_{…}^\frac{n}{2}`)
+                // is one operand, so it is grouped like a braced script
+                let script_start = matches!(tokens.last().map(String::as_str), Some("^" | "_"))
+                    .then_some(tokens.len());
                 if let Some(&next_char) = self.chars.peek() {
                     if next_char.is_ascii_digit() {
                         self.tokenize_shorthand_fraction(tokens);
@@ -585,21 +1005,16 @@ impl<'a> Tokenizer<'a> {
                                 if let Some(denom_str) = self.consume_brace_group() {
                                     let nt = numer_str.trim();
                                     let dt = denom_str.trim();
-                                    if nt == "d"
-                                        && dt.starts_with('d')
-                                        && dt[1..].chars().all(|c| c.is_alphabetic())
-                                    {
-                                        self.errors.push(format!(
-                                            "Leibniz derivative notation \\frac{{d}}{{{}}} is not supported as an expression. Use the 'differentiate' tool instead.",
-                                            dt
-                                        ));
-                                        return;
-                                    }
-                                    if nt == "\\partial" && dt.starts_with("\\partial") {
-                                        self.errors.push(format!(
-                                            "Partial derivative notation \\frac{{\\partial}}{{{}}} is not supported as an expression. Use the 'differentiate' tool instead.",
-                                            dt
-                                        ));
+                                    if let Some(var) = leibniz_variable(nt, dt) {
+                                        match var {
+                                            Ok(var) => tokens.extend([
+                                                "DERIV".to_string(),
+                                                "{".to_string(),
+                                                var,
+                                                "}".to_string(),
+                                            ]),
+                                            Err(e) => self.errors.push(e),
+                                        }
                                         return;
                                     }
                                     let numer_tokens = Tokenizer::new(&numer_str).tokenize();
@@ -616,6 +1031,10 @@ impl<'a> Tokenizer<'a> {
                         }
                     }
                 }
+                if let Some(start) = script_start.filter(|&start| tokens.len() > start) {
+                    tokens.insert(start, "(".to_string());
+                    tokens.push(")".to_string());
+                }
             }
             "binom" => {
                 current_token.clear();
@@ -650,27 +1069,32 @@ impl<'a> Tokenizer<'a> {
                 };
                 let numer_tokens = Tokenizer::new(&numer_str).tokenize();
                 let denom_tokens = Tokenizer::new(&denom_str).tokenize();
-                tokens.push("(".to_string());
+                tokens.push("binom".to_string());
+                tokens.push("{".to_string());
                 tokens.extend(numer_tokens);
-                tokens.push(")".to_string());
-                tokens.push("(".to_string());
+                tokens.push("}".to_string());
+                tokens.push("{".to_string());
                 tokens.extend(denom_tokens);
-                tokens.push(")".to_string());
-                tokens.push("binom".to_string());
+                tokens.push("}".to_string());
             }
-            // Handle absolute value delimiters \left| and \right|
+            // Handle absolute value and norm delimiters \left| … \right|,
+            // \left\| … \right\| and \left\Vert … \right\Vert
             "left" => {
-                if let Some('|') = self.chars.peek() {
+                if self.consume_left_right_bar() {
                     tokens.push("ABS_START".to_string());
-                    self.chars.next(); // Consume the '|'
                 }
             }
             "right" => {
-                if let Some('|') = self.chars.peek() {
+                if self.consume_left_right_bar() {
                     tokens.push("ABS_END".to_string());
-                    self.chars.next(); // Consume the '|'
                 }
             }
+            "lvert" | "lVert" => {
+                tokens.push("ABS_START".to_string());
+            }
+            "rvert" | "rVert" => {
+                tokens.push("ABS_END".to_string());
+            }
             "lfloor" => {
                 tokens.push("FLOOR_START".to_string());
             }
@@ -797,10 +1221,44 @@ impl<'a> Tokenizer<'a> {
                     tokens.push("sqrt".to_string());
                 }
             }
+            // Matrix environments: \begin{vmatrix} a & b \\ c & d \end{vmatrix}.
+            // The parser turns the delimited span into a single operand.
+            "begin" | "end" => {
+                self.skip_whitespace_chars();
+                if self.chars.peek() != Some(&'{') {
+                    self.errors.push(format!(
+                        "\\{} requires an environment name.",
+                        stripped_token
+                    ));
+                    return;
+                }
+                self.chars.next();
+                let Some(name) = self.consume_brace_group() else {
+                    self.errors
+                        .push(format!("\\{}: unclosed environment name.", stripped_token));
+                    return;
+                };
+                let name = name.trim();
+                if !MATRIX_ENVIRONMENTS.contains(&name) {
+                    self.errors
+                        .push(format!("Unsupported environment '{}'.", name));
+                    return;
+                }
+                let prefix = if stripped_token == "begin" {
+                    MATRIX_BEGIN_PREFIX
+                } else {
+                    MATRIX_END_PREFIX
+                };
+                tokens.push(format!("{}{}", prefix, name));
+            }
             // LaTeX spacing — silently ignore
             "," | ";" | "!" | ":" | "quad" | "qquad" | "enspace" | "thinspace" => {
                 current_token.clear();
             }
+            // Typesetting that does not change the meaning: `\sum\limits_{k=1}^{n}`
+            "limits" | "nolimits" | "displaystyle" | "textstyle" => {
+                current_token.clear();
+            }
             _ => {
                 if let Some(ch) = greek_letter(&stripped_token) {
                     tokens.push(ch.to_string());
@@ -812,6 +1270,39 @@ impl<'a> Tokenizer<'a> {
         current_token.clear();
     }
 
+    /// Read `_1`, `_{12}`, `_{max}` or `_{\alpha}` following a variable. Any
+    /// other `_` (an expression subscript such as `a_{n+1}`) is left
+    /// unread for the parser to reject.
+    fn read_subscript(&mut self) -> Option<String> {
+        let mut ahead = self.chars.clone();
+        if ahead.next() != Some('_') {
+            return None;
+        }
+        let subscript = match ahead.next()? {
+            c if c.is_alphanumeric() => c.to_string(),
+            '{' => {
+                let mut text = String::new();
+                for c in ahead.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    text.push(c);
+                }
+                let text = text.trim();
+                match text.strip_prefix('\\').and_then(greek_letter) {
+                    Some(letter) => letter.to_string(),
+                    None if !text.is_empty() && text.chars().all(char::is_alphanumeric) => {
+                        text.to_string()
+                    }
+                    None => return None,
+                }
+            }
+            _ => return None,
+        };
+        self.chars = ahead;
+        Some(subscript)
+    }
+
     fn consume_brace_group(&mut self) -> Option<String> {
         let mut depth = 1;
         let mut content = String::new();
@@ -866,6 +1357,45 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    /// Consume the rest of a bar command whose backslash was just read:
+    /// `\|`, `\vert` or `\Vert`.
+    fn consume_bar_command(&mut self) -> bool {
+        if self.chars.peek() == Some(&'|') {
+            self.chars.next();
+            return true;
+        }
+        let mut probe = self.chars.clone();
+        let mut cmd = String::new();
+        while probe.peek().is_some_and(|c| c.is_alphabetic()) {
+            cmd.push(probe.next().unwrap());
+        }
+        if matches!(cmd.as_str(), "vert" | "Vert") {
+            self.chars = probe;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume the bar after `\left` or `\right`: `|` or a bar command.
+    fn consume_left_right_bar(&mut self) -> bool {
+        if self.chars.peek() == Some(&'|') {
+            self.chars.next();
+            return true;
+        }
+        let mut probe = self.chars.clone();
+        if probe.next() != Some('\\') {
+            return false;
+        }
+        let before = std::mem::replace(&mut self.chars, probe);
+        if self.consume_bar_command() {
+            true
+        } else {
+            self.chars = before;
+            false
+        }
+    }
+
     /// Consume `\name` when `name` matches; leaves cursor after the command name.
     fn try_consume_latex_command(&mut self, name: &str) -> bool {
         self.skip_whitespace_chars();
@@ -1045,32 +1575,59 @@ impl<'a> Tokenizer<'a> {
     }
 }
 
+/// Push a bare bar: it closes the innermost open bar when an operator
+/// could come next, and opens one otherwise.
+fn push_bar(tokens: &mut Vec<String>, last_token: Option<&str>, bare_abs_depth: &mut u32) {
+    if *bare_abs_depth > 0 && !token_expects_operand(last_token) {
+        tokens.push("ABS_END".to_string());
+        *bare_abs_depth -= 1;
+        return;
+    }
+    // Implicit multiplication: 2|x|, x|y|, (a+b)|x|, |x||y|
+    if let Some(last) = last_token {
+        if last == "ABS_END" || needs_implicit_mul_after_token(last, tokens) {
+            tokens.push("*".to_string());
+        }
+    }
+    tokens.push("ABS_START".to_string());
+    *bare_abs_depth += 1;
+}
+
 /// Does an operand (not an operator) come next after this token? The
 /// single source of truth for unary-minus contexts and bare-|x| closing
 /// decisions.
 fn token_expects_operand(last_token: Option<&str>) -> bool {
     match last_token {
         None => true,
-        Some(last) => matches!(
-            last,
-            "+" | "-"
-                | "*"
-                | "/"
-                | "^"
-                | "="
-                | "=="
-                | "<"
-                | ">"
-                | "<="
-                | ">="
-                | "("
-                | "{"
-                | ","
-                | "NEG"
-                | "ABS_START"
-                | "FLOOR_START"
-                | "CEIL_START"
-        ),
+        Some(last) => {
+            matches!(
+                last,
+                "+" | "-"
+                    | "*"
+                    | "CROSS"
+                    | "MOD"
+                    | "/"
+                    | "^"
+                    | "="
+                    | "=="
+                    | "<"
+                    | ">"
+                    | "<="
+                    | ">="
+                    | "("
+                    | "{"
+                    | ","
+                    | "NEG"
+                    | "ABS_START"
+                    | "FLOOR_START"
+                    | "CEIL_START"
+                    | "&"
+                    | "&&"
+                    | "||"
+                    | "NOT"
+                    | MATRIX_ROW
+            ) || last.starts_with(MATRIX_BEGIN_PREFIX)
+        }
     }
 }
 
@@ -1120,7 +1677,7 @@ mod tests {
     fn test_tokenize_latex_times() {
         let mut tokenizer = Tokenizer::new(r"4 \times 2");
         let tokens = tokenizer.tokenize();
-        assert_eq!(tokens, vec!["4", "*", "2"]);
+        assert_eq!(tokens, vec!["4", "CROSS", "2"]);
     }
 
     #[test]
@@ -1149,7 +1706,7 @@ mod tests {
         let mut tokenizer = Tokenizer::new("\\binom{5}{2}");
         let tokens = tokenizer.tokenize();
         assert!(tokenizer.errors.is_empty());
-        assert_eq!(tokens, vec!["(", "5", ")", "(", "2", ")", "binom"]);
+        assert_eq!(tokens, vec!["binom", "{", "5", "}", "{", "2", "}"]);
     }
 
     #[test]
@@ -1259,11 +1816,47 @@ mod tests {
         assert_eq!(tokens, vec!["5", ">", "3", "&&", "4", "<=", "10"]);
     }
 
+    #[test]
+    fn test_tokenize_logical_operators() {
+        let tokens = Tokenizer::new("a || !b").tokenize();
+        assert_eq!(tokens, vec!["a", "||", "NOT", "b"]);
+        let tokens = Tokenizer::new("a \\lor \\lnot b \\land c").tokenize();
+        assert_eq!(tokens, vec!["a", "||", "NOT", "b", "&&", "c"]);
+        // Adjacent bars still close and open absolute values
+        let tokens = Tokenizer::new("|x||y|").tokenize();
+        assert_eq!(
+            tokens,
+            vec![
+                "ABS_START",
+                "x",
+                "ABS_END",
+                "*",
+                "ABS_START",
+                "y",
+                "ABS_END"
+            ]
+        );
+        // `!` after an operand stays a factorial
+        let tokens = Tokenizer::new("n! > 1").tokenize();
+        assert_eq!(tokens, vec!["n", "FACT", ">", "1"]);
+    }
+
     #[test]
     fn test_tokenize_absolute_value() {
         let mut tokenizer = Tokenizer::new("\\left|x + 3\\right|");
         let tokens = tokenizer.tokenize();
         assert_eq!(tokens, vec!["ABS_START", "x", "+", "3", "ABS_END"]);
+        for norm in [
+            "\\|x\\|",
+            "\\left\\|x\\right\\|",
+            "\\Vert x\\Vert",
+            "\\lVert x\\rVert",
+        ] {
+            assert_eq!(
+                Tokenizer::new(norm).tokenize(),
+                vec!["ABS_START", "x", "ABS_END"]
+            );
+        }
     }
 
     #[test]
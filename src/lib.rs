@@ -2,35 +2,58 @@
 
 pub mod foundation {
     pub mod assumptions;
+    pub mod ast_json;
+    pub mod constants;
     pub mod environment;
+    pub mod error;
     pub mod exact;
     pub mod integer;
+    pub mod intern;
     pub mod node;
+    pub mod progress;
+    pub mod special;
+    pub mod tree_path;
+    pub mod visit;
 }
 
 pub mod language {
+    pub mod asciimath;
+    pub mod domains;
+    pub mod extension;
+    pub mod format;
     pub(crate) mod function_meta;
     pub mod functions;
+    pub mod incremental;
+    pub mod latex;
     pub mod parser;
+    pub mod plain;
+    pub mod sharing;
     pub mod tokenizer;
 }
 
 pub mod math {
     pub mod algebra {
         pub mod algebraic;
+        pub mod decomposition;
         pub mod ext_poly;
+        pub mod factor;
         pub mod matrix;
         pub mod mod_poly;
         pub mod multipoly;
         pub mod partial_fractions;
         pub mod polynomial;
+        pub mod quaternion;
         pub mod rational_function;
+        pub mod vector;
     }
 
     pub mod transform {
+        pub mod compile;
         pub mod composition;
         pub mod error_eval;
         pub mod evaluator;
+        pub(crate) mod like_terms;
+        pub mod rewrite;
         pub mod simplify;
         pub(crate) mod simplify_literal;
         pub mod substitute;
@@ -39,57 +62,107 @@ pub mod math {
     pub mod calculus {
         pub mod derivative;
         pub mod fps;
+        pub mod infinite_series;
         pub mod integration;
         pub mod limits;
+        pub mod piecewise;
+        pub mod plot;
+        pub mod probability;
         pub mod risch;
         pub mod series;
         pub mod special_functions;
+        pub mod symmetry;
     }
 
     pub mod solving {
+        pub mod domain;
         pub mod expression;
         pub mod inequality;
+        pub mod ivp;
         pub mod ode;
+        pub mod roots;
         pub mod systems;
     }
 }
 
 pub mod validation {
     pub mod chain;
+    pub mod consistency;
+    #[cfg(feature = "golden")]
+    pub mod golden;
+    pub mod problem_set;
     pub mod status;
     pub mod verify;
 }
 
 pub mod interface {
+    pub mod session;
     pub mod wasm_bindings;
 }
 
 // Flat re-exports — preserve existing `crate::` / `arithma::` paths.
 
 pub(crate) use language::function_meta;
+pub(crate) use math::transform::like_terms;
 pub(crate) use math::transform::simplify_literal;
 
 pub use foundation::assumptions;
 pub use foundation::assumptions::Assumptions;
+pub use foundation::ast_json;
+pub use foundation::constants;
+pub use foundation::constants::Constant;
 pub use foundation::environment;
-pub use foundation::environment::Environment;
+pub use foundation::environment::{Environment, UserFunction};
+pub use foundation::error;
+pub use foundation::error::ArithmaError;
 pub use foundation::exact;
 pub use foundation::exact::ExactNum;
 pub use foundation::integer;
 pub use foundation::integer::{
-    as_non_negative_integer, binom, extract_square_factors, factorial, gcd, lcm,
-    parse_non_negative_integer, prime_factorize, prime_factorize_latex,
+    as_non_negative_integer, binom, extract_square_factors, factorial, gcd, is_prime, lcm,
+    parse_non_negative_integer, permutations, powmod, prime_factorize, prime_factorize_latex,
+    prime_factorize_node,
 };
+pub use foundation::intern;
+pub use foundation::intern::{NodeArena, NodeId};
 pub use foundation::node;
 pub use foundation::node::Node;
+pub use foundation::progress;
+pub use foundation::progress::{with_progress, Progress};
+pub use foundation::special;
+pub use foundation::tree_path;
+pub use foundation::tree_path::{apply_edits, diff_trees, TreeEdit};
+pub use foundation::visit;
 
+pub use language::asciimath;
+pub use language::asciimath::parse_asciimath;
+pub use language::domains;
+pub use language::domains::{principal_branch, DomainPolicy, PrincipalBranch};
+pub use language::extension;
+pub use language::extension::{ParserExtension, ParserExtensions};
+pub use language::format;
+pub use language::format::{FormatOptions, MulSymbol, NumberStyle};
 pub use language::functions;
 pub use language::functions::FUNCTION_REGISTRY;
+pub use language::incremental;
+pub use language::incremental::IncrementalParser;
 pub use language::parser;
-pub use language::parser::{build_expression_tree, parse_latex, parse_latex_raw, shunting_yard};
+pub use language::parser::{
+    build_expression_tree, build_expression_tree_with_options, parse_latex, parse_latex_raw,
+    parse_latex_raw_with_env, parse_latex_raw_with_extensions, parse_latex_raw_with_options,
+    CommandInput, ParseError, ParseLimit, ParserOptions,
+};
+pub use language::plain;
+pub use language::plain::parse_plain;
+pub use language::sharing;
+pub use language::sharing::{
+    share_subexpressions, share_subexpressions_with, SharedExpression, SharingOptions,
+};
 pub use language::tokenizer;
 pub use language::tokenizer::Tokenizer;
 
+pub use math::transform::compile;
+pub use math::transform::compile::CompiledExpr;
 pub use math::transform::composition;
 pub use math::transform::composition::{compose, compose_latex, compose_multiple};
 pub use math::transform::error_eval;
@@ -98,15 +171,21 @@ pub use math::transform::error_eval::{
 };
 pub use math::transform::evaluator;
 pub use math::transform::evaluator::Evaluator;
+pub use math::transform::rewrite;
+pub use math::transform::rewrite::{apply_rules, Rule, RuleSet};
 pub use math::transform::simplify;
 pub use math::transform::substitute;
 pub use math::transform::substitute::{substitute, substitute_latex};
 
 pub use math::algebra::algebraic;
+pub use math::algebra::decomposition;
+pub use math::algebra::decomposition::{EigenDecomposition, Eigenpair, LuDecomposition};
 pub use math::algebra::ext_poly;
 pub use math::algebra::ext_poly::ExtPoly;
+pub use math::algebra::factor;
+pub use math::algebra::factor::{factor, factor_latex, factor_polynomial, Factorization};
 pub use math::algebra::matrix;
-pub use math::algebra::matrix::{parse_latex_matrix, Matrix};
+pub use math::algebra::matrix::{parse_latex_matrix, Matrix, RowOp};
 pub use math::algebra::mod_poly;
 pub use math::algebra::mod_poly::{factor_mod_p, factor_over_q, ModPoly};
 pub use math::algebra::multipoly;
@@ -116,26 +195,44 @@ pub use math::algebra::partial_fractions::{
     partial_fraction_decomposition, partial_fractions_latex,
 };
 pub use math::algebra::polynomial;
-pub use math::algebra::polynomial::Polynomial;
+pub use math::algebra::polynomial::{poly_div, poly_div_latex, Polynomial};
+pub use math::algebra::quaternion;
+pub use math::algebra::quaternion::{parse_latex_quaternion, Quaternion};
 pub use math::algebra::rational_function;
 pub use math::algebra::rational_function::RationalFunction;
+pub use math::algebra::vector;
+pub use math::algebra::vector::{parse_latex_vector, Vector};
 
 pub use math::calculus::derivative;
 pub use math::calculus::derivative::{
-    differentiate, differentiate_and_evaluate, differentiate_latex, partial_derivative,
+    differentiate, differentiate_and_evaluate, differentiate_latex, differentiate_many,
+    differentiate_numeric, differentiate_or_unevaluated, partial_derivative,
 };
 pub use math::calculus::fps;
 pub use math::calculus::fps::FormalPowerSeries;
+pub use math::calculus::infinite_series;
+pub use math::calculus::infinite_series::{
+    sum_infinite_series, SeriesAcceleration, SeriesOptions, SeriesSum,
+};
 pub use math::calculus::integration;
 pub use math::calculus::integration::{
     definite_integral, definite_integral_exact, definite_integral_exact_latex,
-    definite_integral_latex, integrate, integrate_latex, integrate_outcome, IntegralOutcome,
+    definite_integral_latex, definite_integral_with_bounds, improper_numeric_integral, integrate,
+    integrate_latex, integrate_many, integrate_or_unevaluated, integrate_outcome, IntegralOutcome,
 };
 pub use math::calculus::limits;
 pub use math::calculus::limits::{
     compute_limit, compute_limit_directed, compute_limit_general, limit_latex, limit_latex_str,
     LimitDirection, LimitPoint, LimitResult,
 };
+pub use math::calculus::piecewise;
+pub use math::calculus::piecewise::{breakpoints, integrate_by_pieces};
+pub use math::calculus::plot;
+pub use math::calculus::plot::PlotData;
+pub use math::calculus::probability;
+pub use math::calculus::probability::{
+    expectation_latex, parse_latex_distribution, variance_latex, DiscreteDistribution,
+};
 pub use math::calculus::risch;
 pub use math::calculus::risch::{
     build_tower, hermite_reduce, try_risch_tower, DifferentialExtension, HermiteResult, RischResult,
@@ -147,25 +244,47 @@ pub use math::calculus::series::{
 };
 pub use math::calculus::special_functions;
 pub use math::calculus::special_functions::SpecialAntiderivative;
+pub use math::calculus::symmetry;
+pub use math::calculus::symmetry::{detect_period, is_even_function, is_odd_function, Parity};
 
+pub use math::solving::domain;
+pub use math::solving::domain::domain_of;
 pub use math::solving::expression;
 pub use math::solving::expression::{
-    solve_for_variable, solve_for_variable_exact, solve_for_variable_nodes, solve_full, SolveResult,
+    solve_for_variable, solve_for_variable_exact, solve_for_variable_general,
+    solve_for_variable_nodes, solve_full, SolveResult,
 };
 pub use math::solving::inequality;
-pub use math::solving::inequality::solve_inequality;
+pub use math::solving::inequality::{solve_inequality, solve_inequality_set, SolutionSet};
+pub use math::solving::ivp;
+pub use math::solving::ivp::{
+    solve_ivp, solve_ivp_latex, solve_ivp_nodes, OdeMethod, OdeOptions, OdeSolution,
+};
 pub use math::solving::ode;
 pub use math::solving::ode::{
-    solve_constant_coeff, solve_constant_coeff_latex, solve_ode_latex, solve_series,
-    solve_series_ivp,
+    matrix_exponential, solve_constant_coeff, solve_constant_coeff_latex,
+    solve_constant_coeff_system, solve_constant_coeff_system_latex, solve_ode_equation_latex,
+    solve_ode_latex, solve_series, solve_series_ivp,
 };
+pub use math::solving::roots;
+pub use math::solving::roots::{find_root, find_roots_in_interval};
 pub use math::solving::systems;
-pub use math::solving::systems::{solve_linear_system, solve_system, SystemSolution};
+pub use math::solving::systems::{
+    solve_linear_system, solve_system, solve_system_unique, SystemSolution,
+};
 
 pub use validation::chain;
+pub use validation::consistency;
+pub use validation::consistency::{check_derivative, check_fundamental_theorem, ConsistencyReport};
+#[cfg(feature = "golden")]
+pub use validation::golden;
+pub use validation::problem_set;
+pub use validation::problem_set::{run_problem_set, ProblemSetReport};
 pub use validation::status;
 pub use validation::verify;
-pub use validation::verify::verify_identity;
+pub use validation::verify::{are_equivalent, verify_identity};
 
+pub use interface::session;
+pub use interface::session::{Binding, Session, SessionOutcome};
 pub use interface::wasm_bindings;
 pub use interface::wasm_bindings::evaluate_latex_expression_js;
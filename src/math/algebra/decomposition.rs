@@ -0,0 +1,528 @@
+//! Factorizations of numeric matrices.
+//!
+//! Cofactor expansion takes `n!` products, which is fine for a symbolic
+//! 3×3 but hopeless for a numeric 12×12. A matrix whose entries are all
+//! numbers is factored instead, in `O(n³)` operations on [`ExactNum`]s:
+//! rational entries stay exact, and float entries are pivoted on the
+//! largest remaining entry of each column (partial pivoting) to keep the
+//! rounding error small. [`Matrix::determinant`], [`Matrix::inverse`] and
+//! [`Matrix::solve`] go through [`Matrix::lu`] whenever they can.
+//!
+//! [`Matrix::qr`] and [`Matrix::cholesky`] take square roots, so they work
+//! in floating point whatever the entries; factors that come out whole are
+//! exact integers again.
+//!
+//! [`Matrix::eigen_decomposition`] is the symbolic exception: it pairs each
+//! eigenvalue of a matrix up to 3×3, entries like `a` and `b` allowed, with
+//! a basis of its eigenspace, and gives `P` and `D` with `A = PDP⁻¹` when
+//! there are enough eigenvectors.
+
+use crate::environment::Environment;
+use crate::error::ArithmaError;
+use crate::exact::ExactNum;
+use crate::matrix::{is_zero_node, Matrix};
+use crate::node::Node;
+use crate::simplify::Simplifiable;
+use crate::vector::Vector;
+
+/// Relative size, against the largest entry of the matrix, below which a
+/// float pivot counts as zero.
+const FLOAT_PIVOT_TOLERANCE: f64 = 1e-12;
+
+/// `PA = LU` for a square matrix `A`: `L` is unit lower triangular, `U`
+/// upper triangular, and `P` the row permutation chosen by pivoting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LuDecomposition {
+    size: usize,
+    /// `L` below the diagonal and `U` on and above it, row-major
+    factors: Vec<ExactNum>,
+    /// Row `i` of `PA` is row `permutation[i]` of `A`
+    permutation: Vec<usize>,
+    /// Whether the permutation is odd
+    odd: bool,
+    /// Whether some column had no nonzero pivot
+    singular: bool,
+}
+
+impl LuDecomposition {
+    /// Factor the `size`×`size` row-major `entries`.
+    pub fn new(size: usize, entries: Vec<ExactNum>) -> Result<Self, ArithmaError> {
+        if size == 0 || entries.len() != size * size {
+            return Err(ArithmaError::DimensionMismatch(format!(
+                "LU decomposition needs a non-empty square matrix, got {} entries for size {}",
+                entries.len(),
+                size
+            )));
+        }
+        let scale = entries.iter().map(|x| x.to_f64().abs()).fold(0.0, f64::max);
+        let mut a = entries;
+        let mut permutation: Vec<usize> = (0..size).collect();
+        let (mut odd, mut singular) = (false, false);
+
+        for k in 0..size {
+            let pivot_row = (k..size)
+                .max_by(|&i, &j| {
+                    let (x, y) = (a[i * size + k].abs(), a[j * size + k].abs());
+                    x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .expect("k < size");
+            if negligible(&a[pivot_row * size + k], scale) {
+                singular = true;
+                continue;
+            }
+            if pivot_row != k {
+                for j in 0..size {
+                    a.swap(k * size + j, pivot_row * size + j);
+                }
+                permutation.swap(k, pivot_row);
+                odd = !odd;
+            }
+            let pivot = a[k * size + k].clone();
+            for i in k + 1..size {
+                let factor = &a[i * size + k] / &pivot;
+                if factor.is_zero() {
+                    a[i * size + k] = factor;
+                    continue;
+                }
+                for j in k + 1..size {
+                    let update = &factor * &a[k * size + j];
+                    a[i * size + j] = &a[i * size + j] - &update;
+                }
+                a[i * size + k] = factor;
+            }
+        }
+
+        Ok(LuDecomposition {
+            size,
+            factors: a,
+            permutation,
+            odd,
+            singular,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the matrix is singular, so that it has no inverse.
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+
+    /// Row `i` of `PA` is row `permutation()[i]` of `A`.
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// The unit lower triangular factor `L`.
+    pub fn lower(&self) -> Matrix {
+        self.triangle(|i, j| match i.cmp(&j) {
+            std::cmp::Ordering::Greater => Some(self.at(i, j).clone()),
+            std::cmp::Ordering::Equal => Some(ExactNum::one()),
+            std::cmp::Ordering::Less => None,
+        })
+    }
+
+    /// The upper triangular factor `U`.
+    pub fn upper(&self) -> Matrix {
+        self.triangle(|i, j| (i <= j).then(|| self.at(i, j).clone()))
+    }
+
+    /// `det A`: the product of the pivots, negated for an odd permutation.
+    pub fn determinant(&self) -> ExactNum {
+        if self.singular {
+            return ExactNum::zero();
+        }
+        let product = (0..self.size).fold(ExactNum::one(), |p, i| p * self.at(i, i).clone());
+        if self.odd {
+            -product
+        } else {
+            product
+        }
+    }
+
+    /// `x` with `Ax = b`, by forward and back substitution.
+    pub fn solve(&self, b: &[ExactNum]) -> Result<Vec<ExactNum>, ArithmaError> {
+        if b.len() != self.size {
+            return Err(ArithmaError::DimensionMismatch(format!(
+                "Right-hand side has {} entries for a {}×{} matrix",
+                b.len(),
+                self.size,
+                self.size
+            )));
+        }
+        if self.singular {
+            return Err(ArithmaError::Domain(
+                "System has no unique solution (singular matrix)".to_string(),
+            ));
+        }
+        let n = self.size;
+        // Ly = Pb
+        let mut y: Vec<ExactNum> = self.permutation.iter().map(|&i| b[i].clone()).collect();
+        for i in 0..n {
+            for j in 0..i {
+                let update = self.at(i, j) * &y[j];
+                y[i] = &y[i] - &update;
+            }
+        }
+        // Ux = y
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                let update = self.at(i, j) * &y[j];
+                y[i] = &y[i] - &update;
+            }
+            y[i] = &y[i] / self.at(i, i);
+        }
+        Ok(y)
+    }
+
+    /// `A⁻¹`, one column at a time.
+    pub fn inverse(&self) -> Result<Matrix, ArithmaError> {
+        if self.singular {
+            return Err(ArithmaError::Domain(
+                "Cannot invert a singular matrix (determinant is zero)".to_string(),
+            ));
+        }
+        let n = self.size;
+        let mut elements = vec![Node::Num(ExactNum::zero()); n * n];
+        for col in 0..n {
+            let unit: Vec<ExactNum> = (0..n)
+                .map(|i| match i == col {
+                    true => ExactNum::one(),
+                    false => ExactNum::zero(),
+                })
+                .collect();
+            for (row, value) in self.solve(&unit)?.into_iter().enumerate() {
+                elements[row * n + col] = Node::Num(value);
+            }
+        }
+        Matrix::new(n, n, elements)
+    }
+
+    fn at(&self, i: usize, j: usize) -> &ExactNum {
+        &self.factors[i * self.size + j]
+    }
+
+    fn triangle(&self, entry: impl Fn(usize, usize) -> Option<ExactNum>) -> Matrix {
+        let n = self.size;
+        let elements = (0..n * n)
+            .map(|k| Node::Num(entry(k / n, k % n).unwrap_or_else(ExactNum::zero)))
+            .collect();
+        Matrix::new(n, n, elements).expect("n×n entries")
+    }
+}
+
+/// An eigenvalue with its algebraic multiplicity and a basis of its
+/// eigenspace, as column vectors.
+#[derive(Clone, Debug)]
+pub struct Eigenpair {
+    pub value: Node,
+    pub multiplicity: usize,
+    pub vectors: Vec<Matrix>,
+}
+
+/// The eigenvalues of a matrix with their eigenspaces.
+#[derive(Clone, Debug)]
+pub struct EigenDecomposition {
+    pub pairs: Vec<Eigenpair>,
+}
+
+impl EigenDecomposition {
+    /// Whether each eigenspace is as large as the multiplicity of its
+    /// eigenvalue, so that the eigenvectors form a basis.
+    pub fn is_diagonalizable(&self) -> bool {
+        self.pairs
+            .iter()
+            .all(|pair| pair.vectors.len() == pair.multiplicity)
+    }
+
+    /// `P`, with the eigenvectors as columns, or `None` when the matrix is
+    /// not diagonalizable.
+    pub fn eigenvector_matrix(&self) -> Option<Matrix> {
+        if !self.is_diagonalizable() {
+            return None;
+        }
+        let columns: Vec<&Matrix> = self.pairs.iter().flat_map(|p| &p.vectors).collect();
+        let n = columns.len();
+        let elements = (0..n * n)
+            .map(|k| columns[k % n].elements[k / n].clone())
+            .collect();
+        Matrix::new(n, n, elements).ok()
+    }
+
+    /// `D`, the eigenvalues on the diagonal in the order of the columns of
+    /// [`eigenvector_matrix`](Self::eigenvector_matrix).
+    pub fn diagonal(&self) -> Option<Matrix> {
+        if !self.is_diagonalizable() {
+            return None;
+        }
+        let values: Vec<&Node> = self
+            .pairs
+            .iter()
+            .flat_map(|p| std::iter::repeat_n(&p.value, p.multiplicity))
+            .collect();
+        let n = values.len();
+        let elements = (0..n * n)
+            .map(|k| match k / n == k % n {
+                true => values[k / n].clone(),
+                false => Node::Num(ExactNum::zero()),
+            })
+            .collect();
+        Matrix::new(n, n, elements).ok()
+    }
+}
+
+impl Matrix {
+    /// Eigenvalues and eigenvectors of a square matrix up to 3×3, with
+    /// symbolic entries allowed. An eigenspace is the null space of
+    /// `A − λI` when row reduction finds it; for an eigenvalue it cannot
+    /// (one with a square root, where the last pivot does not simplify to
+    /// zero) a 2×2 eigenvector is read off a row, `(b, λ − a)`, and a 3×3
+    /// one is the cross product of two rows.
+    pub fn eigen_decomposition(
+        &self,
+        env: &Environment,
+    ) -> Result<EigenDecomposition, ArithmaError> {
+        if !self.is_square() {
+            return Err(ArithmaError::DimensionMismatch(
+                "Cannot compute eigenvectors of a non-square matrix".to_string(),
+            ));
+        }
+        if self.rows > 3 {
+            return Err(ArithmaError::NotImplemented(format!(
+                "Eigen decomposition is supported up to 3×3, got {}×{}",
+                self.rows, self.rows
+            )));
+        }
+        let mut pairs: Vec<Eigenpair> = Vec::new();
+        for value in self.eigenvalues(env)? {
+            match pairs.iter_mut().find(|pair| pair.value == value) {
+                Some(pair) => pair.multiplicity += 1,
+                None => pairs.push(Eigenpair {
+                    value,
+                    multiplicity: 1,
+                    vectors: Vec::new(),
+                }),
+            }
+        }
+        for pair in &mut pairs {
+            pair.vectors = self.eigenspace(&pair.value, env)?;
+            if pair.vectors.is_empty() {
+                return Err(ArithmaError::NotImplemented(format!(
+                    "Found no eigenvector for the eigenvalue {}",
+                    pair.value
+                )));
+            }
+        }
+        Ok(EigenDecomposition { pairs })
+    }
+
+    /// A basis of the null space of `A − λI`.
+    fn eigenspace(&self, value: &Node, env: &Environment) -> Result<Vec<Matrix>, ArithmaError> {
+        let n = self.rows;
+        let mut shifted = self.clone();
+        for i in 0..n {
+            let entry = &mut shifted.elements[i * n + i];
+            *entry =
+                Node::Subtract(Box::new(entry.clone()), Box::new(value.clone())).simplify(env)?;
+        }
+        let basis = shifted.null_space(env)?;
+        if !basis.is_empty() {
+            return Ok(basis);
+        }
+
+        let row = |i: usize| shifted.elements[i * n..(i + 1) * n].to_vec();
+        let candidates: Vec<Vec<Node>> = match n {
+            // (b, λ − a) is orthogonal to the first row (a − λ, b), and
+            // (λ − d, c) to the second (c, d − λ)
+            2 => {
+                let minus = |i: usize| {
+                    Node::Subtract(Box::new(value.clone()), Box::new(self.elements[i].clone()))
+                };
+                vec![
+                    vec![self.elements[1].clone(), minus(0)],
+                    vec![minus(3), self.elements[2].clone()],
+                ]
+            }
+            3 => {
+                let mut products = Vec::new();
+                for (i, j) in [(0, 1), (0, 2), (1, 2)] {
+                    let product = Vector::new(row(i))?.cross(&Vector::new(row(j))?, env)?;
+                    products.push(product.components);
+                }
+                products
+            }
+            _ => Vec::new(),
+        };
+        for candidate in candidates {
+            let components = candidate
+                .into_iter()
+                .map(|c| c.simplify(env))
+                .collect::<Result<Vec<_>, _>>()?;
+            if components.iter().any(|c| !is_zero_node(c)) {
+                return Ok(vec![Matrix::new(n, 1, components)?]);
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// The LU decomposition of a square matrix whose entries simplify to
+    /// numbers, or `None` when some entry is symbolic (`x`, `\sqrt{2}`).
+    pub fn lu(&self, env: &Environment) -> Result<Option<LuDecomposition>, ArithmaError> {
+        if !self.is_square() {
+            return Err(ArithmaError::DimensionMismatch(
+                "LU decomposition needs a square matrix".to_string(),
+            ));
+        }
+        match self.numeric_entries(env) {
+            Some(entries) => LuDecomposition::new(self.rows, entries).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// `A = QR` by Householder reflections, for a matrix whose entries
+    /// simplify to numbers: `Q` is an orthogonal `m`×`m` matrix and `R` an
+    /// upper triangular `m`×`n` one whose diagonal is non-negative.
+    pub fn qr(&self, env: &Environment) -> Result<(Matrix, Matrix), ArithmaError> {
+        let (m, n) = (self.rows, self.cols);
+        let mut r = self.float_entries("QR", env)?;
+        let scale = r.iter().map(|x| x.abs()).fold(0.0, f64::max);
+        let mut q: Vec<f64> = (0..m * m)
+            .map(|k| if k / m == k % m { 1.0 } else { 0.0 })
+            .collect();
+
+        for k in 0..n.min(m.saturating_sub(1)) {
+            let mut v: Vec<f64> = (k..m).map(|i| r[i * n + k]).collect();
+            let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm <= FLOAT_PIVOT_TOLERANCE * scale {
+                continue;
+            }
+            v[0] += norm.copysign(v[0]);
+            let v_norm2: f64 = v.iter().map(|x| x * x).sum();
+            // R ← HR and Q ← QH for H = I − 2vvᵀ/(vᵀv)
+            for j in k..n {
+                let s: f64 = v
+                    .iter()
+                    .enumerate()
+                    .map(|(i, vi)| vi * r[(k + i) * n + j])
+                    .sum();
+                for (i, vi) in v.iter().enumerate() {
+                    r[(k + i) * n + j] -= 2.0 * s * vi / v_norm2;
+                }
+            }
+            for row in 0..m {
+                let s: f64 = v
+                    .iter()
+                    .enumerate()
+                    .map(|(i, vi)| vi * q[row * m + k + i])
+                    .sum();
+                for (i, vi) in v.iter().enumerate() {
+                    q[row * m + k + i] -= 2.0 * s * vi / v_norm2;
+                }
+            }
+        }
+
+        for i in 0..m {
+            for j in 0..i.min(n) {
+                r[i * n + j] = 0.0;
+            }
+            if i < n && r[i * n + i] < 0.0 {
+                r[i * n..(i + 1) * n].iter_mut().for_each(|x| *x = -*x);
+                (0..m).for_each(|row| q[row * m + i] = -q[row * m + i]);
+            }
+        }
+        Ok((float_matrix(m, m, q)?, float_matrix(m, n, r)?))
+    }
+
+    /// `A = LLᵀ` for a symmetric positive definite matrix whose entries
+    /// simplify to numbers: `L` is lower triangular with a positive
+    /// diagonal. A matrix that is not positive definite is an error naming
+    /// the first leading minor that fails.
+    pub fn cholesky(&self, env: &Environment) -> Result<Matrix, ArithmaError> {
+        if !self.is_square() {
+            return Err(ArithmaError::DimensionMismatch(
+                "Cholesky decomposition needs a square matrix".to_string(),
+            ));
+        }
+        let n = self.rows;
+        let a = self.float_entries("Cholesky", env)?;
+        let scale = a.iter().map(|x| x.abs()).fold(0.0, f64::max);
+        let tolerance = FLOAT_PIVOT_TOLERANCE * scale;
+        for i in 0..n {
+            for j in 0..i {
+                if (a[i * n + j] - a[j * n + i]).abs() > tolerance {
+                    return Err(ArithmaError::Domain(format!(
+                        "Cholesky decomposition needs a symmetric matrix, but entries ({}, {}) and ({}, {}) differ",
+                        i + 1,
+                        j + 1,
+                        j + 1,
+                        i + 1
+                    )));
+                }
+            }
+        }
+
+        let mut l = vec![0.0f64; n * n];
+        for j in 0..n {
+            let pivot = a[j * n + j] - (0..j).map(|k| l[j * n + k].powi(2)).sum::<f64>();
+            if pivot <= tolerance {
+                return Err(ArithmaError::Domain(format!(
+                    "Cholesky decomposition needs a positive definite matrix, \
+                     but its leading {}×{} minor is not positive",
+                    j + 1,
+                    j + 1
+                )));
+            }
+            let diagonal = pivot.sqrt();
+            l[j * n + j] = diagonal;
+            for i in j + 1..n {
+                let dot: f64 = (0..j).map(|k| l[i * n + k] * l[j * n + k]).sum();
+                l[i * n + j] = (a[i * n + j] - dot) / diagonal;
+            }
+        }
+        float_matrix(n, n, l)
+    }
+
+    /// Every entry as an `f64`, or an error naming the `decomposition`
+    /// when one is symbolic.
+    fn float_entries(
+        &self,
+        decomposition: &str,
+        env: &Environment,
+    ) -> Result<Vec<f64>, ArithmaError> {
+        let entries = self.numeric_entries(env).ok_or_else(|| {
+            ArithmaError::InvalidArgument(format!(
+                "{} decomposition needs a matrix whose entries are numbers",
+                decomposition
+            ))
+        })?;
+        Ok(entries.iter().map(ExactNum::to_f64).collect())
+    }
+
+    /// Every entry as a number, if each simplifies to one.
+    pub(crate) fn numeric_entries(&self, env: &Environment) -> Option<Vec<ExactNum>> {
+        self.elements
+            .iter()
+            .map(|element| match element.simplify(env) {
+                Ok(Node::Num(n)) => Some(n),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn float_matrix(rows: usize, cols: usize, entries: Vec<f64>) -> Result<Matrix, ArithmaError> {
+    let elements = entries
+        .into_iter()
+        .map(|x| Node::Num(ExactNum::from_f64(x)))
+        .collect();
+    Matrix::new(rows, cols, elements)
+}
+
+fn negligible(x: &ExactNum, scale: f64) -> bool {
+    match x {
+        ExactNum::Float(f) => f.abs() <= FLOAT_PIVOT_TOLERANCE * scale,
+        ExactNum::Rational(_) => x.is_zero(),
+    }
+}
@@ -0,0 +1,87 @@
+use num_rational::BigRational;
+use num_traits::{One, Zero};
+
+use crate::exact::ExactNum;
+use crate::mod_poly::factor_over_q;
+use crate::node::Node;
+use crate::polynomial::Polynomial;
+
+/// A polynomial over Q as `content · ∏ factorᵐ`, with each factor a
+/// primitive integer polynomial irreducible over Q.
+#[derive(Clone, Debug)]
+pub struct Factorization {
+    /// Rational constant in front of the factors
+    pub content: BigRational,
+    /// Distinct irreducible factors with their multiplicities, in the order
+    /// the factorization first produced them
+    pub factors: Vec<(Polynomial, usize)>,
+}
+
+impl Factorization {
+    /// True when the input did not split: a single factor of degree ≥ 2.
+    pub fn is_irreducible(&self) -> bool {
+        matches!(self.factors.as_slice(), [(f, 1)] if f.degree().unwrap_or(0) > 1)
+    }
+
+    /// The factored form as an expression tree, unsimplified so the
+    /// product structure survives (`simplify` would multiply it back out).
+    pub fn to_node(&self) -> Node {
+        if self.content.is_zero() {
+            return Node::Num(ExactNum::zero());
+        }
+        let product = self
+            .factors
+            .iter()
+            .map(|(f, m)| {
+                if *m == 1 {
+                    f.to_node()
+                } else {
+                    Node::Power(
+                        Box::new(f.to_node()),
+                        Box::new(Node::Num(ExactNum::integer(*m as i64))),
+                    )
+                }
+            })
+            .reduce(|acc, f| Node::Multiply(Box::new(acc), Box::new(f)));
+
+        let content = Node::Num(ExactNum::Rational(self.content.clone()));
+        match product {
+            None => content,
+            Some(p) if self.content.is_one() => p,
+            Some(p) if (-&self.content).is_one() => Node::Negate(Box::new(p)),
+            Some(p) => Node::Multiply(Box::new(content), Box::new(p)),
+        }
+    }
+}
+
+/// Factor a univariate polynomial over Q, grouping repeated factors.
+pub fn factor_polynomial(poly: &Polynomial) -> Factorization {
+    let (content, factors) = factor_over_q(poly);
+    let mut grouped: Vec<(Polynomial, usize)> = Vec::new();
+    for f in factors {
+        if let Some(entry) = grouped.iter_mut().find(|(g, _)| *g == f) {
+            entry.1 += 1;
+        } else {
+            grouped.push((f, 1));
+        }
+    }
+    Factorization {
+        content,
+        factors: grouped,
+    }
+}
+
+/// Factor a polynomial expression in `var` over Q:
+/// `x^2 + 5x + 6 → (x + 2)(x + 3)`, `2x^3 - 2x → 2x(x - 1)(x + 1)`.
+/// Common factors, quadratics, and higher-degree polynomials are all
+/// handled exactly (square-free decomposition + Berlekamp–Zassenhaus).
+pub fn factor(expr: &Node, var: &str) -> Result<Node, String> {
+    let poly = Polynomial::from_node(expr, var).map_err(|e| format!("Not a polynomial: {}", e))?;
+    Ok(factor_polynomial(&poly).to_node())
+}
+
+/// Parse a LaTeX polynomial and return its factored form as LaTeX.
+pub fn factor_latex(latex: &str, var: &str) -> Result<String, String> {
+    let expr = crate::parser::parse_latex_raw(latex)?;
+    Ok(factor(&expr, var)?.to_string())
+}
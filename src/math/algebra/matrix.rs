@@ -2,13 +2,23 @@ use std::fmt;
 use std::ops::{Add, Mul, Neg, Sub};
 
 use crate::environment::Environment;
+use crate::error::ArithmaError;
 use crate::exact::ExactNum;
 use crate::node::Node;
+use crate::parser::ParseError;
 use crate::simplify::Simplifiable;
+use crate::vector::Vector;
 use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+/// Products with at least this many multiply-adds compute their entries on
+/// several threads.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_PRODUCTS: usize = 4096;
 
 /// Represents a mathematical matrix with expression elements
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "MatrixFields")]
 pub struct Matrix {
     /// Number of rows in the matrix
     pub rows: usize,
@@ -18,17 +28,82 @@ pub struct Matrix {
     pub elements: Vec<Node>,
 }
 
+/// Deserialization goes through [`Matrix::new`], so a payload whose element
+/// count disagrees with its dimensions is rejected.
+#[derive(Deserialize)]
+struct MatrixFields {
+    rows: usize,
+    cols: usize,
+    elements: Vec<Node>,
+}
+
+impl TryFrom<MatrixFields> for Matrix {
+    type Error = ArithmaError;
+
+    fn try_from(fields: MatrixFields) -> Result<Self, ArithmaError> {
+        Matrix::new(fields.rows, fields.cols, fields.elements)
+    }
+}
+
+/// `\det`: applied to a matrix inside an expression, its determinant.
+pub const DETERMINANT: &str = "det";
+/// The exponent of `A^T`, the transpose.
+pub const TRANSPOSE: &str = "T";
+
+/// Default tolerance for [`Matrix::equals`] when entries differ only by
+/// floating-point error.
+pub const MATRIX_EQ_TOLERANCE: f64 = 1e-10;
+
+/// An elementary row operation, as recorded by [`Matrix::rref_with_steps`].
+/// Row indices are 0-based; the LaTeX rendering labels rows from 1.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RowOp {
+    /// Exchange rows `i` and `j`.
+    Swap(usize, usize),
+    /// Multiply row `row` by a nonzero factor.
+    Scale { row: usize, factor: Node },
+    /// Add `factor` times row `source` to row `target`.
+    AddMultiple {
+        target: usize,
+        source: usize,
+        factor: Node,
+    },
+}
+
+impl fmt::Display for RowOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RowOp::Swap(i, j) => write!(f, "R_{{{}}} \\leftrightarrow R_{{{}}}", i + 1, j + 1),
+            RowOp::Scale { row, factor } => {
+                write!(f, "R_{{{}}} \\to ({}) R_{{{}}}", row + 1, factor, row + 1)
+            }
+            RowOp::AddMultiple {
+                target,
+                source,
+                factor,
+            } => write!(
+                f,
+                "R_{{{}}} \\to R_{{{}}} + ({}) R_{{{}}}",
+                target + 1,
+                target + 1,
+                factor,
+                source + 1
+            ),
+        }
+    }
+}
+
 impl Matrix {
     /// Create a new matrix with specified dimensions and elements
-    pub fn new(rows: usize, cols: usize, elements: Vec<Node>) -> Result<Self, String> {
+    pub fn new(rows: usize, cols: usize, elements: Vec<Node>) -> Result<Self, ArithmaError> {
         if elements.len() != rows * cols {
-            return Err(format!(
+            return Err(ArithmaError::DimensionMismatch(format!(
                 "Invalid matrix: expected {} elements for {}x{} matrix, but got {}",
                 rows * cols,
                 rows,
                 cols,
                 elements.len()
-            ));
+            )));
         }
 
         Ok(Matrix {
@@ -38,10 +113,26 @@ impl Matrix {
         })
     }
 
+    /// Serialize as `{"rows":…,"cols":…,"elements":[…]}` with each element
+    /// in the [`Node::to_json`] encoding.
+    pub fn to_json(&self) -> Result<String, ArithmaError> {
+        serde_json::to_string(self)
+            .map_err(|e| ArithmaError::Eval(format!("Failed to serialize matrix: {}", e)))
+    }
+
+    /// Rebuild a matrix written by [`Matrix::to_json`].
+    pub fn from_json(json: &str) -> Result<Matrix, ArithmaError> {
+        serde_json::from_str(json).map_err(|e| {
+            ArithmaError::Parse(ParseError::new(format!("Invalid matrix JSON: {}", e)))
+        })
+    }
+
     /// Create a new matrix from a 2D vector of Node elements
-    pub fn from_elements(elements: Vec<Vec<Node>>) -> Result<Self, String> {
+    pub fn from_elements(elements: Vec<Vec<Node>>) -> Result<Self, ArithmaError> {
         if elements.is_empty() {
-            return Err("Cannot create matrix with no rows".to_string());
+            return Err(ArithmaError::DimensionMismatch(
+                "Cannot create matrix with no rows".to_string(),
+            ));
         }
 
         let rows = elements.len();
@@ -50,7 +141,9 @@ impl Matrix {
         // Check that all rows have the same length
         for row in &elements {
             if row.len() != cols {
-                return Err("All rows in a matrix must have the same length".to_string());
+                return Err(ArithmaError::DimensionMismatch(
+                    "All rows in a matrix must have the same length".to_string(),
+                ));
             }
         }
 
@@ -60,6 +153,32 @@ impl Matrix {
         Matrix::new(rows, cols, flat_elements)
     }
 
+    /// The matrix of a [`Node::Matrix`] literal, or a vector literal as a
+    /// column; `None` for any other node.
+    pub fn from_node(node: &Node) -> Option<Matrix> {
+        match node {
+            Node::Matrix(rows) => Matrix::from_elements(rows.clone()).ok(),
+            _ => Vector::from_node(node).map(|v| v.to_matrix()),
+        }
+    }
+
+    /// The matrix as a literal node: a vector literal when it has a single
+    /// column, as the parser reads one.
+    pub fn to_node(&self) -> Node {
+        if self.cols == 1 {
+            return Node::Function(
+                crate::vector::VECTOR_LITERAL.to_string(),
+                self.elements.clone(),
+            );
+        }
+        Node::Matrix(
+            self.elements
+                .chunks(self.cols)
+                .map(<[Node]>::to_vec)
+                .collect(),
+        )
+    }
+
     /// Create an identity matrix of specified size
     pub fn identity(size: usize) -> Self {
         let mut elements = vec![Node::Num(ExactNum::zero()); size * size];
@@ -82,24 +201,24 @@ impl Matrix {
     }
 
     /// Get an element at a specific position (row, col)
-    pub fn get(&self, row: usize, col: usize) -> Result<&Node, String> {
+    pub fn get(&self, row: usize, col: usize) -> Result<&Node, ArithmaError> {
         if row >= self.rows || col >= self.cols {
-            return Err(format!(
+            return Err(ArithmaError::DimensionMismatch(format!(
                 "Matrix index out of bounds: ({}, {}) for {}x{} matrix",
                 row, col, self.rows, self.cols
-            ));
+            )));
         }
 
         Ok(&self.elements[row * self.cols + col])
     }
 
     /// Set an element at a specific position (row, col)
-    pub fn set(&mut self, row: usize, col: usize, value: Node) -> Result<(), String> {
+    pub fn set(&mut self, row: usize, col: usize, value: Node) -> Result<(), ArithmaError> {
         if row >= self.rows || col >= self.cols {
-            return Err(format!(
+            return Err(ArithmaError::DimensionMismatch(format!(
                 "Matrix index out of bounds: ({}, {}) for {}x{} matrix",
                 row, col, self.rows, self.cols
-            ));
+            )));
         }
 
         self.elements[row * self.cols + col] = value;
@@ -123,14 +242,24 @@ impl Matrix {
         }
     }
 
-    /// Calculate the determinant of a square matrix
-    pub fn determinant(&self, env: &Environment) -> Result<Node, String> {
+    /// Calculate the determinant of a square matrix: by LU decomposition
+    /// when every entry is a number, by cofactor expansion otherwise.
+    pub fn determinant(&self, env: &Environment) -> Result<Node, ArithmaError> {
         if !self.is_square() {
-            return Err("Cannot calculate determinant of a non-square matrix".to_string());
+            return Err(ArithmaError::DimensionMismatch(
+                "Cannot calculate determinant of a non-square matrix".to_string(),
+            ));
+        }
+        if self.rows > 2 {
+            if let Some(lu) = self.lu(env)? {
+                return Ok(Node::Num(lu.determinant()));
+            }
         }
 
         match self.rows {
-            0 => Err("Cannot calculate determinant of an empty matrix".to_string()),
+            0 => Err(ArithmaError::DimensionMismatch(
+                "Cannot calculate determinant of an empty matrix".to_string(),
+            )),
             1 => Ok(self.elements[0].clone()),
             2 => {
                 // For 2x2 matrix: ad - bc
@@ -177,16 +306,18 @@ impl Matrix {
     }
 
     /// Get the minor matrix by removing a specific row and column
-    pub fn minor(&self, row: usize, col: usize) -> Result<Matrix, String> {
+    pub fn minor(&self, row: usize, col: usize) -> Result<Matrix, ArithmaError> {
         if !self.is_square() {
-            return Err("Cannot get minor of a non-square matrix".to_string());
+            return Err(ArithmaError::DimensionMismatch(
+                "Cannot get minor of a non-square matrix".to_string(),
+            ));
         }
 
         if row >= self.rows || col >= self.cols {
-            return Err(format!(
+            return Err(ArithmaError::DimensionMismatch(format!(
                 "Matrix index out of bounds: ({}, {}) for {}x{} matrix",
                 row, col, self.rows, self.cols
-            ));
+            )));
         }
 
         let new_size = self.rows - 1;
@@ -210,9 +341,11 @@ impl Matrix {
     }
 
     /// Calculate the matrix of cofactors
-    pub fn cofactor_matrix(&self, env: &Environment) -> Result<Matrix, String> {
+    pub fn cofactor_matrix(&self, env: &Environment) -> Result<Matrix, ArithmaError> {
         if !self.is_square() {
-            return Err("Cannot calculate cofactors of a non-square matrix".to_string());
+            return Err(ArithmaError::DimensionMismatch(
+                "Cannot calculate cofactors of a non-square matrix".to_string(),
+            ));
         }
 
         let size = self.rows;
@@ -236,15 +369,23 @@ impl Matrix {
     }
 
     /// Calculate the adjugate (adjoint) of the matrix
-    pub fn adjugate(&self, env: &Environment) -> Result<Matrix, String> {
+    pub fn adjugate(&self, env: &Environment) -> Result<Matrix, ArithmaError> {
         // The adjugate is the transpose of the cofactor matrix
         Ok(self.cofactor_matrix(env)?.transpose())
     }
 
-    /// Calculate the inverse of a square matrix
-    pub fn inverse(&self, env: &Environment) -> Result<Matrix, String> {
+    /// Calculate the inverse of a square matrix: by LU decomposition when
+    /// every entry is a number, as adjugate over determinant otherwise.
+    pub fn inverse(&self, env: &Environment) -> Result<Matrix, ArithmaError> {
         if !self.is_square() {
-            return Err("Cannot invert a non-square matrix".to_string());
+            return Err(ArithmaError::DimensionMismatch(
+                "Cannot invert a non-square matrix".to_string(),
+            ));
+        }
+        if self.rows > 0 {
+            if let Some(lu) = self.lu(env)? {
+                return lu.inverse();
+            }
         }
 
         let det = self.determinant(env)?;
@@ -252,7 +393,9 @@ impl Matrix {
         // Check if determinant is zero
         if let Node::Num(ref n) = det {
             if n.is_zero() {
-                return Err("Cannot invert a singular matrix (determinant is zero)".to_string());
+                return Err(ArithmaError::Domain(
+                    "Cannot invert a singular matrix (determinant is zero)".to_string(),
+                ));
             }
         }
 
@@ -287,9 +430,109 @@ impl Matrix {
         result
     }
 
+    fn check_row(&self, row: usize) -> Result<(), ArithmaError> {
+        if row >= self.rows {
+            return Err(ArithmaError::DimensionMismatch(format!(
+                "Matrix row index out of bounds: {} for {}x{} matrix",
+                row, self.rows, self.cols
+            )));
+        }
+        Ok(())
+    }
+
+    /// Exchange rows `i` and `j` in place
+    pub fn row_swap(&mut self, i: usize, j: usize) -> Result<(), ArithmaError> {
+        self.check_row(i)?;
+        self.check_row(j)?;
+        if i != j {
+            for c in 0..self.cols {
+                self.elements.swap(i * self.cols + c, j * self.cols + c);
+            }
+        }
+        Ok(())
+    }
+
+    /// Multiply row `row` by `factor` in place. The factor must not be zero,
+    /// since scaling by zero is not an invertible row operation.
+    pub fn row_scale(
+        &mut self,
+        row: usize,
+        factor: &Node,
+        env: &Environment,
+    ) -> Result<(), ArithmaError> {
+        self.check_row(row)?;
+        if is_zero_node(factor) {
+            return Err(ArithmaError::InvalidArgument(
+                "Cannot scale a row by zero".to_string(),
+            ));
+        }
+        for c in 0..self.cols {
+            let idx = row * self.cols + c;
+            self.elements[idx] = Node::Multiply(
+                Box::new(factor.clone()),
+                Box::new(self.elements[idx].clone()),
+            )
+            .simplify(env)?;
+        }
+        Ok(())
+    }
+
+    /// Add `factor` times row `source` to row `target` in place
+    pub fn row_addmul(
+        &mut self,
+        target: usize,
+        source: usize,
+        factor: &Node,
+        env: &Environment,
+    ) -> Result<(), ArithmaError> {
+        self.check_row(target)?;
+        self.check_row(source)?;
+        if target == source {
+            return Err(ArithmaError::InvalidArgument(
+                "Cannot add a multiple of a row to itself".to_string(),
+            ));
+        }
+        for c in 0..self.cols {
+            let product = Node::Multiply(
+                Box::new(factor.clone()),
+                Box::new(self.elements[source * self.cols + c].clone()),
+            )
+            .simplify(env)?;
+            let idx = target * self.cols + c;
+            self.elements[idx] =
+                Node::Add(Box::new(self.elements[idx].clone()), Box::new(product)).simplify(env)?;
+        }
+        Ok(())
+    }
+
+    /// Apply a recorded row operation in place
+    pub fn apply_row_op(&mut self, op: &RowOp, env: &Environment) -> Result<(), ArithmaError> {
+        match op {
+            RowOp::Swap(i, j) => self.row_swap(*i, *j),
+            RowOp::Scale { row, factor } => self.row_scale(*row, factor, env),
+            RowOp::AddMultiple {
+                target,
+                source,
+                factor,
+            } => self.row_addmul(*target, *source, factor, env),
+        }
+    }
+
     /// Perform Gauss-Jordan elimination to find the reduced row echelon form (RREF)
-    pub fn rref(&self, env: &Environment) -> Result<Matrix, String> {
+    pub fn rref(&self, env: &Environment) -> Result<Matrix, ArithmaError> {
+        Ok(self.rref_with_steps(env)?.0)
+    }
+
+    /// Gauss-Jordan elimination that also returns the row operations performed,
+    /// in order. Replaying the steps on `self` with [`Matrix::apply_row_op`]
+    /// reproduces the returned RREF. Operations that would leave the matrix
+    /// unchanged (scaling by 1, adding 0 times a row) are not recorded.
+    pub fn rref_with_steps(&self, env: &Environment) -> Result<(Matrix, Vec<RowOp>), ArithmaError> {
         let mut result = self.clone();
+        for element in result.elements.iter_mut() {
+            *element = element.simplify(env).unwrap_or_else(|_| element.clone());
+        }
+        let mut steps = Vec::new();
         let mut lead = 0;
 
         for r in 0..self.rows {
@@ -305,7 +548,7 @@ impl Matrix {
                         i = r;
                         lead += 1;
                         if self.cols == lead {
-                            return Ok(result);
+                            return Ok((result, steps));
                         }
                     }
                 } else {
@@ -313,74 +556,81 @@ impl Matrix {
                 }
             }
 
-            // Swap rows i and r
             if i != r {
-                for j in 0..self.cols {
-                    let temp = result.elements[i * self.cols + j].clone();
-                    result.elements[i * self.cols + j] = result.elements[r * self.cols + j].clone();
-                    result.elements[r * self.cols + j] = temp;
-                }
+                let op = RowOp::Swap(i, r);
+                result.apply_row_op(&op, env)?;
+                steps.push(op);
             }
 
-            // Scale row r
             let pivot = result.elements[r * self.cols + lead].clone();
-            for j in 0..self.cols {
-                result.elements[r * self.cols + j] = Node::Divide(
-                    Box::new(result.elements[r * self.cols + j].clone()),
-                    Box::new(pivot.clone()),
-                )
-                .simplify(env)?;
+            if !is_one_node(&pivot) {
+                let factor = Node::Divide(Box::new(Node::Num(ExactNum::one())), Box::new(pivot))
+                    .simplify(env)?;
+                let op = RowOp::Scale { row: r, factor };
+                result.apply_row_op(&op, env)?;
+                steps.push(op);
             }
 
-            // Eliminate other rows
             for i in 0..self.rows {
-                if i != r {
-                    let factor = result.elements[i * self.cols + lead].clone();
-                    for j in 0..self.cols {
-                        let subtraction = Node::Multiply(
-                            Box::new(factor.clone()),
-                            Box::new(result.elements[r * self.cols + j].clone()),
-                        )
-                        .simplify(env)?;
-
-                        result.elements[i * self.cols + j] = Node::Subtract(
-                            Box::new(result.elements[i * self.cols + j].clone()),
-                            Box::new(subtraction),
-                        )
-                        .simplify(env)?;
-                    }
+                if i == r {
+                    continue;
                 }
+                let entry = &result.elements[i * self.cols + lead];
+                if is_zero_node(entry) {
+                    continue;
+                }
+                let factor = Node::Negate(Box::new(entry.clone())).simplify(env)?;
+                let op = RowOp::AddMultiple {
+                    target: i,
+                    source: r,
+                    factor,
+                };
+                result.apply_row_op(&op, env)?;
+                steps.push(op);
             }
 
             lead += 1;
         }
 
-        Ok(result)
+        Ok((result, steps))
     }
 
     /// Solve a system of linear equations represented as Ax = b
     /// Returns x, the solution vector
-    pub fn solve(&self, b: &Matrix, env: &Environment) -> Result<Matrix, String> {
+    pub fn solve(&self, b: &Matrix, env: &Environment) -> Result<Matrix, ArithmaError> {
         if self.rows != b.rows {
-            return Err(format!(
+            return Err(ArithmaError::DimensionMismatch(format!(
                 "Matrix dimensions don't match for solving equations: A is {}x{}, b is {}x{}",
                 self.rows, self.cols, b.rows, b.cols
-            ));
+            )));
         }
 
         if b.cols != 1 {
-            return Err("Right-hand side must be a column vector".to_string());
+            return Err(ArithmaError::DimensionMismatch(
+                "Right-hand side must be a column vector".to_string(),
+            ));
         }
 
         if !self.is_square() {
-            return Err("Coefficient matrix must be square".to_string());
+            return Err(ArithmaError::DimensionMismatch(
+                "Coefficient matrix must be square".to_string(),
+            ));
+        }
+
+        if self.rows > 0 {
+            if let (Some(lu), Some(rhs)) = (self.lu(env)?, b.numeric_entries(env)) {
+                let x = lu.solve(&rhs)?;
+                return Matrix::new(self.rows, 1, x.into_iter().map(Node::Num).collect());
+            }
         }
 
         // Check if matrix is invertible
         let det = self.determinant(env)?;
         if let Node::Num(ref n) = det {
             if n.is_zero() {
-                return Err("System has no unique solution (singular matrix)".to_string());
+                return Err(ArithmaError::Domain(
+                    "System has no unique solution (singular matrix)".to_string(),
+                ));
             }
         }
 
@@ -390,39 +640,149 @@ impl Matrix {
     }
 
     /// Multiply this matrix by another matrix
-    pub fn multiply(&self, other: &Matrix, env: &Environment) -> Result<Matrix, String> {
+    pub fn multiply(&self, other: &Matrix, env: &Environment) -> Result<Matrix, ArithmaError> {
         if self.cols != other.rows {
-            return Err(format!(
+            return Err(ArithmaError::DimensionMismatch(format!(
                 "Matrix dimensions don't match for multiplication: {}x{} * {}x{}",
                 self.rows, self.cols, other.rows, other.cols
-            ));
+            )));
         }
 
-        let mut result = Vec::with_capacity(self.rows * other.cols);
+        let entries = self.rows * other.cols;
 
-        for i in 0..self.rows {
-            for j in 0..other.cols {
-                let mut sum = Node::Num(ExactNum::zero());
+        #[cfg(feature = "parallel")]
+        if entries * self.cols >= PARALLEL_MIN_PRODUCTS {
+            use rayon::prelude::*;
+            let result = (0..entries)
+                .into_par_iter()
+                .map(|n| self.inner_product(other, n / other.cols, n % other.cols, env))
+                .collect::<Result<Vec<_>, ArithmaError>>()?;
+            return Matrix::new(self.rows, other.cols, result);
+        }
 
-                for k in 0..self.cols {
-                    let product = Node::Multiply(
-                        Box::new(self.elements[i * self.cols + k].clone()),
-                        Box::new(other.elements[k * other.cols + j].clone()),
-                    )
-                    .simplify(env)?;
+        let result = (0..entries)
+            .map(|n| self.inner_product(other, n / other.cols, n % other.cols, env))
+            .collect::<Result<Vec<_>, ArithmaError>>()?;
+        Matrix::new(self.rows, other.cols, result)
+    }
 
-                    sum = Node::Add(Box::new(sum), Box::new(product)).simplify(env)?;
-                }
+    /// Entry (i, j) of `self · other`: row i of `self` times column j of
+    /// `other`.
+    fn inner_product(
+        &self,
+        other: &Matrix,
+        i: usize,
+        j: usize,
+        env: &Environment,
+    ) -> Result<Node, ArithmaError> {
+        let mut sum = Node::Num(ExactNum::zero());
+        for k in 0..self.cols {
+            let product = Node::Multiply(
+                Box::new(self.elements[i * self.cols + k].clone()),
+                Box::new(other.elements[k * other.cols + j].clone()),
+            )
+            .simplify(env)?;
 
-                result.push(sum);
+            sum = Node::Add(Box::new(sum), Box::new(product)).simplify(env)?;
+        }
+        Ok(sum)
+    }
+
+    /// `Aⁿ` for an integer `n`, by repeated squaring; a negative power is
+    /// a power of the inverse and `A⁰` is the identity.
+    pub fn power(&self, n: i64, env: &Environment) -> Result<Matrix, ArithmaError> {
+        if !self.is_square() {
+            return Err(ArithmaError::DimensionMismatch(format!(
+                "Cannot raise a non-square {}x{} matrix to a power",
+                self.rows, self.cols
+            )));
+        }
+        let mut square = if n < 0 {
+            self.inverse(env)?
+        } else {
+            self.clone()
+        };
+        let mut result = Matrix::identity(self.rows);
+        let mut k = n.unsigned_abs();
+        while k > 0 {
+            if k & 1 == 1 {
+                result = result.multiply(&square, env)?;
+            }
+            k >>= 1;
+            if k > 0 {
+                square = square.multiply(&square, env)?;
             }
         }
+        Ok(result)
+    }
 
-        Matrix::new(self.rows, other.cols, result)
+    /// Simplify every entry.
+    fn simplify_entries(&self, env: &Environment) -> Result<Matrix, ArithmaError> {
+        let elements = self
+            .elements
+            .iter()
+            .map(|e| e.simplify(env))
+            .collect::<Result<Vec<_>, String>>()?;
+        Matrix::new(self.rows, self.cols, elements)
+    }
+
+    /// Replace `var` by `value` in every entry and simplify. Entries stay
+    /// symbolic where other variables remain.
+    pub fn substitute(
+        &self,
+        var: &str,
+        value: &Node,
+        env: &Environment,
+    ) -> Result<Matrix, ArithmaError> {
+        let elements = self
+            .elements
+            .iter()
+            .map(|e| {
+                let substituted = crate::substitute::substitute_variable(e, var, value)?;
+                Ok(substituted.simplify(env).unwrap_or(substituted))
+            })
+            .collect::<Result<Vec<_>, ArithmaError>>()?;
+        Matrix::new(self.rows, self.cols, elements)
+    }
+
+    /// Evaluate a matrix of expressions (e.g. a Jacobian) at `var = value`,
+    /// returning a numeric matrix. Entries that simplify to exact numbers
+    /// stay exact; the rest are evaluated in floating point with `env`
+    /// supplying any other variables. An entry that still depends on an
+    /// unbound variable is an error naming its position.
+    pub fn evaluate_at(
+        &self,
+        var: &str,
+        value: &Node,
+        env: &Environment,
+    ) -> Result<Matrix, ArithmaError> {
+        let substituted = self.substitute(var, value, env)?;
+        let elements = substituted
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(idx, e)| match e {
+                Node::Num(_) => Ok(e.clone()),
+                _ => crate::evaluator::Evaluator::evaluate(e, env)
+                    .map(|v| Node::Num(ExactNum::Float(v)))
+                    .map_err(|err| {
+                        ArithmaError::Eval(format!(
+                            "Entry ({}, {}) = {} is not numeric at {} = {}: {}",
+                            idx / self.cols,
+                            idx % self.cols,
+                            e,
+                            var,
+                            value,
+                            err
+                        ))
+                    }),
+            })
+            .collect::<Result<Vec<_>, ArithmaError>>()?;
+        Matrix::new(self.rows, self.cols, elements)
     }
 
     /// Calculate the rank of the matrix
-    pub fn rank(&self, env: &Environment) -> Result<usize, String> {
+    pub fn rank(&self, env: &Environment) -> Result<usize, ArithmaError> {
         let rref = self.rref(env)?;
         let mut rank = 0;
 
@@ -442,15 +802,116 @@ impl Matrix {
         Ok(rank)
     }
 
+    /// Column index of the leading entry of each nonzero row of an RREF matrix
+    fn pivot_columns(rref: &Matrix) -> Vec<usize> {
+        (0..rref.rows)
+            .filter_map(|i| {
+                (0..rref.cols).find(|&j| !is_zero_node(&rref.elements[i * rref.cols + j]))
+            })
+            .collect()
+    }
+
+    /// Basis of the null space {x : Ax = 0}, one column vector per free variable
+    /// of the RREF. Empty when the columns are linearly independent.
+    pub fn null_space(&self, env: &Environment) -> Result<Vec<Matrix>, ArithmaError> {
+        let rref = self.rref(env)?;
+        let pivots = Self::pivot_columns(&rref);
+
+        let mut basis = Vec::new();
+        for free in (0..self.cols).filter(|j| !pivots.contains(j)) {
+            let mut vector = vec![Node::Num(ExactNum::zero()); self.cols];
+            vector[free] = Node::Num(ExactNum::one());
+            for (row, &pivot) in pivots.iter().enumerate() {
+                let entry = rref.elements[row * rref.cols + free].clone();
+                vector[pivot] = Node::Negate(Box::new(entry)).simplify(env)?;
+            }
+            basis.push(Matrix::new(self.cols, 1, vector)?);
+        }
+
+        Ok(basis)
+    }
+
+    /// Basis of the column space: the columns of the original matrix that hold
+    /// a pivot in the RREF
+    pub fn column_space(&self, env: &Environment) -> Result<Vec<Matrix>, ArithmaError> {
+        let rref = self.rref(env)?;
+        Self::pivot_columns(&rref)
+            .into_iter()
+            .map(|j| {
+                let column = (0..self.rows)
+                    .map(|i| self.elements[i * self.cols + j].clone())
+                    .collect();
+                Matrix::new(self.rows, 1, column)
+            })
+            .collect()
+    }
+
+    /// Basis of the row space: the nonzero rows of the RREF, as column vectors
+    pub fn row_space(&self, env: &Environment) -> Result<Vec<Matrix>, ArithmaError> {
+        let rref = self.rref(env)?;
+        let rank = Self::pivot_columns(&rref).len();
+        (0..rank)
+            .map(|i| {
+                let row = rref.elements[i * rref.cols..(i + 1) * rref.cols].to_vec();
+                Matrix::new(rref.cols, 1, row)
+            })
+            .collect()
+    }
+
+    /// Basis of the left null space {y : Aᵀy = 0}
+    pub fn left_null_space(&self, env: &Environment) -> Result<Vec<Matrix>, ArithmaError> {
+        self.transpose().null_space(env)
+    }
+
+    /// Elementwise equality with the default tolerance [`MATRIX_EQ_TOLERANCE`].
+    /// See [`Matrix::approx_equals`].
+    pub fn equals(&self, other: &Matrix, env: &Environment) -> bool {
+        self.approx_equals(other, MATRIX_EQ_TOLERANCE, env)
+    }
+
+    /// Elementwise equality. Exact numeric entries must match exactly, and a
+    /// float entry matches a number within `tolerance`. Other entries are
+    /// equal when their difference simplifies to zero, or evaluates to a
+    /// number within `tolerance`; a difference that still contains free
+    /// variables counts as unequal.
+    pub fn approx_equals(&self, other: &Matrix, tolerance: f64, env: &Environment) -> bool {
+        if self.rows != other.rows || self.cols != other.cols {
+            return false;
+        }
+        self.elements
+            .iter()
+            .zip(other.elements.iter())
+            .all(|(a, b)| entries_equal(a, b, tolerance, env))
+    }
+
+    /// Whether this is a square matrix equal to the identity
+    pub fn is_identity(&self, env: &Environment) -> bool {
+        self.is_square() && self.equals(&Matrix::identity(self.rows), env)
+    }
+
+    /// Whether this is a square matrix equal to its transpose
+    pub fn is_symmetric(&self, env: &Environment) -> bool {
+        self.is_square() && self.equals(&self.transpose(), env)
+    }
+
+    /// Whether AᵀA is the identity
+    pub fn is_orthogonal(&self, env: &Environment) -> bool {
+        self.is_square()
+            && self
+                .transpose()
+                .multiply(self, env)
+                .is_ok_and(|product| product.is_identity(env))
+    }
+
     /// Computes the characteristic polynomial det(A - λI) as a Polynomial in λ.
     pub fn characteristic_polynomial(
         &self,
         env: &Environment,
-    ) -> Result<crate::polynomial::Polynomial, String> {
+    ) -> Result<crate::polynomial::Polynomial, ArithmaError> {
         if !self.is_square() {
-            return Err(
+            return Err(ArithmaError::DimensionMismatch(
                 "Cannot compute characteristic polynomial of a non-square matrix".to_string(),
-            );
+            ));
         }
         let lambda_var = "__lambda__";
         let lambda = Node::Variable(lambda_var.to_string());
@@ -470,23 +931,89 @@ impl Matrix {
         let det_expr = shifted_matrix.determinant(env)?;
         let det_simplified = det_expr.simplify(env).unwrap_or(det_expr);
 
-        crate::polynomial::Polynomial::from_node(&det_simplified, lambda_var)
-            .map_err(|e| format!("Characteristic polynomial extraction failed: {}", e))
+        crate::polynomial::Polynomial::from_node(&det_simplified, lambda_var).map_err(|e| {
+            ArithmaError::NotImplemented(format!(
+                "Characteristic polynomial extraction failed: {}",
+                e
+            ))
+        })
+    }
+
+    /// The characteristic polynomial `det(λI − A)` as an expression in
+    /// `lambda_var`, for entries that may be symbolic. The coefficient of
+    /// `λ^{n−k}` is `(−1)^k` times the sum of the `k×k` principal minors, so
+    /// a 2×2 matrix gives `λ^2 − (a + d)λ + (ad − bc)`.
+    pub fn char_poly(&self, lambda_var: &str, env: &Environment) -> Result<Node, ArithmaError> {
+        if !self.is_square() || self.rows == 0 {
+            return Err(ArithmaError::DimensionMismatch(
+                "Cannot compute characteristic polynomial of a non-square matrix".to_string(),
+            ));
+        }
+        let n = self.rows;
+        let lambda = Node::Variable(lambda_var.to_string());
+        let lambda_power = |k: usize| match k {
+            1 => lambda.clone(),
+            _ => Node::Power(
+                Box::new(lambda.clone()),
+                Box::new(Node::Num(ExactNum::integer(k as i64))),
+            ),
+        };
+
+        let mut poly = lambda_power(n);
+        for k in 1..=n {
+            let minors = self.principal_minor_sum(k, env)?;
+            // Pull a sign out of the coefficient so the sum reads `− 3λ`, not `+ −3λ`
+            let (magnitude, negative) = match minors {
+                Node::Num(ref c) if c.is_zero() => continue,
+                Node::Num(ref c) if c.is_negative() => (Node::Num(-c.clone()), k % 2 == 0),
+                Node::Negate(inner) => (*inner, k % 2 == 0),
+                other => (other, k % 2 == 1),
+            };
+            let term = match (n - k, &magnitude) {
+                (0, _) => magnitude,
+                (power, Node::Num(c)) if c.is_one() => lambda_power(power),
+                (power, _) => Node::Multiply(Box::new(magnitude), Box::new(lambda_power(power))),
+            };
+            poly = match negative {
+                true => Node::Subtract(Box::new(poly), Box::new(term)),
+                false => Node::Add(Box::new(poly), Box::new(term)),
+            };
+        }
+        Ok(poly)
+    }
+
+    /// Sum of the determinants of the `k×k` submatrices on the diagonal.
+    fn principal_minor_sum(&self, k: usize, env: &Environment) -> Result<Node, ArithmaError> {
+        let n = self.rows;
+        let mut sum = Node::Num(ExactNum::zero());
+        for mask in (0u32..1 << n).filter(|mask| mask.count_ones() as usize == k) {
+            let indices: Vec<usize> = (0..n).filter(|i| mask & (1 << i) != 0).collect();
+            let elements = indices
+                .iter()
+                .flat_map(|&i| indices.iter().map(move |&j| (i, j)))
+                .map(|(i, j)| self.elements[i * self.cols + j].clone())
+                .collect();
+            let minor = Matrix::new(k, k, elements)?.determinant(env)?;
+            sum = Node::Add(Box::new(sum), Box::new(minor));
+        }
+        Ok(sum.simplify(env)?)
     }
 
     /// Computes the eigenvalues of a square matrix via the characteristic polynomial.
     /// Returns eigenvalues with algebraic multiplicity.
     /// Supports matrices up to 4×4 (Cardano for 3×3, Ferrari for 4×4).
     /// Falls back to symbolic computation when entries contain variables.
-    pub fn eigenvalues(&self, env: &Environment) -> Result<Vec<Node>, String> {
+    pub fn eigenvalues(&self, env: &Environment) -> Result<Vec<Node>, ArithmaError> {
         if !self.is_square() {
-            return Err("Cannot compute eigenvalues of a non-square matrix".to_string());
+            return Err(ArithmaError::DimensionMismatch(
+                "Cannot compute eigenvalues of a non-square matrix".to_string(),
+            ));
         }
         if self.rows > 4 {
-            return Err(format!(
+            return Err(ArithmaError::NotImplemented(format!(
                 "Eigenvalue computation for {}×{} matrices is not supported (max 4×4)",
                 self.rows, self.rows
-            ));
+            )));
         }
 
         // For purely numeric matrices, compute directly with f64 to avoid
@@ -793,17 +1320,19 @@ impl Matrix {
     }
 
     /// Symbolic eigenvalue computation for matrices with variable entries.
-    fn eigenvalues_symbolic(&self, env: &Environment) -> Result<Vec<Node>, String> {
+    fn eigenvalues_symbolic(&self, env: &Environment) -> Result<Vec<Node>, ArithmaError> {
         match self.rows {
             1 => Ok(vec![self.elements[0].clone()]),
             2 => self.eigenvalues_symbolic_2x2(env),
             3 => self.eigenvalues_symbolic_3x3(env),
-            _ => Err("Symbolic eigenvalues for 4×4+ not yet implemented".to_string()),
+            _ => Err(ArithmaError::NotImplemented(
+                "Symbolic eigenvalues for 4×4+ not yet implemented".to_string(),
+            )),
         }
     }
 
     /// Symbolic eigenvalues for a 2×2 matrix via the quadratic formula.
-    fn eigenvalues_symbolic_2x2(&self, env: &Environment) -> Result<Vec<Node>, String> {
+    fn eigenvalues_symbolic_2x2(&self, env: &Environment) -> Result<Vec<Node>, ArithmaError> {
         let a = &self.elements[0];
         let d = &self.elements[3];
         let b = &self.elements[1];
@@ -847,7 +1376,7 @@ impl Matrix {
     /// Symbolic eigenvalues for a 3×3 matrix.
     /// Tries to find a root among row sums, column sums, and diagonal elements,
     /// then deflates to a quadratic.
-    fn eigenvalues_symbolic_3x3(&self, env: &Environment) -> Result<Vec<Node>, String> {
+    fn eigenvalues_symbolic_3x3(&self, env: &Environment) -> Result<Vec<Node>, ArithmaError> {
         let lambda_var = "__lambda__";
         let lambda = Node::Variable(lambda_var.to_string());
 
@@ -1035,28 +1564,55 @@ impl Matrix {
             }
         }
 
-        Err("Could not find symbolic eigenvalues: no candidate root verified".to_string())
+        Err(ArithmaError::NotImplemented(
+            "Could not find symbolic eigenvalues: no candidate root verified".to_string(),
+        ))
     }
 }
 
 /// Check whether a Node expression represents zero.
-fn is_zero_node(node: &Node) -> bool {
+pub(crate) fn is_zero_node(node: &Node) -> bool {
     match node {
         Node::Num(n) => n.to_f64() == 0.0,
         _ => format!("{}", node) == "0",
     }
 }
 
+fn entries_equal(a: &Node, b: &Node, tolerance: f64, env: &Environment) -> bool {
+    if a == b {
+        return true;
+    }
+    // Compare literals directly: simplifying their difference would snap a
+    // float near a simple rational onto it and bypass the tolerance.
+    if let (Node::Num(x), Node::Num(y)) = (a, b) {
+        return matches!((x, y), (ExactNum::Float(_), _) | (_, ExactNum::Float(_)))
+            && (x.to_f64() - y.to_f64()).abs() <= tolerance;
+    }
+    let difference = Node::Subtract(Box::new(a.clone()), Box::new(b.clone()));
+    let Ok(simplified) = difference.simplify(env) else {
+        return false;
+    };
+    if is_zero_node(&simplified) {
+        return true;
+    }
+    crate::evaluator::Evaluator::evaluate(&simplified, env)
+        .is_ok_and(|d| d.is_finite() && d.abs() <= tolerance)
+}
+
+fn is_one_node(node: &Node) -> bool {
+    matches!(node, Node::Num(n) if n.is_one())
+}
+
 // Implement addition for matrices
 impl Add for Matrix {
-    type Output = Result<Matrix, String>;
+    type Output = Result<Matrix, ArithmaError>;
 
     fn add(self, other: Matrix) -> Self::Output {
         if self.rows != other.rows || self.cols != other.cols {
-            return Err(format!(
+            return Err(ArithmaError::DimensionMismatch(format!(
                 "Matrix dimensions don't match for addition: {}x{} + {}x{}",
                 self.rows, self.cols, other.rows, other.cols
-            ));
+            )));
         }
 
         let mut result = Vec::with_capacity(self.rows * self.cols);
@@ -1074,14 +1630,14 @@ impl Add for Matrix {
 
 // Implement subtraction for matrices
 impl Sub for Matrix {
-    type Output = Result<Matrix, String>;
+    type Output = Result<Matrix, ArithmaError>;
 
     fn sub(self, other: Matrix) -> Self::Output {
         if self.rows != other.rows || self.cols != other.cols {
-            return Err(format!(
+            return Err(ArithmaError::DimensionMismatch(format!(
                 "Matrix dimensions don't match for subtraction: {}x{} - {}x{}",
                 self.rows, self.cols, other.rows, other.cols
-            ));
+            )));
         }
 
         let mut result = Vec::with_capacity(self.rows * self.cols);
@@ -1155,8 +1711,107 @@ impl fmt::Display for Matrix {
     }
 }
 
+/// An arithmetic node with a [`Node::Matrix`] operand, its operands
+/// already simplified: `A ± B` entrywise, scalar multiples and quotients,
+/// `AB` (a vector literal taking part as a column), `A^T`, integer powers
+/// and `-A`. Adding a scalar to a matrix is an error, as is any other
+/// operation a matrix has no meaning in.
+pub(crate) fn simplify_matrix_operation(
+    node: &Node,
+    env: &Environment,
+) -> Result<Node, ArithmaError> {
+    let scaled = |m: Matrix, k: &Node, divide: bool| {
+        let elements = m
+            .elements
+            .into_iter()
+            .map(|e| {
+                let (e, k) = (Box::new(e), Box::new(k.clone()));
+                match divide {
+                    true => Node::Divide(e, k),
+                    false => Node::Multiply(k, e),
+                }
+            })
+            .collect();
+        Matrix::new(m.rows, m.cols, elements)
+    };
+    let result = match node {
+        Node::Add(l, r) | Node::Subtract(l, r) => {
+            let adding = matches!(node, Node::Add(..));
+            match (Matrix::from_node(l), Matrix::from_node(r)) {
+                (Some(a), Some(b)) if adding => (a + b)?,
+                (Some(a), Some(b)) => (a - b)?,
+                _ => {
+                    return Err(ArithmaError::DimensionMismatch(format!(
+                        "Cannot {} a matrix and a scalar: {}",
+                        if adding { "add" } else { "subtract" },
+                        node
+                    )))
+                }
+            }
+        }
+        Node::Multiply(l, r) => match (Matrix::from_node(l), Matrix::from_node(r)) {
+            (Some(a), Some(b)) => a.multiply(&b, env)?,
+            (Some(a), None) => scaled(a, r, false)?,
+            (None, Some(b)) => scaled(b, l, false)?,
+            (None, None) => return Ok(node.clone()),
+        },
+        Node::Divide(l, r) => match (Matrix::from_node(l), Matrix::from_node(r)) {
+            (Some(a), None) => scaled(a, r, true)?,
+            _ => {
+                return Err(ArithmaError::DimensionMismatch(format!(
+                    "Cannot divide by a matrix in {}: multiply by its inverse ^{{-1}}",
+                    node
+                )))
+            }
+        },
+        Node::Power(base, exponent) => {
+            let Some(a) = Matrix::from_node(base) else {
+                return Err(ArithmaError::DimensionMismatch(format!(
+                    "Cannot raise to a matrix power: {}",
+                    node
+                )));
+            };
+            let integer = match exponent.as_ref() {
+                Node::Num(n) => n.to_i64(),
+                _ => None,
+            };
+            match (exponent.as_ref(), integer) {
+                (Node::Variable(v), _) if v == TRANSPOSE => a.transpose(),
+                (_, Some(k)) => a.power(k, env)?,
+                _ => {
+                    return Err(ArithmaError::InvalidArgument(format!(
+                        "A matrix power needs an integer exponent or ^T, got ^{{{}}}",
+                        exponent
+                    )))
+                }
+            }
+        }
+        Node::Negate(inner) => match Matrix::from_node(inner) {
+            Some(a) => -a,
+            None => return Ok(node.clone()),
+        },
+        _ => return Ok(node.clone()),
+    };
+    Ok(result.simplify_entries(env)?.to_node())
+}
+
+/// `\det` of a simplified [`Node::Matrix`] argument; `None` leaves any
+/// other call as it is.
+pub(crate) fn simplify_matrix_function(
+    name: &str,
+    args: &[Node],
+    env: &Environment,
+) -> Option<Result<Node, ArithmaError>> {
+    match (name, args) {
+        (DETERMINANT, [matrix @ Node::Matrix(_)]) => {
+            Some(Matrix::from_node(matrix)?.determinant(env))
+        }
+        _ => None,
+    }
+}
+
 /// Parse a LaTeX matrix expression and return a Matrix object
-pub fn parse_latex_matrix(latex: &str, env: &Environment) -> Result<Matrix, String> {
+pub fn parse_latex_matrix(latex: &str, env: &Environment) -> Result<Matrix, ArithmaError> {
     let mut content = latex.trim().to_string();
 
     // Check if we have a matrix environment
@@ -1178,7 +1833,9 @@ pub fn parse_latex_matrix(latex: &str, env: &Environment) -> Result<Matrix, Stri
     }
 
     if !found_env {
-        return Err("Invalid matrix format: missing matrix environment".to_string());
+        return Err(ArithmaError::Parse(ParseError::new(
+            "Invalid matrix format: missing matrix environment",
+        )));
     }
 
     // Split into rows by \\
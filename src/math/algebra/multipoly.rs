@@ -610,15 +610,13 @@ impl MultiPoly {
                 Ok(MultiPoly::Constant(r))
             }
             Node::Variable(v) => Ok(MultiPoly::variable(v)),
-            Node::Add(left, right) => {
-                let l = Self::from_node(left)?;
-                let r = Self::from_node(right)?;
-                Ok(&l + &r)
-            }
-            Node::Subtract(left, right) => {
-                let l = Self::from_node(left)?;
-                let r = Self::from_node(right)?;
-                Ok(&l - &r)
+            Node::Add(..) | Node::Subtract(..) => {
+                let mut sum = MultiPoly::zero();
+                for (term, negative) in node.sum_terms() {
+                    let term = Self::from_node(term)?;
+                    sum = if negative { &sum - &term } else { &sum + &term };
+                }
+                Ok(sum)
             }
             Node::Multiply(left, right) => {
                 let l = Self::from_node(left)?;
@@ -4,6 +4,7 @@ use num_traits::{One, Signed, ToPrimitive, Zero};
 use std::fmt;
 use std::ops::{Add, Mul, Neg, Sub};
 
+use crate::error::ArithmaError;
 use crate::exact::ExactNum;
 use crate::node::Node;
 
@@ -53,6 +54,19 @@ impl Polynomial {
         }
     }
 
+    /// Each float converted exactly, so `0.1` becomes the nearest binary
+    /// fraction rather than `1/10`.
+    pub fn from_f64_coeffs(coeffs: &[f64], var: &str) -> Result<Self, String> {
+        let coeffs = coeffs
+            .iter()
+            .map(|&c| {
+                BigRational::from_float(c)
+                    .ok_or_else(|| format!("Cannot use {} as a polynomial coefficient", c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_coeffs(coeffs, var))
+    }
+
     pub fn monomial(coeff: BigRational, degree: usize, var: &str) -> Self {
         if coeff.is_zero() {
             return Self::zero(var);
@@ -97,6 +111,19 @@ impl Polynomial {
             .unwrap_or_else(BigRational::zero)
     }
 
+    /// All coefficients, least-degree first; empty for the zero polynomial.
+    pub fn coeffs(&self) -> &[BigRational] {
+        &self.coeffs
+    }
+
+    /// The coefficients rounded to f64, least-degree first.
+    pub fn to_f64_coeffs(&self) -> Vec<f64> {
+        self.coeffs
+            .iter()
+            .map(|c| c.to_f64().unwrap_or(f64::NAN))
+            .collect()
+    }
+
     pub fn variable(&self) -> &str {
         &self.variable
     }
@@ -111,6 +138,27 @@ impl Polynomial {
         result
     }
 
+    /// Evaluate at a float by Horner's method on rounded coefficients.
+    pub fn evaluate_f64(&self, x: f64) -> f64 {
+        horner_f64(&self.to_f64_coeffs(), x)
+    }
+
+    /// Raise to a non-negative integer power by repeated squaring.
+    pub fn pow(&self, mut n: u32) -> Self {
+        let mut result = Self::one(&self.variable);
+        let mut base = self.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = &result * &base;
+            }
+            n >>= 1;
+            if n > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
     /// Make monic (leading coefficient = 1) by dividing by the leading coefficient.
     pub fn make_monic(&self) -> Self {
         match self.leading_coeff() {
@@ -376,6 +424,9 @@ impl Polynomial {
     /// Convert a Node AST into a polynomial, if the expression is polynomial
     /// in the given variable.
     pub fn from_node(node: &Node, var: &str) -> Result<Self, String> {
+        if let Some((c, degree)) = as_monomial(node, var) {
+            return Ok(Self::monomial(c, degree, var));
+        }
         match node {
             Node::Num(n) => {
                 let r = exact_to_rational(n)?;
@@ -391,15 +442,34 @@ impl Polynomial {
                     ))
                 }
             }
-            Node::Add(left, right) => {
-                let l = Self::from_node(left, var)?;
-                let r = Self::from_node(right, var)?;
-                Ok(&l + &r)
-            }
-            Node::Subtract(left, right) => {
-                let l = Self::from_node(left, var)?;
-                let r = Self::from_node(right, var)?;
-                Ok(&l - &r)
+            Node::Add(..) | Node::Subtract(..) => {
+                // Term by term into one coefficient vector, so converting an
+                // expanded polynomial takes time linear in its size
+                let mut coeffs: Vec<BigRational> = Vec::new();
+                let mut add = |degree: usize, c: BigRational, negative: bool| {
+                    if coeffs.len() <= degree {
+                        coeffs.resize(degree + 1, BigRational::zero());
+                    }
+                    if negative {
+                        coeffs[degree] -= c;
+                    } else {
+                        coeffs[degree] += c;
+                    }
+                };
+                for (term, negative) in node.sum_terms() {
+                    match as_monomial(term, var) {
+                        Some((c, degree)) => add(degree, c, negative),
+                        None => {
+                            let poly = Self::from_node(term, var)?;
+                            for (degree, c) in poly.coeffs.into_iter().enumerate() {
+                                if !c.is_zero() {
+                                    add(degree, c, negative);
+                                }
+                            }
+                        }
+                    }
+                }
+                Ok(Self::from_coeffs(coeffs, var))
             }
             Node::Multiply(left, right) => {
                 let l = Self::from_node(left, var)?;
@@ -418,11 +488,8 @@ impl Polynomial {
                         if e < 0 {
                             return Err("Negative exponent in polynomial".to_string());
                         }
-                        let mut result = Self::one(var);
-                        for _ in 0..e {
-                            result = &result * &base_poly;
-                        }
-                        Ok(result)
+                        let e = u32::try_from(e).map_err(|_| "Exponent too large")?;
+                        Ok(base_poly.pow(e))
                     }
                     _ => Err("Non-constant exponent in polynomial".to_string()),
                 }
@@ -526,6 +593,48 @@ impl Polynomial {
         roots
     }
 
+    /// The distinct real roots, in increasing order, to f64 precision.
+    ///
+    /// Works on the square-free part, so every root is a sign change.
+    /// Between consecutive real roots of the derivative the polynomial is
+    /// monotone, and each such interval (out to the Cauchy bound) holds
+    /// at most one root, found by bisection.
+    pub fn real_roots(&self) -> Vec<f64> {
+        if self.degree().unwrap_or(0) == 0 {
+            return vec![];
+        }
+        let mut roots = real_roots_f64(&self.square_free_part().to_f64_coeffs());
+        // Rational roots are exact; replace the bisected approximations
+        for exact in self.rational_roots() {
+            let r = exact.to_f64().unwrap_or(f64::NAN);
+            if let Some(near) = roots
+                .iter_mut()
+                .min_by(|a, b| (**a - r).abs().total_cmp(&(**b - r).abs()))
+            {
+                *near = r;
+            }
+        }
+        roots
+    }
+
+    /// Every complex root as `(re, im)`, repeated by multiplicity.
+    ///
+    /// Each factor of the square-free decomposition is solved by
+    /// Durand–Kerner iteration, which converges quickly on simple roots.
+    pub fn complex_roots(&self) -> Vec<(f64, f64)> {
+        let mut roots = Vec::new();
+        for (factor, multiplicity) in self.square_free_decomposition() {
+            if factor.degree().unwrap_or(0) == 0 {
+                continue;
+            }
+            let simple = durand_kerner(&factor.to_f64_coeffs());
+            for _ in 0..multiplicity {
+                roots.extend(simple.iter().copied());
+            }
+        }
+        roots
+    }
+
     /// Convert back to a Node AST.
     pub fn to_node(&self) -> Node {
         if self.is_zero() {
@@ -546,29 +655,8 @@ impl Polynomial {
             return Node::Num(ExactNum::zero());
         }
 
-        let make_term = |deg: usize, coeff: &BigRational, var: &str| -> Node {
-            let abs_coeff = coeff.abs();
-            if deg == 0 {
-                rational_to_node(&abs_coeff)
-            } else {
-                let var_part = if deg == 1 {
-                    Node::Variable(var.to_string())
-                } else {
-                    Node::Power(
-                        Box::new(Node::Variable(var.to_string())),
-                        Box::new(Node::Num(ExactNum::integer(deg as i64))),
-                    )
-                };
-                if abs_coeff.is_one() {
-                    var_part
-                } else {
-                    Node::Multiply(Box::new(rational_to_node(&abs_coeff)), Box::new(var_part))
-                }
-            }
-        };
-
         let (first_deg, first_coeff) = terms.remove(0);
-        let first_term = make_term(first_deg, &first_coeff, &self.variable);
+        let first_term = unsigned_term(first_deg, &first_coeff.abs(), &self.variable);
         let mut result = if first_coeff.is_negative() {
             Node::Negate(Box::new(first_term))
         } else {
@@ -576,7 +664,7 @@ impl Polynomial {
         };
 
         for (deg, coeff) in terms {
-            let term = make_term(deg, &coeff, &self.variable);
+            let term = unsigned_term(deg, &coeff.abs(), &self.variable);
             if coeff.is_negative() {
                 result = Node::Subtract(Box::new(result), Box::new(term));
             } else {
@@ -588,6 +676,156 @@ impl Polynomial {
     }
 }
 
+/// Long division of polynomial expressions in `var`:
+/// `numerator = quotient · denominator + remainder`, with the remainder
+/// of lower degree than the denominator. Both must have rational
+/// coefficients; anything else, or a zero denominator, is an
+/// [`ArithmaError::InvalidArgument`].
+pub fn poly_div(
+    numerator: &Node,
+    denominator: &Node,
+    var: &str,
+) -> Result<(Node, Node), ArithmaError> {
+    let polynomial = |node: &Node| {
+        Polynomial::from_node(node, var)
+            .map_err(|e| ArithmaError::InvalidArgument(format!("Not a polynomial: {}", e)))
+    };
+    let (quotient, remainder) = polynomial(numerator)?
+        .div_rem(&polynomial(denominator)?)
+        .map_err(ArithmaError::InvalidArgument)?;
+    Ok((quotient.to_node(), remainder.to_node()))
+}
+
+/// [`poly_div`] on LaTeX: `x^3+2x+1` by `x-1` gives `x^{2} + x + 3` and `4`.
+pub fn poly_div_latex(
+    numerator_latex: &str,
+    denominator_latex: &str,
+    var: &str,
+) -> Result<(String, String), ArithmaError> {
+    let numerator = crate::parser::parse_latex_raw(numerator_latex)?;
+    let denominator = crate::parser::parse_latex_raw(denominator_latex)?;
+    let (quotient, remainder) = poly_div(&numerator, &denominator, var)?;
+    Ok((quotient.to_string(), remainder.to_string()))
+}
+
+fn horner_f64(coeffs: &[f64], x: f64) -> f64 {
+    coeffs.iter().rev().fold(0.0, |acc, &c| acc * x + c)
+}
+
+/// Real roots of a square-free polynomial given by ascending f64
+/// coefficients (trailing coefficient nonzero), in increasing order.
+fn real_roots_f64(coeffs: &[f64]) -> Vec<f64> {
+    let n = coeffs.len().saturating_sub(1);
+    match n {
+        0 => return vec![],
+        1 => return vec![-coeffs[0] / coeffs[1]],
+        _ => {}
+    }
+    let lead = coeffs[n];
+    let bound = 1.0
+        + coeffs[..n]
+            .iter()
+            .fold(0.0_f64, |m, c| m.max((c / lead).abs()));
+    let derivative: Vec<f64> = coeffs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * i as f64)
+        .collect();
+    let mut ends = vec![-bound];
+    ends.extend(
+        real_roots_f64(&derivative)
+            .into_iter()
+            .filter(|x| x.abs() < bound),
+    );
+    ends.push(bound);
+
+    let f = |x: f64| horner_f64(coeffs, x);
+    let mut roots: Vec<f64> = Vec::new();
+    for w in ends.windows(2) {
+        let (mut a, mut b) = (w[0], w[1]);
+        let (fa, fb) = (f(a), f(b));
+        if fa == 0.0 {
+            roots.push(a);
+            continue;
+        }
+        if fa.signum() == fb.signum() {
+            continue;
+        }
+        // Halve until the midpoint is one of the ends
+        loop {
+            let mid = 0.5 * (a + b);
+            if mid <= a || mid >= b {
+                break;
+            }
+            let fm = f(mid);
+            if fm == 0.0 {
+                a = mid;
+                b = mid;
+                break;
+            }
+            if fm.signum() == fa.signum() {
+                a = mid;
+            } else {
+                b = mid;
+            }
+        }
+        roots.push(if f(a).abs() <= f(b).abs() { a } else { b });
+    }
+    if f(bound) == 0.0 {
+        roots.push(bound);
+    }
+    roots.dedup();
+    roots
+}
+
+/// All complex roots of a polynomial given by ascending coefficients,
+/// as `(re, im)` pairs, by Durand–Kerner iteration. Intended for
+/// polynomials whose roots are simple, such as square-free factors.
+pub(crate) fn durand_kerner(coeffs: &[f64]) -> Vec<(f64, f64)> {
+    type C = (f64, f64);
+    fn mul(a: C, b: C) -> C {
+        (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+    }
+    fn div(a: C, b: C) -> C {
+        let d = b.0 * b.0 + b.1 * b.1;
+        ((a.0 * b.0 + a.1 * b.1) / d, (a.1 * b.0 - a.0 * b.1) / d)
+    }
+
+    let n = coeffs.len() - 1;
+    let lead = coeffs[n];
+    let monic: Vec<f64> = coeffs.iter().map(|c| c / lead).collect();
+    let eval = |z: C| {
+        monic.iter().rev().fold((0.0, 0.0), |acc, &c| {
+            let p = mul(acc, z);
+            (p.0 + c, p.1)
+        })
+    };
+
+    let mut roots: Vec<C> = (0..n)
+        .scan((1.0, 0.0), |z, _| {
+            let current = *z;
+            *z = mul(*z, (0.4, 0.9));
+            Some(current)
+        })
+        .collect();
+    for _ in 0..500 {
+        let mut max_step: f64 = 0.0;
+        for k in 0..n {
+            let denom = (0..n).filter(|&j| j != k).fold((1.0, 0.0), |acc, j| {
+                mul(acc, (roots[k].0 - roots[j].0, roots[k].1 - roots[j].1))
+            });
+            let step = div(eval(roots[k]), denom);
+            roots[k] = (roots[k].0 - step.0, roots[k].1 - step.1);
+            max_step = max_step.max(step.0.abs() + step.1.abs());
+        }
+        if max_step < 1e-14 {
+            break;
+        }
+    }
+    roots
+}
+
 pub(crate) fn rational_to_node(r: &BigRational) -> Node {
     if r.is_integer() {
         Node::Num(ExactNum::integer(r.numer().try_into().unwrap_or(0)))
@@ -599,6 +837,63 @@ pub(crate) fn rational_to_node(r: &BigRational) -> Node {
     }
 }
 
+/// `c·var^k` as it is written in [`Polynomial::to_node`], for `c ≥ 0`.
+fn unsigned_term(degree: usize, coeff: &BigRational, var: &str) -> Node {
+    if degree == 0 {
+        return rational_to_node(coeff);
+    }
+    let var_part = if degree == 1 {
+        Node::Variable(var.to_string())
+    } else {
+        Node::Power(
+            Box::new(Node::Variable(var.to_string())),
+            Box::new(Node::Num(ExactNum::integer(degree as i64))),
+        )
+    };
+    if coeff.is_one() {
+        var_part
+    } else {
+        Node::Multiply(Box::new(rational_to_node(coeff)), Box::new(var_part))
+    }
+}
+
+/// `Polynomial::monomial(coeff, degree, var).to_node()`, without the dense
+/// coefficient vector: a single term of a long polynomial costs as much
+/// as the term, not as its degree.
+pub(crate) fn monomial_to_node(coeff: &BigRational, degree: usize, var: &str) -> Node {
+    if coeff.is_zero() {
+        return Node::Num(ExactNum::zero());
+    }
+    let term = unsigned_term(degree, &coeff.abs(), var);
+    if coeff.is_negative() {
+        Node::Negate(Box::new(term))
+    } else {
+        term
+    }
+}
+
+/// `c·var^k` as `(c, k)`: the shape of a term of an expanded polynomial,
+/// read without building a dense polynomial for it.
+pub(crate) fn as_monomial(node: &Node, var: &str) -> Option<(BigRational, usize)> {
+    match node {
+        Node::Num(n) => Some((exact_to_rational(n).ok()?, 0)),
+        Node::Variable(v) if v == var => Some((BigRational::one(), 1)),
+        Node::Power(base, exponent) => match (&**base, &**exponent) {
+            (Node::Variable(v), Node::Num(e)) if v == var => {
+                Some((BigRational::one(), usize::try_from(e.to_i64()?).ok()?))
+            }
+            _ => None,
+        },
+        Node::Multiply(left, right) => {
+            let (a, j) = as_monomial(left, var)?;
+            let (b, k) = as_monomial(right, var)?;
+            Some((a * b, j + k))
+        }
+        Node::Negate(inner) => as_monomial(inner, var).map(|(c, k)| (-c, k)),
+        _ => None,
+    }
+}
+
 fn exact_to_rational(n: &ExactNum) -> Result<BigRational, String> {
     match n {
         ExactNum::Rational(r) => Ok(r.clone()),
@@ -709,6 +1004,9 @@ impl<'a> Mul for &'a Polynomial {
                 continue;
             }
             for (j, b) in rhs.coeffs.iter().enumerate() {
+                if b.is_zero() {
+                    continue;
+                }
                 coeffs[i + j] = &coeffs[i + j] + &(a * b);
             }
         }
@@ -1124,4 +1422,62 @@ mod tests {
         assert_eq!(*mult, 3);
         assert_eq!(format!("{}", factor), "x + 1");
     }
+
+    #[test]
+    fn test_f64_coefficients_and_evaluation() {
+        let p = Polynomial::from_f64_coeffs(&[1.0, -0.5, 2.0], "x").unwrap();
+        assert_eq!(p.coeffs(), &[int(1), rat(-1, 2), int(2)]);
+        assert_eq!(p.to_f64_coeffs(), vec![1.0, -0.5, 2.0]);
+        assert_eq!(p.evaluate_f64(2.0), 8.0);
+        assert!(Polynomial::from_f64_coeffs(&[f64::NAN], "x").is_err());
+    }
+
+    #[test]
+    fn test_pow() {
+        let p = Polynomial::from_coeffs(vec![int(1), int(1)], "x");
+        assert_eq!(format!("{}", p.pow(3)), "x^3 + 3x^2 + 3x + 1");
+        assert_eq!(format!("{}", p.pow(0)), "1");
+    }
+
+    #[test]
+    fn test_real_roots_irrational() {
+        // x^2 - 2
+        let p = Polynomial::from_coeffs(vec![int(-2), int(0), int(1)], "x");
+        let roots = p.real_roots();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] + 2f64.sqrt()).abs() < 1e-15);
+        assert!((roots[1] - 2f64.sqrt()).abs() < 1e-15);
+
+        // x^5 - x - 1 has a single real root
+        let q =
+            Polynomial::from_coeffs(vec![int(-1), int(-1), int(0), int(0), int(0), int(1)], "x");
+        let roots = q.real_roots();
+        assert_eq!(roots.len(), 1);
+        assert!(q.evaluate_f64(roots[0]).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_real_roots_repeated_and_none() {
+        // (x - 1)^2 (x + 2) has distinct roots -2 and 1, exactly
+        let p = &Polynomial::from_coeffs(vec![int(-1), int(1)], "x").pow(2)
+            * &Polynomial::from_coeffs(vec![int(2), int(1)], "x");
+        assert_eq!(p.real_roots(), vec![-2.0, 1.0]);
+        let q = Polynomial::from_coeffs(vec![int(1), int(0), int(1)], "x");
+        assert!(q.real_roots().is_empty());
+        assert!(Polynomial::constant(int(3), "x").real_roots().is_empty());
+    }
+
+    #[test]
+    fn test_complex_roots_with_multiplicity() {
+        // (x^2 + 1)(x - 3)^2
+        let p = &Polynomial::from_coeffs(vec![int(1), int(0), int(1)], "x")
+            * &Polynomial::from_coeffs(vec![int(-3), int(1)], "x").pow(2);
+        let mut roots = p.complex_roots();
+        roots.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        let expected = [(0.0, -1.0), (0.0, 1.0), (3.0, 0.0), (3.0, 0.0)];
+        assert_eq!(roots.len(), expected.len());
+        for ((re, im), (e_re, e_im)) in roots.iter().zip(expected) {
+            assert!((re - e_re).abs() < 1e-12 && (im - e_im).abs() < 1e-12);
+        }
+    }
 }
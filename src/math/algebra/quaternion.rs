@@ -0,0 +1,327 @@
+use std::fmt;
+
+use crate::environment::Environment;
+use crate::exact::ExactNum;
+use crate::matrix::is_zero_node;
+use crate::node::Node;
+use crate::simplify::{expand, Simplifiable};
+
+/// A quaternion `w + x·i + y·j + z·k` with expression components.
+#[derive(Clone, Debug)]
+pub struct Quaternion {
+    /// Scalar (real) part
+    pub w: Node,
+    /// Coefficient of `i`
+    pub x: Node,
+    /// Coefficient of `j`
+    pub y: Node,
+    /// Coefficient of `k`
+    pub z: Node,
+}
+
+fn num(n: i64) -> Node {
+    Node::Num(ExactNum::integer(n))
+}
+
+fn add(a: Node, b: Node) -> Node {
+    Node::Add(Box::new(a), Box::new(b))
+}
+
+fn sub(a: Node, b: Node) -> Node {
+    Node::Subtract(Box::new(a), Box::new(b))
+}
+
+fn mul(a: &Node, b: &Node) -> Node {
+    Node::Multiply(Box::new(a.clone()), Box::new(b.clone()))
+}
+
+impl Quaternion {
+    /// Create a quaternion from its four components.
+    pub fn new(w: Node, x: Node, y: Node, z: Node) -> Self {
+        Quaternion { w, x, y, z }
+    }
+
+    /// The multiplicative identity `1`.
+    pub fn identity() -> Self {
+        Quaternion::new(num(1), num(0), num(0), num(0))
+    }
+
+    /// A pure quaternion `v₁i + v₂j + v₃k` representing a 3-vector.
+    pub fn pure(v: [Node; 3]) -> Self {
+        let [x, y, z] = v;
+        Quaternion::new(num(0), x, y, z)
+    }
+
+    /// Rotation by `angle` about `axis`: `cos(θ/2) + sin(θ/2)·â`.
+    /// The axis need not be a unit vector; it is normalized here.
+    pub fn from_axis_angle(
+        axis: [Node; 3],
+        angle: Node,
+        env: &Environment,
+    ) -> Result<Self, String> {
+        let length_sq = axis
+            .iter()
+            .map(|c| mul(c, c))
+            .reduce(add)
+            .expect("three components")
+            .simplify(env)?;
+        if is_zero_node(&length_sq) {
+            return Err("Rotation axis must be nonzero".to_string());
+        }
+        let length = Node::Sqrt(Box::new(length_sq)).simplify(env)?;
+        let half = Node::Divide(Box::new(angle), Box::new(num(2)));
+        let sin_half = Node::Function("sin".to_string(), vec![half.clone()]);
+        let cos_half = Node::Function("cos".to_string(), vec![half]);
+        let [x, y, z] =
+            axis.map(|c| Node::Divide(Box::new(mul(&sin_half, &c)), Box::new(length.clone())));
+        Quaternion::new(cos_half, x, y, z).simplified(env)
+    }
+
+    /// The components in `w, x, y, z` order.
+    pub fn components(&self) -> [&Node; 4] {
+        [&self.w, &self.x, &self.y, &self.z]
+    }
+
+    /// The vector part `(x, y, z)`.
+    pub fn vector_part(&self) -> [Node; 3] {
+        [self.x.clone(), self.y.clone(), self.z.clone()]
+    }
+
+    /// Components are kept expanded: products of components are sums of
+    /// monomials, and the plain simplifier leaves e.g. `(√2/2)²` factored.
+    fn simplified(self, env: &Environment) -> Result<Self, String> {
+        Ok(Quaternion::new(
+            expand(&self.w, env)?,
+            expand(&self.x, env)?,
+            expand(&self.y, env)?,
+            expand(&self.z, env)?,
+        ))
+    }
+
+    /// Component-wise sum.
+    pub fn add(&self, other: &Quaternion, env: &Environment) -> Result<Quaternion, String> {
+        Quaternion::new(
+            add(self.w.clone(), other.w.clone()),
+            add(self.x.clone(), other.x.clone()),
+            add(self.y.clone(), other.y.clone()),
+            add(self.z.clone(), other.z.clone()),
+        )
+        .simplified(env)
+    }
+
+    /// Multiply every component by a scalar expression.
+    pub fn scale(&self, factor: &Node, env: &Environment) -> Result<Quaternion, String> {
+        Quaternion::new(
+            mul(factor, &self.w),
+            mul(factor, &self.x),
+            mul(factor, &self.y),
+            mul(factor, &self.z),
+        )
+        .simplified(env)
+    }
+
+    /// Hamilton product `self · other` (not commutative).
+    pub fn multiply(&self, other: &Quaternion, env: &Environment) -> Result<Quaternion, String> {
+        let (w1, x1, y1, z1) = (&self.w, &self.x, &self.y, &self.z);
+        let (w2, x2, y2, z2) = (&other.w, &other.x, &other.y, &other.z);
+        Quaternion::new(
+            sub(sub(sub(mul(w1, w2), mul(x1, x2)), mul(y1, y2)), mul(z1, z2)),
+            sub(add(add(mul(w1, x2), mul(x1, w2)), mul(y1, z2)), mul(z1, y2)),
+            add(add(sub(mul(w1, y2), mul(x1, z2)), mul(y1, w2)), mul(z1, x2)),
+            add(sub(add(mul(w1, z2), mul(x1, y2)), mul(y1, x2)), mul(z1, w2)),
+        )
+        .simplified(env)
+    }
+
+    /// Conjugate `w − x·i − y·j − z·k`.
+    pub fn conjugate(&self, env: &Environment) -> Result<Quaternion, String> {
+        let neg = |c: &Node| Node::Negate(Box::new(c.clone()));
+        Quaternion::new(self.w.clone(), neg(&self.x), neg(&self.y), neg(&self.z)).simplified(env)
+    }
+
+    /// `w² + x² + y² + z²`, which equals `q·q̄`.
+    pub fn norm_squared(&self, env: &Environment) -> Result<Node, String> {
+        self.components()
+            .into_iter()
+            .map(|c| mul(c, c))
+            .reduce(add)
+            .expect("four components")
+            .simplify(env)
+    }
+
+    /// Euclidean norm `√(w² + x² + y² + z²)`.
+    pub fn norm(&self, env: &Environment) -> Result<Node, String> {
+        Node::Sqrt(Box::new(self.norm_squared(env)?)).simplify(env)
+    }
+
+    /// Multiplicative inverse `q̄ / |q|²`.
+    pub fn inverse(&self, env: &Environment) -> Result<Quaternion, String> {
+        let norm_sq = self.norm_squared(env)?;
+        if is_zero_node(&norm_sq) {
+            return Err("Cannot invert the zero quaternion".to_string());
+        }
+        let conj = self.conjugate(env)?;
+        let div = |c: Node| Node::Divide(Box::new(c), Box::new(norm_sq.clone()));
+        Quaternion::new(div(conj.w), div(conj.x), div(conj.y), div(conj.z)).simplified(env)
+    }
+
+    /// Rotate a 3-vector: the vector part of `q·v·q⁻¹`. For a unit
+    /// quaternion from [`Quaternion::from_axis_angle`] this is the rotation
+    /// by that angle about that axis; a non-unit `q` rotates identically
+    /// because its scale cancels.
+    pub fn rotate_vector(&self, v: &[Node; 3], env: &Environment) -> Result<[Node; 3], String> {
+        let rotated = self
+            .multiply(&Quaternion::pure(v.clone()), env)?
+            .multiply(&self.inverse(env)?, env)?;
+        Ok(rotated.vector_part())
+    }
+
+    /// Render as `w + x i + y j + z k`, omitting zero components. The
+    /// output parses back with [`parse_latex_quaternion`].
+    pub fn to_latex(&self) -> String {
+        let mut out = String::new();
+        for (component, unit) in [
+            (&self.w, ""),
+            (&self.x, "i"),
+            (&self.y, "j"),
+            (&self.z, "k"),
+        ] {
+            if is_zero_node(component) {
+                continue;
+            }
+            let (magnitude, negative) = match component {
+                Node::Num(n) if n.to_f64() < 0.0 => (Node::Num(n.abs()), true),
+                Node::Negate(inner) => ((**inner).clone(), true),
+                other => (other.clone(), false),
+            };
+            let term = if unit.is_empty() {
+                magnitude.to_string()
+            } else if matches!(&magnitude, Node::Num(n) if n.is_one()) {
+                unit.to_string()
+            } else {
+                mul(&magnitude, &Node::Variable(unit.to_string())).to_string()
+            };
+            match (out.is_empty(), negative) {
+                (true, true) => out.push_str(&format!("-{}", term)),
+                (true, false) => out.push_str(&term),
+                (false, true) => out.push_str(&format!(" - {}", term)),
+                (false, false) => out.push_str(&format!(" + {}", term)),
+            }
+        }
+        if out.is_empty() {
+            out.push('0');
+        }
+        out
+    }
+}
+
+impl fmt::Display for Quaternion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_latex())
+    }
+}
+
+/// Parse a quaternion written as a sum of scalar, `i`, `j`, and `k` terms,
+/// e.g. `1 + 2i - 3j + \frac{1}{2}k` or `a + b \cdot i`. Products and powers
+/// are expanded first, so `2(i + j)` is accepted; a term that is not
+/// linear in exactly one unit (`i^2`, `i \cdot j`) is an error. Quaternion
+/// multiplication rules are not applied while parsing.
+pub fn parse_latex_quaternion(latex: &str, env: &Environment) -> Result<Quaternion, String> {
+    let expr = crate::parser::parse_latex_raw(latex)?;
+    let expanded = expand(&expr, env)?;
+
+    let mut terms = Vec::new();
+    flatten_signed_terms(&expanded, false, &mut terms);
+
+    let mut parts: [Vec<Node>; 4] = Default::default();
+    for (term, negative) in terms {
+        let (slot, coeff) = split_unit(&term)?;
+        parts[slot].push(if negative {
+            Node::Negate(Box::new(coeff))
+        } else {
+            coeff
+        });
+    }
+
+    let [w, x, y, z] = parts.map(|terms| terms.into_iter().reduce(add).unwrap_or_else(|| num(0)));
+    Quaternion::new(w, x, y, z).simplified(env)
+}
+
+fn flatten_signed_terms(node: &Node, negative: bool, out: &mut Vec<(Node, bool)>) {
+    match node {
+        Node::Add(l, r) => {
+            flatten_signed_terms(l, negative, out);
+            flatten_signed_terms(r, negative, out);
+        }
+        Node::Subtract(l, r) => {
+            flatten_signed_terms(l, negative, out);
+            flatten_signed_terms(r, !negative, out);
+        }
+        Node::Negate(inner) => flatten_signed_terms(inner, !negative, out),
+        other => out.push((other.clone(), negative)),
+    }
+}
+
+/// Which component a term belongs to (0 = scalar, 1..3 = i, j, k) and its
+/// coefficient.
+fn split_unit(term: &Node) -> Result<(usize, Node), String> {
+    fn unit_slot(node: &Node) -> Option<usize> {
+        match node {
+            Node::Variable(v) => ["i", "j", "k"].iter().position(|u| u == v).map(|p| p + 1),
+            _ => None,
+        }
+    }
+    fn mentions_unit(node: &Node) -> bool {
+        ["i", "j", "k"].iter().any(|u| node.contains_variable(u))
+    }
+
+    let mut factors = Vec::new();
+    let mut divisor: Option<Node> = None;
+    let mut stack = vec![term.clone()];
+    while let Some(node) = stack.pop() {
+        match node {
+            Node::Multiply(l, r) => {
+                stack.push(*r);
+                stack.push(*l);
+            }
+            Node::Divide(n, d) if divisor.is_none() && !mentions_unit(&d) => {
+                divisor = Some(*d);
+                stack.push(*n);
+            }
+            other => factors.push(other),
+        }
+    }
+
+    let unit_positions: Vec<usize> = factors
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| unit_slot(f).is_some())
+        .map(|(idx, _)| idx)
+        .collect();
+    let slot = match unit_positions.as_slice() {
+        [] => 0,
+        [idx] => unit_slot(&factors.remove(*idx)).expect("unit factor"),
+        _ => {
+            return Err(format!(
+                "Quaternion term '{}' multiplies several units",
+                term
+            ))
+        }
+    };
+    if factors.iter().any(mentions_unit) {
+        return Err(format!(
+            "Quaternion term '{}' is not linear in i, j, k",
+            term
+        ));
+    }
+
+    let coeff = factors
+        .into_iter()
+        .reduce(|a, b| Node::Multiply(Box::new(a), Box::new(b)))
+        .unwrap_or_else(|| num(1));
+    let coeff = match divisor {
+        Some(d) => Node::Divide(Box::new(coeff), Box::new(d)),
+        None => coeff,
+    };
+    Ok((slot, coeff))
+}
@@ -0,0 +1,373 @@
+//! Vectors with expression components.
+//!
+//! A [`Vector`] is the column of a one-column (or one-row) matrix, with
+//! the dot and cross products, norms and projections. Inside expressions a
+//! vector appears as `\vec{a}` (a name bound to a list in the
+//! [`Environment`]) or as a literal `\begin{pmatrix} 1 \\ 2 \\ 3 \end{pmatrix}`;
+//! the parser turns `\cdot` between two vectors into a dot product,
+//! `\times` into a cross product, and `|\vec{a}|` is the Euclidean norm.
+//! In the tree these are functions named by the constants below, so
+//! evaluation and simplification dispatch on them like any other call.
+
+use std::fmt;
+
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::exact::ExactNum;
+use crate::matrix::{is_zero_node, parse_latex_matrix, Matrix};
+use crate::node::Node;
+use crate::simplify::Simplifiable;
+
+/// `\vec{a}`: the vector bound to the name `a`.
+pub const VECTOR_NAME: &str = "vec";
+/// A literal vector; its arguments are the components.
+pub const VECTOR_LITERAL: &str = "vector";
+/// `\vec{a} \cdot \vec{b}`
+pub const DOT: &str = "dot";
+/// `\vec{a} \times \vec{b}`
+pub const CROSS: &str = "cross";
+
+/// A column vector with expression components.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vector {
+    pub components: Vec<Node>,
+}
+
+fn mul(a: &Node, b: &Node) -> Node {
+    Node::Multiply(Box::new(a.clone()), Box::new(b.clone()))
+}
+
+fn sub(a: Node, b: Node) -> Node {
+    Node::Subtract(Box::new(a), Box::new(b))
+}
+
+impl Vector {
+    /// A vector with at least one component.
+    pub fn new(components: Vec<Node>) -> Result<Self, String> {
+        if components.is_empty() {
+            return Err("A vector needs at least one component".to_string());
+        }
+        Ok(Vector { components })
+    }
+
+    /// The entries of a one-column or one-row matrix.
+    pub fn from_matrix(matrix: &Matrix) -> Result<Self, String> {
+        if matrix.cols != 1 && matrix.rows != 1 {
+            return Err(format!(
+                "A vector is a single row or column, got a {}×{} matrix",
+                matrix.rows, matrix.cols
+            ));
+        }
+        Vector::new(matrix.elements.clone())
+    }
+
+    /// The vector as a one-column matrix.
+    pub fn to_matrix(&self) -> Matrix {
+        Matrix::new(self.len(), 1, self.components.clone()).expect("one entry per row")
+    }
+
+    /// The vector from a literal node, or `None` for any other node.
+    pub fn from_node(node: &Node) -> Option<Self> {
+        match node {
+            Node::Function(name, components) if name == VECTOR_LITERAL => {
+                Vector::new(components.clone()).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// The vector as a literal node.
+    pub fn to_node(&self) -> Node {
+        Node::Function(VECTOR_LITERAL.to_string(), self.components.clone())
+    }
+
+    /// Number of components.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    fn check_same_length(&self, other: &Vector, operation: &str) -> Result<(), String> {
+        if self.len() != other.len() {
+            return Err(format!(
+                "Cannot take the {} of vectors with {} and {} components",
+                operation,
+                self.len(),
+                other.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// `a · b = Σ aᵢbᵢ`
+    pub fn dot(&self, other: &Vector, env: &Environment) -> Result<Node, String> {
+        self.check_same_length(other, "dot product")?;
+        self.components
+            .iter()
+            .zip(&other.components)
+            .map(|(a, b)| mul(a, b))
+            .reduce(|sum, term| Node::Add(Box::new(sum), Box::new(term)))
+            .expect("at least one component")
+            .simplify(env)
+    }
+
+    /// `a × b` for 3-vectors.
+    pub fn cross(&self, other: &Vector, env: &Environment) -> Result<Vector, String> {
+        if self.len() != 3 || other.len() != 3 {
+            return Err(format!(
+                "The cross product needs two 3-component vectors, got {} and {}",
+                self.len(),
+                other.len()
+            ));
+        }
+        let (a, b) = (&self.components, &other.components);
+        let components = [(1, 2), (2, 0), (0, 1)]
+            .iter()
+            .map(|&(i, j)| sub(mul(&a[i], &b[j]), mul(&a[j], &b[i])).simplify(env))
+            .collect::<Result<_, _>>()?;
+        Vector::new(components)
+    }
+
+    /// The Euclidean norm `‖a‖ = √(a · a)`.
+    pub fn norm(&self, env: &Environment) -> Result<Node, String> {
+        Node::Sqrt(Box::new(self.dot(self, env)?)).simplify(env)
+    }
+
+    /// The unit vector `a / ‖a‖`.
+    pub fn normalize(&self, env: &Environment) -> Result<Vector, String> {
+        let norm = self.norm(env)?;
+        if is_zero_node(&norm) {
+            return Err("Cannot normalize the zero vector".to_string());
+        }
+        self.scale_by(&norm, true, env)
+    }
+
+    /// The signed length of this vector along `onto`: `a · b / ‖b‖`.
+    pub fn scalar_projection(&self, onto: &Vector, env: &Environment) -> Result<Node, String> {
+        let norm = onto.norm(env)?;
+        if is_zero_node(&norm) {
+            return Err("Cannot project onto the zero vector".to_string());
+        }
+        Node::Divide(Box::new(self.dot(onto, env)?), Box::new(norm)).simplify(env)
+    }
+
+    /// The component of this vector along `onto`: `(a · b / b · b) b`.
+    pub fn projection(&self, onto: &Vector, env: &Environment) -> Result<Vector, String> {
+        let length_sq = onto.dot(onto, env)?;
+        if is_zero_node(&length_sq) {
+            return Err("Cannot project onto the zero vector".to_string());
+        }
+        let factor = Node::Divide(Box::new(self.dot(onto, env)?), Box::new(length_sq));
+        onto.scale_by(&factor, false, env)
+    }
+
+    /// Multiply (or, with `divide`, divide) every component by `factor`.
+    fn scale_by(&self, factor: &Node, divide: bool, env: &Environment) -> Result<Vector, String> {
+        let components = self
+            .components
+            .iter()
+            .map(|c| {
+                let (c, k) = (Box::new(c.clone()), Box::new(factor.clone()));
+                match divide {
+                    true => Node::Divide(c, k),
+                    false => Node::Multiply(k, c),
+                }
+                .simplify(env)
+            })
+            .collect::<Result<_, _>>()?;
+        Vector::new(components)
+    }
+
+    /// The vector as a LaTeX column: `\begin{pmatrix} 1 \\ 2 \end{pmatrix}`.
+    pub fn to_latex(&self) -> String {
+        let rows: Vec<String> = self.components.iter().map(|c| c.to_latex()).collect();
+        format!(
+            "\\begin{{pmatrix}} {} \\end{{pmatrix}}",
+            rows.join(" \\\\ ")
+        )
+    }
+}
+
+/// `(1, 2, 3)`
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let components: Vec<String> = self.components.iter().map(|c| c.to_string()).collect();
+        write!(f, "({})", components.join(", "))
+    }
+}
+
+/// Whether `name` is one of the vector functions above.
+pub fn is_vector_function(name: &str) -> bool {
+    matches!(name, VECTOR_NAME | VECTOR_LITERAL | DOT | CROSS)
+}
+
+/// Display for a vector function node, in the LaTeX it parses from.
+pub(crate) fn fmt_vector_function(
+    name: &str,
+    args: &[Node],
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    let operand = |node: &Node| match node {
+        Node::Function(name, _) if matches!(name.as_str(), VECTOR_NAME | VECTOR_LITERAL) => {
+            node.to_string()
+        }
+        _ => format!("({})", node),
+    };
+    match (name, args) {
+        (VECTOR_NAME, [Node::Variable(v)]) => {
+            write!(f, "\\vec{{{}}}", crate::tokenizer::variable_latex(v))
+        }
+        (VECTOR_LITERAL, components) => {
+            let rows: Vec<String> = components.iter().map(|c| c.to_string()).collect();
+            write!(
+                f,
+                "\\begin{{pmatrix}} {} \\end{{pmatrix}}",
+                rows.join(" \\\\ ")
+            )
+        }
+        (DOT, [l, r]) => write!(f, "{} \\cdot {}", operand(l), operand(r)),
+        (CROSS, [l, r]) => write!(f, "{} \\times {}", operand(l), operand(r)),
+        _ => {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            write!(f, "{}({})", name, args.join(", "))
+        }
+    }
+}
+
+/// Parse a one-column (or one-row) matrix environment as a vector.
+pub fn parse_latex_vector(latex: &str, env: &Environment) -> Result<Vector, String> {
+    Vector::from_matrix(&parse_latex_matrix(latex, env)?)
+}
+
+/// Whether `node` denotes a vector rather than a number: a vector name or
+/// literal, a cross product, or a sum, difference, negation or scalar
+/// multiple of vectors.
+pub fn is_vector_expression(node: &Node) -> bool {
+    match node {
+        Node::Function(name, _) => matches!(name.as_str(), VECTOR_NAME | VECTOR_LITERAL | CROSS),
+        Node::Add(l, r) | Node::Subtract(l, r) => {
+            is_vector_expression(l) && is_vector_expression(r)
+        }
+        Node::Negate(inner) => is_vector_expression(inner),
+        Node::Multiply(l, r) => is_vector_expression(l) != is_vector_expression(r),
+        Node::Divide(l, r) => is_vector_expression(l) && !is_vector_expression(r),
+        _ => false,
+    }
+}
+
+/// The components of a vector expression, evaluated exactly.
+pub fn evaluate_vector(node: &Node, env: &Environment) -> Result<Vec<ExactNum>, String> {
+    let elementwise =
+        |l: &Node, r: &Node, f: fn(ExactNum, ExactNum) -> ExactNum| -> Result<_, String> {
+            let (a, b) = (evaluate_vector(l, env)?, evaluate_vector(r, env)?);
+            if a.len() != b.len() {
+                return Err(format!(
+                    "Cannot combine vectors with {} and {} components",
+                    a.len(),
+                    b.len()
+                ));
+            }
+            Ok(a.into_iter().zip(b).map(|(x, y)| f(x, y)).collect())
+        };
+    let scaled = |v: &Node, k: &Node, f: fn(ExactNum, ExactNum) -> ExactNum| {
+        let k = Evaluator::evaluate_exact(k, env)?;
+        Ok(evaluate_vector(v, env)?
+            .into_iter()
+            .map(|c| f(c, k.clone()))
+            .collect())
+    };
+    match node {
+        Node::Function(name, args) if name == VECTOR_NAME => match args.as_slice() {
+            [Node::Variable(v)] => env
+                .get_list(v)
+                .map(<[ExactNum]>::to_vec)
+                .ok_or_else(|| format!("No vector is bound to '{}'", v)),
+            [inner] => evaluate_vector(inner, env),
+            _ => Err("\\vec takes one name".to_string()),
+        },
+        Node::Function(name, args) if name == VECTOR_LITERAL => args
+            .iter()
+            .map(|c| Evaluator::evaluate_exact(c, env).map_err(String::from))
+            .collect(),
+        Node::Function(name, args) if name == CROSS && args.len() == 2 => {
+            let to_vector = |node: &Node| -> Result<Vector, String> {
+                let values = evaluate_vector(node, env)?;
+                Vector::new(values.into_iter().map(Node::Num).collect())
+            };
+            let product = to_vector(&args[0])?.cross(&to_vector(&args[1])?, env)?;
+            product
+                .components
+                .iter()
+                .map(|c| Evaluator::evaluate_exact(c, env).map_err(String::from))
+                .collect()
+        }
+        Node::Add(l, r) => elementwise(l, r, |a, b| a + b),
+        Node::Subtract(l, r) => elementwise(l, r, |a, b| a - b),
+        Node::Negate(inner) => Ok(evaluate_vector(inner, env)?
+            .into_iter()
+            .map(|c| -c)
+            .collect()),
+        Node::Multiply(l, r) if is_vector_expression(r) => scaled(r, l, |c, k| k * c),
+        Node::Multiply(l, r) => scaled(l, r, |c, k| c * k),
+        Node::Divide(l, r) => scaled(l, r, |c, k| c / k),
+        _ => Err(format!("{} is not a vector", node)),
+    }
+}
+
+/// `a · b` of two vector expressions, evaluated exactly.
+pub fn evaluate_dot(a: &Node, b: &Node, env: &Environment) -> Result<ExactNum, String> {
+    let (a, b) = (evaluate_vector(a, env)?, evaluate_vector(b, env)?);
+    if a.len() != b.len() {
+        return Err(format!(
+            "Cannot take the dot product of vectors with {} and {} components",
+            a.len(),
+            b.len()
+        ));
+    }
+    Ok(a.into_iter()
+        .zip(b)
+        .fold(ExactNum::zero(), |sum, (x, y)| sum + x * y))
+}
+
+/// `‖a‖` of a vector expression, evaluated exactly where the square root
+/// is rational.
+pub fn evaluate_norm(a: &Node, env: &Environment) -> Result<ExactNum, String> {
+    Ok(evaluate_dot(a, a, env)?.sqrt())
+}
+
+/// A vector function of simplified arguments, computed where they are
+/// literal vectors (or `\vec{a}` with `a` bound to a list): `None` leaves
+/// the call as it is.
+pub(crate) fn simplify_vector_function(
+    name: &str,
+    args: &[Node],
+    env: &Environment,
+) -> Option<Result<Node, String>> {
+    let literal = |node: &Node| match node {
+        Node::Function(n, inner) if n == VECTOR_NAME => match inner.as_slice() {
+            [Node::Variable(v)] => env.get_list(v).and_then(|values| {
+                Vector::new(values.iter().cloned().map(Node::Num).collect()).ok()
+            }),
+            [inner] => Vector::from_node(inner),
+            _ => None,
+        },
+        _ => Vector::from_node(node),
+    };
+    match (name, args) {
+        (VECTOR_NAME, [_]) => {
+            literal(&Node::Function(name.to_string(), args.to_vec())).map(|v| Ok(v.to_node()))
+        }
+        (DOT, [a, b]) => {
+            let (a, b) = (literal(a)?, literal(b)?);
+            Some(a.dot(&b, env))
+        }
+        (CROSS, [a, b]) => {
+            let (a, b) = (literal(a)?, literal(b)?);
+            Some(a.cross(&b, env).map(|v| v.to_node()))
+        }
+        _ => None,
+    }
+}
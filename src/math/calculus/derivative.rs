@@ -1,10 +1,12 @@
+use crate::error::ArithmaError;
 use crate::exact::ExactNum;
 use crate::function_meta::canonical_function_name;
 use crate::node::Node;
-use crate::polynomial::Polynomial;
+use crate::polynomial::{as_monomial, monomial_to_node, Polynomial};
 use crate::substitute::substitute_variable;
+use num_rational::BigRational;
 
-pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
+pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, ArithmaError> {
     let env = crate::environment::Environment::new();
     let expr =
         &crate::simplify::Simplifiable::simplify(expr, &env).unwrap_or_else(|_| expr.clone());
@@ -13,12 +15,21 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
     // *literal* zero. Without this, d/dx of a constant like √π/2 is an
     // exact-zero tree — (1/(2√π))·0 — that downstream consumers must
     // evaluate to discover is zero; if that tree ends up multiplying a
-    // function that refuses numeric evaluation (erf, Ei, li), every sample
+    // function that refuses numeric evaluation (Ei, li), every sample
     // point starves. Zeros should be born literal.
     if expr.is_provably_free_of(var_name) {
         return Ok(Node::Num(ExactNum::zero()));
     }
 
+    // c·xⁿ on its own, without a dense polynomial of degree n
+    if let Some((coeff, degree)) = as_monomial(expr, var_name) {
+        let derivative = coeff * BigRational::from_integer(degree.into());
+        return Ok(monomial_to_node(
+            &derivative,
+            degree.saturating_sub(1),
+            var_name,
+        ));
+    }
     if let Ok(poly) = Polynomial::from_node(expr, var_name) {
         return Ok(poly.derivative().to_node());
     }
@@ -36,24 +47,20 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
             }
         }
 
-        // d/dx(f + g) = d/dx(f) + d/dx(g)
-        Node::Add(left, right) => {
-            let left_derivative = differentiate(left, var_name)?;
-            let right_derivative = differentiate(right, var_name)?;
-            Ok(Node::Add(
-                Box::new(left_derivative),
-                Box::new(right_derivative),
-            ))
-        }
-
-        // d/dx(f - g) = d/dx(f) - d/dx(g)
-        Node::Subtract(left, right) => {
-            let left_derivative = differentiate(left, var_name)?;
-            let right_derivative = differentiate(right, var_name)?;
-            Ok(Node::Subtract(
-                Box::new(left_derivative),
-                Box::new(right_derivative),
-            ))
+        // d/dx(f ± g) = d/dx(f) ± d/dx(g), over the whole chain at once:
+        // recursing into a long sum one `+` at a time would simplify every
+        // partial sum again
+        Node::Add(..) | Node::Subtract(..) => {
+            let mut terms = expr.sum_terms().into_iter();
+            let (first, _) = terms.next().expect("a sum has a term");
+            terms.try_fold(differentiate(first, var_name)?, |sum, (term, negative)| {
+                let derivative = Box::new(differentiate(term, var_name)?);
+                Ok(if negative {
+                    Node::Subtract(Box::new(sum), derivative)
+                } else {
+                    Node::Add(Box::new(sum), derivative)
+                })
+            })
         }
 
         // Product rule: d/dx(f*g) = f*dg/dx + g*df/dx
@@ -64,7 +71,7 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
             // Constant-factor cases first: d(c·f) = c·f'. Emitting the dead
             // term f·0 instead would keep a mention of f in the result —
             // harmless for most f, but a function that refuses numeric
-            // evaluation (erf, Ei, li) would poison every evaluation of a
+            // evaluation (Ei, li) would poison every evaluation of a
             // derivative it appears in, even multiplied by zero.
             let left_is_zero = matches!(left_derivative, Node::Num(ref n) if n.is_zero());
             let right_is_zero = matches!(right_derivative, Node::Num(ref n) if n.is_zero());
@@ -214,9 +221,11 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                     let original = Node::Power(base.clone(), exponent.clone());
                     let ln_base = Node::Function("ln".to_string(), vec![*base.clone()]);
                     let term1 = Node::Multiply(Box::new(exp_deriv), Box::new(ln_base));
-                    let term2 = Node::Multiply(
-                        exponent.clone(),
-                        Box::new(Node::Divide(Box::new(base_deriv), base.clone())),
+                    // g·f'/f as a single quotient so a base that is (a multiple
+                    // of) its own exponent cancels: d/dx xˣ = xˣ(ln x + 1).
+                    let term2 = Node::Divide(
+                        Box::new(Node::Multiply(exponent.clone(), Box::new(base_deriv))),
+                        base.clone(),
                     );
                     Ok(Node::Multiply(
                         Box::new(original),
@@ -226,25 +235,8 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
             }
         }
 
-        // d/dx(sqrt(f)) = 1/(2*sqrt(f)) * df/dx
-        Node::Sqrt(operand) => {
-            let operand_derivative = differentiate(operand, var_name)?;
-
-            // 1/(2*sqrt(f))
-            let coefficient = Node::Divide(
-                Box::new(Node::Num(ExactNum::one())),
-                Box::new(Node::Multiply(
-                    Box::new(Node::Num(ExactNum::two())),
-                    Box::new(Node::Sqrt(operand.clone())),
-                )),
-            );
-
-            // 1/(2*sqrt(f)) * df/dx
-            Ok(Node::Multiply(
-                Box::new(coefficient),
-                Box::new(operand_derivative),
-            ))
-        }
+        // d/dx(sqrt(f)) = df/dx / (2*sqrt(f))
+        Node::Sqrt(operand) => sqrt_rule(expr, operand, var_name),
 
         // d/dx(|f|) = sgn(f) * df/dx where sgn(f) = f/|f| for f != 0
         Node::Abs(operand) => {
@@ -257,6 +249,13 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
             Ok(Node::Multiply(Box::new(sign), Box::new(operand_derivative)))
         }
 
+        // Step functions are constant between their jumps: the derivative
+        // is zero almost everywhere, and `differentiate_with_domain` names
+        // the jumps it leaves out.
+        Node::Floor(_) | Node::Ceil(_) | Node::Round(_) | Node::Trunc(_) => {
+            Ok(Node::Num(ExactNum::zero()))
+        }
+
         // d/dx(-f) = -df/dx
         Node::Negate(operand) => {
             let operand_derivative = differentiate(operand, var_name)?;
@@ -288,14 +287,98 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                     ))
                 } else {
                     // For now, return an error for the more complex case where bounds depend on the variable
-                    Err(
+                    Err(ArithmaError::NotImplemented(
                         "Differentiation of summations with variable bounds not yet implemented"
                             .to_string(),
-                    )
+                    ))
                 }
             }
         }
 
+        // A list's elements are constants, so only the body varies
+        Node::SumOver(index, list, body) => {
+            if list.contains_variable(var_name) {
+                return Err(ArithmaError::InvalidArgument(format!(
+                    "Cannot differentiate with respect to the list '{}'",
+                    var_name
+                )));
+            }
+            if index == var_name || !body.contains_variable(var_name) {
+                return Ok(Node::Num(ExactNum::zero()));
+            }
+            Ok(Node::SumOver(
+                index.clone(),
+                list.clone(),
+                Box::new(differentiate(body, var_name)?),
+            ))
+        }
+
+        Node::Integral(var, bounds, body) => {
+            let Some((lower, upper)) = bounds else {
+                // Fundamental theorem: d/dx ∫ f(x) dx = f(x)
+                if var == var_name {
+                    return Ok((**body).clone());
+                }
+                let body_derivative = differentiate(body, var_name)?;
+                return Ok(Node::Integral(var.clone(), None, Box::new(body_derivative)));
+            };
+            // Leibniz rule: d/dx ∫_{a(x)}^{b(x)} f(t, x) dt
+            //   = f(b, x)·b' − f(a, x)·a' + ∫_a^b ∂f/∂x dt
+            let is_zero = |n: &Node| matches!(n, Node::Num(v) if v.is_zero());
+            let mut terms = Vec::new();
+            let upper_rate = differentiate(upper, var_name)?;
+            if !is_zero(&upper_rate) {
+                let at_upper = substitute_variable(body, var, upper)?;
+                terms.push(Node::Multiply(Box::new(at_upper), Box::new(upper_rate)));
+            }
+            let lower_rate = differentiate(lower, var_name)?;
+            if !is_zero(&lower_rate) {
+                let at_lower = substitute_variable(body, var, lower)?;
+                terms.push(Node::Negate(Box::new(Node::Multiply(
+                    Box::new(at_lower),
+                    Box::new(lower_rate),
+                ))));
+            }
+            if var != var_name && body.contains_variable(var_name) {
+                terms.push(Node::Integral(
+                    var.clone(),
+                    bounds.clone(),
+                    Box::new(differentiate(body, var_name)?),
+                ));
+            }
+            Ok(terms
+                .into_iter()
+                .reduce(|acc, term| Node::Add(Box::new(acc), Box::new(term)))
+                .unwrap_or(Node::Num(ExactNum::zero())))
+        }
+
+        // The input is simplified first, so a limit still here is one the
+        // engine could not resolve
+        Node::Limit(..) => {
+            if !expr.contains_variable(var_name) {
+                return Ok(Node::Num(ExactNum::zero()));
+            }
+            Err(ArithmaError::NotImplemented(format!(
+                "Cannot differentiate an unresolved limit with respect to '{}'",
+                var_name
+            )))
+        }
+
+        // d/dy (d/dx f): resolve the inner derivative when possible,
+        // otherwise hold the outer one too
+        Node::Derivative(var, body) => {
+            if let Ok(inner) = differentiate(body, var) {
+                return differentiate(&inner, var_name);
+            }
+            if !expr.contains_variable(var_name) {
+                return Ok(Node::Num(ExactNum::zero()));
+            }
+            Ok(Node::Derivative(
+                var_name.to_string(),
+                Box::new(expr.clone()),
+            ))
+        }
+
         // Generalized product rule: d/dx ∏_{i=a}^{b} f(i,x) = Σ_k (df(k,x)/dx · ∏_{j≠k} f(j,x))
         Node::Product(index, start, end, body) => {
             if index == var_name || !body.contains_variable(var_name) {
@@ -303,21 +386,21 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
             }
 
             // Bounds must not depend on the differentiation variable.
-            let bound_is_const = |b: &Node| -> Result<bool, String> {
+            let bound_is_const = |b: &Node| -> Result<bool, ArithmaError> {
                 Ok(matches!(differentiate(b, var_name)?, Node::Num(n) if n.is_zero()))
             };
             if !(bound_is_const(start)? && bound_is_const(end)?) {
-                return Err(
+                return Err(ArithmaError::NotImplemented(
                     "Differentiation of products with variable bounds not yet implemented"
                         .to_string(),
-                );
+                ));
             }
 
             // Expand the finite product ∏_{i=a}^{b} f(i, x) into an explicit chain
             // of factors, then let the ordinary product rule (Node::Multiply) derive
             // it. This reproduces the generalized product rule without hand-rolling it.
             let bound_err = || {
-                "Differentiation of product notation requires constant integer bounds when the body depends on the differentiation variable".to_string()
+                ArithmaError::NotImplemented("Differentiation of product notation requires constant integer bounds when the body depends on the differentiation variable".to_string())
             };
             let (start_i, end_i) = match (start.as_ref(), end.as_ref()) {
                 (Node::Num(a), Node::Num(b)) => (
@@ -340,34 +423,24 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
         // Function differentiation
         Node::Function(name, args) => {
             match canonical_function_name(name) {
+                // Zero almost everywhere, like the step-function nodes
+                "floor" | "ceil" | "round" | "trunc" | "sgn" | "sign" if args.len() == 1 => {
+                    Ok(Node::Num(ExactNum::zero()))
+                }
                 "sqrt" => {
                     if args.len() != 1 {
-                        return Err("sqrt function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "sqrt function requires exactly one argument".to_string(),
+                        ));
                     }
-
-                    // d/dx(sqrt(f)) = 1/(2*sqrt(f)) * df/dx
-                    let operand = &args[0];
-                    let operand_derivative = differentiate(operand, var_name)?;
-
-                    // 1/(2*sqrt(f))
-                    let coefficient = Node::Divide(
-                        Box::new(Node::Num(ExactNum::one())),
-                        Box::new(Node::Multiply(
-                            Box::new(Node::Num(ExactNum::two())),
-                            Box::new(Node::Function("sqrt".to_string(), vec![operand.clone()])),
-                        )),
-                    );
-
-                    // 1/(2*sqrt(f)) * df/dx
-                    Ok(Node::Multiply(
-                        Box::new(coefficient),
-                        Box::new(operand_derivative),
-                    ))
+                    sqrt_rule(expr, &args[0], var_name)
                 }
                 // --- Circular trigonometric ---
                 "sin" => {
                     if args.len() != 1 {
-                        return Err("sin function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "sin function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(sin(f)) = cos(f) * df/dx
@@ -385,7 +458,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "cos" => {
                     if args.len() != 1 {
-                        return Err("cos function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "cos function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(cos(f)) = -sin(f) * df/dx
@@ -406,7 +481,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "tan" => {
                     if args.len() != 1 {
-                        return Err("tan function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "tan function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(tan(f)) = sec^2(f) * df/dx
@@ -431,7 +508,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Reciprocal trigonometric ---
                 "csc" => {
                     if args.len() != 1 {
-                        return Err("csc function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "csc function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(csc(f)) = -csc(f)·cot(f) · f'
                     let f = &args[0];
@@ -446,7 +525,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "sec" => {
                     if args.len() != 1 {
-                        return Err("sec function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "sec function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(sec(f)) = sec(f)·tan(f) · f'
                     let f = &args[0];
@@ -461,7 +542,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "cot" => {
                     if args.len() != 1 {
-                        return Err("cot function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "cot function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(cot(f)) = -csc²(f) · f'
                     let f = &args[0];
@@ -477,7 +560,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Inverse circular trigonometric ---
                 "arcsin" => {
                     if args.len() != 1 {
-                        return Err("arcsin function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arcsin function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arcsin(f)) = 1/√(1-f²) · f'
                     let f = &args[0];
@@ -498,7 +583,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "arccos" => {
                     if args.len() != 1 {
-                        return Err("arccos function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arccos function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arccos(f)) = -1/√(1-f²) · f'
                     let f = &args[0];
@@ -519,7 +606,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "arctan" => {
                     if args.len() != 1 {
-                        return Err("arctan function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arctan function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arctan(f)) = 1/(1+f²) · f'
                     let f = &args[0];
@@ -541,7 +630,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Inverse reciprocal trigonometric ---
                 "arccsc" => {
                     if args.len() != 1 {
-                        return Err("arccsc function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arccsc function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arccsc(f)) = -1/(|f|·√(f²-1)) · f'
                     let f = &args[0];
@@ -565,7 +656,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "arcsec" => {
                     if args.len() != 1 {
-                        return Err("arcsec function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arcsec function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arcsec(f)) = 1/(|f|·√(f²-1)) · f'
                     let f = &args[0];
@@ -589,7 +682,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "arccot" => {
                     if args.len() != 1 {
-                        return Err("arccot function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arccot function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arccot(f)) = -1/(1+f²) · f'
                     let f = &args[0];
@@ -611,7 +706,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Hyperbolic ---
                 "sinh" => {
                     if args.len() != 1 {
-                        return Err("sinh function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "sinh function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(sinh(f)) = cosh(f) · f'
                     let f = &args[0];
@@ -623,7 +720,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "cosh" => {
                     if args.len() != 1 {
-                        return Err("cosh function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "cosh function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(cosh(f)) = sinh(f) · f'
                     let f = &args[0];
@@ -635,7 +734,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "tanh" => {
                     if args.len() != 1 {
-                        return Err("tanh function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "tanh function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(tanh(f)) = (1 - tanh²(f)) · f'
                     let f = &args[0];
@@ -654,7 +755,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Reciprocal hyperbolic ---
                 "csch" => {
                     if args.len() != 1 {
-                        return Err("csch function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "csch function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(csch(f)) = -csch(f)·coth(f) · f'
                     let f = &args[0];
@@ -669,7 +772,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "sech" => {
                     if args.len() != 1 {
-                        return Err("sech function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "sech function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(sech(f)) = -sech(f)·tanh(f) · f'
                     let f = &args[0];
@@ -684,7 +789,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "coth" => {
                     if args.len() != 1 {
-                        return Err("coth function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "coth function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(coth(f)) = -csch²(f) · f'
                     let f = &args[0];
@@ -700,7 +807,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Inverse hyperbolic ---
                 "arcsinh" => {
                     if args.len() != 1 {
-                        return Err("arcsinh function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arcsinh function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arcsinh(f)) = 1/√(1+f²) · f'
                     let f = &args[0];
@@ -721,7 +830,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "arccosh" => {
                     if args.len() != 1 {
-                        return Err("arccosh function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arccosh function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arccosh(f)) = 1/√(f²-1) · f'
                     let f = &args[0];
@@ -742,7 +853,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "arctanh" => {
                     if args.len() != 1 {
-                        return Err("arctanh function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arctanh function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arctanh(f)) = 1/(1-f²) · f'
                     let f = &args[0];
@@ -764,7 +877,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Inverse reciprocal hyperbolic ---
                 "arccsch" => {
                     if args.len() != 1 {
-                        return Err("arccsch function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arccsch function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arccsch(f)) = -1/(|f|·√(f²+1)) · f'
                     let f = &args[0];
@@ -788,7 +903,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "arcsech" => {
                     if args.len() != 1 {
-                        return Err("arcsech function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arcsech function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arcsech(f)) = -1/(f·√(1-f²)) · f'
                     let f = &args[0];
@@ -812,7 +929,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "arccoth" => {
                     if args.len() != 1 {
-                        return Err("arccoth function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "arccoth function requires exactly one argument".to_string(),
+                        ));
                     }
                     // d/dx(arccoth(f)) = 1/(1-f²) · f'
                     let f = &args[0];
@@ -834,7 +953,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Logarithmic and exponential ---
                 "log" => {
                     if args.len() != 1 {
-                        return Err("log function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "log function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(log10(f)) = 1/(f*ln(10)) * df/dx
@@ -857,7 +978,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "ln" => {
                     if args.len() != 1 {
-                        return Err("ln function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "ln function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(ln(f)) = 1/f * df/dx
@@ -878,7 +1001,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "lg" => {
                     if args.len() != 1 {
-                        return Err("lg function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "lg function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(lg(f)) = 1/(f*ln(2)) * df/dx
@@ -901,7 +1026,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "exp" => {
                     if args.len() != 1 {
-                        return Err("exp function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "exp function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(exp(f)) = exp(f) * df/dx
@@ -920,7 +1047,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 // --- Special functions (non-elementary antiderivatives) ---
                 "erf" => {
                     if args.len() != 1 {
-                        return Err("erf function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "erf function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(erf(f)) = (2/√π)·exp(−f²) · df/dx  (DLMF 7.2.1)
@@ -947,7 +1076,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "Ei" => {
                     if args.len() != 1 {
-                        return Err("Ei function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "Ei function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(Ei(f)) = (exp(f)/f) · df/dx  (DLMF 6.2.5)
@@ -966,7 +1097,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "li" => {
                     if args.len() != 1 {
-                        return Err("li function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "li function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // d/dx(li(f)) = (1/ln(f)) · df/dx  (DLMF 6.2.8)
@@ -985,7 +1118,9 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 }
                 "abs" => {
                     if args.len() != 1 {
-                        return Err("abs function requires exactly one argument".to_string());
+                        return Err(ArithmaError::InvalidArgument(
+                            "abs function requires exactly one argument".to_string(),
+                        ));
                     }
 
                     // Same as Node::Abs case
@@ -1001,34 +1136,246 @@ pub fn differentiate(expr: &Node, var_name: &str) -> Result<Node, String> {
                     // sgn(f) * df/dx
                     Ok(Node::Multiply(Box::new(sign), Box::new(operand_derivative)))
                 }
-                _ => Err(format!(
-                    "Differentiation not implemented for function: {}",
-                    name
-                )),
+                _ => registry_chain_rule(name, args, var_name),
             }
         }
 
         // Not yet implemented for other node types
-        _ => Err(format!(
+        _ => Err(ArithmaError::NotImplemented(format!(
             "Differentiation not implemented for this expression type: {:?}",
             expr
+        ))),
+    }
+}
+
+/// d/dx f(a₁, …, aₙ) = Σ ∂f/∂aᵢ · daᵢ/dx, with the partials from the
+/// function's registry entry. Arguments free of `var_name` are skipped, so
+/// `\gcd(x, 2)`-style functions only need the partials they have.
+fn registry_chain_rule(name: &str, args: &[Node], var_name: &str) -> Result<Node, ArithmaError> {
+    let Some(function) = crate::functions::FUNCTION_REGISTRY.get(name) else {
+        return Err(ArithmaError::NotImplemented(format!(
+            "Differentiation not implemented for function: {}",
+            name
+        )));
+    };
+    let mut terms = Vec::new();
+    for (index, arg) in args.iter().enumerate() {
+        if arg.is_provably_free_of(var_name) {
+            continue;
+        }
+        let partial = function.partial_derivative(args, index).ok_or_else(|| {
+            ArithmaError::NotImplemented(if args.len() == 1 {
+                format!("Differentiation not implemented for function: {}", name)
+            } else {
+                format!(
+                    "Differentiation not implemented for function: {} (argument {})",
+                    name,
+                    index + 1
+                )
+            })
+        })?;
+        let inner = differentiate(arg, var_name)?;
+        terms.push(Node::Multiply(Box::new(partial), Box::new(inner)));
+    }
+    Ok(terms
+        .into_iter()
+        .reduce(|sum, term| Node::Add(Box::new(sum), Box::new(term)))
+        .unwrap_or(Node::Num(ExactNum::zero())))
+}
+
+/// Chain rule for a square root, shared by `Node::Sqrt` and the `sqrt`
+/// function form: d/dx √f = f' / (2√f). `sqrt_node` is the root as written,
+/// so the result keeps the caller's representation.
+fn sqrt_rule(sqrt_node: &Node, operand: &Node, var_name: &str) -> Result<Node, ArithmaError> {
+    let operand_derivative = differentiate(operand, var_name)?;
+    Ok(Node::Divide(
+        Box::new(operand_derivative),
+        Box::new(Node::Multiply(
+            Box::new(Node::Num(ExactNum::two())),
+            Box::new(sqrt_node.clone()),
         )),
+    ))
+}
+
+/// d/dx of `expr`, keeping whatever the engine cannot resolve as a
+/// [`Node::Derivative`] instead of failing. Sums, differences, negations,
+/// products and quotients by constants are split first, so only the
+/// unresolved pieces stay held: d/dx (x² + \gcd(x, 6)) is
+/// `2x + \frac{d}{dx}{\gcd(x, 6)}`.
+pub fn differentiate_or_unevaluated(expr: &Node, var_name: &str) -> Node {
+    if let Ok(derivative) = differentiate(expr, var_name) {
+        return derivative;
+    }
+    let d = |n: &Node| Box::new(differentiate_or_unevaluated(n, var_name));
+    match expr {
+        Node::Add(l, r) => Node::Add(d(l), d(r)),
+        Node::Subtract(l, r) => Node::Subtract(d(l), d(r)),
+        Node::Negate(inner) => Node::Negate(d(inner)),
+        Node::Multiply(l, r) => Node::Add(
+            Box::new(Node::Multiply(d(l), r.clone())),
+            Box::new(Node::Multiply(l.clone(), d(r))),
+        ),
+        Node::Divide(numer, denom) if !denom.contains_variable(var_name) => {
+            Node::Divide(d(numer), denom.clone())
+        }
+        _ => Node::Derivative(var_name.to_string(), Box::new(expr.clone())),
     }
 }
 
+/// Numeric d/dx of `expr` at `var = at`, for expressions the symbolic
+/// engine cannot differentiate.
+///
+/// Central differences `(f(x+h) − f(x−h)) / 2h` with `h` halved five
+/// times are combined by Richardson extrapolation, each column cancelling
+/// the next even power of `h` in the error. The estimate whose change
+/// from the previous diagonal entry is smallest is returned, which stops
+/// before rounding error in the small steps takes over. `h` is the
+/// largest step; `1e-2 · (1 + |at|)` suits smooth functions of moderate
+/// size.
+pub fn differentiate_numeric(expr: &Node, var: &str, at: f64, h: f64) -> Result<f64, ArithmaError> {
+    if !(h.is_finite() && h > 0.0) {
+        return Err(ArithmaError::Eval(format!(
+            "The step h must be positive, not {}",
+            h
+        )));
+    }
+    let mut env = crate::environment::Environment::new();
+    env.set(var, at);
+    // Report a missing variable or a point outside the domain as it is
+    crate::evaluator::Evaluator::evaluate(expr, &env)?;
+    numeric_derivative_at(expr, var, at, h, &env)
+}
+
+/// Numeric d/dx of `body` at the value `env` gives `var_name`, as
+/// [`differentiate_numeric`] computes it.
+pub(crate) fn numeric_derivative(
+    body: &Node,
+    var_name: &str,
+    env: &crate::environment::Environment,
+) -> Result<f64, ArithmaError> {
+    let x = env
+        .get_exact(var_name)
+        .map(ExactNum::to_f64)
+        .ok_or_else(|| {
+            ArithmaError::Eval(format!(
+                "The derivative with respect to '{}' needs a value for '{}'",
+                var_name, var_name
+            ))
+        })?;
+    numeric_derivative_at(body, var_name, x, 1e-2 * (1.0 + x.abs()), env)
+}
+
+fn numeric_derivative_at(
+    body: &Node,
+    var_name: &str,
+    x: f64,
+    h: f64,
+    env: &crate::environment::Environment,
+) -> Result<f64, ArithmaError> {
+    let mut point_env = env.child();
+    let mut f = |at: f64| {
+        point_env.set(var_name, at);
+        crate::evaluator::Evaluator::evaluate(body, &point_env)
+            .ok()
+            .filter(|v| v.is_finite())
+    };
+    richardson_derivative(&mut f, x, h).ok_or_else(|| {
+        ArithmaError::Domain(format!(
+            "The derivative of {} is undefined at {} = {}",
+            body, var_name, x
+        ))
+    })
+}
+
+/// Richardson extrapolation of central differences: row `i` of the
+/// tableau starts from step `h / 2^i`. Steps that reach outside the
+/// domain are skipped until a smaller one fits.
+fn richardson_derivative(f: &mut dyn FnMut(f64) -> Option<f64>, x: f64, h: f64) -> Option<f64> {
+    const ROWS: usize = 6;
+    let mut h = h;
+    let mut previous: Vec<f64> = Vec::new();
+    let mut best: Option<(f64, f64)> = None;
+    for _ in 0..ROWS + 10 {
+        let central = (|| Some((f(x + h)? - f(x - h)?) / (2.0 * h)))();
+        h /= 2.0;
+        let Some(central) = central else {
+            // Too wide for the domain: start the tableau over, smaller
+            if previous.is_empty() {
+                continue;
+            }
+            break;
+        };
+        let mut row = vec![central];
+        let mut factor = 4.0;
+        for j in 1..=previous.len() {
+            let refined = row[j - 1] + (row[j - 1] - previous[j - 1]) / (factor - 1.0);
+            row.push(refined);
+            factor *= 4.0;
+        }
+        let estimate = row[row.len() - 1];
+        let change = previous
+            .last()
+            .map_or(f64::INFINITY, |p| (estimate - p).abs());
+        if best.is_none_or(|(_, c)| change <= c) {
+            best = Some((estimate, change));
+        } else if previous.len() >= 2 {
+            // Rounding error has started to grow
+            break;
+        }
+        previous = row;
+        if previous.len() == ROWS {
+            break;
+        }
+    }
+    best.map(|(estimate, _)| estimate)
+}
+
 /// Computes the partial derivative of an expression with respect to a variable
-pub fn partial_derivative(expr: &Node, var_name: &str) -> Result<Node, String> {
+pub fn partial_derivative(expr: &Node, var_name: &str) -> Result<Node, ArithmaError> {
     // For now, the implementation is the same as the regular derivative
     differentiate(expr, var_name)
 }
 
+/// Differentiate each expression with respect to `var_name`, simplified,
+/// one result per input. The simplification environment is built once, and
+/// repeated inputs (common when filling Jacobian or Hessian rows) are
+/// differentiated once and shared. A failure affects only its own entry.
+pub fn differentiate_many(exprs: &[Node], var_name: &str) -> Vec<Result<Node, ArithmaError>> {
+    let env = crate::environment::Environment::new();
+    map_distinct(exprs, |expr| {
+        let derivative = differentiate(expr, var_name)?;
+        Ok(crate::simplify::Simplifiable::simplify(&derivative, &env).unwrap_or(derivative))
+    })
+}
+
+/// Apply `op` to each expression, computing structurally equal inputs only
+/// once. Batches are small (matrix rows, plot layers), so a linear scan of
+/// the inputs seen so far beats hashing trees.
+pub(crate) fn map_distinct(
+    exprs: &[Node],
+    op: impl Fn(&Node) -> Result<Node, ArithmaError>,
+) -> Vec<Result<Node, ArithmaError>> {
+    let mut seen: Vec<(&Node, Result<Node, ArithmaError>)> = Vec::new();
+    exprs
+        .iter()
+        .map(|expr| {
+            if let Some((_, result)) = seen.iter().find(|(prev, _)| *prev == expr) {
+                return result.clone();
+            }
+            let result = op(expr);
+            seen.push((expr, result.clone()));
+            result
+        })
+        .collect()
+}
+
 /// Differentiate a LaTeX expression and evaluate at the given environment.
 /// This avoids the lossy round-trip through Display formatting.
 pub fn differentiate_and_evaluate(
     latex_expr: &str,
     var_name: &str,
     env: &crate::environment::Environment,
-) -> Result<f64, String> {
+) -> Result<f64, ArithmaError> {
     let mut tokenizer = crate::tokenizer::Tokenizer::new(latex_expr);
     let tokens = tokenizer.tokenize();
     let expr = crate::parser::build_expression_tree(tokens)?;
@@ -1036,6 +1383,68 @@ pub fn differentiate_and_evaluate(
     crate::evaluator::Evaluator::evaluate(&derivative, env)
 }
 
+/// A derivative together with the step functions of the variable it was
+/// taken through. Their derivative is zero only almost everywhere, so
+/// `result` is the derivative away from their jumps and undefined at them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DomainDerivative {
+    pub result: Node,
+    /// Each distinct floor, ceiling, rounding, truncation, sign or
+    /// `\bmod` subexpression that depends on the variable, in the order
+    /// they first appear: `\lfloor x \rfloor` jumps at every integer.
+    pub step_functions: Vec<Node>,
+}
+
+impl DomainDerivative {
+    /// The step functions as a note, `valid except where \floor{x} jumps`,
+    /// or `None` when the derivative holds everywhere it is defined.
+    pub fn domain_note(&self) -> Option<String> {
+        if self.step_functions.is_empty() {
+            return None;
+        }
+        let terms: Vec<String> = self.step_functions.iter().map(Node::to_string).collect();
+        Some(format!(
+            "valid except where {} {}",
+            terms.join(", "),
+            if terms.len() == 1 { "jumps" } else { "jump" }
+        ))
+    }
+}
+
+fn is_step_function(node: &Node) -> bool {
+    match node {
+        Node::Floor(_) | Node::Ceil(_) | Node::Round(_) | Node::Trunc(_) => true,
+        Node::Function(name, _) => matches!(
+            name.as_str(),
+            "floor" | "ceil" | "round" | "trunc" | "sgn" | "sign" | "mod"
+        ),
+        _ => false,
+    }
+}
+
+/// Differentiate `expr` and report the step functions of `var_name` whose
+/// jumps the derivative silently leaves out: d/dx ⌊x⌋ is 0 between the
+/// integers and undefined at them.
+pub fn differentiate_with_domain(
+    expr: &Node,
+    var_name: &str,
+) -> Result<DomainDerivative, ArithmaError> {
+    let result = differentiate(expr, var_name)?;
+    let mut step_functions: Vec<Node> = Vec::new();
+    expr.visit(&mut |node| {
+        if is_step_function(node)
+            && !node.is_provably_free_of(var_name)
+            && !step_functions.contains(node)
+        {
+            step_functions.push(node.clone());
+        }
+    });
+    Ok(DomainDerivative {
+        result,
+        step_functions,
+    })
+}
+
 /// Differentiate a LaTeX expression with respect to a variable
 ///
 /// # Arguments
@@ -1046,7 +1455,7 @@ pub fn differentiate_and_evaluate(
 /// # Returns
 ///
 /// The derivative of the expression as a LaTeX string
-pub fn differentiate_latex(latex_expr: &str, var_name: &str) -> Result<String, String> {
+pub fn differentiate_latex(latex_expr: &str, var_name: &str) -> Result<String, ArithmaError> {
     let mut tokenizer = crate::tokenizer::Tokenizer::new(latex_expr);
     let tokens = tokenizer.tokenize();
     let expr = crate::parser::build_expression_tree(tokens)?;
@@ -1065,13 +1474,13 @@ mod tests {
     use crate::tokenizer::Tokenizer;
     use crate::Environment;
 
-    fn parse_expression(latex: &str) -> Result<Node, String> {
+    fn parse_expression(latex: &str) -> Result<Node, ArithmaError> {
         let mut tokenizer = Tokenizer::new(latex);
         let tokens = tokenizer.tokenize();
-        build_expression_tree(tokens)
+        Ok(build_expression_tree(tokens)?)
     }
 
-    fn evaluate_expression(expr: &Node, env: &Environment) -> Result<f64, String> {
+    fn evaluate_expression(expr: &Node, env: &Environment) -> Result<f64, ArithmaError> {
         Evaluator::evaluate(expr, env)
     }
 
@@ -0,0 +1,309 @@
+//! Numeric sums of infinite series, `\sum_{n=a}^{\infty} f(n)`.
+//!
+//! Partial sums are taken over 32, 64, 128, … terms. At each length the
+//! sum is estimated by an accelerated value, and the series is summed once
+//! two successive estimates agree. The difference between them is the
+//! reported error. [`SeriesAcceleration::Auto`] picks the estimate from
+//! the shape of the tail:
+//!
+//! - alternating terms of decreasing size: Euler's transform, which
+//!   repeatedly averages neighbouring partial sums;
+//! - terms of one sign whose ratio settles below 1: the partial sum plus
+//!   the geometric tail `a_N r / (1 − r)`;
+//! - terms of one sign decaying like `C n^{-p}`: the partial sum plus the
+//!   integral of `C x^{-p}` beyond the last term.
+//!
+//! The estimates at successive lengths are then extrapolated once more by
+//! Aitken's Δ², since their errors shrink by a steady factor per doubling.
+//! Terms that do not tend to zero, or decay no faster than `1/n`, are
+//! reported as divergent rather than summed.
+
+use crate::environment::Environment;
+use crate::error::ArithmaError;
+use crate::evaluator::Evaluator;
+use crate::exact::ExactNum;
+use crate::node::Node;
+use crate::status::free_variables;
+use crate::substitute::substitute_variable;
+
+/// Partial sums averaged by Euler's transform or extrapolated by Aitken's
+const TRANSFORM_POINTS: usize = 16;
+const FIRST_LENGTH: usize = 32;
+/// A power-law tail decaying no faster than `n^{-(1 + this)}` is treated
+/// as divergent
+const SLOWEST_DECAY: f64 = 0.02;
+/// Terms summed before the tail is judged: `n^{20}/2^n` still grows at
+/// `n = 29`
+const SETTLED_LENGTH: usize = 256;
+
+/// How partial sums are turned into an estimate of the whole sum.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SeriesAcceleration {
+    /// Euler's transform for alternating series, a geometric or power-law
+    /// tail estimate otherwise
+    #[default]
+    Auto,
+    /// The partial sums themselves, with no extrapolation
+    None,
+    /// Aitken's Δ² process, iterated, on the last partial sums
+    Aitken,
+    /// Euler's transform: repeated averaging of the last partial sums
+    Euler,
+}
+
+/// Settings for [`sum_infinite_series`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeriesOptions {
+    pub acceleration: SeriesAcceleration,
+    /// Relative difference between successive estimates accepted as
+    /// converged
+    pub tolerance: f64,
+    /// Terms summed before giving up
+    pub max_terms: usize,
+}
+
+impl Default for SeriesOptions {
+    fn default() -> Self {
+        SeriesOptions {
+            acceleration: SeriesAcceleration::Auto,
+            tolerance: 1e-10,
+            max_terms: 1 << 20,
+        }
+    }
+}
+
+/// The value of an infinite series and how far it may be off.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeriesSum {
+    pub value: f64,
+    /// The change in the estimate when the number of terms was last
+    /// doubled
+    pub error_estimate: f64,
+    /// Terms evaluated
+    pub terms: usize,
+}
+
+/// Sum `body` over `index = start, start + 1, …` numerically. Other
+/// variables in `body` take their values from `env`.
+pub fn sum_infinite_series(
+    body: &Node,
+    index: &str,
+    start: i64,
+    env: &Environment,
+    options: &SeriesOptions,
+) -> Result<SeriesSum, ArithmaError> {
+    let mut term = term_function(body, index, env)?;
+    let mut terms: Vec<f64> = Vec::new();
+    let mut partial_sums: Vec<f64> = Vec::new();
+    let (mut sum, mut compensation) = (0.0_f64, 0.0_f64);
+    // The estimate at each length, and its extrapolation across lengths
+    let mut estimates: Vec<f64> = Vec::new();
+    let mut previous: Option<f64> = None;
+    let mut length = FIRST_LENGTH.min(options.max_terms.max(2));
+    loop {
+        while terms.len() < length {
+            let n = start + terms.len() as i64;
+            let a = term(n)?;
+            if !a.is_finite() {
+                return Err(ArithmaError::Domain(format!(
+                    "The term of the series is undefined at {} = {}",
+                    index, n
+                )));
+            }
+            // Neumaier summation keeps a million terms accurate
+            let t = sum + a;
+            compensation += if sum.abs() >= a.abs() {
+                (sum - t) + a
+            } else {
+                (a - t) + sum
+            };
+            sum = t;
+            terms.push(a);
+            partial_sums.push(sum + compensation);
+        }
+        estimates.push(estimate(
+            &terms,
+            &partial_sums,
+            start,
+            options.acceleration,
+        )?);
+        // Errors that shrink by a steady factor per doubling, as the
+        // tail estimates' do, are extrapolated away by Aitken's Δ²
+        let estimate = match estimates.len() {
+            n if n >= 3 && options.acceleration != SeriesAcceleration::None => {
+                aitken(&estimates[n - 3..])
+            }
+            n => estimates[n - 1],
+        };
+        if let Some(prev) = previous {
+            let change = (estimate - prev).abs();
+            if change <= options.tolerance * estimate.abs().max(1.0) {
+                // Steady estimates of a series whose terms do not vanish,
+                // such as Euler's means of Σ(−1)^n, are not a sum
+                check_terms_vanish(&terms)?;
+                return Ok(SeriesSum {
+                    value: estimate,
+                    error_estimate: change,
+                    terms: terms.len(),
+                });
+            }
+        }
+        previous = Some(estimate);
+        if length >= options.max_terms {
+            return Err(ArithmaError::NotImplemented(format!(
+                "The series did not converge within {} terms (last estimate {})",
+                terms.len(),
+                estimate
+            )));
+        }
+        length = (length * 2).min(options.max_terms);
+    }
+}
+
+/// Whether a summation's upper bound is `\infty`.
+pub fn is_infinite_upper(end: &Node) -> bool {
+    matches!(end, Node::Variable(name) if name == crate::limits::INFINITY)
+}
+
+type Term<'a> = Box<dyn FnMut(i64) -> Result<f64, ArithmaError> + 'a>;
+
+/// `f(n)` for each index value: compiled when every other variable is
+/// bound, by the tree walk otherwise.
+fn term_function<'a>(
+    body: &Node,
+    index: &str,
+    env: &'a Environment,
+) -> Result<Term<'a>, ArithmaError> {
+    let mut bound = body.clone();
+    for name in free_variables(&[body]) {
+        if name == index {
+            continue;
+        }
+        if let Some(value) = env.get_exact(&name) {
+            bound = substitute_variable(&bound, &name, &Node::Num(value.clone()))?;
+        }
+    }
+    if let Ok(compiled) = Evaluator::compile(&bound, &[index]) {
+        return Ok(Box::new(move |n| Ok(compiled.eval(&[n as f64]))));
+    }
+    let mut term_env = env.child();
+    let index = index.to_string();
+    Ok(Box::new(move |n| {
+        term_env.set_exact(&index, ExactNum::integer(n));
+        Evaluator::evaluate(&bound, &term_env)
+    }))
+}
+
+fn estimate(
+    terms: &[f64],
+    partial_sums: &[f64],
+    start: i64,
+    acceleration: SeriesAcceleration,
+) -> Result<f64, ArithmaError> {
+    let len = terms.len();
+    let last_sum = partial_sums[len - 1];
+    let tail = &terms[len / 2..];
+    if tail.iter().all(|&a| a == 0.0) {
+        return Ok(last_sum);
+    }
+    let settled = len >= SETTLED_LENGTH;
+    if settled {
+        check_terms_vanish(terms)?;
+    }
+    let recent = &partial_sums[len.saturating_sub(TRANSFORM_POINTS)..];
+    match acceleration {
+        SeriesAcceleration::None => Ok(last_sum),
+        SeriesAcceleration::Euler => Ok(euler_transform(recent)),
+        SeriesAcceleration::Aitken => Ok(aitken(recent)),
+        SeriesAcceleration::Auto => {
+            let last = &terms[len.saturating_sub(TRANSFORM_POINTS)..];
+            // Euler's transform assigns values to divergent alternating
+            // series too, so the terms' sizes must also be shrinking
+            let alternating = last.windows(2).all(|w| w[0] * w[1] < 0.0);
+            let shrinking = last.windows(2).all(|w| w[1].abs() < w[0].abs());
+            if alternating && shrinking {
+                return Ok(euler_transform(recent));
+            }
+            let one_sign = tail.iter().all(|&a| a > 0.0) || tail.iter().all(|&a| a < 0.0);
+            if !one_sign {
+                return Ok(last_sum);
+            }
+            // A geometric tail keeps its ratio; a power-law tail's ratio
+            // creeps towards 1, halving 1 − r each time n doubles
+            let (i_mid, i_last) = (len / 2, len - 1);
+            let a_last = terms[i_last];
+            let ratio = a_last / terms[i_last - 1];
+            let mid_ratio = terms[i_mid] / terms[i_mid - 1];
+            if ratio < 1.0 && 1.0 - ratio > 0.75 * (1.0 - mid_ratio) {
+                return Ok(last_sum + a_last * ratio / (1.0 - ratio));
+            }
+            // Fit a_n ≈ C n^{-p} between the middle and the end
+            let n_of = |i: usize| (start + i as i64) as f64;
+            if n_of(i_mid) < 1.0 {
+                return Ok(last_sum);
+            }
+            let p = (terms[i_mid] / a_last).ln() / (n_of(i_last) / n_of(i_mid)).ln();
+            if p <= 1.0 + SLOWEST_DECAY {
+                if !settled {
+                    return Ok(last_sum);
+                }
+                return Err(ArithmaError::Domain(format!(
+                    "The series diverges, or converges too slowly to sum: its terms decay like 1/n^{{{:.2}}}",
+                    p
+                )));
+            }
+            // Σ_{k>N} C k^{-p} ≈ ∫_{N+½}^∞ C x^{-p} dx
+            let n_last = n_of(i_last);
+            let c = a_last * n_last.powf(p);
+            Ok(last_sum + c * (n_last + 0.5).powf(1.0 - p) / (p - 1.0))
+        }
+    }
+}
+
+/// A series whose terms do not shrink towards zero diverges.
+fn check_terms_vanish(terms: &[f64]) -> Result<(), ArithmaError> {
+    let len = terms.len();
+    let largest = |range: &[f64]| range.iter().fold(0.0_f64, |m, a| m.max(a.abs()));
+    let (third_quarter, last_quarter) = (
+        largest(&terms[len / 2..3 * len / 4]),
+        largest(&terms[3 * len / 4..]),
+    );
+    if last_quarter >= third_quarter && last_quarter > 1e-300 {
+        return Err(ArithmaError::Domain(
+            "The series diverges: its terms do not tend to 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Average neighbouring values until one is left.
+fn euler_transform(sums: &[f64]) -> f64 {
+    let mut level = sums.to_vec();
+    while level.len() > 1 {
+        level = level.windows(2).map(|w| 0.5 * (w[0] + w[1])).collect();
+    }
+    level[0]
+}
+
+/// Aitken's Δ², applied to its own output while three values remain.
+fn aitken(sums: &[f64]) -> f64 {
+    let mut level = sums.to_vec();
+    while level.len() >= 3 {
+        let next: Vec<f64> = level
+            .windows(3)
+            .map(|w| {
+                let denominator = w[2] - 2.0 * w[1] + w[0];
+                if denominator == 0.0 {
+                    w[2]
+                } else {
+                    w[2] - (w[2] - w[1]).powi(2) / denominator
+                }
+            })
+            .collect();
+        if next.iter().any(|v| !v.is_finite()) {
+            break;
+        }
+        level = next;
+    }
+    level[level.len() - 1]
+}
@@ -1,20 +1,23 @@
 use crate::environment::Environment;
+use crate::error::ArithmaError;
 use crate::exact::ExactNum;
 use crate::function_meta::canonical_function_name;
+use crate::limits::{LimitDirection, LimitPoint, LimitResult};
 use crate::node::Node;
 use crate::parser::build_expression_tree;
 use crate::polynomial::Polynomial;
 use crate::risch::{try_risch_tower, RischResult};
 use crate::simplify::Simplifiable;
 use crate::substitute::substitute_variable;
+use crate::symmetry::{symbolic_parity, Parity};
 use crate::tokenizer::Tokenizer;
 use num_traits::{One, ToPrimitive, Zero};
 
-fn try_risch_fallback(expr: &Node, var_name: &str) -> Option<Result<Node, String>> {
+fn try_risch_fallback(expr: &Node, var_name: &str) -> Option<Result<Node, ArithmaError>> {
     if let Some(result) = try_risch_tower(expr, var_name) {
         return Some(match result {
             RischResult::Elementary(node) => Ok(node),
-            RischResult::NonElementary(reason) => Err(format!("NON_ELEMENTARY: {}", reason)),
+            RischResult::NonElementary(reason) => Err(ArithmaError::NonElementary(reason)),
         });
     }
     None
@@ -40,28 +43,24 @@ pub enum IntegralOutcome {
 /// a post-pass on the whole simplified integrand — because constant factors
 /// peel off inside `integrate` before the Risch proof fires, so the interior
 /// error path no longer sees the full integrand.
-pub fn integrate_outcome(expr: &Node, var_name: &str) -> Result<IntegralOutcome, String> {
+pub fn integrate_outcome(expr: &Node, var_name: &str) -> Result<IntegralOutcome, ArithmaError> {
     match integrate(expr, var_name) {
         Ok(node) => Ok(IntegralOutcome::Elementary(node)),
-        Err(e) => match e.strip_prefix("NON_ELEMENTARY: ") {
-            Some(certificate) => {
-                let env = Environment::new();
-                let simplified = expr.simplify(&env).unwrap_or_else(|_| expr.clone());
-                let special = crate::special_functions::recognize_special_antiderivative(
-                    &simplified,
-                    var_name,
-                );
-                Ok(IntegralOutcome::NonElementary {
-                    certificate: certificate.to_string(),
-                    special,
-                })
-            }
-            None => Err(e),
-        },
+        Err(ArithmaError::NonElementary(certificate)) => {
+            let env = Environment::new();
+            let simplified = expr.simplify(&env).unwrap_or_else(|_| expr.clone());
+            let special =
+                crate::special_functions::recognize_special_antiderivative(&simplified, var_name);
+            Ok(IntegralOutcome::NonElementary {
+                certificate,
+                special,
+            })
+        }
+        Err(e) => Err(e),
     }
 }
 
-pub fn integrate(expr: &Node, var_name: &str) -> Result<Node, String> {
+pub fn integrate(expr: &Node, var_name: &str) -> Result<Node, ArithmaError> {
     let env = crate::environment::Environment::new();
     let expr =
         &crate::simplify::Simplifiable::simplify(expr, &env).unwrap_or_else(|_| expr.clone());
@@ -107,6 +106,9 @@ pub fn integrate(expr: &Node, var_name: &str) -> Result<Node, String> {
             }
         }
 
+        // Fundamental theorem: ∫ d/dx f dx = f + C
+        Node::Derivative(var, body) if var == var_name => Ok((**body).clone()),
+
         // Addition: ∫(f+g) dx = ∫f dx + ∫g dx
         Node::Add(left, right) => {
             let left_integral = integrate(left, var_name)?;
@@ -204,7 +206,9 @@ pub fn integrate(expr: &Node, var_name: &str) -> Result<Node, String> {
             if let Some(result) = try_risch_fallback(expr, var_name) {
                 return result;
             }
-            Err("Integration of this expression is not yet implemented".to_string())
+            Err(ArithmaError::NotImplemented(
+                "Integration of this expression is not yet implemented".to_string(),
+            ))
         }
 
         // Multiplication by a constant: ∫(k*f) dx = k*∫f dx
@@ -246,15 +250,33 @@ pub fn integrate(expr: &Node, var_name: &str) -> Result<Node, String> {
                 return result;
             }
 
+            // ∫e^{ax}·sin(bx), ∫e^{ax}·cos(bx) — cyclic integration by parts
+            if let Some(result) = try_cyclic_exp_trig(left, right, var_name) {
+                return result;
+            }
+            if let Some(result) = try_cyclic_exp_trig(right, left, var_name) {
+                return result;
+            }
+
             // U-substitution: f(g(x)) · g'(x) patterns
             if let Some(result) = try_u_substitution(expr, var_name) {
                 return result;
             }
 
+            // Integration by parts with u = polynomial, dv = any integrable factor
+            if let Some(result) = try_polynomial_ibp(left, right, var_name) {
+                return result;
+            }
+            if let Some(result) = try_polynomial_ibp(right, left, var_name) {
+                return result;
+            }
+
             if let Some(result) = try_risch_fallback(expr, var_name) {
                 return result;
             }
-            Err("Integration of this product is not yet implemented".to_string())
+            Err(ArithmaError::NotImplemented(
+                "Integration of this product is not yet implemented".to_string(),
+            ))
         }
 
         // Division: Special case for 1/x
@@ -371,7 +393,9 @@ pub fn integrate(expr: &Node, var_name: &str) -> Result<Node, String> {
             if let Some(result) = try_risch_fallback(expr, var_name) {
                 return result;
             }
-            Err("Integration of this division is not yet implemented".to_string())
+            Err(ArithmaError::NotImplemented(
+                "Integration of this division is not yet implemented".to_string(),
+            ))
         }
 
         Node::Negate(inner) => {
@@ -384,7 +408,9 @@ pub fn integrate(expr: &Node, var_name: &str) -> Result<Node, String> {
             if let Some(result) = try_trig_substitution_sqrt(inner, var_name) {
                 return result;
             }
-            Err("Integration of this sqrt expression is not yet implemented".to_string())
+            Err(ArithmaError::NotImplemented(
+                "Integration of this sqrt expression is not yet implemented".to_string(),
+            ))
         }
 
         // Standard function integrals
@@ -420,22 +446,24 @@ pub fn integrate(expr: &Node, var_name: &str) -> Result<Node, String> {
             if let Some(result) = try_risch_fallback(&full_expr, var_name) {
                 return result;
             }
-            Err(format!(
+            Err(ArithmaError::NotImplemented(format!(
                 "Integration of {}(...) with non-linear argument not yet implemented",
                 name
-            ))
+            )))
         }
 
         _ => {
             if let Some(result) = try_risch_fallback(expr, var_name) {
                 return result;
             }
-            Err("Integration of this expression is not yet implemented".to_string())
+            Err(ArithmaError::NotImplemented(
+                "Integration of this expression is not yet implemented".to_string(),
+            ))
         }
     }
 }
 
-fn integrate_standard_function(name: &str, var: &str) -> Result<Node, String> {
+fn integrate_standard_function(name: &str, var: &str) -> Result<Node, ArithmaError> {
     let x = || Node::Variable(var.to_string());
     let half_x = || Node::Divide(Box::new(x()), Box::new(Node::Num(ExactNum::integer(2))));
     let ln_abs = |inner: Node| Node::Function("ln".to_string(), vec![Node::Abs(Box::new(inner))]);
@@ -639,7 +667,18 @@ fn integrate_standard_function(name: &str, var: &str) -> Result<Node, String> {
         )),
         // ∫exp(x) = exp(x)
         "exp" => Ok(Node::Function("exp".to_string(), vec![x()])),
-        _ => Err(format!("Integration of {}(x) not implemented", name)),
+        // ∫√x = (2/3)·x^{3/2}
+        "sqrt" => Ok(Node::Multiply(
+            Box::new(Node::Num(ExactNum::rational(2, 3))),
+            Box::new(Node::Power(
+                Box::new(x()),
+                Box::new(Node::Num(ExactNum::rational(3, 2))),
+            )),
+        )),
+        _ => Err(ArithmaError::NotImplemented(format!(
+            "Integration of {}(x) not implemented",
+            name
+        ))),
     }
 }
 
@@ -1055,7 +1094,7 @@ fn try_parametric_quadratic_integral(
     numerator: &Node,
     denominator: &Node,
     var: &str,
-) -> Option<Result<Node, String>> {
+) -> Option<Result<Node, ArithmaError>> {
     let (a, b, c) = try_decompose_quadratic(denominator, var)?;
 
     // Extract numerator as px + q
@@ -1216,7 +1255,7 @@ fn try_tabular_integration(
     u_candidate: &Node,
     dv_candidate: &Node,
     var: &str,
-) -> Option<Result<Node, String>> {
+) -> Option<Result<Node, ArithmaError>> {
     // u must be polynomial, dv must be repeatedly integratable (sin/cos/exp)
     if Polynomial::from_node(u_candidate, var).is_err() {
         return None;
@@ -1282,17 +1321,48 @@ fn try_tabular_integration(
     Some(Ok(result))
 }
 
-/// Single-step integration by parts for ln(x) × polynomial.
-/// Uses u = ln(x), dv = polynomial. Result: uv - ∫v·du.
+thread_local! {
+    static IBP_DEPTH: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Nesting limit for integration by parts. Each IBP step integrates a new
+/// product; without a limit, ∫x·arcsin(x) → ∫x²/√(1-x²) → ∫x·arcsin(x) → …
+/// cycles between the two IBP forms forever.
+const MAX_IBP_DEPTH: usize = 6;
+
+fn with_ibp_budget<T>(f: impl FnOnce() -> Option<T>) -> Option<T> {
+    let depth = IBP_DEPTH.with(|d| d.get());
+    if depth >= MAX_IBP_DEPTH {
+        return None;
+    }
+    IBP_DEPTH.with(|d| d.set(depth + 1));
+    let result = f();
+    IBP_DEPTH.with(|d| d.set(depth));
+    result
+}
+
+/// Single-step integration by parts for {ln, inverse trig/hyperbolic} × polynomial.
+/// Uses u = the transcendental factor, whose derivative is algebraic, and
+/// dv = polynomial. Result: uv - ∫v·du.
 fn try_log_integration(
     log_candidate: &Node,
     poly_candidate: &Node,
     var: &str,
-) -> Option<Result<Node, String>> {
-    // Check log_candidate is ln(x) or similar
+) -> Option<Result<Node, ArithmaError>> {
+    // Check log_candidate is ln(x), arctan(x), or similar
     let is_log = match log_candidate {
         Node::Function(name, args) if args.len() == 1 => {
-            matches!(name.as_str(), "ln" | "log") && contains_var(&args[0], var)
+            matches!(
+                canonical_function_name(name),
+                "ln" | "log"
+                    | "arcsin"
+                    | "arccos"
+                    | "arctan"
+                    | "arccot"
+                    | "arcsinh"
+                    | "arccosh"
+                    | "arctanh"
+            ) && contains_var(&args[0], var)
         }
         _ => false,
     };
@@ -1334,14 +1404,88 @@ fn try_log_integration(
             .unwrap_or_else(|| crate::simplify::Simplifiable::simplify(&v_du, &env).unwrap_or(v_du))
     };
 
-    let remaining = match integrate(&v_du_reparsed, var) {
-        Ok(r) => crate::simplify::Simplifiable::simplify(&r, &env).unwrap_or(r),
-        Err(e) => return Some(Err(e)),
-    };
+    let remaining = with_ibp_budget(|| integrate(&v_du_reparsed, var).ok())?;
+    let remaining = crate::simplify::Simplifiable::simplify(&remaining, &env).unwrap_or(remaining);
 
     Some(Ok(Node::Subtract(Box::new(uv), Box::new(remaining))))
 }
 
+/// Integration by parts for polynomial × f(x) where f has a known
+/// antiderivative F but is outside the tabular family (sec², 1/√x, …):
+/// ∫p·f = p·F - ∫p'·F. The remaining integral has a polynomial factor of
+/// lower degree, so the recursion terminates.
+fn try_polynomial_ibp(
+    poly_candidate: &Node,
+    dv_candidate: &Node,
+    var: &str,
+) -> Option<Result<Node, ArithmaError>> {
+    let poly = Polynomial::from_node(poly_candidate, var).ok()?;
+    if poly.degree().unwrap_or(0) < 1 || Polynomial::from_node(dv_candidate, var).is_ok() {
+        return None;
+    }
+
+    let env = crate::environment::Environment::new();
+    let v = integrate(dv_candidate, var).ok()?;
+    let v = crate::simplify::Simplifiable::simplify(&v, &env).unwrap_or(v);
+    let du = poly.derivative().to_node();
+    let v_du = Node::Multiply(Box::new(du), Box::new(v.clone()));
+    let v_du = crate::simplify::Simplifiable::simplify(&v_du, &env).unwrap_or(v_du);
+    let remaining = with_ibp_budget(|| integrate(&v_du, var).ok())?;
+
+    let uv = Node::Multiply(Box::new(poly.to_node()), Box::new(v));
+    let result = Node::Subtract(Box::new(uv), Box::new(remaining));
+    Some(Ok(
+        crate::simplify::Simplifiable::simplify(&result, &env).unwrap_or(result)
+    ))
+}
+
+/// Cyclic integration by parts for exp(ax+c) × {sin, cos}(bx+d). Two rounds
+/// of IBP return the original integral I, and solving the resulting linear
+/// equation for I gives
+///   ∫e^u·sin(w) = e^u·(a·sin(w) - b·cos(w)) / (a²+b²)
+///   ∫e^u·cos(w) = e^u·(a·cos(w) + b·sin(w)) / (a²+b²)
+fn try_cyclic_exp_trig(
+    exp_candidate: &Node,
+    trig_candidate: &Node,
+    var: &str,
+) -> Option<Result<Node, ArithmaError>> {
+    let (exp_arg, trig_name, trig_arg) = match (exp_candidate, trig_candidate) {
+        (Node::Function(e, e_args), Node::Function(t, t_args))
+            if e == "exp" && e_args.len() == 1 && t_args.len() == 1 =>
+        {
+            (&e_args[0], t.as_str(), &t_args[0])
+        }
+        _ => return None,
+    };
+    if !matches!(trig_name, "sin" | "cos") {
+        return None;
+    }
+    let (a, _) = extract_linear_arg(exp_arg, var)?;
+    let (b, _) = extract_linear_arg(trig_arg, var)?;
+
+    let denom = &(&a * &a) + &(&b * &b);
+    let sin_w = Node::Function("sin".to_string(), vec![trig_arg.clone()]);
+    let cos_w = Node::Function("cos".to_string(), vec![trig_arg.clone()]);
+    let scaled =
+        |k: &ExactNum, f: Node| Node::Multiply(Box::new(Node::Num(k.clone())), Box::new(f));
+    let combination = if trig_name == "sin" {
+        Node::Subtract(Box::new(scaled(&a, sin_w)), Box::new(scaled(&b, cos_w)))
+    } else {
+        Node::Add(Box::new(scaled(&a, cos_w)), Box::new(scaled(&b, sin_w)))
+    };
+    let result = Node::Divide(
+        Box::new(Node::Multiply(
+            Box::new(exp_candidate.clone()),
+            Box::new(combination),
+        )),
+        Box::new(Node::Num(denom)),
+    );
+    let env = crate::environment::Environment::new();
+    Some(Ok(
+        crate::simplify::Simplifiable::simplify(&result, &env).unwrap_or(result)
+    ))
+}
+
 /// Extract (function_name, argument, exponent) from a trig power like sin^n(x).
 /// Returns None if the node isn't a trig power.
 fn extract_trig_power(node: &Node) -> Option<(&str, &Node, u32)> {
@@ -1393,7 +1537,7 @@ fn try_trig_power_integral(
     base: &Node,
     exponent: &Node,
     var: &str,
-) -> Option<Result<Node, String>> {
+) -> Option<Result<Node, ArithmaError>> {
     let power_node = Node::Power(Box::new(base.clone()), Box::new(exponent.clone()));
     let (func, arg, n) = extract_trig_power(&power_node)?;
 
@@ -1405,7 +1549,7 @@ fn try_trig_power_integral(
     Some(integrate_trig_power(func, var, n))
 }
 
-fn integrate_trig_power(func: &str, var: &str, n: u32) -> Result<Node, String> {
+fn integrate_trig_power(func: &str, var: &str, n: u32) -> Result<Node, ArithmaError> {
     let env = crate::environment::Environment::new();
     let x = || Node::Variable(var.to_string());
 
@@ -1510,7 +1654,7 @@ fn integrate_trig_power(func: &str, var: &str, n: u32) -> Result<Node, String> {
 }
 
 /// Integrate products sin^m(x) · cos^n(x).
-fn try_trig_product_integral(expr: &Node, var: &str) -> Option<Result<Node, String>> {
+fn try_trig_product_integral(expr: &Node, var: &str) -> Option<Result<Node, ArithmaError>> {
     let env = crate::environment::Environment::new();
 
     // Flatten factors and identify trig powers
@@ -1546,6 +1690,11 @@ fn try_trig_product_integral(expr: &Node, var: &str) -> Option<Result<Node, Stri
         return None;
     }
 
+    // Remaining factors are pulled out as constants, so they must be.
+    if other_factors.iter().any(|f| contains_var(f, var)) {
+        return None;
+    }
+
     // Need at least two trig factors to be a "product" (single powers handled elsewhere)
     if sin_power == 0 || cos_power == 0 {
         return None;
@@ -1604,7 +1753,12 @@ fn try_trig_product_integral(expr: &Node, var: &str) -> Option<Result<Node, Stri
 /// `u_func` is the function that becomes u (e.g., "sin" when cos is odd).
 /// `u_power` is the existing power of u_func.
 /// `k` is the number of squared pairs to convert: (1-u²)^k.
-fn integrate_mixed_odd(u_func: &str, u_power: u32, k: u32, var: &str) -> Result<Node, String> {
+fn integrate_mixed_odd(
+    u_func: &str,
+    u_power: u32,
+    k: u32,
+    var: &str,
+) -> Result<Node, ArithmaError> {
     let env = crate::environment::Environment::new();
     let u_var = "_u_";
 
@@ -1671,7 +1825,7 @@ fn integrate_mixed_even(
     expand_half: u32,
     keep_half: u32,
     var: &str,
-) -> Result<Node, String> {
+) -> Result<Node, ArithmaError> {
     let env = crate::environment::Environment::new();
     let p = expand_half as usize;
     let q = keep_half;
@@ -1682,8 +1836,9 @@ fn integrate_mixed_even(
     let mut terms: Vec<Node> = Vec::new();
 
     for j in 0..=p {
-        let mut coeff = crate::integer::binom(&ExactNum::from_usize(p), &ExactNum::from_usize(j))
-            .ok_or_else(|| format!("binom({p}, {j}) out of range"))?;
+        let mut coeff =
+            crate::integer::binom(&ExactNum::from_usize(p), &ExactNum::from_usize(j))
+                .ok_or_else(|| ArithmaError::Eval(format!("binom({p}, {j}) out of range")))?;
         if j % 2 == 1 {
             coeff = -coeff;
         }
@@ -1871,7 +2026,7 @@ fn try_partial_fraction_integration(
     numerator: &Node,
     denominator: &Node,
     var: &str,
-) -> Option<Result<Node, String>> {
+) -> Option<Result<Node, ArithmaError>> {
     let num_poly = Polynomial::from_node(numerator, var).ok()?;
     let den_poly = Polynomial::from_node(denominator, var).ok()?;
 
@@ -2011,7 +2166,7 @@ fn integrate_general_quartic_rational(
     numerator: &crate::polynomial::Polynomial,
     denom: &crate::polynomial::Polynomial,
     var: &str,
-) -> Result<Node, String> {
+) -> Result<Node, ArithmaError> {
     use crate::algebraic::{find_real_root, try_rational_root, NumberField};
     use num_bigint::BigInt;
     use num_rational::BigRational;
@@ -2058,10 +2213,10 @@ fn integrate_general_quartic_rational(
         // Rational resolvent root — need only √(2m-b)
         let s_sq_val = &two_r * &m_rat - &b_dep;
         if s_sq_val <= zero_r {
-            return Err(
+            return Err(ArithmaError::NotImplemented(
                 "Resolvent root gives non-positive s² — quartic may have repeated roots"
                     .to_string(),
-            );
+            ));
         }
         let s_sq_f64 = rat_to_f64(&s_sq_val);
         let s_f64 = s_sq_f64.sqrt();
@@ -2098,9 +2253,9 @@ fn integrate_general_quartic_rational(
             }
         }
         if s_approx <= 0.0 {
-            return Err(
+            return Err(ArithmaError::NotImplemented(
                 "Could not find positive real root of factoring field polynomial".to_string(),
-            );
+            ));
         }
 
         // Build the number field Q(s)
@@ -2186,7 +2341,9 @@ fn integrate_general_quartic_rational(
     let (a1_f, b1_f, a2_f, b2_f) = match det {
         Some(v) => v,
         None => {
-            return Err("Singular partial fraction system for quartic".to_string());
+            return Err(ArithmaError::NotImplemented(
+                "Singular partial fraction system for quartic".to_string(),
+            ));
         }
     };
 
@@ -2393,7 +2550,7 @@ fn integrate_biquadratic_rational(
     b: &num_rational::BigRational,
     d: &num_rational::BigRational,
     var: &str,
-) -> Result<Node, String> {
+) -> Result<Node, ArithmaError> {
     use num_bigint::BigInt;
     use num_rational::BigRational;
 
@@ -2443,7 +2600,9 @@ fn integrate_biquadratic_rational(
     // Inner discriminant: 4b - d = 4b - (2b - p) = 2b + p
     let inner_disc = &two * b + p;
     if inner_disc <= zero {
-        return Err("Biquadratic inner discriminant non-positive".to_string());
+        return Err(ArithmaError::NotImplemented(
+            "Biquadratic inner discriminant non-positive".to_string(),
+        ));
     }
 
     let env = crate::environment::Environment::new();
@@ -2601,7 +2760,7 @@ fn integrate_biquadratic_rational(
 fn integrate_pf_term(
     term: &crate::partial_fractions::PartialFractionTerm,
     var: &str,
-) -> Result<Node, String> {
+) -> Result<Node, ArithmaError> {
     let q = &term.denominator;
     let n = &term.numerator;
     let k = term.power;
@@ -2754,7 +2913,9 @@ fn integrate_pf_term(
         let alpha_sq = &c_denom - &h * &h;
 
         if alpha_sq <= num_rational::BigRational::zero() {
-            return Err("Quadratic factor has non-positive discriminant".to_string());
+            return Err(ArithmaError::NotImplemented(
+                "Quadratic factor has non-positive discriminant".to_string(),
+            ));
         }
 
         let alpha_node = node_sqrt_rat(&alpha_sq);
@@ -2869,10 +3030,10 @@ fn integrate_pf_term(
         }
     } else {
         // Higher degree or higher power — not yet implemented
-        Err(format!(
+        Err(ArithmaError::NotImplemented(format!(
             "Integration of degree-{} factor to power {} not yet implemented",
             q_deg, k
-        ))
+        )))
     }
 }
 
@@ -2883,7 +3044,7 @@ fn integrate_pf_term(
 ///   √(k² - u²) → u = k·sin(θ): result = (k²/2)(θ + sin(θ)cos(θ))
 ///   √(u² + k²) → u = k·tan(θ): result = (k²/2)(ln|sec(θ)+tan(θ)| + sec(θ)tan(θ))
 ///   √(u² - k²) → u = k·sec(θ): result involves sec and log terms
-fn try_trig_substitution_sqrt(inner: &Node, var: &str) -> Option<Result<Node, String>> {
+fn try_trig_substitution_sqrt(inner: &Node, var: &str) -> Option<Result<Node, ArithmaError>> {
     let poly = Polynomial::from_node(inner, var).ok()?;
     if poly.degree()? != 2 {
         return None;
@@ -3091,7 +3252,7 @@ fn rational_to_node(r: &num_rational::BigRational) -> Node {
 
 /// U-substitution: Given ∫h(x)dx, find g(x) such that h(x) = f(g(x))·g'(x)·c,
 /// then result = c · F(g(x)) where F is the antiderivative of f.
-fn try_u_substitution(expr: &Node, var: &str) -> Option<Result<Node, String>> {
+fn try_u_substitution(expr: &Node, var: &str) -> Option<Result<Node, ArithmaError>> {
     let env = crate::environment::Environment::new();
 
     // Decompose into multiplicative factors
@@ -3299,7 +3460,7 @@ fn replace_subexpr(expr: &Node, target: &Node, replacement: &Node) -> Node {
 /// # Returns
 ///
 /// The integral of the expression as a LaTeX string
-pub fn integrate_latex(latex_expr: &str, var_name: &str) -> Result<String, String> {
+pub fn integrate_latex(latex_expr: &str, var_name: &str) -> Result<String, ArithmaError> {
     let mut tokenizer = Tokenizer::new(latex_expr);
     let tokens = tokenizer.tokenize();
     let expr = build_expression_tree(tokens)?;
@@ -3309,6 +3470,41 @@ pub fn integrate_latex(latex_expr: &str, var_name: &str) -> Result<String, Strin
     Ok(format!("{} + C", simplified))
 }
 
+/// Antiderivatives (without `+ C`) of each expression with respect to
+/// `var_name`, simplified, one result per input. Like
+/// [`crate::derivative::differentiate_many`], setup is shared and
+/// repeated inputs are integrated once; a non-elementary or unsupported
+/// integrand fails only its own entry.
+pub fn integrate_many(exprs: &[Node], var_name: &str) -> Vec<Result<Node, ArithmaError>> {
+    let env = Environment::new();
+    crate::derivative::map_distinct(exprs, |expr| {
+        let integral = integrate(expr, var_name)?;
+        Ok(integral.simplify(&env).unwrap_or(integral))
+    })
+}
+
+/// Antiderivative of `expr`, keeping whatever the engine cannot resolve as
+/// an indefinite [`Node::Integral`] instead of failing. Sums, differences,
+/// negations and constant factors are split first, so only the unresolved
+/// pieces stay held: ∫ (x + e^{-x²}) dx is `\frac{x^{2}}{2} + \int e^{-x^{2}} \, dx`.
+pub fn integrate_or_unevaluated(expr: &Node, var_name: &str) -> Node {
+    if let Ok(integral) = integrate(expr, var_name) {
+        return integral;
+    }
+    let int = |n: &Node| Box::new(integrate_or_unevaluated(n, var_name));
+    match expr {
+        Node::Add(l, r) => Node::Add(int(l), int(r)),
+        Node::Subtract(l, r) => Node::Subtract(int(l), int(r)),
+        Node::Negate(inner) => Node::Negate(int(inner)),
+        Node::Multiply(l, r) if !l.contains_variable(var_name) => Node::Multiply(l.clone(), int(r)),
+        Node::Multiply(l, r) if !r.contains_variable(var_name) => Node::Multiply(int(l), r.clone()),
+        Node::Divide(numer, denom) if !denom.contains_variable(var_name) => {
+            Node::Divide(int(numer), denom.clone())
+        }
+        _ => Node::Integral(var_name.to_string(), None, Box::new(expr.clone())),
+    }
+}
+
 /// Calculates the definite integral of an expression between two bounds
 ///
 /// # Arguments
@@ -3320,13 +3516,43 @@ pub fn integrate_latex(latex_expr: &str, var_name: &str) -> Result<String, Strin
 ///
 /// # Returns
 ///
-/// The definite integral value
+/// The definite integral value. Either bound may be `±f64::INFINITY`, and
+/// the integrand may be undefined at a bound: such an improper integral
+/// takes the limit of the antiderivative there, or without an
+/// antiderivative is computed by [`improper_numeric_integral`]. An
+/// integral that diverges is an error.
 pub fn definite_integral(
     expr: &Node,
     var_name: &str,
     lower: f64,
     upper: f64,
-) -> Result<f64, String> {
+) -> Result<f64, ArithmaError> {
+    let improper = |x: f64| x.is_infinite() || !defined_at(expr, var_name, x);
+    if improper(lower) || improper(upper) {
+        return improper_integral(expr, var_name, lower, upper);
+    }
+
+    if lower == -upper && lower != upper {
+        let bounds = (
+            Node::Num(ExactNum::from_f64(lower)),
+            Node::Num(ExactNum::from_f64(upper)),
+        );
+        check_no_poles_in_interval(expr, var_name, &bounds.0, &bounds.1)?;
+        if symbolic_parity(expr, var_name) == Some(Parity::Odd) {
+            return Ok(0.0);
+        }
+    }
+
+    if crate::piecewise::has_breakpoints(expr, var_name) {
+        let exact = definite_integral_exact(
+            expr,
+            var_name,
+            &Node::Num(ExactNum::from_f64(lower)),
+            &Node::Num(ExactNum::from_f64(upper)),
+        )?;
+        return crate::evaluator::Evaluator::evaluate(&exact, &Environment::new());
+    }
+
     // First find the indefinite integral
     let indefinite = integrate(expr, var_name)?;
 
@@ -3344,6 +3570,205 @@ pub fn definite_integral(
     Ok(upper_value - lower_value)
 }
 
+/// [`definite_integral`] with an infinite bound or an integrand undefined at
+/// a bound.
+fn improper_integral(
+    expr: &Node,
+    var_name: &str,
+    lower: f64,
+    upper: f64,
+) -> Result<f64, ArithmaError> {
+    let env = Environment::new();
+    match definite_integral_exact(expr, var_name, &bound_node(lower), &bound_node(upper)) {
+        Ok(exact) => return crate::evaluator::Evaluator::evaluate(&exact, &env),
+        Err(err @ ArithmaError::Domain(_)) => return Err(err),
+        Err(_) => {}
+    }
+    let mut inner_env = env.child();
+    let integrand = |x: f64| {
+        inner_env.set(var_name, x);
+        crate::evaluator::Evaluator::evaluate(expr, &inner_env)
+            .ok()
+            .filter(|v| v.is_finite())
+    };
+    improper_numeric_integral(integrand, lower, upper, 1e-10).ok_or_else(|| {
+        ArithmaError::Domain(format!(
+            "The improper integral over [{}, {}] does not appear to converge",
+            lower, upper
+        ))
+    })
+}
+
+/// `∫ₐᵇ f` by [`numeric_integral`] after a change of variable that removes
+/// infinite bounds and softens singularities at the ends: `x = a + t/(1−t)`
+/// for `[a, ∞)`, `x = b − t/(1−t)` for `(−∞, b]`, and `x = a + (b−a)(3t² −
+/// 2t³)` for a finite interval with an end where `f` is undefined (`dx/dt`
+/// vanishes at both ends, which tames `1/√x`). `(−∞, ∞)` is split at 0.
+/// `None` when `f` is undefined inside the interval, or when `|f|` times
+/// the distance to an improper end (`|x|` toward ±∞) does not shrink
+/// toward it, the usual sign that the integral diverges.
+pub fn improper_numeric_integral(
+    mut f: impl FnMut(f64) -> Option<f64>,
+    a: f64,
+    b: f64,
+    tol: f64,
+) -> Option<f64> {
+    improper_numeric_step(&mut f, a, b, tol)
+}
+
+fn improper_numeric_step(
+    f: &mut dyn FnMut(f64) -> Option<f64>,
+    a: f64,
+    b: f64,
+    tol: f64,
+) -> Option<f64> {
+    if a > b {
+        return improper_numeric_step(f, b, a, tol).map(|v| -v);
+    }
+    if a == b {
+        return Some(0.0);
+    }
+    if a.is_infinite() && b.is_infinite() {
+        let left = improper_numeric_step(f, a, 0.0, tol / 2.0)?;
+        return Some(left + improper_numeric_step(f, 0.0, b, tol / 2.0)?);
+    }
+    let singular_a = a.is_infinite() || f(a).is_none();
+    let singular_b = b.is_infinite() || f(b).is_none();
+    if !singular_a && !singular_b {
+        return numeric_integral(f, a, b, tol);
+    }
+    // A singular finite end of a half-line gets its own change of variable
+    if a.is_infinite() && singular_b && !b.is_infinite() {
+        let right = improper_numeric_step(f, b - 1.0, b, tol / 2.0)?;
+        return Some(improper_numeric_step(f, a, b - 1.0, tol / 2.0)? + right);
+    }
+    if b.is_infinite() && singular_a && !a.is_infinite() {
+        let left = improper_numeric_step(f, a, a + 1.0, tol / 2.0)?;
+        return Some(left + improper_numeric_step(f, a + 1.0, b, tol / 2.0)?);
+    }
+    // The integrand must shrink faster than 1/distance toward each
+    // improper end
+    let mut decays = |end: f64, inward: f64| {
+        let mut weight = |k: i32| -> Option<f64> {
+            let (x, distance) = if end.is_infinite() {
+                (-inward * 10f64.powi(k), 10f64.powi(k))
+            } else {
+                let d = 10f64.powi(-k) * (1.0 + end.abs());
+                (end + inward * d, d)
+            };
+            Some((f(x)? * distance).abs())
+        };
+        let (first, last) = (weight(3)?, weight(8)?);
+        Some(last < 1e-6 || last <= 0.5 * first)
+    };
+    if (singular_a && !decays(a, 1.0)?) || (singular_b && !decays(b, -1.0)?) {
+        return None;
+    }
+    // x and dx/dt at t ∈ [0, 1]; None where x is infinite
+    let change = |t: f64| -> Option<(f64, f64)> {
+        if a.is_infinite() || b.is_infinite() {
+            if t >= 1.0 {
+                return None;
+            }
+            let x = if a.is_infinite() {
+                b - t / (1.0 - t)
+            } else {
+                a + t / (1.0 - t)
+            };
+            Some((x, 1.0 / ((1.0 - t) * (1.0 - t))))
+        } else {
+            Some((
+                a + (b - a) * t * t * (3.0 - 2.0 * t),
+                (b - a) * 6.0 * t * (1.0 - t),
+            ))
+        }
+    };
+    let mut g = |t: f64| -> Option<f64> {
+        let (x, dxdt) = change(t)?;
+        Some(f(x)? * dxdt)
+    };
+    numeric_integral(
+        |t| {
+            if t > 0.0 && t < 1.0 {
+                return g(t);
+            }
+            // An improper end: the value just inside it
+            g(t).or_else(|| g(t + if t <= 0.0 { 1e-9 } else { -1e-9 }))
+                .or(Some(0.0))
+        },
+        0.0,
+        1.0,
+        tol,
+    )
+}
+
+/// Subdivision depth limit for [`numeric_integral`].
+const MAX_SIMPSON_DEPTH: u32 = 18;
+
+/// Steps a quadrature job reports progress in: thousandths of `[a, b]`.
+const QUADRATURE_STEPS: u64 = 1000;
+
+/// `∫ₐᵇ f` by adaptive Simpson quadrature to within about `tol`; `None`
+/// when `f` is undefined (or infinite) at any point it visits. Under
+/// [`with_progress`](crate::progress::with_progress) it reports the share
+/// of `[a, b]` already integrated, since subintervals finish left to right.
+pub fn numeric_integral(
+    mut f: impl FnMut(f64) -> Option<f64>,
+    a: f64,
+    b: f64,
+    tol: f64,
+) -> Option<f64> {
+    let mut job = crate::progress::Job::start(QUADRATURE_STEPS);
+    let mut covered = |x: f64| {
+        let share = if b == a { 1.0 } else { (x - a) / (b - a) };
+        job.set((share * QUADRATURE_STEPS as f64) as u64);
+    };
+    let (fa, fm, fb) = (f(a)?, f((a + b) / 2.0)?, f(b)?);
+    let whole = (b - a) / 6.0 * (fa + 4.0 * fm + fb);
+    simpson_step(
+        &mut f,
+        &mut covered,
+        a,
+        b,
+        fa,
+        fm,
+        fb,
+        whole,
+        tol,
+        MAX_SIMPSON_DEPTH,
+    )
+}
+
+/// `covered` is called with the right end of each finished subinterval.
+#[allow(clippy::too_many_arguments)] // the recursion carries the shared endpoint values
+fn simpson_step(
+    f: &mut impl FnMut(f64) -> Option<f64>,
+    covered: &mut impl FnMut(f64),
+    a: f64,
+    b: f64,
+    fa: f64,
+    fm: f64,
+    fb: f64,
+    whole: f64,
+    tol: f64,
+    depth: u32,
+) -> Option<f64> {
+    let m = (a + b) / 2.0;
+    let (lm, rm) = ((a + m) / 2.0, (m + b) / 2.0);
+    let (flm, frm) = (f(lm)?, f(rm)?);
+    let left = (m - a) / 6.0 * (fa + 4.0 * flm + fm);
+    let right = (b - m) / 6.0 * (fm + 4.0 * frm + fb);
+    let delta = left + right - whole;
+    if depth == 0 || delta.abs() <= 15.0 * tol {
+        covered(b);
+        return Some(left + right + delta / 15.0);
+    }
+    Some(
+        simpson_step(f, covered, a, m, fa, flm, fm, left, tol / 2.0, depth - 1)?
+            + simpson_step(f, covered, m, b, fm, frm, fb, right, tol / 2.0, depth - 1)?,
+    )
+}
+
 /// Calculates the definite integral of a LaTeX expression between two bounds
 ///
 /// # Arguments
@@ -3361,7 +3786,7 @@ pub fn definite_integral_latex(
     var_name: &str,
     lower: f64,
     upper: f64,
-) -> Result<String, String> {
+) -> Result<String, ArithmaError> {
     // Parse the input expression
     let mut tokenizer = Tokenizer::new(latex_expr);
     let tokens = tokenizer.tokenize();
@@ -3419,25 +3844,25 @@ fn collect_denominators(node: &Node, var: &str, out: &mut Vec<Node>) {
 /// the Cauchy principal value nobody asked for). Refuse when a denominator
 /// vanishes inside the interval: exactly (polynomial roots) where possible,
 /// by sign-change/magnitude scan otherwise. Symbolic bounds skip the check.
-fn check_no_poles_in_interval(
+/// A pole at an end of the interval is allowed: the integral is improper
+/// there and [`definite_integral_exact`] takes the antiderivative's limit.
+/// Over an infinite interval only polynomial denominators are checked.
+pub(crate) fn check_no_poles_in_interval(
     expr: &Node,
     var: &str,
     lower: &Node,
     upper: &Node,
-) -> Result<(), String> {
+) -> Result<(), ArithmaError> {
     let env = Environment::new();
-    let (lo, hi) = match (
-        crate::evaluator::Evaluator::evaluate(lower, &env),
-        crate::evaluator::Evaluator::evaluate(upper, &env),
-    ) {
-        (Ok(a), Ok(b)) if a.is_finite() && b.is_finite() => {
+    let (lo, hi) = match (bound_value(lower), bound_value(upper)) {
+        (Some(a), Some(b)) => {
             if a <= b {
                 (a, b)
             } else {
                 (b, a)
             }
         }
-        _ => return Ok(()), // symbolic or infinite bounds: not locatable here
+        _ => return Ok(()), // symbolic bounds: not locatable here
     };
 
     let mut denominators = Vec::new();
@@ -3449,11 +3874,11 @@ fn check_no_poles_in_interval(
             if let Ok(result) = crate::expression::solve_full(&eq, var) {
                 for root in &result.solutions {
                     if let Ok(r) = crate::evaluator::Evaluator::evaluate(root, &env) {
-                        if r >= lo - 1e-12 && r <= hi + 1e-12 {
-                            return Err(format!(
+                        if r > lo + 1e-12 && r < hi - 1e-12 {
+                            return Err(ArithmaError::Domain(format!(
                                 "Definite integral is improper: the integrand has a singularity at {} = {} inside [{}, {}]. FTC does not apply; the integral may diverge.",
                                 var, root, lo, hi
-                            ));
+                            )));
                         }
                     }
                 }
@@ -3461,23 +3886,26 @@ fn check_no_poles_in_interval(
             }
         }
         // Non-polynomial (or unsolvable) denominator: scan for sign changes
-        // or near-vanishing magnitude across the interval.
+        // or near-vanishing magnitude across the interior of the interval.
+        if lo.is_infinite() || hi.is_infinite() {
+            continue;
+        }
         let n = 128;
         let mut prev: Option<f64> = None;
-        for i in 0..=n {
+        for i in 1..n {
             let t = lo + (hi - lo) * (i as f64) / (n as f64);
             let mut sample_env = Environment::new();
             sample_env.set(var, t);
             if let Ok(v) = crate::evaluator::Evaluator::evaluate(den, &sample_env) {
                 if !v.is_finite() || v.abs() < 1e-9 {
-                    return Err(format!(
+                    return Err(ArithmaError::Domain(format!(
                         "Definite integral is improper: a denominator vanishes near {} ≈ {} inside [{}, {}].",
                         var, t, lo, hi
-                    ));
+                    )));
                 }
                 if let Some(p) = prev {
                     if p.signum() != v.signum() {
-                        return Err(format!(
+                        return Err(ArithmaError::Domain(format!(
                             "Definite integral is improper: a denominator changes sign between {} ≈ {} and {} ≈ {} inside [{}, {}].",
                             var,
                             lo + (hi - lo) * ((i - 1) as f64) / (n as f64),
@@ -3485,7 +3913,7 @@ fn check_no_poles_in_interval(
                             t,
                             lo,
                             hi
-                        ));
+                        )));
                     }
                 }
                 prev = Some(v);
@@ -3503,16 +3931,187 @@ pub fn definite_integral_exact(
     var_name: &str,
     lower: &Node,
     upper: &Node,
-) -> Result<Node, String> {
+) -> Result<Node, ArithmaError> {
     check_no_poles_in_interval(expr, var_name, lower, upper)?;
-    let antideriv = integrate(expr, var_name)?;
     let env = Environment::new();
-    let f_upper = substitute_variable(&antideriv, var_name, upper)?;
-    let f_lower = substitute_variable(&antideriv, var_name, lower)?;
-    let f_upper = f_upper.simplify(&env)?;
-    let f_lower = f_lower.simplify(&env)?;
+    let infinite = is_infinite_bound(lower) || is_infinite_bound(upper);
+    if is_symmetric_interval(lower, upper, &env) {
+        // Over (−∞, ∞) the two halves of an odd integrand may each diverge
+        let parity = symbolic_parity(expr, var_name);
+        if parity == Some(Parity::Odd) && !infinite {
+            return Ok(Node::Num(ExactNum::zero()));
+        }
+        if parity == Some(Parity::Even) {
+            // ∫₋ₐᵃ f = 2∫₀ᵃ f
+            let half =
+                definite_integral_exact(expr, var_name, &Node::Num(ExactNum::zero()), upper)?;
+            let doubled = Node::Multiply(Box::new(Node::Num(ExactNum::integer(2))), Box::new(half));
+            return Ok(doubled.simplify(&env)?);
+        }
+    }
+    // |f|, floor, ceiling and piecewise integrands, one smooth piece at a time
+    if crate::piecewise::has_breakpoints(expr, var_name) && !infinite {
+        if let Some(total) = crate::piecewise::integrate_by_pieces(expr, var_name, lower, upper)? {
+            return Ok(total);
+        }
+    }
+    let antideriv = integrate(expr, var_name)?;
+    let f_upper = antiderivative_at(&antideriv, expr, var_name, upper, lower)?;
+    let f_lower = antiderivative_at(&antideriv, expr, var_name, lower, upper)?;
     let diff = Node::Subtract(Box::new(f_upper), Box::new(f_lower));
-    diff.simplify(&env)
+    Ok(diff.simplify(&env)?)
+}
+
+/// `F(bound)` for the antiderivative `F` of `integrand`. At an improper
+/// end, `±∞` or a point where the integrand is undefined, this is the
+/// limit of `F` as the variable approaches `bound` from inside the
+/// interval (from the side of `other`); an infinite limit means the
+/// integral diverges.
+fn antiderivative_at(
+    antideriv: &Node,
+    integrand: &Node,
+    var: &str,
+    bound: &Node,
+    other: &Node,
+) -> Result<Node, ArithmaError> {
+    let env = Environment::new();
+    let point = match crate::limits::limit_point_of(bound, &env) {
+        Ok(LimitPoint::Finite(p)) if defined_at(integrand, var, p.to_f64()) => None,
+        Ok(point) => Some(point),
+        Err(_) => None, // symbolic bound
+    };
+    let substituted = substitute_variable(antideriv, var, bound)?.simplify(&env)?;
+    let Some(point) = point else {
+        return Ok(substituted);
+    };
+    // F itself may be continuous where the integrand is not (x·ln x − x at 0)
+    if !is_infinite_bound(bound)
+        && crate::evaluator::Evaluator::evaluate(&substituted, &env).is_ok_and(f64::is_finite)
+    {
+        return Ok(substituted);
+    }
+    let direction = match (&point, bound_value(other)) {
+        (LimitPoint::Finite(p), Some(o)) if o < p.to_f64() => LimitDirection::Left,
+        (LimitPoint::Finite(_), Some(_)) => LimitDirection::Right,
+        _ => LimitDirection::Both,
+    };
+    match crate::limits::compute_limit_directed(antideriv, var, &point, &direction) {
+        Ok(LimitResult::Finite(v)) => Ok(exact_constant(v)),
+        Ok(infinite) => Err(ArithmaError::Domain(format!(
+            "The improper integral diverges: the antiderivative tends to {} as {} → {}",
+            infinite, var, bound
+        ))),
+        Err(e) => Err(ArithmaError::NotImplemented(format!(
+            "Cannot take the limit of the antiderivative at the improper bound {}: {}",
+            bound, e
+        ))),
+    }
+}
+
+/// A limit value as an exact node where it is recognisably one: a float
+/// that is a small-denominator rational, or a rational multiple of π
+/// (`arctan` at ±∞).
+fn exact_constant(value: ExactNum) -> Node {
+    let rational = crate::series::try_rationalize(&value);
+    if let ExactNum::Rational(_) = rational {
+        return Node::Num(rational);
+    }
+    let over_pi = ExactNum::from_f64(value.to_f64() / std::f64::consts::PI);
+    match crate::series::try_rationalize(&over_pi) {
+        ExactNum::Rational(r) if r.is_one() => Node::Variable("π".to_string()),
+        ExactNum::Rational(r) => Node::Multiply(
+            Box::new(Node::Num(ExactNum::Rational(r))),
+            Box::new(Node::Variable("π".to_string())),
+        ),
+        ExactNum::Float(_) => Node::Num(value),
+    }
+}
+
+/// Whether `bound` is written `\infty` or `-\infty`.
+fn is_infinite_bound(bound: &Node) -> bool {
+    matches!(
+        crate::limits::limit_point_of(bound, &Environment::new()),
+        Ok(LimitPoint::PosInfinity | LimitPoint::NegInfinity)
+    )
+}
+
+/// The numeric value of a bound, `±f64::INFINITY` for `±\infty`, or
+/// `None` when it has free variables.
+fn bound_value(bound: &Node) -> Option<f64> {
+    match crate::limits::limit_point_of(bound, &Environment::new()).ok()? {
+        LimitPoint::Finite(v) => Some(v.to_f64()).filter(|v| v.is_finite()),
+        LimitPoint::PosInfinity => Some(f64::INFINITY),
+        LimitPoint::NegInfinity => Some(f64::NEG_INFINITY),
+    }
+}
+
+/// A bound as a node, with `±f64::INFINITY` as `±\infty`.
+fn bound_node(value: f64) -> Node {
+    let infinity = Node::Variable(crate::limits::INFINITY.to_string());
+    if value == f64::INFINITY {
+        infinity
+    } else if value == f64::NEG_INFINITY {
+        Node::Negate(Box::new(infinity))
+    } else {
+        Node::Num(ExactNum::from_f64(value))
+    }
+}
+
+/// Whether `expr` has a finite value at `var = x`.
+fn defined_at(expr: &Node, var: &str, x: f64) -> bool {
+    let mut env = Environment::new();
+    env.set(var, x);
+    crate::evaluator::Evaluator::evaluate(expr, &env).is_ok_and(f64::is_finite)
+}
+
+/// `∫` of `expr` between bounds given as expressions. A variable in a
+/// bound that `env` has a value for takes that value; constants such as
+/// `\pi` stay exact, and bounds that are still symbolic give a symbolic
+/// result, `∫₀ᵃ x dx = a²/2`. With numeric bounds and no antiderivative the
+/// value comes from quadrature instead.
+pub fn definite_integral_with_bounds(
+    expr: &Node,
+    var_name: &str,
+    lower: &Node,
+    upper: &Node,
+    env: &Environment,
+) -> Result<Node, ArithmaError> {
+    let bind = |bound: &Node| -> Result<Node, ArithmaError> {
+        let mut bound = bound.clone();
+        for name in env.variable_names() {
+            if let Some(value) = env.get_exact(name) {
+                bound = substitute_variable(&bound, name, &Node::Num(value.clone()))?;
+            }
+        }
+        Ok(bound.simplify(env)?)
+    };
+    let (lower, upper) = (bind(lower)?, bind(upper)?);
+    match definite_integral_exact(expr, var_name, &lower, &upper) {
+        Ok(result) => Ok(result),
+        Err(err @ ArithmaError::Domain(_)) => Err(err),
+        Err(err) => {
+            let bounds_are_numbers = crate::evaluator::Evaluator::evaluate(&lower, env).is_ok()
+                && crate::evaluator::Evaluator::evaluate(&upper, env).is_ok();
+            if !bounds_are_numbers {
+                return Err(err);
+            }
+            let integral = Node::Integral(
+                var_name.to_string(),
+                Some((Box::new(lower), Box::new(upper))),
+                Box::new(expr.clone()),
+            );
+            Ok(Node::Num(crate::evaluator::Evaluator::evaluate_exact(
+                &integral, env,
+            )?))
+        }
+    }
+}
+
+/// Whether `[lower, upper]` is `[−a, a]` for some nonzero `a`.
+fn is_symmetric_interval(lower: &Node, upper: &Node, env: &Environment) -> bool {
+    let sum = Node::Add(Box::new(lower.clone()), Box::new(upper.clone()));
+    let is_zero = |n: &Node| matches!(n, Node::Num(v) if v.is_zero());
+    !is_zero(upper) && sum.simplify(env).is_ok_and(|s| is_zero(&s))
 }
 
 pub fn definite_integral_exact_latex(
@@ -3520,7 +4119,7 @@ pub fn definite_integral_exact_latex(
     var_name: &str,
     lower_latex: &str,
     upper_latex: &str,
-) -> Result<String, String> {
+) -> Result<String, ArithmaError> {
     let mut tok = Tokenizer::new(latex_expr);
     let expr = build_expression_tree(tok.tokenize())?;
 
@@ -3543,7 +4142,7 @@ mod tests {
     fn parse_expression(latex: &str) -> Result<Node, String> {
         let mut tokenizer = Tokenizer::new(latex);
         let tokens = tokenizer.tokenize();
-        build_expression_tree(tokens)
+        Ok(build_expression_tree(tokens)?)
     }
 
     fn approx_eq(a: f64, b: f64, epsilon: f64) -> bool {
@@ -3738,7 +4337,7 @@ mod tests {
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
-            err.starts_with("NON_ELEMENTARY:"),
+            matches!(err, ArithmaError::NonElementary(_)),
             "Expected NON_ELEMENTARY, got: {}",
             err
         );
@@ -3764,7 +4363,10 @@ mod tests {
         let expr = parse_expression("\\exp(x^3)").unwrap();
         let result = integrate(&expr, "x");
         assert!(result.is_err());
-        assert!(result.unwrap_err().starts_with("NON_ELEMENTARY:"));
+        assert!(matches!(
+            result.unwrap_err(),
+            ArithmaError::NonElementary(_)
+        ));
     }
 
     #[test]
@@ -3795,7 +4397,7 @@ mod tests {
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
-            err.starts_with("NON_ELEMENTARY:"),
+            matches!(err, ArithmaError::NonElementary(_)),
             "Expected NON_ELEMENTARY, got: {}",
             err
         );
@@ -3809,7 +4411,7 @@ mod tests {
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
-            err.starts_with("NON_ELEMENTARY:"),
+            matches!(err, ArithmaError::NonElementary(_)),
             "Expected NON_ELEMENTARY, got: {}",
             err
         );
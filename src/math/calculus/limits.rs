@@ -6,6 +6,7 @@ use crate::derivative::differentiate;
 use crate::environment::Environment;
 use crate::evaluator::Evaluator;
 use crate::exact::ExactNum;
+pub use crate::node::LimitDirection;
 use crate::node::Node;
 use crate::parser::build_expression_tree;
 use crate::polynomial::Polynomial;
@@ -13,13 +14,6 @@ use crate::series::try_rationalize;
 use crate::simplify::Simplifiable;
 use crate::tokenizer::Tokenizer;
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum LimitDirection {
-    Both,
-    Left,
-    Right,
-}
-
 #[derive(Clone, Debug, PartialEq)]
 pub enum LimitResult {
     Finite(ExactNum),
@@ -275,6 +269,10 @@ fn limit_at_infinity(
         }
     }
 
+    if let Some(result) = limit_by_parts_at_infinity(&simplified, var, positive, depth) {
+        return result;
+    }
+
     if let Node::Divide(num, den) = &simplified {
         return limit_quotient_at_infinity(num, den, var, positive, depth);
     }
@@ -395,14 +393,155 @@ fn limit_at_infinity(
         return Ok(result);
     }
 
+    // No ∞ sign in the message: callers read "+∞"/"-∞" as the answer
     Err(format!(
-        "Cannot compute limit of {} as {} → {}∞",
+        "Cannot compute limit of {} as {} grows {}",
         simplified,
         var,
-        if positive { "+" } else { "-" }
+        if positive {
+            "without bound"
+        } else {
+            "negative without bound"
+        }
     ))
 }
 
+/// Limits at ±∞ that follow from the limits of the parts: sums and
+/// constant multiples, powers, roots, absolute values and logarithms (a
+/// negative power of something unbounded → 0, `ln` of it → +∞), and
+/// `arctan` and `tanh`, which level off at `±π/2` and `±1`. The parts are
+/// smaller expressions, so the recursion keeps `depth`. `None` leaves the
+/// expression to the other rules.
+fn limit_by_parts_at_infinity(
+    expr: &Node,
+    var: &str,
+    positive: bool,
+    depth: usize,
+) -> Option<Result<ExactNum, String>> {
+    let limit = |n: &Node| limit_at_infinity(n, var, positive, depth);
+    let finite = |n: &Node| limit(n).ok().filter(|v| !v.is_nan_or_inf());
+    // Divergence to ±∞, negated when `flip`
+    let infinite = |result: &Result<ExactNum, String>, flip: bool| match result {
+        Err(msg) => parse_infinity_error(msg).map(|inf| {
+            let up = (inf == LimitResult::PosInfinity) != flip;
+            format!("Limit is {}∞", if up { "+" } else { "-" })
+        }),
+        Ok(_) => None,
+    };
+    match expr {
+        Node::Add(l, r) | Node::Subtract(l, r) => {
+            let flip = matches!(expr, Node::Subtract(..));
+            let (a, b) = (limit(l), limit(r));
+            match (&a, &b) {
+                (Ok(x), Ok(y)) if !x.is_nan_or_inf() && !y.is_nan_or_inf() => {
+                    Some(Ok(if flip { x - y } else { x + y }))
+                }
+                (Ok(x), Err(_)) if !x.is_nan_or_inf() => infinite(&b, flip).map(Err),
+                (Err(_), Ok(y)) if !y.is_nan_or_inf() => infinite(&a, false).map(Err),
+                _ => None,
+            }
+        }
+        Node::Negate(inner) => {
+            let result = limit(inner);
+            match &result {
+                Ok(v) if !v.is_nan_or_inf() => Some(Ok(-v.clone())),
+                _ => infinite(&result, true).map(Err),
+            }
+        }
+        Node::Multiply(c, f) | Node::Multiply(f, c) | Node::Divide(f, c)
+            if !contains_var(c, var) =>
+        {
+            let c = finite(c)?;
+            let result = limit(f);
+            match &result {
+                Ok(v) if !v.is_nan_or_inf() => Some(Ok(if matches!(expr, Node::Divide(..)) {
+                    v / &c
+                } else {
+                    v * &c
+                })),
+                _ if c.is_zero() => None,
+                _ => infinite(&result, c.is_negative()).map(Err),
+            }
+        }
+        Node::Power(base, exp) if !contains_var(exp, var) => {
+            let exponent = Evaluator::evaluate_exact(exp, &Environment::new()).ok()?;
+            let result = limit(base);
+            match &result {
+                Ok(v) if !v.is_nan_or_inf() && !v.is_zero() => Some(Ok(v.powf(&exponent))),
+                _ if exponent.is_negative() => {
+                    infinite(&result, false).map(|_| Ok(ExactNum::zero()))
+                }
+                _ if exponent.is_zero() => None,
+                _ => match infinite(&result, false)? {
+                    up if up.contains("+∞") => Some(Err(up)),
+                    _ => None,
+                },
+            }
+        }
+        Node::Abs(inner) | Node::Sqrt(inner) => {
+            let result = limit(inner);
+            match &result {
+                Ok(v) if !v.is_nan_or_inf() => {
+                    let at = if matches!(expr, Node::Abs(_)) {
+                        Node::Abs(Box::new(Node::Num(v.clone())))
+                    } else {
+                        Node::Sqrt(Box::new(Node::Num(v.clone())))
+                    };
+                    Some(
+                        Evaluator::evaluate_exact(&at, &Environment::new())
+                            .map_err(|e| e.to_string()),
+                    )
+                }
+                _ => match infinite(&result, false)? {
+                    down if down.contains("-∞") && matches!(expr, Node::Sqrt(_)) => None,
+                    _ => Some(Err("Limit is +∞".to_string())),
+                },
+            }
+        }
+        Node::Function(name, args)
+            if args.len() == 1 && matches!(name.as_str(), "ln" | "log" | "lg") =>
+        {
+            let result = limit(&args[0]);
+            match &result {
+                Ok(v) if !v.is_nan_or_inf() && v.to_f64() > 0.0 => {
+                    let at = Node::Function(name.clone(), vec![Node::Num(v.clone())]);
+                    Some(
+                        Evaluator::evaluate_exact(&at, &Environment::new())
+                            .map_err(|e| e.to_string()),
+                    )
+                }
+                _ => match infinite(&result, false)? {
+                    up if up.contains("+∞") => Some(Err(up)),
+                    _ => None,
+                },
+            }
+        }
+        Node::Function(name, args)
+            if args.len() == 1 && matches!(name.as_str(), "arctan" | "atan" | "tanh") =>
+        {
+            let level = if name == "tanh" {
+                1.0
+            } else {
+                std::f64::consts::FRAC_PI_2
+            };
+            match limit(&args[0]) {
+                Ok(v) if !v.is_nan_or_inf() => {
+                    let at = Node::Function(name.clone(), vec![Node::Num(v)]);
+                    Some(
+                        Evaluator::evaluate_exact(&at, &Environment::new())
+                            .map_err(|e| e.to_string()),
+                    )
+                }
+                result => match infinite(&result, false)? {
+                    msg if msg.contains("+∞") => Some(Ok(ExactNum::from_f64(level))),
+                    _ => Some(Ok(ExactNum::from_f64(-level))),
+                },
+            }
+        }
+        _ => None,
+    }
+}
+
 fn limit_quotient_at_infinity(
     num: &Node,
     den: &Node,
@@ -516,6 +655,13 @@ fn limit_product_at_infinity(
 }
 
 fn is_decaying_exp(expr: &Node, var: &str, positive: bool) -> bool {
+    match expr {
+        Node::Negate(inner) => return is_decaying_exp(inner, var, positive),
+        Node::Multiply(c, f) | Node::Multiply(f, c) if !contains_var(c, var) => {
+            return is_decaying_exp(f, var, positive)
+        }
+        _ => {}
+    }
     if let Node::Function(name, args) = expr {
         if name == "exp" && args.len() == 1 {
             if let Ok(p) = Polynomial::from_node(&args[0], var) {
@@ -762,6 +908,18 @@ fn limit_internal(
         }
     }
 
+    // Step 1b: a logarithm of a vanishing argument tends to -∞ (a Taylor
+    // expansion at the singularity never resolves it)
+    if let Node::Function(name, args) = expr {
+        if matches!(name.as_str(), "ln" | "log" | "lg") && args.len() == 1 {
+            if let Ok(v) = limit_internal(&args[0], var, point, depth + 1) {
+                if v.is_zero() {
+                    return Err("Limit is -∞ (logarithm of a vanishing argument)".to_string());
+                }
+            }
+        }
+    }
+
     // Step 2: if it's a quotient, analyze the form
     if let Node::Divide(numer, denom) = expr {
         return limit_quotient(numer, denom, var, point, depth);
@@ -941,6 +1099,149 @@ fn try_polynomial_cancel(
     Some(Ok(&n_val / &d_val))
 }
 
+/// Name of the variable `\\infty` parses to.
+pub const INFINITY: &str = "∞";
+
+/// Closest sample spacing tried by [`numeric_limit`], as a power of ten.
+const NUMERIC_LIMIT_STEPS: i32 = 8;
+
+/// The point a `\\lim` approaches: `\\infty` and `-\\infty` as written,
+/// anything else by its value in `env`.
+pub fn limit_point_of(point: &Node, env: &Environment) -> Result<LimitPoint, String> {
+    match point {
+        Node::Variable(v) if v == INFINITY => Ok(LimitPoint::PosInfinity),
+        Node::Negate(inner) if matches!(&**inner, Node::Variable(v) if v == INFINITY) => {
+            Ok(LimitPoint::NegInfinity)
+        }
+        _ => Evaluator::evaluate_exact(point, env)
+            .map(LimitPoint::Finite)
+            .map_err(|e| format!("The limit point must have a value: {}", e)),
+    }
+}
+
+/// A resolved limit as a node: a number, `\\infty`, or `-\\infty`.
+pub fn limit_result_to_node(result: LimitResult) -> Node {
+    let infinity = || Node::Variable(INFINITY.to_string());
+    match result {
+        LimitResult::Finite(v) => Node::Num(v),
+        LimitResult::PosInfinity => infinity(),
+        LimitResult::NegInfinity => Node::Negate(Box::new(infinity())),
+    }
+}
+
+/// Resolve `\\lim_{var \\to point} body` symbolically. Only a body whose
+/// sole free variable is `var` is attempted; anything else, or a limit
+/// the engine cannot decide, is an error.
+pub fn resolve_limit(
+    var: &str,
+    point: &Node,
+    direction: LimitDirection,
+    body: &Node,
+) -> Result<LimitResult, String> {
+    let others: Vec<String> = crate::status::free_variables(&[body])
+        .into_iter()
+        .filter(|v| v != var)
+        .collect();
+    if !others.is_empty() {
+        return Err(format!(
+            "The limit depends on {}, which has no value",
+            others.join(", ")
+        ));
+    }
+    let point = limit_point_of(point, &Environment::new())?;
+    compute_limit_directed(body, var, &point, &direction)
+}
+
+/// Numeric value of `\\lim_{var \\to point} body` with the free variables
+/// taken from `env`. Tries [`resolve_limit`] first; when the body has
+/// other free variables or the engine gives up, samples the body ever
+/// closer to the point with [`numeric_limit`]. A divergent limit is an
+/// error naming its sign.
+pub fn evaluate_limit(
+    var: &str,
+    point: &Node,
+    direction: LimitDirection,
+    body: &Node,
+    env: &Environment,
+) -> Result<ExactNum, String> {
+    let limit_point = limit_point_of(point, env)?;
+    let symbolic_error = match resolve_limit(var, point, direction, body) {
+        Ok(LimitResult::Finite(v)) => return Ok(v),
+        Ok(infinite) => return Err(format!("The limit diverges to {}", infinite)),
+        Err(e) if e.contains("does not exist") => return Err(e),
+        Err(e) => e,
+    };
+    let mut inner_env = env.child();
+    let f = |x: f64| {
+        inner_env.set(var, x);
+        Evaluator::evaluate(body, &inner_env)
+            .ok()
+            .filter(|v| v.is_finite())
+    };
+    numeric_limit(f, &limit_point, &direction)
+        .map(ExactNum::from_f64)
+        .ok_or_else(|| {
+            format!(
+                "The limit could not be determined symbolically ({}) or numerically",
+                symbolic_error
+            )
+        })
+}
+
+/// Sample `f` at points approaching `point` (spacings 10⁻¹ … 10⁻⁸, scaled
+/// by the point's size; or out to ±10⁸ for ±∞) and accept the value the
+/// samples settle on. Settling means two consecutive samples agree to
+/// about six digits, which oscillation and divergence never do and
+/// floating-point cancellation very close to the point cannot spoil. A
+/// two-sided limit needs both sides to settle on the same value.
+pub fn numeric_limit(
+    mut f: impl FnMut(f64) -> Option<f64>,
+    point: &LimitPoint,
+    direction: &LimitDirection,
+) -> Option<f64> {
+    match point {
+        LimitPoint::PosInfinity => settle_along(&mut f, |t| t),
+        LimitPoint::NegInfinity => settle_along(&mut f, |t| -t),
+        LimitPoint::Finite(p) => {
+            let p = p.to_f64();
+            let scale = 1.0 + p.abs();
+            let from_left =
+                |f: &mut dyn FnMut(f64) -> Option<f64>| settle_along(f, |t| p - scale / t);
+            let from_right =
+                |f: &mut dyn FnMut(f64) -> Option<f64>| settle_along(f, |t| p + scale / t);
+            match direction {
+                LimitDirection::Left => from_left(&mut f),
+                LimitDirection::Right => from_right(&mut f),
+                LimitDirection::Both => {
+                    let (left, right) = (from_left(&mut f)?, from_right(&mut f)?);
+                    let tol = 1e-6 * (1.0 + left.abs().max(right.abs()));
+                    ((left - right).abs() <= tol).then_some((left + right) / 2.0)
+                }
+            }
+        }
+    }
+}
+
+/// Sample `f` at `at(10), at(100), …` and take the settled value.
+fn settle_along(f: &mut dyn FnMut(f64) -> Option<f64>, at: impl Fn(f64) -> f64) -> Option<f64> {
+    let samples: Vec<Option<f64>> = (1..=NUMERIC_LIMIT_STEPS)
+        .map(|k| f(at(10f64.powi(k))))
+        .collect();
+    settled_value(&samples)
+}
+
+/// The later sample of the closest-agreeing consecutive pair, if that
+/// pair agrees to within a relative 10⁻⁶.
+fn settled_value(samples: &[Option<f64>]) -> Option<f64> {
+    samples
+        .windows(2)
+        .filter_map(|pair| Some((pair[0]?, pair[1]?)))
+        .map(|(a, b)| ((a - b).abs() / (1.0 + b.abs()), b))
+        .min_by(|x, y| x.0.total_cmp(&y.0))
+        .filter(|(gap, _)| *gap <= 1e-6)
+        .map(|(_, b)| b)
+}
+
 /// Parse a limit point string into a LimitPoint and direction.
 /// Accepts directional suffixes: "0+", "0-", "3+", "3-", "-1+", "-1-".
 /// A trailing '+' or '-' after a digit indicates direction (right or left).
@@ -0,0 +1,330 @@
+//! Definite integrals of piecewise-smooth integrands.
+//!
+//! `|f|`, `\lfloor f \rfloor`, `\lceil f \rceil` and `Piecewise` have no
+//! single elementary antiderivative, but each is smooth between its
+//! breakpoints: the roots of an absolute value's argument or of a
+//! condition, and the points where a floor or ceiling argument crosses an
+//! integer. [`integrate_by_pieces`] splits `[a, b]` there and, on each
+//! piece, replaces every such node by the branch it takes, so the ordinary
+//! engine integrates `\int_{-1}^{1} |x| \, dx` exactly as
+//! `\int_{-1}^{0} -x \, dx + \int_{0}^{1} x \, dx = 1`.
+
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::exact::ExactNum;
+use crate::node::Node;
+use crate::simplify::Simplifiable;
+
+/// Most pieces one integral is split into; `\lfloor 1000x \rfloor` over
+/// `[0, 10]` is refused rather than integrated piece by piece.
+const MAX_PIECES: usize = 1000;
+
+/// Where each piece is probed to pick, and then confirm, the branch every
+/// switching node takes there: interior and irregular, so a breakpoint the
+/// solver missed shows up as two different branches.
+const PROBE_FRACTIONS: [f64; 7] = [0.5, 0.071, 0.193, 0.337, 0.619, 0.758, 0.916];
+
+/// Samples used to bound a floor or ceiling argument over the interval.
+const RANGE_SAMPLES: usize = 64;
+
+/// Whether `expr` has an absolute value, floor, ceiling or piecewise
+/// node that switches branch as `var` varies.
+pub fn has_breakpoints(expr: &Node, var: &str) -> bool {
+    match expr {
+        Node::Abs(inner) | Node::Floor(inner) | Node::Ceil(inner) => inner.contains_variable(var),
+        Node::Piecewise(arms) => arms
+            .iter()
+            .any(|(e, c)| e.contains_variable(var) || c.contains_variable(var)),
+        _ => smooth_children(expr)
+            .iter()
+            .any(|child| has_breakpoints(child, var)),
+    }
+}
+
+/// The breakpoints of `expr` strictly between `lower` and `upper`, in
+/// order from `lower` to `upper`, as exact values where the solver finds
+/// them. Breakpoints the solver cannot locate are left out; the piecewise
+/// integration notices them when it picks branches.
+pub fn breakpoints(
+    expr: &Node,
+    var: &str,
+    lower: &Node,
+    upper: &Node,
+) -> Result<Vec<Node>, String> {
+    let (a, b) = (number(lower)?, number(upper)?);
+    let (lo, hi) = (a.min(b), a.max(b));
+    let mut found = Vec::new();
+    collect_breakpoints(expr, var, lo, hi, &mut found)?;
+
+    let mut located: Vec<(f64, Node)> = found
+        .into_iter()
+        .filter_map(|node| {
+            let x = number(&node).ok()?;
+            (x > lo && x < hi).then_some((x, node))
+        })
+        .collect();
+    located.sort_by(|p, q| p.0.total_cmp(&q.0));
+    located.dedup_by(|p, q| (p.0 - q.0).abs() <= 1e-12 * (1.0 + p.0.abs()));
+    if a > b {
+        located.reverse();
+    }
+    Ok(located.into_iter().map(|(_, node)| node).collect())
+}
+
+/// `\int_{lower}^{upper} expr \, d var`, split at the breakpoints of
+/// `expr` and summed. `None` when a bound is not a number, so the
+/// breakpoints cannot be placed.
+pub fn integrate_by_pieces(
+    expr: &Node,
+    var: &str,
+    lower: &Node,
+    upper: &Node,
+) -> Result<Option<Node>, String> {
+    if number(lower).is_err() || number(upper).is_err() {
+        return Ok(None);
+    }
+    let inner = breakpoints(expr, var, lower, upper)?;
+    if inner.len() >= MAX_PIECES {
+        return Err(format!(
+            "The integrand {} has more than {} breakpoints between {} and {}",
+            expr, MAX_PIECES, lower, upper
+        ));
+    }
+
+    let mut points = Vec::with_capacity(inner.len() + 2);
+    points.push(lower.clone());
+    points.extend(inner);
+    points.push(upper.clone());
+
+    let env = Environment::new();
+    let mut total: Option<Node> = None;
+    for piece in points.windows(2) {
+        let (a, b) = (&piece[0], &piece[1]);
+        let probes: Vec<f64> = {
+            let (xa, xb) = (number(a)?, number(b)?);
+            PROBE_FRACTIONS.iter().map(|t| xa + t * (xb - xa)).collect()
+        };
+        let smooth = select_branches(expr, var, &probes).map_err(|node| {
+            format!(
+                "Cannot split the integral of {} at its breakpoints: {} does not keep one branch between {} = {} and {}",
+                expr, node, var, a, b
+            )
+        })?;
+        let value = crate::integration::definite_integral_exact(&smooth, var, a, b)?;
+        total = Some(match total {
+            None => value,
+            Some(sum) => Node::Add(Box::new(sum), Box::new(value)),
+        });
+    }
+    let total = total.expect("an interval has at least one piece");
+    total.simplify(&env).map(Some)
+}
+
+fn number(node: &Node) -> Result<f64, String> {
+    Evaluator::evaluate(node, &Environment::new())
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| format!("{} is not a finite number", node))
+}
+
+fn value_at(node: &Node, var: &str, x: f64) -> Option<f64> {
+    let mut env = Environment::new();
+    env.set(var, x);
+    Evaluator::evaluate(node, &env)
+        .ok()
+        .filter(|v| v.is_finite())
+}
+
+/// Children that integration sees through. Sums, integrals and limits
+/// bind their own variables and are left alone.
+fn smooth_children(node: &Node) -> Vec<&Node> {
+    match node {
+        Node::Add(l, r)
+        | Node::Subtract(l, r)
+        | Node::Multiply(l, r)
+        | Node::Divide(l, r)
+        | Node::Power(l, r)
+        | Node::Greater(l, r)
+        | Node::Less(l, r)
+        | Node::GreaterEqual(l, r)
+        | Node::LessEqual(l, r)
+        | Node::And(l, r)
+        | Node::Or(l, r)
+        | Node::Equal(l, r) => vec![l, r],
+        Node::Negate(inner) | Node::Not(inner) | Node::Sqrt(inner) => vec![inner],
+        Node::Function(_, args) => args.iter().collect(),
+        _ => vec![],
+    }
+}
+
+fn collect_breakpoints(
+    node: &Node,
+    var: &str,
+    lo: f64,
+    hi: f64,
+    out: &mut Vec<Node>,
+) -> Result<(), String> {
+    match node {
+        Node::Abs(inner) => {
+            if inner.contains_variable(var) {
+                roots(inner, var, out);
+            }
+            collect_breakpoints(inner, var, lo, hi, out)
+        }
+        Node::Floor(inner) | Node::Ceil(inner) => {
+            if inner.contains_variable(var) {
+                integer_crossings(inner, var, lo, hi, out)?;
+            }
+            collect_breakpoints(inner, var, lo, hi, out)
+        }
+        Node::Piecewise(arms) => {
+            for (value, condition) in arms {
+                condition_breakpoints(condition, var, out);
+                collect_breakpoints(value, var, lo, hi, out)?;
+                collect_breakpoints(condition, var, lo, hi, out)?;
+            }
+            Ok(())
+        }
+        _ => smooth_children(node)
+            .into_iter()
+            .try_for_each(|child| collect_breakpoints(child, var, lo, hi, out)),
+    }
+}
+
+/// Where a branch condition can change truth: the roots of each comparison
+/// in it, through `\land`, `\lor` and `\lnot`.
+fn condition_breakpoints(condition: &Node, var: &str, out: &mut Vec<Node>) {
+    match condition {
+        Node::Greater(l, r)
+        | Node::Less(l, r)
+        | Node::GreaterEqual(l, r)
+        | Node::LessEqual(l, r)
+        | Node::Equal(l, r) => {
+            let difference = Node::Subtract(l.clone(), r.clone());
+            if difference.contains_variable(var) {
+                roots(&difference, var, out);
+            }
+        }
+        Node::And(l, r) | Node::Or(l, r) => {
+            condition_breakpoints(l, var, out);
+            condition_breakpoints(r, var, out);
+        }
+        Node::Not(inner) => condition_breakpoints(inner, var, out),
+        _ => {}
+    }
+}
+
+/// Real roots of `f` the solver can express. An argument that itself
+/// switches branch (`|\lfloor x \rfloor - 1|`) is skipped: its inner
+/// breakpoints split the interval, and each piece is checked anyway.
+fn roots(f: &Node, var: &str, out: &mut Vec<Node>) {
+    if has_breakpoints(f, var) {
+        return;
+    }
+    let equation = Node::Equation(Box::new(f.clone()), Box::new(Node::Num(ExactNum::zero())));
+    if let Ok(result) = crate::expression::solve_full(&equation, var) {
+        out.extend(result.solutions);
+    }
+}
+
+/// Points where `f` crosses an integer between `lo` and `hi`.
+fn integer_crossings(
+    f: &Node,
+    var: &str,
+    lo: f64,
+    hi: f64,
+    out: &mut Vec<Node>,
+) -> Result<(), String> {
+    let values: Vec<f64> = (0..=RANGE_SAMPLES)
+        .filter_map(|i| value_at(f, var, lo + (hi - lo) * i as f64 / RANGE_SAMPLES as f64))
+        .collect();
+    let (Some(min), Some(max)) = (
+        values.iter().copied().reduce(f64::min),
+        values.iter().copied().reduce(f64::max),
+    ) else {
+        return Ok(());
+    };
+    let (first, last) = (min.floor() as i64 + 1, max.ceil() as i64 - 1);
+    if last - first + 1 >= MAX_PIECES as i64 {
+        return Err(format!(
+            "The integrand crosses more than {} integers in \\lfloor {} \\rfloor",
+            MAX_PIECES, f
+        ));
+    }
+    for n in first..=last {
+        let shifted = Node::Subtract(
+            Box::new(f.clone()),
+            Box::new(Node::Num(ExactNum::integer(n))),
+        );
+        roots(&shifted, var, out);
+    }
+    Ok(())
+}
+
+/// `node` with every switching node replaced by the branch it takes at
+/// `probes`. On failure, the node whose branch differs between probes.
+fn select_branches(node: &Node, var: &str, probes: &[f64]) -> Result<Node, Node> {
+    let select = |n: &Node| select_branches(n, var, probes).map(Box::new);
+    let consistent = |values: Vec<Option<i64>>, culprit: &Node| -> Result<i64, Node> {
+        let mut defined = values.into_iter().flatten();
+        let first = defined.next().ok_or_else(|| culprit.clone())?;
+        match defined.all(|v| v == first) {
+            true => Ok(first),
+            false => Err(culprit.clone()),
+        }
+    };
+    Ok(match node {
+        Node::Abs(inner) if inner.contains_variable(var) => {
+            let inner = select_branches(inner, var, probes)?;
+            let signs: Vec<_> = probes
+                .iter()
+                .map(|&x| value_at(&inner, var, x))
+                .filter(|v| v.is_none_or(|v| v.abs() > 1e-12))
+                .map(|v| v.map(|v| v.signum() as i64))
+                .collect();
+            // Zero throughout the piece: either branch will do
+            if signs.is_empty() {
+                return Ok(inner);
+            }
+            match consistent(signs, node)? {
+                -1 => Node::Negate(Box::new(inner)),
+                _ => inner,
+            }
+        }
+        Node::Floor(inner) | Node::Ceil(inner) if inner.contains_variable(var) => {
+            let inner = select_branches(inner, var, probes)?;
+            let round = match node {
+                Node::Floor(_) => f64::floor,
+                _ => f64::ceil,
+            };
+            let levels = probes
+                .iter()
+                .map(|&x| value_at(&inner, var, x).map(|v| round(v) as i64))
+                .collect();
+            Node::Num(ExactNum::integer(consistent(levels, node)?))
+        }
+        Node::Piecewise(arms) => {
+            let arm_at = |x: f64| {
+                arms.iter()
+                    .position(|(_, condition)| value_at(condition, var, x) == Some(1.0))
+                    .map(|i| i as i64)
+            };
+            let arm = consistent(probes.iter().map(|&x| arm_at(x)).collect(), node)?;
+            select_branches(&arms[arm as usize].0, var, probes)?
+        }
+        Node::Add(l, r) => Node::Add(select(l)?, select(r)?),
+        Node::Subtract(l, r) => Node::Subtract(select(l)?, select(r)?),
+        Node::Multiply(l, r) => Node::Multiply(select(l)?, select(r)?),
+        Node::Divide(l, r) => Node::Divide(select(l)?, select(r)?),
+        Node::Power(l, r) => Node::Power(select(l)?, select(r)?),
+        Node::Negate(inner) => Node::Negate(select(inner)?),
+        Node::Sqrt(inner) => Node::Sqrt(select(inner)?),
+        Node::Function(name, args) => Node::Function(
+            name.clone(),
+            args.iter()
+                .map(|a| select_branches(a, var, probes))
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => node.clone(),
+    })
+}
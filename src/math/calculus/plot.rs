@@ -0,0 +1,173 @@
+//! Sampling an expression for plotting.
+//!
+//! [`sample`] evaluates `f(x)` on an even grid, with NaN where `f` is
+//! undefined. [`plot`] goes on to cut the samples into the pieces a
+//! frontend should draw as separate lines: a line must not run across a
+//! gap where `f` is undefined (`\sqrt{x}` for `x < 0`, `\ln(x)` at 0) nor
+//! join the two sides of a jump or a pole (`\lfloor x \rfloor`,
+//! `\frac{1}{x}`, `\tan(x)`).
+//!
+//! A jump is suspected between neighbouring samples whose difference stands
+//! out from those around it, and confirmed by bisecting the interval: the
+//! difference across a discontinuity stays large however small the interval
+//! gets, while a steep but continuous stretch flattens out. Both use the
+//! expression [compiled](crate::Evaluator::compile) once.
+
+use serde::Serialize;
+
+use crate::compile::CompiledExpr;
+use crate::evaluator::Evaluator;
+use crate::node::Node;
+
+/// How many times a suspected jump is bisected.
+const BISECTIONS: usize = 60;
+
+/// A difference that shrinks below this fraction of itself under bisection
+/// was a steep continuous stretch, not a jump.
+const JUMP_RATIO: f64 = 0.1;
+
+/// A difference between neighbouring samples this many times larger than
+/// those on either side is a suspected jump.
+const JUMP_FACTOR: f64 = 4.0;
+
+/// An expression sampled by [`plot`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PlotData {
+    /// The samples as lines to draw, in order of `x`, each with at least
+    /// one point
+    pub segments: Vec<Vec<(f64, f64)>>,
+    /// Where `f` jumps or has a pole between two samples, located to about
+    /// machine precision
+    pub discontinuities: Vec<f64>,
+    /// Ranges `[a, b]` of consecutive samples at which `f` is undefined or
+    /// infinite
+    pub undefined: Vec<(f64, f64)>,
+}
+
+/// `f(x)` for `f = expr` at `n` evenly spaced `x` from `start` to `end`,
+/// both included. The value is NaN where `f` is undefined, including
+/// everywhere when `expr` has a free variable other than `var`.
+pub fn sample(expr: &Node, var: &str, start: f64, end: f64, n: usize) -> Vec<(f64, f64)> {
+    match Evaluator::compile(expr, &[var]) {
+        Ok(f) => sample_compiled(&f, start, end, n),
+        Err(_) => grid(start, end, n).map(|x| (x, f64::NAN)).collect(),
+    }
+}
+
+/// [`sample`] `expr` and cut the samples into the lines to draw, reporting
+/// where it jumps and where it is undefined.
+pub fn plot(expr: &Node, var: &str, start: f64, end: f64, n: usize) -> Result<PlotData, String> {
+    if n < 2 {
+        return Err("At least 2 samples are required".to_string());
+    }
+    if !(start.is_finite() && end.is_finite() && start < end) {
+        return Err(format!("Invalid plot range [{}, {}]", start, end));
+    }
+    let f = Evaluator::compile(expr, &[var])?;
+    let points = sample_compiled(&f, start, end, n);
+    let defined = |i: usize| points[i].1.is_finite();
+
+    let mut undefined = Vec::new();
+    let mut run_start = None;
+    for i in 0..n {
+        match (defined(i), run_start) {
+            (false, None) => run_start = Some(i),
+            (true, Some(first)) => {
+                undefined.push((points[first].0, points[i - 1].0));
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(first) = run_start {
+        undefined.push((points[first].0, points[n - 1].0));
+    }
+
+    // Signed differences between neighbouring samples, NaN across a gap
+    let steps: Vec<f64> = (0..n - 1).map(|i| points[i + 1].1 - points[i].1).collect();
+    let mut discontinuities = Vec::new();
+    let mut segments = Vec::new();
+    let mut segment = Vec::new();
+    for i in 0..n {
+        if !defined(i) {
+            if !segment.is_empty() {
+                segments.push(std::mem::take(&mut segment));
+            }
+            continue;
+        }
+        segment.push(points[i]);
+        if i + 1 < n && steps[i].is_finite() && suspect_jump(&steps, i) {
+            if let Some(x) = locate_jump(&f, points[i], points[i + 1]) {
+                discontinuities.push(x);
+                segments.push(std::mem::take(&mut segment));
+            }
+        }
+    }
+    if !segment.is_empty() {
+        segments.push(segment);
+    }
+
+    Ok(PlotData {
+        segments,
+        discontinuities,
+        undefined,
+    })
+}
+
+fn grid(start: f64, end: f64, n: usize) -> impl Iterator<Item = f64> {
+    (0..n).map(move |i| {
+        if i + 1 == n && n > 1 {
+            end
+        } else {
+            start + (end - start) * i as f64 / (n.max(2) - 1) as f64
+        }
+    })
+}
+
+fn sample_compiled(f: &CompiledExpr, start: f64, end: f64, n: usize) -> Vec<(f64, f64)> {
+    grid(start, end, n).map(|x| (x, f.eval(&[x]))).collect()
+}
+
+/// Whether `f` may jump between samples `i` and `i + 1`: their difference
+/// is much larger than those beside it, or goes the other way from both,
+/// as across the pole of `\frac{1}{x}` or `\tan(x)`. A missing neighbour
+/// (an edge, or an undefined sample) does not count against it.
+fn suspect_jump(steps: &[f64], i: usize) -> bool {
+    let neighbours: Vec<f64> = [i.checked_sub(1), Some(i + 1)]
+        .into_iter()
+        .flatten()
+        .filter_map(|j| steps.get(j).copied())
+        .filter(|s| s.is_finite())
+        .collect();
+    let around = neighbours.iter().fold(0.0_f64, |m, s| m.max(s.abs()));
+    let step = steps[i];
+    let stands_out = step.abs() > JUMP_FACTOR * around;
+    let reverses = !neighbours.is_empty()
+        && neighbours
+            .iter()
+            .all(|s| *s != 0.0 && s.signum() != step.signum());
+    (stands_out || reverses) && step.abs() > f64::EPSILON * (1.0 + around)
+}
+
+/// Where `f` jumps between `a` and `b`, if it does: bisect towards the half
+/// with the larger difference and see whether the difference survives.
+fn locate_jump(f: &CompiledExpr, a: (f64, f64), b: (f64, f64)) -> Option<f64> {
+    let initial = (b.1 - a.1).abs();
+    let (mut a, mut b) = (a, b);
+    for _ in 0..BISECTIONS {
+        let x = 0.5 * (a.0 + b.0);
+        if x <= a.0 || x >= b.0 {
+            break;
+        }
+        let mid = (x, f.eval(&[x]));
+        if !mid.1.is_finite() {
+            return Some(x);
+        }
+        if (mid.1 - a.1).abs() >= (b.1 - mid.1).abs() {
+            b = mid;
+        } else {
+            a = mid;
+        }
+    }
+    ((b.1 - a.1).abs() > JUMP_RATIO * initial).then_some(0.5 * (a.0 + b.0))
+}
@@ -0,0 +1,254 @@
+//! Expectation and variance over finite discrete distributions.
+//!
+//! A [`DiscreteDistribution`] is either a list of `(value, probability)`
+//! outcomes, written in LaTeX as
+//!
+//! ```text
+//! (0, \frac{1}{4}), (1, \frac{1}{2}), (2, \frac{1}{4})
+//! ```
+//!
+//! or a probability mass function over an integer range, such as
+//! `\frac{1}{n}` for `k = 1, …, n`. Either may have symbolic values and
+//! probabilities (`(1, p), (0, 1 - p)`). `E[f(X)]` is the sum of
+//! `p · f(x)` over the outcomes: a finite sum for a list, and a
+//! [`Node::Summation`] for a mass function, closed by the summation
+//! engine where it can, so `E[X] = \frac{n + 1}{2}` for the uniform
+//! distribution on `1, …, n`.
+
+use crate::environment::Environment;
+use crate::exact::ExactNum;
+use crate::node::Node;
+use crate::parser::parse_latex;
+use crate::simplify::Simplifiable;
+use crate::substitute::substitute_variable;
+
+/// The distribution of a discrete random variable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiscreteDistribution {
+    /// Each `(value, probability)` pair; values need not be distinct
+    Outcomes(Vec<(Node, Node)>),
+    /// `P(X = index) = pmf` for each integer `index` from `start` to `end`
+    Mass {
+        index: String,
+        start: Node,
+        end: Node,
+        pmf: Node,
+    },
+}
+
+impl DiscreteDistribution {
+    /// A distribution over listed outcomes. Probabilities that simplify to
+    /// numbers must not be negative, and must add up to 1 when they all do.
+    pub fn from_outcomes(outcomes: Vec<(Node, Node)>, env: &Environment) -> Result<Self, String> {
+        if outcomes.is_empty() {
+            return Err("A distribution needs at least one outcome".to_string());
+        }
+        for (value, probability) in &outcomes {
+            if let Node::Num(p) = probability.simplify(env)? {
+                if p.is_negative() {
+                    return Err(format!(
+                        "The probability of {} is {}, which is negative",
+                        value, p
+                    ));
+                }
+            }
+        }
+        let distribution = DiscreteDistribution::Outcomes(outcomes);
+        distribution.check_total(env)?;
+        Ok(distribution)
+    }
+
+    /// Equally likely outcomes.
+    pub fn uniform(values: Vec<Node>, env: &Environment) -> Result<Self, String> {
+        let probability = Node::Divide(
+            Box::new(Node::Num(ExactNum::one())),
+            Box::new(Node::Num(ExactNum::integer(values.len() as i64))),
+        );
+        Self::from_outcomes(
+            values
+                .into_iter()
+                .map(|value| (value, probability.clone()))
+                .collect(),
+            env,
+        )
+    }
+
+    /// `P(X = index) = pmf` for `index` from `start` to `end`. The mass
+    /// must add up to 1 when its sum simplifies to a number.
+    pub fn from_pmf(
+        index: &str,
+        start: Node,
+        end: Node,
+        pmf: Node,
+        env: &Environment,
+    ) -> Result<Self, String> {
+        let distribution = DiscreteDistribution::Mass {
+            index: index.to_string(),
+            start,
+            end,
+            pmf,
+        };
+        distribution.check_total(env)?;
+        Ok(distribution)
+    }
+
+    /// `E[f(X)]`, with `f` an expression in `var`.
+    pub fn expectation(&self, f: &Node, var: &str, env: &Environment) -> Result<Node, String> {
+        match self {
+            DiscreteDistribution::Outcomes(outcomes) => {
+                let mut terms = Vec::with_capacity(outcomes.len());
+                for (value, probability) in outcomes {
+                    terms.push(Node::Multiply(
+                        Box::new(probability.clone()),
+                        Box::new(substitute_variable(f, var, value)?),
+                    ));
+                }
+                terms
+                    .into_iter()
+                    .reduce(|sum, term| Node::Add(Box::new(sum), Box::new(term)))
+                    .expect("at least one outcome")
+                    .simplify(env)
+            }
+            DiscreteDistribution::Mass {
+                index,
+                start,
+                end,
+                pmf,
+            } => {
+                let at_index = substitute_variable(f, var, &Node::Variable(index.clone()))?;
+                Node::Summation(
+                    index.clone(),
+                    Box::new(start.clone()),
+                    Box::new(end.clone()),
+                    Box::new(Node::Multiply(Box::new(pmf.clone()), Box::new(at_index))),
+                )
+                .simplify(env)
+            }
+        }
+    }
+
+    /// `E[X]`
+    pub fn mean(&self, env: &Environment) -> Result<Node, String> {
+        const X: &str = "X";
+        self.expectation(&Node::Variable(X.to_string()), X, env)
+    }
+
+    /// `Var(f(X)) = E[f(X)²] - E[f(X)]²`
+    pub fn variance(&self, f: &Node, var: &str, env: &Environment) -> Result<Node, String> {
+        let square =
+            |node: Node| Node::Power(Box::new(node), Box::new(Node::Num(ExactNum::integer(2))));
+        let mean_of_square = self.expectation(&square(f.clone()), var, env)?;
+        let mean = self.expectation(f, var, env)?;
+        Node::Subtract(Box::new(mean_of_square), Box::new(square(mean))).simplify(env)
+    }
+
+    /// Fail when the probabilities add up to a number other than 1.
+    fn check_total(&self, env: &Environment) -> Result<(), String> {
+        let total = self.expectation(&Node::Num(ExactNum::one()), "", env)?;
+        match total {
+            Node::Num(ref n) if !n.is_one() => {
+                Err(format!("The probabilities add up to {}, not 1", total))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A list of outcomes `(x_1, p_1), (x_2, p_2), …`, optionally inside
+/// `\{ … \}`.
+pub fn parse_latex_distribution(
+    latex: &str,
+    env: &Environment,
+) -> Result<DiscreteDistribution, String> {
+    let text = latex
+        .replace("\\left(", "(")
+        .replace("\\right)", ")")
+        .replace("\\left\\{", "\\{")
+        .replace("\\right\\}", "\\}");
+    let mut text = text.trim();
+    if let Some(inner) = text.strip_prefix("\\{").and_then(|t| t.strip_suffix("\\}")) {
+        text = inner.trim();
+    }
+
+    let mut outcomes = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let Some(inner) = rest.strip_prefix('(') else {
+            return Err(format!(
+                "Expected an outcome (value, probability) at '{}'",
+                rest
+            ));
+        };
+        let close =
+            matching_paren(inner).ok_or_else(|| format!("Unclosed outcome in '{}'", latex))?;
+        let pair = &inner[..close];
+        let (value, probability) = split_top_level_comma(pair)
+            .ok_or_else(|| format!("An outcome is a pair (value, probability), got ({})", pair))?;
+        let parse = |s: &str| parse_latex(s, env).map_err(|e| e.to_string());
+        outcomes.push((parse(value)?, parse(probability)?));
+
+        rest = inner[close + 1..].trim_start();
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after.trim_start();
+        } else if !rest.is_empty() {
+            return Err(format!("Expected ',' between outcomes at '{}'", rest));
+        }
+    }
+    DiscreteDistribution::from_outcomes(outcomes, env)
+}
+
+/// `E[f(X)]` for LaTeX `f` in `var` and a LaTeX list of outcomes.
+pub fn expectation_latex(
+    f_latex: &str,
+    var: &str,
+    distribution_latex: &str,
+) -> Result<String, String> {
+    let env = Environment::new();
+    let f = parse_latex(f_latex, &env).map_err(|e| e.to_string())?;
+    let distribution = parse_latex_distribution(distribution_latex, &env)?;
+    Ok(distribution.expectation(&f, var, &env)?.to_string())
+}
+
+/// `Var(f(X))` for LaTeX `f` in `var` and a LaTeX list of outcomes.
+pub fn variance_latex(
+    f_latex: &str,
+    var: &str,
+    distribution_latex: &str,
+) -> Result<String, String> {
+    let env = Environment::new();
+    let f = parse_latex(f_latex, &env).map_err(|e| e.to_string())?;
+    let distribution = parse_latex_distribution(distribution_latex, &env)?;
+    Ok(distribution.variance(&f, var, &env)?.to_string())
+}
+
+/// Byte index of the `)` closing a group whose `(` has been stripped.
+fn matching_paren(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' | '{' | '[' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' | '}' | ']' => depth = depth.checked_sub(1)?,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `s` split at its only comma outside any brackets.
+fn split_top_level_comma(s: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    let mut split = None;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => match split {
+                None => split = Some(i),
+                Some(_) => return None,
+            },
+            _ => {}
+        }
+    }
+    split.map(|i| (s[..i].trim(), s[i + 1..].trim()))
+}
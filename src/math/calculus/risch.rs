@@ -1449,41 +1449,29 @@ fn integrate_poly_log(
     _ext: &DifferentialExtension,
     var: &str,
 ) -> Option<RischResult> {
+    // Seek Σ q_k θ^k with q_k' + (k+1)·q_{k+1}/x = a_k, top degree down.
+    // Each q_k is fixed only up to a constant until the level below it is
+    // integrated: a ln(x) = θ term in ∫ rhs_k is absorbed by adding
+    // b/(k+1) to q_{k+1}, which cancels exactly that term. The top level
+    // may therefore produce a constant θ^{n+1} coefficient (∫θ/x = θ²/2).
     let deg = num.degree().unwrap_or(0);
-    let mut q: Vec<RationalFunction> = vec![RationalFunction::zero(var); deg + 1];
-    let mut ln_x_accum = BigRational::zero();
+    let mut q: Vec<RationalFunction> = vec![RationalFunction::zero(var); deg + 2];
     let x_rf = RationalFunction::from_poly(Polynomial::x(var));
 
     for k in (0..=deg).rev() {
         let a_k_rf = num.coeff(k);
-
-        let rhs = if k == deg {
-            // Top degree: RHS = a_n directly
-            a_k_rf
-        } else if k == 0 {
-            // Degree 0: use (q[1] + Δ) in the correction instead of q[1]
-            let delta_rf = RationalFunction::from_constant(ln_x_accum.clone(), var);
-            let q1_adjusted = &q[1] + &delta_rf;
-            let q1_div_x = q1_adjusted.checked_div(&x_rf).ok()?;
-            let scalar_rf =
-                RationalFunction::from_constant(BigRational::from_integer(BigInt::from(1i64)), var);
-            let correction = &q1_div_x * &scalar_rf;
-            &a_k_rf - &correction
-        } else {
-            // Intermediate degrees: RHS = a_k - (k+1)·q_{k+1}/x
-            let q_kp1_div_x = q[k + 1].checked_div(&x_rf).ok()?;
-            let scalar = BigRational::from_integer(BigInt::from(k as i64 + 1));
-            let scalar_rf = RationalFunction::from_constant(scalar, var);
-            let correction = &q_kp1_div_x * &scalar_rf;
-            &a_k_rf - &correction
-        };
+        let q_kp1_div_x = q[k + 1].checked_div(&x_rf).ok()?;
+        let scalar = BigRational::from_integer(BigInt::from(k as i64 + 1));
+        let correction = &q_kp1_div_x * &RationalFunction::from_constant(scalar.clone(), var);
+        let rhs = &a_k_rf - &correction;
 
         match integrate_rational_base(&rhs, var) {
             Ok(result) => {
                 q[k] = result.rational_part;
-                // ln(x) = θ, so any ln(x) produced by integration is absorbed
-                // into the θ¹ coefficient via the Δ accumulator.
-                ln_x_accum += result.ln_x_coeff;
+                if !result.ln_x_coeff.is_zero() {
+                    let shift = RationalFunction::from_constant(result.ln_x_coeff / scalar, var);
+                    q[k + 1] = &q[k + 1] + &shift;
+                }
             }
             Err(msg) => {
                 // integrate_rational_base returns Err for ln(x+a) with a≠0
@@ -1493,12 +1481,6 @@ fn integrate_poly_log(
         }
     }
 
-    // Adjust q[1] by adding Δ
-    if deg >= 1 && !ln_x_accum.is_zero() {
-        let delta_rf = RationalFunction::from_constant(ln_x_accum, var);
-        q[1] = &q[1] + &delta_rf;
-    }
-
     let ln_x = Node::Function("ln".to_string(), vec![Node::Variable(var.to_string())]);
     let mut terms: Vec<Node> = Vec::new();
     for (k, qk) in q.iter().enumerate() {
@@ -99,6 +99,8 @@ fn node_count(node: &Node) -> usize {
         | Node::Less(l, r)
         | Node::GreaterEqual(l, r)
         | Node::LessEqual(l, r)
+        | Node::And(l, r)
+        | Node::Or(l, r)
         | Node::Equal(l, r)
         | Node::Equation(l, r) => node_count(l) + node_count(r),
         Node::Sqrt(inner)
@@ -108,14 +110,26 @@ fn node_count(node: &Node) -> usize {
         | Node::Round(inner)
         | Node::Trunc(inner)
         | Node::Negate(inner)
+        | Node::Not(inner)
         | Node::Factorial(inner) => node_count(inner),
         Node::Piecewise(arms) => arms
             .iter()
             .map(|(e, c)| node_count(e) + node_count(c))
             .sum(),
+        Node::Matrix(rows) => rows.iter().flatten().map(node_count).sum(),
         Node::Summation(_, a, b, c) | Node::Product(_, a, b, c) => {
             node_count(a) + node_count(b) + node_count(c)
         }
+        Node::Integral(_, bounds, body) => {
+            bounds
+                .as_ref()
+                .map_or(0, |(lo, hi)| node_count(lo) + node_count(hi))
+                + node_count(body)
+        }
+        Node::Derivative(_, body) => node_count(body),
+        Node::Limit(_, point, _, body) | Node::SumOver(_, point, body) => {
+            node_count(point) + node_count(body)
+        }
         Node::Function(_, args) => args.iter().map(node_count).sum(),
         Node::Num(_) | Node::Variable(_) => 0,
     }
@@ -297,9 +297,10 @@ fn roundtrip_holds(integrand: &Node, form: &Node, var: &str) -> bool {
         Err(_) => return false,
     };
     // Simplify before evaluating: the product rule leaves terms like
-    // (d/dx √π)·erf(x) — an exact zero multiplied by a function that
-    // (deliberately) refuses numeric evaluation. Folding the zeros away
-    // leaves the derivative erf/Ei/li-free and hence evaluable.
+    // (d/dx √π)·erf(x) — an exact zero multiplied by a special function,
+    // and Ei and li (deliberately) refuse numeric evaluation. Folding the
+    // zeros away leaves the derivative special-function-free and hence
+    // evaluable.
     let env = Environment::new();
     let derivative = derivative.simplify(&env).unwrap_or(derivative);
     let mut checked = 0usize;
@@ -0,0 +1,307 @@
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{Signed, Zero};
+
+use crate::compile::CompiledExpr;
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::exact::ExactNum;
+use crate::function_meta::canonical_function_name;
+use crate::matrix::is_zero_node;
+use crate::node::Node;
+use crate::polynomial::Polynomial;
+
+/// Symmetry of `f` under `x → −x`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parity {
+    /// `f(−x) = f(x)`
+    Even,
+    /// `f(−x) = −f(x)`
+    Odd,
+    /// Neither identity holds
+    Neither,
+}
+
+/// Sample points for numeric probing: irregular so that coincidental
+/// agreement (e.g. at multiples of π) is unlikely.
+const PROBE_POINTS: [f64; 10] = [
+    0.137, 0.421, 0.733, 1.049, 1.618, 2.236, 2.791, 3.301, 4.567, 6.02,
+];
+const PROBE_TOLERANCE: f64 = 1e-9;
+/// Most times longer than one of its parts a combined period may be, and
+/// the largest divisor tried when shortening it.
+const PERIOD_RATIO_LIMIT: i64 = 12;
+
+/// Whether `f(−x) = f(x)` for `f = expr` as a function of `var`.
+pub fn is_even_function(expr: &Node, var: &str) -> bool {
+    parity(expr, var) == Parity::Even || is_zero_node(expr)
+}
+
+/// Whether `f(−x) = −f(x)` for `f = expr` as a function of `var`.
+pub fn is_odd_function(expr: &Node, var: &str) -> bool {
+    parity(expr, var) == Parity::Odd || is_zero_node(expr)
+}
+
+/// Parity of `expr` in `var`. Structural rules decide sums, products,
+/// powers, and the odd/even elementary functions; anything they cannot
+/// settle is probed numerically at [`PROBE_POINTS`].
+pub fn parity(expr: &Node, var: &str) -> Parity {
+    symbolic_parity(expr, var).unwrap_or_else(|| numeric_parity(expr, var))
+}
+
+/// Functions with `f(−x) = −f(x)`.
+fn is_odd_name(name: &str) -> bool {
+    matches!(
+        canonical_function_name(name),
+        "sin"
+            | "tan"
+            | "csc"
+            | "cot"
+            | "arcsin"
+            | "arctan"
+            | "arccsc"
+            | "sinh"
+            | "tanh"
+            | "csch"
+            | "coth"
+            | "arcsinh"
+            | "arctanh"
+            | "arccsch"
+            | "erf"
+    )
+}
+
+/// Functions with `f(−x) = f(x)`.
+fn is_even_name(name: &str) -> bool {
+    matches!(
+        canonical_function_name(name),
+        "cos" | "sec" | "cosh" | "sech"
+    )
+}
+
+/// Parity of `expr` in `var` by the structural rules alone: `None` when
+/// they cannot decide. Callers that turn the answer into an exact result,
+/// such as `0` for an odd integrand over a symmetric interval, use this
+/// rather than [`parity`], whose numeric probe is only a heuristic.
+pub(crate) fn symbolic_parity(expr: &Node, var: &str) -> Option<Parity> {
+    use Parity::*;
+    if !expr.contains_variable(var) {
+        return Some(Even);
+    }
+    match expr {
+        Node::Variable(_) => Some(Odd),
+        Node::Negate(inner) => symbolic_parity(inner, var),
+        Node::Add(l, r) | Node::Subtract(l, r) => {
+            match (symbolic_parity(l, var)?, symbolic_parity(r, var)?) {
+                (Even, Even) => Some(Even),
+                (Odd, Odd) => Some(Odd),
+                // even + odd can still cancel to something symmetric
+                _ => None,
+            }
+        }
+        Node::Multiply(l, r) | Node::Divide(l, r) => {
+            match (symbolic_parity(l, var)?, symbolic_parity(r, var)?) {
+                (Even, Even) | (Odd, Odd) => Some(Even),
+                (Even, Odd) | (Odd, Even) => Some(Odd),
+                _ => None,
+            }
+        }
+        Node::Power(base, exp) if !exp.contains_variable(var) => {
+            match (symbolic_parity(base, var)?, &**exp) {
+                (Even, _) => Some(Even),
+                (Odd, Node::Num(n)) if n.is_integer() => Some(if n.is_even() { Even } else { Odd }),
+                _ => None,
+            }
+        }
+        Node::Sqrt(inner) => match symbolic_parity(inner, var)? {
+            Even => Some(Even),
+            _ => None,
+        },
+        Node::Abs(inner) => match symbolic_parity(inner, var)? {
+            Even | Odd => Some(Even),
+            Neither => None,
+        },
+        Node::Function(name, args) if args.len() == 1 => match symbolic_parity(&args[0], var)? {
+            Even => Some(Even),
+            Odd if is_odd_name(name) => Some(Odd),
+            Odd if is_even_name(name) => Some(Even),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Compare `f(x)` with `f(−x)` at the probe points. Points where either
+/// side is undefined are skipped; at least three must be usable.
+fn numeric_parity(expr: &Node, var: &str) -> Parity {
+    let eval = |x: f64| {
+        let mut env = Environment::new();
+        env.set(var, x);
+        Evaluator::evaluate(expr, &env)
+            .ok()
+            .filter(|v| v.is_finite())
+    };
+    let close = |a: f64, b: f64| (a - b).abs() <= PROBE_TOLERANCE * (1.0 + a.abs().max(b.abs()));
+
+    let pairs: Vec<(f64, f64)> = PROBE_POINTS
+        .iter()
+        .filter_map(|&x| Some((eval(x)?, eval(-x)?)))
+        .collect();
+    if pairs.len() < 3 {
+        return Parity::Neither;
+    }
+    if pairs.iter().all(|&(f, g)| close(f, g)) {
+        Parity::Even
+    } else if pairs.iter().all(|&(f, g)| close(f, -g)) {
+        Parity::Odd
+    } else {
+        Parity::Neither
+    }
+}
+
+/// A period of `expr` in `var`, as a rational multiple of π: `2\pi` for
+/// `\sin(x) + \cos(x)`, `\pi` for `\tan(x)` and `\sin^2(x)`, `\frac{2}{3}\pi`
+/// for `\cos(3x)`. Built structurally from trig functions of linear
+/// arguments (the least common multiple of the parts), then shortened
+/// numerically to the fundamental period when a divisor also works.
+/// Expressions the rules do not recognise, such as `\sin(\sin(x))`, are
+/// probed at [`PROBE_POINTS`] for the shortest period `p/q·π` with
+/// `q ≤ 12` and `p/q ≤ 12`. `None` for non-periodic expressions or
+/// incommensurable periods, which include decimal frequencies and parts
+/// whose common period would be more than 12 times either one.
+pub fn detect_period(expr: &Node, var: &str) -> Option<Node> {
+    let structural = structural_period(expr, var);
+    if matches!(structural, Some(Period::Any | Period::Aperiodic)) {
+        return None;
+    }
+    let f = Evaluator::compile(expr, &[var]).ok()?;
+    let fundamental = match structural {
+        Some(Period::Pi(candidate)) => (2..=PERIOD_RATIO_LIMIT)
+            .rev()
+            .map(|k| &candidate / BigRational::from_integer(BigInt::from(k)))
+            .find(|t| repeats_with_period(&f, to_f64(t) * std::f64::consts::PI))
+            .unwrap_or(candidate),
+        _ => numeric_period(&f)?,
+    };
+    Some(pi_multiple(&fundamental))
+}
+
+#[derive(Clone, Debug)]
+enum Period {
+    /// Every shift is a period (the expression ignores `var`)
+    Any,
+    /// Periodic with period `c·π`
+    Pi(BigRational),
+    /// Known to have no period worth reporting
+    Aperiodic,
+}
+
+/// The period the structural rules find, or `None` when they cannot tell.
+fn structural_period(expr: &Node, var: &str) -> Option<Period> {
+    if !expr.contains_variable(var) {
+        return Some(Period::Any);
+    }
+    match expr {
+        Node::Add(l, r)
+        | Node::Subtract(l, r)
+        | Node::Multiply(l, r)
+        | Node::Divide(l, r)
+        | Node::Power(l, r) => combine(structural_period(l, var), structural_period(r, var)),
+        Node::Negate(inner) | Node::Sqrt(inner) | Node::Abs(inner) => structural_period(inner, var),
+        Node::Function(name, args) if args.len() == 1 => {
+            let base = match canonical_function_name(name) {
+                "sin" | "cos" | "sec" | "csc" => Some(BigRational::from_integer(BigInt::from(2))),
+                "tan" | "cot" => Some(BigRational::from_integer(BigInt::from(1))),
+                _ => None,
+            };
+            match base {
+                Some(base) => {
+                    // A decimal frequency is a rounded irrational as often
+                    // as not, so its period is no common period
+                    if has_float(&args[0]) {
+                        return Some(Period::Aperiodic);
+                    }
+                    let poly = Polynomial::from_node(&args[0], var).ok()?;
+                    if poly.degree() != Some(1) {
+                        return Some(Period::Aperiodic);
+                    }
+                    Some(Period::Pi(base / poly.coeff(1).abs()))
+                }
+                // Any other function of a periodic argument is periodic
+                None => structural_period(&args[0], var),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Least common period: lcm(a/b, c/d) = lcm(a, c) / gcd(b, d). Aperiodic
+/// when it is more than [`PERIOD_RATIO_LIMIT`] times either part, as for
+/// nearly incommensurable frequencies. Unknown when either part is.
+fn combine(a: Option<Period>, b: Option<Period>) -> Option<Period> {
+    match (a?, b?) {
+        (Period::Any, p) | (p, Period::Any) => Some(p),
+        (Period::Aperiodic, _) | (_, Period::Aperiodic) => Some(Period::Aperiodic),
+        (Period::Pi(p), Period::Pi(q)) => {
+            let numer = p.numer().lcm(q.numer());
+            let denom = p.denom().gcd(q.denom());
+            let lcm = BigRational::new(numer, denom);
+            let limit = BigRational::from_integer(BigInt::from(PERIOD_RATIO_LIMIT));
+            if &lcm / &p > limit || &lcm / &q > limit {
+                return Some(Period::Aperiodic);
+            }
+            Some(Period::Pi(lcm))
+        }
+    }
+}
+
+/// The shortest `c` with `f(x + cπ) = f(x)` at the probe points, trying
+/// `c = p/q` for `q` and `p/q` up to [`PERIOD_RATIO_LIMIT`].
+fn numeric_period(f: &CompiledExpr) -> Option<BigRational> {
+    let limit = PERIOD_RATIO_LIMIT;
+    let mut candidates: Vec<BigRational> = (1..=limit)
+        .flat_map(|q| (1..=limit * q).map(move |p| BigRational::new(p.into(), q.into())))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+        .into_iter()
+        .find(|c| repeats_with_period(f, to_f64(c) * std::f64::consts::PI))
+}
+
+fn has_float(node: &Node) -> bool {
+    matches!(node, Node::Num(ExactNum::Float(_))) || node.children().into_iter().any(has_float)
+}
+
+fn repeats_with_period(f: &CompiledExpr, period: f64) -> bool {
+    let eval = |x: f64| Some(f.eval(&[x])).filter(|v| v.is_finite());
+    let mut checked = 0;
+    for &x in &PROBE_POINTS {
+        if let (Some(a), Some(b)) = (eval(x), eval(x + period)) {
+            if (a - b).abs() > PROBE_TOLERANCE * (1.0 + a.abs().max(b.abs())) {
+                return false;
+            }
+            checked += 1;
+        }
+    }
+    checked >= 3
+}
+
+fn to_f64(r: &BigRational) -> f64 {
+    ExactNum::Rational(r.clone()).to_f64()
+}
+
+fn pi_multiple(c: &BigRational) -> Node {
+    let pi = Node::Variable("π".to_string());
+    if c == &BigRational::from_integer(BigInt::from(1)) {
+        pi
+    } else if c.is_zero() {
+        Node::Num(ExactNum::zero())
+    } else {
+        Node::Multiply(
+            Box::new(Node::Num(ExactNum::Rational(c.clone()))),
+            Box::new(pi),
+        )
+    }
+}
@@ -0,0 +1,133 @@
+//! The natural domain of an expression: the real values of a variable at
+//! which every part of it is defined.
+//!
+//! Each operation that restricts its argument contributes a condition, the
+//! condition is solved as an inequality, and the domain is the intersection
+//! of the results:
+//!
+//! - a denominator is nonzero, as is the base of a negative integer power;
+//! - the radicand of a square root, or of any even root `b^{p/q}`, is
+//!   nonnegative (positive when the exponent is negative);
+//! - a variable base under a variable exponent is positive;
+//! - the argument of `ln`, `log` and `lg` is positive;
+//! - the inverse trigonometric and hyperbolic functions keep to their real
+//!   ranges, e.g. `-1 \leq a \leq 1` for `\arcsin a` and `a \geq 1` for
+//!   `\operatorname{arccosh} a`.
+//!
+//! So `\frac{1}{x-2} + \sqrt{x}` has domain `x \geq 0, x \neq 2`.
+//! Functions whose domain excludes infinitely many points, such as `\tan`
+//! of a variable argument, are reported as an error rather than
+//! approximated.
+
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::exact::ExactNum;
+use crate::inequality::{solve_inequality_set, SolutionSet};
+use crate::node::Node;
+
+/// The set of values of `var` at which `expr` is defined over the reals.
+pub fn domain_of(expr: &Node, var: &str) -> Result<SolutionSet, String> {
+    let mut domain = SolutionSet::all();
+    restrict(expr, var, &mut domain)?;
+    Ok(domain)
+}
+
+fn restrict(node: &Node, var: &str, domain: &mut SolutionSet) -> Result<(), String> {
+    let children = node.children();
+    // A sum, product, integral or limit over `var` binds it in the body;
+    // only the bounds constrain the free `var`.
+    let searched = match node {
+        Node::Summation(index, ..)
+        | Node::Product(index, ..)
+        | Node::SumOver(index, ..)
+        | Node::Integral(index, ..)
+        | Node::Limit(index, ..)
+        | Node::Derivative(index, _)
+            if index == var =>
+        {
+            &children[..children.len() - 1]
+        }
+        _ => &children[..],
+    };
+    for child in searched {
+        restrict(child, var, domain)?;
+    }
+
+    let condition = match node {
+        Node::Divide(_, den) => nonzero(den, var)?,
+        Node::Sqrt(arg) => holds(Node::GreaterEqual(arg.clone(), zero()), var)?,
+        Node::Power(base, exp) => power_condition(base, exp, var)?,
+        Node::Function(name, args) if args.len() == 1 => function_condition(name, &args[0], var)?,
+        _ => return Ok(()),
+    };
+    *domain = domain.intersect(&condition);
+    Ok(())
+}
+
+fn power_condition(base: &Node, exp: &Node, var: &str) -> Result<SolutionSet, String> {
+    if exp.contains_variable(var) {
+        if base.contains_variable(var) {
+            return holds(Node::Greater(Box::new(base.clone()), zero()), var);
+        }
+        return Ok(SolutionSet::all());
+    }
+    let exponent = Evaluator::evaluate_exact(exp, &Environment::new())
+        .map_err(|e| format!("Cannot find the domain: exponent {exp} is not a number ({e})"))?;
+    let negative = exponent.is_negative();
+    let even_root = match exponent.to_rational() {
+        Some(r) => r.denom() % 2u32 == 0u32.into(),
+        None => !exponent.is_integer(),
+    };
+    let base = Box::new(base.clone());
+    if even_root && negative {
+        holds(Node::Greater(base, zero()), var)
+    } else if even_root {
+        holds(Node::GreaterEqual(base, zero()), var)
+    } else if negative {
+        nonzero(&base, var)
+    } else {
+        Ok(SolutionSet::all())
+    }
+}
+
+fn function_condition(name: &str, arg: &Node, var: &str) -> Result<SolutionSet, String> {
+    let a = || Box::new(arg.clone());
+    let num = |n: i64| Box::new(Node::Num(ExactNum::integer(n)));
+    match name {
+        "sqrt" => holds(Node::GreaterEqual(a(), zero()), var),
+        "ln" | "log" | "lg" => holds(Node::Greater(a(), zero()), var),
+        "arcsin" | "asin" | "arccos" | "acos" => Ok(holds(Node::GreaterEqual(a(), num(-1)), var)?
+            .intersect(&holds(Node::LessEqual(a(), num(1)), var)?)),
+        "arccosh" | "acosh" => holds(Node::GreaterEqual(a(), num(1)), var),
+        "arctanh" | "atanh" => Ok(holds(Node::Greater(a(), num(-1)), var)?
+            .intersect(&holds(Node::Less(a(), num(1)), var)?)),
+        "arcsec" | "arccsc" => Ok(holds(Node::LessEqual(a(), num(-1)), var)?
+            .union(&holds(Node::GreaterEqual(a(), num(1)), var)?)),
+        "arccoth" => {
+            Ok(holds(Node::Less(a(), num(-1)), var)?
+                .union(&holds(Node::Greater(a(), num(1)), var)?))
+        }
+        "arcsech" => Ok(holds(Node::Greater(a(), zero()), var)?
+            .intersect(&holds(Node::LessEqual(a(), num(1)), var)?)),
+        "arccsch" | "coth" | "csch" => nonzero(arg, var),
+        "tan" | "sec" | "csc" | "cot" | "Gamma" | "lgamma" if arg.contains_variable(var) => Err(
+            format!("Cannot find the domain: \\{name} is undefined at infinitely many points"),
+        ),
+        _ => Ok(SolutionSet::all()),
+    }
+}
+
+/// Where `expr ≠ 0`, as `expr < 0` joined with `expr > 0`.
+fn nonzero(expr: &Node, var: &str) -> Result<SolutionSet, String> {
+    let e = || Box::new(expr.clone());
+    Ok(holds(Node::Less(e(), zero()), var)?.union(&holds(Node::Greater(e(), zero()), var)?))
+}
+
+fn holds(condition: Node, var: &str) -> Result<SolutionSet, String> {
+    solve_inequality_set(&condition, var)
+        .map_err(|e| format!("Cannot find the domain: {condition} ({e})"))
+}
+
+fn zero() -> Box<Node> {
+    Box::new(Node::Num(ExactNum::zero()))
+}
@@ -17,7 +17,12 @@ pub fn extract_variable(expr: &str) -> Option<String> {
 #[derive(Debug)]
 pub struct SolveResult {
     pub solutions: Vec<Node>,
+    /// Number of non-real roots, counted with multiplicity.
     pub complex_omitted: usize,
+    /// The distinct non-real roots as `a + b i` with `i` as a symbol:
+    /// exact for quadratic factors, f64 for cubic and quartic ones. Empty
+    /// when some irreducible factor has degree > 4.
+    pub complex_solutions: Vec<Node>,
     /// When the solver cannot express all roots, this carries the reason.
     /// Distinguishes "roots are complex" from "roots may exist but have no
     /// closed-form radical expression" (Abel-Ruffini).
@@ -69,11 +74,25 @@ pub fn solve_full(expr: &Node, target_var: &str) -> Result<SolveResult, String>
             Err(e) if e == "No real solutions" => (Vec::new(), None),
             Err(e) => return Err(e),
         };
-        let complex_omitted = degree.saturating_sub(solutions.len());
+        // Count non-real roots from the factorization so a repeated real
+        // root (x − 1)² is not mistaken for a missing complex one.
+        let (complex_omitted, complex_solutions) = match complex_roots(&poly) {
+            Some(roots) => {
+                let count = roots.len();
+                let mut seen = std::collections::HashSet::new();
+                let distinct = roots
+                    .into_iter()
+                    .filter(|r| seen.insert(format!("{}", r)))
+                    .collect();
+                (count, distinct)
+            }
+            None => (degree.saturating_sub(solutions.len()), Vec::new()),
+        };
 
         Ok(SolveResult {
             solutions,
             complex_omitted,
+            complex_solutions,
             impossibility_reason,
         })
     } else {
@@ -81,11 +100,102 @@ pub fn solve_full(expr: &Node, target_var: &str) -> Result<SolveResult, String>
         Ok(SolveResult {
             solutions,
             complex_omitted: 0,
+            complex_solutions: Vec::new(),
             impossibility_reason: None,
         })
     }
 }
 
+/// Every non-real root of `poly`, with multiplicity. Quadratic factors
+/// give exact `p ± q i`; cubic and quartic factors are solved numerically.
+/// `None` when an irreducible factor has degree > 4.
+fn complex_roots(poly: &Polynomial) -> Option<Vec<Node>> {
+    let (_, factors) = crate::mod_poly::factor_over_q(poly);
+    let mut roots = Vec::new();
+    for factor in &factors {
+        match factor.degree() {
+            None | Some(0) | Some(1) => {}
+            Some(2) => roots.extend(complex_quadratic_roots(factor)),
+            Some(3) | Some(4) => {
+                let coeffs: Vec<f64> = (0..=factor.degree().unwrap_or(0))
+                    .map(|k| rational_to_f64(&factor.coeff(k)))
+                    .collect();
+                let mut numeric: Vec<(f64, f64)> = crate::polynomial::durand_kerner(&coeffs)
+                    .into_iter()
+                    .filter(|&(_, im)| im.abs() > 1e-9)
+                    .map(|(re, im)| (snap(re), snap(im)))
+                    .collect();
+                // Conjugate pairs together, `+` before `−`
+                numeric.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.total_cmp(&a.1)));
+                roots.extend(numeric.into_iter().map(|(re, im)| {
+                    complex_node(
+                        Node::Num(ExactNum::from_f64(re)),
+                        Node::Num(ExactNum::from_f64(im.abs())),
+                        im < 0.0,
+                    )
+                }));
+            }
+            _ => return None,
+        }
+    }
+    Some(roots)
+}
+
+/// Both roots of a quadratic with negative discriminant, `re ± im·i`.
+fn complex_quadratic_roots(poly: &Polynomial) -> Vec<Node> {
+    let a = poly.coeff(2);
+    let b = poly.coeff(1);
+    let c = poly.coeff(0);
+    let discriminant = &b * &b - BigRational::from_integer(BigInt::from(4)) * &a * &c;
+    if !discriminant.is_negative() {
+        return Vec::new();
+    }
+
+    let two_a = BigRational::from_integer(BigInt::from(2)) * &a;
+    let re = rational_to_node(&(-&b / &two_a));
+    let im = match exact_rational_sqrt(&-&discriminant) {
+        Some(root) => rational_to_node(&(root / two_a.abs())),
+        None => {
+            let env = crate::environment::Environment::new();
+            let im = Node::Divide(
+                Box::new(Node::Sqrt(Box::new(rational_to_node(&-&discriminant)))),
+                Box::new(rational_to_node(&two_a.abs())),
+            );
+            crate::simplify::Simplifiable::simplify(&im, &env).unwrap_or(im)
+        }
+    };
+    vec![
+        complex_node(re.clone(), im.clone(), false),
+        complex_node(re, im, true),
+    ]
+}
+
+/// `re + im·i` (or `re − im·i`), dropping a zero real part and a unit
+/// imaginary coefficient. Left unsimplified so the real part stays first.
+fn complex_node(re: Node, im: Node, negative: bool) -> Node {
+    let i_sym = Node::Variable("i".to_string());
+    let im_part = match &im {
+        Node::Num(n) if n.is_one() => i_sym,
+        _ => Node::Multiply(Box::new(im), Box::new(i_sym)),
+    };
+    match (is_effectively_zero(&re), negative) {
+        (true, false) => im_part,
+        (true, true) => Node::Negate(Box::new(im_part)),
+        (false, false) => Node::Add(Box::new(re), Box::new(im_part)),
+        (false, true) => Node::Subtract(Box::new(re), Box::new(im_part)),
+    }
+}
+
+/// Round away f64 noise so `-0.99999999999998` prints as `-1`.
+fn snap(v: f64) -> f64 {
+    let rounded = (v * 1e10).round() / 1e10;
+    if rounded == 0.0 {
+        0.0
+    } else {
+        rounded
+    }
+}
+
 pub fn solve_for_variable(expr: &Node, target_var: &str) -> Result<f64, String> {
     let solutions = solve_polynomial(expr, target_var)?;
     if solutions.is_empty() {
@@ -119,10 +229,12 @@ fn solve_polynomial(expr: &Node, target_var: &str) -> Result<Vec<ExactNum>, Stri
             if let Some(cleared) = try_clear_denominators(&simplified, target_var) {
                 let cleared_simplified =
                     crate::simplify::Simplifiable::simplify(&cleared, &env).unwrap_or(cleared);
-                Polynomial::from_node(&cleared_simplified, target_var)
-                    .map_err(|e| format!("Cannot convert to polynomial: {}", e))?
+                match Polynomial::from_node(&cleared_simplified, target_var) {
+                    Ok(p) => p,
+                    Err(e) => return inverted_exact_or(expr, target_var, e),
+                }
             } else {
-                return Err(format!("Cannot convert to polynomial: {}", orig_err));
+                return inverted_exact_or(expr, target_var, orig_err);
             }
         }
     };
@@ -238,14 +350,14 @@ fn solve_polynomial_nodes(expr: &Node, target_var: &str) -> Result<Vec<Node>, St
                         if let Some(roots) = try_solve_parametric(&cleared_simplified, target_var) {
                             return Ok(roots);
                         }
-                        return Err(format!("Cannot convert to polynomial: {}", orig_err));
+                        return inverted_or(expr, target_var, orig_err);
                     }
                 }
             } else {
                 if let Some(roots) = try_solve_parametric(&simplified, target_var) {
                     return Ok(roots);
                 }
-                return Err(format!("Cannot convert to polynomial: {}", orig_err));
+                return inverted_or(expr, target_var, orig_err);
             }
         }
     };
@@ -782,6 +894,7 @@ fn contains_var(node: &Node, var: &str) -> bool {
         | Node::Divide(a, b)
         | Node::Power(a, b) => contains_var(a, var) || contains_var(b, var),
         Node::Negate(inner)
+        | Node::Not(inner)
         | Node::Sqrt(inner)
         | Node::Abs(inner)
         | Node::Floor(inner)
@@ -795,6 +908,8 @@ fn contains_var(node: &Node, var: &str) -> bool {
         | Node::Less(a, b)
         | Node::GreaterEqual(a, b)
         | Node::LessEqual(a, b)
+        | Node::And(a, b)
+        | Node::Or(a, b)
         | Node::Equal(a, b) => contains_var(a, var) || contains_var(b, var),
         Node::Summation(_, start, end, body) => {
             contains_var(start, var) || contains_var(end, var) || contains_var(body, var)
@@ -802,9 +917,20 @@ fn contains_var(node: &Node, var: &str) -> bool {
         Node::Product(_, start, end, body) => {
             contains_var(start, var) || contains_var(end, var) || contains_var(body, var)
         }
+        Node::Integral(_, bounds, body) => {
+            bounds
+                .as_ref()
+                .is_some_and(|(lo, hi)| contains_var(lo, var) || contains_var(hi, var))
+                || contains_var(body, var)
+        }
+        Node::Derivative(_, body) => contains_var(body, var),
+        Node::Limit(_, point, _, body) | Node::SumOver(_, point, body) => {
+            contains_var(point, var) || contains_var(body, var)
+        }
         Node::Piecewise(cases) => cases
             .iter()
             .any(|(val, cond)| contains_var(val, var) || contains_var(cond, var)),
+        Node::Matrix(rows) => rows.iter().flatten().any(|e| contains_var(e, var)),
     }
 }
 
@@ -856,3 +982,340 @@ fn try_clear_denominators(expr: &Node, var: &str) -> Option<Node> {
         None
     }
 }
+
+/// Fallback once polynomial methods fail: the roots from
+/// [`solve_by_inversion`], or the polynomial conversion error.
+fn inverted_or(expr: &Node, var: &str, poly_err: String) -> Result<Vec<Node>, String> {
+    match solve_by_inversion(expr, var, false) {
+        Some(roots) if roots.is_empty() => Err("No real solutions".to_string()),
+        Some(roots) => Ok(roots),
+        None => Err(format!("Cannot convert to polynomial: {}", poly_err)),
+    }
+}
+
+/// [`inverted_or`] with each root evaluated; a root such as `e^{2}` with
+/// no rational value becomes a float.
+fn inverted_exact_or(expr: &Node, var: &str, poly_err: String) -> Result<Vec<ExactNum>, String> {
+    let env = crate::environment::Environment::new();
+    inverted_or(expr, var, poly_err)?
+        .iter()
+        .map(|root| match root {
+            Node::Num(n) => Ok(n.clone()),
+            other => crate::evaluator::Evaluator::evaluate(other, &env)
+                .map(ExactNum::Float)
+                .map_err(String::from),
+        })
+        .collect()
+}
+
+/// How [`solve_by_inversion`] undoes one function applied to the unknown.
+#[derive(Clone, Copy)]
+enum Undo {
+    /// Apply the named inverse function
+    Apply(&'static str),
+    /// `e^{c}`
+    Exp,
+    /// `base^{c}`
+    Pow(i64),
+}
+
+/// Which preimages of `c` an inverted function has besides the principal one.
+#[derive(Clone, Copy)]
+enum Branches {
+    /// Injective: the principal value is the only solution
+    One,
+    /// Even: `±` the principal value (`\cosh`)
+    PlusMinus,
+    /// `\arcsin c + 2πn` and `π − \arcsin c + 2πn`
+    Sine,
+    /// `±\arccos c + 2πn`
+    Cosine,
+    /// `\arctan c + πn`
+    Tangent,
+}
+
+struct Inversion {
+    name: &'static str,
+    undo: Undo,
+    branches: Branches,
+    /// Values the function takes; a right-hand side outside has no preimage
+    range: fn(f64) -> bool,
+}
+
+const ANY: fn(f64) -> bool = |c| !c.is_nan();
+
+const INVERSIONS: [Inversion; 13] = [
+    Inversion {
+        name: "sin",
+        undo: Undo::Apply("arcsin"),
+        branches: Branches::Sine,
+        range: |c| c.abs() <= 1.0,
+    },
+    Inversion {
+        name: "cos",
+        undo: Undo::Apply("arccos"),
+        branches: Branches::Cosine,
+        range: |c| c.abs() <= 1.0,
+    },
+    Inversion {
+        name: "tan",
+        undo: Undo::Apply("arctan"),
+        branches: Branches::Tangent,
+        range: ANY,
+    },
+    Inversion {
+        name: "arcsin",
+        undo: Undo::Apply("sin"),
+        branches: Branches::One,
+        range: |c| c.abs() <= std::f64::consts::FRAC_PI_2,
+    },
+    Inversion {
+        name: "arccos",
+        undo: Undo::Apply("cos"),
+        branches: Branches::One,
+        range: |c| (0.0..=std::f64::consts::PI).contains(&c),
+    },
+    Inversion {
+        name: "arctan",
+        undo: Undo::Apply("tan"),
+        branches: Branches::One,
+        range: |c| c.abs() < std::f64::consts::FRAC_PI_2,
+    },
+    Inversion {
+        name: "sinh",
+        undo: Undo::Apply("arcsinh"),
+        branches: Branches::One,
+        range: ANY,
+    },
+    Inversion {
+        name: "cosh",
+        undo: Undo::Apply("arccosh"),
+        branches: Branches::PlusMinus,
+        range: |c| c >= 1.0,
+    },
+    Inversion {
+        name: "tanh",
+        undo: Undo::Apply("arctanh"),
+        branches: Branches::One,
+        range: |c| c.abs() < 1.0,
+    },
+    Inversion {
+        name: "exp",
+        undo: Undo::Apply("ln"),
+        branches: Branches::One,
+        range: |c| c > 0.0,
+    },
+    Inversion {
+        name: "ln",
+        undo: Undo::Exp,
+        branches: Branches::One,
+        range: ANY,
+    },
+    Inversion {
+        name: "log",
+        undo: Undo::Pow(10),
+        branches: Branches::One,
+        range: ANY,
+    },
+    Inversion {
+        name: "lg",
+        undo: Undo::Pow(2),
+        branches: Branches::One,
+        range: ANY,
+    },
+];
+
+/// Like [`solve_for_variable_nodes`], but an equation solved by inverting a
+/// periodic function returns every solution family rather than the
+/// principal value: `\sin(x) = 1` gives `π/2 + 2πn`, with `n` (or the
+/// first of `k`, `m` not already in the equation) ranging over ℤ.
+pub fn solve_for_variable_general(expr: &Node, target_var: &str) -> Result<Vec<Node>, String> {
+    match solve_by_inversion(expr, target_var, true) {
+        Some(roots) if roots.is_empty() => Err("No real solutions".to_string()),
+        Some(roots) => Ok(roots),
+        None => solve_polynomial_nodes(expr, target_var),
+    }
+}
+
+/// Isolate `var` in an equation where it occurs once, by undoing the
+/// operations around it from the outside in: `\ln(x) = 2` → `x = e^{2}`,
+/// `2^{x} = 8` → `x = \ln 8 / \ln 2 = 3`, `\sin(x) = 1` → `x = π/2`.
+/// `None` when the unknown cannot be isolated this way; an empty list when
+/// it can but every right-hand side falls outside the function's range.
+fn solve_by_inversion(expr: &Node, var: &str, general: bool) -> Option<Vec<Node>> {
+    let (lhs, rhs) = match expr {
+        Node::Equation(l, r) if !contains_var(r, var) => ((**l).clone(), (**r).clone()),
+        Node::Equation(l, r) if !contains_var(l, var) => ((**r).clone(), (**l).clone()),
+        Node::Equation(l, r) => (
+            Node::Subtract(l.clone(), r.clone()),
+            Node::Num(ExactNum::integer(0)),
+        ),
+        other => (other.clone(), Node::Num(ExactNum::integer(0))),
+    };
+    // A periodic function not at the top of the tree leaves its family
+    // parameter inside later inversions, which is still a valid family
+    let parameter = ["n", "k", "m"]
+        .into_iter()
+        .find(|p| *p != var && !contains_var(expr, p))
+        .unwrap_or("n");
+    let mut roots = Vec::new();
+    isolate(&lhs, rhs, var, general.then_some(parameter), &mut roots)?;
+
+    let env = crate::environment::Environment::new();
+    let mut seen = std::collections::HashSet::new();
+    Some(
+        roots
+            .into_iter()
+            .map(|r| crate::simplify::Simplifiable::simplify(&r, &env).unwrap_or(r))
+            .filter(|r| seen.insert(format!("{}", r)))
+            .collect(),
+    )
+}
+
+/// Push every `var` solving `lhs = rhs` onto `out`. `None` when `var`
+/// occurs more than once or under an operation with no inverse here.
+fn isolate(
+    lhs: &Node,
+    rhs: Node,
+    var: &str,
+    parameter: Option<&str>,
+    out: &mut Vec<Node>,
+) -> Option<()> {
+    let b = Box::new;
+    let num = |n: i64| Node::Num(ExactNum::integer(n));
+    match lhs {
+        Node::Variable(v) if v == var => {
+            out.push(rhs);
+            Some(())
+        }
+        Node::Add(l, r) => match (contains_var(l, var), contains_var(r, var)) {
+            (true, false) => isolate(l, Node::Subtract(b(rhs), r.clone()), var, parameter, out),
+            (false, true) => isolate(r, Node::Subtract(b(rhs), l.clone()), var, parameter, out),
+            _ => None,
+        },
+        Node::Subtract(l, r) => match (contains_var(l, var), contains_var(r, var)) {
+            (true, false) => isolate(l, Node::Add(b(rhs), r.clone()), var, parameter, out),
+            (false, true) => isolate(r, Node::Subtract(l.clone(), b(rhs)), var, parameter, out),
+            _ => None,
+        },
+        Node::Multiply(l, r) => match (contains_var(l, var), contains_var(r, var)) {
+            (true, false) => isolate(l, Node::Divide(b(rhs), r.clone()), var, parameter, out),
+            (false, true) => isolate(r, Node::Divide(b(rhs), l.clone()), var, parameter, out),
+            _ => None,
+        },
+        Node::Divide(l, r) => match (contains_var(l, var), contains_var(r, var)) {
+            (true, false) => isolate(l, Node::Multiply(b(rhs), r.clone()), var, parameter, out),
+            (false, true) => isolate(r, Node::Divide(l.clone(), b(rhs)), var, parameter, out),
+            _ => None,
+        },
+        Node::Negate(inner) => isolate(inner, Node::Negate(b(rhs)), var, parameter, out),
+        Node::Sqrt(inner) => {
+            if constant_value(&rhs).is_some_and(|c| c < 0.0) {
+                return Some(());
+            }
+            isolate(inner, Node::Power(b(rhs), b(num(2))), var, parameter, out)
+        }
+        Node::Abs(inner) => {
+            if constant_value(&rhs).is_some_and(|c| c < 0.0) {
+                return Some(());
+            }
+            isolate(inner, rhs.clone(), var, parameter, out)?;
+            isolate(inner, Node::Negate(b(rhs)), var, parameter, out)
+        }
+        Node::Power(base, exponent) => match (contains_var(base, var), contains_var(exponent, var))
+        {
+            // a^{f} = c  →  f = \ln c / \ln a
+            (false, true) => {
+                if constant_value(&rhs).is_some_and(|c| c <= 0.0) {
+                    return Some(());
+                }
+                let ln = |n: Node| Node::Function("ln".to_string(), vec![n]);
+                let solved = if matches!(&**base, Node::Variable(e) if e == "e") {
+                    ln(rhs)
+                } else {
+                    Node::Divide(b(ln(rhs)), b(ln((**base).clone())))
+                };
+                isolate(exponent, solved, var, parameter, out)
+            }
+            // f^{k} = c  →  f = ±c^{1/k} for even k, c^{1/k} for odd k
+            (true, false) => {
+                let k = match &**exponent {
+                    Node::Num(n) => n.to_i64().filter(|k| *k != 0)?,
+                    _ => return None,
+                };
+                let c = constant_value(&rhs);
+                let root = |c: Node| Node::Power(b(c), b(Node::Divide(b(num(1)), b(num(k)))));
+                if k % 2 == 0 {
+                    if c.is_some_and(|c| c < 0.0) {
+                        return Some(());
+                    }
+                    isolate(base, root(rhs.clone()), var, parameter, out)?;
+                    isolate(base, Node::Negate(b(root(rhs))), var, parameter, out)
+                } else if c.is_some_and(|c| c < 0.0) {
+                    let real = Node::Negate(b(root(Node::Negate(b(rhs)))));
+                    isolate(base, real, var, parameter, out)
+                } else {
+                    isolate(base, root(rhs), var, parameter, out)
+                }
+            }
+            _ => None,
+        },
+        Node::Function(name, args) if name == "sqrt" && args.len() == 1 => {
+            isolate(&Node::Sqrt(b(args[0].clone())), rhs, var, parameter, out)
+        }
+        Node::Function(name, args) if args.len() == 1 => {
+            let name = crate::function_meta::canonical_function_name(name);
+            let inversion = INVERSIONS.iter().find(|inv| inv.name == name)?;
+            if constant_value(&rhs).is_some_and(|c| !(inversion.range)(c)) {
+                return Some(());
+            }
+            let principal = match inversion.undo {
+                Undo::Apply(inverse) => Node::Function(inverse.to_string(), vec![rhs]),
+                Undo::Exp => Node::Power(b(Node::Variable("e".to_string())), b(rhs)),
+                Undo::Pow(base) => Node::Power(b(num(base)), b(rhs)),
+            };
+            let period = |turns: i64| match parameter {
+                Some(n) => Node::Multiply(
+                    b(Node::Multiply(
+                        b(num(turns)),
+                        b(Node::Variable("π".to_string())),
+                    )),
+                    b(Node::Variable(n.to_string())),
+                ),
+                None => num(0),
+            };
+            let with_period = |value: Node, turns: i64| Node::Add(b(value), b(period(turns)));
+            let preimages = match (inversion.branches, parameter) {
+                (Branches::PlusMinus, _) => {
+                    vec![principal.clone(), Node::Negate(b(principal))]
+                }
+                (Branches::Sine, Some(_)) => vec![
+                    with_period(principal.clone(), 2),
+                    with_period(
+                        Node::Subtract(b(Node::Variable("π".to_string())), b(principal)),
+                        2,
+                    ),
+                ],
+                (Branches::Cosine, Some(_)) => vec![
+                    with_period(principal.clone(), 2),
+                    with_period(Node::Negate(b(principal)), 2),
+                ],
+                (Branches::Tangent, Some(_)) => vec![with_period(principal, 1)],
+                _ => vec![principal],
+            };
+            for preimage in preimages {
+                isolate(&args[0], preimage, var, parameter, out)?;
+            }
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+/// The value of a variable-free right-hand side, for range checks.
+fn constant_value(node: &Node) -> Option<f64> {
+    let env = crate::environment::Environment::new();
+    crate::evaluator::Evaluator::evaluate(node, &env)
+        .ok()
+        .filter(|v| v.is_finite())
+}
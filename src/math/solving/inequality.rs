@@ -5,6 +5,304 @@ use crate::polynomial::Polynomial;
 use crate::simplify::Simplifiable;
 use num_rational::BigRational;
 use num_traits::{Signed, Zero};
+use std::cmp::Ordering;
+
+/// One finite end of an [`Interval`], included in it or not.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    pub value: ExactNum,
+    pub closed: bool,
+}
+
+/// A connected subset of ℝ. A missing endpoint leaves that side unbounded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interval {
+    pub lower: Option<Endpoint>,
+    pub upper: Option<Endpoint>,
+}
+
+/// The solution set of an inequality: disjoint intervals in increasing
+/// order. `Display` gives interval notation, `(-∞, -2) ∪ (2, ∞)`;
+/// [`SolutionSet::to_latex`] the same in LaTeX, and
+/// [`SolutionSet::to_latex_in`] the relation form, `x > 3`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SolutionSet {
+    pub intervals: Vec<Interval>,
+}
+
+impl Interval {
+    pub fn contains(&self, x: f64) -> bool {
+        let above = self.lower.as_ref().is_none_or(|e| {
+            let v = e.value.to_f64();
+            x > v || (e.closed && x == v)
+        });
+        let below = self.upper.as_ref().is_none_or(|e| {
+            let v = e.value.to_f64();
+            x < v || (e.closed && x == v)
+        });
+        above && below
+    }
+
+    /// Interval notation in LaTeX: `(3, \\infty)`, `[-2, 2]`.
+    pub fn to_latex(&self) -> String {
+        let lower = match &self.lower {
+            Some(e) => Node::Num(e.value.clone()).to_latex(),
+            None => "-\\infty".to_string(),
+        };
+        let upper = match &self.upper {
+            Some(e) => Node::Num(e.value.clone()).to_latex(),
+            None => "\\infty".to_string(),
+        };
+        format!(
+            "{}{}, {}{}",
+            self.left_bracket(),
+            lower,
+            upper,
+            self.right_bracket()
+        )
+    }
+
+    /// `x > 3`, `-2 \leq x \leq 2`, `x = 0` for a single point.
+    fn to_latex_in(&self, var: &str) -> String {
+        let value = |e: &Endpoint| Node::Num(e.value.clone()).to_latex();
+        let below = |e: &Endpoint| if e.closed { "\\leq" } else { "<" };
+        match (&self.lower, &self.upper) {
+            (None, None) => format!("{var} \\in \\mathbb{{R}}"),
+            (Some(lo), None) => {
+                let op = if lo.closed { "\\geq" } else { ">" };
+                format!("{var} {op} {}", value(lo))
+            }
+            (None, Some(hi)) => format!("{var} {} {}", below(hi), value(hi)),
+            (Some(lo), Some(hi)) if lo.value == hi.value => format!("{var} = {}", value(lo)),
+            (Some(lo), Some(hi)) => format!(
+                "{} {} {var} {} {}",
+                value(lo),
+                below(lo),
+                below(hi),
+                value(hi)
+            ),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match (&self.lower, &self.upper) {
+            (Some(lo), Some(hi)) => match lo.value.partial_cmp(&hi.value) {
+                Some(Ordering::Less) => false,
+                Some(Ordering::Equal) => !(lo.closed && hi.closed),
+                _ => true,
+            },
+            _ => false,
+        }
+    }
+
+    fn left_bracket(&self) -> &'static str {
+        match &self.lower {
+            Some(e) if e.closed => "[",
+            _ => "(",
+        }
+    }
+
+    fn right_bracket(&self) -> &'static str {
+        match &self.upper {
+            Some(e) if e.closed => "]",
+            _ => ")",
+        }
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.left_bracket())?;
+        match &self.lower {
+            Some(e) => write!(f, "{}", Node::Num(e.value.clone()))?,
+            None => f.write_str("-∞")?,
+        }
+        f.write_str(", ")?;
+        match &self.upper {
+            Some(e) => write!(f, "{}", Node::Num(e.value.clone()))?,
+            None => f.write_str("∞")?,
+        }
+        f.write_str(self.right_bracket())
+    }
+}
+
+impl SolutionSet {
+    pub fn empty() -> Self {
+        SolutionSet::default()
+    }
+
+    /// All of ℝ.
+    pub fn all() -> Self {
+        SolutionSet {
+            intervals: vec![Interval {
+                lower: None,
+                upper: None,
+            }],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.intervals.iter().any(|i| i.contains(x))
+    }
+
+    /// The points in both sets.
+    pub fn intersect(&self, other: &SolutionSet) -> SolutionSet {
+        let mut intervals: Vec<Interval> = Vec::new();
+        for a in &self.intervals {
+            for b in &other.intervals {
+                let interval = Interval {
+                    lower: pick_endpoint(&a.lower, &b.lower, Ordering::Greater, false),
+                    upper: pick_endpoint(&a.upper, &b.upper, Ordering::Less, false),
+                };
+                if !interval.is_empty() {
+                    intervals.push(interval);
+                }
+            }
+        }
+        intervals.sort_by(|a, b| compare_lower(&a.lower, &b.lower));
+        SolutionSet { intervals }
+    }
+
+    /// The points in either set. Intervals that overlap or share an
+    /// included endpoint merge; two open ends at the same point stay apart,
+    /// so `x < 0` joined with `x > 0` keeps the hole at 0.
+    pub fn union(&self, other: &SolutionSet) -> SolutionSet {
+        let mut all: Vec<Interval> = self
+            .intervals
+            .iter()
+            .chain(&other.intervals)
+            .cloned()
+            .collect();
+        all.sort_by(|a, b| compare_lower(&a.lower, &b.lower));
+        let mut intervals: Vec<Interval> = Vec::new();
+        for next in all {
+            if let Some(last) = intervals.last_mut() {
+                let joins = match (&last.upper, &next.lower) {
+                    (None, _) | (_, None) => true,
+                    (Some(hi), Some(lo)) => match lo.value.partial_cmp(&hi.value) {
+                        Some(Ordering::Less) => true,
+                        Some(Ordering::Equal) => hi.closed || lo.closed,
+                        _ => false,
+                    },
+                };
+                if joins {
+                    last.upper = pick_endpoint(&last.upper, &next.upper, Ordering::Greater, true);
+                    continue;
+                }
+            }
+            intervals.push(next);
+        }
+        SolutionSet { intervals }
+    }
+
+    /// Interval notation in LaTeX: `(-\infty, -2) \cup (2, \infty)`, or
+    /// `\emptyset`.
+    pub fn to_latex(&self) -> String {
+        if self.is_empty() {
+            return "\\emptyset".to_string();
+        }
+        let parts: Vec<String> = self.intervals.iter().map(Interval::to_latex).collect();
+        parts.join(" \\cup ")
+    }
+
+    /// The set as a condition on `var`: `x > 3`, `-2 \leq x \leq 2`,
+    /// `x < -2 \lor x > 2`, or `x \in \emptyset` when nothing satisfies it.
+    /// Single excluded points are listed after the rest: `x \geq 0, x \neq 2`
+    /// for `[0, 2) ∪ (2, ∞)`, and just `x \neq 1` for ℝ without 1.
+    pub fn to_latex_in(&self, var: &str) -> String {
+        if self.is_empty() {
+            return format!("{var} \\in \\emptyset");
+        }
+        let mut hulls: Vec<Interval> = Vec::new();
+        let mut holes: Vec<&ExactNum> = Vec::new();
+        for interval in &self.intervals {
+            if let Some(last) = hulls.last_mut() {
+                if let (Some(hi), Some(lo)) = (&last.upper, &interval.lower) {
+                    if hi.value == lo.value && !hi.closed && !lo.closed {
+                        holes.push(&lo.value);
+                        last.upper = interval.upper.clone();
+                        continue;
+                    }
+                }
+            }
+            hulls.push(interval.clone());
+        }
+        let mut conditions: Vec<String> = Vec::new();
+        let everywhere = hulls.len() == 1 && hulls[0].lower.is_none() && hulls[0].upper.is_none();
+        if !everywhere || holes.is_empty() {
+            let parts: Vec<String> = hulls.iter().map(|i| i.to_latex_in(var)).collect();
+            conditions.push(parts.join(" \\lor "));
+        }
+        for hole in holes {
+            conditions.push(format!(
+                "{var} \\neq {}",
+                Node::Num(hole.clone()).to_latex()
+            ));
+        }
+        conditions.join(", ")
+    }
+}
+
+impl std::fmt::Display for SolutionSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("∅");
+        }
+        for (i, interval) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ∪ ")?;
+            }
+            write!(f, "{interval}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Of two lower (or upper) ends, the one further in `direction`; `None` is
+/// unbounded. With `loosest` an unbounded end wins and a tied end is
+/// included if either includes it; otherwise the bounded end wins and a
+/// tie is included only if both include it.
+fn pick_endpoint(
+    a: &Option<Endpoint>,
+    b: &Option<Endpoint>,
+    direction: Ordering,
+    loosest: bool,
+) -> Option<Endpoint> {
+    match (a, b) {
+        (None, _) | (_, None) if loosest => None,
+        (None, other) | (other, None) => other.clone(),
+        (Some(x), Some(y)) => match x.value.partial_cmp(&y.value) {
+            Some(order) if order == direction => Some(x.clone()),
+            Some(Ordering::Equal) | None => Some(Endpoint {
+                value: x.value.clone(),
+                closed: if loosest {
+                    x.closed || y.closed
+                } else {
+                    x.closed && y.closed
+                },
+            }),
+            Some(_) => Some(y.clone()),
+        },
+    }
+}
+
+/// Order intervals by their lower end, unbounded first.
+fn compare_lower(a: &Option<Endpoint>, b: &Option<Endpoint>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, _) => Ordering::Less,
+        (_, None) => Ordering::Greater,
+        (Some(x), Some(y)) => x
+            .value
+            .partial_cmp(&y.value)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| y.closed.cmp(&x.closed)),
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 enum IneqType {
@@ -32,11 +330,30 @@ fn sign_satisfies(val: &BigRational, ineq: IneqType) -> bool {
 #[derive(Debug, Clone)]
 struct CritPoint {
     value: BigRational,
-    display: String,
+    exact: ExactNum,
     is_pole: bool,
 }
 
+impl CritPoint {
+    fn endpoint(&self, closed: bool) -> Endpoint {
+        Endpoint {
+            value: self.exact.clone(),
+            closed,
+        }
+    }
+}
+
+/// Solve a polynomial or rational inequality in `target_var`, as interval
+/// notation: `x^2 - 4 > 0` → `(-∞, -2) ∪ (2, ∞)`.
 pub fn solve_inequality(expr: &Node, target_var: &str) -> Result<String, String> {
+    solve_inequality_set(expr, target_var).map(|set| set.to_string())
+}
+
+/// [`solve_inequality`] as a [`SolutionSet`]. Both sides are moved to one
+/// and the sign of the difference is read off between its roots and poles,
+/// so dividing through by a negative coefficient needs no special case:
+/// `-2x > 4` → `x < -2`.
+pub fn solve_inequality_set(expr: &Node, target_var: &str) -> Result<SolutionSet, String> {
     let (lhs, rhs, ineq_type) = match expr {
         Node::Greater(l, r) => (l.as_ref(), r.as_ref(), IneqType::Gt),
         Node::GreaterEqual(l, r) => (l.as_ref(), r.as_ref(), IneqType::Ge),
@@ -96,10 +413,9 @@ fn find_rational_and_irrational_roots(poly: &Polynomial) -> Vec<CritPoint> {
     let mut remaining = poly.clone();
 
     for root in &rat_roots {
-        let display = format!("{}", Node::Num(exact_from_rational(root)));
         points.push(CritPoint {
             value: root.clone(),
-            display,
+            exact: exact_from_rational(root),
             is_pole: false,
         });
         remaining = remaining.deflate(root);
@@ -111,10 +427,9 @@ fn find_rational_and_irrational_roots(poly: &Polynomial) -> Vec<CritPoint> {
             let a = remaining.coeff(1);
             let b = remaining.coeff(0);
             let root = -b / a;
-            let display = format!("{}", Node::Num(exact_from_rational(&root)));
             points.push(CritPoint {
+                exact: exact_from_rational(&root),
                 value: root,
-                display,
                 is_pole: false,
             });
         }
@@ -138,13 +453,13 @@ fn find_rational_and_irrational_roots(poly: &Polynomial) -> Vec<CritPoint> {
                 if let Some(r1_rat) = r1_exact.to_rational() {
                     points.push(CritPoint {
                         value: r1_rat,
-                        display: format!("{}", Node::Num(r1_exact)),
+                        exact: r1_exact,
                         is_pole: false,
                     });
                 } else {
                     points.push(CritPoint {
                         value: BigRational::from_float(r1).unwrap_or_default(),
-                        display: format!("{}", Node::Num(r1_exact)),
+                        exact: r1_exact,
                         is_pole: false,
                     });
                 }
@@ -152,22 +467,21 @@ fn find_rational_and_irrational_roots(poly: &Polynomial) -> Vec<CritPoint> {
                 if let Some(r2_rat) = r2_exact.to_rational() {
                     points.push(CritPoint {
                         value: r2_rat,
-                        display: format!("{}", Node::Num(r2_exact)),
+                        exact: r2_exact,
                         is_pole: false,
                     });
                 } else {
                     points.push(CritPoint {
                         value: BigRational::from_float(r2).unwrap_or_default(),
-                        display: format!("{}", Node::Num(r2_exact)),
+                        exact: r2_exact,
                         is_pole: false,
                     });
                 }
             } else if disc.is_zero() {
                 let root = -b / (BigRational::from_integer(2.into()) * a);
-                let display = format!("{}", Node::Num(exact_from_rational(&root)));
                 points.push(CritPoint {
+                    exact: exact_from_rational(&root),
                     value: root,
-                    display,
                     is_pole: false,
                 });
             }
@@ -183,16 +497,16 @@ fn find_rational_and_irrational_roots(poly: &Polynomial) -> Vec<CritPoint> {
     points
 }
 
-fn solve_poly_inequality(poly: &Polynomial, ineq: IneqType) -> Result<String, String> {
+fn solve_poly_inequality(poly: &Polynomial, ineq: IneqType) -> Result<SolutionSet, String> {
     let degree = poly.degree();
 
     if degree.is_none() || degree == Some(0) {
         let c = poly.coeff(0);
         let sat = sign_satisfies(&c, ineq);
         return Ok(if sat {
-            "(-∞, ∞)".to_string()
+            SolutionSet::all()
         } else {
-            "∅".to_string()
+            SolutionSet::empty()
         });
     }
 
@@ -201,20 +515,20 @@ fn solve_poly_inequality(poly: &Polynomial, ineq: IneqType) -> Result<String, St
     if points.is_empty() {
         let val = poly.evaluate(&BigRational::zero());
         return Ok(if sign_satisfies(&val, ineq) {
-            "(-∞, ∞)".to_string()
+            SolutionSet::all()
         } else {
-            "∅".to_string()
+            SolutionSet::empty()
         });
     }
 
-    build_solution_intervals(&points, |x| poly.evaluate(x), ineq)
+    Ok(build_solution_set(&points, |x| poly.evaluate(x), ineq))
 }
 
 fn solve_rational_inequality(
     num: &Polynomial,
     den: &Polynomial,
     ineq: IneqType,
-) -> Result<String, String> {
+) -> Result<SolutionSet, String> {
     let mut points = find_rational_and_irrational_roots(num);
     let mut den_points = find_rational_and_irrational_roots(den);
     for p in &mut den_points {
@@ -232,7 +546,7 @@ fn solve_rational_inequality(
         }
     });
 
-    build_solution_intervals(
+    Ok(build_solution_set(
         &points,
         |x| {
             let d = den.evaluate(x);
@@ -242,57 +556,42 @@ fn solve_rational_inequality(
             num.evaluate(x) / d
         },
         ineq,
-    )
+    ))
 }
 
-fn build_solution_intervals<F>(
-    points: &[CritPoint],
-    eval: F,
-    ineq: IneqType,
-) -> Result<String, String>
+fn build_solution_set<F>(points: &[CritPoint], eval: F, ineq: IneqType) -> SolutionSet
 where
     F: Fn(&BigRational) -> BigRational,
 {
     let includes_eq = ineq.includes_zero();
 
-    let mut intervals: Vec<String> = Vec::new();
+    let mut intervals: Vec<Interval> = Vec::new();
 
-    // State for merging adjacent satisfied intervals
-    let mut in_interval = false;
-    let mut interval_start: Option<(String, bool)> = None; // (display, is_closed)
+    // Lower end of the interval being built, while inside one (`Some(None)`
+    // is an interval running from −∞)
+    let mut open_from: Option<Option<Endpoint>> = None;
 
     // Test region before first root
     let first = &points[0];
     let test = &first.value - BigRational::from_integer(1.into());
-    let val = eval(&test);
-    let region_sat = sign_satisfies(&val, ineq);
-
-    if region_sat {
-        in_interval = true;
-        interval_start = Some(("-∞".to_string(), false));
+    if sign_satisfies(&eval(&test), ineq) {
+        open_from = Some(None);
     }
 
     for (i, pt) in points.iter().enumerate() {
-        let point_included = if pt.is_pole { false } else { includes_eq };
-
-        if in_interval {
-            if point_included {
-                // Continue the interval through this point
-            } else {
-                // Close the interval before this point
-                let (start_str, start_closed) = interval_start.take().unwrap();
-                let left_br = if start_closed { "[" } else { "(" };
-                let right_br = ")";
-                intervals.push(format!(
-                    "{}{}, {}{}",
-                    left_br, start_str, pt.display, right_br
-                ));
-                in_interval = false;
+        let point_included = !pt.is_pole && includes_eq;
+
+        if !point_included {
+            // Close the interval before this point
+            if let Some(lower) = open_from.take() {
+                intervals.push(Interval {
+                    lower,
+                    upper: Some(pt.endpoint(false)),
+                });
             }
-        } else if point_included {
+        } else if open_from.is_none() {
             // Start a potential new interval at this isolated point
-            interval_start = Some((pt.display.clone(), true));
-            in_interval = true;
+            open_from = Some(Some(pt.endpoint(true)));
         }
 
         // Test region after this point (before next point or to +∞)
@@ -305,39 +604,27 @@ where
             let test = &pt.value + BigRational::from_integer(1.into());
             eval(&test)
         };
-        let next_region_sat = sign_satisfies(&next_val, ineq);
 
-        if in_interval && !next_region_sat {
+        if !sign_satisfies(&next_val, ineq) {
             // Close the interval at this point
-            let (start_str, start_closed) = interval_start.take().unwrap();
-            let left_br = if start_closed { "[" } else { "(" };
-            let right_closed = if pt.is_pole { false } else { includes_eq };
-            let right_br = if right_closed { "]" } else { ")" };
-            intervals.push(format!(
-                "{}{}, {}{}",
-                left_br, start_str, pt.display, right_br
-            ));
-            in_interval = false;
-        } else if !in_interval && next_region_sat {
+            if let Some(lower) = open_from.take() {
+                intervals.push(Interval {
+                    lower,
+                    upper: Some(pt.endpoint(point_included)),
+                });
+            }
+        } else if open_from.is_none() {
             // Start a new interval after this point
-            let left_closed = if pt.is_pole { false } else { includes_eq };
-            interval_start = Some((pt.display.clone(), left_closed));
-            in_interval = true;
+            open_from = Some(Some(pt.endpoint(point_included)));
         }
     }
 
     // Close any remaining open interval
-    if in_interval {
-        let (start_str, start_closed) = interval_start.take().unwrap();
-        let left_br = if start_closed { "[" } else { "(" };
-        intervals.push(format!("{}{}, ∞)", left_br, start_str));
+    if let Some(lower) = open_from {
+        intervals.push(Interval { lower, upper: None });
     }
 
-    if intervals.is_empty() {
-        Ok("∅".to_string())
-    } else {
-        Ok(intervals.join(" ∪ "))
-    }
+    SolutionSet { intervals }
 }
 
 fn exact_from_rational(r: &BigRational) -> ExactNum {
@@ -0,0 +1,527 @@
+//! Numeric solutions of initial value problems `y' = f(t, y)`, `y(t₀) = y₀`.
+//!
+//! [`ode`](crate::ode) finds closed forms; this module steps through
+//! equations that have none, or that a plot only needs values of. The state
+//! `y` is a vector, so a system (or a higher-order equation rewritten as
+//! one) is solved the same way as a single equation. Three methods:
+//!
+//! - [`OdeMethod::Rk4`]: classic fourth-order Runge–Kutta with a fixed step.
+//! - [`OdeMethod::Rk45`]: Dormand–Prince 5(4), which compares a fifth- and
+//!   a fourth-order step to estimate the error and grows or shrinks the
+//!   step to keep it within `atol + rtol·|y|`.
+//! - [`OdeMethod::ImplicitEuler`]: backward Euler with Newton iterations,
+//!   for stiff problems (`y' = −1000(y − cos t)`) where an explicit method
+//!   needs tiny steps to stay stable. A step whose Newton iteration does
+//!   not converge is retried at half the size.
+//!
+//! The [`OdeSolution`] keeps `y` and `y'` at every step, and interpolates
+//! between them with cubic Hermite polynomials, so it can be sampled on
+//! any grid ([`OdeSolution::at`], [`OdeSolution::sample`]).
+
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::node::Node;
+use crate::parser::parse_latex;
+
+/// How [`solve_ivp`] steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OdeMethod {
+    /// Fourth-order Runge–Kutta, fixed step
+    Rk4,
+    /// Dormand–Prince 5(4), adaptive step
+    #[default]
+    Rk45,
+    /// Backward Euler, for stiff problems
+    ImplicitEuler,
+}
+
+/// Settings for [`solve_ivp`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OdeOptions {
+    pub method: OdeMethod,
+    /// Step size for the fixed-step methods and first trial step for
+    /// `Rk45`; a hundredth of the interval when `None`
+    pub step: Option<f64>,
+    /// Relative error allowed per `Rk45` step
+    pub rtol: f64,
+    /// Absolute error allowed per `Rk45` step
+    pub atol: f64,
+    /// Most steps (accepted or rejected) before giving up
+    pub max_steps: usize,
+}
+
+impl Default for OdeOptions {
+    fn default() -> Self {
+        OdeOptions {
+            method: OdeMethod::default(),
+            step: None,
+            rtol: 1e-6,
+            atol: 1e-9,
+            max_steps: 100_000,
+        }
+    }
+}
+
+impl OdeOptions {
+    /// Default settings for `method`.
+    pub fn with_method(method: OdeMethod) -> Self {
+        OdeOptions {
+            method,
+            ..Self::default()
+        }
+    }
+}
+
+/// The steps taken by [`solve_ivp`], with `y` and `y'` at each.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OdeSolution {
+    /// Times from `t₀` to `t_end`, in the direction of integration
+    pub t: Vec<f64>,
+    /// The state at each time
+    pub y: Vec<Vec<f64>>,
+    /// `f(t, y)` at each time
+    pub dy: Vec<Vec<f64>>,
+    /// Steps thrown away for a too-large error or a failed Newton iteration
+    pub rejected_steps: usize,
+}
+
+impl OdeSolution {
+    /// The solution at `t` by cubic Hermite interpolation between the
+    /// steps around it, or `None` outside the solved interval.
+    pub fn at(&self, t: f64) -> Option<Vec<f64>> {
+        let (first, last) = (self.t[0], *self.t.last()?);
+        if self.t.len() == 1 {
+            return (t == first).then(|| self.y[0].clone());
+        }
+        let forward = last >= first;
+        if !t.is_finite() || (forward && !(first..=last).contains(&t)) {
+            return None;
+        }
+        if !forward && !(last..=first).contains(&t) {
+            return None;
+        }
+        let i = match self
+            .t
+            .partition_point(|&s| if forward { s <= t } else { s >= t })
+        {
+            0 => 0,
+            i if i >= self.t.len() => self.t.len().saturating_sub(2),
+            i => i - 1,
+        };
+        let (t0, t1) = (self.t[i], self.t[i + 1]);
+        let h = t1 - t0;
+        let s = (t - t0) / h;
+        let h00 = (1.0 + 2.0 * s) * (1.0 - s) * (1.0 - s);
+        let h10 = s * (1.0 - s) * (1.0 - s);
+        let h01 = s * s * (3.0 - 2.0 * s);
+        let h11 = s * s * (s - 1.0);
+        Some(
+            (0..self.y[i].len())
+                .map(|k| {
+                    h00 * self.y[i][k]
+                        + h10 * h * self.dy[i][k]
+                        + h01 * self.y[i + 1][k]
+                        + h11 * h * self.dy[i + 1][k]
+                })
+                .collect(),
+        )
+    }
+
+    /// A closure over [`at`](Self::at), for plotting code that takes a
+    /// function.
+    pub fn interpolant(&self) -> impl Fn(f64) -> Option<Vec<f64>> + '_ {
+        move |t| self.at(t)
+    }
+
+    /// `samples` evenly spaced points from `t₀` to `t_end`, both included.
+    pub fn sample(&self, samples: usize) -> Vec<(f64, Vec<f64>)> {
+        let (first, last) = (self.t[0], self.t[self.t.len() - 1]);
+        let samples = samples.max(2);
+        (0..samples)
+            .map(|i| {
+                let t = if i + 1 == samples {
+                    last
+                } else {
+                    first + (last - first) * i as f64 / (samples - 1) as f64
+                };
+                (t, self.at(t).expect("t is inside the solved interval"))
+            })
+            .collect()
+    }
+}
+
+/// Solve `y' = f(t, y)` from `y(t0) = y0` up to `t_end`, which may be
+/// before `t0`.
+pub fn solve_ivp<F>(
+    mut f: F,
+    t0: f64,
+    y0: &[f64],
+    t_end: f64,
+    options: &OdeOptions,
+) -> Result<OdeSolution, String>
+where
+    F: FnMut(f64, &[f64]) -> Result<Vec<f64>, String>,
+{
+    if !t0.is_finite() || !t_end.is_finite() {
+        return Err("The interval of an initial value problem must be finite".to_string());
+    }
+    if y0.is_empty() {
+        return Err("An initial value problem needs at least one unknown".to_string());
+    }
+    let span = t_end - t0;
+    let step = options.step.unwrap_or(span.abs() / 100.0);
+    if span != 0.0 && !(step.is_finite() && step > 0.0) {
+        return Err(format!("Step size must be positive, got {}", step));
+    }
+
+    let dy0 = checked_rhs(&mut f, t0, y0)?;
+    let mut solution = OdeSolution {
+        t: vec![t0],
+        y: vec![y0.to_vec()],
+        dy: vec![dy0],
+        rejected_steps: 0,
+    };
+    if span == 0.0 {
+        return Ok(solution);
+    }
+
+    let direction = span.signum();
+    // Ends within a few ulps of `t_end` count as reaching it
+    let tolerance = 4.0 * f64::EPSILON * t0.abs().max(t_end.abs());
+    let mut h = step;
+    let mut steps = 0;
+    loop {
+        let t = *solution.t.last().expect("at least the initial point");
+        let remaining = (t_end - t) * direction;
+        if remaining <= tolerance {
+            break;
+        }
+        if steps == options.max_steps {
+            return Err(format!(
+                "Gave up at t = {} after {} steps; raise max_steps or the step size",
+                t, steps
+            ));
+        }
+        steps += 1;
+        let h_try = h.min(remaining);
+        let y = solution.y.last().expect("one state per time").clone();
+        let dy = solution.dy.last().expect("one derivative per time").clone();
+        let outcome = match options.method {
+            OdeMethod::Rk4 => rk4_step(&mut f, t, &y, &dy, direction * h_try).map(Some)?,
+            OdeMethod::Rk45 => {
+                let (next, error) = dormand_prince_step(&mut f, t, &y, &dy, direction * h_try)?;
+                let norm = error_norm(&error, &y, &next, options);
+                // Standard controller: h·(1/err)^(1/5) with a safety factor
+                let factor = if norm == 0.0 {
+                    5.0
+                } else {
+                    (0.9 * norm.powf(-0.2)).clamp(0.2, 5.0)
+                };
+                h = h_try * factor;
+                (norm <= 1.0).then_some(next)
+            }
+            OdeMethod::ImplicitEuler => {
+                let next = backward_euler_step(&mut f, t, &y, direction * h_try)?;
+                if next.is_none() {
+                    h = h_try / 2.0;
+                }
+                next
+            }
+        };
+        if !h.is_finite() || h <= tolerance {
+            return Err(format!(
+                "Step size underflow at t = {}; the problem may be singular there",
+                t
+            ));
+        }
+        let Some(next) = outcome else {
+            solution.rejected_steps += 1;
+            continue;
+        };
+        let t_next = if remaining - h_try <= tolerance {
+            t_end
+        } else {
+            t + direction * h_try
+        };
+        let dy_next = checked_rhs(&mut f, t_next, &next)?;
+        solution.t.push(t_next);
+        solution.y.push(next);
+        solution.dy.push(dy_next);
+        if options.method == OdeMethod::ImplicitEuler {
+            // Recover toward the requested step after a halving
+            h = (2.0 * h).min(step);
+        }
+    }
+    Ok(solution)
+}
+
+/// [`solve_ivp`] for a system of `(y_i, rhs_i)` pairs, `y_i' = rhs_i`, each
+/// `rhs_i` an expression in `indep` and the unknowns; other variables are
+/// read from `env`.
+pub fn solve_ivp_nodes(
+    system: &[(&str, Node)],
+    indep: &str,
+    t0: f64,
+    y0: &[f64],
+    t_end: f64,
+    options: &OdeOptions,
+    env: &Environment,
+) -> Result<OdeSolution, String> {
+    if system.len() != y0.len() {
+        return Err(format!(
+            "{} equations but {} initial values",
+            system.len(),
+            y0.len()
+        ));
+    }
+    let mut point = env.child();
+    solve_ivp(
+        |t, y| {
+            point.set(indep, t);
+            for ((name, _), value) in system.iter().zip(y) {
+                point.set(name, *value);
+            }
+            system
+                .iter()
+                .map(|(_, rhs)| Evaluator::evaluate(rhs, &point).map_err(String::from))
+                .collect()
+        },
+        t0,
+        y0,
+        t_end,
+        options,
+    )
+}
+
+/// [`solve_ivp`] for `dep' = rhs`, with `rhs` in LaTeX.
+pub fn solve_ivp_latex(
+    rhs_latex: &str,
+    indep: &str,
+    dep: &str,
+    t0: f64,
+    y0: f64,
+    t_end: f64,
+    options: &OdeOptions,
+) -> Result<OdeSolution, String> {
+    let env = Environment::new();
+    let rhs = parse_latex(rhs_latex, &env).map_err(|e| e.to_string())?;
+    solve_ivp_nodes(&[(dep, rhs)], indep, t0, &[y0], t_end, options, &env)
+}
+
+fn checked_rhs<F>(f: &mut F, t: f64, y: &[f64]) -> Result<Vec<f64>, String>
+where
+    F: FnMut(f64, &[f64]) -> Result<Vec<f64>, String>,
+{
+    let dy = f(t, y)?;
+    if dy.len() != y.len() {
+        return Err(format!(
+            "The right-hand side has {} components for {} unknowns",
+            dy.len(),
+            y.len()
+        ));
+    }
+    if let Some(bad) = dy.iter().find(|v| !v.is_finite()) {
+        return Err(format!("The right-hand side is {} at t = {}", bad, t));
+    }
+    Ok(dy)
+}
+
+/// `y + Σ cᵢ·kᵢ`
+fn combine(y: &[f64], terms: &[(f64, &[f64])]) -> Vec<f64> {
+    let mut out = y.to_vec();
+    for (c, k) in terms {
+        for (o, v) in out.iter_mut().zip(k.iter()) {
+            *o += c * v;
+        }
+    }
+    out
+}
+
+fn rk4_step<F>(f: &mut F, t: f64, y: &[f64], k1: &[f64], h: f64) -> Result<Vec<f64>, String>
+where
+    F: FnMut(f64, &[f64]) -> Result<Vec<f64>, String>,
+{
+    let k2 = checked_rhs(f, t + h / 2.0, &combine(y, &[(h / 2.0, k1)]))?;
+    let k3 = checked_rhs(f, t + h / 2.0, &combine(y, &[(h / 2.0, &k2)]))?;
+    let k4 = checked_rhs(f, t + h, &combine(y, &[(h, &k3)]))?;
+    Ok(combine(
+        y,
+        &[
+            (h / 6.0, k1),
+            (h / 3.0, &k2),
+            (h / 3.0, &k3),
+            (h / 6.0, &k4),
+        ],
+    ))
+}
+
+/// One Dormand–Prince step: the fifth-order result and its difference
+/// from the embedded fourth-order one.
+fn dormand_prince_step<F>(
+    f: &mut F,
+    t: f64,
+    y: &[f64],
+    k1: &[f64],
+    h: f64,
+) -> Result<(Vec<f64>, Vec<f64>), String>
+where
+    F: FnMut(f64, &[f64]) -> Result<Vec<f64>, String>,
+{
+    let k2 = checked_rhs(f, t + h / 5.0, &combine(y, &[(h / 5.0, k1)]))?;
+    let k3 = checked_rhs(
+        f,
+        t + 3.0 * h / 10.0,
+        &combine(y, &[(h * 3.0 / 40.0, k1), (h * 9.0 / 40.0, &k2)]),
+    )?;
+    let k4 = checked_rhs(
+        f,
+        t + 4.0 * h / 5.0,
+        &combine(
+            y,
+            &[
+                (h * 44.0 / 45.0, k1),
+                (h * -56.0 / 15.0, &k2),
+                (h * 32.0 / 9.0, &k3),
+            ],
+        ),
+    )?;
+    let k5 = checked_rhs(
+        f,
+        t + 8.0 * h / 9.0,
+        &combine(
+            y,
+            &[
+                (h * 19372.0 / 6561.0, k1),
+                (h * -25360.0 / 2187.0, &k2),
+                (h * 64448.0 / 6561.0, &k3),
+                (h * -212.0 / 729.0, &k4),
+            ],
+        ),
+    )?;
+    let k6 = checked_rhs(
+        f,
+        t + h,
+        &combine(
+            y,
+            &[
+                (h * 9017.0 / 3168.0, k1),
+                (h * -355.0 / 33.0, &k2),
+                (h * 46732.0 / 5247.0, &k3),
+                (h * 49.0 / 176.0, &k4),
+                (h * -5103.0 / 18656.0, &k5),
+            ],
+        ),
+    )?;
+    let next = combine(
+        y,
+        &[
+            (h * 35.0 / 384.0, k1),
+            (h * 500.0 / 1113.0, &k3),
+            (h * 125.0 / 192.0, &k4),
+            (h * -2187.0 / 6784.0, &k5),
+            (h * 11.0 / 84.0, &k6),
+        ],
+    );
+    let k7 = checked_rhs(f, t + h, &next)?;
+    // Fifth-order weights minus the fourth-order ones
+    let error = combine(
+        &vec![0.0; y.len()],
+        &[
+            (h * 71.0 / 57600.0, k1),
+            (h * -71.0 / 16695.0, &k3),
+            (h * 71.0 / 1920.0, &k4),
+            (h * -17253.0 / 339200.0, &k5),
+            (h * 22.0 / 525.0, &k6),
+            (-h / 40.0, &k7),
+        ],
+    );
+    Ok((next, error))
+}
+
+/// Root-mean-square of the error scaled by the tolerance of each component.
+fn error_norm(error: &[f64], y: &[f64], next: &[f64], options: &OdeOptions) -> f64 {
+    let sum: f64 = error
+        .iter()
+        .zip(y.iter().zip(next))
+        .map(|(e, (a, b))| {
+            let scale = options.atol + options.rtol * a.abs().max(b.abs());
+            (e / scale).powi(2)
+        })
+        .sum();
+    (sum / error.len() as f64).sqrt()
+}
+
+/// `y_{n+1} = y_n + h·f(t + h, y_{n+1})` by Newton's method with a
+/// finite-difference Jacobian, or `None` when it does not converge.
+fn backward_euler_step<F>(f: &mut F, t: f64, y: &[f64], h: f64) -> Result<Option<Vec<f64>>, String>
+where
+    F: FnMut(f64, &[f64]) -> Result<Vec<f64>, String>,
+{
+    const MAX_ITERATIONS: usize = 20;
+    let n = y.len();
+    let t_next = t + h;
+    // Explicit Euler predictor
+    let mut guess = combine(y, &[(h, &checked_rhs(f, t, y)?)]);
+    for _ in 0..MAX_ITERATIONS {
+        let Ok(value) = checked_rhs(f, t_next, &guess) else {
+            return Ok(None);
+        };
+        let residual: Vec<f64> = (0..n).map(|i| guess[i] - y[i] - h * value[i]).collect();
+
+        // J = I − h·∂f/∂y, column by column
+        let mut jacobian = vec![0.0; n * n];
+        for j in 0..n {
+            let delta = f64::EPSILON.sqrt() * guess[j].abs().max(1.0);
+            let mut shifted = guess.clone();
+            shifted[j] += delta;
+            let Ok(value_shifted) = checked_rhs(f, t_next, &shifted) else {
+                return Ok(None);
+            };
+            for i in 0..n {
+                let partial = (value_shifted[i] - value[i]) / delta;
+                jacobian[i * n + j] = if i == j { 1.0 } else { 0.0 } - h * partial;
+            }
+        }
+        let Some(correction) = solve_dense(jacobian, residual, n) else {
+            return Ok(None);
+        };
+        let mut size = 0.0f64;
+        for (g, c) in guess.iter_mut().zip(&correction) {
+            *g -= c;
+            size = size.max(c.abs() / g.abs().max(1.0));
+        }
+        if size <= 1e-10 {
+            return Ok(Some(guess));
+        }
+    }
+    Ok(None)
+}
+
+/// `x` with `Ax = b` by Gaussian elimination with partial pivoting, or
+/// `None` for a singular `A`.
+fn solve_dense(mut a: Vec<f64>, mut b: Vec<f64>, n: usize) -> Option<Vec<f64>> {
+    for k in 0..n {
+        let pivot = (k..n).max_by(|&i, &j| a[i * n + k].abs().total_cmp(&a[j * n + k].abs()))?;
+        if a[pivot * n + k].abs() < 1e-300 {
+            return None;
+        }
+        if pivot != k {
+            for j in 0..n {
+                a.swap(k * n + j, pivot * n + j);
+            }
+            b.swap(k, pivot);
+        }
+        for i in k + 1..n {
+            let factor = a[i * n + k] / a[k * n + k];
+            for j in k..n {
+                a[i * n + j] -= factor * a[k * n + j];
+            }
+            b[i] -= factor * b[k];
+        }
+    }
+    for i in (0..n).rev() {
+        let tail: f64 = (i + 1..n).map(|j| a[i * n + j] * b[j]).sum();
+        b[i] = (b[i] - tail) / a[i * n + i];
+    }
+    Some(b)
+}
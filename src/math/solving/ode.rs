@@ -9,6 +9,7 @@ use crate::environment::Environment;
 use crate::exact::ExactNum;
 use crate::fps::FormalPowerSeries;
 use crate::integration::integrate;
+use crate::matrix::{parse_latex_matrix, Matrix};
 use crate::node::Node;
 use crate::polynomial::Polynomial;
 use crate::simplify::Simplifiable;
@@ -235,6 +236,292 @@ pub fn solve_constant_coeff(
     }
 }
 
+/// `e^{At}` with `t` the variable `indep`, for a square matrix `A`.
+///
+/// A 2×2 matrix with complex eigenvalues `s ± ωi` gives the real form
+/// `e^{st}(cos(ωt)·I + sin(ωt)/ω·(A − sI))`. Otherwise Putzer's algorithm
+/// writes `e^{At} = Σ r_{k+1}(t)·P_k` with `P_k = Π_{j≤k}(A − λ_j I)`, where
+/// `r_{k+1}` is the divided difference of `λ ↦ e^{λt}` over `λ_1, …, λ_{k+1}`
+/// (`t^m e^{λt}/m!` over an eigenvalue repeated `m + 1` times), so
+/// repeated eigenvalues and missing eigenvectors need no special case.
+/// The divided differences are kept as coefficients of each `t^m e^{λt}`,
+/// so every entry comes out as a sum of such terms. Exact eigenvalues give
+/// an exact result; symbolic entries are allowed whenever the eigenvalues
+/// are found.
+pub fn matrix_exponential(a: &Matrix, indep: &str, env: &Environment) -> Result<Matrix, String> {
+    if !a.is_square() {
+        return Err("The matrix exponential needs a square matrix".to_string());
+    }
+    let n = a.rows;
+    let t = var(indep);
+    if n == 2 {
+        if let Some(rotation) = exponential_2x2_complex(a, &t, env)? {
+            return Ok(rotation);
+        }
+    }
+
+    // Distinct eigenvalues, and each eigenvalue as an index into them, with
+    // equal ones next to each other for the divided differences
+    let mut distinct: Vec<Node> = Vec::new();
+    let mut eigenvalues: Vec<usize> = Vec::with_capacity(n);
+    let values = match exact_eigenvalues(a, env) {
+        Some(values) => values,
+        None => a.eigenvalues(env)?,
+    };
+    for value in values {
+        if contains_var(&value, "i") && !a.elements.iter().any(|e| contains_var(e, "i")) {
+            return Err(format!(
+                "Complex eigenvalue {} for a {}×{} matrix; only the 2×2 case has a real form",
+                value, n, n
+            ));
+        }
+        match distinct.iter().position(|v| *v == value) {
+            Some(g) => {
+                let last = eigenvalues
+                    .iter()
+                    .rposition(|&h| h == g)
+                    .expect("seen before");
+                eigenvalues.insert(last + 1, g);
+            }
+            None => {
+                eigenvalues.push(distinct.len());
+                distinct.push(value);
+            }
+        }
+    }
+    if eigenvalues.len() != n {
+        return Err(format!(
+            "Found {} of the {} eigenvalues of the matrix",
+            eigenvalues.len(),
+            n
+        ));
+    }
+
+    // Coefficient of t^m e^{λ_g t} in each entry, keyed by (g, m)
+    let mut coefficients: Vec<(ExpTerm, Vec<Node>)> = Vec::new();
+    let mut term = Matrix::identity(n);
+    for k in 0..n {
+        if k > 0 {
+            term = term.multiply(&shift(a, &distinct[eigenvalues[k - 1]], env)?, env)?;
+        }
+        for (key, r) in exp_divided_difference(&eigenvalues[..=k], &distinct, env)? {
+            let index = match coefficients.iter().position(|(k, _)| *k == key) {
+                Some(index) => index,
+                None => {
+                    coefficients.push((key, vec![num(0); n * n]));
+                    coefficients.len() - 1
+                }
+            };
+            for (entry, p) in coefficients[index].1.iter_mut().zip(&term.elements) {
+                *entry = add(entry.clone(), mul(r.clone(), p.clone()));
+            }
+        }
+    }
+
+    let mut elements = vec![num(0); n * n];
+    for ((g, m), entries) in &coefficients {
+        let mut basis = exp(mul(distinct[*g].clone(), t.clone()));
+        if *m > 0 {
+            basis = mul(
+                Node::Power(Box::new(t.clone()), Box::new(num(*m as i64))),
+                basis,
+            );
+        }
+        for (element, entry) in elements.iter_mut().zip(entries) {
+            let coefficient = entry.simplify(env)?;
+            if !matches!(&coefficient, Node::Num(c) if c.is_zero()) {
+                *element = add(element.clone(), mul(coefficient, basis.clone()));
+            }
+        }
+    }
+    let elements = elements
+        .iter()
+        .map(|element| element.simplify(env))
+        .collect::<Result<_, _>>()?;
+    Ok(Matrix::new(n, n, elements)?)
+}
+
+/// Solve the system `y' = Ay` for constant `A`: `y = e^{At}·y_0` with the
+/// given `y(0)`, or the general solution with constants `C_{1}, …, C_{n}`.
+pub fn solve_constant_coeff_system(
+    a: &Matrix,
+    indep: &str,
+    initial: Option<&[Node]>,
+    env: &Environment,
+) -> Result<Vec<Node>, String> {
+    let n = a.rows;
+    let y0: Vec<Node> = match initial {
+        Some(values) if values.len() != n => {
+            return Err(format!(
+                "Expected {} initial values for a {}×{} system, got {}",
+                n,
+                n,
+                n,
+                values.len()
+            ))
+        }
+        Some(values) => values.to_vec(),
+        None => (1..=n).map(|i| var(&format!("C_{{{}}}", i))).collect(),
+    };
+    let exponential = matrix_exponential(a, indep, env)?;
+    let solution = exponential.multiply(&Matrix::new(n, 1, y0)?, env)?;
+    Ok(solution.elements)
+}
+
+/// Solve `\vec{y}' = A\vec{y}` from a LaTeX matrix: one `y_{i} = …` per
+/// component, with constants `C_{i}`.
+pub fn solve_constant_coeff_system_latex(
+    matrix_latex: &str,
+    indep: &str,
+) -> Result<String, String> {
+    let env = Environment::new();
+    let a = parse_latex_matrix(matrix_latex, &env)?;
+    let solution = solve_constant_coeff_system(&a, indep, None, &env)?;
+    Ok(solution
+        .iter()
+        .enumerate()
+        .map(|(i, y)| format!("y_{{{}}} = {}", i + 1, y))
+        .collect::<Vec<_>>()
+        .join(", "))
+}
+
+/// The eigenvalues of a matrix with rational entries, with multiplicity,
+/// when its characteristic polynomial splits over Q into linear and real
+/// quadratic factors. [`Matrix::eigenvalues`] answers numeric matrices in
+/// floating point, which would split a double eigenvalue in two.
+fn exact_eigenvalues(a: &Matrix, env: &Environment) -> Option<Vec<Node>> {
+    let char_poly = a.characteristic_polynomial(env).ok()?;
+    let (_, factors) = crate::mod_poly::factor_over_q(&char_poly);
+    let mut values = Vec::with_capacity(a.rows);
+    for factor in factors {
+        let coefficient = |i: usize| Node::Num(ExactNum::Rational(factor.coeff(i)));
+        match factor.degree()? {
+            1 => values.push(Node::Negate(Box::new(coefficient(0))).simplify(env).ok()?),
+            2 => {
+                // Monic λ² + pλ + q: λ = −p/2 ± √(p²/4 − q)
+                let half = Node::Divide(Box::new(coefficient(1)), Box::new(num(2)));
+                let discriminant = Node::Subtract(
+                    Box::new(Node::Power(Box::new(half.clone()), Box::new(num(2)))),
+                    Box::new(coefficient(0)),
+                )
+                .simplify(env)
+                .ok()?;
+                if matches!(&discriminant, Node::Num(d) if d.is_negative()) {
+                    return None;
+                }
+                let root = Node::Sqrt(Box::new(discriminant));
+                let centre = Node::Negate(Box::new(half));
+                for value in [
+                    add(centre.clone(), root.clone()),
+                    Node::Subtract(Box::new(centre), Box::new(root)),
+                ] {
+                    values.push(value.simplify(env).ok()?);
+                }
+            }
+            _ => return None,
+        }
+    }
+    (values.len() == a.rows).then_some(values)
+}
+
+/// `(g, m)` for the term `t^m e^{λ_g t}`
+type ExpTerm = (usize, usize);
+
+/// `A − λI`
+fn shift(a: &Matrix, lambda: &Node, env: &Environment) -> Result<Matrix, String> {
+    let n = a.rows;
+    let mut shifted = a.clone();
+    for i in 0..n {
+        let entry = &mut shifted.elements[i * n + i];
+        *entry = Node::Subtract(Box::new(entry.clone()), Box::new(lambda.clone())).simplify(env)?;
+    }
+    Ok(shifted)
+}
+
+/// The divided difference of `λ ↦ e^{λt}` over the eigenvalues
+/// `distinct[g]` for `g` in `groups`, where equal ones are adjacent, as
+/// coefficients of `t^m e^{λ_g t}` keyed by `(g, m)`.
+fn exp_divided_difference(
+    groups: &[usize],
+    distinct: &[Node],
+    env: &Environment,
+) -> Result<Vec<(ExpTerm, Node)>, String> {
+    let (first, last) = (groups[0], groups[groups.len() - 1]);
+    if first == last {
+        // All equal: the m-th derivative over m!, t^m e^{λt}/m!
+        let m = groups.len() - 1;
+        let factorial: i64 = (1..=m as i64).product();
+        let coefficient = Node::Num(ExactNum::rational(1, factorial));
+        return Ok(vec![((first, m), coefficient)]);
+    }
+    let gap = Node::Subtract(
+        Box::new(distinct[last].clone()),
+        Box::new(distinct[first].clone()),
+    );
+    let mut terms = exp_divided_difference(&groups[1..], distinct, env)?;
+    for (key, c) in exp_divided_difference(&groups[..groups.len() - 1], distinct, env)? {
+        let negated = Node::Negate(Box::new(c));
+        match terms.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = add(existing.clone(), negated),
+            None => terms.push((key, negated)),
+        }
+    }
+    terms
+        .into_iter()
+        .map(|(key, c)| {
+            let divided = Node::Divide(Box::new(c), Box::new(gap.clone())).simplify(env)?;
+            Ok((key, divided))
+        })
+        .collect()
+}
+
+/// The real form of `e^{At}` when the 2×2 matrix `A` has complex
+/// eigenvalues, or `None` when its discriminant is not a negative number.
+fn exponential_2x2_complex(
+    a: &Matrix,
+    t: &Node,
+    env: &Environment,
+) -> Result<Option<Matrix>, String> {
+    let e = &a.elements;
+    let half = |node: Node| Node::Divide(Box::new(node), Box::new(num(2)));
+    let s = half(add(e[0].clone(), e[3].clone())).simplify(env)?;
+    let det = Node::Subtract(
+        Box::new(mul(e[0].clone(), e[3].clone())),
+        Box::new(mul(e[1].clone(), e[2].clone())),
+    );
+    // Eigenvalues s ± √(s² − det)
+    let discriminant = Node::Subtract(
+        Box::new(Node::Power(Box::new(s.clone()), Box::new(num(2)))),
+        Box::new(det),
+    )
+    .simplify(env)?;
+    match &discriminant {
+        Node::Num(d) if d.is_negative() => {}
+        _ => return Ok(None),
+    }
+    let omega = Node::Sqrt(Box::new(Node::Negate(Box::new(discriminant)))).simplify(env)?;
+    let omega_t = mul(omega.clone(), t.clone());
+    let envelope = exp(mul(s.clone(), t.clone()));
+    let shifted = shift(a, &s, env)?;
+    let elements = (0..4)
+        .map(|k| {
+            let diagonal = if k == 0 || k == 3 {
+                cos(omega_t.clone())
+            } else {
+                num(0)
+            };
+            let sine_coefficient = Node::Divide(
+                Box::new(shifted.elements[k].clone()),
+                Box::new(omega.clone()),
+            )
+            .simplify(env)?;
+            let entry = add(diagonal, mul(sine_coefficient, sin(omega_t.clone())));
+            mul(envelope.clone(), entry).simplify(env)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Some(Matrix::new(2, 2, elements)?))
+}
+
 /// Try to solve dy/dx = f(x,y) as a separable ODE.
 /// Separable means f(x,y) = g(x) * h(y).
 fn try_separable(rhs: &Node, indep: &str, dep: &str) -> Option<Node> {
@@ -462,6 +749,73 @@ pub fn solve_ode_latex(rhs_latex: &str, indep: &str, dep: &str) -> Result<String
     }
 }
 
+/// Solve a first-order ODE written as an equation in `dep` and its
+/// derivative, `y' + 2y = x` (also `y^{\prime}` or `\frac{dy}{dx}`), by
+/// solving the equation for `y'` and passing the result to
+/// [`solve_first_order`]. The equation must be linear in `y'`.
+pub fn solve_ode_equation_latex(
+    equation_latex: &str,
+    indep: &str,
+    dep: &str,
+) -> Result<String, String> {
+    // The derivative becomes an ordinary variable: `y_{prime}` parses as
+    // `y_prime`
+    let derivative = format!("{}_prime", dep);
+    let subscripted = format!("{}_{{prime}}", dep);
+    let dep_re = regex::escape(dep);
+    let second = regex::Regex::new(&format!(
+        r"(^|[^\\A-Za-z]){}(''|\^\{{?\\prime\\prime)",
+        dep_re
+    ))
+    .map_err(|e| e.to_string())?;
+    if second.is_match(equation_latex) {
+        return Err("Only first-order equations are supported".to_string());
+    }
+    let notations = [
+        format!(
+            r"\\frac\{{\s*d\s*{}\s*\}}\{{\s*d\s*{}\s*\}}",
+            dep_re,
+            regex::escape(indep)
+        ),
+        format!(r"(^|[^\\A-Za-z]){}(?:'|\^\{{\\prime\}}|\^\\prime)", dep_re),
+    ];
+    let mut rewritten = equation_latex.to_string();
+    for (i, pattern) in notations.iter().enumerate() {
+        let re = regex::Regex::new(pattern).map_err(|e| e.to_string())?;
+        let replacement = if i == 0 {
+            subscripted.clone()
+        } else {
+            format!("${{1}}{}", subscripted)
+        };
+        rewritten = re
+            .replace_all(&rewritten, replacement.as_str())
+            .into_owned();
+    }
+    let mut tokenizer = Tokenizer::new(&rewritten);
+    let (lhs, rhs) = match build_expression_tree(tokenizer.tokenize())? {
+        Node::Equation(lhs, rhs) => (*lhs, *rhs),
+        _ => return Err(format!("Expected an equation in {dep} and {dep}'")),
+    };
+    if !contains_var(&lhs, &derivative) && !contains_var(&rhs, &derivative) {
+        return Err(format!("The equation has no derivative {dep}' of {dep}"));
+    }
+    // g = a·y' + b, so y' = −b/a
+    let g = Node::Subtract(Box::new(lhs), Box::new(rhs));
+    let a = simplify(&crate::derivative::differentiate(&g, &derivative).map_err(String::from)?);
+    if contains_var(&a, &derivative) {
+        return Err(format!("The equation must be linear in {dep}'"));
+    }
+    let b = crate::substitute::substitute_variable(&g, &derivative, &Node::Num(ExactNum::zero()))?;
+    let minus_b = simplify(&Node::Negate(Box::new(b)));
+    let slope = simplify(&Node::Divide(Box::new(minus_b), Box::new(a)));
+    let solution = solve_first_order(&slope, indep, dep)?;
+    if let Node::Equation(_, _) = &solution {
+        Ok(format!("{}", solution))
+    } else {
+        Ok(format!("{} = {}", dep, solution))
+    }
+}
+
 /// Solve a second-order constant-coefficient ODE from numeric coefficients.
 pub fn solve_constant_coeff_latex(a: f64, b: f64, c: f64, indep: &str) -> Result<String, String> {
     let a_num = ExactNum::from_f64(a);
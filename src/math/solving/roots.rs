@@ -0,0 +1,222 @@
+//! Numeric roots of arbitrary expressions.
+//!
+//! For equations the symbolic solver cannot invert, such as `\cos{x} = x`.
+//! An equation `a = b` is solved as `a - b = 0`. Both the function and its
+//! symbolic derivative are compiled once, and Newton's method runs on them.
+//! Where Newton fails (a flat tangent, a step that leaves the domain, no
+//! convergence) the root is bracketed by a sign change and found by
+//! bisection, with Newton steps taken whenever they stay inside the
+//! bracket.
+
+use crate::compile::CompiledExpr;
+use crate::derivative::differentiate;
+use crate::evaluator::Evaluator;
+use crate::node::Node;
+
+const MAX_NEWTON_STEPS: usize = 50;
+const MAX_BRACKET_STEPS: usize = 200;
+/// Subintervals searched for a sign change by [`find_root`] at each width
+const BRACKET_POINTS: usize = 64;
+/// Subintervals scanned for sign changes by [`find_roots_in_interval`]
+const SCAN_POINTS: usize = 1000;
+
+/// A root of `expr` (or of `lhs - rhs` for an equation) near `guess`.
+pub fn find_root(expr: &Node, var: &str, guess: f64) -> Result<f64, String> {
+    if !guess.is_finite() {
+        return Err(format!("The starting guess {} is not finite", guess));
+    }
+    let f = RootFunction::new(expr, var)?;
+    if let Some(root) = f.newton(guess) {
+        return Ok(root);
+    }
+    // Widen an interval around the guess until f changes sign somewhere
+    // in it, and take the root nearest the guess
+    let mut width = 0.1 * guess.abs().max(1.0);
+    while width <= 1e12 {
+        let xs: Vec<f64> = (0..=BRACKET_POINTS)
+            .map(|i| guess - width + 2.0 * width * i as f64 / BRACKET_POINTS as f64)
+            .collect();
+        let nearest = xs
+            .windows(2)
+            .filter_map(|w| f.bracketed(w[0], w[1]))
+            .min_by(|a, b| (a - guess).abs().total_cmp(&(b - guess).abs()));
+        if let Some(root) = nearest {
+            return Ok(root);
+        }
+        width *= 4.0;
+    }
+    Err(format!(
+        "No root of {} found near {} = {}",
+        f.expr, var, guess
+    ))
+}
+
+/// The roots of `expr` (or of `lhs - rhs` for an equation) in `[lo, hi]`,
+/// in increasing order.
+///
+/// The interval is scanned on a fine grid: each sign change is refined by
+/// bisection, and a root where f touches zero without crossing is found
+/// by Newton's method from the grid point where |f| is smallest. A sign
+/// change across a pole is not a root and is dropped, as are roots
+/// closer together than the grid can separate.
+pub fn find_roots_in_interval(
+    expr: &Node,
+    var: &str,
+    lo: f64,
+    hi: f64,
+) -> Result<Vec<f64>, String> {
+    if !(lo.is_finite() && hi.is_finite() && lo < hi) {
+        return Err(format!("Invalid interval [{}, {}]", lo, hi));
+    }
+    let f = RootFunction::new(expr, var)?;
+    let step = (hi - lo) / SCAN_POINTS as f64;
+    let xs: Vec<f64> = (0..=SCAN_POINTS)
+        .map(|i| {
+            if i == SCAN_POINTS {
+                hi
+            } else {
+                lo + step * i as f64
+            }
+        })
+        .collect();
+    let ys: Vec<f64> = xs.iter().map(|&x| f.value(x)).collect();
+
+    let mut roots = Vec::new();
+    for i in 0..SCAN_POINTS {
+        let (y0, y1) = (ys[i], ys[i + 1]);
+        if y0 == 0.0 {
+            roots.push(xs[i]);
+        } else if y0.is_finite() && y1.is_finite() && y0.signum() != y1.signum() && y1 != 0.0 {
+            if let Some(root) = f.bracketed(xs[i], xs[i + 1]) {
+                roots.push(root);
+            }
+        } else if i > 0 && ys[i - 1].is_finite() && y0.is_finite() && y1.is_finite() {
+            // |f| dips towards zero at xs[i] without a sign change
+            let dip = y0.abs() < ys[i - 1].abs() && y0.abs() <= y1.abs();
+            if dip && ys[i - 1].signum() == y0.signum() {
+                if let Some(root) = f.newton(xs[i]) {
+                    if root >= xs[i - 1] && root <= xs[i + 1] {
+                        roots.push(root);
+                    }
+                }
+            }
+        }
+    }
+    if ys[SCAN_POINTS] == 0.0 {
+        roots.push(hi);
+    }
+
+    roots.sort_by(f64::total_cmp);
+    roots.dedup_by(|a, b| (*a - *b).abs() <= 1e-9 * b.abs().max(1.0));
+    Ok(roots)
+}
+
+/// f and f′ compiled for evaluation at many points.
+struct RootFunction {
+    expr: Node,
+    f: CompiledExpr,
+    df: Option<CompiledExpr>,
+}
+
+impl RootFunction {
+    fn new(expr: &Node, var: &str) -> Result<Self, String> {
+        let expr = match expr {
+            Node::Equation(lhs, rhs) => Node::Subtract(lhs.clone(), rhs.clone()),
+            other => other.clone(),
+        };
+        let f = Evaluator::compile(&expr, &[var]).map_err(|e| e.to_string())?;
+        // Without a derivative every root is found by bisection
+        let df = differentiate(&expr, var)
+            .ok()
+            .and_then(|d| Evaluator::compile(&d, &[var]).ok());
+        Ok(RootFunction { expr, f, df })
+    }
+
+    fn value(&self, x: f64) -> f64 {
+        self.f.eval(&[x])
+    }
+
+    fn slope(&self, x: f64) -> Option<f64> {
+        let d = self.df.as_ref()?.eval(&[x]);
+        (d.is_finite() && d != 0.0).then_some(d)
+    }
+
+    /// Newton's method from `x`, or `None` if it does not converge to a
+    /// point where f vanishes.
+    fn newton(&self, mut x: f64) -> Option<f64> {
+        for _ in 0..MAX_NEWTON_STEPS {
+            let y = self.value(x);
+            if y == 0.0 {
+                return Some(x);
+            }
+            let next = x - y / self.slope(x)?;
+            if !next.is_finite() {
+                return None;
+            }
+            if (next - x).abs() <= 1e-14 * x.abs().max(1.0) {
+                return self.is_root(next).then_some(next);
+            }
+            x = next;
+        }
+        self.is_root(x).then_some(x)
+    }
+
+    /// A root in `[a, b]` if f changes sign across it, by Newton steps
+    /// that stay inside the shrinking bracket and bisection otherwise.
+    fn bracketed(&self, mut a: f64, mut b: f64) -> Option<f64> {
+        let (mut fa, fb) = (self.value(a), self.value(b));
+        let ends = fa.abs().min(fb.abs());
+        if fa == 0.0 {
+            return Some(a);
+        }
+        if fb == 0.0 {
+            return Some(b);
+        }
+        if !(fa.is_finite() && fb.is_finite()) || fa.signum() == fb.signum() {
+            return None;
+        }
+        let mut x = 0.5 * (a + b);
+        for _ in 0..MAX_BRACKET_STEPS {
+            let y = self.value(x);
+            if y == 0.0 {
+                return Some(x);
+            }
+            if !y.is_finite() {
+                return None;
+            }
+            if y.signum() == fa.signum() {
+                a = x;
+                fa = y;
+            } else {
+                b = x;
+            }
+            let tolerance = 1e-15 * x.abs().max(1.0);
+            if (b - a).abs() <= tolerance {
+                break;
+            }
+            let (lo, hi) = (a.min(b), a.max(b));
+            match self.slope(x).map(|d| x - y / d) {
+                Some(next) if next > lo && next < hi => {
+                    let converged = (next - x).abs() <= tolerance;
+                    x = next;
+                    if converged {
+                        break;
+                    }
+                }
+                _ => x = 0.5 * (a + b),
+            }
+        }
+        // A sign change across a pole shrinks onto it with |f| growing
+        (self.value(x).abs() <= ends).then_some(x)
+    }
+
+    fn is_root(&self, x: f64) -> bool {
+        let y = self.value(x);
+        if !y.is_finite() {
+            return false;
+        }
+        // Compare |f| with the size of f's terms near x through its slope
+        let scale = self.slope(x).map_or(1.0, |d| d.abs() * x.abs().max(1.0));
+        y.abs() <= 1e-9 * scale.max(1.0)
+    }
+}
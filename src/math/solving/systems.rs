@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use num_rational::BigRational;
 use num_traits::{One, Zero};
 
@@ -5,6 +7,7 @@ use crate::derivative::differentiate;
 use crate::environment::Environment;
 use crate::exact::ExactNum;
 use crate::expression::solve_full;
+use crate::matrix::Matrix;
 use crate::node::Node;
 use crate::simplify::Simplifiable;
 use crate::substitute::substitute_variable;
@@ -94,9 +97,89 @@ pub fn solve_system(equations: &[Node], vars: &[String]) -> Result<SystemSolutio
         return Ok(solution);
     }
 
+    if let Some(solution) = solve_symbolic_linear_system(equations, vars) {
+        return Ok(solution);
+    }
+
     solve_by_substitution(equations, vars)
 }
 
+/// Solve a system that must have exactly one solution, keyed by variable:
+/// `x + y = 5, x - y = 1` → `{x: 3, y: 2}`. Several solution sets,
+/// a parametric family, or no solution are reported as errors.
+pub fn solve_system_unique(
+    equations: &[Node],
+    vars: &[&str],
+) -> Result<HashMap<String, Node>, String> {
+    let vars: Vec<String> = vars.iter().map(|v| v.to_string()).collect();
+    match solve_system(equations, &vars)? {
+        SystemSolution::Unique(solution) => Ok(solution.into_iter().collect()),
+        SystemSolution::Multiple(sets) => Err(format!(
+            "System has {} solutions, not a unique one",
+            sets.len()
+        )),
+        SystemSolution::Parametric { free_vars, .. } => Err(format!(
+            "System has infinitely many solutions (free: {})",
+            free_vars.join(", ")
+        )),
+        SystemSolution::NoSolution => Err("System has no solution".to_string()),
+    }
+}
+
+/// Square linear systems whose coefficients involve other symbols
+/// (`a x + b y = c, d x + e y = f`): solve `A·v = b` with [`Matrix::solve`].
+/// The result is the generic solution, valid wherever `det A ≠ 0`. `None`
+/// when the system is not linear in `vars`, not square, or singular.
+fn solve_symbolic_linear_system(equations: &[Node], vars: &[String]) -> Option<SystemSolution> {
+    if equations.len() != vars.len() {
+        return None;
+    }
+    let env = Environment::new();
+    let zero = Node::Num(ExactNum::integer(0));
+
+    let mut coefficients = Vec::with_capacity(equations.len());
+    let mut rhs = Vec::with_capacity(equations.len());
+    for eq in equations {
+        let Node::Equation(lhs, r) = eq else {
+            return None;
+        };
+        let expr = Node::Subtract(lhs.clone(), r.clone());
+        let mut row = Vec::with_capacity(vars.len());
+        for var in vars {
+            let deriv = differentiate(&expr, var).ok()?;
+            let deriv = deriv.simplify(&env).unwrap_or(deriv);
+            if vars.iter().any(|v| contains_var(&deriv, v)) {
+                return None;
+            }
+            row.push(deriv);
+        }
+        let mut constant = expr;
+        for var in vars {
+            constant = substitute_variable(&constant, var, &zero).ok()?;
+        }
+        let constant = Node::Negate(Box::new(constant));
+        rhs.push(vec![constant.simplify(&env).unwrap_or(constant)]);
+        coefficients.push(row);
+    }
+
+    let a = Matrix::from_elements(coefficients).ok()?;
+    let b = Matrix::from_elements(rhs).ok()?;
+    let det = a.determinant(&env).ok()?;
+    if matches!(&det, Node::Num(n) if n.is_zero()) {
+        return None;
+    }
+    let solution = a.solve(&b, &env).ok()?;
+    let values = vars
+        .iter()
+        .enumerate()
+        .map(|(i, var)| {
+            let value = solution.get(i, 0).ok()?.clone();
+            Some((var.clone(), value.simplify(&env).unwrap_or(value)))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(SystemSolution::Unique(values))
+}
+
 fn solve_by_substitution(equations: &[Node], vars: &[String]) -> Result<SystemSolution, String> {
     if equations.len() == 1 && vars.len() == 1 {
         let result = solve_full(&equations[0], &vars[0])?;
@@ -306,6 +389,7 @@ fn contains_var(node: &Node, var: &str) -> bool {
         | Node::Divide(a, b)
         | Node::Power(a, b) => contains_var(a, var) || contains_var(b, var),
         Node::Negate(inner)
+        | Node::Not(inner)
         | Node::Sqrt(inner)
         | Node::Abs(inner)
         | Node::Floor(inner)
@@ -319,6 +403,8 @@ fn contains_var(node: &Node, var: &str) -> bool {
         | Node::Less(a, b)
         | Node::GreaterEqual(a, b)
         | Node::LessEqual(a, b)
+        | Node::And(a, b)
+        | Node::Or(a, b)
         | Node::Equal(a, b) => contains_var(a, var) || contains_var(b, var),
         Node::Summation(_, start, end, body) => {
             contains_var(start, var) || contains_var(end, var) || contains_var(body, var)
@@ -326,9 +412,20 @@ fn contains_var(node: &Node, var: &str) -> bool {
         Node::Product(_, start, end, body) => {
             contains_var(start, var) || contains_var(end, var) || contains_var(body, var)
         }
+        Node::Integral(_, bounds, body) => {
+            bounds
+                .as_ref()
+                .is_some_and(|(lo, hi)| contains_var(lo, var) || contains_var(hi, var))
+                || contains_var(body, var)
+        }
+        Node::Derivative(_, body) => contains_var(body, var),
+        Node::Limit(_, point, _, body) | Node::SumOver(_, point, body) => {
+            contains_var(point, var) || contains_var(body, var)
+        }
         Node::Piecewise(cases) => cases
             .iter()
             .any(|(val, cond)| contains_var(val, var) || contains_var(cond, var)),
+        Node::Matrix(rows) => rows.iter().flatten().any(|e| contains_var(e, var)),
     }
 }
 
@@ -690,4 +787,66 @@ mod tests {
             _ => panic!("Expected solutions, got {:?}", result),
         }
     }
+
+    #[test]
+    fn test_symbolic_coefficients_2x2() {
+        let result = solve_sys_poly(&["a x + y = 1", "x - y = 0"], &["x", "y"]).unwrap();
+        match &result {
+            SystemSolution::Unique(solutions) => {
+                let mut env = Environment::new();
+                env.set("a", 3.0);
+                for (var, value) in solutions {
+                    let v = crate::Evaluator::evaluate(value, &env).unwrap();
+                    assert!((v - 0.25).abs() < 1e-12, "{} = {}", var, value);
+                }
+            }
+            _ => panic!("Expected a unique solution, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_symbolic_coefficients_general_2x2() {
+        // Cramer: x = (ce − bf)/(ae − bd), y = (af − cd)/(ae − bd)
+        let result = solve_sys_poly(&["a x + b y = c", "d x + e y = f"], &["x", "y"]).unwrap();
+        let SystemSolution::Unique(solutions) = result else {
+            panic!("Expected a unique solution");
+        };
+        let mut env = Environment::new();
+        for (name, value) in [
+            ("a", 2.0),
+            ("b", 3.0),
+            ("c", 4.0),
+            ("d", 5.0),
+            ("e", 7.0),
+            ("f", 1.0),
+        ] {
+            env.set(name, value);
+        }
+        let x = crate::Evaluator::evaluate(&solutions[0].1, &env).unwrap();
+        let y = crate::Evaluator::evaluate(&solutions[1].1, &env).unwrap();
+        assert!((x + 25.0).abs() < 1e-9, "x = {}", x);
+        assert!((y - 18.0).abs() < 1e-9, "y = {}", y);
+    }
+
+    #[test]
+    fn test_solve_system_unique_map() {
+        let equations = vec![parse("x + y = 5"), parse("x - y = 1")];
+        let solution = solve_system_unique(&equations, &["x", "y"]).unwrap();
+        assert_eq!(solution["x"].to_string(), "3");
+        assert_eq!(solution["y"].to_string(), "2");
+    }
+
+    #[test]
+    fn test_solve_system_unique_rejects_non_unique() {
+        let circle = vec![parse("x + y = 1"), parse("x^2 + y^2 = 1")];
+        let err = solve_system_unique(&circle, &["x", "y"]).unwrap_err();
+        assert!(err.contains("2 solutions"), "{}", err);
+
+        let inconsistent = vec![parse("x + y = 1"), parse("x + y = 2")];
+        assert!(solve_system_unique(&inconsistent, &["x", "y"]).is_err());
+
+        let dependent = vec![parse("x + y = 1"), parse("2x + 2y = 2")];
+        let err = solve_system_unique(&dependent, &["x", "y"]).unwrap_err();
+        assert!(err.contains("infinitely many"), "{}", err);
+    }
 }
@@ -0,0 +1,343 @@
+//! Expressions compiled for repeated numeric evaluation.
+//!
+//! [`Evaluator::evaluate`] walks the tree and does exact arithmetic on every
+//! call, which is what a single answer wants and far too slow for a plot or
+//! a Monte-Carlo estimate that evaluates the same expression at thousands
+//! of points. [`Evaluator::compile`] turns the tree into a chain of closures
+//! over `f64` once; [`CompiledExpr::eval`] then runs it on a slice of
+//! variable values.
+//!
+//! Subtrees that do not depend on the compiled variables are evaluated
+//! exactly at compile time and become constants. Σ and Π over an integer
+//! range, derivatives (through their symbolic derivative) and the built-in
+//! functions compile natively; anything else that depends on the variables,
+//! such as an integral with variable bounds, falls back to the tree walk at
+//! each point. Where the tree walk would fail, the compiled expression gives
+//! NaN, as evaluation under [`DomainPolicy::Nan`] does for arguments outside
+//! a function's domain.
+
+use std::fmt;
+
+use crate::constants::Constant;
+use crate::domains::{principal_branch, DomainPolicy};
+use crate::environment::Environment;
+use crate::error::ArithmaError;
+use crate::evaluator::Evaluator;
+use crate::exact::ExactNum;
+use crate::functions::{call_function_with_policy, undefined_function, FUNCTION_REGISTRY};
+use crate::matrix;
+use crate::node::Node;
+use crate::status::free_variables;
+use crate::vector;
+
+type Code = Box<dyn Fn(&[f64]) -> f64 + Send + Sync>;
+
+/// An expression compiled by [`Evaluator::compile`].
+pub struct CompiledExpr {
+    variables: Vec<String>,
+    code: Code,
+}
+
+impl CompiledExpr {
+    pub(crate) fn new(expr: &Node, vars: &[&str]) -> Result<Self, ArithmaError> {
+        let variables: Vec<String> = vars.iter().map(|v| v.to_string()).collect();
+        if let Some(unknown) = free_variables(&[expr])
+            .into_iter()
+            .find(|v| !variables.contains(v))
+        {
+            return Err(ArithmaError::Eval(format!(
+                "Variable '{}' is not defined.",
+                unknown
+            )));
+        }
+        let code = compile(expr, &variables)?.into_code();
+        Ok(CompiledExpr { variables, code })
+    }
+
+    /// The value at `values`, given in the order of the variables the
+    /// expression was compiled for.
+    ///
+    /// # Panics
+    ///
+    /// If there are not as many values as variables.
+    pub fn eval(&self, values: &[f64]) -> f64 {
+        assert_eq!(
+            values.len(),
+            self.variables.len(),
+            "expected a value for each of {:?}",
+            self.variables
+        );
+        (self.code)(values)
+    }
+
+    /// The variables, in the order [`eval`](Self::eval) takes their values.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+}
+
+impl fmt::Debug for CompiledExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompiledExpr")
+            .field("variables", &self.variables)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A compiled subtree: a constant, folded into its parent, or code reading
+/// the variable values.
+enum Compiled {
+    Const(f64),
+    Code(Code),
+}
+
+impl Compiled {
+    fn into_code(self) -> Code {
+        match self {
+            Compiled::Const(c) => Box::new(move |_| c),
+            Compiled::Code(code) => code,
+        }
+    }
+
+    fn map(self, f: impl Fn(f64) -> f64 + Send + Sync + 'static) -> Compiled {
+        match self {
+            Compiled::Const(c) => Compiled::Const(f(c)),
+            Compiled::Code(a) => Compiled::Code(Box::new(move |v| f(a(v)))),
+        }
+    }
+
+    fn zip(self, other: Compiled, f: impl Fn(f64, f64) -> f64 + Send + Sync + 'static) -> Compiled {
+        match (self, other) {
+            (Compiled::Const(a), Compiled::Const(b)) => Compiled::Const(f(a, b)),
+            (Compiled::Const(a), Compiled::Code(b)) => {
+                Compiled::Code(Box::new(move |v| f(a, b(v))))
+            }
+            (Compiled::Code(a), Compiled::Const(b)) => {
+                Compiled::Code(Box::new(move |v| f(a(v), b)))
+            }
+            (Compiled::Code(a), Compiled::Code(b)) => {
+                Compiled::Code(Box::new(move |v| f(a(v), b(v))))
+            }
+        }
+    }
+}
+
+/// A connective on truth values: nonzero is true, and an undefined operand
+/// leaves the result undefined.
+fn logical(a: f64, b: f64, holds: fn(bool, bool) -> bool) -> f64 {
+    if a.is_nan() || b.is_nan() {
+        f64::NAN
+    } else if holds(a != 0.0, b != 0.0) {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// `slots` names the value at each index of the slice the code reads; a
+/// Σ or Π index is appended after the compiled variables, so the last slot
+/// of a name wins.
+fn compile(node: &Node, slots: &[String]) -> Result<Compiled, ArithmaError> {
+    let truth = |holds: bool| if holds { 1.0 } else { 0.0 };
+    Ok(match node {
+        Node::Num(n) => Compiled::Const(n.to_f64()),
+        Node::Variable(name) => match slots.iter().rposition(|s| s == name) {
+            Some(i) => Compiled::Code(Box::new(move |v| v[i])),
+            None => {
+                if let Some(constant) = Constant::from_name(name) {
+                    return Ok(Compiled::Const(constant.value()));
+                }
+                return Err(ArithmaError::Eval(format!(
+                    "Variable '{}' is not defined.",
+                    name
+                )));
+            }
+        },
+        Node::Add(l, r) => compile(l, slots)?.zip(compile(r, slots)?, |a, b| a + b),
+        Node::Subtract(l, r) | Node::Equation(l, r) => {
+            compile(l, slots)?.zip(compile(r, slots)?, |a, b| a - b)
+        }
+        Node::Multiply(l, r) => compile(l, slots)?.zip(compile(r, slots)?, |a, b| a * b),
+        Node::Divide(l, r) => compile(l, slots)?.zip(compile(r, slots)?, divide),
+        Node::Power(base, exponent) => {
+            let base = compile(base, slots)?;
+            match compile(exponent, slots)? {
+                Compiled::Const(n) if n.fract() == 0.0 && n.abs() <= i32::MAX as f64 => {
+                    base.map(move |b| b.powi(n as i32))
+                }
+                exponent => base.zip(exponent, f64::powf),
+            }
+        }
+        Node::Negate(inner) => compile(inner, slots)?.map(|a| -a),
+        Node::Sqrt(inner) => compile(inner, slots)?.map(f64::sqrt),
+        Node::Abs(inner) if !vector::is_vector_expression(inner) => {
+            compile(inner, slots)?.map(f64::abs)
+        }
+        Node::Floor(inner) => compile(inner, slots)?.map(f64::floor),
+        Node::Ceil(inner) => compile(inner, slots)?.map(f64::ceil),
+        Node::Round(inner) => compile(inner, slots)?.map(f64::round),
+        Node::Trunc(inner) => compile(inner, slots)?.map(f64::trunc),
+        Node::Factorial(inner) => compile(inner, slots)?.map(|n| {
+            crate::special::factorial(&ExactNum::from_f64(n)).map_or(f64::NAN, |f| f.to_f64())
+        }),
+        Node::Greater(l, r) => compile(l, slots)?.zip(compile(r, slots)?, move |a, b| truth(a > b)),
+        Node::Less(l, r) => compile(l, slots)?.zip(compile(r, slots)?, move |a, b| truth(a < b)),
+        Node::GreaterEqual(l, r) => {
+            compile(l, slots)?.zip(compile(r, slots)?, move |a, b| truth(a >= b))
+        }
+        Node::LessEqual(l, r) => {
+            compile(l, slots)?.zip(compile(r, slots)?, move |a, b| truth(a <= b))
+        }
+        Node::Equal(l, r) => compile(l, slots)?.zip(compile(r, slots)?, move |a, b| truth(a == b)),
+        Node::And(l, r) => {
+            compile(l, slots)?.zip(compile(r, slots)?, move |a, b| logical(a, b, |p, q| p && q))
+        }
+        Node::Or(l, r) => {
+            compile(l, slots)?.zip(compile(r, slots)?, move |a, b| logical(a, b, |p, q| p || q))
+        }
+        Node::Not(inner) => compile(inner, slots)?.map(move |a| logical(a, a, |p, _| !p)),
+        Node::Piecewise(arms) => {
+            let arms = arms
+                .iter()
+                .map(|(value, condition)| {
+                    Ok((
+                        compile(value, slots)?.into_code(),
+                        compile(condition, slots)?.into_code(),
+                    ))
+                })
+                .collect::<Result<Vec<_>, ArithmaError>>()?;
+            Compiled::Code(Box::new(move |v| {
+                arms.iter()
+                    .find(|(_, condition)| condition(v) == 1.0)
+                    .map_or(f64::NAN, |(value, _)| value(v))
+            }))
+        }
+        Node::Summation(index, start, end, body) | Node::Product(index, start, end, body)
+            if depends_on(node, slots) =>
+        {
+            let is_sum = matches!(node, Node::Summation(..));
+            let start = compile(start, slots)?.into_code();
+            let end = compile(end, slots)?.into_code();
+            let mut body_slots = slots.to_vec();
+            body_slots.push(index.clone());
+            let body = compile(body, &body_slots)?.into_code();
+            Compiled::Code(Box::new(move |v| {
+                let (start, end) = (start(v), end(v));
+                if start.fract() != 0.0 || end.fract() != 0.0 {
+                    return f64::NAN;
+                }
+                let mut scope = v.to_vec();
+                scope.push(0.0);
+                let index = scope.len() - 1;
+                let mut acc = if is_sum { 0.0 } else { 1.0 };
+                let mut k = start;
+                while k <= end {
+                    scope[index] = k;
+                    let term = body(&scope);
+                    acc = if is_sum { acc + term } else { acc * term };
+                    k += 1.0;
+                }
+                acc
+            }))
+        }
+        Node::Derivative(var, body) if depends_on(node, slots) => {
+            match crate::derivative::differentiate(body, var) {
+                Ok(derivative) => compile(&derivative, slots)?,
+                Err(_) => tree_walk(node, slots),
+            }
+        }
+        Node::Function(name, args)
+            if name != matrix::DETERMINANT && !vector::is_vector_function(name) =>
+        {
+            compile_call(name, args, slots)?
+        }
+        _ if depends_on(node, slots) => tree_walk(node, slots),
+        _ => Compiled::Const(Evaluator::evaluate(node, &Environment::new()).unwrap_or(f64::NAN)),
+    })
+}
+
+fn compile_call(name: &str, args: &[Node], slots: &[String]) -> Result<Compiled, ArithmaError> {
+    // A compiled expression has no environment to define functions in
+    if FUNCTION_REGISTRY.get(name).is_none() {
+        return Err(ArithmaError::Eval(undefined_function(name)));
+    }
+    let mut compiled = args
+        .iter()
+        .map(|arg| compile(arg, slots))
+        .collect::<Result<Vec<_>, ArithmaError>>()?;
+    if let (Some(f), 1) = (native_function(name), compiled.len()) {
+        let arg = compiled.pop().expect("one argument");
+        return Ok(match principal_branch(name) {
+            Some(branch) => arg.map(move |x| if branch.contains(x) { f(x) } else { f64::NAN }),
+            None => arg.map(f),
+        });
+    }
+    let name = name.to_string();
+    let call = move |values: Vec<f64>| {
+        let args = values.into_iter().map(ExactNum::from_f64).collect();
+        call_function_with_policy(&name, args, DomainPolicy::Nan).map_or(f64::NAN, |v| v.to_f64())
+    };
+    if compiled.iter().all(|arg| matches!(arg, Compiled::Const(_))) {
+        let values = compiled
+            .iter()
+            .map(|arg| match arg {
+                Compiled::Const(c) => *c,
+                Compiled::Code(_) => unreachable!("every argument is constant"),
+            })
+            .collect();
+        return Ok(Compiled::Const(call(values)));
+    }
+    let args: Vec<Code> = compiled.into_iter().map(Compiled::into_code).collect();
+    Ok(Compiled::Code(Box::new(move |v| {
+        call(args.iter().map(|arg| arg(v)).collect())
+    })))
+}
+
+/// Built-in functions whose handler is the `f64` function of the same
+/// name, inside the principal domain.
+fn native_function(name: &str) -> Option<fn(f64) -> f64> {
+    Some(match name {
+        "sin" => f64::sin,
+        "cos" => f64::cos,
+        "tan" => f64::tan,
+        "arcsin" | "asin" => f64::asin,
+        "arccos" | "acos" => f64::acos,
+        "arctan" | "atan" => f64::atan,
+        "sinh" => f64::sinh,
+        "cosh" => f64::cosh,
+        "tanh" => f64::tanh,
+        "arcsinh" | "asinh" => f64::asinh,
+        "exp" => f64::exp,
+        "ln" => f64::ln,
+        "log" => f64::log10,
+        "lg" => f64::log2,
+        "sqrt" => f64::sqrt,
+        _ => return None,
+    })
+}
+
+/// Division by zero is undefined in exact arithmetic too.
+fn divide(a: f64, b: f64) -> f64 {
+    if b == 0.0 {
+        f64::NAN
+    } else {
+        a / b
+    }
+}
+
+fn depends_on(node: &Node, slots: &[String]) -> bool {
+    free_variables(&[node]).iter().any(|v| slots.contains(v))
+}
+
+/// `node` evaluated by the tree walk at each point.
+fn tree_walk(node: &Node, slots: &[String]) -> Compiled {
+    let node = node.clone();
+    let slots = slots.to_vec();
+    Compiled::Code(Box::new(move |v| {
+        let mut env = Environment::new();
+        for (name, value) in slots.iter().zip(v) {
+            env.set(name, *value);
+        }
+        Evaluator::evaluate(&node, &env).unwrap_or(f64::NAN)
+    }))
+}
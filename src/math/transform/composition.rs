@@ -85,58 +85,13 @@ pub fn validate_composition(
 
 /// Helper function to collect variables from an expression
 fn collect_variables(node: &Node, vars: &mut Vec<String>) {
-    match node {
-        Node::Variable(name) if !vars.contains(name) => {
-            vars.push(name.clone());
-        }
-        Node::Add(left, right)
-        | Node::Subtract(left, right)
-        | Node::Multiply(left, right)
-        | Node::Divide(left, right)
-        | Node::Power(left, right)
-        | Node::Greater(left, right)
-        | Node::Less(left, right)
-        | Node::GreaterEqual(left, right)
-        | Node::LessEqual(left, right)
-        | Node::Equal(left, right)
-        | Node::Equation(left, right) => {
-            collect_variables(left, vars);
-            collect_variables(right, vars);
-        }
-        Node::Sqrt(operand)
-        | Node::Abs(operand)
-        | Node::Floor(operand)
-        | Node::Ceil(operand)
-        | Node::Round(operand)
-        | Node::Trunc(operand)
-        | Node::Negate(operand)
-        | Node::Factorial(operand) => {
-            collect_variables(operand, vars);
-        }
-        Node::Piecewise(conditions) => {
-            for (expr, cond) in conditions {
-                collect_variables(expr, vars);
-                collect_variables(cond, vars);
-            }
-        }
-        Node::Summation(_index, start, end, body) => {
-            collect_variables(start, vars);
-            collect_variables(end, vars);
-            collect_variables(body, vars);
-        }
-        Node::Product(_index, start, end, body) => {
-            collect_variables(start, vars);
-            collect_variables(end, vars);
-            collect_variables(body, vars);
-        }
-        Node::Function(_, args) => {
-            for arg in args {
-                collect_variables(arg, vars);
+    node.visit(&mut |n| {
+        if let Node::Variable(name) = n {
+            if !vars.contains(name) {
+                vars.push(name.clone());
             }
         }
-        // Other node types don't contain variables
-        _ => {}
-    }
+    });
 }
 
 /// Creates a new function by forming the composition of multiple functions
@@ -180,7 +135,7 @@ mod tests {
     fn parse_expression(latex: &str) -> Result<Node, String> {
         let mut tokenizer = Tokenizer::new(latex);
         let tokens = tokenizer.tokenize();
-        build_expression_tree(tokens)
+        Ok(build_expression_tree(tokens)?)
     }
 
     #[test]
@@ -97,10 +97,9 @@ fn eval(node: &Node, env: &Environment) -> Result<(f64, f64, bool), String> {
                 // The caller's f64 IS the input — the bound measures the
                 // algorithm's error on it, not the caller's intent.
                 Ok((val.to_f64(), 0.0, false))
-            } else if var == "π" {
-                Ok((std::f64::consts::PI, std::f64::consts::PI * EPS, false))
-            } else if var == "e" {
-                Ok((std::f64::consts::E, std::f64::consts::E * EPS, false))
+            } else if let Some(constant) = crate::constants::Constant::from_name(var) {
+                let v = constant.value();
+                Ok((v, v * EPS, false))
             } else {
                 Err(format!("Variable '{}' is not defined.", var))
             }
@@ -255,7 +254,7 @@ fn fold_range(
         return Err("range bounds must be exact integers".to_string());
     }
     let (lo, hi) = (sv as i64, ev as i64);
-    let mut scoped = env.clone();
+    let mut scoped = env.child();
     let mut acc = (identity, 0.0, false);
     for i in lo..=hi {
         scoped.set_exact(index_var, crate::exact::ExactNum::integer(i));
@@ -318,11 +317,14 @@ fn unary_function(name: &str, x: f64, xe: f64) -> Result<(f64, f64), String> {
         }
         "abs" => (x.abs(), 1.0, 0.0),
         "erf" => {
-            let d = 2.0 / std::f64::consts::PI.sqrt() * (-x * x).exp();
-            // erf itself is not in std; refuse rather than approximate the
-            // value — but the model exists if a value source appears.
-            let _ = d;
-            return Err("no f64 evaluation for erf here".to_string());
+            let v = crate::special::erf(x);
+            let d = std::f64::consts::FRAC_2_SQRT_PI * (-x * x).exp();
+            (v, d, v.abs() * 4.0 * EPS)
+        }
+        "erfc" => {
+            let v = crate::special::erfc(x);
+            let d = std::f64::consts::FRAC_2_SQRT_PI * (-x * x).exp();
+            (v, d, v.abs() * 4.0 * EPS)
         }
         _ => return Err(format!("no floating-point error model for '{}'", name)),
     };
@@ -1,28 +1,58 @@
+use crate::compile::CompiledExpr;
+use crate::constants::Constant;
 use crate::environment::Environment;
+use crate::error::ArithmaError;
 use crate::exact::ExactNum;
-use crate::functions::call_function;
+use crate::functions::call_function_with_policy;
+use crate::limits::LimitPoint;
+use crate::matrix::{self, Matrix};
 use crate::node::Node;
+use crate::progress::Job;
 use crate::simplify::Simplifiable;
+use crate::vector;
+
+/// Σ/Π ranges at least this long are evaluated on several threads.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_TERMS: u64 = 10_000;
+/// Terms one thread evaluates at a time.
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK: i64 = 1024;
+/// Chunks between progress reports.
+#[cfg(feature = "parallel")]
+const PARALLEL_BATCH: usize = 64;
 
 pub struct Evaluator;
 
 impl Evaluator {
-    pub fn evaluate(node: &Node, env: &Environment) -> Result<f64, String> {
+    pub fn evaluate(node: &Node, env: &Environment) -> Result<f64, ArithmaError> {
         Self::evaluate_exact(node, env).map(|n| n.to_f64())
     }
 
-    pub fn evaluate_exact(node: &Node, env: &Environment) -> Result<ExactNum, String> {
+    /// `expr` compiled for fast evaluation at many values of `vars`; see
+    /// [`compile`](crate::compile). Fails if `expr` has a free variable not
+    /// among `vars`.
+    pub fn compile(expr: &Node, vars: &[&str]) -> Result<CompiledExpr, ArithmaError> {
+        CompiledExpr::new(expr, vars)
+    }
+
+    pub fn evaluate_exact(node: &Node, env: &Environment) -> Result<ExactNum, ArithmaError> {
         match node {
             Node::Num(n) => Ok(n.clone()),
             Node::Variable(ref var) => {
                 if let Some(val) = env.get_exact(var) {
                     Ok(val.clone())
-                } else if var == "π" {
-                    Ok(ExactNum::Float(std::f64::consts::PI))
-                } else if var == "e" {
-                    Ok(ExactNum::Float(std::f64::consts::E))
+                } else if let Some(constant) = Constant::from_name(var) {
+                    Ok(ExactNum::Float(constant.value()))
+                } else if env.get_list(var).is_some() {
+                    Err(ArithmaError::Eval(format!(
+                        "'{}' is a list; sum over it with \\sum_{{x \\in {}}} or pass it to a function",
+                        var, var
+                    )))
                 } else {
-                    Err(format!("Variable '{}' is not defined.", var))
+                    Err(ArithmaError::Eval(format!(
+                        "Variable '{}' is not defined.",
+                        var
+                    )))
                 }
             }
             Node::Negate(expr) => {
@@ -31,8 +61,12 @@ impl Evaluator {
             }
             Node::Factorial(expr) => {
                 let value = Self::evaluate_exact(expr, env)?;
-                crate::integer::factorial(&value)
-                    .ok_or_else(|| "factorial requires a non-negative integer.".to_string())
+                crate::special::factorial(&value).ok_or_else(|| {
+                    ArithmaError::Domain(
+                        "factorial requires a non-negative integer or a non-integer real."
+                            .to_string(),
+                    )
+                })
             }
             Node::Add(left, right) => {
                 let l = Self::evaluate_exact(left, env)?;
@@ -62,11 +96,16 @@ impl Evaluator {
             Node::Sqrt(operand) => {
                 let value = Self::evaluate_exact(operand, env)?;
                 if value.is_negative() {
-                    Err("Square root of negative number is not supported.".to_string())
+                    Err(ArithmaError::Domain(
+                        "Square root of negative number is not supported.".to_string(),
+                    ))
                 } else {
                     Ok(value.sqrt())
                 }
             }
+            Node::Abs(operand) if vector::is_vector_expression(operand) => {
+                Ok(vector::evaluate_norm(operand, env)?)
+            }
             Node::Abs(operand) => {
                 let value = Self::evaluate_exact(operand, env)?;
                 Ok(value.abs())
@@ -132,45 +171,188 @@ impl Evaluator {
                     ExactNum::zero()
                 })
             }
+            // Connectives read any nonzero value as true and stop as soon
+            // as the left operand decides the result
+            Node::And(left, right) => {
+                let holds = !Self::evaluate_exact(left, env)?.is_zero()
+                    && !Self::evaluate_exact(right, env)?.is_zero();
+                Ok(if holds {
+                    ExactNum::one()
+                } else {
+                    ExactNum::zero()
+                })
+            }
+            Node::Or(left, right) => {
+                let holds = !Self::evaluate_exact(left, env)?.is_zero()
+                    || !Self::evaluate_exact(right, env)?.is_zero();
+                Ok(if holds {
+                    ExactNum::one()
+                } else {
+                    ExactNum::zero()
+                })
+            }
+            Node::Not(inner) => Ok(if Self::evaluate_exact(inner, env)?.is_zero() {
+                ExactNum::one()
+            } else {
+                ExactNum::zero()
+            }),
             Node::Equation(left, right) => {
                 let l = Self::evaluate_exact(left, env)?;
                 let r = Self::evaluate_exact(right, env)?;
                 Ok(l - r)
             }
+            Node::Summation(ref index_var, start, end, body)
+                if crate::infinite_series::is_infinite_upper(end) =>
+            {
+                let (start_i, _) = Self::integer_range_bounds(start, start, env, "sum")?;
+                let series = crate::infinite_series::sum_infinite_series(
+                    body,
+                    index_var,
+                    start_i,
+                    env,
+                    &crate::infinite_series::SeriesOptions::default(),
+                )?;
+                Ok(ExactNum::from_f64(series.value))
+            }
             Node::Summation(ref index_var, start, end, body) => {
-                let start_val = Self::evaluate_exact(start, env)?;
-                let end_val = Self::evaluate_exact(end, env)?;
+                let (start_i, end_i) = Self::integer_range_bounds(start, end, env, "sum")?;
 
-                let (start_i, end_i) = Self::integer_range_bounds(&start_val, &end_val, "sum")?;
+                #[cfg(feature = "parallel")]
+                if Self::range_length(start_i, end_i) >= PARALLEL_MIN_TERMS {
+                    return Self::evaluate_range_parallel(
+                        index_var,
+                        (start_i, end_i),
+                        body,
+                        env,
+                        ExactNum::zero(),
+                        |a, b| a + b,
+                    );
+                }
 
-                let mut sum_env = env.clone();
+                let mut sum_env = env.child();
                 let mut sum = ExactNum::zero();
+                let mut job = Job::start(Self::range_length(start_i, end_i));
 
                 for i in start_i..=end_i {
                     sum_env.set_exact(index_var, ExactNum::integer(i));
                     let value = Self::evaluate_exact(body, &sum_env)?;
                     sum = sum + value;
+                    job.advance(1);
                 }
 
                 Ok(sum)
             }
             Node::Product(ref index_var, start, end, body) => {
-                let start_val = Self::evaluate_exact(start, env)?;
-                let end_val = Self::evaluate_exact(end, env)?;
+                let (start_i, end_i) = Self::integer_range_bounds(start, end, env, "product")?;
 
-                let (start_i, end_i) = Self::integer_range_bounds(&start_val, &end_val, "product")?;
+                #[cfg(feature = "parallel")]
+                if Self::range_length(start_i, end_i) >= PARALLEL_MIN_TERMS {
+                    return Self::evaluate_range_parallel(
+                        index_var,
+                        (start_i, end_i),
+                        body,
+                        env,
+                        ExactNum::one(),
+                        |a, b| a * b,
+                    );
+                }
 
-                let mut prod_env = env.clone();
+                let mut prod_env = env.child();
                 let mut product = ExactNum::one();
+                let mut job = Job::start(Self::range_length(start_i, end_i));
 
                 for i in start_i..=end_i {
                     prod_env.set_exact(index_var, ExactNum::integer(i));
                     let value = Self::evaluate_exact(body, &prod_env)?;
                     product = product * value;
+                    job.advance(1);
                 }
 
                 Ok(product)
             }
+            Node::SumOver(ref index_var, list, body) => {
+                let values = match list.as_ref() {
+                    Node::Variable(name) => env.get_list(name).ok_or_else(|| {
+                        ArithmaError::Eval(format!(
+                            "'{}' is not bound to a list in the environment",
+                            name
+                        ))
+                    })?,
+                    other => {
+                        return Err(ArithmaError::Eval(format!(
+                            "Cannot sum over {}: not a list name",
+                            other
+                        )))
+                    }
+                };
+                let mut sum_env = env.child();
+                let mut sum = ExactNum::zero();
+                let mut job = Job::start(values.len() as u64);
+                for value in values {
+                    sum_env.set_exact(index_var, value.clone());
+                    sum = sum + Self::evaluate_exact(body, &sum_env)?;
+                    job.advance(1);
+                }
+                Ok(sum)
+            }
+            Node::Integral(ref var, bounds, body) => {
+                let Some((lower, upper)) = bounds else {
+                    return Err(ArithmaError::Eval(format!(
+                        "An indefinite integral has no numeric value; give bounds, as in \\int_{{a}}^{{b}} … \\, d{}",
+                        var
+                    )));
+                };
+                // ±∞ stays as written for the improper integral
+                let bound = |b: &Node| -> Result<(Node, f64), ArithmaError> {
+                    match crate::limits::limit_point_of(b, env) {
+                        Ok(LimitPoint::PosInfinity) => Ok((b.clone(), f64::INFINITY)),
+                        Ok(LimitPoint::NegInfinity) => Ok((b.clone(), f64::NEG_INFINITY)),
+                        _ => {
+                            let value = Self::evaluate_exact(b, env)?;
+                            let x = value.to_f64();
+                            Ok((Node::Num(value), x))
+                        }
+                    }
+                };
+                let ((lower, a), (upper, b)) = (bound(lower)?, bound(upper)?);
+                crate::integration::check_no_poles_in_interval(body, var, &lower, &upper)?;
+                // Exact through the antiderivative when there is one,
+                // otherwise by quadrature
+                match crate::integration::definite_integral_exact(body, var, &lower, &upper) {
+                    Ok(value) => return Self::evaluate_exact(&value, env),
+                    // Divergence found through the limit at ±∞
+                    Err(e @ ArithmaError::Domain(_)) if a.is_infinite() || b.is_infinite() => {
+                        return Err(e)
+                    }
+                    Err(_) => {}
+                }
+                let mut inner_env = env.child();
+                let integrand = |x: f64| {
+                    inner_env.set(var, x);
+                    Self::evaluate(body, &inner_env)
+                        .ok()
+                        .filter(|v| v.is_finite())
+                };
+                crate::integration::improper_numeric_integral(integrand, a, b, 1e-10)
+                    .map(ExactNum::from_f64)
+                    .ok_or_else(|| {
+                        ArithmaError::Domain(format!(
+                            "The integrand is undefined somewhere in [{}, {}]",
+                            lower, upper
+                        ))
+                    })
+            }
+            Node::Limit(ref var, point, direction, body) => Ok(crate::limits::evaluate_limit(
+                var, point, *direction, body, env,
+            )?),
+            // Exact through the symbolic derivative when there is one,
+            // otherwise by finite differences at the variable's value
+            Node::Derivative(ref var, body) => match crate::derivative::differentiate(body, var) {
+                Ok(derivative) => Self::evaluate_exact(&derivative, env),
+                Err(_) => Ok(ExactNum::from_f64(crate::derivative::numeric_derivative(
+                    body, var, env,
+                )?)),
+            },
             Node::Piecewise(conditions) => {
                 for (expr, cond) in conditions {
                     let cond_val = Self::evaluate_exact(cond, env)?;
@@ -178,38 +360,167 @@ impl Evaluator {
                         return Self::evaluate_exact(expr, env);
                     }
                 }
-                Err("No condition in Piecewise expression evaluated to true.".to_string())
+                Err(ArithmaError::Domain(
+                    "No condition in Piecewise expression evaluated to true.".to_string(),
+                ))
+            }
+            Node::Matrix(_) => Err(ArithmaError::DimensionMismatch(format!(
+                "{} is a matrix, not a number; take its determinant with \\det",
+                node
+            ))),
+            // \det of a matrix expression: its determinant
+            Node::Function(ref name, ref args)
+                if name == matrix::DETERMINANT && args.len() == 1 =>
+            {
+                match args[0].simplify(env)? {
+                    m @ Node::Matrix(_) => {
+                        let m = Matrix::from_node(&m).ok_or_else(|| {
+                            ArithmaError::DimensionMismatch(
+                                "Matrix rows differ in length".to_string(),
+                            )
+                        })?;
+                        Self::evaluate_exact(&m.determinant(env)?, env)
+                    }
+                    arg => Self::evaluate_exact(&arg, env),
+                }
+            }
+            Node::Function(ref name, ref args) if vector::is_vector_function(name) => {
+                match (name.as_str(), args.as_slice()) {
+                    (vector::DOT, [a, b]) => Ok(vector::evaluate_dot(a, b, env)?),
+                    _ => Err(ArithmaError::DimensionMismatch(format!(
+                        "{} is a vector, not a number; take its norm |…| or a dot product",
+                        node
+                    ))),
+                }
             }
             Node::Function(ref name, ref args) => {
                 let mut evaluated_args = Vec::new();
                 for arg in args {
-                    evaluated_args.push(Self::evaluate_exact(arg, env)?);
+                    // A list argument contributes each of its elements
+                    let list = match arg {
+                        Node::Variable(v) => env.get_list(v),
+                        _ => None,
+                    };
+                    match list {
+                        Some(values) => evaluated_args.extend_from_slice(values),
+                        None => evaluated_args.push(Self::evaluate_exact(arg, env)?),
+                    }
+                }
+                if let Some(function) = env.get_function(name) {
+                    if evaluated_args.len() != function.params.len() {
+                        return Err(ArithmaError::InvalidArgument(format!(
+                            "{} takes {} argument(s) but {} were given",
+                            name,
+                            function.params.len(),
+                            evaluated_args.len()
+                        )));
+                    }
+                    let mut scope = env.child();
+                    for (param, value) in function.params.iter().zip(evaluated_args) {
+                        scope.set_exact(param, value);
+                    }
+                    return Self::evaluate_exact(&function.body, &scope);
+                }
+                if let Some(function) = env.registered_function(name) {
+                    if let Some(arity) = function
+                        .get_arg_count()
+                        .filter(|n| *n != evaluated_args.len())
+                    {
+                        return Err(ArithmaError::InvalidArgument(format!(
+                            "{} takes {} argument(s) but {} were given",
+                            name,
+                            arity,
+                            evaluated_args.len()
+                        )));
+                    }
+                    return function.call(evaluated_args).map_err(ArithmaError::Eval);
+                }
+                if let [x] = evaluated_args.as_slice() {
+                    if let Some(result) = env.domain_policy().check(name, x) {
+                        return result.map_err(ArithmaError::Domain);
+                    }
                 }
-                call_function(name, evaluated_args)
+                Ok(call_function_with_policy(
+                    name,
+                    evaluated_args,
+                    env.domain_policy(),
+                )?)
+            }
+        }
+    }
+
+    pub fn simplify(node: &Node, env: &Environment) -> Result<Node, ArithmaError> {
+        Ok(node.simplify(env)?)
+    }
+
+    /// `combine` folded over `body` at every integer of `range`, in chunks
+    /// spread over rayon's threads. Chunk results combine in range order,
+    /// so an exact sum or product is the same as the sequential one; a
+    /// float one may differ in the last bits. Progress advances a batch
+    /// of chunks at a time.
+    #[cfg(feature = "parallel")]
+    fn evaluate_range_parallel(
+        index_var: &str,
+        (start, end): (i64, i64),
+        body: &Node,
+        env: &Environment,
+        identity: ExactNum,
+        combine: fn(ExactNum, ExactNum) -> ExactNum,
+    ) -> Result<ExactNum, ArithmaError> {
+        use rayon::prelude::*;
+
+        let mut job = Job::start(Self::range_length(start, end));
+        let chunks: Vec<(i64, i64)> = (start..=end)
+            .step_by(PARALLEL_CHUNK as usize)
+            .map(|lo| (lo, end.min(lo.saturating_add(PARALLEL_CHUNK - 1))))
+            .collect();
+        let mut total = identity.clone();
+        for batch in chunks.chunks(PARALLEL_BATCH) {
+            let partials = batch
+                .par_iter()
+                .map(|&(lo, hi)| {
+                    let mut chunk_env = env.child();
+                    let mut acc = identity.clone();
+                    for i in lo..=hi {
+                        chunk_env.set_exact(index_var, ExactNum::integer(i));
+                        acc = combine(acc, Self::evaluate_exact(body, &chunk_env)?);
+                    }
+                    Ok(acc)
+                })
+                .collect::<Result<Vec<_>, ArithmaError>>()?;
+            for (partial, &(lo, hi)) in partials.into_iter().zip(batch) {
+                total = combine(total, partial);
+                job.advance(Self::range_length(lo, hi));
             }
         }
+        Ok(total)
     }
 
-    pub fn simplify(node: &Node, env: &Environment) -> Result<Node, String> {
-        node.simplify(env)
+    /// Number of terms in `start..=end`; zero when the range is empty.
+    fn range_length(start: i64, end: i64) -> u64 {
+        (end as i128 - start as i128 + 1).max(0) as u64
     }
 
     /// Σ/Π range bounds must be integers. Truncating (0.5 → empty range → 0,
     /// 2.7 → 2) would manufacture a value the expression never had — which
     /// numeric samplers then serialize inside "counterexamples". An empty
     /// integer range (start > end) is legitimate and yields the identity
-    /// element; a non-integer bound is an error.
+    /// element; a non-integer bound is an error. Bounds may be expressions
+    /// (`n + 1`, `2k`) over variables bound in `env`.
     fn integer_range_bounds(
-        start: &ExactNum,
-        end: &ExactNum,
+        start: &Node,
+        end: &Node,
+        env: &Environment,
         kind: &str,
-    ) -> Result<(i64, i64), String> {
-        let start_i = start
-            .to_i64()
-            .ok_or_else(|| format!("{kind} lower bound is not an integer: {start}"))?;
-        let end_i = end
-            .to_i64()
-            .ok_or_else(|| format!("{kind} upper bound is not an integer: {end}"))?;
-        Ok((start_i, end_i))
+    ) -> Result<(i64, i64), ArithmaError> {
+        let bound = |node: &Node, which: &str| {
+            let value = Self::evaluate_exact(node, env).map_err(|e| {
+                ArithmaError::Eval(format!("Cannot evaluate {kind} {which} bound {node}: {e}"))
+            })?;
+            value.to_i64().ok_or_else(|| {
+                ArithmaError::Domain(format!("{kind} {which} bound is not an integer: {value}"))
+            })
+        };
+        Ok((bound(start, "lower")?, bound(end, "upper")?))
     }
 }
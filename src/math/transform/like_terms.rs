@@ -0,0 +1,229 @@
+//! Like-term collection used by [`crate::simplify`]: the canonical form of
+//! a sum.
+//!
+//! A sum is flattened through `+`, `-` and unary minus into terms, each a
+//! coefficient times a monomial. A monomial is a product of powers of atoms
+//! (variables, function applications, and anything else that is not a sum,
+//! a product or a number) with numeric exponents: factors with the same
+//! base multiply by adding exponents, so `x \cdot x^{-2}` is `x^{-1}`, and
+//! the factors are sorted, so `x\sin(x)` and `\sin(x)x` are the same
+//! monomial. Terms with the same monomial add their coefficients
+//! (`2x - 3x + x^{2}` is `x^{2} - x`) and the numbers fold into one
+//! constant, written last. Monomials are [interned](crate::intern), so
+//! finding the term a monomial belongs to is a hash lookup and collecting
+//! a sum takes time linear in its size.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::exact::ExactNum;
+use crate::intern::{NodeArena, NodeId};
+use crate::node::Node;
+
+/// A product of powers of atoms, in canonical order: variables first,
+/// alphabetically, then the other atoms by how they print.
+#[derive(Clone, Debug, PartialEq)]
+struct Monomial(Vec<(Node, ExactNum)>);
+
+impl Monomial {
+    /// `coefficient · monomial` for one term of a sum.
+    fn of(node: &Node) -> (ExactNum, Monomial) {
+        let mut coefficient = ExactNum::one();
+        let mut factors = Vec::new();
+        multiply_into(node, &mut coefficient, &mut factors);
+        factors.retain(|(_, exponent): &(Node, ExactNum)| !exponent.is_zero());
+        factors.sort_by_cached_key(|(base, _)| {
+            (!matches!(base, Node::Variable(_)), format!("{}", base))
+        });
+        (coefficient, Monomial(factors))
+    }
+
+    fn variable(&self) -> Option<&str> {
+        match self.0.as_slice() {
+            [(Node::Variable(name), exponent)] if exponent.is_one() => Some(name),
+            _ => None,
+        }
+    }
+
+    fn to_node(&self) -> Node {
+        let mut factors = self.0.iter().map(|(base, exponent)| {
+            if exponent.is_one() {
+                base.clone()
+            } else {
+                Node::Power(
+                    Box::new(base.clone()),
+                    Box::new(Node::Num(exponent.clone())),
+                )
+            }
+        });
+        let first = factors.next().expect("a monomial has a factor");
+        factors.fold(first, |product, factor| {
+            Node::Multiply(Box::new(product), Box::new(factor))
+        })
+    }
+}
+
+fn multiply_into(node: &Node, coefficient: &mut ExactNum, factors: &mut Vec<(Node, ExactNum)>) {
+    match node {
+        Node::Num(n) => *coefficient = &*coefficient * n,
+        Node::Multiply(left, right) => {
+            multiply_into(left, coefficient, factors);
+            multiply_into(right, coefficient, factors);
+        }
+        Node::Negate(inner) => {
+            *coefficient = -coefficient.clone();
+            multiply_into(inner, coefficient, factors);
+        }
+        Node::Divide(numerator, denominator) => match &**denominator {
+            Node::Num(n) if !n.is_zero() => {
+                *coefficient = &*coefficient / n;
+                multiply_into(numerator, coefficient, factors);
+            }
+            _ => push_factor(factors, node, ExactNum::one()),
+        },
+        Node::Power(base, exponent) => match (&**base, &**exponent) {
+            (Node::Num(_), _) => push_factor(factors, node, ExactNum::one()),
+            (_, Node::Num(e)) => push_factor(factors, base, e.clone()),
+            _ => push_factor(factors, node, ExactNum::one()),
+        },
+        _ => push_factor(factors, node, ExactNum::one()),
+    }
+}
+
+fn push_factor(factors: &mut Vec<(Node, ExactNum)>, base: &Node, exponent: ExactNum) {
+    match factors.iter_mut().find(|(b, _)| b == base) {
+        Some((_, e)) => *e = &*e + &exponent,
+        None => factors.push((base.clone(), exponent)),
+    }
+}
+
+/// The terms of a sum with like terms combined. See the [module docs](self).
+pub(crate) struct LikeTerms {
+    /// Non-constant terms in the order they first appear
+    terms: Vec<(Monomial, ExactNum)>,
+    /// Where in `terms` each monomial is
+    positions: HashMap<NodeId, usize>,
+    monomials: NodeArena,
+    constant: ExactNum,
+    /// How many terms the sum was written with
+    written: usize,
+    /// Whether every written term was a number, a variable or a number
+    /// times a variable
+    linear: bool,
+}
+
+impl LikeTerms {
+    pub(crate) fn collect(node: &Node) -> Self {
+        let mut like_terms = LikeTerms {
+            terms: Vec::new(),
+            positions: HashMap::new(),
+            monomials: NodeArena::new(),
+            constant: ExactNum::zero(),
+            written: 0,
+            linear: true,
+        };
+        like_terms.add(node, &ExactNum::one());
+        like_terms
+    }
+
+    fn add(&mut self, node: &Node, sign: &ExactNum) {
+        for (term, negative) in node.sum_terms() {
+            let sign = if negative {
+                -sign.clone()
+            } else {
+                sign.clone()
+            };
+            match term {
+                Node::Negate(inner) => self.add(inner, &-sign),
+                _ => self.add_term(term, &sign),
+            }
+        }
+    }
+
+    fn add_term(&mut self, node: &Node, sign: &ExactNum) {
+        self.written += 1;
+        self.linear &= match node {
+            Node::Num(_) | Node::Variable(_) => true,
+            Node::Multiply(left, right) => {
+                matches!((&**left, &**right), (Node::Num(_), Node::Variable(_)))
+            }
+            _ => false,
+        };
+        let (coefficient, monomial) = Monomial::of(node);
+        let coefficient = &coefficient * sign;
+        if monomial.0.is_empty() {
+            self.constant = &self.constant + &coefficient;
+            return;
+        }
+        let id = self.monomials.intern(&monomial.to_node());
+        match self.positions.entry(id) {
+            Entry::Occupied(position) => {
+                let c = &mut self.terms[*position.get()].1;
+                *c = &*c + &coefficient;
+            }
+            Entry::Vacant(position) => {
+                position.insert(self.terms.len());
+                self.terms.push((monomial, coefficient));
+            }
+        }
+    }
+
+    /// Whether the sum is linear in plain variables: `2x - y + 3`.
+    pub(crate) fn is_linear(&self) -> bool {
+        self.linear
+    }
+
+    /// Whether collecting shortened the sum: terms combined or cancelled,
+    /// or numbers folded.
+    pub(crate) fn combines(&self) -> bool {
+        let remaining = self.terms.iter().filter(|(_, c)| !c.is_zero()).count()
+            + usize::from(!self.constant.is_zero());
+        remaining < self.written
+    }
+
+    /// The sum in canonical form, the constant last. A linear sum has its
+    /// variables in alphabetical order; other terms keep the order they
+    /// first appeared in.
+    pub(crate) fn to_node(&self) -> Node {
+        let mut terms: Vec<&(Monomial, ExactNum)> = self
+            .terms
+            .iter()
+            .filter(|(_, coefficient)| !coefficient.is_zero())
+            .collect();
+        if self.linear {
+            terms.sort_by_key(|(monomial, _)| monomial.variable());
+        }
+
+        // (magnitude, negative) for each nonzero term
+        let constant = (!self.constant.is_zero())
+            .then(|| (Node::Num(self.constant.abs()), self.constant.is_negative()));
+        let mut signed = terms
+            .into_iter()
+            .map(|(monomial, coefficient)| {
+                let magnitude = coefficient.abs();
+                let node = if magnitude.is_one() {
+                    monomial.to_node()
+                } else {
+                    Node::Multiply(Box::new(Node::Num(magnitude)), Box::new(monomial.to_node()))
+                };
+                (node, coefficient.is_negative())
+            })
+            .chain(constant);
+
+        let Some((first, negative)) = signed.next() else {
+            return Node::Num(ExactNum::zero());
+        };
+        let first = if negative {
+            Node::Negate(Box::new(first))
+        } else {
+            first
+        };
+        signed.fold(first, |sum, (term, negative)| {
+            if negative {
+                Node::Subtract(Box::new(sum), Box::new(term))
+            } else {
+                Node::Add(Box::new(sum), Box::new(term))
+            }
+        })
+    }
+}
@@ -0,0 +1,298 @@
+//! Pattern-matching rewrite rules.
+//!
+//! A [`Rule`] rewrites expressions matching its pattern. Variables whose
+//! name ends in `_` are wildcards: `a_` matches any subexpression, and a
+//! wildcard used twice must match the same subexpression both times, up to
+//! [structural equality](Node::structurally_equal). Everything else in a
+//! pattern matches itself, except that the two operands of a `+` or a
+//! `\cdot` may match in either order, so `a_ + 0` also rewrites `0 + x`.
+//!
+//! ```text
+//! a_ + a_ -> 2 a_
+//! \sin(\arcsin(u_)) -> u_
+//! ```
+//!
+//! A [`RuleSet`] tries its rules in order and [`apply_rules`] rewrites a
+//! whole tree with one. Rules are plain rewrites, valid only where the
+//! identity they state is; nothing checks that. The identities
+//! [`crate::simplify`] knows in this form are in [`RuleSet::builtin`], and
+//! rules set with [`Environment::set_rules`](crate::Environment::set_rules)
+//! extend simplification.
+
+use std::collections::HashMap;
+
+use crate::node::{same_operator, Node};
+use crate::parser::parse_latex_raw;
+
+lazy_static::lazy_static! {
+    /// `\sin^{2}u + \cos^{2}u = 1`, with or without a common coefficient.
+    pub(crate) static ref PYTHAGOREAN_RULES: RuleSet = RuleSet::parse(&[
+        "\\sin^{2}(u_) + \\cos^{2}(u_) -> 1",
+        "c_ \\sin^{2}(u_) + c_ \\cos^{2}(u_) -> c_",
+    ])
+    .expect("built-in rules parse");
+
+    /// Functions applied to their inverse, where that holds for every `u`.
+    pub(crate) static ref INVERSE_RULES: RuleSet = RuleSet::parse(&[
+        "\\sin(\\arcsin(u_)) -> u_",
+        "\\cos(\\arccos(u_)) -> u_",
+        "\\tan(\\arctan(u_)) -> u_",
+        "\\sinh(\\arcsinh(u_)) -> u_",
+        "\\cosh(\\arccosh(u_)) -> u_",
+        "\\tanh(\\arctanh(u_)) -> u_",
+        "\\arcsinh(\\sinh(u_)) -> u_",
+        "\\arctanh(\\tanh(u_)) -> u_",
+        "\\arccosh(\\cosh(u_)) -> |u_|",
+    ])
+    .expect("built-in rules parse");
+}
+
+/// How a wildcard `a_` is written for the parser, which reads `a_{w}` as
+/// the variable `a_w`.
+const WILDCARD_SUBSCRIPT: &str = "WILDCARD";
+
+/// A rewrite `pattern -> replacement`. See the [module docs](self).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    pattern: Node,
+    replacement: Node,
+}
+
+impl Rule {
+    /// A rule from trees whose wildcards are variables named `a_`, `b_`, …
+    /// Every wildcard of the replacement must occur in the pattern.
+    pub fn new(pattern: Node, replacement: Node) -> Result<Self, String> {
+        let mut bound = Vec::new();
+        collect_wildcards(&pattern, &mut bound);
+        let mut used = Vec::new();
+        collect_wildcards(&replacement, &mut used);
+        if let Some(unbound) = used.iter().find(|w| !bound.contains(w)) {
+            return Err(format!(
+                "Wildcard {} of the replacement does not occur in the pattern",
+                unbound
+            ));
+        }
+        Ok(Rule {
+            pattern,
+            replacement,
+        })
+    }
+
+    /// A rule written `pattern -> replacement` in LaTeX, e.g.
+    /// `a_ + a_ -> 2 a_`.
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let (pattern, replacement) = rule
+            .split_once("->")
+            .ok_or_else(|| format!("Rule '{}' has no '->'", rule))?;
+        let parse_side = |side: &str| {
+            parse_latex_raw(&mark_wildcards(side.trim()))
+                .map(|mut node| {
+                    rename_wildcards(&mut node);
+                    node
+                })
+                .map_err(|e| format!("Invalid rule '{}': {}", rule, e.message))
+        };
+        Rule::new(parse_side(pattern)?, parse_side(replacement)?)
+    }
+
+    pub fn pattern(&self) -> &Node {
+        &self.pattern
+    }
+
+    pub fn replacement(&self) -> &Node {
+        &self.replacement
+    }
+
+    /// `node` rewritten by this rule, if the pattern matches it as a whole.
+    pub fn rewrite(&self, node: &Node) -> Option<Node> {
+        let mut bindings = HashMap::new();
+        if !matches(&self.pattern, node, &mut bindings) {
+            return None;
+        }
+        let mut result = self.replacement.clone();
+        instantiate(&mut result, &bindings);
+        Some(result)
+    }
+}
+
+/// An ordered list of [`Rule`]s.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The rules of [`Rule::parse`] syntax, in order.
+    pub fn parse(rules: &[&str]) -> Result<Self, String> {
+        rules
+            .iter()
+            .map(|rule| Rule::parse(rule))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|rules| RuleSet { rules })
+    }
+
+    /// The identities simplification applies as rules: the Pythagorean
+    /// identity and the functions that cancel their inverse.
+    pub fn builtin() -> Self {
+        RuleSet {
+            rules: PYTHAGOREAN_RULES
+                .rules
+                .iter()
+                .chain(&INVERSE_RULES.rules)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Add `rule` after those already in the set.
+    pub fn push(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+
+    /// [`push`](Self::push), for building a set in one expression.
+    pub fn with(mut self, rule: Rule) -> Self {
+        self.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `node` rewritten by the first rule whose pattern matches it as a
+    /// whole.
+    pub fn rewrite(&self, node: &Node) -> Option<Node> {
+        self.rules.iter().find_map(|rule| rule.rewrite(node))
+    }
+}
+
+/// Rewrite `expr` with `rules` until no rule applies, or for at most
+/// `max_iters` passes. Each pass rewrites the tree bottom up, every
+/// subexpression at most once, so rules that undo each other stop after
+/// `max_iters`.
+pub fn apply_rules(expr: &Node, rules: &RuleSet, max_iters: usize) -> Node {
+    let mut current = expr.clone();
+    for _ in 0..max_iters {
+        if !rewrite_pass(&mut current, rules) {
+            break;
+        }
+    }
+    current
+}
+
+/// One bottom-up pass; whether anything was rewritten.
+fn rewrite_pass(node: &mut Node, rules: &RuleSet) -> bool {
+    let mut changed = false;
+    for child in node.children_mut() {
+        changed |= rewrite_pass(child, rules);
+    }
+    if let Some(rewritten) = rules.rewrite(node) {
+        changed |= rewritten != *node;
+        *node = rewritten;
+    }
+    changed
+}
+
+fn is_wildcard(name: &str) -> bool {
+    name.len() > 1 && name.ends_with('_')
+}
+
+fn matches(pattern: &Node, node: &Node, bindings: &mut HashMap<String, Node>) -> bool {
+    match (pattern, node) {
+        (Node::Variable(name), _) if is_wildcard(name) => match bindings.get(name) {
+            Some(bound) => bound.structurally_equal(node),
+            None => {
+                bindings.insert(name.clone(), node.clone());
+                true
+            }
+        },
+        (Node::Add(p, q), Node::Add(l, r)) | (Node::Multiply(p, q), Node::Multiply(l, r)) => {
+            let before = bindings.clone();
+            if matches(p, l, bindings) && matches(q, r, bindings) {
+                return true;
+            }
+            *bindings = before.clone();
+            if matches(p, r, bindings) && matches(q, l, bindings) {
+                return true;
+            }
+            *bindings = before;
+            false
+        }
+        _ => {
+            same_operator(pattern, node)
+                && pattern
+                    .children()
+                    .into_iter()
+                    .zip(node.children())
+                    .all(|(p, n)| matches(p, n, bindings))
+        }
+    }
+}
+
+fn instantiate(node: &mut Node, bindings: &HashMap<String, Node>) {
+    if let Node::Variable(name) = node {
+        if let Some(bound) = bindings.get(name) {
+            *node = bound.clone();
+        }
+        return;
+    }
+    for child in node.children_mut() {
+        instantiate(child, bindings);
+    }
+}
+
+fn collect_wildcards(node: &Node, out: &mut Vec<String>) {
+    node.visit(&mut |n| {
+        if let Node::Variable(name) = n {
+            if is_wildcard(name) && !out.contains(name) {
+                out.push(name.clone());
+            }
+        }
+    });
+}
+
+/// `a_` → `a_{WILDCARD}` wherever the `_` starts no subscript.
+fn mark_wildcards(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut marked = String::with_capacity(source.len());
+    for (i, &c) in chars.iter().enumerate() {
+        marked.push(c);
+        let after_letter = i > 0 && chars[i - 1].is_ascii_alphabetic();
+        let subscript = chars
+            .get(i + 1)
+            .is_some_and(|next| next.is_ascii_alphanumeric() || *next == '{');
+        if c == '_' && after_letter && !subscript {
+            marked.push('{');
+            marked.push_str(WILDCARD_SUBSCRIPT);
+            marked.push('}');
+        }
+    }
+    marked
+}
+
+/// The variable `a_WILDCARD` the parser read → the wildcard `a_`.
+fn rename_wildcards(node: &mut Node) {
+    if let Node::Variable(name) = node {
+        if let Some(stem) = name.strip_suffix(WILDCARD_SUBSCRIPT) {
+            if stem.ends_with('_') {
+                *name = stem.to_string();
+            }
+        }
+        return;
+    }
+    for child in node.children_mut() {
+        rename_wildcards(child);
+    }
+}
@@ -1,16 +1,22 @@
 use crate::environment::Environment;
 use crate::exact::ExactNum;
-use crate::function_meta::is_transcendental_function;
+use crate::function_meta::{
+    canonical_function_name, is_special_function, is_transcendental_function,
+};
 use crate::integer::{extract_square_factors, gcd, lcm, prime_factorize};
+use crate::like_terms::LikeTerms;
+use crate::matrix::{simplify_matrix_function, simplify_matrix_operation};
 use crate::multipoly::MultiPoly;
 use crate::node::Node;
-use crate::polynomial::Polynomial;
+use crate::polynomial::{as_monomial, monomial_to_node, Polynomial};
+use crate::rewrite::{RuleSet, INVERSE_RULES, PYTHAGOREAN_RULES};
 use crate::simplify_literal::try_normalize_pi_multiple;
+use crate::symmetry::{symbolic_parity, Parity};
+use crate::vector::{self, Vector};
 use num_bigint::BigInt;
 use num_integer::Integer;
 use num_rational::BigRational;
 use num_traits::{One, Signed, Zero};
-use std::collections::HashMap;
 
 /// Attempt to convert an f64 to an exact rational p/q with small denominator.
 /// Checks denominators 1..100; returns the first match within 1e-10 tolerance.
@@ -71,6 +77,33 @@ fn try_fold_factorial_arg(arg: &Node) -> Option<Node> {
     Some(Node::Num(crate::integer::factorial(n)?))
 }
 
+/// Whether `node` takes only integer values: integer literals, variables
+/// assumed integer, and sums, products and natural powers of those, plus
+/// anything already rounded. Floor, ceiling, rounding and truncation leave
+/// such a value unchanged.
+fn is_integer_valued(node: &Node, env: &Environment) -> bool {
+    match node {
+        Node::Num(n) => n.is_integer(),
+        Node::Variable(name) => env.assumptions().is_integer(name),
+        Node::Floor(_) | Node::Ceil(_) | Node::Round(_) | Node::Trunc(_) => true,
+        Node::Add(l, r) | Node::Subtract(l, r) | Node::Multiply(l, r) => {
+            is_integer_valued(l, env) && is_integer_valued(r, env)
+        }
+        Node::Negate(inner) | Node::Factorial(inner) => is_integer_valued(inner, env),
+        Node::Power(base, exp) => {
+            is_integer_valued(base, env)
+                && matches!(&**exp, Node::Num(n) if n.is_integer() && !n.is_negative())
+        }
+        Node::Function(name, _) => {
+            matches!(
+                name.as_str(),
+                "floor" | "ceil" | "round" | "trunc" | "sgn" | "sign"
+            )
+        }
+        _ => false,
+    }
+}
+
 fn try_fold_factorial(args: &[Node]) -> Option<Node> {
     if args.len() != 1 {
         return None;
@@ -122,6 +155,26 @@ fn try_fold_lcm(args: &[Node]) -> Option<Node> {
     Some(Node::Num(result))
 }
 
+fn try_fold_number_theory(name: &str, args: &[Node]) -> Option<Node> {
+    let numbers: Vec<&ExactNum> = args
+        .iter()
+        .map(|arg| match arg {
+            Node::Num(n) => Some(n),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    match (name, numbers.as_slice()) {
+        ("isprime", [n]) => Some(Node::Num(ExactNum::integer(
+            crate::integer::is_prime(n)? as i64
+        ))),
+        ("powmod", [base, exponent, modulus]) => {
+            Some(Node::Num(crate::integer::powmod(base, exponent, modulus)?))
+        }
+        ("factorint", [n]) => crate::integer::prime_factorize_node(n),
+        _ => None,
+    }
+}
+
 /// Argument for `ln` inside `ln(a^b)`: use `|a|` when `b` is an even integer so
 /// `ln(x^2)` and `ln((-x)^2)` both become `2·ln|x|` on the real line.
 fn ln_power_log_arg(base: &Node, exp: &Node, env: &Environment) -> Node {
@@ -152,6 +205,9 @@ fn try_exact_function_value(name: &str, args: &[Node]) -> Option<Node> {
     if name == "lcm" {
         return try_fold_lcm(args);
     }
+    if matches!(name, "isprime" | "powmod" | "factorint") {
+        return try_fold_number_theory(name, args);
+    }
 
     if args.len() == 1 {
         let arg = &args[0];
@@ -277,8 +333,12 @@ fn simplify_log_function(log_name: &str, arg: &Node, env: &Environment) -> Optio
             }
         }
     }
+    let expand = |a: &Node, b: &Node| {
+        env.simplify_options().expand_logarithms
+            || (is_known_positive(a, env) && is_known_positive(b, env))
+    };
     if let Node::Multiply(a, b) = arg {
-        if !is_known_positive(a, env) || !is_known_positive(b, env) {
+        if !expand(a, b) {
             return None;
         }
         let log_a = Node::Function(log_name.to_string(), vec![*a.clone()])
@@ -292,7 +352,7 @@ fn simplify_log_function(log_name: &str, arg: &Node, env: &Environment) -> Optio
             .ok();
     }
     if let Node::Divide(a, b) = arg {
-        if !is_known_positive(a, env) || !is_known_positive(b, env) {
+        if !expand(a, b) {
             return None;
         }
         let log_a = Node::Function(log_name.to_string(), vec![*a.clone()])
@@ -308,6 +368,45 @@ fn simplify_log_function(log_name: &str, arg: &Node, env: &Environment) -> Optio
     factor_log_integer(log_name, arg)?.simplify(env).ok()
 }
 
+/// `\tan u`, `\cot u`, `\sec u` or `\csc u` as a quotient of `\sin u`
+/// and `\cos u`, for [`SimplifyOptions::sin_cos_form`].
+fn in_sin_cos(name: &str, args: &[Node]) -> Option<Node> {
+    let [u] = args else {
+        return None;
+    };
+    let call = |f: &str| Node::Function(f.to_string(), vec![u.clone()]);
+    let (numerator, denominator) = match canonical_function_name(name) {
+        "tan" => (call("sin"), call("cos")),
+        "cot" => (call("cos"), call("sin")),
+        "sec" => (Node::Num(ExactNum::one()), call("cos")),
+        "csc" => (Node::Num(ExactNum::one()), call("sin")),
+        _ => return None,
+    };
+    Some(Node::Divide(Box::new(numerator), Box::new(denominator)))
+}
+
+/// `\ln(e^{u})` or `e^{\ln u}` with its argument simplified but the
+/// composition kept, when [`SimplifyOptions::log_exp_inverses`] is off.
+fn keep_log_exp_inverse(
+    name: &str,
+    args: &[Node],
+    env: &Environment,
+) -> Option<Result<Node, String>> {
+    let [arg] = args else {
+        return None;
+    };
+    let arg = match arg.simplify(env) {
+        Ok(arg) => arg,
+        Err(e) => return Some(Err(e)),
+    };
+    let inverse = match (name, &arg) {
+        ("exp", Node::Function(inner, _)) => inner == "ln",
+        ("ln", Node::Function(inner, _)) => inner == "exp",
+        _ => false,
+    };
+    inverse.then(|| Ok(Node::Function(name.to_string(), vec![arg])))
+}
+
 fn is_zero_node(node: &Node) -> bool {
     match node {
         Node::Num(n) => n.is_zero(),
@@ -378,17 +477,89 @@ fn try_combine_function_terms(
         .ok()
 }
 
+/// Which identity rewrites [`Simplifiable::simplify`] applies, read from
+/// the [`Environment`] (see [`Environment::set_simplify_options`]). The
+/// defaults keep the rewrites that hold wherever both sides are defined
+/// and leave the aggressive ones off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimplifyOptions {
+    /// `\sin^{2} u + \cos^{2} u → 1`, `1 - \sin^{2} u → \cos^{2} u`
+    pub pythagorean: bool,
+    /// `\ln(e^{u}) → u` and `e^{\ln u} → u`; the second drops the
+    /// requirement `u > 0`
+    pub log_exp_inverses: bool,
+    /// `\ln(ab) → \ln a + \ln b` and `\ln\frac{a}{b} → \ln a - \ln b` for
+    /// any `a` and `b`, not only ones known to be positive
+    pub expand_logarithms: bool,
+    /// Write `\tan`, `\cot`, `\sec` and `\csc` in terms of `\sin` and
+    /// `\cos`, instead of folding quotients of those into them
+    pub sin_cos_form: bool,
+    /// Replace [constants](crate::constants) by their float values, so
+    /// `2\pi` simplifies to `6.283…`, instead of keeping them symbolic
+    pub fold_constants: bool,
+}
+
+impl Default for SimplifyOptions {
+    fn default() -> Self {
+        SimplifyOptions {
+            pythagorean: true,
+            log_exp_inverses: true,
+            expand_logarithms: false,
+            sin_cos_form: false,
+            fold_constants: false,
+        }
+    }
+}
+
+/// [`Simplifiable::simplify`] under `options` instead of those of `env`.
+pub fn simplify_with_options(
+    node: &Node,
+    env: &Environment,
+    options: SimplifyOptions,
+) -> Result<Node, String> {
+    let mut env = env.clone();
+    env.set_simplify_options(options);
+    node.simplify(&env)
+}
+
 pub trait Simplifiable {
     fn simplify(&self, env: &Environment) -> Result<Node, String>;
 }
 
 impl Simplifiable for Node {
     fn simplify(&self, env: &Environment) -> Result<Node, String> {
+        let simplified = self.simplify_node(env)?;
+        match env.rules().rewrite(&simplified) {
+            Some(rewritten) if rewritten != simplified => {
+                // What the rule wrote is simplified without the rules, so
+                // one that simplification undoes cannot loop.
+                let mut env = env.clone();
+                env.set_rules(RuleSet::new());
+                rewritten.simplify(&env)
+            }
+            _ => Ok(simplified),
+        }
+    }
+}
+
+impl Node {
+    /// Simplification proper, before the rules of `env`.
+    fn simplify_node(&self, env: &Environment) -> Result<Node, String> {
         match self {
             Node::Add(left, right) => {
+                if let Some(flat) = simplify_long_sum(self, env) {
+                    return flat;
+                }
                 let left_simplified = left.simplify(env)?;
                 let right_simplified = right.simplify(env)?;
 
+                if is_matrix(&left_simplified) || is_matrix(&right_simplified) {
+                    return Ok(simplify_matrix_operation(
+                        &Node::Add(Box::new(left_simplified), Box::new(right_simplified)),
+                        env,
+                    )?);
+                }
+
                 if let (Node::Num(ref l), Node::Num(ref r)) = (&left_simplified, &right_simplified)
                 {
                     return Ok(Node::Num(l + r));
@@ -406,8 +577,17 @@ impl Simplifiable for Node {
                 }
 
                 // sin²(x) + cos²(x) → 1
-                if let Some(result) = try_pythagorean(&left_simplified, &right_simplified) {
-                    return Ok(result);
+                if env.simplify_options().pythagorean {
+                    let sum = Node::Add(
+                        Box::new(left_simplified.clone()),
+                        Box::new(right_simplified.clone()),
+                    );
+                    if let Some(result) = PYTHAGOREAN_RULES.rewrite(&sum) {
+                        return Ok(result);
+                    }
+                    if let Some(reduced) = try_pythagorean_in_sum(&sum) {
+                        return reduced.simplify(env);
+                    }
                 }
 
                 // a·f(x) + b·f(x) → (a+b)·f(x) (before fraction combination)
@@ -429,16 +609,7 @@ impl Simplifiable for Node {
                 if let Some(combined) = try_combine_like_radicals(&result, env) {
                     return Ok(combined);
                 }
-                let mut term_map: HashMap<String, ExactNum> = HashMap::new();
-                if collect_terms(&result, &mut term_map, env).is_ok() {
-                    Ok(rebuild_expression(term_map))
-                } else if let Some(normalized) = try_polynomial_normalize(&result) {
-                    Ok(normalized)
-                } else if let Some(normalized) = try_rational_normalize(&result, env) {
-                    Ok(normalized)
-                } else {
-                    Ok(result)
-                }
+                Ok(canonical_sum(result, env))
             }
             Node::Num(n) => {
                 if let ExactNum::Float(f) = n {
@@ -452,6 +623,13 @@ impl Simplifiable for Node {
                 let left_simplified = left.simplify(env)?;
                 let right_simplified = right.simplify(env)?;
 
+                if is_matrix(&left_simplified) || is_matrix(&right_simplified) {
+                    return Ok(simplify_matrix_operation(
+                        &Node::Multiply(Box::new(left_simplified), Box::new(right_simplified)),
+                        env,
+                    )?);
+                }
+
                 // Handle multiplication by zero
                 if let Node::Num(ref n) = left_simplified {
                     if n.is_zero() {
@@ -600,6 +778,13 @@ impl Simplifiable for Node {
                 let base_simplified = base.simplify(env)?;
                 let exponent_simplified = exponent.simplify(env)?;
 
+                if is_matrix(&base_simplified) || is_matrix(&exponent_simplified) {
+                    return Ok(simplify_matrix_operation(
+                        &Node::Power(Box::new(base_simplified), Box::new(exponent_simplified)),
+                        env,
+                    )?);
+                }
+
                 // e^x → exp(x)
                 if let Node::Variable(ref v) = base_simplified {
                     if v == "e" {
@@ -674,9 +859,19 @@ impl Simplifiable for Node {
                 ))
             }
             Node::Subtract(left, right) => {
+                if let Some(flat) = simplify_long_sum(self, env) {
+                    return flat;
+                }
                 let left_simplified = left.simplify(env)?;
                 let right_simplified = right.simplify(env)?;
 
+                if is_matrix(&left_simplified) || is_matrix(&right_simplified) {
+                    return Ok(simplify_matrix_operation(
+                        &Node::Subtract(Box::new(left_simplified), Box::new(right_simplified)),
+                        env,
+                    )?);
+                }
+
                 if let (Node::Num(ref l), Node::Num(ref r)) = (&left_simplified, &right_simplified)
                 {
                     return Ok(Node::Num(l - r));
@@ -693,9 +888,11 @@ impl Simplifiable for Node {
                     }
                 }
 
+                let pythagorean = env.simplify_options().pythagorean;
+
                 // 1 - sin²(x) → cos²(x), 1 - cos²(x) → sin²(x)
                 if let Node::Num(ref n) = left_simplified {
-                    if n.is_one() {
+                    if n.is_one() && pythagorean {
                         if let Some(args) = is_trig_squared(&right_simplified, "sin") {
                             return Ok(Node::Power(
                                 Box::new(Node::Function("cos".to_string(), args)),
@@ -713,7 +910,7 @@ impl Simplifiable for Node {
 
                 // sin²(x) - 1 → -cos²(x), cos²(x) - 1 → -sin²(x)
                 if let Node::Num(ref n) = right_simplified {
-                    if n.is_one() {
+                    if n.is_one() && pythagorean {
                         if let Some(args) = is_trig_squared(&left_simplified, "sin") {
                             return Ok(Node::Negate(Box::new(Node::Power(
                                 Box::new(Node::Function("cos".to_string(), args)),
@@ -729,6 +926,16 @@ impl Simplifiable for Node {
                     }
                 }
 
+                let difference = Node::Subtract(
+                    Box::new(left_simplified.clone()),
+                    Box::new(right_simplified.clone()),
+                );
+                if pythagorean {
+                    if let Some(reduced) = try_pythagorean_in_sum(&difference) {
+                        return reduced.simplify(env);
+                    }
+                }
+
                 // a·f(x) - b·f(x) → (a-b)·f(x) (before fraction combination)
                 if let Some(combined) =
                     try_combine_function_terms(&left_simplified, &right_simplified, true, env)
@@ -748,19 +955,16 @@ impl Simplifiable for Node {
                 if let Some(combined) = try_combine_like_radicals(&result, env) {
                     return Ok(combined);
                 }
-                let mut term_map: HashMap<String, ExactNum> = HashMap::new();
-                if collect_terms(&result, &mut term_map, env).is_ok() {
-                    Ok(rebuild_expression(term_map))
-                } else if let Some(normalized) = try_polynomial_normalize(&result) {
-                    Ok(normalized)
-                } else if let Some(normalized) = try_rational_normalize(&result, env) {
-                    Ok(normalized)
-                } else {
-                    Ok(result)
-                }
+                Ok(canonical_sum(result, env))
             }
             Node::Negate(operand) => {
                 let simplified = operand.simplify(env)?;
+                if is_matrix(&simplified) {
+                    return Ok(simplify_matrix_operation(
+                        &Node::Negate(Box::new(simplified)),
+                        env,
+                    )?);
+                }
                 if let Node::Num(ref n) = simplified {
                     return Ok(Node::Num(-n.clone()));
                 }
@@ -787,6 +991,13 @@ impl Simplifiable for Node {
                 let left_simplified = left.simplify(env)?;
                 let right_simplified = right.simplify(env)?;
 
+                if is_matrix(&left_simplified) || is_matrix(&right_simplified) {
+                    return Ok(simplify_matrix_operation(
+                        &Node::Divide(Box::new(left_simplified), Box::new(right_simplified)),
+                        env,
+                    )?);
+                }
+
                 // 0/u → 0, justified by Q(x) semantics (removable domain
                 // differences do not exist in the rational function field,
                 // consistent with pole cancellation elsewhere) — so the
@@ -873,6 +1084,9 @@ impl Simplifiable for Node {
                                 if reduced == neg_one {
                                     return Ok(Node::Negate(mr.clone()));
                                 }
+                                if let Some(quotient) = scaled_quotient(&reduced, mr, env) {
+                                    return quotient;
+                                }
                                 return Node::Multiply(Box::new(Node::Num(reduced)), mr.clone())
                                     .simplify(env);
                             }
@@ -885,6 +1099,9 @@ impl Simplifiable for Node {
                                 if reduced == neg_one {
                                     return Ok(Node::Negate(ml.clone()));
                                 }
+                                if let Some(quotient) = scaled_quotient(&reduced, ml, env) {
+                                    return quotient;
+                                }
                                 return Node::Multiply(ml.clone(), Box::new(Node::Num(reduced)))
                                     .simplify(env);
                             }
@@ -1000,61 +1217,74 @@ impl Simplifiable for Node {
                     }
                 }
 
-                // sin(x) / cos(x) → tan(x), cos(x) / sin(x) → cot(x)
-                if let (
-                    Node::Function(ref fname1, ref args1),
-                    Node::Function(ref fname2, ref args2),
-                ) = (&left_simplified, &right_simplified)
-                {
-                    if fname1 == "sin" && fname2 == "cos" && args1 == args2 {
-                        return Ok(Node::Function("tan".to_string(), args1.clone()));
-                    }
-                    if fname1 == "cos" && fname2 == "sin" && args1 == args2 {
-                        return Ok(Node::Function("cot".to_string(), args1.clone()));
+                let fold_trig_quotients = !env.simplify_options().sin_cos_form;
+
+                if fold_trig_quotients {
+                    // sin(x) / cos(x) → tan(x), cos(x) / sin(x) → cot(x)
+                    if let (
+                        Node::Function(ref fname1, ref args1),
+                        Node::Function(ref fname2, ref args2),
+                    ) = (&left_simplified, &right_simplified)
+                    {
+                        if fname1 == "sin" && fname2 == "cos" && args1 == args2 {
+                            return Ok(Node::Function("tan".to_string(), args1.clone()));
+                        }
+                        if fname1 == "cos" && fname2 == "sin" && args1 == args2 {
+                            return Ok(Node::Function("cot".to_string(), args1.clone()));
+                        }
                     }
-                }
 
-                // k·sin(x) / cos(x) → k·tan(x), k·cos(x) / sin(x) → k·cot(x)
-                if let Node::Multiply(ref ml, ref mr) = left_simplified {
-                    if let Node::Function(ref fname2, ref args2) = right_simplified {
-                        let (coeff, func) = if matches!(**ml, Node::Function(_, _)) {
-                            (mr, ml)
-                        } else {
-                            (ml, mr)
-                        };
-                        if let Node::Function(ref fname1, ref args1) = **func {
-                            if args1 == args2 {
-                                if fname1 == "sin" && fname2 == "cos" {
-                                    return Node::Multiply(
-                                        coeff.clone(),
-                                        Box::new(Node::Function("tan".to_string(), args1.clone())),
-                                    )
-                                    .simplify(env);
-                                }
-                                if fname1 == "cos" && fname2 == "sin" {
-                                    return Node::Multiply(
-                                        coeff.clone(),
-                                        Box::new(Node::Function("cot".to_string(), args1.clone())),
-                                    )
-                                    .simplify(env);
+                    // k·sin(x) / cos(x) → k·tan(x), k·cos(x) / sin(x) → k·cot(x)
+                    if let Node::Multiply(ref ml, ref mr) = left_simplified {
+                        if let Node::Function(ref fname2, ref args2) = right_simplified {
+                            let (coeff, func) = if matches!(**ml, Node::Function(_, _)) {
+                                (mr, ml)
+                            } else {
+                                (ml, mr)
+                            };
+                            if let Node::Function(ref fname1, ref args1) = **func {
+                                if args1 == args2 {
+                                    if fname1 == "sin" && fname2 == "cos" {
+                                        return Node::Multiply(
+                                            coeff.clone(),
+                                            Box::new(Node::Function(
+                                                "tan".to_string(),
+                                                args1.clone(),
+                                            )),
+                                        )
+                                        .simplify(env);
+                                    }
+                                    if fname1 == "cos" && fname2 == "sin" {
+                                        return Node::Multiply(
+                                            coeff.clone(),
+                                            Box::new(Node::Function(
+                                                "cot".to_string(),
+                                                args1.clone(),
+                                            )),
+                                        )
+                                        .simplify(env);
+                                    }
                                 }
                             }
                         }
                     }
-                }
 
-                // 1 / sin(x) → csc(x), 1 / cos(x) → sec(x), 1 / tan(x) → cot(x)
-                if let Node::Num(ref n) = left_simplified {
-                    if n.is_one() {
-                        if let Node::Function(ref fname, ref args) = right_simplified {
-                            let recip = match fname.as_str() {
-                                "sin" => Some("csc"),
-                                "cos" => Some("sec"),
-                                "tan" => Some("cot"),
-                                _ => None,
-                            };
-                            if let Some(recip_name) = recip {
-                                return Ok(Node::Function(recip_name.to_string(), args.clone()));
+                    // 1 / sin(x) → csc(x), 1 / cos(x) → sec(x), 1 / tan(x) → cot(x)
+                    if let Node::Num(ref n) = left_simplified {
+                        if n.is_one() {
+                            if let Node::Function(ref fname, ref args) = right_simplified {
+                                let recip = match fname.as_str() {
+                                    "sin" => Some("csc"),
+                                    "cos" => Some("sec"),
+                                    "tan" => Some("cot"),
+                                    _ => None,
+                                };
+                                if let Some(recip_name) = recip {
+                                    return Ok(Node::Function(
+                                        recip_name.to_string(),
+                                        args.clone(),
+                                    ));
+                                }
                             }
                         }
                     }
@@ -1125,6 +1355,24 @@ impl Simplifiable for Node {
                 let start_simplified = start.simplify(env)?;
                 let end_simplified = end.simplify(env)?;
 
+                if crate::infinite_series::is_infinite_upper(&end_simplified) {
+                    let body_simplified = body.simplify(env)?;
+                    if let Some(result) = try_infinite_geometric_sum(
+                        index_var,
+                        &start_simplified,
+                        &body_simplified,
+                        env,
+                    ) {
+                        return result;
+                    }
+                    return Ok(Node::Summation(
+                        index_var.clone(),
+                        Box::new(start_simplified),
+                        Box::new(end_simplified),
+                        Box::new(body_simplified),
+                    ));
+                }
+
                 // Try telescoping on the unsimplified body (before simplification
                 // merges the difference into a single fraction)
                 if let Some(result) =
@@ -1146,7 +1394,7 @@ impl Simplifiable for Node {
                         let range_size = (end_val - start_val + 1.0) as usize;
                         if range_size <= 10 {
                             let mut sum_node = Node::Num(ExactNum::zero());
-                            let mut sum_env = env.clone();
+                            let mut sum_env = env.child();
 
                             let start_i = start_val as i64;
                             let end_i = end_val as i64;
@@ -1204,7 +1452,7 @@ impl Simplifiable for Node {
                         let range_size = (end_val - start_val + 1.0) as usize;
                         if range_size <= 10 {
                             let mut prod_node = Node::Num(ExactNum::one());
-                            let mut prod_env = env.clone();
+                            let mut prod_env = env.child();
 
                             let start_i = start_val as i64;
                             let end_i = end_val as i64;
@@ -1245,11 +1493,79 @@ impl Simplifiable for Node {
                     Box::new(body_simplified),
                 ))
             }
+            Node::Integral(var, bounds, body) => {
+                let body_simplified = body.simplify(env)?;
+                let bounds_simplified = match bounds {
+                    Some((lower, upper)) => Some((lower.simplify(env)?, upper.simplify(env)?)),
+                    None => None,
+                };
+                // Resolve through the integration engine when it can; an
+                // integral it cannot do (or an improper one) stays as is
+                let resolved = match &bounds_simplified {
+                    Some((lower, upper)) => crate::integration::definite_integral_exact(
+                        &body_simplified,
+                        var,
+                        lower,
+                        upper,
+                    ),
+                    None => crate::integration::integrate(&body_simplified, var),
+                };
+                match resolved {
+                    Ok(result) => result.simplify(env),
+                    Err(_) => Ok(Node::Integral(
+                        var.clone(),
+                        bounds_simplified.map(|(lower, upper)| (Box::new(lower), Box::new(upper))),
+                        Box::new(body_simplified),
+                    )),
+                }
+            }
+            // The list's values are only known at evaluation time
+            Node::SumOver(index_var, list, body) => Ok(Node::SumOver(
+                index_var.clone(),
+                list.clone(),
+                Box::new(body.simplify(env)?),
+            )),
+            Node::Derivative(var, body) => {
+                let body_simplified = body.simplify(env)?;
+                // Resolve when the derivative engine can; otherwise keep
+                // the derivative with its body simplified
+                match crate::derivative::differentiate(&body_simplified, var) {
+                    Ok(result) => result.simplify(env),
+                    Err(_) => Ok(Node::Derivative(var.clone(), Box::new(body_simplified))),
+                }
+            }
+            Node::Limit(var, point, direction, body) => {
+                let body_simplified = body.simplify(env)?;
+                let point_simplified = point.simplify(env)?;
+                if !body_simplified.contains_variable(var) {
+                    return Ok(body_simplified);
+                }
+                // Resolve when the limit engine can; otherwise keep the
+                // limit with its parts simplified
+                match crate::limits::resolve_limit(
+                    var,
+                    &point_simplified,
+                    *direction,
+                    &body_simplified,
+                ) {
+                    Ok(result) => Ok(crate::limits::limit_result_to_node(result)),
+                    Err(_) => Ok(Node::Limit(
+                        var.clone(),
+                        Box::new(point_simplified),
+                        *direction,
+                        Box::new(body_simplified),
+                    )),
+                }
+            }
             Node::Abs(operand) => {
                 let simplified = operand.simplify(env)?;
                 if let Node::Num(ref n) = simplified {
                     return Ok(Node::Num(n.abs()));
                 }
+                // |(3, 4)| → 5
+                if let Some(v) = Vector::from_node(&simplified) {
+                    return v.norm(env);
+                }
                 // |x| → x when x is nonnegative or a known positive constant
                 if let Node::Variable(ref v) = simplified {
                     if v == "e" || v == "π" || env.assumptions().is_nonneg(v) {
@@ -1274,6 +1590,9 @@ impl Simplifiable for Node {
                 if let Node::Num(ref n) = simplified {
                     return Ok(Node::Num(n.floor()));
                 }
+                if is_integer_valued(&simplified, env) {
+                    return Ok(simplified);
+                }
                 Ok(Node::Floor(Box::new(simplified)))
             }
             Node::Ceil(operand) => {
@@ -1281,6 +1600,9 @@ impl Simplifiable for Node {
                 if let Node::Num(ref n) = simplified {
                     return Ok(Node::Num(n.ceil()));
                 }
+                if is_integer_valued(&simplified, env) {
+                    return Ok(simplified);
+                }
                 Ok(Node::Ceil(Box::new(simplified)))
             }
             Node::Round(operand) => {
@@ -1288,6 +1610,9 @@ impl Simplifiable for Node {
                 if let Node::Num(ref n) = simplified {
                     return Ok(Node::Num(n.round()));
                 }
+                if is_integer_valued(&simplified, env) {
+                    return Ok(simplified);
+                }
                 Ok(Node::Round(Box::new(simplified)))
             }
             Node::Trunc(operand) => {
@@ -1295,6 +1620,9 @@ impl Simplifiable for Node {
                 if let Node::Num(ref n) = simplified {
                     return Ok(Node::Num(n.trunc()));
                 }
+                if is_integer_valued(&simplified, env) {
+                    return Ok(simplified);
+                }
                 Ok(Node::Trunc(Box::new(simplified)))
             }
             Node::Sqrt(operand) => {
@@ -1350,22 +1678,27 @@ impl Simplifiable for Node {
                 if let Some(result) = try_simplify_sqrt_product(&simplified, env, true) {
                     return Ok(result);
                 }
-                // sqrt(x²) → x when x positive, |x| otherwise
+                // sqrt(x²) → x when x positive, |x| otherwise; sqrt(x⁴) → x²
                 if let Node::Power(ref base, ref exp) = simplified {
-                    if let Node::Num(ref e) = **exp {
-                        if e == &ExactNum::two() {
-                            if let Node::Variable(ref v) = **base {
-                                if env.assumptions().is_nonneg(v) {
-                                    return Ok(*base.clone());
-                                }
-                            }
-                            return Ok(Node::Abs(base.clone()));
-                        }
+                    if let Some(result) = sqrt_of_even_power(base, exp, env) {
+                        return result;
                     }
                 }
                 Ok(Node::Sqrt(Box::new(simplified)))
             }
             Node::Function(name, args) => {
+                let options = env.simplify_options();
+                if options.sin_cos_form {
+                    if let Some(rewritten) = in_sin_cos(name, args) {
+                        return rewritten.simplify(env);
+                    }
+                }
+                if !options.log_exp_inverses {
+                    if let Some(kept) = keep_log_exp_inverse(name, args, env) {
+                        return kept;
+                    }
+                }
+
                 // Fold `exp(ln x)` and `exp(k·ln a)` before inner rewrites (e.g. `log(10) → 1`).
                 if name == "exp" && args.len() == 1 {
                     if let Some(result) = try_exact_function_value(name, args) {
@@ -1373,11 +1706,27 @@ impl Simplifiable for Node {
                     }
                 }
 
+                // Cancel inverse pairs before the inner call folds to an exact
+                // value: arctan(tan(π/5)) would become arctan(√(5 − 2√5)).
+                if let [arg] = args.as_slice() {
+                    if let Some(cancelled) = cancel_inverse_composition(name, arg, env) {
+                        return cancelled.simplify(env);
+                    }
+                }
+
                 let simplified_args: Vec<Node> = args
                     .iter()
                     .map(|a| a.simplify(env))
                     .collect::<Result<Vec<_>, _>>()?;
 
+                if let Some(result) = vector::simplify_vector_function(name, &simplified_args, env)
+                {
+                    return result;
+                }
+                if let Some(result) = simplify_matrix_function(name, &simplified_args, env) {
+                    return Ok(result?);
+                }
+
                 if simplified_args.len() == 1 {
                     if let Some(exact) = try_exact_function_value(name, &simplified_args) {
                         return Ok(exact);
@@ -1386,6 +1735,11 @@ impl Simplifiable for Node {
                     if name == "abs" {
                         return Ok(Node::Abs(Box::new(simplified_args[0].clone())));
                     }
+                    if matches!(name.as_str(), "floor" | "ceil" | "round" | "trunc")
+                        && is_integer_valued(&simplified_args[0], env)
+                    {
+                        return Ok(simplified_args[0].clone());
+                    }
                     if name == "floor" {
                         return Ok(Node::Floor(Box::new(simplified_args[0].clone())));
                     }
@@ -1400,6 +1754,9 @@ impl Simplifiable for Node {
                     }
 
                     let arg = &simplified_args[0];
+                    if let Some(cancelled) = cancel_inverse_composition(name, arg, env) {
+                        return cancelled.simplify(env);
+                    }
                     match name.as_str() {
                         "ln" | "log" | "lg" => {
                             if let Some(result) = simplify_log_function(name, arg, env) {
@@ -1463,17 +1820,10 @@ impl Simplifiable for Node {
                             if let Some(result) = try_simplify_sqrt_product(arg, env, false) {
                                 return Ok(result);
                             }
-                            // sqrt(x²) → x when x nonneg, |x| otherwise
+                            // sqrt(x²) → x when x nonneg, |x| otherwise; sqrt(x⁴) → x²
                             if let Node::Power(base, exp) = arg {
-                                if let Node::Num(ref e) = **exp {
-                                    if e == &ExactNum::two() {
-                                        if let Node::Variable(ref v) = **base {
-                                            if env.assumptions().is_nonneg(v) {
-                                                return Ok(*base.clone());
-                                            }
-                                        }
-                                        return Ok(Node::Abs(base.clone()));
-                                    }
+                                if let Some(result) = sqrt_of_even_power(base, exp, env) {
+                                    return result;
                                 }
                             }
                         }
@@ -1537,7 +1887,9 @@ impl Simplifiable for Node {
                     .iter()
                     .any(|a| matches!(a, Node::Num(ExactNum::Float(_))));
                 let keep_symbolic = is_transcendental_function(name) && !any_inexact;
-                if all_numeric && !keep_symbolic {
+                // A special function still folds where its value is exact
+                // (Γ(5) = 24, B(2, 3) = 1/12).
+                if all_numeric && (!keep_symbolic || is_special_function(name)) {
                     let exact_args: Vec<ExactNum> = simplified_args
                         .iter()
                         .map(|a| {
@@ -1551,7 +1903,9 @@ impl Simplifiable for Node {
                     if let Ok(result) = crate::functions::call_function(name, exact_args) {
                         match &result {
                             ExactNum::Rational(_) => return Ok(Node::Num(result)),
-                            ExactNum::Float(f) if f.is_finite() => return Ok(Node::Num(result)),
+                            ExactNum::Float(f) if f.is_finite() && !keep_symbolic => {
+                                return Ok(Node::Num(result))
+                            }
                             _ => {}
                         }
                     }
@@ -1559,11 +1913,61 @@ impl Simplifiable for Node {
 
                 Ok(Node::Function(name.clone(), simplified_args))
             }
+            Node::Matrix(rows) => Ok(Node::Matrix(
+                rows.iter()
+                    .map(|row| row.iter().map(|e| e.simplify(env)).collect())
+                    .collect::<Result<_, _>>()?,
+            )),
+            Node::Variable(name) if env.simplify_options().fold_constants => {
+                Ok(match crate::constants::Constant::from_name(name) {
+                    Some(constant) => Node::Num(ExactNum::Float(constant.value())),
+                    None => self.clone(),
+                })
+            }
             _ => Ok(self.clone()),
         }
     }
 }
 
+/// `(p/q)·expr` as the quotient `\frac{p·expr}{q}`, which is how
+/// `\frac{2x}{4}` reads once reduced: `\frac{x}{2}`, not `\frac{1}{2}x`.
+/// `None` for a whole or float coefficient.
+fn scaled_quotient(
+    coefficient: &ExactNum,
+    expr: &Node,
+    env: &Environment,
+) -> Option<Result<Node, String>> {
+    let ExactNum::Rational(r) = coefficient else {
+        return None;
+    };
+    if r.is_integer() {
+        return None;
+    }
+    let numerator = ExactNum::Rational(BigRational::from_integer(r.numer().abs()));
+    let denominator = Node::Num(ExactNum::Rational(BigRational::from_integer(
+        r.denom().clone(),
+    )));
+    let scaled = if numerator.is_one() {
+        Ok(expr.clone())
+    } else {
+        Node::Multiply(Box::new(Node::Num(numerator)), Box::new(expr.clone())).simplify(env)
+    };
+    Some(scaled.map(|scaled| {
+        let quotient = Node::Divide(Box::new(scaled), Box::new(denominator));
+        if r.is_negative() {
+            Node::Negate(Box::new(quotient))
+        } else {
+            quotient
+        }
+    }))
+}
+
+/// Whether `node` is a matrix literal, making an operation on it a matrix
+/// operation.
+fn is_matrix(node: &Node) -> bool {
+    matches!(node, Node::Matrix(_))
+}
+
 fn has_leading_negative(node: &Node) -> bool {
     match node {
         Node::Negate(_) => true,
@@ -1584,110 +1988,111 @@ fn negate_leading(node: &Node) -> Node {
     }
 }
 
-fn collect_terms_inner(
-    node: &Node,
-    term_map: &mut HashMap<String, ExactNum>,
-    sign: &ExactNum,
-) -> Result<(), String> {
-    match node {
-        Node::Add(left, right) => {
-            collect_terms_inner(left, term_map, sign)?;
-            collect_terms_inner(right, term_map, sign)?;
-        }
-        Node::Subtract(left, right) => {
-            collect_terms_inner(left, term_map, sign)?;
-            let neg_sign = sign.clone() * ExactNum::integer(-1);
-            collect_terms_inner(right, term_map, &neg_sign)?;
+/// Sums with at least this many terms are simplified term by term and put
+/// in canonical form once. Simplifying them pair by pair, as shorter sums
+/// are, would canonicalize every prefix of the sum again, which makes a
+/// sum of n terms cost n² or worse.
+const FLAT_SUM_TERMS: usize = 8;
+
+/// A sum of [`FLAT_SUM_TERMS`] or more terms, simplified in one pass over
+/// its simplified terms. `None` for shorter sums, sums with a term that
+/// is not a [like term](is_like_term) (fractions combine over a common
+/// denominator and products of sums expand only pair by pair), and when
+/// the environment has rewrite rules, which may match partial sums.
+fn simplify_long_sum(sum: &Node, env: &Environment) -> Option<Result<Node, String>> {
+    if !env.rules().is_empty() {
+        return None;
+    }
+    let terms = sum.sum_terms();
+    if terms.len() < FLAT_SUM_TERMS || !terms.iter().all(|(term, _)| is_like_term(term)) {
+        return None;
+    }
+    let pythagorean = env.simplify_options().pythagorean;
+    let mut simplified = Vec::with_capacity(terms.len());
+    for (term, negative) in terms {
+        let term = match term.simplify(env) {
+            Ok(term) => term,
+            Err(e) => return Some(Err(e)),
+        };
+        // `1 - \sin^{2} u → \cos^{2} u` and its kin match pairs of terms
+        if !is_like_term(&term) || (pythagorean && has_trig_power(&term)) {
+            return None;
         }
-        Node::Negate(inner) => {
-            let neg_sign = sign.clone() * ExactNum::integer(-1);
-            collect_terms_inner(inner, term_map, &neg_sign)?;
+        if !is_zero_node(&term) {
+            simplified.push((term, negative));
         }
-        Node::Multiply(left, right) => {
-            if let (Node::Num(ref coef), Node::Variable(ref var)) = (&**left, &**right) {
-                let entry = term_map.entry(var.clone()).or_insert_with(ExactNum::zero);
-                *entry = entry.clone() + coef.clone() * sign.clone();
+    }
+    let sum = match simplified.len() {
+        0 => return Some(Ok(Node::Num(ExactNum::zero()))),
+        1 => {
+            let (term, negative) = simplified.pop().expect("one term");
+            return Some(if negative {
+                Node::Negate(Box::new(term)).simplify(env)
             } else {
-                return Err("Unsupported multiply form in collect_terms".to_string());
-            }
+                Ok(term)
+            });
         }
-        Node::Variable(var) => {
-            let entry = term_map.entry(var.clone()).or_insert_with(ExactNum::zero);
-            *entry = entry.clone() + sign.clone();
+        _ => rebuild_signed_terms(simplified),
+    };
+    if pythagorean {
+        if let Some(result) = PYTHAGOREAN_RULES.rewrite(&sum) {
+            return Some(Ok(result));
         }
-        Node::Num(num) => {
-            let entry = term_map
-                .entry("".to_string())
-                .or_insert_with(ExactNum::zero);
-            *entry = entry.clone() + num.clone() * sign.clone();
+        if let Some(reduced) = try_pythagorean_in_sum(&sum) {
+            return Some(reduced.simplify(env));
         }
-        _ => return Err("Unsupported node type in collect_terms".to_string()),
     }
-    Ok(())
-}
-
-fn collect_terms(
-    node: &Node,
-    term_map: &mut HashMap<String, ExactNum>,
-    _env: &Environment,
-) -> Result<(), String> {
-    collect_terms_inner(node, term_map, &ExactNum::one())
+    if let Some(combined) = try_combine_like_radicals(&sum, env) {
+        return Some(Ok(combined));
+    }
+    Some(Ok(canonical_sum(sum, env)))
 }
 
-fn rebuild_expression(term_map: HashMap<String, ExactNum>) -> Node {
-    let mut terms: Vec<(String, ExactNum)> = term_map.into_iter().collect();
-
-    // Sort: variables alphabetically first, constant term last
-    terms.sort_by(|a, b| match (a.0.is_empty(), b.0.is_empty()) {
-        (true, false) => std::cmp::Ordering::Greater,
-        (false, true) => std::cmp::Ordering::Less,
-        _ => a.0.cmp(&b.0),
-    });
-
-    // Build (abs_node, is_negative) pairs for non-zero terms
-    let mut signed_terms: Vec<(Node, bool)> = vec![];
-
-    for (var, coef) in terms {
-        if coef.is_zero() {
-            continue;
+/// Whether `term` is a number times powers of variables and function
+/// applications, with positive numeric exponents: a term that
+/// [`LikeTerms`] merges the way the pairwise rules for `+` and `-` would.
+fn is_like_term(term: &Node) -> bool {
+    match term {
+        Node::Num(_) | Node::Variable(_) | Node::Function(..) => true,
+        Node::Negate(inner) => is_like_term(inner),
+        Node::Multiply(left, right) => is_like_term(left) && is_like_term(right),
+        Node::Power(base, exponent) => {
+            matches!(**base, Node::Variable(_) | Node::Function(..))
+                && matches!(&**exponent, Node::Num(e) if !e.is_negative() && !e.is_zero())
         }
-        let negative = coef.is_negative();
-        let abs_coef = if negative {
-            -coef.clone()
-        } else {
-            coef.clone()
-        };
-
-        let node = if var.is_empty() {
-            Node::Num(abs_coef)
-        } else if abs_coef.is_one() {
-            Node::Variable(var)
-        } else {
-            Node::Multiply(Box::new(Node::Num(abs_coef)), Box::new(Node::Variable(var)))
-        };
-        signed_terms.push((node, negative));
+        _ => false,
     }
+}
 
-    if signed_terms.is_empty() {
-        return Node::Num(ExactNum::zero());
+/// Whether a factor of `term` is a power of a trigonometric function.
+fn has_trig_power(term: &Node) -> bool {
+    match term {
+        Node::Negate(inner) => has_trig_power(inner),
+        Node::Multiply(left, right) => has_trig_power(left) || has_trig_power(right),
+        Node::Power(base, _) => matches!(
+            &**base,
+            Node::Function(name, _) if matches!(name.as_str(), "sin" | "cos" | "tan" | "sec" | "csc" | "cot")
+        ),
+        _ => false,
     }
+}
 
-    let (first_node, first_neg) = signed_terms.remove(0);
-    let mut result = if first_neg {
-        Node::Negate(Box::new(first_node))
+/// A simplified sum in canonical form: a linear sum by [`LikeTerms`], a
+/// polynomial or rational function in normal form, and anything else with
+/// its like terms collected when that shortens it.
+fn canonical_sum(sum: Node, env: &Environment) -> Node {
+    let like_terms = LikeTerms::collect(&sum);
+    if like_terms.is_linear() {
+        like_terms.to_node()
+    } else if let Some(normalized) = try_polynomial_normalize(&sum) {
+        normalized
+    } else if let Some(normalized) = try_rational_normalize(&sum, env) {
+        normalized
+    } else if like_terms.combines() {
+        like_terms.to_node()
     } else {
-        first_node
-    };
-
-    for (node, negative) in signed_terms {
-        result = if negative {
-            Node::Subtract(Box::new(result), Box::new(node))
-        } else {
-            Node::Add(Box::new(result), Box::new(node))
-        };
+        sum
     }
-
-    result
 }
 
 fn find_single_variable(node: &Node) -> Option<String> {
@@ -1751,6 +2156,9 @@ fn try_rational_normalize(node: &Node, env: &Environment) -> Option<Node> {
 
 fn try_polynomial_normalize(node: &Node) -> Option<Node> {
     if let Some(var) = find_single_variable(node) {
+        if let Some((coeff, degree)) = as_monomial(node, &var) {
+            return Some(monomial_to_node(&coeff, degree, &var));
+        }
         let poly = Polynomial::from_node(node, &var).ok()?;
         return Some(poly.to_node());
     }
@@ -1980,42 +2388,44 @@ fn is_trig_squared(node: &Node, func_name: &str) -> Option<Vec<Node>> {
     None
 }
 
-fn try_pythagorean(left: &Node, right: &Node) -> Option<Node> {
-    // sin²(x) + cos²(x) → 1
-    if let (Some(sin_args), Some(cos_args)) =
-        (is_trig_squared(left, "sin"), is_trig_squared(right, "cos"))
-    {
-        if sin_args == cos_args {
-            return Some(Node::Num(ExactNum::one()));
-        }
-    }
-    // cos²(x) + sin²(x) → 1
-    if let (Some(cos_args), Some(sin_args)) =
-        (is_trig_squared(left, "cos"), is_trig_squared(right, "sin"))
-    {
-        if cos_args == sin_args {
-            return Some(Node::Num(ExactNum::one()));
-        }
+/// c·sin²(u) + c·cos²(u) → c anywhere in a flat sum of three or more terms,
+/// so the identity does not depend on the two squares being adjacent.
+fn try_pythagorean_in_sum(sum: &Node) -> Option<Node> {
+    let mut terms = Vec::new();
+    flatten_add_sub_terms(sum, &mut terms, false);
+    if terms.len() < 3 {
+        return None;
     }
 
-    // a·sin²(x) + a·cos²(x) → a (with coefficient)
-    if let (Some((coeff_l, sin_args)), Some((coeff_r, cos_args))) = (
-        extract_coeff_trig_sq(left, "sin"),
-        extract_coeff_trig_sq(right, "cos"),
-    ) {
-        if sin_args == cos_args && coeff_l == coeff_r {
-            return Some(Node::Num(coeff_l));
-        }
-    }
-    if let (Some((coeff_l, cos_args)), Some((coeff_r, sin_args))) = (
-        extract_coeff_trig_sq(left, "cos"),
-        extract_coeff_trig_sq(right, "sin"),
-    ) {
-        if cos_args == sin_args && coeff_l == coeff_r {
-            return Some(Node::Num(coeff_l));
+    let signed = |(term, negative): &(Node, bool), func: &str| {
+        extract_coeff_trig_sq(term, func).map(|(c, args)| (if *negative { -c } else { c }, args))
+    };
+    for i in 0..terms.len() {
+        let Some((c_sin, sin_args)) = signed(&terms[i], "sin") else {
+            continue;
+        };
+        let partner = (0..terms.len()).find(|&j| {
+            j != i
+                && signed(&terms[j], "cos")
+                    .is_some_and(|(c_cos, cos_args)| c_cos == c_sin && cos_args == sin_args)
+        });
+        if let Some(j) = partner {
+            let rest = terms
+                .iter()
+                .enumerate()
+                .filter(|(k, _)| *k != i && *k != j)
+                .map(|(_, t)| t);
+            let mut result = Node::Num(c_sin);
+            for (term, negative) in rest {
+                result = if *negative {
+                    Node::Subtract(Box::new(result), Box::new(term.clone()))
+                } else {
+                    Node::Add(Box::new(result), Box::new(term.clone()))
+                };
+            }
+            return Some(result);
         }
     }
-
     None
 }
 
@@ -2081,19 +2491,83 @@ fn radicals_match(left: &Node, right: &Node) -> bool {
     }
 }
 
-fn simplify_sqrt_squared(radicand: Node, env: &Environment) -> Option<Node> {
-    if let Node::Num(ref n) = radicand {
-        if n.is_negative() {
-            return None;
-        }
+/// `√(u^{2k}) = |u|^k`, with the bars dropped when `k` is even or `u` is
+/// a variable assumed nonnegative. `None` unless the exponent is a
+/// positive even integer.
+fn sqrt_of_even_power(base: &Node, exp: &Node, env: &Environment) -> Option<Result<Node, String>> {
+    let Node::Num(e) = exp else {
+        return None;
+    };
+    if !e.is_integer() || !e.is_even() || e.is_negative() || e.is_zero() {
+        return None;
     }
-    if let Node::Variable(ref v) = radicand {
-        if env.assumptions().is_nonneg(v) {
-            return Some(radicand);
-        }
-        return Some(Node::Abs(Box::new(radicand)));
+    let half = e.clone() / ExactNum::two();
+    let nonneg = matches!(base, Node::Variable(v) if env.assumptions().is_nonneg(v));
+    let root = if nonneg || half.is_even() {
+        base.clone()
+    } else {
+        Node::Abs(Box::new(base.clone()))
+    };
+    if half.is_one() {
+        return Some(Ok(root));
     }
-    radicand.simplify(env).ok()
+    Some(Node::Power(Box::new(root), Box::new(Node::Num(half))).simplify(env))
+}
+
+/// Cancel a function applied to its own inverse. `f(f⁻¹(u)) → u` holds
+/// wherever the composition is defined (`\sin(\arcsin u)`,
+/// `\tanh(\arctanh u)`), as do the bijective hyperbolic round trips
+/// `\arcsinh(\sinh u)` and `\arctanh(\tanh u)`; `\arccosh(\cosh u) = |u|`.
+/// These are the rewrite rules in [`INVERSE_RULES`]. The circular inverses only undo their function on the principal
+/// branch, so `\arcsin(\sin u)`, `\arctan(\tan u)` and `\arccos(\cos u)`
+/// cancel only for a constant `u` inside that range. (`ln`/`exp` pairs
+/// are handled by the log and exp rules.)
+fn cancel_inverse_composition(name: &str, arg: &Node, env: &Environment) -> Option<Node> {
+    let Node::Function(inner_name, inner_args) = arg else {
+        return None;
+    };
+    let [u] = inner_args.as_slice() else {
+        return None;
+    };
+    let half_pi = std::f64::consts::FRAC_PI_2;
+    let in_range = |lo: f64, hi: f64| {
+        crate::evaluator::Evaluator::evaluate(u, env)
+            .is_ok_and(|v| v >= lo - 1e-12 && v <= hi + 1e-12)
+    };
+    let (name, inner_name) = (
+        canonical_function_name(name),
+        canonical_function_name(inner_name),
+    );
+    let composition = Node::Function(
+        name.to_string(),
+        vec![Node::Function(inner_name.to_string(), vec![u.clone()])],
+    );
+    if let Some(cancelled) = INVERSE_RULES.rewrite(&composition) {
+        return Some(cancelled);
+    }
+    let cancels = match (name, inner_name) {
+        ("arcsin", "sin") => in_range(-half_pi, half_pi),
+        // tan is undefined at ±π/2 itself
+        ("arctan", "tan") => in_range(-half_pi + 1e-9, half_pi - 1e-9),
+        ("arccos", "cos") => in_range(0.0, std::f64::consts::PI),
+        _ => false,
+    };
+    cancels.then(|| u.clone())
+}
+
+fn simplify_sqrt_squared(radicand: Node, env: &Environment) -> Option<Node> {
+    if let Node::Num(ref n) = radicand {
+        if n.is_negative() {
+            return None;
+        }
+    }
+    if let Node::Variable(ref v) = radicand {
+        if env.assumptions().is_nonneg(v) {
+            return Some(radicand);
+        }
+        return Some(Node::Abs(Box::new(radicand)));
+    }
+    radicand.simplify(env).ok()
 }
 
 /// Attach a scalar coefficient to a simplified term (folds into `Num` when possible).
@@ -2770,6 +3244,10 @@ fn try_geometric_sum(
     env: &Environment,
 ) -> Option<Result<Node, String>> {
     let (coeff, base) = detect_geometric(body, index_var)?;
+    if proper_fraction(&base).is_some() {
+        // Written from the first term by the ratio rule: 1 − (½)^n
+        return None;
+    }
 
     let end_plus_1 = Node::Add(
         Box::new(end.clone()),
@@ -2790,6 +3268,295 @@ fn try_geometric_sum(
     Some(result.simplify(env))
 }
 
+/// Split `body` into a factor free of `index_var` and factors `b^{e(k)}`
+/// with `b` free of `index_var` and `e` linear in it. `None` when some
+/// factor is neither, or no exponent actually depends on the index.
+fn exponential_factors(body: &Node, index_var: &str) -> Option<(Node, Vec<(Node, Node)>)> {
+    fn split(node: &Node, index_var: &str, out: &mut Vec<(Node, Node)>) -> Option<Node> {
+        if !node.contains_variable(index_var) {
+            return Some(node.clone());
+        }
+        match node {
+            Node::Multiply(l, r) => Some(Node::Multiply(
+                Box::new(split(l, index_var, out)?),
+                Box::new(split(r, index_var, out)?),
+            )),
+            Node::Divide(l, r) => {
+                let numer = split(l, index_var, out)?;
+                let mut denominator_factors = Vec::new();
+                let denom = split(r, index_var, &mut denominator_factors)?;
+                out.extend(
+                    denominator_factors
+                        .into_iter()
+                        .map(|(base, exp)| (base, Node::Negate(Box::new(exp)))),
+                );
+                Some(Node::Divide(Box::new(numer), Box::new(denom)))
+            }
+            Node::Negate(inner) => Some(Node::Negate(Box::new(split(inner, index_var, out)?))),
+            Node::Power(base, exp) if !base.contains_variable(index_var) => {
+                let degree = Polynomial::from_node(exp, index_var).ok()?.degree()?;
+                (degree <= 1).then(|| out.push((*base.clone(), *exp.clone())))?;
+                Some(Node::Num(ExactNum::one()))
+            }
+            _ => None,
+        }
+    }
+    let mut factors = Vec::new();
+    let coefficient = split(body, index_var, &mut factors)?;
+    factors.retain(|(_, exp)| exp.contains_variable(index_var));
+    (!factors.is_empty()).then_some((coefficient, factors))
+}
+
+/// The number of terms in `Σ_{k=start}^{end}`: `end − start + 1`.
+fn range_count(start: &Node, end: &Node) -> Node {
+    Node::Add(
+        Box::new(Node::Subtract(
+            Box::new(end.clone()),
+            Box::new(start.clone()),
+        )),
+        Box::new(Node::Num(ExactNum::integer(1))),
+    )
+}
+
+/// Geometric series found from the ratio of consecutive terms rather than
+/// the `a·r^k` shape: when every factor of the body is constant or
+/// `b^{e(k)}` with `e` linear, `f(k+1)/f(k) = ∏ b^{e'}` is free of `k`, and
+/// Σ_{k=a}^{b} f(k) = f(a)·(r^{b−a+1} − 1)/(r − 1). Covers `3·2^{k+1}`,
+/// `\frac{1}{3^k}`, `x^{k−1}` and `\frac{5·3^k}{2^k}`.
+fn try_ratio_geometric_sum(
+    index_var: &str,
+    start: &Node,
+    end: &Node,
+    body: &Node,
+    env: &Environment,
+) -> Option<Result<Node, String>> {
+    let (_, factors) = exponential_factors(body, index_var)?;
+    let mut ratio = Node::Num(ExactNum::one());
+    for (base, exp) in factors {
+        let slope = Polynomial::from_node(&exp, index_var).ok()?.coeff(1);
+        ratio = Node::Multiply(
+            Box::new(ratio),
+            Box::new(Node::Power(
+                Box::new(base),
+                Box::new(Node::Num(ExactNum::Rational(slope))),
+            )),
+        );
+    }
+    let ratio = match ratio.simplify(env) {
+        Ok(r) => r,
+        Err(e) => return Some(Err(e)),
+    };
+    let first = match crate::substitute::substitute_variable(body, index_var, start) {
+        Ok(f) => f,
+        Err(e) => return Some(Err(e)),
+    };
+    let count = range_count(start, end);
+    let result = if matches!(&ratio, Node::Num(r) if r.is_one()) {
+        Node::Multiply(Box::new(first), Box::new(count))
+    } else if let Some(r) = proper_fraction(&ratio) {
+        // |r| < 1: f(a)/(1 − r) · (1 − r^{count}), with f(a)/(1 − r)
+        // folded when the first term is a number
+        let scale = Node::Divide(
+            Box::new(first),
+            Box::new(Node::Num(ExactNum::one() - r.clone())),
+        );
+        let scale = match scale.simplify(env) {
+            Ok(s) => s,
+            Err(e) => return Some(Err(e)),
+        };
+        let tail = Node::Subtract(
+            Box::new(Node::Num(ExactNum::one())),
+            Box::new(Node::Power(Box::new(ratio), Box::new(count))),
+        );
+        if matches!(&scale, Node::Num(s) if s.is_one()) {
+            tail
+        } else {
+            Node::Multiply(Box::new(scale), Box::new(tail))
+        }
+    } else {
+        let numerator = Node::Subtract(
+            Box::new(Node::Power(Box::new(ratio.clone()), Box::new(count))),
+            Box::new(Node::Num(ExactNum::one())),
+        );
+        let denominator = Node::Subtract(Box::new(ratio), Box::new(Node::Num(ExactNum::one())));
+        Node::Multiply(
+            Box::new(first),
+            Box::new(Node::Divide(Box::new(numerator), Box::new(denominator))),
+        )
+    };
+    Some(result.simplify(env))
+}
+
+/// A numeric ratio strictly between −1 and 1 (other than 0).
+fn proper_fraction(node: &Node) -> Option<&ExactNum> {
+    match node {
+        Node::Num(r) if !r.is_zero() && r.to_f64().abs() < 1.0 => Some(r),
+        _ => None,
+    }
+}
+
+/// Arithmetic series found from the difference of consecutive terms: when
+/// `f(k+1) − f(k)` simplifies to something free of `k`, Σ_{k=a}^{b} f(k) is
+/// the term count times the mean of the first and last terms.
+fn try_difference_arithmetic_sum(
+    index_var: &str,
+    start: &Node,
+    end: &Node,
+    body: &Node,
+    env: &Environment,
+) -> Option<Result<Node, String>> {
+    let at = |k: &Node| crate::substitute::substitute_variable(body, index_var, k).ok();
+    let next = Node::Add(
+        Box::new(Node::Variable(index_var.to_string())),
+        Box::new(Node::Num(ExactNum::one())),
+    );
+    let difference = Node::Subtract(Box::new(at(&next)?), Box::new(body.clone()))
+        .simplify(env)
+        .ok()?;
+    if difference.contains_variable(index_var) {
+        return None;
+    }
+    let ends = Node::Add(Box::new(at(start)?), Box::new(at(end)?));
+    let result = Node::Divide(
+        Box::new(Node::Multiply(
+            Box::new(range_count(start, end)),
+            Box::new(ends),
+        )),
+        Box::new(Node::Num(ExactNum::integer(2))),
+    );
+    Some(result.simplify(env))
+}
+
+/// Σ_{k=−m}^{m} f(k) = 0 for odd `f`: the terms cancel in pairs.
+fn try_odd_symmetric_sum(
+    index_var: &str,
+    start: &Node,
+    end: &Node,
+    body: &Node,
+    env: &Environment,
+) -> Option<Result<Node, String>> {
+    let bound_sum = Node::Add(Box::new(start.clone()), Box::new(end.clone()))
+        .simplify(env)
+        .ok()?;
+    let symmetric = matches!(bound_sum, Node::Num(ref n) if n.is_zero());
+    (symmetric && symbolic_parity(body, index_var) == Some(Parity::Odd))
+        .then(|| Ok(Node::Num(ExactNum::zero())))
+}
+
+/// Longest period worth summing one cycle of.
+const MAX_SUMMATION_PERIOD: i64 = 64;
+
+/// The period of `body` over the integers, when it is built from
+/// `(−1)^{e(k)}` and trig functions of `cπk + d` with `c` rational.
+/// `\cos(\frac{\pi k}{2})` has period 4, `(−1)^k \sin(\frac{\pi k}{3})` 6.
+fn integer_period(body: &Node, index_var: &str) -> Option<i64> {
+    if !body.contains_variable(index_var) {
+        return Some(1);
+    }
+    let lcm_of = |a: i64, b: i64| a / a.gcd(&b) * b;
+    match body {
+        Node::Power(base, exp) if matches!(**base, Node::Num(ref n) if n == &-ExactNum::one()) => {
+            let poly = Polynomial::from_node(exp, index_var).ok()?;
+            if poly.degree()? > 1 {
+                return None;
+            }
+            let slope = poly.coeff(1);
+            slope
+                .is_integer()
+                .then(|| if slope.to_integer().is_even() { 1 } else { 2 })
+        }
+        Node::Function(name, args) if args.len() == 1 => {
+            let half_turns = match canonical_function_name(name) {
+                "sin" | "cos" | "sec" | "csc" => 2,
+                "tan" | "cot" => 1,
+                _ => return integer_period(&args[0], index_var),
+            };
+            // The coefficient of k, as a multiple of π: it doubles when π does
+            let slope_with_pi = |pi: i64| -> Option<BigRational> {
+                let arg = crate::substitute::substitute_variable(
+                    &args[0],
+                    "π",
+                    &Node::Num(ExactNum::integer(pi)),
+                )
+                .ok()?;
+                let poly = Polynomial::from_node(&arg, index_var).ok()?;
+                (poly.degree()? <= 1).then(|| poly.coeff(1))
+            };
+            let c = slope_with_pi(1)?;
+            if slope_with_pi(2)? != &c * BigRational::from_integer(BigInt::from(2)) || c.is_zero() {
+                return None;
+            }
+            // Smallest P with P·c a multiple of `half_turns`
+            let (a, b) = (c.numer().abs(), c.denom().clone());
+            let turn = BigInt::from(half_turns) * &b;
+            let period = (&turn / a.gcd(&turn)).to_string().parse::<i64>().ok()?;
+            (period <= MAX_SUMMATION_PERIOD).then_some(period)
+        }
+        Node::Add(l, r)
+        | Node::Subtract(l, r)
+        | Node::Multiply(l, r)
+        | Node::Divide(l, r)
+        | Node::Power(l, r) => {
+            let period = lcm_of(integer_period(l, index_var)?, integer_period(r, index_var)?);
+            (period <= MAX_SUMMATION_PERIOD).then_some(period)
+        }
+        Node::Negate(inner) | Node::Abs(inner) | Node::Sqrt(inner) => {
+            integer_period(inner, index_var)
+        }
+        Node::Function(_, args) => args.iter().try_fold(1, |p, arg| {
+            let period = lcm_of(p, integer_period(arg, index_var)?);
+            (period <= MAX_SUMMATION_PERIOD).then_some(period)
+        }),
+        _ => None,
+    }
+}
+
+/// A periodic body over a long integer range: whole cycles sum to
+/// `cycles · Σ(one period)`, and the leftover terms repeat the first ones,
+/// so Σ_{k=0}^{1000} \cos(\frac{\pi k}{2}) costs five terms, not 1001.
+fn try_periodic_sum(
+    index_var: &str,
+    start: &Node,
+    end: &Node,
+    body: &Node,
+    env: &Environment,
+) -> Option<Result<Node, String>> {
+    let (Node::Num(a), Node::Num(b)) = (start, end) else {
+        return None;
+    };
+    let (a, b) = (a.to_i64()?, b.to_i64()?);
+    let count = b.checked_sub(a)?.checked_add(1)?;
+    let period = integer_period(body, index_var)?;
+    if count <= period {
+        return None;
+    }
+    let partial = |terms: i64| -> Result<Node, String> {
+        let mut sum = Node::Num(ExactNum::zero());
+        for k in a..a + terms {
+            let term = crate::substitute::substitute_variable(
+                body,
+                index_var,
+                &Node::Num(ExactNum::integer(k)),
+            )?;
+            sum = Node::Add(Box::new(sum), Box::new(term));
+        }
+        sum.simplify(env)
+    };
+    let result = (|| {
+        let cycle = partial(period)?;
+        let rest = partial(count % period)?;
+        Node::Add(
+            Box::new(Node::Multiply(
+                Box::new(Node::Num(ExactNum::integer(count / period))),
+                Box::new(cycle),
+            )),
+            Box::new(rest),
+        )
+        .simplify(env)
+    })();
+    Some(result)
+}
+
 /// Telescoping: detect f(k) = g(k) − g(k±1) and collapse.
 /// Closed forms for Σ/Π (Faulhaber, geometric, telescoping, constant-body)
 /// are theorems about integer ranges. Substituting a numeric non-integer
@@ -2800,7 +3567,42 @@ fn try_geometric_sum(
 /// must keep its closed form.
 fn range_bounds_admit_closed_form(start: &Node, end: &Node) -> bool {
     let non_integer_numeric = |n: &Node| matches!(n, Node::Num(v) if !v.is_integer());
-    !non_integer_numeric(start) && !non_integer_numeric(end)
+    !non_integer_numeric(start)
+        && !non_integer_numeric(end)
+        && !crate::infinite_series::is_infinite_upper(end)
+}
+
+/// Σ_{k=a}^{∞} of a geometric body with a numeric ratio |r| < 1 is
+/// f(a)/(1 − r). Other infinite series are left to numeric evaluation.
+fn try_infinite_geometric_sum(
+    index_var: &str,
+    start: &Node,
+    body: &Node,
+    env: &Environment,
+) -> Option<Result<Node, String>> {
+    let (_, factors) = exponential_factors(body, index_var)?;
+    let mut ratio = Node::Num(ExactNum::one());
+    for (base, exp) in factors {
+        let slope = Polynomial::from_node(&exp, index_var).ok()?.coeff(1);
+        ratio = Node::Multiply(
+            Box::new(ratio),
+            Box::new(Node::Power(
+                Box::new(base),
+                Box::new(Node::Num(ExactNum::Rational(slope))),
+            )),
+        );
+    }
+    let ratio = ratio.simplify(env).ok()?;
+    let r = proper_fraction(&ratio)?;
+    let first = match crate::substitute::substitute_variable(body, index_var, start) {
+        Ok(f) => f,
+        Err(e) => return Some(Err(e)),
+    };
+    let result = Node::Divide(
+        Box::new(first),
+        Box::new(Node::Num(ExactNum::one() - r.clone())),
+    );
+    Some(result.simplify(env))
 }
 
 fn try_telescoping_sum(
@@ -3079,6 +3881,11 @@ fn try_symbolic_summation(
         return Some(result.simplify(env));
     }
 
+    // Odd body over a symmetric range: Σ_{k=-n}^{n} \sin(k) = 0
+    if let Some(result) = try_odd_symmetric_sum(index_var, start, end, body, env) {
+        return Some(result);
+    }
+
     // Polynomial body: Faulhaber's formulas
     if let Ok(poly) = Polynomial::from_node(body, index_var) {
         if let Some(result) = try_faulhaber_sum(&poly, start, end, env) {
@@ -3096,6 +3903,11 @@ fn try_symbolic_summation(
         return Some(result);
     }
 
+    // Geometric by consecutive-term ratio: 3·2^{k+1}, 1/3^k, x^{k-1}
+    if let Some(result) = try_ratio_geometric_sum(index_var, start, end, body, env) {
+        return Some(result);
+    }
+
     // Telescoping sum
     if let Some(result) = try_telescoping_sum(index_var, start, end, body, env) {
         return Some(result);
@@ -3106,6 +3918,16 @@ fn try_symbolic_summation(
         return Some(result);
     }
 
+    // Arithmetic by consecutive-term difference
+    if let Some(result) = try_difference_arithmetic_sum(index_var, start, end, body, env) {
+        return Some(result);
+    }
+
+    // Periodic body over a long numeric range: one cycle plus the remainder
+    if let Some(result) = try_periodic_sum(index_var, start, end, body, env) {
+        return Some(result);
+    }
+
     None
 }
 
@@ -3248,6 +4070,32 @@ fn try_symbolic_product(
                     }
                 }
             }
+            // Symbolic upper bound: ∏_{k=1}^{n} k = n!, and from a later
+            // integer start ∏_{k=a}^{n} k = n! / (a − 1)!
+            if let Node::Num(start_n) = start {
+                let start_i = start_n
+                    .to_i64()
+                    .filter(|&a| a >= 1 && start_n.is_integer())?;
+                let fact = Node::Factorial(Box::new(end.clone()));
+                if start_i == 1 {
+                    return Some(Ok(fact));
+                }
+                let below = crate::integer::factorial(&ExactNum::integer(start_i - 1))?;
+                let result = Node::Divide(Box::new(fact), Box::new(Node::Num(below)));
+                return Some(result.simplify(env));
+            }
+        }
+    }
+
+    // ∏ k^c = (∏ k)^c for an index-free exponent
+    if let Node::Power(base, exp) = body {
+        if matches!(&**base, Node::Variable(v) if v == index_var)
+            && !exp.contains_variable(index_var)
+        {
+            if let Some(Ok(product)) = try_symbolic_product(index_var, start, end, base, env) {
+                let result = Node::Power(Box::new(product), exp.clone());
+                return Some(result.simplify(env));
+            }
         }
     }
 
@@ -3262,6 +4110,400 @@ fn try_symbolic_product(
     None
 }
 
+/// A simplified expression together with the points its simplification
+/// added to the domain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DomainSimplification {
+    pub result: Node,
+    /// `(variable, value)` where the original expression divides by zero
+    /// and `result` does not: cancelling `x - 1` from
+    /// `\frac{x^{2} - 1}{x - 1}` leaves `x + 1`, and `(x, 1)`. Only rational
+    /// zeros of denominators in one variable are found.
+    pub excluded: Vec<(String, ExactNum)>,
+}
+
+impl DomainSimplification {
+    /// The exclusions as LaTeX, `x \neq 1, x \neq -2`, or `None` when the
+    /// simplification kept the domain.
+    pub fn domain_note(&self) -> Option<String> {
+        if self.excluded.is_empty() {
+            return None;
+        }
+        let conditions: Vec<String> = self
+            .excluded
+            .iter()
+            .map(|(var, value)| format!("{} \\neq {}", var, Node::Num(value.clone())))
+            .collect();
+        Some(conditions.join(", "))
+    }
+}
+
+/// Simplify `node` and report the points where it was undefined but its
+/// simplified form is not, which plain `simplify` drops silently: the
+/// simplifier works in the field of rational functions, where
+/// `\frac{x^{2} - 1}{x - 1}` and `x + 1` are equal.
+pub fn simplify_with_domain(
+    node: &Node,
+    env: &Environment,
+) -> Result<DomainSimplification, String> {
+    let result = node.simplify(env)?;
+    let (mut before, mut after) = (Vec::new(), Vec::new());
+    collect_denominators(node, &mut before);
+    collect_denominators(&result, &mut after);
+
+    let mut excluded: Vec<(String, ExactNum)> = Vec::new();
+    for denominator in &before {
+        let mut vars = std::collections::HashSet::new();
+        collect_variables(denominator, &mut vars);
+        let (Some(var), 1) = (vars.iter().next(), vars.len()) else {
+            continue;
+        };
+        let Ok(poly) = Polynomial::from_node(denominator, var) else {
+            continue;
+        };
+        for root in poly.rational_roots() {
+            let value = Node::Num(ExactNum::Rational(root.clone()));
+            let vanishes = |d: &Node| {
+                crate::substitute::substitute_variable(d, var, &value)
+                    .and_then(|d| d.simplify(env))
+                    .is_ok_and(|d| matches!(d, Node::Num(n) if n.is_zero()))
+            };
+            let point = (var.clone(), ExactNum::Rational(root));
+            if !after.iter().any(vanishes) && !excluded.contains(&point) {
+                excluded.push(point);
+            }
+        }
+    }
+    Ok(DomainSimplification { result, excluded })
+}
+
+/// Every divisor in `node`: right sides of quotients and bases of negative
+/// powers, nested ones included.
+fn collect_denominators(node: &Node, denominators: &mut Vec<Node>) {
+    match node {
+        Node::Divide(num, den) => {
+            denominators.push((**den).clone());
+            collect_denominators(num, denominators);
+            collect_denominators(den, denominators);
+        }
+        Node::Power(base, exponent) => {
+            if matches!(&**exponent, Node::Num(n) if n.is_negative()) {
+                denominators.push((**base).clone());
+            }
+            collect_denominators(base, denominators);
+            collect_denominators(exponent, denominators);
+        }
+        _ => {
+            for child in node.children() {
+                collect_denominators(child, denominators);
+            }
+        }
+    }
+}
+
+/// Upper bound on the number of terms a single distribution step in
+/// [`expand`] may produce; past it the expansion is refused rather than
+/// allowed to exhaust memory.
+const MAX_EXPANDED_TERMS: usize = 10_000;
+
+/// Expand products and non-negative integer powers of sums into a flat sum
+/// of monomials with like terms collected: `(x+1)^2 → x^2 + 2x + 1`,
+/// `(x+2)(x-3) → x^2 - x - 6`. Expansion reaches into function arguments
+/// and both sides of an equation; a quotient distributes its numerator
+/// over the (expanded) denominator. `simplify` alone keeps `(x+1)^2`
+/// compact — this is the opt-in pass that multiplies everything out.
+/// Terms are ordered by total degree, then lexicographically.
+pub fn expand(node: &Node, env: &Environment) -> Result<Node, String> {
+    match node {
+        Node::Equation(left, right) => Ok(Node::Equation(
+            Box::new(expand(left, env)?),
+            Box::new(expand(right, env)?),
+        )),
+        _ => Ok(rebuild_signed_terms(collect_expanded_terms(
+            expand_terms(node, env)?,
+            env,
+        ))),
+    }
+}
+
+/// Expand `node` into signed product terms (`true` marks a subtracted term).
+fn expand_terms(node: &Node, env: &Environment) -> Result<Vec<(Node, bool)>, String> {
+    let negated = |terms: Vec<(Node, bool)>| terms.into_iter().map(|(t, neg)| (t, !neg));
+    match node {
+        Node::Add(left, right) => {
+            let mut terms = expand_terms(left, env)?;
+            terms.extend(expand_terms(right, env)?);
+            Ok(terms)
+        }
+        Node::Subtract(left, right) => {
+            let mut terms = expand_terms(left, env)?;
+            terms.extend(negated(expand_terms(right, env)?));
+            Ok(terms)
+        }
+        Node::Negate(inner) => Ok(negated(expand_terms(inner, env)?).collect()),
+        Node::Multiply(left, right) => {
+            distribute_terms(&expand_terms(left, env)?, &expand_terms(right, env)?)
+        }
+        Node::Divide(num, den) => {
+            let den = expand(den, env)?;
+            Ok(expand_terms(num, env)?
+                .into_iter()
+                .map(|(t, neg)| (Node::Divide(Box::new(t), Box::new(den.clone())), neg))
+                .collect())
+        }
+        Node::Power(base, exp) => {
+            let base_terms = collect_expanded_terms(expand_terms(base, env)?, env);
+            let power = match exp.as_ref() {
+                Node::Num(n) if n.is_integer() => n.to_i64().filter(|&e| e >= 1),
+                _ => None,
+            };
+            match power {
+                Some(e) if base_terms.len() > 1 => {
+                    // Collect like terms after every step so (x+1)^n grows
+                    // linearly, not as 2^n unreduced products.
+                    let mut result = base_terms.clone();
+                    for _ in 1..e {
+                        result =
+                            collect_expanded_terms(distribute_terms(&result, &base_terms)?, env);
+                    }
+                    Ok(result)
+                }
+                _ => Ok(vec![(
+                    Node::Power(
+                        Box::new(rebuild_signed_terms(base_terms)),
+                        Box::new(expand(exp, env)?),
+                    ),
+                    false,
+                )]),
+            }
+        }
+        Node::Function(name, args) => {
+            let args = args
+                .iter()
+                .map(|arg| expand(arg, env))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(vec![(Node::Function(name.clone(), args), false)])
+        }
+        Node::Sqrt(inner) => Ok(vec![(Node::Sqrt(Box::new(expand(inner, env)?)), false)]),
+        Node::Abs(inner) => Ok(vec![(Node::Abs(Box::new(expand(inner, env)?)), false)]),
+        other => Ok(vec![(other.clone(), false)]),
+    }
+}
+
+/// Every pairwise product of two signed term lists.
+fn distribute_terms(
+    left: &[(Node, bool)],
+    right: &[(Node, bool)],
+) -> Result<Vec<(Node, bool)>, String> {
+    if left.len().saturating_mul(right.len()) > MAX_EXPANDED_TERMS {
+        return Err(format!(
+            "Expansion would exceed {} terms",
+            MAX_EXPANDED_TERMS
+        ));
+    }
+    let mut terms = Vec::with_capacity(left.len() * right.len());
+    for (l, l_neg) in left {
+        for (r, r_neg) in right {
+            terms.push((
+                Node::Multiply(Box::new(l.clone()), Box::new(r.clone())),
+                l_neg != r_neg,
+            ));
+        }
+    }
+    Ok(terms)
+}
+
+/// A monomial of an expansion: rational coefficient times factors, each a
+/// base raised to a numeric exponent, kept sorted by base.
+struct ExpandedMonomial {
+    coeff: ExactNum,
+    factors: Vec<(String, Node, ExactNum)>,
+}
+
+impl ExpandedMonomial {
+    fn from_term(term: &Node) -> Self {
+        let mut monomial = ExpandedMonomial {
+            coeff: ExactNum::one(),
+            factors: Vec::new(),
+        };
+        monomial.absorb(term, &ExactNum::one());
+        monomial.factors.retain(|(_, _, e)| !e.is_zero());
+        monomial
+            .factors
+            .sort_by(|a, b| factor_rank(a).cmp(&factor_rank(b)));
+        monomial
+    }
+
+    /// Multiply `node^exp` into the monomial.
+    fn absorb(&mut self, node: &Node, exp: &ExactNum) {
+        match node {
+            Node::Num(n) if exp.is_one() => self.coeff = self.coeff.clone() * n.clone(),
+            Node::Negate(inner) if exp.is_one() => {
+                self.coeff = -self.coeff.clone();
+                self.absorb(inner, exp);
+            }
+            Node::Multiply(left, right) => {
+                self.absorb(left, exp);
+                self.absorb(right, exp);
+            }
+            Node::Divide(num, den) if exp.is_one() => {
+                self.absorb(num, exp);
+                match den.as_ref() {
+                    Node::Num(d) if !d.is_zero() => {
+                        self.coeff = self.coeff.clone() / d.clone();
+                    }
+                    _ => self.absorb(den, &-exp.clone()),
+                }
+            }
+            Node::Power(base, e) => match e.as_ref() {
+                Node::Num(n) => self.absorb(base, &(exp.clone() * n.clone())),
+                _ => self.push_factor(node, exp),
+            },
+            _ => self.push_factor(node, exp),
+        }
+    }
+
+    fn push_factor(&mut self, base: &Node, exp: &ExactNum) {
+        let key = base.to_string();
+        if let Some(factor) = self.factors.iter_mut().find(|(k, _, _)| *k == key) {
+            factor.2 = factor.2.clone() + exp.clone();
+        } else {
+            self.factors.push((key, base.clone(), exp.clone()));
+        }
+    }
+
+    fn degree(&self) -> f64 {
+        self.factors.iter().map(|(_, _, e)| e.to_f64()).sum()
+    }
+
+    /// Graded lexicographic order: higher total degree first, then higher
+    /// powers of earlier bases first.
+    fn order(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+        let by_degree = other
+            .degree()
+            .partial_cmp(&self.degree())
+            .unwrap_or(Ordering::Equal);
+        if by_degree != Ordering::Equal {
+            return by_degree;
+        }
+        for (a, b) in self.factors.iter().zip(&other.factors) {
+            match factor_rank(a).cmp(&factor_rank(b)) {
+                Ordering::Equal => {}
+                unequal => return unequal,
+            }
+            match b.2.to_f64().partial_cmp(&a.2.to_f64()) {
+                Some(Ordering::Equal) | None => {}
+                Some(unequal) => return unequal,
+            }
+        }
+        other.factors.len().cmp(&self.factors.len())
+    }
+
+    /// The monomial without its coefficient's sign, and whether it is negative.
+    fn to_signed_node(&self) -> (Node, bool) {
+        let power = |base: &Node, e: &ExactNum| {
+            if e.is_one() {
+                base.clone()
+            } else {
+                Node::Power(Box::new(base.clone()), Box::new(Node::Num(e.clone())))
+            }
+        };
+        let product = |nodes: Vec<Node>| {
+            nodes
+                .into_iter()
+                .reduce(|acc, n| Node::Multiply(Box::new(acc), Box::new(n)))
+        };
+        let negative = self.coeff.to_f64() < 0.0;
+        let magnitude = self.coeff.abs();
+        let mut numer: Vec<Node> = Vec::new();
+        if !magnitude.is_one() || self.factors.iter().all(|(_, _, e)| e.to_f64() < 0.0) {
+            numer.push(Node::Num(magnitude));
+        }
+        numer.extend(
+            self.factors
+                .iter()
+                .filter(|(_, _, e)| e.to_f64() > 0.0)
+                .map(|(_, b, e)| power(b, e)),
+        );
+        let denom: Vec<Node> = self
+            .factors
+            .iter()
+            .filter(|(_, _, e)| e.to_f64() < 0.0)
+            .map(|(_, b, e)| power(b, &-e.clone()))
+            .collect();
+
+        let body = product(numer).expect("coefficient or factor present");
+        let node = match product(denom) {
+            Some(d) => Node::Divide(Box::new(body), Box::new(d)),
+            None => body,
+        };
+        (node, negative)
+    }
+}
+
+/// Variables order before compound factors (`x·sin(x)`, not `sin(x)·x`),
+/// then by printed form.
+fn factor_rank(factor: &(String, Node, ExactNum)) -> (bool, &str) {
+    (!matches!(factor.1, Node::Variable(_)), factor.0.as_str())
+}
+
+/// Simplify each product term, split it into coefficient and factors, and
+/// combine terms with identical factors. Terms are never re-simplified as
+/// a sum: the sum-level simplifier re-nests multivariate polynomials.
+fn collect_expanded_terms(terms: Vec<(Node, bool)>, env: &Environment) -> Vec<(Node, bool)> {
+    let mut monomials: Vec<ExpandedMonomial> = Vec::new();
+    for (term, negative) in terms {
+        let simplified = term.simplify(env).unwrap_or(term);
+        let mut parts = Vec::new();
+        flatten_add_sub_terms(&simplified, &mut parts, negative);
+        for (part, part_negative) in parts {
+            let mut monomial = ExpandedMonomial::from_term(&part);
+            if part_negative {
+                monomial.coeff = -monomial.coeff;
+            }
+            let same_factors = |m: &&mut ExpandedMonomial| {
+                m.factors.len() == monomial.factors.len()
+                    && m.factors
+                        .iter()
+                        .zip(&monomial.factors)
+                        .all(|(a, b)| a.0 == b.0 && a.2 == b.2)
+            };
+            if let Some(existing) = monomials.iter_mut().find(same_factors) {
+                existing.coeff = existing.coeff.clone() + monomial.coeff;
+            } else {
+                monomials.push(monomial);
+            }
+        }
+    }
+    monomials.retain(|m| !m.coeff.is_zero());
+    monomials.sort_by(|a, b| a.order(b));
+    monomials
+        .iter()
+        .map(ExpandedMonomial::to_signed_node)
+        .collect()
+}
+
+/// Reassemble signed terms into a left-nested `Add`/`Subtract` chain.
+fn rebuild_signed_terms(terms: Vec<(Node, bool)>) -> Node {
+    let mut iter = terms.into_iter();
+    let Some((first, first_neg)) = iter.next() else {
+        return Node::Num(ExactNum::zero());
+    };
+    let start = if first_neg {
+        Node::Negate(Box::new(first))
+    } else {
+        first
+    };
+    iter.fold(start, |acc, (term, neg)| {
+        if neg {
+            Node::Subtract(Box::new(acc), Box::new(term))
+        } else {
+            Node::Add(Box::new(acc), Box::new(term))
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     fn simplify_latex(input: &str) -> String {
@@ -3944,4 +5186,38 @@ mod tests {
     fn test_combine_function_subtract() {
         assert_eq!(simplify_latex("5\\cos(x) - 3\\cos(x)"), "2\\cos(x)");
     }
+
+    // --- Inverse-function cancellation ---
+
+    #[test]
+    fn test_function_of_its_inverse_cancels() {
+        assert_eq!(simplify_latex("\\sin(\\arcsin(x))"), "x");
+        assert_eq!(simplify_latex("\\cos(\\arccos(2x + 1))"), "2x + 1");
+        assert_eq!(simplify_latex("\\tanh(\\arctanh(x))"), "x");
+        assert_eq!(simplify_latex("\\arcsinh(\\sinh(x))"), "x");
+        assert_eq!(simplify_latex("\\arccosh(\\cosh(x))"), "|x|");
+    }
+
+    #[test]
+    fn test_circular_inverse_cancels_only_on_principal_branch() {
+        assert_eq!(
+            simplify_latex("\\arcsin(\\sin(\\frac{1}{2}))"),
+            "\\frac{1}{2}"
+        );
+        assert_eq!(
+            simplify_latex("\\arctan(\\tan(\\frac{\\pi}{5}))"),
+            "\\frac{\\pi}{5}"
+        );
+        assert_eq!(simplify_latex("\\arccos(\\cos(1))"), "1");
+        // Outside the principal range, or unknown: left alone
+        assert_eq!(simplify_latex("\\arcsin(\\sin(3))"), "\\arcsin(\\sin(3))");
+        assert_eq!(simplify_latex("\\arcsin(\\sin(x))"), "\\arcsin(\\sin(x))");
+    }
+
+    #[test]
+    fn test_sqrt_of_even_power() {
+        assert_eq!(simplify_latex("\\sqrt{x^4}"), "x^{2}");
+        assert_eq!(simplify_latex("\\sqrt{x^6}"), "|x|^{3}");
+        assert_eq!(simplify_latex("\\sqrt{(x-1)^{4}}"), "(x - 1)^{2}");
+    }
 }
@@ -60,193 +60,68 @@ pub fn substitute_latex(
 /// Substitute a variable in an expression with the provided value
 pub fn substitute_variable(node: &Node, var_name: &str, value: &Node) -> Result<Node, String> {
     match node {
-        Node::Num(_) => Ok(node.clone()),
-        Node::Variable(name) => {
-            if name == var_name {
-                Ok(value.clone())
-            } else {
-                Ok(node.clone())
-            }
+        Node::Variable(name) if name == var_name => return Ok(value.clone()),
+        // An indefinite integral is a function of its variable:
+        // substituting for it means evaluating the antiderivative
+        Node::Integral(var, None, body) if var == var_name => {
+            let antiderivative = crate::integration::integrate(body, var)?;
+            return substitute_variable(&antiderivative, var_name, value);
         }
-        Node::Add(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::Add(Box::new(left_subst), Box::new(right_subst)))
+        // The derivative is a function of its variable, like an
+        // indefinite integral: substituting for it needs the result
+        Node::Derivative(var, body) if var == var_name => {
+            let derivative = crate::derivative::differentiate(body, var).map_err(|e| {
+                format!(
+                    "Cannot substitute for '{}' in an unresolved derivative: {}",
+                    var, e
+                )
+            })?;
+            return substitute_variable(&derivative, var_name, value);
         }
-        Node::Subtract(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::Subtract(Box::new(left_subst), Box::new(right_subst)))
-        }
-        Node::Multiply(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::Multiply(Box::new(left_subst), Box::new(right_subst)))
-        }
-        Node::Divide(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::Divide(Box::new(left_subst), Box::new(right_subst)))
-        }
-        Node::Power(base, exponent) => {
-            let base_subst = substitute_variable(base, var_name, value)?;
-            let exp_subst = substitute_variable(exponent, var_name, value)?;
-            Ok(Node::Power(Box::new(base_subst), Box::new(exp_subst)))
-        }
-        Node::Sqrt(operand) => {
-            let operand_subst = substitute_variable(operand, var_name, value)?;
-            Ok(Node::Sqrt(Box::new(operand_subst)))
-        }
-        Node::Abs(operand) => {
-            let operand_subst = substitute_variable(operand, var_name, value)?;
-            Ok(Node::Abs(Box::new(operand_subst)))
-        }
-        Node::Floor(operand) => {
-            let operand_subst = substitute_variable(operand, var_name, value)?;
-            Ok(Node::Floor(Box::new(operand_subst)))
-        }
-        Node::Ceil(operand) => {
-            let operand_subst = substitute_variable(operand, var_name, value)?;
-            Ok(Node::Ceil(Box::new(operand_subst)))
-        }
-        Node::Round(operand) => {
-            let operand_subst = substitute_variable(operand, var_name, value)?;
-            Ok(Node::Round(Box::new(operand_subst)))
-        }
-        Node::Trunc(operand) => {
-            let operand_subst = substitute_variable(operand, var_name, value)?;
-            Ok(Node::Trunc(Box::new(operand_subst)))
-        }
-        Node::Negate(operand) => {
-            let operand_subst = substitute_variable(operand, var_name, value)?;
-            Ok(Node::Negate(Box::new(operand_subst)))
-        }
-        Node::Factorial(operand) => {
-            let operand_subst = substitute_variable(operand, var_name, value)?;
-            Ok(Node::Factorial(Box::new(operand_subst)))
-        }
-
-        Node::Greater(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::Greater(Box::new(left_subst), Box::new(right_subst)))
-        }
-        Node::Less(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::Less(Box::new(left_subst), Box::new(right_subst)))
-        }
-        Node::GreaterEqual(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::GreaterEqual(
-                Box::new(left_subst),
-                Box::new(right_subst),
-            ))
-        }
-        Node::LessEqual(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::LessEqual(Box::new(left_subst), Box::new(right_subst)))
-        }
-        Node::Equal(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::Equal(Box::new(left_subst), Box::new(right_subst)))
-        }
-        Node::Equation(left, right) => {
-            let left_subst = substitute_variable(left, var_name, value)?;
-            let right_subst = substitute_variable(right, var_name, value)?;
-            Ok(Node::Equation(Box::new(left_subst), Box::new(right_subst)))
-        }
-
-        Node::Piecewise(conditions) => {
-            let mut new_conditions = Vec::new();
-            for (expr, cond) in conditions {
-                let expr_subst = substitute_variable(expr, var_name, value)?;
-                let cond_subst = substitute_variable(cond, var_name, value)?;
-                new_conditions.push((expr_subst, cond_subst));
-            }
-            Ok(Node::Piecewise(new_conditions))
+        Node::Derivative(var, body)
+            if body.contains_variable(var_name) && value.contains_variable(var) =>
+        {
+            return Err(capture_error(node, var_name, value, var));
         }
+        _ => {}
+    }
 
-        Node::Summation(index, start, end, body) => {
-            // Substituting a value that mentions the bound index into a
-            // summation that contains the target variable would capture the
-            // index (x := k under Σ_k turns k·x into k² silently). Refuse
-            // explicitly — a wrong answer in either direction is worse than
-            // an error.
-            if node.contains_variable(var_name) && value.contains_variable(index) {
-                return Err(format!(
-                    "substituting '{}' for '{}' would capture the summation index '{}'; rename the bound index first",
-                    value, var_name, index
-                ));
-            }
-            // If the summation uses the same index variable, don't substitute in the body
-            // to avoid variable capture issues
-            if index == var_name {
-                let start_subst = substitute_variable(start, var_name, value)?;
-                let end_subst = substitute_variable(end, var_name, value)?;
-                Ok(Node::Summation(
-                    index.clone(),
-                    Box::new(start_subst),
-                    Box::new(end_subst),
-                    body.clone(),
-                ))
-            } else {
-                let start_subst = substitute_variable(start, var_name, value)?;
-                let end_subst = substitute_variable(end, var_name, value)?;
-                let body_subst = substitute_variable(body, var_name, value)?;
-                Ok(Node::Summation(
-                    index.clone(),
-                    Box::new(start_subst),
-                    Box::new(end_subst),
-                    Box::new(body_subst),
-                ))
-            }
+    // Substituting a value that mentions a bound variable into a binder
+    // that contains the target variable would capture it (x := k under Σ_k
+    // turns k·x into k² silently). Refuse explicitly — a wrong answer in
+    // either direction is worse than an error. When the binder binds the
+    // target itself, its body has no free occurrence to replace.
+    let bound = node.bound_variable();
+    if let Some(bound) = bound {
+        if node.contains_variable(var_name) && value.contains_variable(bound) {
+            return Err(capture_error(node, var_name, value, bound));
         }
-
-        Node::Product(index, start, end, body) => {
-            // Same capture refusal as Summation.
-            if node.contains_variable(var_name) && value.contains_variable(index) {
-                return Err(format!(
-                    "substituting '{}' for '{}' would capture the product index '{}'; rename the bound index first",
-                    value, var_name, index
-                ));
-            }
-            // If the product uses the same index variable, don't substitute in the body
-            // to avoid variable capture issues
-            if index == var_name {
-                let start_subst = substitute_variable(start, var_name, value)?;
-                let end_subst = substitute_variable(end, var_name, value)?;
-                Ok(Node::Product(
-                    index.clone(),
-                    Box::new(start_subst),
-                    Box::new(end_subst),
-                    body.clone(),
-                ))
-            } else {
-                let start_subst = substitute_variable(start, var_name, value)?;
-                let end_subst = substitute_variable(end, var_name, value)?;
-                let body_subst = substitute_variable(body, var_name, value)?;
-                Ok(Node::Product(
-                    index.clone(),
-                    Box::new(start_subst),
-                    Box::new(end_subst),
-                    Box::new(body_subst),
-                ))
-            }
+    }
+    let body = node.children().len().saturating_sub(1);
+    let mut index = 0;
+    node.try_map_children(|child| {
+        let in_scope = index == body && bound == Some(var_name);
+        index += 1;
+        if in_scope {
+            Ok(child.clone())
+        } else {
+            substitute_variable(child, var_name, value)
         }
+    })
+}
 
-        Node::Function(name, args) => {
-            let mut new_args = Vec::new();
-            for arg in args {
-                let arg_subst = substitute_variable(arg, var_name, value)?;
-                new_args.push(arg_subst);
-            }
-            Ok(Node::Function(name.clone(), new_args))
-        }
-    }
+fn capture_error(binder: &Node, var_name: &str, value: &Node, bound: &str) -> String {
+    let (what, advice) = match binder {
+        Node::Summation(..) | Node::SumOver(..) => ("summation index", "the bound index"),
+        Node::Product(..) => ("product index", "the bound index"),
+        Node::Integral(..) => ("integration variable", "it"),
+        Node::Derivative(..) => ("differentiation variable", "it"),
+        _ => ("limit variable", "it"),
+    };
+    format!(
+        "substituting '{}' for '{}' would capture the {} '{}'; rename {} first",
+        value, var_name, what, bound, advice
+    )
 }
 
 #[cfg(test)]
@@ -258,7 +133,7 @@ mod tests {
     fn parse_expression(latex: &str) -> Result<Node, String> {
         let mut tokenizer = Tokenizer::new(latex);
         let tokens = tokenizer.tokenize();
-        build_expression_tree(tokens)
+        Ok(build_expression_tree(tokens)?)
     }
 
     #[test]
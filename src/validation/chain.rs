@@ -322,7 +322,7 @@ fn check_derivative_of(
 /// equals ladder must not depend on the simplifier. If (and only if) the
 /// raw comparison is inconclusive, the constructed side is simplified and
 /// retried: the product rule leaves exact-zero terms that can still mention
-/// a special function (erf, Ei, li) which refuses numeric evaluation, and
+/// a special function (Ei, li) which refuses numeric evaluation, and
 /// folding them away is what makes the derivative sampleable at all.
 ///
 /// The retry can *pass* (sampling evidence, with `simplify+` named in the
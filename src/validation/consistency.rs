@@ -0,0 +1,212 @@
+//! Numeric cross-checks of the symbolic calculus engine.
+//!
+//! [`check_fundamental_theorem`] integrates and differentiates an
+//! expression symbolically, then holds both results against independent
+//! numerics: `F(b) − F(a)` against adaptive Simpson quadrature of `f` over
+//! `[a, b]`, and `f'` against [`differentiate_numeric`] at sample points
+//! inside the interval. [`check_derivative`] runs the second half alone,
+//! for expressions whose integral is beside the point. A regression in either engine — or the
+//! removal of a derivative special case — surfaces as a `Fail` with the
+//! numbers that disagree.
+
+use crate::derivative::{differentiate, differentiate_numeric};
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::integration::{integrate, numeric_integral};
+use crate::node::Node;
+use crate::status::Verdict;
+
+/// Fractions of `[a, b]` where the derivative is sampled: interior and
+/// irregular, so endpoints and symmetric points are avoided.
+const SAMPLE_FRACTIONS: [f64; 7] = [0.071, 0.193, 0.337, 0.482, 0.619, 0.758, 0.916];
+const MIN_SAMPLES: usize = 3;
+
+/// Outcome of [`check_fundamental_theorem`].
+#[derive(Debug, Clone)]
+pub struct ConsistencyReport {
+    /// `Fail` if either check failed, `Pass` if both passed
+    pub verdict: Verdict,
+    pub integral: IntegralCheck,
+    pub derivative: DerivativeCheck,
+}
+
+/// `F(b) − F(a)` for the symbolic antiderivative against quadrature.
+#[derive(Debug, Clone)]
+pub struct IntegralCheck {
+    pub verdict: Verdict,
+    pub antiderivative: Option<Node>,
+    pub symbolic_value: Option<f64>,
+    pub numeric_value: Option<f64>,
+    /// Why the check was inconclusive
+    pub note: Option<String>,
+}
+
+/// The symbolic derivative against finite differences.
+#[derive(Debug, Clone)]
+pub struct DerivativeCheck {
+    pub verdict: Verdict,
+    pub derivative: Option<Node>,
+    /// Every point where both values were defined and the finite
+    /// difference was stable
+    pub samples: Vec<DerivativeSample>,
+    /// Why the check was inconclusive
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DerivativeSample {
+    pub x: f64,
+    pub symbolic: f64,
+    pub finite_difference: f64,
+}
+
+impl DerivativeSample {
+    fn agrees(&self, tol: f64) -> bool {
+        close(self.symbolic, self.finite_difference, tol)
+    }
+}
+
+/// Cross-check integration and differentiation of `expr` in `var` over
+/// `[a, b]`, with relative tolerance `tol` (scaled by `1 + |value|`).
+/// Checks that cannot run — the integral is not elementary, `f` has a
+/// singularity in the interval, other variables are free — come back
+/// `Inconclusive` with a note rather than failing.
+pub fn check_fundamental_theorem(
+    expr: &Node,
+    var: &str,
+    a: f64,
+    b: f64,
+    tol: f64,
+) -> ConsistencyReport {
+    let integral = check_integral(expr, var, a, b, tol);
+    let derivative = check_derivative(expr, var, a, b, tol);
+    let verdict = match (integral.verdict, derivative.verdict) {
+        (Verdict::Fail, _) | (_, Verdict::Fail) => Verdict::Fail,
+        (Verdict::Pass, Verdict::Pass) => Verdict::Pass,
+        _ => Verdict::Inconclusive,
+    };
+    ConsistencyReport {
+        verdict,
+        integral,
+        derivative,
+    }
+}
+
+fn check_integral(expr: &Node, var: &str, a: f64, b: f64, tol: f64) -> IntegralCheck {
+    let mut check = IntegralCheck {
+        verdict: Verdict::Inconclusive,
+        antiderivative: None,
+        symbolic_value: None,
+        numeric_value: None,
+        note: None,
+    };
+    let antiderivative = match integrate(expr, var) {
+        Ok(f) => f,
+        Err(e) => {
+            check.note = Some(format!("No symbolic antiderivative: {}", e));
+            return check;
+        }
+    };
+    check.symbolic_value = match (
+        eval_at(&antiderivative, var, b),
+        eval_at(&antiderivative, var, a),
+    ) {
+        (Some(fb), Some(fa)) => Some(fb - fa),
+        _ => None,
+    };
+    check.antiderivative = Some(antiderivative);
+    // Quadrature runs well inside the comparison tolerance so its own
+    // error cannot decide the verdict
+    check.numeric_value = numeric_integral(|x| eval_at(expr, var, x), a, b, tol * 1e-2);
+
+    match (check.symbolic_value, check.numeric_value) {
+        (Some(symbolic), Some(numeric)) => {
+            check.verdict = if close(symbolic, numeric, tol) {
+                Verdict::Pass
+            } else {
+                Verdict::Fail
+            };
+        }
+        (None, _) => {
+            check.note = Some(format!(
+                "The antiderivative is undefined at {} = {} or {}",
+                var, a, b
+            ))
+        }
+        (_, None) => {
+            check.note = Some(format!(
+                "The integrand is undefined somewhere in [{}, {}]",
+                a, b
+            ))
+        }
+    }
+    check
+}
+
+/// Cross-check the symbolic derivative of `expr` in `var` against
+/// numeric differentiation at interior points of `[a, b]`, with the
+/// tolerance of [`check_fundamental_theorem`]. `Inconclusive` when there
+/// is no symbolic derivative or too few points where both are defined.
+pub fn check_derivative(expr: &Node, var: &str, a: f64, b: f64, tol: f64) -> DerivativeCheck {
+    let mut check = DerivativeCheck {
+        verdict: Verdict::Inconclusive,
+        derivative: None,
+        samples: Vec::new(),
+        note: None,
+    };
+    let derivative = match differentiate(expr, var) {
+        Ok(d) => d,
+        Err(e) => {
+            check.note = Some(format!("No symbolic derivative: {}", e));
+            return check;
+        }
+    };
+    check.samples = SAMPLE_FRACTIONS
+        .iter()
+        .filter_map(|t| {
+            let x = a + t * (b - a);
+            Some(DerivativeSample {
+                x,
+                symbolic: eval_at(&derivative, var, x)?,
+                finite_difference: stable_numeric_derivative(expr, var, x, tol)?,
+            })
+        })
+        .collect();
+    check.derivative = Some(derivative);
+
+    if check.samples.iter().any(|s| !s.agrees(tol)) {
+        check.verdict = Verdict::Fail;
+    } else if check.samples.len() >= MIN_SAMPLES {
+        check.verdict = Verdict::Pass;
+    } else {
+        check.note = Some(format!(
+            "Only {} sample point{} with a defined, stable finite difference (need {})",
+            check.samples.len(),
+            if check.samples.len() == 1 { "" } else { "s" },
+            MIN_SAMPLES
+        ));
+    }
+    check
+}
+
+fn eval_at(expr: &Node, var: &str, x: f64) -> Option<f64> {
+    let mut env = Environment::new();
+    env.set(var, x);
+    Evaluator::evaluate(expr, &env)
+        .ok()
+        .filter(|v| v.is_finite())
+}
+
+fn close(a: f64, b: f64, tol: f64) -> bool {
+    (a - b).abs() <= tol * (1.0 + a.abs().max(b.abs()))
+}
+
+/// [`differentiate_numeric`] at `x`. Near a singularity the differences
+/// are unreliable; the estimate is only trusted when starting from an
+/// eighth of the step leaves it unchanged to within `tol`.
+fn stable_numeric_derivative(expr: &Node, var: &str, x: f64, tol: f64) -> Option<f64> {
+    let h = 1e-2 * (1.0 + x.abs());
+    let coarse = differentiate_numeric(expr, var, x, h).ok()?;
+    let fine = differentiate_numeric(expr, var, x, h / 8.0).ok()?;
+    (coarse.is_finite() && close(coarse, fine, tol)).then_some(fine)
+}
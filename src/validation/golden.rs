@@ -0,0 +1,195 @@
+//! Golden-file snapshots of LaTeX output (enabled by the `golden` feature).
+//!
+//! A [`Snapshot`] collects labelled results — derivatives, antiderivatives,
+//! simplifications, or any `Node` — renders each with `to_latex`, and
+//! compares the whole listing against a checked-in `<name>.golden` file.
+//! A mismatch reports a line diff of the changed entries, so a change to
+//! the output format shows up as a readable review item rather than a
+//! wall of failing string asserts.
+//!
+//! Set `ARITHMA_BLESS=1` to write the current output as the new golden
+//! file instead of comparing.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::derivative::differentiate;
+use crate::environment::Environment;
+use crate::error::ArithmaError;
+use crate::integration::integrate;
+use crate::node::Node;
+use crate::parser::parse_latex_raw;
+use crate::simplify::Simplifiable;
+
+/// Environment variable that rewrites golden files instead of checking them.
+pub const BLESS_VAR: &str = "ARITHMA_BLESS";
+
+/// Unchanged lines kept around each change in a diff.
+const DIFF_CONTEXT: usize = 2;
+
+/// A named listing of `label => latex` lines checked against a golden file.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    path: PathBuf,
+    entries: Vec<(String, String)>,
+}
+
+impl Snapshot {
+    /// A snapshot stored as `dir/name.golden`.
+    pub fn new(dir: impl AsRef<Path>, name: &str) -> Self {
+        Snapshot {
+            path: dir.as_ref().join(format!("{}.golden", name)),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Path of the golden file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record an expression under `label`.
+    pub fn record(&mut self, label: &str, expr: &Node) -> &mut Self {
+        self.entries.push((label.to_string(), expr.to_latex()));
+        self
+    }
+
+    /// Record a fallible result; errors are kept as `error: <message>` so
+    /// an operation starting (or stopping) to fail is part of the diff.
+    pub fn record_result(&mut self, label: &str, result: Result<Node, String>) -> &mut Self {
+        let rendered = match result {
+            Ok(expr) => expr.to_latex(),
+            Err(e) => format!("error: {}", e),
+        };
+        self.entries.push((label.to_string(), rendered));
+        self
+    }
+
+    /// Record `d/d{var}` of `latex`, labelled `d/d{var} {latex}`.
+    pub fn differentiate(&mut self, latex: &str, var: &str) -> &mut Self {
+        let result = parse_latex_raw(latex)
+            .map_err(ArithmaError::from)
+            .and_then(|expr| differentiate(&expr, var))
+            .map_err(String::from);
+        self.record_result(&format!("d/d{} {}", var, latex), result)
+    }
+
+    /// Record the antiderivative of `latex` in `var`, labelled
+    /// `int d{var} {latex}`.
+    pub fn integrate(&mut self, latex: &str, var: &str) -> &mut Self {
+        let result = parse_latex_raw(latex)
+            .map_err(ArithmaError::from)
+            .and_then(|expr| integrate(&expr, var))
+            .map_err(String::from);
+        self.record_result(&format!("int d{} {}", var, latex), result)
+    }
+
+    /// Record the simplified form of `latex`, labelled `simplify {latex}`.
+    pub fn simplify(&mut self, latex: &str) -> &mut Self {
+        let env = Environment::new();
+        let result = parse_latex_raw(latex)
+            .map_err(String::from)
+            .and_then(|expr| expr.simplify(&env));
+        self.record_result(&format!("simplify {}", latex), result)
+    }
+
+    /// The listing as it is written to the golden file.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (label, latex) in &self.entries {
+            let _ = writeln!(out, "{} => {}", label, latex);
+        }
+        out
+    }
+
+    /// Compare against the golden file (or rewrite it when `ARITHMA_BLESS`
+    /// is set). The error describes a missing file or shows the diff.
+    pub fn check(&self) -> Result<(), String> {
+        let actual = self.render();
+        if std::env::var_os(BLESS_VAR).is_some() {
+            if let Some(dir) = self.path.parent() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+            }
+            return std::fs::write(&self.path, actual)
+                .map_err(|e| format!("Cannot write {}: {}", self.path.display(), e));
+        }
+        let expected = std::fs::read_to_string(&self.path).map_err(|e| {
+            format!(
+                "Cannot read golden file {} ({}); run with {}=1 to create it",
+                self.path.display(),
+                e,
+                BLESS_VAR
+            )
+        })?;
+        if expected == actual {
+            return Ok(());
+        }
+        Err(format!(
+            "{} does not match (- golden, + actual); run with {}=1 to accept:\n{}",
+            self.path.display(),
+            BLESS_VAR,
+            diff_lines(&expected, &actual)
+        ))
+    }
+
+    /// [`check`](Self::check), panicking with the diff on a mismatch.
+    pub fn assert(&self) {
+        if let Err(e) = self.check() {
+            panic!("{}", e);
+        }
+    }
+}
+
+/// Line diff of `expected` against `actual`: changed lines marked `-`/`+`,
+/// a little unchanged context around each change, and `…` for the rest.
+pub fn diff_lines(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<(char, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', old[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new[j]));
+            j += 1;
+        }
+    }
+
+    let near_change = |k: usize| {
+        let lo = k.saturating_sub(DIFF_CONTEXT);
+        let hi = (k + DIFF_CONTEXT + 1).min(lines.len());
+        lines[lo..hi].iter().any(|(mark, _)| *mark != ' ')
+    };
+    let mut out = String::new();
+    let mut elided = false;
+    for (k, (mark, line)) in lines.iter().enumerate() {
+        if near_change(k) {
+            let _ = writeln!(out, "{} {}", mark, line);
+            elided = false;
+        } else if !elided {
+            out.push_str("  …\n");
+            elided = true;
+        }
+    }
+    out
+}
@@ -0,0 +1,376 @@
+//! Data-driven problem sets: `(input, expected)` pairs run through the
+//! engine with a pass/fail report.
+//!
+//! A problem set is a CSV file with one `input,expected` row per problem,
+//! or a JSON array of `{"input": …, "expected": …}` objects. Each input
+//! runs in a fresh [`Session`], so it may be an assignment (`x = 2 + 3`
+//! expects `5`) as well as an expression. A result passes when it matches
+//! the expected answer as displayed, as a number, or as an identity over
+//! its free variables; an expected answer of `error` (or `error: text`)
+//! asserts that the input is rejected, with `text` in the message.
+//!
+//! ```text
+//! input,expected
+//! "\frac{d}{dx} x^3",3x^{2}
+//! "\int_0^1 x \, dx",\frac{1}{2}
+//! (x+1)^2,x^2 + 2x + 1
+//! (x+1,error: mismatched parentheses
+//! ```
+
+use std::fmt;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::assumptions::Assumptions;
+use crate::environment::Environment;
+use crate::evaluator::Evaluator;
+use crate::node::Node;
+use crate::parser::parse_latex;
+use crate::session::{Binding, Session, SessionOutcome};
+use crate::status::{free_variables, Verdict};
+use crate::verify::verify_identity;
+
+/// Relative tolerance for comparing numeric answers.
+const NUMERIC_TOLERANCE: f64 = 1e-9;
+
+/// One row of a problem set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// Line the row starts on in a CSV file, or 1-based position in a
+    /// JSON array
+    pub line: usize,
+    pub input: String,
+    pub expected: String,
+}
+
+/// The outcome of one [`Problem`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProblemResult {
+    pub problem: Problem,
+    /// What the engine gave, as displayed, or its error message
+    pub actual: Result<String, String>,
+    pub verdict: Verdict,
+    /// Why the verdict is not a pass, when the diff alone does not say
+    pub note: Option<String>,
+}
+
+/// Results for a whole problem set, in input order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProblemSetReport {
+    pub results: Vec<ProblemResult>,
+}
+
+impl ProblemSetReport {
+    pub fn passed(&self) -> usize {
+        self.count(Verdict::Pass)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(Verdict::Fail)
+    }
+
+    pub fn inconclusive(&self) -> usize {
+        self.count(Verdict::Inconclusive)
+    }
+
+    /// Whether every problem passed; inconclusive problems do not.
+    pub fn all_passed(&self) -> bool {
+        self.passed() == self.results.len()
+    }
+
+    /// The problems that did not pass, in input order.
+    pub fn failures(&self) -> impl Iterator<Item = &ProblemResult> {
+        self.results.iter().filter(|r| r.verdict != Verdict::Pass)
+    }
+
+    fn count(&self, verdict: Verdict) -> usize {
+        self.results.iter().filter(|r| r.verdict == verdict).count()
+    }
+}
+
+/// Each problem that did not pass as a `- expected` / `+ actual` diff,
+/// then a one-line summary.
+impl fmt::Display for ProblemSetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in self.failures() {
+            let problem = &result.problem;
+            writeln!(
+                f,
+                "line {} [{}]: {}",
+                problem.line,
+                result.verdict.as_str(),
+                problem.input
+            )?;
+            writeln!(f, "- {}", problem.expected)?;
+            match &result.actual {
+                Ok(actual) => writeln!(f, "+ {}", actual)?,
+                Err(message) => writeln!(f, "+ error: {}", message)?,
+            }
+            if let Some(note) = &result.note {
+                writeln!(f, "  ({})", note)?;
+            }
+        }
+        write!(
+            f,
+            "{} problems: {} passed, {} failed, {} inconclusive",
+            self.results.len(),
+            self.passed(),
+            self.failed(),
+            self.inconclusive()
+        )
+    }
+}
+
+/// Run a problem set given as JSON text (anything starting with `[`) or
+/// as the path of a `.json` or CSV file.
+pub fn run_problem_set(source: &str) -> Result<ProblemSetReport, String> {
+    let problems = if source.trim_start().starts_with('[') {
+        parse_problem_json(source)?
+    } else {
+        let path = Path::new(source);
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read problem set {}: {}", source, e))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => parse_problem_json(&text)?,
+            _ => parse_problem_csv(&text)?,
+        }
+    };
+    Ok(run_problems(&problems))
+}
+
+/// Run each problem in a fresh session.
+pub fn run_problems(problems: &[Problem]) -> ProblemSetReport {
+    ProblemSetReport {
+        results: problems.iter().map(run_problem).collect(),
+    }
+}
+
+/// Problems from CSV text with two columns, input then expected. Fields
+/// holding a comma, quote or line break are quoted, with `""` for a
+/// quote. An `input,expected` header, blank lines and lines starting
+/// with `#` are skipped.
+pub fn parse_problem_csv(text: &str) -> Result<Vec<Problem>, String> {
+    let mut problems = Vec::new();
+    for (line, fields) in csv_records(text)? {
+        if fields.len() == 1 && fields[0].trim().is_empty() {
+            continue;
+        }
+        if fields[0].trim_start().starts_with('#') {
+            continue;
+        }
+        if fields.len() != 2 {
+            return Err(format!(
+                "line {}: expected 2 fields (input, expected), found {}",
+                line,
+                fields.len()
+            ));
+        }
+        let (input, expected) = (fields[0].trim(), fields[1].trim());
+        if problems.is_empty()
+            && input.eq_ignore_ascii_case("input")
+            && expected.eq_ignore_ascii_case("expected")
+        {
+            continue;
+        }
+        problems.push(Problem {
+            line,
+            input: input.to_string(),
+            expected: expected.to_string(),
+        });
+    }
+    Ok(problems)
+}
+
+/// Problems from a JSON array of `{"input": …, "expected": …}` objects.
+pub fn parse_problem_json(text: &str) -> Result<Vec<Problem>, String> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|e| format!("Invalid problem set JSON: {}", e))?;
+    let entries = value
+        .as_array()
+        .ok_or("A JSON problem set must be an array of {\"input\", \"expected\"} objects")?;
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let field = |key: &str| {
+                entry
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| format!("problem {}: missing string field \"{}\"", i + 1, key))
+            };
+            Ok(Problem {
+                line: i + 1,
+                input: field("input")?.to_string(),
+                expected: field("expected")?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Records of `text` with the line each starts on.
+fn csv_records(text: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                fields.push(std::mem::take(&mut field));
+                records.push((start, std::mem::take(&mut fields)));
+                line += 1;
+                start = line;
+            }
+            _ => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if quoted {
+        return Err(format!("line {}: unterminated quoted field", start));
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    Ok(records)
+}
+
+fn run_problem(problem: &Problem) -> ProblemResult {
+    let outcome = Session::new().execute(&problem.input);
+    let actual = outcome.as_ref().map(render).map_err(Clone::clone);
+    let (verdict, note) = judge(&problem.expected, &outcome, &actual);
+    ProblemResult {
+        problem: problem.clone(),
+        actual,
+        verdict,
+        note,
+    }
+}
+
+fn render(outcome: &SessionOutcome) -> String {
+    match outcome {
+        SessionOutcome::Assigned { name, binding } => format!("{} = {}", name, binding),
+        SessionOutcome::Evaluated(node) => node.to_string(),
+    }
+}
+
+/// The value an outcome is compared by: an assignment is compared by what
+/// it binds.
+fn value_of(outcome: &SessionOutcome) -> Option<Node> {
+    match outcome {
+        SessionOutcome::Evaluated(node) => Some(node.clone()),
+        SessionOutcome::Assigned { binding, .. } => match binding {
+            Binding::Value(v) => Some(Node::Num(v.clone())),
+            Binding::Expression(expr) => Some(expr.clone()),
+            Binding::List(_) => None,
+        },
+    }
+}
+
+fn judge(
+    expected: &str,
+    outcome: &Result<SessionOutcome, String>,
+    actual: &Result<String, String>,
+) -> (Verdict, Option<String>) {
+    if let Some(wanted) = expected_error(expected) {
+        return match outcome {
+            Err(message) if message.to_lowercase().contains(&wanted.to_lowercase()) => {
+                (Verdict::Pass, None)
+            }
+            Err(_) => (
+                Verdict::Fail,
+                Some(format!("the error does not mention \"{}\"", wanted)),
+            ),
+            Ok(_) => (Verdict::Fail, Some("expected an error".to_string())),
+        };
+    }
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(_) => return (Verdict::Fail, None),
+    };
+    if actual.as_deref() == Ok(expected) {
+        return (Verdict::Pass, None);
+    }
+    let env = Environment::new();
+    let want = match parse_latex(expected, &env) {
+        Ok(node) => node,
+        Err(e) => return (Verdict::Fail, Some(format!("expected answer: {}", e))),
+    };
+    // A list binding only passes by displaying exactly as expected
+    let Some(got) = value_of(outcome) else {
+        return (Verdict::Fail, None);
+    };
+    if got.to_string() == want.to_string() {
+        return (Verdict::Pass, None);
+    }
+
+    let variables = free_variables(&[&got, &want]);
+    if variables.is_empty() {
+        return match (
+            Evaluator::evaluate(&got, &env),
+            Evaluator::evaluate(&want, &env),
+        ) {
+            (Ok(a), Ok(b)) if close(a, b) => (Verdict::Pass, None),
+            (Ok(_), Ok(_)) => (Verdict::Fail, None),
+            _ => (
+                Verdict::Inconclusive,
+                Some("the answers differ in form and cannot be evaluated".to_string()),
+            ),
+        };
+    }
+    let result = verify_identity(&got, &want, &variables, &Assumptions::new());
+    if result.passed {
+        (Verdict::Pass, None)
+    } else if result.insufficient_points {
+        (
+            Verdict::Inconclusive,
+            Some("too few sample points to compare the answers".to_string()),
+        )
+    } else {
+        let note = result.counterexample.map(|c| {
+            let point: Vec<String> = c
+                .point
+                .iter()
+                .map(|(var, value)| format!("{} = {}", var, value))
+                .collect();
+            format!("they differ at {}", point.join(", "))
+        });
+        (Verdict::Fail, note)
+    }
+}
+
+/// `text` in an expected answer of the form `error` or `error: text`.
+fn expected_error(expected: &str) -> Option<&str> {
+    let rest = expected.strip_prefix("error")?;
+    match rest.trim_start().strip_prefix(':') {
+        Some(text) => Some(text.trim()),
+        None if rest.trim().is_empty() => Some(""),
+        None => None,
+    }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    a == b || (a - b).abs() <= NUMERIC_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
@@ -585,7 +585,7 @@ impl StatusReport {
 /// shadows Euler's constant and manufactures false counterexamples)
 /// and from the ℚ-exact fragment (they are not rational atoms).
 pub fn is_builtin_constant(name: &str) -> bool {
-    matches!(name, "e" | "π")
+    crate::constants::is_constant(name)
 }
 
 pub fn is_algebraic_exact(node: &Node) -> bool {
@@ -619,54 +619,24 @@ fn is_integer_exponent(node: &Node) -> bool {
 /// (`y + Σ_{y=1}^{3} y` has a free y), which turns variable inference —
 /// and everything downstream of it — silently wrong.
 fn collect_variables(node: &Node, vars: &mut BTreeSet<String>, bound: &mut Vec<String>) {
-    match node {
-        Node::Variable(v) => {
-            if !is_builtin_constant(v) && !bound.iter().any(|b| b == v) {
-                vars.insert(v.clone());
-            }
+    if let Node::Variable(v) = node {
+        if !is_builtin_constant(v) && !bound.iter().any(|b| b == v) {
+            vars.insert(v.clone());
         }
-        Node::Num(_) => {}
-        Node::Add(l, r)
-        | Node::Subtract(l, r)
-        | Node::Multiply(l, r)
-        | Node::Divide(l, r)
-        | Node::Power(l, r)
-        | Node::Greater(l, r)
-        | Node::Less(l, r)
-        | Node::GreaterEqual(l, r)
-        | Node::LessEqual(l, r)
-        | Node::Equal(l, r)
-        | Node::Equation(l, r) => {
-            collect_variables(l, vars, bound);
-            collect_variables(r, vars, bound);
-        }
-        Node::Sqrt(inner)
-        | Node::Abs(inner)
-        | Node::Floor(inner)
-        | Node::Ceil(inner)
-        | Node::Round(inner)
-        | Node::Trunc(inner)
-        | Node::Negate(inner)
-        | Node::Factorial(inner) => collect_variables(inner, vars, bound),
-        Node::Piecewise(arms) => {
-            for (expr, cond) in arms {
-                collect_variables(expr, vars, bound);
-                collect_variables(cond, vars, bound);
-            }
-        }
-        Node::Summation(idx, start, end, body) | Node::Product(idx, start, end, body) => {
-            // The index is bound in the body only; the bounds are outside
-            // the binder's scope.
-            collect_variables(start, vars, bound);
-            collect_variables(end, vars, bound);
-            bound.push(idx.clone());
-            collect_variables(body, vars, bound);
-            bound.pop();
-        }
-        Node::Function(_, args) => {
-            for a in args {
-                collect_variables(a, vars, bound);
+        return;
+    }
+    // A binder's variable is bound in its body only; the bounds, the
+    // list or the approach point are outside its scope.
+    let children = node.children();
+    let body = children.len().saturating_sub(1);
+    for (i, child) in children.into_iter().enumerate() {
+        match node.bound_variable() {
+            Some(var) if i == body => {
+                bound.push(var.to_string());
+                collect_variables(child, vars, bound);
+                bound.pop();
             }
+            _ => collect_variables(child, vars, bound),
         }
     }
 }
@@ -276,6 +276,88 @@ pub fn verify_identity(
     }
 }
 
+/// Random points [`are_equivalent`] compares the two sides at.
+const EQUIVALENCE_SAMPLES: usize = 16;
+/// Draws allowed for those points, counting ones outside the assumptions
+/// or where both sides are undefined.
+const EQUIVALENCE_DRAWS: usize = 64;
+
+/// Whether `a` and `b` are the same expression, e.g. `(x+1)^{2}` and
+/// `x^{2}+2x+1`.
+///
+/// Trees that are [structurally equal](Node::structurally_equal) are, and
+/// so are expressions whose difference simplifies to 0 (in the rational
+/// functions: `\frac{x^{2}-1}{x-1}` and `x+1` are equivalent); a
+/// difference that simplifies to another number means they are not.
+/// Otherwise both sides are evaluated at pseudo-random values of their
+/// free variables, which `env`'s assumptions restrict, and they are
+/// equivalent if they agree at enough points. A point where only one side
+/// is defined is a disagreement. The generator is seeded, so the answer is
+/// reproducible, but a sampled `true` is numeric evidence, not a proof:
+/// use [`verify_identity`] to see how much evidence there was.
+pub fn are_equivalent(a: &Node, b: &Node, env: &Environment) -> bool {
+    if a.structurally_equal(b) {
+        return true;
+    }
+    let difference = Node::Subtract(Box::new(a.clone()), Box::new(b.clone()));
+    if let Ok(Node::Num(n)) = difference.simplify(env) {
+        return n.is_zero();
+    }
+
+    let variables = free_variables(&[a, b]);
+    let assumptions = env.assumptions();
+    let mut rng = SplitMix64(0x5eed_a417_4a11_0001);
+    let mut agreed = 0;
+    for _ in 0..EQUIVALENCE_DRAWS {
+        let mut point = env.child();
+        let mut admissible = true;
+        for var in &variables {
+            let mut value = rng.next_f64() * 8.0 - 4.0;
+            if assumptions.is_integer(var) {
+                value = value.round();
+            }
+            admissible &= point_satisfies_assumptions(var, value, assumptions);
+            point.set(var, value);
+        }
+        if !admissible {
+            continue;
+        }
+        let (Ok(a_value), Ok(b_value)) = (
+            Evaluator::evaluate(a, &point),
+            Evaluator::evaluate(b, &point),
+        ) else {
+            continue;
+        };
+        if a_value.is_nan() && b_value.is_nan() {
+            continue;
+        }
+        if !values_match(a_value, b_value) {
+            return false;
+        }
+        agreed += 1;
+        if agreed == EQUIVALENCE_SAMPLES || variables.is_empty() {
+            return true;
+        }
+    }
+    agreed >= MIN_POINTS_FOR_PASS
+}
+
+/// The SplitMix64 generator, for sample points that are spread out but
+/// the same on every run.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        // The top 53 bits, as a fraction in [0, 1)
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
 pub(crate) fn point_satisfies_assumptions(var: &str, val: f64, assumptions: &Assumptions) -> bool {
     if assumptions.is_positive(var) && val <= 0.0 {
         return false;
@@ -325,6 +407,8 @@ fn collect_variable_length_ranges(node: &Node, sampled: &[String], out: &mut Vec
         | Node::Less(l, r)
         | Node::GreaterEqual(l, r)
         | Node::LessEqual(l, r)
+        | Node::And(l, r)
+        | Node::Or(l, r)
         | Node::Equal(l, r)
         | Node::Equation(l, r) => {
             collect_variable_length_ranges(l, sampled, out);
@@ -337,6 +421,7 @@ fn collect_variable_length_ranges(node: &Node, sampled: &[String], out: &mut Vec
         | Node::Round(inner)
         | Node::Trunc(inner)
         | Node::Negate(inner)
+        | Node::Not(inner)
         | Node::Factorial(inner) => collect_variable_length_ranges(inner, sampled, out),
         Node::Piecewise(arms) => {
             for (expr, cond) in arms {
@@ -344,11 +429,28 @@ fn collect_variable_length_ranges(node: &Node, sampled: &[String], out: &mut Vec
                 collect_variable_length_ranges(cond, sampled, out);
             }
         }
+        Node::Matrix(rows) => {
+            for entry in rows.iter().flatten() {
+                collect_variable_length_ranges(entry, sampled, out);
+            }
+        }
         Node::Function(_, args) => {
             for a in args {
                 collect_variable_length_ranges(a, sampled, out);
             }
         }
+        Node::Integral(_, bounds, body) => {
+            if let Some((lower, upper)) = bounds {
+                collect_variable_length_ranges(lower, sampled, out);
+                collect_variable_length_ranges(upper, sampled, out);
+            }
+            collect_variable_length_ranges(body, sampled, out);
+        }
+        Node::Derivative(_, body) => collect_variable_length_ranges(body, sampled, out),
+        Node::Limit(_, point, _, body) | Node::SumOver(_, point, body) => {
+            collect_variable_length_ranges(point, sampled, out);
+            collect_variable_length_ranges(body, sampled, out);
+        }
         Node::Summation(_, start, end, body) | Node::Product(_, start, end, body) => {
             let bound_vars = free_variables(&[start, end]);
             if !bound_vars.is_empty() && bound_vars.iter().all(|v| sampled.contains(v)) {
@@ -386,6 +488,8 @@ fn collect_symbolic_bound_pairs(node: &Node, out: &mut Vec<(String, String)>) {
         | Node::Less(l, r)
         | Node::GreaterEqual(l, r)
         | Node::LessEqual(l, r)
+        | Node::And(l, r)
+        | Node::Or(l, r)
         | Node::Equal(l, r)
         | Node::Equation(l, r) => {
             collect_symbolic_bound_pairs(l, out);
@@ -398,6 +502,7 @@ fn collect_symbolic_bound_pairs(node: &Node, out: &mut Vec<(String, String)>) {
         | Node::Round(inner)
         | Node::Trunc(inner)
         | Node::Negate(inner)
+        | Node::Not(inner)
         | Node::Factorial(inner) => collect_symbolic_bound_pairs(inner, out),
         Node::Piecewise(arms) => {
             for (expr, cond) in arms {
@@ -405,11 +510,28 @@ fn collect_symbolic_bound_pairs(node: &Node, out: &mut Vec<(String, String)>) {
                 collect_symbolic_bound_pairs(cond, out);
             }
         }
+        Node::Matrix(rows) => {
+            for entry in rows.iter().flatten() {
+                collect_symbolic_bound_pairs(entry, out);
+            }
+        }
         Node::Function(_, args) => {
             for a in args {
                 collect_symbolic_bound_pairs(a, out);
             }
         }
+        Node::Integral(_, bounds, body) => {
+            if let Some((lower, upper)) = bounds {
+                collect_symbolic_bound_pairs(lower, out);
+                collect_symbolic_bound_pairs(upper, out);
+            }
+            collect_symbolic_bound_pairs(body, out);
+        }
+        Node::Derivative(_, body) => collect_symbolic_bound_pairs(body, out),
+        Node::Limit(_, point, _, body) | Node::SumOver(_, point, body) => {
+            collect_symbolic_bound_pairs(point, out);
+            collect_symbolic_bound_pairs(body, out);
+        }
         Node::Summation(_, start, end, body) | Node::Product(_, start, end, body) => {
             if let (Node::Variable(lo), Node::Variable(hi)) = (start.as_ref(), end.as_ref()) {
                 if lo != hi && !out.iter().any(|(a, b)| a == lo && b == hi) {
@@ -435,6 +557,8 @@ fn collect_range_bound_constraints(node: &Node, out: &mut HashMap<String, RangeB
         | Node::Less(l, r)
         | Node::GreaterEqual(l, r)
         | Node::LessEqual(l, r)
+        | Node::And(l, r)
+        | Node::Or(l, r)
         | Node::Equal(l, r)
         | Node::Equation(l, r) => {
             collect_range_bound_constraints(l, out);
@@ -447,6 +571,7 @@ fn collect_range_bound_constraints(node: &Node, out: &mut HashMap<String, RangeB
         | Node::Round(inner)
         | Node::Trunc(inner)
         | Node::Negate(inner)
+        | Node::Not(inner)
         | Node::Factorial(inner) => collect_range_bound_constraints(inner, out),
         Node::Piecewise(arms) => {
             for (expr, cond) in arms {
@@ -454,11 +579,28 @@ fn collect_range_bound_constraints(node: &Node, out: &mut HashMap<String, RangeB
                 collect_range_bound_constraints(cond, out);
             }
         }
+        Node::Matrix(rows) => {
+            for entry in rows.iter().flatten() {
+                collect_range_bound_constraints(entry, out);
+            }
+        }
         Node::Function(_, args) => {
             for a in args {
                 collect_range_bound_constraints(a, out);
             }
         }
+        Node::Integral(_, bounds, body) => {
+            if let Some((lower, upper)) = bounds {
+                collect_range_bound_constraints(lower, out);
+                collect_range_bound_constraints(upper, out);
+            }
+            collect_range_bound_constraints(body, out);
+        }
+        Node::Derivative(_, body) => collect_range_bound_constraints(body, out),
+        Node::Limit(_, point, _, body) | Node::SumOver(_, point, body) => {
+            collect_range_bound_constraints(point, out);
+            collect_range_bound_constraints(body, out);
+        }
         Node::Summation(_, start, end, body) | Node::Product(_, start, end, body) => {
             let constant_of = |bound: &Node| Evaluator::evaluate(bound, &Environment::new()).ok();
             // Variables in the upper bound are bounded below by a constant
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod constants_tests {
+    use arithma::parser::{parse_latex, parse_latex_raw};
+    use arithma::simplify::{simplify_with_options, SimplifyOptions};
+    use arithma::{Constant, Environment, Evaluator};
+
+    fn simplify(latex: &str) -> String {
+        parse_latex(latex, &Environment::new()).unwrap().to_string()
+    }
+
+    fn evaluate(latex: &str) -> f64 {
+        Evaluator::evaluate(&parse_latex_raw(latex).unwrap(), &Environment::new()).unwrap()
+    }
+
+    #[test]
+    fn test_constants_stay_symbolic() {
+        assert_eq!(simplify("\\pi + \\pi"), "2\\pi");
+        assert_eq!(simplify("2\\tau"), "2\\tau");
+        assert_eq!(
+            simplify("\\operatorname{GoldenRatio}^{2}"),
+            "\\operatorname{GoldenRatio}^{2}"
+        );
+        assert_eq!(simplify("TAU"), "\\tau");
+        assert_eq!(simplify("GOLDENRATIO"), "\\operatorname{GoldenRatio}");
+    }
+
+    #[test]
+    fn test_constants_evaluate_on_demand() {
+        assert_eq!(evaluate("\\tau"), std::f64::consts::TAU);
+        assert!(
+            (evaluate("\\operatorname{GoldenRatio}^{2} - \\operatorname{GoldenRatio} - 1")).abs()
+                < 1e-12
+        );
+        assert_eq!(evaluate("e"), std::f64::consts::E);
+        let f = Evaluator::compile(&parse_latex_raw("\\tau x").unwrap(), &["x"]).unwrap();
+        assert_eq!(f.eval(&[0.5]), std::f64::consts::PI);
+    }
+
+    #[test]
+    fn test_phi_is_an_ordinary_variable() {
+        let expr = parse_latex_raw("\\phi + 1").unwrap();
+        assert!(Evaluator::evaluate(&expr, &Environment::new()).is_err());
+        let mut env = Environment::new();
+        env.set("φ", 2.0);
+        assert_eq!(Evaluator::evaluate(&expr, &env), Ok(3.0));
+    }
+
+    #[test]
+    fn test_a_binding_wins_over_a_constant() {
+        let mut env = Environment::new();
+        env.set("τ", 2.0);
+        let expr = parse_latex_raw("\\tau + 1").unwrap();
+        assert_eq!(Evaluator::evaluate(&expr, &env), Ok(3.0));
+    }
+
+    #[test]
+    fn test_fold_constants_option() {
+        let options = SimplifyOptions {
+            fold_constants: true,
+            ..SimplifyOptions::default()
+        };
+        let folded = simplify_with_options(
+            &parse_latex_raw("2\\tau").unwrap(),
+            &Environment::new(),
+            options,
+        )
+        .unwrap();
+        assert_eq!(
+            Evaluator::evaluate(&folded, &Environment::new()),
+            Ok(2.0 * std::f64::consts::TAU)
+        );
+        assert!(matches!(folded, arithma::Node::Num(_)));
+    }
+
+    #[test]
+    fn test_constant_names() {
+        for constant in Constant::ALL {
+            assert_eq!(Constant::from_name(constant.name()), Some(constant));
+        }
+        assert_eq!(Constant::from_word("PI"), Some(Constant::Pi));
+        assert_eq!(Constant::from_name("x"), None);
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod environment_tests {
+    use arithma::parser::{parse_latex_raw, parse_latex_raw_with_env};
+    use arithma::{Environment, Evaluator};
+
+    #[test]
+    fn test_child_scope_shadows_and_falls_back() {
+        let mut env = Environment::new();
+        env.set("x", 2.0);
+        env.set("y", 3.0);
+        env.set_list("data", &[1.0, 2.0]);
+
+        let mut child = env.child();
+        assert_eq!(child.depth(), 2);
+        child.set("x", 10.0);
+        child.set("z", 1.0);
+        assert_eq!(child.get("x"), Some(10.0));
+        assert_eq!(child.get("y"), Some(3.0));
+        assert_eq!(child.get_list("data").map(<[_]>::len), Some(2));
+
+        // The parent never sees the child's bindings
+        assert_eq!(env.get("x"), Some(2.0));
+        assert_eq!(env.get("z"), None);
+
+        // A scalar shadows a list of the same name, and removing a name
+        // the parent binds hides it only in the child
+        child.set("data", 5.0);
+        assert_eq!(child.get_list("data"), None);
+        assert!(child.remove("y"));
+        assert_eq!(child.get("y"), None);
+        assert!(!child.variable_names().any(|name| name == "y"));
+        assert_eq!(env.get("y"), Some(3.0));
+        assert!(env.get_list("data").is_some());
+    }
+
+    #[test]
+    fn test_push_and_pop_scope() {
+        let mut env = Environment::new();
+        env.set("x", 1.0);
+        env.push_scope();
+        env.set("x", 5.0);
+        env.define_function("f", &["t"], parse_latex_raw("t + x").unwrap())
+            .unwrap();
+        let call = parse_latex_raw_with_env("f(1)", &env).unwrap();
+        assert_eq!(Evaluator::evaluate(&call, &env), Ok(6.0));
+        assert!(env.pop_scope());
+        assert_eq!(env.get("x"), Some(1.0));
+        assert!(env.get_function("f").is_none());
+        assert!(!env.pop_scope());
+    }
+
+    #[test]
+    fn test_summation_index_stays_in_its_scope() {
+        let mut env = Environment::new();
+        env.set("i", 100.0);
+        let sum = parse_latex_raw("\\sum_{i=1}^{3} i + i").unwrap();
+        assert_eq!(Evaluator::evaluate(&sum, &env), Ok(106.0));
+        assert_eq!(env.get("i"), Some(100.0));
+    }
+}
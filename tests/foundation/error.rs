@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod error_tests {
+    use std::error::Error;
+
+    use arithma::{
+        differentiate, integrate, parse_latex_raw, ArithmaError, DomainPolicy, Environment,
+        Evaluator, ExactNum, Matrix, Node,
+    };
+
+    fn evaluate(latex: &str) -> Result<f64, ArithmaError> {
+        Evaluator::evaluate(&parse_latex_raw(latex).unwrap(), &Environment::new())
+    }
+
+    #[test]
+    fn test_failures_are_classified() {
+        assert!(matches!(evaluate("x + 1"), Err(ArithmaError::Eval(_))));
+        assert!(matches!(evaluate("(-1)!"), Err(ArithmaError::Domain(_))));
+        let mut env = Environment::new();
+        env.set("x", 2.0);
+        env.set_domain_policy(DomainPolicy::Error);
+        let err = Evaluator::evaluate(&parse_latex_raw("\\arcsin(x)").unwrap(), &env);
+        assert!(matches!(err, Err(ArithmaError::Domain(_))), "{:?}", err);
+
+        let a = Matrix::new(2, 2, vec![Node::Num(ExactNum::integer(1)); 4]).unwrap();
+        let b = Matrix::new(3, 1, vec![Node::Num(ExactNum::integer(1)); 3]).unwrap();
+        let err = a.multiply(&b, &Environment::new()).unwrap_err();
+        assert!(matches!(err, ArithmaError::DimensionMismatch(_)), "{}", err);
+        let err = a.inverse(&Environment::new()).unwrap_err();
+        assert!(matches!(err, ArithmaError::Domain(_)), "{}", err);
+
+        let integrand = parse_latex_raw("\\exp(-x^2)").unwrap();
+        assert!(matches!(
+            integrate(&integrand, "x"),
+            Err(ArithmaError::NonElementary(_))
+        ));
+        let expr = parse_latex_raw("\\gcd(x, 2)").unwrap();
+        assert!(matches!(
+            differentiate(&expr, "x"),
+            Err(ArithmaError::NotImplemented(_))
+        ));
+    }
+
+    #[test]
+    fn test_messages_read_as_before() {
+        let err = evaluate("y").unwrap_err();
+        assert_eq!(err.to_string(), "Variable 'y' is not defined.");
+        assert_eq!(String::from(err), "Variable 'y' is not defined.");
+
+        let integrand = parse_latex_raw("\\exp(-x^2)").unwrap();
+        let err = integrate(&integrand, "x").unwrap_err();
+        assert!(err.to_string().starts_with("NON_ELEMENTARY: "), "{}", err);
+        assert!(!err.message().starts_with("NON_ELEMENTARY"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_errors_keep_their_location() {
+        let parse_error = parse_latex_raw("x + _").unwrap_err();
+        let err = ArithmaError::from(parse_error.clone());
+        assert_eq!(err.to_string(), parse_error.to_string());
+        assert!(err.source().is_some());
+        let ArithmaError::Parse(inner) = err else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(inner.span, parse_error.span);
+    }
+}
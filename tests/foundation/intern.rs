@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod intern_tests {
+    use arithma::{parse_latex_raw, Node, NodeArena};
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    #[test]
+    fn equal_subtrees_share_an_id() {
+        let mut arena = NodeArena::new();
+        let expr = parse("\\frac{\\sin(x^{2}+1)}{\\cos(x^{2}+1)}");
+        let id = arena.intern(&expr);
+        let &[numerator, denominator] = arena.children(id) else {
+            panic!("a quotient has two children");
+        };
+        assert_eq!(arena.children(numerator), arena.children(denominator));
+        // x, 2, x^2, 1, x^2+1, sin, cos, the quotient
+        assert_eq!(arena.len(), 8);
+        assert_eq!(arena.size(id), 13);
+        let inner = arena.children(numerator)[0];
+        assert_eq!(arena.intern(&parse("x^{2}+1")), inner);
+    }
+
+    #[test]
+    fn get_gives_back_the_interned_tree() {
+        let mut arena = NodeArena::new();
+        let expr = parse("\\sum_{k=1}^{n} \\frac{k}{2} + 0.5x");
+        let id = arena.intern(&expr);
+        assert_eq!(arena.get(id), expr);
+        let half = arena.intern(&parse("\\frac{1}{2}"));
+        let point_five = arena.intern(&parse("0.5"));
+        assert_ne!(half, point_five);
+    }
+}
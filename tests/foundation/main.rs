@@ -1 +1,9 @@
 mod assumptions;
+mod constants;
+mod environment;
+mod error;
+mod intern;
+mod progress;
+mod serialization;
+mod tree_path;
+mod visit;
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod progress_tests {
+    use arithma::{parse_latex_raw, with_progress, Environment, Evaluator, Progress};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Evaluate `latex`, returning the value and every progress report.
+    fn evaluate_recording(latex: &str) -> (f64, Vec<Progress>) {
+        let expr = parse_latex_raw(latex).unwrap();
+        let reports = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&reports);
+        let value = with_progress(
+            move |p| sink.borrow_mut().push(p),
+            || Evaluator::evaluate(&expr, &Environment::new()).unwrap(),
+        );
+        let reports = reports.borrow().clone();
+        (value, reports)
+    }
+
+    fn assert_monotone_to_completion(reports: &[Progress]) {
+        assert!(!reports.is_empty());
+        assert!(reports.windows(2).all(|w| w[0].completed < w[1].completed));
+        let last = reports.last().unwrap();
+        assert_eq!(last.completed, last.total);
+        assert_eq!(last.percent(), 100.0);
+    }
+
+    #[test]
+    fn test_summation_reports_terms_completed() {
+        let (value, reports) = evaluate_recording("\\sum_{k=1}^{10} k");
+        assert_eq!(value, 55.0);
+        assert_eq!(reports.len(), 11);
+        assert_eq!(
+            reports[0],
+            Progress {
+                completed: 0,
+                total: 10
+            }
+        );
+        assert_monotone_to_completion(&reports);
+    }
+
+    #[test]
+    fn test_reports_are_throttled_for_long_ranges() {
+        let (_, reports) = evaluate_recording("\\sum_{k=1}^{20000} k");
+        assert!(reports.len() <= 1001, "{} reports", reports.len());
+        assert_monotone_to_completion(&reports);
+    }
+
+    #[test]
+    fn test_only_the_outermost_job_reports() {
+        let (value, reports) = evaluate_recording("\\sum_{i=1}^{3} \\sum_{j=1}^{4} i j");
+        assert_eq!(value, 60.0);
+        assert!(reports.iter().all(|p| p.total == 3), "{:?}", reports);
+        assert_monotone_to_completion(&reports);
+    }
+
+    #[test]
+    fn test_numeric_integral_reports_share_of_interval() {
+        let (value, reports) = evaluate_recording("\\int_0^1 e^{-x^2} \\, dx");
+        assert!((value - 0.746_824_132_812_427).abs() < 1e-9, "{}", value);
+        assert!(reports.iter().all(|p| p.total == 1000));
+        assert_monotone_to_completion(&reports);
+    }
+
+    #[test]
+    fn test_no_callback_outside_with_progress() {
+        let expr = parse_latex_raw("\\sum_{k=1}^{5} k").unwrap();
+        let calls = Rc::new(RefCell::new(0));
+        let counter = Rc::clone(&calls);
+        with_progress(move |_| *counter.borrow_mut() += 1, || ());
+        Evaluator::evaluate(&expr, &Environment::new()).unwrap();
+        assert_eq!(*calls.borrow(), 0);
+    }
+}
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod serialization_tests {
+    use arithma::{parse_latex_matrix, parse_latex_raw, Environment, ExactNum, Matrix, Node};
+
+    fn round_trip(latex: &str) {
+        let expr = parse_latex_raw(latex).unwrap();
+        let json = expr.to_json().unwrap();
+        assert_eq!(Node::from_json(&json).unwrap(), expr, "{}", json);
+    }
+
+    #[test]
+    fn test_node_json_round_trip() {
+        for latex in [
+            "x^2 + 3x - 1",
+            "\\frac{\\sin(x)}{\\sqrt{x}}",
+            "\\sum_{k=1}^{n}{k^{2}}",
+            "|x - 1| \\geq 2",
+            "0.25\\pi",
+            "\\binom{n}{2} = 10",
+        ] {
+            round_trip(latex);
+        }
+    }
+
+    #[test]
+    fn test_node_json_keeps_exact_numbers() {
+        let expr = Node::Num(ExactNum::rational(-7, 3));
+        let back = Node::from_json(&expr.to_json().unwrap()).unwrap();
+        assert_eq!(back, expr);
+        assert!(matches!(back, Node::Num(ExactNum::Rational(_))));
+    }
+
+    #[test]
+    fn test_node_json_shape() {
+        let json = Node::Variable("x".to_string()).to_json().unwrap();
+        assert_eq!(json, r#"{"name":"x","op":"variable"}"#);
+    }
+
+    #[test]
+    fn test_node_from_invalid_json() {
+        let err = Node::from_json(r#"{"Nope":1}"#).unwrap_err();
+        assert!(err.starts_with("Invalid expression JSON"), "{}", err);
+        assert!(Node::Num(ExactNum::Float(f64::NAN))
+            .to_json()
+            .and_then(|json| Node::from_json(&json))
+            .is_err());
+    }
+
+    #[test]
+    fn test_matrix_json_round_trip() {
+        let env = Environment::new();
+        let m = parse_latex_matrix(
+            "\\begin{pmatrix} 1 & x \\\\ \\frac{1}{2} & y^2 \\end{pmatrix}",
+            &env,
+        )
+        .unwrap();
+        let back = Matrix::from_json(&m.to_json().unwrap()).unwrap();
+        assert_eq!((back.rows, back.cols), (2, 2));
+        assert_eq!(back.elements, m.elements);
+    }
+
+    #[test]
+    fn test_matrix_json_checks_dimensions() {
+        let x = Node::Variable("x".to_string()).to_json().unwrap();
+        let json = format!(r#"{{"rows":2,"cols":2,"elements":[{x}]}}"#);
+        let err = Matrix::from_json(&json).unwrap_err();
+        assert!(err.to_string().contains("expected 4 elements"), "{}", err);
+    }
+}
+
+#[cfg(test)]
+mod json_ast_tests {
+    use arithma::{parse_latex, parse_latex_raw, Environment, ExactNum, Node};
+    use serde_json::json;
+
+    fn build(value: serde_json::Value) -> Result<Node, String> {
+        Node::from_json(&value.to_string())
+    }
+
+    fn var(name: &str) -> serde_json::Value {
+        json!({"op": "variable", "name": name})
+    }
+
+    #[test]
+    fn test_build_without_latex() {
+        let expr = build(json!({"op": "add", "args": [
+            {"op": "power", "args": [var("x"), {"op": "num", "value": 2}]},
+            {"op": "num", "value": "1/3"}
+        ]}))
+        .unwrap();
+        assert_eq!(
+            expr,
+            Node::Add(
+                Box::new(parse_latex_raw("x^2").unwrap()),
+                Box::new(Node::Num(ExactNum::rational(1, 3)))
+            )
+        );
+        assert_eq!(expr.to_string(), "x^{2} + \\frac{1}{3}");
+
+        let sine = build(json!({"op": "function", "name": "sin", "args": [var("x")]})).unwrap();
+        assert_eq!(sine, parse_latex_raw("\\sin(x)").unwrap());
+
+        // Sums and products of more than two operands group from the left
+        let sum = build(json!({"op": "add", "args": [var("a"), var("b"), var("c")]})).unwrap();
+        assert_eq!(sum, parse_latex_raw("a + b + c").unwrap());
+    }
+
+    #[test]
+    fn test_numbers() {
+        let num = |value| match build(json!({"op": "num", "value": value})).unwrap() {
+            Node::Num(n) => n,
+            other => panic!("expected a number, got {}", other),
+        };
+        assert!(matches!(num(json!(3)), ExactNum::Rational(_)));
+        assert!(matches!(num(json!("-7/3")), ExactNum::Rational(_)));
+        assert!(matches!(
+            num(json!("123456789012345678901234567890")),
+            ExactNum::Rational(_)
+        ));
+        assert!(matches!(num(json!(0.25)), ExactNum::Float(_)));
+        assert_eq!(
+            Node::Num(ExactNum::rational(-7, 3)).to_json().unwrap(),
+            r#"{"op":"num","value":"-7/3"}"#
+        );
+        assert!(build(json!({"op": "num", "value": "1/0"})).is_err());
+        assert!(build(json!({"op": "num", "value": "x"})).is_err());
+    }
+
+    #[test]
+    fn test_binders_round_trip() {
+        for latex in [
+            "\\sum_{k=1}^{n} k^2",
+            "\\prod_{i=1}^{5} i",
+            "\\int_0^1 x^2 \\, dx",
+            "\\int \\sin(x) \\, dx",
+            "\\lim_{x \\to 0^{+}} \\frac{1}{x}",
+            "\\frac{d}{dx}\\gcd(x, 6)",
+            "|x| + \\lfloor y \\rfloor - 3!",
+        ] {
+            let expr = parse_latex_raw(latex).unwrap();
+            let json = expr.to_json().unwrap();
+            assert_eq!(Node::from_json(&json).unwrap(), expr, "{}", json);
+        }
+        let piecewise = Node::Piecewise(vec![
+            (
+                parse_latex_raw("x").unwrap(),
+                parse_latex_raw("x > 0").unwrap(),
+            ),
+            (
+                parse_latex_raw("-x").unwrap(),
+                parse_latex_raw("x \\leq 0").unwrap(),
+            ),
+        ]);
+        assert_eq!(
+            Node::from_json(&piecewise.to_json().unwrap()).unwrap(),
+            piecewise
+        );
+        let matrix =
+            parse_latex_raw("\\begin{pmatrix} 1 & x \\\\ 0 & 2 \\\\ y & 3 \\end{pmatrix}").unwrap();
+        let json = matrix.to_json().unwrap();
+        assert!(json.contains(r#""op":"matrix""#) && json.contains(r#""cols":2"#));
+        assert_eq!(Node::from_json(&json).unwrap(), matrix);
+        assert!(build(json!({"op": "matrix", "cols": 2, "args": [var("x")]})).is_err());
+        let limit = build(json!({"op": "limit", "var": "x", "args": [
+            {"op": "num", "value": 0}, var("x")
+        ]}))
+        .unwrap();
+        assert_eq!(limit, parse_latex_raw("\\lim_{x \\to 0} x").unwrap());
+    }
+
+    #[test]
+    fn test_built_expressions_run_through_the_engine() {
+        let expr = build(json!({"op": "integral", "var": "x", "args": [
+            {"op": "num", "value": 0}, {"op": "num", "value": 1}, var("x")
+        ]}))
+        .unwrap();
+        let env = Environment::new();
+        assert_eq!(
+            parse_latex(&expr.to_latex(), &env).unwrap().to_string(),
+            "\\frac{1}{2}"
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_path() {
+        let err = build(json!({"op": "add", "args": [var("x"), {"op": "sine", "args": []}]}))
+            .unwrap_err();
+        assert!(
+            err.contains("$.args[1]") && err.contains("unknown op \"sine\""),
+            "{}",
+            err
+        );
+        let err = build(json!({"op": "variable", "nmae": "x"})).unwrap_err();
+        assert!(err.contains("unknown field \"nmae\""), "{}", err);
+        let err = build(json!({"op": "subtract", "args": [var("x")]})).unwrap_err();
+        assert!(err.contains("takes 2 arguments, found 1"), "{}", err);
+        let err =
+            build(json!({"op": "summation", "args": [var("x"), var("x"), var("x")]})).unwrap_err();
+        assert!(err.contains("\"var\""), "{}", err);
+        let err = build(json!({"op": "piecewise", "args": [var("x")]})).unwrap_err();
+        assert!(err.contains("pairs"), "{}", err);
+        assert!(build(json!(["x"])).is_err());
+    }
+}
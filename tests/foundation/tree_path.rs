@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tree_path_tests {
+    use arithma::{apply_edits, diff_trees, parse_latex_raw, Node};
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    #[test]
+    fn test_paths_follow_child_order() {
+        let expr = parse("\\frac{x^2}{y} + \\sin(z)");
+        assert_eq!(expr.get_at(&[]), Some(&expr));
+        assert_eq!(expr.get_at(&[0, 0, 1]), Some(&parse("2")));
+        assert_eq!(expr.get_at(&[0, 1]), Some(&parse("y")));
+        assert_eq!(expr.get_at(&[1, 0]), Some(&parse("z")));
+        assert_eq!(expr.get_at(&[1, 1]), None);
+        assert_eq!(expr.get_at(&[0, 0, 1, 0]), None);
+
+        let sum = parse("\\sum_{k=1}^{n} k^2");
+        assert_eq!(sum.get_at(&[1]), Some(&parse("n")));
+        assert_eq!(sum.get_at(&[2]), Some(&parse("k^2")));
+        let integral = parse("\\int_0^1 x \\, dx");
+        assert_eq!(integral.get_at(&[2]), Some(&parse("x")));
+    }
+
+    #[test]
+    fn test_replace_at_returns_the_old_subtree() {
+        let mut expr = parse("x^2 + 3x");
+        let old = expr.replace_at(&[1, 0], parse("5")).unwrap();
+        assert_eq!(old, parse("3"));
+        assert_eq!(expr, parse("x^2 + 5x"));
+
+        let old = expr.replace_at(&[], parse("y")).unwrap();
+        assert_eq!(old, parse("x^2 + 5x"));
+        assert_eq!(expr, parse("y"));
+
+        let err = expr.replace_at(&[0], parse("1")).unwrap_err();
+        assert!(err.contains("No subtree at path [0]"), "{}", err);
+    }
+
+    #[test]
+    fn test_diff_finds_the_smallest_replacements() {
+        let old = parse("\\sin(x^2) + 3y");
+        let new = parse("\\sin(x^3) + 3z");
+        let edits = diff_trees(&old, &new);
+        let paths: Vec<&[usize]> = edits.iter().map(|e| e.path.as_slice()).collect();
+        assert_eq!(paths, vec![&[0, 0, 1][..], &[1, 1][..]]);
+        assert_eq!(edits[1].to_string(), "[1, 1]: y → z");
+        assert_eq!(apply_edits(&old, &edits).unwrap(), new);
+
+        assert!(diff_trees(&old, &old).is_empty());
+        // A different function replaces the whole call
+        let edits = diff_trees(&parse("\\sin(x)"), &parse("\\cos(x)"));
+        assert_eq!(edits.len(), 1);
+        assert!(edits[0].path.is_empty());
+    }
+
+    #[test]
+    fn test_apply_edits_checks_the_old_subtree() {
+        let edits = diff_trees(&parse("x + 1"), &parse("x + 2"));
+        let err = apply_edits(&parse("x + 5"), &edits).unwrap_err();
+        assert!(err.contains("is 5, not 1"), "{}", err);
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod visit_tests {
+    use arithma::{parse_latex_raw, Node};
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    #[test]
+    fn visit_is_parents_before_children() {
+        let mut seen = Vec::new();
+        parse("x + \\sin(y)").visit(&mut |node| seen.push(node.to_string()));
+        assert_eq!(seen, ["x + \\sin(y)", "x", "\\sin(y)", "y"]);
+    }
+
+    #[test]
+    fn fold_accumulates_over_every_node() {
+        let expr = parse("\\frac{x^{2}}{1 + x}");
+        assert_eq!(expr.fold(0, &mut |count, _| count + 1), 7);
+        let x_count = expr.fold(0, &mut |count, node| match node {
+            Node::Variable(name) if name == "x" => count + 1,
+            _ => count,
+        });
+        assert_eq!(x_count, 2);
+    }
+
+    #[test]
+    fn map_children_keeps_the_operator() {
+        let doubled = parse("\\sum_{k=1}^{n} k")
+            .map_children(|child| Node::Multiply(Box::new(parse("2")), Box::new(child.clone())));
+        assert_eq!(doubled, parse("\\sum_{k=2 \\cdot 1}^{2n} 2k"));
+        assert_eq!(parse("x").map_children(|_| parse("y")), parse("x"));
+    }
+
+    #[test]
+    fn try_map_children_stops_at_the_first_error() {
+        let result = parse("\\max(a, b, c)").try_map_children(|child| match child {
+            Node::Variable(name) if name == "b" => Err(name.clone()),
+            _ => Ok(child.clone()),
+        });
+        assert_eq!(result, Err("b".to_string()));
+    }
+
+    #[test]
+    fn bound_variables() {
+        assert_eq!(parse("\\sum_{k=1}^{n} k").bound_variable(), Some("k"));
+        assert_eq!(parse("\\int_{0}^{1} t \\, dt").bound_variable(), Some("t"));
+        assert_eq!(parse("\\int t \\, dt").bound_variable(), None);
+        assert_eq!(parse("x + 1").bound_variable(), None);
+    }
+}
@@ -0,0 +1,21 @@
+#[cfg(test)]
+mod batch_js_tests {
+    use arithma::wasm_bindings::{differentiate_many_js, integrate_many_js};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_differentiate_many_js_reports_per_entry() {
+        let out = differentiate_many_js(r#"["x^2", "\\sin(", "3x"]"#, "x").unwrap();
+        let entries: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(entries[0], json!({"latex": "2x"}));
+        assert!(entries[1]["error"].is_string());
+        assert_eq!(entries[2], json!({"latex": "3"}));
+    }
+
+    #[test]
+    fn test_integrate_many_js() {
+        let out = integrate_many_js(r#"["2x", "\\cos(x)"]"#, "x").unwrap();
+        let entries: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(entries, json!([{"latex": "x^{2}"}, {"latex": "\\sin(x)"}]));
+    }
+}
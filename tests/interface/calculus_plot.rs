@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod calculus_plot_tests {
+    use arithma::wasm_bindings::{calculus_plot_data, calculus_plot_js};
+
+    #[test]
+    fn test_three_curves_on_shared_grid() {
+        let data = calculus_plot_data("x^2", "x", 0.0, 2.0, 201).unwrap();
+        assert_eq!(data.x.len(), 201);
+        assert_eq!(data.f.len(), 201);
+        assert_eq!(data.derivative.len(), 201);
+        assert_eq!(data.integral.len(), 201);
+        assert_eq!(data.derivative_latex.as_deref(), Some("2x"));
+
+        for (i, &x) in data.x.iter().enumerate() {
+            assert!((data.f[i].unwrap() - x * x).abs() < 1e-12);
+            assert!((data.derivative[i].unwrap() - 2.0 * x).abs() < 1e-12);
+            // Trapezoid error for x² with h = 0.01 is at most h²·x/6.
+            assert!((data.integral[i].unwrap() - x.powi(3) / 3.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_integral_restarts_after_pole() {
+        let data = calculus_plot_data("\\frac{1}{x}", "x", -1.0, 1.0, 5).unwrap();
+        assert_eq!(
+            data.f,
+            vec![Some(-1.0), Some(-2.0), None, Some(2.0), Some(1.0)]
+        );
+        assert_eq!(
+            data.integral,
+            vec![Some(0.0), Some(-0.75), None, Some(0.0), Some(0.75)]
+        );
+        assert_eq!(data.derivative[2], None);
+    }
+
+    #[test]
+    fn test_singular_endpoint_leaves_the_rest_of_the_integral() {
+        let data = calculus_plot_data("\\ln(x)", "x", 0.0, 1.0, 1001).unwrap();
+        assert_eq!(data.f[0], None);
+        assert_eq!(data.integral[0], None);
+        assert_eq!(data.integral[1], Some(0.0));
+        // ∫_{x₁}^{1} ln x dx = x₁ − x₁ ln x₁ − 1
+        let x1 = data.x[1];
+        let expected = x1 - x1 * x1.ln() - 1.0;
+        assert!((data.integral[1000].unwrap() - expected).abs() < 1e-3);
+        assert!(data.integral.iter().skip(1).all(Option::is_some));
+    }
+
+    #[test]
+    fn test_rejects_bad_range() {
+        assert!(calculus_plot_data("x", "x", 1.0, 0.0, 10).is_err());
+        assert!(calculus_plot_data("x", "x", 0.0, 1.0, 1).is_err());
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let json = calculus_plot_js("\\sin(x)", "x", 0.0, 3.0, 4).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for key in ["x", "f", "derivative", "integral"] {
+            assert_eq!(value[key].as_array().unwrap().len(), 4, "{}", key);
+        }
+        assert_eq!(value["derivative_latex"], "\\cos(x)");
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod differentiate_js_tests {
+    use arithma::wasm_bindings::{
+        differentiate_expression_js, differentiate_js, partial_derivative_js,
+    };
+
+    #[test]
+    fn test_differentiate_expression_js() {
+        assert_eq!(
+            differentiate_expression_js("x^3 + \\sin(x)", "x").unwrap(),
+            "3x^{2} + \\cos(x)"
+        );
+        assert_eq!(
+            differentiate_js("x^3", "x").unwrap(),
+            differentiate_expression_js("x^3", "x").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_partial_derivative_js_holds_other_variables_constant() {
+        assert_eq!(
+            partial_derivative_js("x^2 y + y^3", "x").unwrap(),
+            "2y \\cdot x"
+        );
+        assert_eq!(
+            partial_derivative_js("x^2 y + y^3", "y").unwrap(),
+            "x^{2} + 3y^{2}"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod evaluate_js_tests {
+    use arithma::wasm_bindings::evaluate_latex_expression_js;
+    use arithma::Environment;
+
+    fn evaluate(latex: &str) -> String {
+        let env = serde_json::to_string(&Environment::new()).unwrap();
+        evaluate_latex_expression_js(latex, &env).unwrap()
+    }
+
+    #[test]
+    fn test_large_integers_stay_exact() {
+        assert_eq!(
+            evaluate("2^{200}"),
+            "1606938044258990275541962092341162602522202993782792835301376"
+        );
+        assert_eq!(
+            evaluate("100!"),
+            "93326215443944152681699238856266700490715968264381621468592963895217599993229915608941463976156518286253697920827223758251185210916864000000000000000000000000"
+        );
+        assert_eq!(evaluate("2^{64} + 1"), "18446744073709551617");
+    }
+
+    #[test]
+    fn test_small_values_still_print_as_f64() {
+        assert_eq!(evaluate("2^{10}"), "1024");
+        assert_eq!(evaluate("2^{53}"), "9007199254740992");
+        assert_eq!(evaluate("\\frac{1}{4}"), "0.25");
+    }
+}
+
+#[cfg(test)]
+mod ast_json_js_tests {
+    use arithma::wasm_bindings::{evaluate_json_js, json_to_latex_js, parse_latex_to_json_js};
+    use arithma::Environment;
+
+    #[test]
+    fn test_cached_ast_rehydrates_without_reparsing() {
+        let ast = parse_latex_to_json_js("\\frac{x^2}{2} + 1").unwrap();
+        assert_eq!(json_to_latex_js(&ast).unwrap(), "\\frac{x^{2}}{2} + 1");
+
+        let mut env = Environment::new();
+        env.set("x", 4.0);
+        let env = serde_json::to_string(&env).unwrap();
+        assert_eq!(evaluate_json_js(&ast, &env).unwrap(), 9.0);
+    }
+}
+
+#[cfg(test)]
+mod tree_path_js_tests {
+    use arithma::wasm_bindings::{
+        diff_trees_js, json_to_latex_js, parse_latex_to_json_js, replace_at_path_js,
+        IncrementalParserHandle,
+    };
+
+    #[test]
+    fn test_edit_a_cached_tree_at_a_path() {
+        let ast = parse_latex_to_json_js("x^2 + 1").unwrap();
+        let three = parse_latex_to_json_js("3").unwrap();
+        let edited = replace_at_path_js(&ast, "[0, 1]", &three).unwrap();
+        assert_eq!(json_to_latex_js(&edited).unwrap(), "x^{3} + 1");
+
+        let edits: serde_json::Value =
+            serde_json::from_str(&diff_trees_js(&ast, &edited).unwrap()).unwrap();
+        assert_eq!(edits[0]["path"], serde_json::json!([0, 1]));
+        assert_eq!(
+            edits[0]["new"],
+            serde_json::json!({"op": "num", "value": 3})
+        );
+    }
+
+    #[test]
+    fn test_incremental_parser_handle_parses_each_edit() {
+        let mut parser = IncrementalParserHandle::new();
+        for input in ["x", "x^2", "x^2 + 1", "x^2 + 10", "x^2 + 10y"] {
+            let ast = parse_latex_to_json_js(input).unwrap();
+            assert_eq!(parser.parse(input).unwrap(), ast);
+        }
+        parser.reset();
+        assert_eq!(
+            parser.parse("x^2 + 1").unwrap(),
+            parse_latex_to_json_js("x^2 + 1").unwrap()
+        );
+    }
+}
+
+#[cfg(test)]
+mod structured_result_js_tests {
+    use arithma::wasm_bindings::{
+        differentiate_expression_json, evaluate_latex_expression_json, integrate_expression_json,
+        simplify_latex_json, solve_json,
+    };
+    use arithma::Environment;
+    use serde_json::{json, Value};
+
+    fn evaluate(latex: &str) -> Value {
+        let env = serde_json::to_string(&Environment::new()).unwrap();
+        serde_json::from_str(&evaluate_latex_expression_json(latex, &env).unwrap()).unwrap()
+    }
+
+    fn parse(result: String) -> Value {
+        serde_json::from_str(&result).unwrap()
+    }
+
+    #[test]
+    fn test_numbers_and_expressions() {
+        assert_eq!(
+            evaluate("\\frac{2}{8}"),
+            json!({"kind": "number", "value": 0.25, "latex": "\\frac{1}{4}", "steps": ["\\frac{2}{8}"]})
+        );
+        assert_eq!(evaluate("2^{64} + 1")["latex"], "18446744073709551617");
+        let expr = evaluate("x + x");
+        assert_eq!(expr["kind"], "expression");
+        assert_eq!(expr["value"], Value::Null);
+        assert_eq!(expr["latex"], "2x");
+    }
+
+    #[test]
+    fn test_equations_are_checked_or_solved() {
+        assert_eq!(
+            evaluate("2 + 3 = 5"),
+            json!({"kind": "equation", "value": null, "latex": "5 = 5", "holds": true})
+        );
+        assert_eq!(evaluate("1 = 2")["holds"], false);
+        let solved = evaluate("x^2 = 4");
+        assert_eq!(solved["kind"], "equation");
+        assert_eq!(solved["solutions"], json!(["2", "-2"]));
+        assert_eq!(solved["latex"], "x = 2, x = -2");
+        assert_eq!(
+            parse(solve_json("2x + 5 = 11", "x").unwrap()),
+            json!({"kind": "equation", "value": null, "latex": "x = 3", "solutions": ["3"]})
+        );
+        assert_eq!(
+            parse(solve_json("2x + 5 > 11", "x").unwrap()),
+            json!({"kind": "inequality", "value": null, "latex": "x > 3", "solutions": ["(3, \\infty)"]})
+        );
+    }
+
+    #[test]
+    fn test_calculus_results_carry_their_steps() {
+        let derivative = parse(differentiate_expression_json("x^3", "x").unwrap());
+        assert_eq!(derivative["kind"], "expression");
+        assert_eq!(derivative["latex"], "3x^{2}");
+        assert_eq!(derivative["steps"][0], "x^{3}");
+
+        let integral = parse(integrate_expression_json("3x^2", "x").unwrap());
+        assert_eq!(integral["latex"], "x^{3} + C");
+
+        let simplified = parse(simplify_latex_json("\\frac{6}{4} \\cdot 2").unwrap());
+        assert_eq!(simplified["kind"], "number");
+        assert_eq!(simplified["latex"], "3");
+        assert_eq!(simplified["steps"], json!(["\\frac{6}{4} \\cdot 2"]));
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod ivp_trajectory_tests {
+    use arithma::wasm_bindings::{ivp_trajectory, solve_ivp_js};
+
+    #[test]
+    fn test_exponential_growth() {
+        for method in ["rk4", "rk45", "implicit_euler"] {
+            let data = ivp_trajectory("y", "t", "y", 0.0, 1.0, 1.0, method, 11).unwrap();
+            assert_eq!(data.t.len(), 11);
+            assert_eq!(data.t[10], 1.0);
+            let tol = if method == "implicit_euler" {
+                0.05
+            } else {
+                1e-5
+            };
+            assert!((data.y[10] - std::f64::consts::E).abs() < tol, "{method}");
+        }
+    }
+
+    #[test]
+    fn test_unknown_method() {
+        assert!(ivp_trajectory("y", "t", "y", 0.0, 1.0, 1.0, "euler", 11).is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        let json = solve_ivp_js("-y", "t", "y", 0.0, 2.0, 1.0, "rk45", 3).unwrap();
+        assert!(json.starts_with(r#"{"t":[0.0,0.5,1.0],"y":[2.0,"#));
+        assert!(json.contains(r#""rejected_steps":"#));
+    }
+}
@@ -0,0 +1,8 @@
+mod batch;
+mod calculus_plot;
+mod differentiate;
+mod evaluate;
+mod ivp_trajectory;
+mod operation;
+mod session;
+mod slope_field;
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod run_operation_js_tests {
+    use arithma::wasm_bindings::run_operation_js;
+    use serde_json::{json, Value};
+
+    fn run(request: Value) -> Value {
+        serde_json::from_str(&run_operation_js(&request.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_simplify_and_evaluate() {
+        let out = run(json!({"operation": "simplify", "latex": "x + x"}));
+        assert_eq!(
+            out,
+            json!({"ok": true, "operation": "simplify", "latex": "2x"})
+        );
+
+        let out = run(json!({
+            "operation": "evaluate",
+            "latex": "x^2 + 1",
+            "env": {"vars": {"x": 3.0}}
+        }));
+        assert_eq!(out["ok"], true);
+        assert_eq!(out["value"], 10.0);
+    }
+
+    #[test]
+    fn test_differentiate_infers_variable_and_chains_variables() {
+        let out = run(json!({"operation": "differentiate", "latex": "x^3"}));
+        assert_eq!(out["latex"], "3x^{2}");
+
+        let out = run(json!({
+            "operation": "differentiate",
+            "latex": "x^2 \\cdot y",
+            "variables": ["x", "y"]
+        }));
+        assert_eq!(out["latex"], "2x");
+    }
+
+    #[test]
+    fn test_integrate_indefinite_and_definite() {
+        let out = run(json!({"operation": "integrate", "latex": "2x"}));
+        assert_eq!(out["latex"], "x^{2} + C");
+
+        let out = run(json!({
+            "operation": "integrate",
+            "latex": "2x",
+            "options": {"lower": 0.0, "upper": 3.0}
+        }));
+        assert!((out["value"].as_f64().unwrap() - 9.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_solve_returns_each_solution() {
+        let out = run(json!({"operation": "solve", "latex": "x^2 - 4 = 0"}));
+        assert_eq!(out["ok"], true);
+        let mut results: Vec<String> = serde_json::from_value(out["results"].clone()).unwrap();
+        results.sort();
+        assert_eq!(results, vec!["-2", "2"]);
+    }
+
+    #[test]
+    fn test_options_drive_limit_taylor_and_substitute() {
+        let out = run(json!({
+            "operation": "limit",
+            "latex": "\\frac{\\sin(x)}{x}",
+            "options": {"point": 0.0}
+        }));
+        assert_eq!(out["latex"], "1");
+
+        let out = run(json!({
+            "operation": "substitute",
+            "latex": "x^2 + y",
+            "variable": "x",
+            "options": {"value": "3"}
+        }));
+        assert_eq!(out["latex"], "3^{2} + y");
+    }
+
+    #[test]
+    fn test_failures_are_reported_not_thrown() {
+        let out = run(json!({"operation": "transmogrify", "latex": "x"}));
+        assert_eq!(out["ok"], false);
+        assert!(out["error"].as_str().unwrap().contains("differentiate"));
+
+        let out = run(json!({"operation": "limit", "latex": "x"}));
+        assert_eq!(out["error"], "A limit needs options.point");
+
+        let out = run(json!({"operation": "differentiate", "latex": "a \\cdot b"}));
+        assert_eq!(out["ok"], false);
+
+        let out: Value = serde_json::from_str(&run_operation_js("{not json")).unwrap();
+        assert_eq!(out["ok"], false);
+        assert!(out["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid request"));
+    }
+}
@@ -0,0 +1,179 @@
+#[cfg(test)]
+mod session_tests {
+    use arithma::wasm_bindings::SessionHandle;
+
+    #[test]
+    fn test_results_are_labelled_in_order() {
+        let mut session = SessionHandle::new();
+        assert_eq!(session.evaluate_entry("2 + 3").unwrap().label, "%1");
+        assert_eq!(session.evaluate_entry("x^2").unwrap().label, "%2");
+        assert_eq!(session.len(), 2);
+    }
+
+    #[test]
+    fn test_reference_earlier_result() {
+        let mut session = SessionHandle::new();
+        session.evaluate_entry("2 + 3").unwrap();
+        let entry = session.evaluate_entry("%1 + 3").unwrap();
+        assert_eq!(entry.value, Some(8.0));
+        assert_eq!(entry.result_latex, "8");
+    }
+
+    #[test]
+    fn test_symbolic_results_substitute_as_trees() {
+        let mut session = SessionHandle::new();
+        session.evaluate_entry("x + 1").unwrap();
+        session.evaluate_entry("x - 1").unwrap();
+        let entry = session.evaluate_entry("%1 \\cdot %2").unwrap();
+        assert_eq!(entry.value, None);
+        let product = entry.result.clone();
+
+        session.set_variable("x", 3.0);
+        let evaluated = session.evaluate_entry("%3").unwrap();
+        assert_eq!(evaluated.value, Some(8.0), "result was {}", product);
+    }
+
+    #[test]
+    fn test_implicit_multiplication_with_reference() {
+        let mut session = SessionHandle::new();
+        session.evaluate_entry("\\frac{1}{2}").unwrap();
+        let entry = session.evaluate_entry("4%1").unwrap();
+        assert_eq!(entry.value, Some(2.0));
+    }
+
+    #[test]
+    fn test_unknown_reference_is_an_error() {
+        let mut session = SessionHandle::new();
+        let err = session.evaluate_entry("%4 + 1").unwrap_err();
+        assert!(err.contains("%4"), "{}", err);
+        assert!(session.is_empty(), "failed inputs are not recorded");
+    }
+
+    #[test]
+    fn test_bare_percent_is_rejected() {
+        let mut session = SessionHandle::new();
+        assert!(session.evaluate_entry("50% + 1").is_err());
+    }
+
+    #[test]
+    fn test_clear_history_restarts_labels() {
+        let mut session = SessionHandle::new();
+        session.evaluate_entry("1").unwrap();
+        session.clear_history();
+        assert_eq!(session.evaluate_entry("2").unwrap().label, "%1");
+    }
+
+    #[test]
+    fn test_history_json() {
+        let mut session = SessionHandle::new();
+        session.evaluate_entry("1 + 1").unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&session.history_json().unwrap()).unwrap();
+        assert_eq!(json[0]["label"], "%1");
+        assert_eq!(json[0]["input"], "1 + 1");
+        assert_eq!(json[0]["result_latex"], "2");
+        assert_eq!(json[0]["value"], 2.0);
+    }
+}
+
+#[cfg(test)]
+mod assignment_session_tests {
+    use arithma::{Binding, Environment, ExactNum, Session, SessionOutcome};
+
+    fn run(session: &mut Session, latex: &str) -> String {
+        match session.execute(latex).unwrap() {
+            SessionOutcome::Assigned { name, binding } => format!("{} := {}", name, binding),
+            SessionOutcome::Evaluated(node) => node.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_assigned_value_is_used_by_later_inputs() {
+        let mut session = Session::new();
+        assert_eq!(run(&mut session, "x = 5"), "x := 5");
+        assert_eq!(run(&mut session, "x + 2"), "7");
+        assert_eq!(run(&mut session, "a = \\frac{1}{3}"), "a := \\frac{1}{3}");
+        assert_eq!(run(&mut session, "a + a"), "\\frac{2}{3}");
+        assert_eq!(session.environment().get("x"), Some(5.0));
+    }
+
+    #[test]
+    fn test_expression_binding_resolves_once_its_variables_are_bound() {
+        let mut session = Session::new();
+        assert_eq!(run(&mut session, "y = t^2 + 1"), "y := t^{2} + 1");
+        assert_eq!(run(&mut session, "2y"), run(&mut session, "2(t^2 + 1)"));
+        run(&mut session, "t = 3");
+        assert_eq!(run(&mut session, "y"), "10");
+    }
+
+    #[test]
+    fn test_reassignment_reads_the_old_value() {
+        let mut session = Session::new();
+        run(&mut session, "n = 1");
+        assert_eq!(run(&mut session, "n = n + 1"), "n := 2");
+        // An unbound name on both sides is an equation, not an assignment
+        assert_eq!(run(&mut session, "z = z + 1"), "z = z + 1");
+        assert!(!session.is_bound("z"));
+    }
+
+    #[test]
+    fn test_bound_index_is_not_substituted() {
+        let mut session = Session::new();
+        run(&mut session, "k = 10");
+        assert_eq!(run(&mut session, "\\sum_{k=1}^{3} k"), "6");
+    }
+
+    #[test]
+    fn test_invalid_assignments_are_errors() {
+        let mut session = Session::new();
+        let err = session.execute("e = 3").unwrap_err();
+        assert!(err.contains("constant"), "{}", err);
+        run(&mut session, "u = v + 1");
+        let err = session.execute("v = u").unwrap_err();
+        assert!(err.contains("itself"), "{}", err);
+    }
+
+    #[test]
+    fn test_bindings_are_listed_and_cleared() {
+        let mut env = Environment::new();
+        env.set_list("data", &[1.0, 2.0]);
+        let mut session = Session::with_environment(env);
+        run(&mut session, "x = 2");
+        run(&mut session, "y = x + w");
+        assert_eq!(
+            session.bindings(),
+            vec![
+                (
+                    "data".to_string(),
+                    Binding::List(vec![ExactNum::integer(1), ExactNum::integer(2)])
+                ),
+                ("x".to_string(), Binding::Value(ExactNum::integer(2))),
+                (
+                    "y".to_string(),
+                    Binding::Expression(arithma::parse_latex_raw("w + 2").unwrap())
+                ),
+            ]
+        );
+        assert!(session.clear("x"));
+        assert!(!session.clear("x"));
+        assert_eq!(run(&mut session, "x + 1"), "x + 1");
+        session.clear_all();
+        assert!(session.bindings().is_empty());
+    }
+
+    #[test]
+    fn test_ans_is_the_previous_result() {
+        let mut session = Session::new();
+        assert_eq!(session.ans(), None);
+        assert_eq!(run(&mut session, "ans + 1"), "ans + 1");
+        run(&mut session, "2 + 3");
+        assert_eq!(run(&mut session, "ans^2"), "25");
+        assert_eq!(run(&mut session, "2ans"), "50");
+        run(&mut session, "y = x + 1");
+        assert_eq!(run(&mut session, "ans \\cdot 2"), "2x + 2");
+        let err = session.execute("ans = 3").unwrap_err();
+        assert!(err.contains("previous result"), "{}", err);
+        session.clear_all();
+        assert!(session.ans().is_some());
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod slope_field_tests {
+    use arithma::wasm_bindings::{slope_field_data, slope_field_js, SlopeFieldGrid};
+
+    fn grid(nx: usize, ny: usize) -> SlopeFieldGrid {
+        SlopeFieldGrid {
+            x_min: -1.0,
+            x_max: 1.0,
+            y_min: 0.0,
+            y_max: 2.0,
+            nx,
+            ny,
+        }
+    }
+
+    #[test]
+    fn test_slopes_on_grid() {
+        let data = slope_field_data("x - y", "x", "y", &grid(5, 3)).unwrap();
+        assert_eq!(data.x, vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(data.y, vec![0.0, 1.0, 2.0]);
+        assert_eq!(data.slopes.len(), 3);
+        for (j, row) in data.slopes.iter().enumerate() {
+            assert_eq!(row.len(), 5);
+            for (i, slope) in row.iter().enumerate() {
+                assert!((slope.unwrap() - (data.x[i] - data.y[j])).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_directions_are_unit_vectors_along_slope() {
+        let data = slope_field_data("x y", "x", "y", &grid(3, 3)).unwrap();
+        for (slopes, directions) in data.slopes.iter().zip(&data.directions) {
+            for (slope, direction) in slopes.iter().zip(directions) {
+                let (m, (dx, dy)) = (slope.unwrap(), direction.unwrap());
+                assert!((dx.hypot(dy) - 1.0).abs() < 1e-12);
+                assert!(dx > 0.0 && (dy / dx - m).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_undefined_slopes_are_null() {
+        let data = slope_field_data("\\frac{1}{y}", "x", "y", &grid(2, 3)).unwrap();
+        assert_eq!(data.slopes[0], vec![None, None]);
+        assert_eq!(data.directions[0], vec![None, None]);
+        assert_eq!(data.slopes[1], vec![Some(1.0), Some(1.0)]);
+    }
+
+    #[test]
+    fn test_rejects_bad_grid() {
+        assert!(slope_field_data("y", "x", "y", &grid(1, 3)).is_err());
+        let mut flipped = grid(3, 3);
+        flipped.y_max = -1.0;
+        assert!(slope_field_data("y", "x", "y", &flipped).is_err());
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let json = slope_field_js(
+            "\\sin(t) - u",
+            "t",
+            "u",
+            r#"{"x_min":0,"x_max":3,"y_min":-1,"y_max":1,"nx":4,"ny":2}"#,
+        )
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["x"].as_array().unwrap().len(), 4);
+        assert_eq!(value["slopes"].as_array().unwrap().len(), 2);
+        assert_eq!(value["directions"][0][0].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod asciimath_tests {
+    use arithma::{parse_asciimath, parse_latex_raw, Node};
+
+    fn same_tree(asciimath: &str, latex: &str) {
+        assert_eq!(
+            parse_asciimath(asciimath).unwrap(),
+            parse_latex_raw(latex).unwrap(),
+            "{asciimath} vs {latex}"
+        );
+    }
+
+    #[test]
+    fn fractions_take_simple_expressions() {
+        same_tree("(x+1)/(x-1)", "\\frac{x+1}{x-1}");
+        same_tree("1/2 x", "\\frac{1}{2} x");
+        same_tree("x+1/2", "x + \\frac{1}{2}");
+        same_tree("frac{a}{b}", "\\frac{a}{b}");
+        same_tree("a-:b", "\\frac{a}{b}");
+    }
+
+    #[test]
+    fn roots() {
+        same_tree("sqrt x", "\\sqrt{x}");
+        same_tree("sqrt(x+1)", "\\sqrt{x+1}");
+        same_tree("sqrt x^2", "\\sqrt{x^2}");
+        same_tree("root(3)(x)", "\\sqrt[3]{x}");
+    }
+
+    #[test]
+    fn functions_and_powers() {
+        same_tree("sin x^2", "\\sin(x^2)");
+        same_tree("sin(x)^2", "\\sin(x)^{2}");
+        same_tree("sin^2 x", "\\sin(x)^{2}");
+        same_tree("log_2 x", "\\log_{2}(x)");
+        same_tree("max(a,b)", "\\max(a,b)");
+        same_tree("e^(x+1) + x^-1", "e^{x+1} + x^{-1}");
+        same_tree("2^3^4", "2^{3^4}");
+        same_tree("-x^2 - 2x", "-x^2 - 2x");
+    }
+
+    #[test]
+    fn symbols_and_brackets() {
+        same_tree("2pi r + alpha beta", "2 \\pi r + \\alpha \\beta");
+        same_tree("x_1 + theta_2", "x_1 + \\theta_2");
+        same_tree("3xx4 + 3**4", "3 \\times 4 + 3 \\cdot 4");
+        same_tree("2|x-1| + n!", "2|x-1| + n!");
+        same_tree("|__x__| + |~y~|", "\\lfloor x \\rfloor + \\lceil y \\rceil");
+        same_tree(
+            "((1,2),(3,4))",
+            "\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}",
+        );
+        same_tree(
+            "[[1,2],[3,4]]",
+            "\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}",
+        );
+        same_tree("x^2 + 2x + 1 = 0", "x^2+2x+1=0");
+        same_tree("x <= 2", "x \\leq 2");
+        // Unknown words are runs of letters, as AsciiMath renders them
+        assert_eq!(
+            parse_asciimath("xy").unwrap(),
+            Node::Multiply(
+                Box::new(Node::Variable("x".into())),
+                Box::new(Node::Variable("y".into()))
+            )
+        );
+    }
+
+    #[test]
+    fn operators() {
+        same_tree("sum_(k=1)^n k^2", "\\sum_{k=1}^{n} k^2");
+        same_tree("prod_(i=1)^n i + 1", "\\prod_{i=1}^{n} i + 1");
+        same_tree("int_0^1 x^2 + 1 dx", "\\int_0^1 x^2 + 1 dx");
+        same_tree("int x dx", "\\int x dx");
+        same_tree(
+            "lim_(x->0) sin(x)/x",
+            "\\lim_{x \\to 0} \\frac{\\sin(x)}{x}",
+        );
+        same_tree("lim_(x->0^+) 1/x", "\\lim_{x \\to 0^+} \\frac{1}{x}");
+        same_tree("lim_(x->oo) 1/x", "\\lim_{x \\to \\infty} \\frac{1}{x}");
+        same_tree("d/dx x^2", "\\frac{d}{dx} x^2");
+    }
+
+    #[test]
+    fn errors() {
+        for (input, message) in [
+            ("(a,b)", "A bracketed list is only allowed as arguments"),
+            ("int x", "Expected the differential, as in int x dx"),
+            ("sum_k^n k", "Expected the index, as in sum_(k=1)^n"),
+            ("((1,2),(3))", "Matrix rows differ in length"),
+            ("3 $", "Unknown character '$'"),
+        ] {
+            assert_eq!(parse_asciimath(input).unwrap_err().message, message);
+        }
+        assert_eq!(parse_asciimath("sin").unwrap_err().char_span(), Some(3..3));
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod determinant_notation_tests {
+    use arithma::{parse_latex, parse_latex_raw, Environment, Evaluator, Node};
+
+    fn simplified(latex: &str) -> String {
+        let env = Environment::new();
+        format!("{}", parse_latex(latex, &env).unwrap())
+    }
+
+    #[test]
+    fn vmatrix_is_determinant() {
+        assert_eq!(
+            simplified("\\begin{vmatrix} 1 & 2 \\\\ 3 & 4 \\end{vmatrix}"),
+            "-2"
+        );
+        assert_eq!(
+            simplified("\\begin{vmatrix} 1 & 2 & 3 \\\\ 4 & 5 & 6 \\\\ 7 & 8 & 10 \\end{vmatrix}"),
+            "-3"
+        );
+    }
+
+    #[test]
+    fn raw_parse_is_det_of_the_matrix() {
+        assert_eq!(
+            parse_latex_raw("\\begin{vmatrix} a & b \\\\ c & d \\end{vmatrix}").unwrap(),
+            parse_latex_raw("\\det\\begin{pmatrix} a & b \\\\ c & d \\end{pmatrix}").unwrap()
+        );
+        assert_eq!(
+            simplified("\\begin{vmatrix} a & b \\\\ c & d \\end{vmatrix}"),
+            simplified("a d - b c")
+        );
+        assert_eq!(simplified("\\begin{vmatrix} 5 \\end{vmatrix}"), "5");
+    }
+
+    #[test]
+    fn large_determinant_is_not_expanded_by_cofactors() {
+        // 10! cofactor terms would be hopeless; the parse must keep the
+        // matrix whole and leave the determinant to LU
+        let rows: Vec<String> = (0..10)
+            .map(|i| {
+                (0..10)
+                    .map(|j| {
+                        if i == j {
+                            "2".to_string()
+                        } else {
+                            ((i * j) % 3).to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" & ")
+            })
+            .collect();
+        let latex = format!(
+            "\\begin{{vmatrix}} {} \\end{{vmatrix}}",
+            rows.join(" \\\\ ")
+        );
+        let node = parse_latex_raw(&latex).unwrap();
+        match &node {
+            Node::Function(name, args) => {
+                assert_eq!(name, "det");
+                assert_eq!(args.len(), 1);
+                match &args[0] {
+                    Node::Matrix(entries) => {
+                        assert_eq!(entries.len(), 10);
+                        assert!(entries
+                            .iter()
+                            .all(|row| row.len() == 10
+                                && row.iter().all(|e| matches!(e, Node::Num(_)))));
+                    }
+                    other => panic!("expected a matrix argument, got {:?}", other),
+                }
+            }
+            other => panic!("expected det of a matrix, got {:?}", other),
+        }
+        let value = Evaluator::evaluate(&node, &Environment::new()).unwrap();
+        assert_eq!(value, -320.0);
+        assert_eq!(simplified(&latex), "-320");
+    }
+
+    #[test]
+    fn bars_around_matrix_are_determinant_not_abs() {
+        for latex in [
+            "|\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}|",
+            "\\left|\\begin{bmatrix} 1 & 2 \\\\ 3 & 4 \\end{bmatrix}\\right|",
+        ] {
+            assert_eq!(simplified(latex), "-2", "{}", latex);
+        }
+    }
+
+    #[test]
+    fn determinant_composes_with_surrounding_expression() {
+        assert_eq!(
+            simplified("2\\begin{vmatrix} x & 1 \\\\ 1 & x \\end{vmatrix} + 1"),
+            "2x^{2} - 1"
+        );
+        // Signs and bars inside entries keep their usual meaning.
+        let node = parse_latex_raw("\\begin{vmatrix} -1 & 0 \\\\ 0 & |x| \\end{vmatrix}").unwrap();
+        let mut env = Environment::new();
+        env.set("x", -3.0);
+        assert_eq!(Evaluator::evaluate(&node, &env).unwrap(), -3.0);
+    }
+
+    #[test]
+    fn det_of_matrix_expression() {
+        assert_eq!(
+            simplified("\\det \\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}"),
+            "-2"
+        );
+        assert_eq!(
+            simplified("\\det\\left(2\\begin{pmatrix} a & b \\\\ c & d \\end{pmatrix}\\right)"),
+            simplified("4(a d - b c)")
+        );
+        let node =
+            parse_latex_raw("\\det \\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}^{T} + 1")
+                .unwrap();
+        assert_eq!(
+            Evaluator::evaluate(&node, &Environment::new()).unwrap(),
+            -1.0
+        );
+    }
+
+    #[test]
+    fn non_determinant_matrices_are_rejected() {
+        let env = Environment::new();
+        // Ragged rows are not a matrix.
+        assert!(parse_latex("\\begin{pmatrix} 1 & 2 \\\\ 3 \\end{pmatrix}", &env).is_err());
+        // Non-square determinant.
+        assert!(parse_latex("\\begin{vmatrix} 1 & 2 \\end{vmatrix}", &env).is_err());
+        // Mismatched environment names.
+        assert!(parse_latex("\\begin{vmatrix} 1 \\end{pmatrix}", &env).is_err());
+        // Vmatrix denotes a norm, not a determinant.
+        assert!(parse_latex("\\begin{Vmatrix} 1 \\end{Vmatrix}", &env).is_err());
+    }
+}
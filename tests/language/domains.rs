@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod domain_policy_tests {
+    use arithma::functions::{call_function, call_function_with_policy};
+    use arithma::{
+        parse_latex_raw, principal_branch, DomainPolicy, Environment, Evaluator, ExactNum,
+    };
+
+    fn eval(latex: &str, env: &Environment) -> Result<f64, String> {
+        Ok(Evaluator::evaluate(&parse_latex_raw(latex).unwrap(), env)?)
+    }
+
+    fn strict() -> Environment {
+        let mut env = Environment::new();
+        env.set_domain_policy(DomainPolicy::Error);
+        env
+    }
+
+    #[test]
+    fn test_default_policy_gives_nan_outside_the_domain() {
+        let env = Environment::new();
+        assert_eq!(env.domain_policy(), DomainPolicy::Nan);
+        for latex in [
+            "\\arcsin(2)",
+            "\\arccos(-1.5)",
+            "\\ln(-1)",
+            "\\ln(0)",
+            "\\log(-10)",
+            "\\arccosh(0)",
+            "\\arctanh(1)",
+        ] {
+            assert!(eval(latex, &env).unwrap().is_nan(), "{}", latex);
+        }
+    }
+
+    #[test]
+    fn test_error_policy_names_the_domain() {
+        let env = strict();
+        let err = eval("\\arcsin(2)", &env).unwrap_err();
+        assert_eq!(
+            err,
+            "\\arcsin(2) is undefined: its principal domain is [-1, 1]"
+        );
+        let err = eval("1 + \\ln(-1)", &env).unwrap_err();
+        assert!(err.contains("(0, ∞)"), "{}", err);
+        let err = eval("\\asin(3)", &env).unwrap_err();
+        assert!(err.starts_with("\\arcsin"), "{}", err);
+    }
+
+    #[test]
+    fn test_in_domain_values_are_unaffected() {
+        let env = strict();
+        let v = eval("\\arcsin(1)", &env).unwrap();
+        assert!((v - std::f64::consts::FRAC_PI_2).abs() < 1e-15);
+        assert_eq!(eval("\\arccos(1)", &env).unwrap(), 0.0);
+        assert!((eval("\\arctan(10^6)", &env).unwrap() - std::f64::consts::FRAC_PI_2).abs() < 1e-5);
+        assert_eq!(eval("\\ln(1)", &env).unwrap(), 0.0);
+        assert_eq!(eval("\\arccosh(1)", &env).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_principal_branch_table() {
+        let arccos = principal_branch("arccos").unwrap();
+        assert_eq!((arccos.domain, arccos.range), ("[-1, 1]", "[0, π]"));
+        assert!(arccos.contains(-1.0) && !arccos.contains(1.0001));
+        assert_eq!(principal_branch("atanh").unwrap().name, "arctanh");
+        assert!(principal_branch("sin").is_none());
+        assert!(principal_branch("exp").is_none());
+    }
+
+    #[test]
+    fn test_registry_calls_follow_the_policy() {
+        let two = || vec![ExactNum::integer(2)];
+        assert!(call_function("arcsin", two()).unwrap().to_f64().is_nan());
+        assert!(call_function_with_policy("arcsin", two(), DomainPolicy::Error).is_err());
+        assert!(call_function_with_policy("ln", two(), DomainPolicy::Error).is_ok());
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod format_tests {
+    use arithma::{parse_latex_raw, ExactNum, FormatOptions, MulSymbol, Node, NumberStyle};
+
+    fn plain(latex: &str) -> String {
+        parse_latex_raw(latex).unwrap().to_plain_string()
+    }
+
+    #[test]
+    fn plain_infix() {
+        assert_eq!(plain("3x^2 + 2x - 1"), "3*x^2 + 2*x - 1");
+        assert_eq!(plain("\\frac{x+1}{x-1}"), "(x + 1)/(x - 1)");
+        assert_eq!(plain("\\sin(x)^{2} + \\cos{x}"), "sin(x)^2 + cos(x)");
+        assert_eq!(plain("\\sqrt{x} \\cdot |y|"), "sqrt(x)*abs(y)");
+        assert_eq!(plain("x^{-1} + 2^{3^{4}}"), "x^(-1) + 2^(3^4)");
+        assert_eq!(plain("a - (b - c)"), "a - (b - c)");
+        assert_eq!(plain("\\pi r^2 + \\alpha"), "pi*r^2 + alpha");
+        assert_eq!(plain("x = -\\sqrt{2}"), "x = -sqrt(2)");
+        assert_eq!(plain("x \\geq 2 \\land x < 5"), "x >= 2 and x < 5");
+    }
+
+    #[test]
+    fn plain_notation_as_calls() {
+        assert_eq!(plain("\\sum_{k=1}^{n} k^2"), "sum(k^2, k, 1, n)");
+        assert_eq!(plain("\\int_0^1 x^2 dx"), "integrate(x^2, x, 0, 1)");
+        assert_eq!(
+            plain("\\lim_{x \\to \\infty} \\frac{1}{x}"),
+            "limit(1/x, x, inf)"
+        );
+        assert_eq!(
+            plain("\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}"),
+            "[[1, 2], [3, 4]]"
+        );
+    }
+
+    #[test]
+    fn plain_rational_literals() {
+        let half = Node::Num(ExactNum::rational(1, 2));
+        let power = Node::Power(Box::new(half.clone()), Box::new(Node::Variable("n".into())));
+        assert_eq!(power.to_plain_string(), "(1/2)^n");
+        let product = Node::Multiply(Box::new(Node::Variable("x".into())), Box::new(half));
+        assert_eq!(product.to_plain_string(), "x*(1/2)");
+    }
+
+    #[test]
+    fn decimal_numbers() {
+        let options = FormatOptions {
+            numbers: NumberStyle::Decimal,
+            precision: Some(4),
+            ..FormatOptions::default()
+        };
+        let third = Node::Multiply(
+            Box::new(Node::Num(ExactNum::rational(1, 3))),
+            Box::new(Node::Variable("x".into())),
+        );
+        assert_eq!(third.to_latex_with(&options), "0.3333 \\cdot x");
+        assert_eq!(third.to_plain_string_with(&options), "0.3333*x");
+        // `\frac{2}{3}` parses as a quotient of integers
+        let quotient = parse_latex_raw("\\frac{2}{3} + y").unwrap();
+        assert_eq!(quotient.to_latex_with(&options), "0.6667 + y");
+        assert_eq!(quotient.to_latex(), "\\frac{2}{3} + y");
+    }
+
+    #[test]
+    fn precision_rounds_floats_only() {
+        let options = FormatOptions {
+            precision: Some(3),
+            ..FormatOptions::default()
+        };
+        let expr = parse_latex_raw("1.23456 x + \\frac{1}{7}").unwrap();
+        assert_eq!(expr.to_latex_with(&options), "1.23 \\cdot x + \\frac{1}{7}");
+        let pi = Node::Num(ExactNum::Float(std::f64::consts::PI));
+        assert_eq!(pi.to_plain_string_with(&options), "pi");
+    }
+
+    #[test]
+    fn multiplication_sign() {
+        let expr = parse_latex_raw("a b + 2x").unwrap();
+        let with = |multiplication| FormatOptions {
+            multiplication,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            expr.to_latex_with(&with(MulSymbol::Times)),
+            "a \\times b + 2x"
+        );
+        assert_eq!(
+            expr.to_plain_string_with(&with(MulSymbol::Cdot)),
+            "a·b + 2·x"
+        );
+        // Any sign parses back to the same tree
+        for sign in [MulSymbol::Cdot, MulSymbol::Times, MulSymbol::Asterisk] {
+            let latex = expr.to_latex_with(&with(sign));
+            assert_eq!(parse_latex_raw(&latex).unwrap(), expr, "{latex}");
+        }
+    }
+}
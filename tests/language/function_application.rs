@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod function_application_tests {
+    use arithma::{
+        parse_latex, parse_latex_raw, parse_latex_raw_with_env, Environment, Evaluator, Node,
+    };
+
+    fn env_with_functions() -> Environment {
+        let mut env = Environment::new();
+        env.define_function("f", &["x"], parse_latex_raw("x^2 + 1").unwrap())
+            .unwrap();
+        env.define_function("g", &["a", "b"], parse_latex_raw("a - b").unwrap())
+            .unwrap();
+        env
+    }
+
+    fn eval(latex: &str, env: &Environment) -> f64 {
+        let expr = parse_latex_raw_with_env(latex, env)
+            .unwrap_or_else(|e| panic!("Failed to parse {}: {}", latex, e));
+        Evaluator::evaluate(&expr, env).unwrap()
+    }
+
+    fn var(name: &str) -> Node {
+        Node::Variable(name.to_string())
+    }
+
+    #[test]
+    fn test_defined_function_parses_as_application() {
+        let env = env_with_functions();
+        assert_eq!(
+            parse_latex_raw_with_env("f(x)", &env).unwrap(),
+            Node::Function("f".to_string(), vec![var("x")])
+        );
+        assert_eq!(eval("f(3)", &env), 10.0);
+        assert_eq!(eval("f\\left(2\\right)", &env), 5.0);
+        assert_eq!(eval("2f(1) + 1", &env), 5.0);
+    }
+
+    #[test]
+    fn test_undefined_identifier_keeps_implicit_multiplication() {
+        let env = env_with_functions();
+        let expected = Node::Multiply(Box::new(var("y")), Box::new(var("x")));
+        assert_eq!(parse_latex_raw_with_env("y(x)", &env).unwrap(), expected);
+        // Without the environment, f(x) is still a product
+        assert_eq!(
+            parse_latex_raw("f(x)").unwrap(),
+            Node::Multiply(Box::new(var("f")), Box::new(var("x")))
+        );
+    }
+
+    #[test]
+    fn test_multiple_and_nested_arguments() {
+        let env = env_with_functions();
+        assert_eq!(eval("g(5, 2)", &env), 3.0);
+        assert_eq!(eval("f(f(2))", &env), 26.0);
+        assert_eq!(eval("g(f(1), f(0))", &env), 1.0);
+        assert_eq!(eval("\\sum_{k=1}^{3} f(k)", &env), 17.0);
+    }
+
+    #[test]
+    fn test_application_round_trips_through_display() {
+        let env = env_with_functions();
+        let expr = parse_latex("g(f(x), 1)", &env).unwrap();
+        assert_eq!(expr.to_string(), "g(f(x), 1)");
+        assert_eq!(parse_latex(&expr.to_string(), &env).unwrap(), expr);
+    }
+
+    #[test]
+    fn test_ambiguity_diagnostics() {
+        let env = env_with_functions();
+        let err = parse_latex_raw_with_env("f + 1", &env)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("apply it with parentheses"), "{}", err);
+
+        let err = parse_latex_raw_with_env("g(1)", &env)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("g takes 2 arguments but 1 was given"),
+            "{}",
+            err
+        );
+
+        let err = parse_latex_raw_with_env("af(x)", &env)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("a \\cdot f(…)"), "{}", err);
+
+        let err = parse_latex_raw_with_env("f()", &env)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Empty argument"), "{}", err);
+    }
+
+    #[test]
+    fn test_define_function_rejects_bad_definitions() {
+        let mut env = Environment::new();
+        let body = parse_latex_raw("x").unwrap();
+        assert!(env.define_function("sin", &["x"], body.clone()).is_err());
+        assert!(env.define_function("h", &[], body.clone()).is_err());
+        assert!(env.define_function("h", &["x", "x"], body).is_err());
+        let recursive = Node::Function("h".to_string(), vec![var("x")]);
+        assert!(env.define_function("h", &["x"], recursive).is_err());
+    }
+
+    #[test]
+    fn test_operatorname_parses_as_a_call() {
+        let foo = |args: Vec<Node>| Node::Function("foo".to_string(), args);
+        assert_eq!(
+            parse_latex_raw("\\operatorname{foo}(x)").unwrap(),
+            foo(vec![var("x")])
+        );
+        assert_eq!(
+            parse_latex_raw("\\operatorname{foo}(x, 2)").unwrap(),
+            foo(vec![var("x"), Node::Num(arithma::ExactNum::integer(2))])
+        );
+        // A built-in name is the built-in function
+        assert_eq!(
+            parse_latex_raw("\\operatorname{sin}(x)").unwrap(),
+            parse_latex_raw("\\sin(x)").unwrap()
+        );
+        assert!(parse_latex_raw("\\operatorname{foo}").is_err());
+
+        let expr = parse_latex_raw("2\\operatorname{area}(r) + 1").unwrap();
+        assert_eq!(expr.to_string(), "2\\operatorname{area}(r) + 1");
+        assert_eq!(parse_latex_raw(&expr.to_string()).unwrap(), expr);
+    }
+
+    #[test]
+    fn test_operatorname_resolves_at_evaluation() {
+        let expr = parse_latex_raw("\\operatorname{area}(3)").unwrap();
+        let error = Evaluator::evaluate(&expr, &Environment::new()).unwrap_err();
+        assert!(error.to_string().contains("Unknown function 'area'"));
+        assert!(Evaluator::compile(&expr, &[]).is_err());
+
+        let mut env = Environment::new();
+        env.define_function("area", &["r"], parse_latex_raw("\\pi r^2").unwrap())
+            .unwrap();
+        assert_eq!(
+            Evaluator::evaluate(&expr, &env).unwrap(),
+            9.0 * std::f64::consts::PI
+        );
+        assert_eq!(eval("\\operatorname{area}(1)", &env), std::f64::consts::PI);
+        assert!(parse_latex_raw_with_env("\\operatorname{area}(1, 2)", &env).is_err());
+    }
+
+    /// `\\operatorname{sinc}(x) = \\sin(x)/x`, 1 at 0.
+    struct Sinc;
+
+    impl arithma::functions::FunctionHandler for Sinc {
+        fn call(&self, args: Vec<arithma::ExactNum>) -> Result<arithma::ExactNum, String> {
+            let x = args[0].to_f64();
+            let y = if x == 0.0 { 1.0 } else { x.sin() / x };
+            Ok(arithma::ExactNum::from_f64(y))
+        }
+
+        fn get_arg_count(&self) -> Option<usize> {
+            Some(1)
+        }
+    }
+
+    #[test]
+    fn test_registered_function_parses_and_evaluates() {
+        let mut env = Environment::new();
+        env.register_function("sinc", Box::new(Sinc)).unwrap();
+        assert!(env.register_function("sin", Box::new(Sinc)).is_err());
+        assert!(env.register_function("x2", Box::new(Sinc)).is_err());
+
+        assert_eq!(eval("sinc(0)", &env), 1.0);
+        assert_eq!(
+            eval("2 \\operatorname{sinc}(\\pi)", &env),
+            2.0 * std::f64::consts::PI.sin() / std::f64::consts::PI
+        );
+        assert!(parse_latex_raw_with_env("sinc(1, 2)", &env).is_err());
+        // Without the parentheses it is still a variable
+        assert_eq!(parse_latex_raw_with_env("sinc", &env).unwrap(), var("sinc"));
+
+        // Child scopes see the registration; a fresh environment does not
+        assert_eq!(eval("sinc(0)", &env.child()), 1.0);
+        let call = parse_latex_raw("\\operatorname{sinc}(0)").unwrap();
+        assert!(Evaluator::evaluate(&call, &Environment::new()).is_err());
+        assert_eq!(
+            env.registered_function_names().collect::<Vec<_>>(),
+            ["sinc"]
+        );
+    }
+}
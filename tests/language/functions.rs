@@ -9,7 +9,7 @@ mod function_tests {
         // Tokenize and parse the input
         let tokens = tokenizer.tokenize(); // Call the instance method on tokenizer
         let parsed_expr = build_expression_tree(tokens)?;
-        Evaluator::evaluate(&parsed_expr, env)
+        Ok(Evaluator::evaluate(&parsed_expr, env)?)
     }
 
     fn simplify_expression(latex: &str) -> Result<Node, String> {
@@ -17,7 +17,7 @@ mod function_tests {
         let tokens = tokenizer.tokenize();
         let parsed = build_expression_tree(tokens)?;
         let env = Environment::new();
-        Evaluator::simplify(&parsed, &env)
+        Ok(Evaluator::simplify(&parsed, &env)?)
     }
 
     // Helper function to evaluate LaTeX expression and return the result
@@ -30,7 +30,7 @@ mod function_tests {
         let env = Environment::new();
         let mut tokenizer = Tokenizer::new(latex);
         let parsed = build_expression_tree(tokenizer.tokenize())?;
-        Evaluator::evaluate_exact(&parsed, &env)
+        Ok(Evaluator::evaluate_exact(&parsed, &env)?)
     }
 
     // Integer arithmetic
@@ -115,6 +115,32 @@ mod function_tests {
         );
     }
 
+    #[test]
+    fn test_ncr_and_npr_functions() {
+        assert_eq!(evaluate_expression("nCr(5, 2)").unwrap(), 10.0);
+        assert_eq!(evaluate_expression("\\nPr(5, 2)").unwrap(), 20.0);
+        assert_eq!(evaluate_expression("nPr(5, 0)").unwrap(), 1.0);
+        assert_eq!(evaluate_expression("nPr(3, 5)").unwrap(), 0.0);
+        assert!(evaluate_expression("nPr(-1, 2)")
+            .unwrap_err()
+            .contains("non-negative"));
+        // Exact well past 2^53: C(50, 25) and P(30, 15) = 30!/15!
+        let exact = |latex: &str| {
+            evaluate_exact_expression(latex)
+                .unwrap()
+                .to_rational()
+                .unwrap()
+                .numer()
+                .to_string()
+        };
+        assert_eq!(exact("nCr(50, 25)"), "126410606437752");
+        assert_eq!(exact("nPr(30, 15)"), "202843204931727360000");
+        assert_eq!(
+            simplify_expression("nPr(4, 2)").unwrap(),
+            Node::Num(ExactNum::integer(12))
+        );
+    }
+
     #[test]
     fn test_binom_simplify() {
         let simplified = simplify_expression("\\binom{2+3}{2}").unwrap();
@@ -269,9 +295,9 @@ mod function_tests {
 
     #[test]
     fn test_factorial_non_integer() {
-        assert!(evaluate_expression("\\factorial{5.5}")
-            .unwrap_err()
-            .contains("non-negative"));
+        // 5.5! = Γ(6.5)
+        let value = evaluate_expression("\\factorial{5.5}").unwrap();
+        assert!((value - 287.885_277_815_044_3).abs() < 1e-10, "{}", value);
     }
 
     #[test]
@@ -345,11 +371,55 @@ mod function_tests {
         );
     }
 
+    #[test]
+    fn test_floor_of_integer_expression_folds() {
+        use arithma::assumptions::{Assumption, Assumptions};
+        let mut assumptions = Assumptions::new();
+        assumptions.assume("n", Assumption::Integer);
+        let env = Environment::with_assumptions(assumptions);
+        let mut tokenizer = Tokenizer::new("\\lfloor 2n + 1 \\rfloor");
+        let parsed = build_expression_tree(tokenizer.tokenize()).unwrap();
+        let simplified = Evaluator::simplify(&parsed, &env).unwrap();
+        assert_eq!(simplified.to_string(), "2n + 1");
+    }
+
+    #[test]
+    fn test_bmod_operator() {
+        assert_eq!(evaluate_expression("7 \\bmod 3").unwrap(), 1.0);
+        // The result takes the sign of the divisor
+        assert_eq!(evaluate_expression("-7 \\bmod 3").unwrap(), 2.0);
+        assert_eq!(evaluate_expression("7 \\bmod -3").unwrap(), -2.0);
+        assert_eq!(evaluate_expression("\\mod(7, 3)").unwrap(), 1.0);
+        assert_eq!(
+            evaluate_exact_expression("\\frac{7}{2} \\bmod 1").unwrap(),
+            ExactNum::rational(1, 2)
+        );
+        let err = evaluate_expression("5 \\bmod 0").unwrap_err();
+        assert!(err.contains("by zero"), "{}", err);
+    }
+
+    #[test]
+    fn test_bmod_display_round_trip() {
+        let mut tokenizer = Tokenizer::new("(a + 1) \\bmod n");
+        let parsed = build_expression_tree(tokenizer.tokenize()).unwrap();
+        let shown = parsed.to_string();
+        let mut tokenizer = Tokenizer::new(&shown);
+        let reparsed = build_expression_tree(tokenizer.tokenize()).unwrap();
+        assert_eq!(reparsed, parsed, "{}", shown);
+    }
+
+    #[test]
+    fn test_sign_function() {
+        assert_eq!(evaluate_expression("\\sgn(-3)").unwrap(), -1.0);
+        assert_eq!(evaluate_expression("\\sign(0)").unwrap(), 0.0);
+        assert_eq!(evaluate_expression("\\sgn(2.5)").unwrap(), 1.0);
+    }
+
     #[test]
     fn test_function_arg_validation() {
         // Test sin function with incorrect number of arguments
         let result = evaluate_expression("\\sin{0, 1}").unwrap_err();
-        assert!(result.contains("The expression did not resolve into a single tree."));
+        assert!(result.contains("Too many arguments for function sin"));
 
         // Test log function with missing arguments
         let result = evaluate_expression("\\log{}").unwrap_err();
@@ -532,4 +602,37 @@ mod function_tests {
         let result = evaluate_expression("\\limsup{1, 3, 2, 5}").unwrap(); // limsup(1, 3, 2, 5) = 5
         assert_eq!(result, 5.0);
     }
+
+    #[test]
+    fn test_number_theory_functions_simplify_exactly() {
+        let simplified = |latex: &str| simplify_expression(latex).unwrap().to_string();
+        assert_eq!(simplified("\\gcd(-12, 18)"), "6");
+        assert_eq!(simplified("\\operatorname{lcm}(4, 6)"), "12");
+        assert_eq!(simplified("\\operatorname{isprime}(97)"), "1");
+        assert_eq!(simplified("isprime(91)"), "0");
+        assert_eq!(simplified("powmod(2, 100, 7)"), "2");
+        assert_eq!(simplified("2^{100} \\bmod 7"), "2");
+        assert_eq!(simplified("2^{100} mod 7"), "2");
+        assert_eq!(simplified("factorint(360)"), "2^{3} \\cdot 3^{2} \\cdot 5");
+        // Symbolic arguments stay as written, and read back
+        assert_eq!(
+            simplified("\\operatorname{lcm}(x, 4)"),
+            "\\operatorname{lcm}(x, 4)"
+        );
+        assert_eq!(simplified("isprime(n)"), "\\operatorname{isprime}(n)");
+        assert_eq!(
+            simplify_expression("\\operatorname{isprime}(n)").unwrap(),
+            simplify_expression("isprime(n)").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_number_theory_functions_evaluate() {
+        assert_eq!(evaluate_expression("isprime(7) + isprime(8)").unwrap(), 1.0);
+        assert_eq!(evaluate_expression("powmod(3, 200, 13)").unwrap(), 9.0);
+        assert_eq!(evaluate_expression("factorint(360)").unwrap(), 360.0);
+        assert_eq!(evaluate_expression("17 mod 5").unwrap(), 2.0);
+        assert!(evaluate_expression("powmod(2, 3, 0)").is_err());
+        assert!(evaluate_expression("isprime(2.5)").is_err());
+    }
 }
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod named_identifier_tests {
+    use arithma::{parse_latex_raw, Environment, Evaluator, Node};
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap_or_else(|e| panic!("Failed to parse {}: {}", latex, e))
+    }
+
+    fn var(name: &str) -> Node {
+        Node::Variable(name.to_string())
+    }
+
+    #[test]
+    fn test_text_and_mathit_names() {
+        assert_eq!(parse("\\text{rate}"), var("rate"));
+        assert_eq!(parse("\\mathit{speed}"), var("speed"));
+        assert_eq!(parse("\\mathrm{mass}"), var("mass"));
+        assert_eq!(parse("\\textit{t}"), var("t"));
+    }
+
+    #[test]
+    fn test_mathrm_e_is_still_euler() {
+        assert_eq!(parse("\\mathrm{e}"), var("e"));
+        let value = Evaluator::evaluate(&parse("\\mathrm{e}^{1}"), &Environment::new()).unwrap();
+        assert!((value - std::f64::consts::E).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_named_identifiers_multiply_implicitly() {
+        let expected = Node::Multiply(Box::new(var("speed")), Box::new(var("time")));
+        assert_eq!(parse("\\mathit{speed}\\mathit{time}"), expected);
+        assert_eq!(parse("\\mathit{speed} \\cdot \\mathit{time}"), expected);
+        assert_eq!(
+            parse("2\\text{rate}"),
+            Node::Multiply(
+                Box::new(Node::Num(arithma::ExactNum::integer(2))),
+                Box::new(var("rate"))
+            )
+        );
+    }
+
+    #[test]
+    fn test_named_identifiers_evaluate_from_environment() {
+        let mut env = Environment::new();
+        env.set("principal", 1000.0);
+        env.set("rate", 0.05);
+        let expr = parse("\\text{principal} \\cdot (1 + \\text{rate})^{2}");
+        let value = Evaluator::evaluate(&expr, &env).unwrap();
+        assert!((value - 1102.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_printed_name_reparses() {
+        let expr = parse("\\text{rate}^{2} + \\text{rate}");
+        assert_eq!(parse(&expr.to_string()), expr);
+    }
+
+    #[test]
+    fn test_invalid_names_are_errors() {
+        assert!(parse_latex_raw("\\text{rate 2}").is_err());
+        assert!(parse_latex_raw("\\text{}").is_err());
+        assert!(parse_latex_raw("\\mathit x").is_err());
+    }
+}
+
+#[cfg(test)]
+mod subscripted_identifier_tests {
+    use arithma::{differentiate, parse_latex_raw, Environment, Evaluator, Node};
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap_or_else(|e| panic!("Failed to parse {}: {}", latex, e))
+    }
+
+    fn var(name: &str) -> Node {
+        Node::Variable(name.to_string())
+    }
+
+    #[test]
+    fn test_subscript_spellings_name_one_variable() {
+        assert_eq!(parse("x_1"), var("x_1"));
+        assert_eq!(parse("x_{1}"), var("x_1"));
+        assert_eq!(parse("x_{12}"), var("x_12"));
+        assert_eq!(parse("v_{max}"), var("v_max"));
+        assert_eq!(parse("\\theta_0"), var("θ_0"));
+        assert_eq!(parse("\\alpha_{i}"), var("α_i"));
+        assert_eq!(parse("F_{\\mu}"), var("F_μ"));
+        assert_eq!(parse("\\text{rate}_1"), var("rate_1"));
+    }
+
+    #[test]
+    fn test_subscripted_names_compose() {
+        assert_eq!(
+            parse("x_1^2"),
+            Node::Power(
+                Box::new(var("x_1")),
+                Box::new(Node::Num(arithma::ExactNum::integer(2)))
+            )
+        );
+        assert_eq!(
+            parse("m_1 v_1"),
+            Node::Multiply(Box::new(var("m_1")), Box::new(var("v_1")))
+        );
+        assert_eq!(parse("2x_1y").to_string(), "2x_{1} \\cdot y");
+    }
+
+    #[test]
+    fn test_physics_expression_evaluates() {
+        let mut env = Environment::new();
+        env.set("v_0", 10.0);
+        env.set("θ_0", std::f64::consts::FRAC_PI_6);
+        env.set("t", 1.0);
+        let expr = parse("v_0 \\sin(\\theta_0) t - \\frac{1}{2} g t^2");
+        env.set("g", 10.0);
+        let value = Evaluator::evaluate(&expr, &env).unwrap();
+        assert!(value.abs() < 1e-12, "{}", value);
+    }
+
+    #[test]
+    fn test_printed_subscripts_reparse() {
+        for latex in ["x_1 + x_{2}", "\\theta_0^{2}", "\\frac{v_{max}}{\\omega_c}"] {
+            let expr = parse(latex);
+            assert_eq!(parse(&expr.to_latex()), expr, "{}", expr.to_latex());
+            assert_eq!(parse(&expr.to_string()), expr, "{}", expr);
+        }
+        assert_eq!(parse("\\theta_0").to_latex(), "\\theta_{0}");
+    }
+
+    #[test]
+    fn test_subscripted_variable_is_distinct_from_its_base() {
+        let d = differentiate(&parse("x_1^2 + x x_1"), "x_1").unwrap();
+        let mut env = Environment::new();
+        env.set("x", 3.0);
+        env.set("x_1", 2.0);
+        assert_eq!(Evaluator::evaluate(&d, &env).unwrap(), 7.0);
+        assert_eq!(
+            differentiate(&parse("x_1"), "x").unwrap(),
+            Node::Num(arithma::ExactNum::integer(0))
+        );
+    }
+
+    #[test]
+    fn test_expression_subscripts_are_errors() {
+        assert!(parse_latex_raw("a_{n+1}").is_err());
+        assert!(parse_latex_raw("x_").is_err());
+    }
+}
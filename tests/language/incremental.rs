@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod incremental_tests {
+    use arithma::{
+        parse_latex_raw, parse_latex_raw_with_options, IncrementalParser, ParserOptions,
+    };
+
+    const INPUTS: &[&str] = &[
+        r"\frac{x^2 + 1}{\sin(2x)} - 3.5y",
+        r"\begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}",
+        r"|x| + ||y - 1|| \cdot 2|z|",
+        r"\sum_{k=1}^{n} k^2 + \int_0^1 x \, dx",
+        r"0.1\overline{6} + 1.2\overline{3}x",
+        r"\sqrt[3]{\alpha_1} \vec{a} \times \vec{b} + x_{10}!",
+        r"\frac{d}{dx} \sin^{-1} x + \lim_{x \to 0} \frac{\sin x}{x}",
+        r"\left| \begin{pmatrix} 3 \\ 4 \end{pmatrix} \right| = 5",
+    ];
+
+    /// Typing each input one character at a time gives what a fresh parse
+    /// of every prefix gives.
+    #[test]
+    fn test_typing_matches_fresh_parse() {
+        let mut parser = IncrementalParser::new();
+        for input in INPUTS {
+            let mut typed = String::new();
+            for c in input.chars() {
+                typed.push(c);
+                assert_eq!(parser.parse(&typed), parse_latex_raw(&typed), "{}", typed);
+            }
+        }
+    }
+
+    /// Deleting from the end, and editing in the middle, do too.
+    #[test]
+    fn test_deleting_and_editing_match_fresh_parse() {
+        let mut parser = IncrementalParser::new();
+        for input in INPUTS {
+            let mut text = input.to_string();
+            while text.pop().is_some() {
+                assert_eq!(parser.parse(&text), parse_latex_raw(&text), "{}", text);
+            }
+            for (i, _) in input.char_indices().step_by(3) {
+                let edited = format!("{}2{}", &input[..i], &input[i..]);
+                assert_eq!(
+                    parser.parse(&edited),
+                    parse_latex_raw(&edited),
+                    "{}",
+                    edited
+                );
+                assert_eq!(parser.parse(input), parse_latex_raw(input), "{}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_appending_reuses_tokens() {
+        let rows: Vec<String> = (0..400).map(|i| format!("{}x", i)).collect();
+        let open = format!(r"\begin{{pmatrix}} {}", rows.join(r" \\ "));
+
+        let mut parser = IncrementalParser::new();
+        assert!(parser.parse(&open).is_err());
+        assert_eq!(parser.reused_tokens(), 0);
+        let before = parser.tokens().len();
+
+        let closed = format!(r"{} \end{{pmatrix}}", open);
+        assert_eq!(parser.parse(&closed), parse_latex_raw(&closed));
+        // All but `* x` of the last entry, which was read up to the end
+        assert_eq!(parser.reused_tokens(), before - 2);
+
+        // Same input: nothing is tokenized again
+        parser.parse(&closed).unwrap();
+        assert_eq!(parser.reused_tokens(), parser.tokens().len());
+    }
+
+    #[test]
+    fn test_whitespace_edit_keeps_tree() {
+        let mut parser = IncrementalParser::new();
+        let a = parser.parse("x^2 + 1").unwrap();
+        let b = parser.parse("x^2 + 1 ").unwrap();
+        assert_eq!(a, b);
+        // `1` was read up to the end of the input, so it is read again
+        assert_eq!(parser.reused_tokens(), parser.tokens().len() - 1);
+    }
+
+    #[test]
+    fn test_errors_report_the_current_input() {
+        let mut parser = IncrementalParser::new();
+        let err = parser.parse("3 + * 4").unwrap_err();
+        assert_eq!(err.span, Some(4..5));
+        let err = parser.parse("3 + * 4 + 55 * ").unwrap_err();
+        assert_eq!(Err(err), parse_latex_raw("3 + * 4 + 55 * "));
+        assert!(parser.parse("3 + 4").is_ok());
+
+        parser.reset();
+        assert_eq!(parser.source(), "");
+        assert_eq!(parser.reused_tokens(), 0);
+    }
+
+    #[test]
+    fn test_options_apply_to_every_parse() {
+        let options = ParserOptions {
+            max_tokens: 4,
+            ..ParserOptions::default()
+        };
+        let mut parser = IncrementalParser::with_options(options);
+        assert!(parser.parse("1 + 2").is_ok());
+        assert_eq!(
+            parser.parse("1 + 2 + 3"),
+            parse_latex_raw_with_options("1 + 2 + 3", &options)
+        );
+        assert!(parser.parse("1 + 2 + 3").is_err());
+    }
+}
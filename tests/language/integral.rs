@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod integral_notation_tests {
+    use arithma::{
+        differentiate, parse_latex, parse_latex_raw, substitute, Environment, Evaluator, Node,
+    };
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    fn value(latex: &str) -> f64 {
+        Evaluator::evaluate(&parse(latex), &Environment::new()).unwrap()
+    }
+
+    #[test]
+    fn test_definite_integral_parses_bounds_and_differential() {
+        match parse("\\int_0^1 x^2 \\, dx") {
+            Node::Integral(var, Some((lower, upper)), body) => {
+                assert_eq!(var, "x");
+                assert_eq!(lower.to_string(), "0");
+                assert_eq!(upper.to_string(), "1");
+                assert_eq!(body.to_string(), "x^{2}");
+            }
+            other => panic!("expected a definite integral, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_differential_spellings() {
+        for latex in [
+            "\\int x \\, dx",
+            "\\int x dx",
+            "\\int x \\,d x",
+            "\\int x \\mathrm{d}x",
+        ] {
+            match parse(latex) {
+                Node::Integral(var, None, _) => assert_eq!(var, "x", "{}", latex),
+                other => panic!("{}: expected an indefinite integral, got {}", latex, other),
+            }
+        }
+        assert!(
+            matches!(parse("\\int_0^{\\pi} \\sin(θ) \\, dθ"), Node::Integral(v, ..) if v == "θ")
+        );
+    }
+
+    #[test]
+    fn test_integral_composes_with_surrounding_operators() {
+        assert_eq!(value("2\\int_0^1 x \\, dx + 1"), 2.0);
+        assert_eq!(value("\\int_0^1 (x + 1) \\, dx"), 1.5);
+        // An empty integrand is 1
+        assert_eq!(value("\\int_2^5 dx"), 3.0);
+    }
+
+    #[test]
+    fn test_nested_integrals_pair_inner_differential_first() {
+        match parse("\\int_0^1 \\int_0^2 x y \\, dx \\, dy") {
+            Node::Integral(outer, _, body) => {
+                assert_eq!(outer, "y");
+                assert!(matches!(*body, Node::Integral(ref inner, ..) if inner == "x"));
+            }
+            other => panic!("expected nested integrals, got {}", other),
+        }
+        assert!((value("\\int_0^1 \\int_0^2 x y \\, dx \\, dy") - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_round_trips_through_latex_and_display() {
+        for latex in [
+            "\\int_0^1 x^2 \\, dx",
+            "\\int \\frac{1}{x} \\, dx",
+            "3 \\cdot \\int_{-1}^{2} e^{x} \\, dx - 1",
+        ] {
+            let expr = parse(latex);
+            assert_eq!(parse(&expr.to_latex()), expr, "{}", expr.to_latex());
+            assert_eq!(parse(&expr.to_string()), expr, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_simplify_resolves_elementary_integrals() {
+        let env = Environment::new();
+        let simplified = |latex: &str| parse_latex(latex, &env).unwrap().to_string();
+        assert_eq!(simplified("\\int_0^1 x^2 \\, dx"), "\\frac{1}{3}");
+        assert_eq!(simplified("\\int_{0}^{\\pi} \\sin(x) \\, dx"), "2");
+        assert_eq!(simplified("\\int \\frac{1}{x} \\, dx"), "\\ln(|x|)");
+        assert_eq!(
+            simplified("\\int_0^x t^2 \\, dt"),
+            "\\frac{1}{3} \\cdot x^{3}"
+        );
+    }
+
+    #[test]
+    fn test_unresolved_integral_stays_symbolic_and_evaluates_numerically() {
+        let expr = parse_latex("\\int_0^1 e^{-x^2} \\, dx", &Environment::new()).unwrap();
+        assert!(matches!(expr, Node::Integral(..)), "{}", expr);
+        let v = Evaluator::evaluate(&expr, &Environment::new()).unwrap();
+        assert!((v - 0.746_824_132_812_427).abs() < 1e-9, "{}", v);
+    }
+
+    #[test]
+    fn test_evaluation_reads_parameters_from_environment() {
+        let mut env = Environment::new();
+        env.set("a", 3.0);
+        let v = Evaluator::evaluate(&parse("\\int_0^1 a x^2 \\, dx"), &env).unwrap();
+        assert!((v - 1.0).abs() < 1e-12, "{}", v);
+    }
+
+    #[test]
+    fn test_improper_and_indefinite_integrals_do_not_evaluate() {
+        let env = Environment::new();
+        let err =
+            Evaluator::evaluate(&parse("\\int_{-1}^{1} \\frac{1}{x} \\, dx"), &env).unwrap_err();
+        assert!(err.to_string().contains("improper"), "{}", err);
+        let err = Evaluator::evaluate(&parse("\\int x \\, dx"), &env).unwrap_err();
+        assert!(err.to_string().contains("indefinite"), "{}", err);
+    }
+
+    #[test]
+    fn test_derivative_of_integral() {
+        // Fundamental theorem on the indefinite form
+        assert_eq!(
+            differentiate(&parse("\\int x^2 \\, dx"), "x").unwrap(),
+            parse("x^2")
+        );
+        // Leibniz rule with a variable upper bound and no antiderivative
+        let d = differentiate(&parse("\\int_0^{x} e^{-t^2} \\, dt"), "x").unwrap();
+        let mut env = Environment::new();
+        env.set("x", 0.5);
+        let v = Evaluator::evaluate(&d, &env).unwrap();
+        assert!((v - (-0.25f64).exp()).abs() < 1e-12, "{} = {}", d, v);
+    }
+
+    #[test]
+    fn test_definite_integral_binds_its_variable() {
+        let expr = parse("\\int_0^1 x \\cdot a \\, dx");
+        assert!(!expr.contains_variable("x"));
+        assert!(expr.contains_variable("a"));
+        let replaced = substitute(&expr, &[("x".to_string(), parse("5"))]).unwrap();
+        assert_eq!(replaced, expr);
+        let err = substitute(&expr, &[("a".to_string(), parse("x"))]).unwrap_err();
+        assert!(err.contains("capture"), "{}", err);
+    }
+
+    #[test]
+    fn test_malformed_integrals_are_errors() {
+        let err = parse_latex_raw("\\int x").unwrap_err();
+        assert!(err.message.contains("differential"), "{}", err);
+        let err = parse_latex_raw("\\int_0 x \\, dx").unwrap_err();
+        assert!(
+            err.message.contains("both a lower and an upper bound"),
+            "{}",
+            err
+        );
+        assert_eq!(err.span, Some(0..4));
+    }
+}
@@ -162,7 +162,7 @@ mod latex_parser_tests {
 
         // Tokenize and parse the input
         let parsed_expr = build_expression_tree(tokens)?;
-        Evaluator::evaluate(&parsed_expr, env)
+        Ok(Evaluator::evaluate(&parsed_expr, env)?)
     }
 
     // Helper function to evaluate LaTeX expression and return the result
@@ -178,7 +178,7 @@ mod latex_parser_tests {
             return Err(err);
         }
         let parsed = build_expression_tree(tokens)?;
-        Evaluator::evaluate_exact(&parsed, &Environment::new())
+        Ok(Evaluator::evaluate_exact(&parsed, &Environment::new())?)
     }
 
     fn approx_eq(a: f64, b: f64, epsilon: f64) -> bool {
@@ -479,3 +479,137 @@ mod latex_parser_tests {
         assert_eq!(result, -8.0);
     }
 }
+
+#[cfg(test)]
+mod to_latex_tests {
+    use arithma::{parse_latex_raw, Environment, ExactNum, Node};
+
+    fn print(latex: &str) -> String {
+        parse_latex_raw(latex).unwrap().to_latex()
+    }
+
+    fn assert_stable(latex: &str) {
+        let expr = parse_latex_raw(latex).unwrap();
+        let printed = expr.to_latex();
+        let reparsed = parse_latex_raw(&printed)
+            .unwrap_or_else(|e| panic!("'{}' printed as unparseable '{}': {}", latex, printed, e));
+        assert_eq!(reparsed, expr, "'{}' printed as '{}'", latex, printed);
+        assert_eq!(reparsed.to_latex(), printed);
+    }
+
+    #[test]
+    fn test_parentheses_only_where_needed() {
+        assert_eq!(print("x + y - z"), "x + y - z");
+        assert_eq!(print("a - (b + c)"), "a - \\left(b + c\\right)");
+        assert_eq!(print("(x+1)^{2}"), "\\left(x + 1\\right)^{2}");
+        assert_eq!(print("x^{y^{z}}"), "x^{y^{z}}");
+        assert_eq!(print("(x^{y})^{z}"), "\\left(x^{y}\\right)^{z}");
+        assert_eq!(print("-(x+1)"), "-\\left(x + 1\\right)");
+        assert_eq!(print("a + -x"), "a + \\left(-x\\right)");
+    }
+
+    #[test]
+    fn test_products_and_fractions() {
+        assert_eq!(print("2x^{3}"), "2x^{3}");
+        assert_eq!(print("-2x"), "-2x");
+        assert_eq!(print("x \\cdot 2"), "x \\cdot 2");
+        assert_eq!(
+            print("(a+b)(a-b)"),
+            "\\left(a + b\\right) \\cdot \\left(a - b\\right)"
+        );
+        assert_eq!(
+            print("\\frac{-b + \\sqrt{b^2-4ac}}{2a}"),
+            "\\frac{-b + \\sqrt{b^{2} - 4ac}}{2a}"
+        );
+        assert_eq!(
+            Node::Num(ExactNum::rational(-3, 4)).to_latex(),
+            "-\\frac{3}{4}"
+        );
+    }
+
+    #[test]
+    fn test_delimited_forms() {
+        assert_eq!(print("\\left|x-1\\right|"), "\\left|x - 1\\right|");
+        assert_eq!(print("\\lfloor x \\rfloor"), "\\lfloor x \\rfloor");
+        assert_eq!(print("\\sin(x)"), "\\sin\\left(x\\right)");
+        assert_eq!(print("\\sum_{k=1}^{n}{k^{2}}"), "\\sum_{k=1}^{n}{k^{2}}");
+        assert_eq!(print("(n+1)!"), "\\left(n + 1\\right)!");
+        assert_eq!(print("a \\geq b"), "a \\geq b");
+    }
+
+    #[test]
+    fn test_logical_connectives() {
+        assert_eq!(print("x > 0 && x < 1"), "x > 0 \\land x < 1");
+        assert_eq!(print("a || b \\land c"), "a \\lor b \\land c");
+        assert_eq!(
+            print("(a || b) \\land c"),
+            "\\left(a \\lor b\\right) \\land c"
+        );
+        assert_eq!(print("!(x > 0)"), "\\lnot x > 0");
+        assert_eq!(
+            print("\\lnot (a \\land b)"),
+            "\\lnot \\left(a \\land b\\right)"
+        );
+        for latex in [
+            "x > 0 \\land x < 10 \\lor x = 20",
+            "a \\land (b \\land c)",
+            "a \\lor (b \\lor c)",
+            "\\lnot \\lnot a",
+            "(\\lnot a) > 0",
+            "(a \\land b) + 1",
+        ] {
+            assert_stable(latex);
+            let expr = parse_latex_raw(latex).unwrap();
+            assert_eq!(
+                parse_latex_raw(&expr.to_string()).unwrap(),
+                expr,
+                "{}",
+                latex
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_print_parse_is_stable() {
+        for latex in [
+            "x + y - z",
+            "a - (b - c)",
+            "2 \\cdot -x",
+            "-x \\cdot y",
+            "\\frac{x+1}{x-1}",
+            "\\sin\\left(x\\right)^{2}",
+            "\\sqrt{x^2+1}",
+            "\\lfloor x/2 \\rfloor",
+            "\\lceil x \\rceil",
+            "2 \\cdot \\sum_{k=1}^{3}{k}",
+            "\\prod_{k=1}^{4}{k+1}",
+            "\\binom{n}{2}",
+            "x^2 = 4",
+            "(\\frac{1}{2})^{2}",
+            "x^{-1}",
+            "- -x",
+            "\\pi r^2",
+            "\\alpha + \\beta",
+            "0.5x",
+            "(-2)^{2}",
+            "-2^{2}",
+            "3!^{2}",
+            "|x|!",
+        ] {
+            assert_stable(latex);
+        }
+    }
+
+    #[test]
+    fn test_user_function_prints_without_backslash() {
+        let mut env = Environment::new();
+        env.define_function("f", &["x"], parse_latex_raw("x^2").unwrap())
+            .unwrap();
+        let expr = Node::Function("f".to_string(), vec![Node::Variable("t".to_string())]);
+        assert_eq!(expr.to_latex(), "f\\left(t\\right)");
+        assert_eq!(
+            arithma::parse_latex_raw_with_env(&expr.to_latex(), &env).unwrap(),
+            expr
+        );
+    }
+}
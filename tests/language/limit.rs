@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod limit_notation_tests {
+    use arithma::{
+        differentiate, parse_latex, parse_latex_raw, substitute, Environment, Evaluator,
+        LimitDirection, Node,
+    };
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    fn simplified(latex: &str) -> String {
+        parse_latex(latex, &Environment::new()).unwrap().to_string()
+    }
+
+    fn value(latex: &str) -> Result<f64, String> {
+        Ok(Evaluator::evaluate(&parse(latex), &Environment::new())?)
+    }
+
+    #[test]
+    fn test_limit_parses_variable_point_and_body() {
+        match parse("\\lim_{x \\to 0} \\frac{\\sin(x)}{x}") {
+            Node::Limit(var, point, LimitDirection::Both, body) => {
+                assert_eq!(var, "x");
+                assert_eq!(point.to_string(), "0");
+                assert_eq!(body.to_string(), "\\frac{\\sin(x)}{x}");
+            }
+            other => panic!("expected a limit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_one_sided_and_infinite_points() {
+        let direction = |latex: &str| match parse(latex) {
+            Node::Limit(_, _, direction, _) => direction,
+            other => panic!("{}: expected a limit, got {}", latex, other),
+        };
+        assert_eq!(direction("\\lim_{x \\to 0^+} x"), LimitDirection::Right);
+        assert_eq!(direction("\\lim_{x \\to 0^{+}} x"), LimitDirection::Right);
+        assert_eq!(direction("\\lim_{x \\to 0^-} x"), LimitDirection::Left);
+        assert_eq!(direction("\\lim_{x \\to -1^{-}} x"), LimitDirection::Left);
+        assert_eq!(
+            direction("\\lim_{x \\rightarrow \\infty} x"),
+            LimitDirection::Both
+        );
+        assert!(matches!(
+            parse("\\lim_{x \\to -\\infty} x"),
+            Node::Limit(_, point, ..) if point.to_string() == "-\\infty"
+        ));
+    }
+
+    #[test]
+    fn test_simplify_resolves_standard_limits() {
+        assert_eq!(simplified("\\lim_{x \\to 0} \\frac{\\sin(x)}{x}"), "1");
+        assert_eq!(simplified("\\lim_{x \\to 1} \\frac{x^2 - 1}{x - 1}"), "2");
+        assert_eq!(
+            simplified("\\lim_{x \\to 0} \\frac{1 - \\cos(x)}{x^2}"),
+            "\\frac{1}{2}"
+        );
+        assert_eq!(
+            simplified("\\lim_{x \\to \\infty} \\frac{2x + 1}{x - 3}"),
+            "2"
+        );
+        assert_eq!(simplified("\\lim_{x \\to -\\infty} e^{x}"), "0");
+        assert_eq!(simplified("\\lim_{x \\to 0^+} \\ln(x)"), "-\\infty");
+        assert_eq!(simplified("\\lim_{x \\to 0^-} \\frac{1}{x}"), "-\\infty");
+    }
+
+    #[test]
+    fn test_limit_composes_with_surrounding_operators() {
+        assert_eq!(
+            simplified("\\lim_{x \\to 1} \\frac{x^2 - 1}{x - 1} + 1"),
+            "3"
+        );
+        assert_eq!(
+            value("2\\lim_{h \\to 0} \\frac{(1 + h)^2 - 1}{h}").unwrap(),
+            4.0
+        );
+    }
+
+    #[test]
+    fn test_two_sided_limit_that_does_not_exist() {
+        let expr = parse_latex("\\lim_{x \\to 0} \\frac{1}{x}", &Environment::new()).unwrap();
+        assert!(matches!(expr, Node::Limit(..)), "{}", expr);
+        let err = value("\\lim_{x \\to 0} \\frac{1}{x}").unwrap_err();
+        assert!(err.contains("does not exist"), "{}", err);
+        let err = value("\\lim_{x \\to 0^+} \\frac{1}{x}").unwrap_err();
+        assert!(err.contains("+\\infty"), "{}", err);
+    }
+
+    #[test]
+    fn test_numeric_fallback_uses_environment() {
+        let mut env = Environment::new();
+        env.set("a", 3.0);
+        let v =
+            Evaluator::evaluate(&parse("\\lim_{x \\to 0} \\frac{\\sin(a x)}{x}"), &env).unwrap();
+        assert!((v - 3.0).abs() < 1e-6, "{}", v);
+        env.set("a", 2.0);
+        let v = Evaluator::evaluate(&parse("\\lim_{x \\to a} x^2"), &env).unwrap();
+        assert!((v - 4.0).abs() < 1e-6, "{}", v);
+    }
+
+    #[test]
+    fn test_oscillating_limit_is_not_given_a_value() {
+        let mut env = Environment::new();
+        env.set("a", 1.0);
+        let expr = parse("\\lim_{x \\to 0} \\sin(\\frac{a}{x})");
+        let err = Evaluator::evaluate(&expr, &env).unwrap_err();
+        assert!(
+            err.to_string().contains("could not be determined"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_round_trips_through_latex_and_display() {
+        for latex in [
+            "\\lim_{x \\to 0} \\frac{\\sin(x)}{x}",
+            "\\lim_{x \\to 0^+} x^x",
+            "\\lim_{x \\to -1^-} \\frac{1}{x + 1} + 2",
+            "\\lim_{t \\to \\infty} (1 + \\frac{1}{t})^t",
+        ] {
+            let expr = parse(latex);
+            assert_eq!(parse(&expr.to_latex()), expr, "{}", expr.to_latex());
+            assert_eq!(parse(&expr.to_string()), expr, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_limit_binds_its_variable() {
+        let expr = parse("\\lim_{x \\to 0} \\frac{\\sin(a x)}{x}");
+        assert!(!expr.contains_variable("x"));
+        assert!(expr.contains_variable("a"));
+        let err = substitute(&expr, &[("a".to_string(), parse("x"))]).unwrap_err();
+        assert!(err.contains("capture"), "{}", err);
+        assert_eq!(differentiate(&expr, "x").unwrap(), parse("0"));
+        assert!(differentiate(&expr, "a").is_err());
+    }
+
+    #[test]
+    fn test_malformed_limits_are_errors() {
+        let err = parse_latex_raw("\\lim x").unwrap_err();
+        assert!(err.message.contains("after \\lim"), "{}", err);
+        let err = parse_latex_raw("\\lim_{x = 0} x").unwrap_err();
+        assert!(err.message.contains("\\to"), "{}", err);
+        let err = parse_latex_raw("\\lim_{x \\to} x").unwrap_err();
+        assert!(err.message.contains("point"), "{}", err);
+    }
+}
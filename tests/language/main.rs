@@ -1,4 +1,18 @@
+mod asciimath;
+mod determinant;
+mod domains;
+mod format;
+mod function_application;
 mod functions;
+mod identifiers;
+mod incremental;
+mod integral;
 mod latex;
+mod limit;
+mod parse_error;
+mod parser_extensions;
 mod parser_hardening;
+mod parser_limits;
+mod plain;
+mod sharing;
 mod summation;
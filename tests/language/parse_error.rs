@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod parse_error_tests {
+    use arithma::{build_expression_tree, parse_latex_raw, Tokenizer};
+
+    #[test]
+    fn test_error_points_at_offending_token() {
+        let err = parse_latex_raw("x + )").unwrap_err();
+        assert_eq!(err.message, "Not enough operands for operator '+'");
+        assert_eq!(err.token_index, Some(2));
+        assert_eq!(err.span, Some(4..5));
+        assert_eq!(err.source.as_deref(), Some("x + )"));
+    }
+
+    #[test]
+    fn test_render_marks_span_with_caret() {
+        let err = parse_latex_raw("3 + * 4").unwrap_err();
+        assert_eq!(
+            err.render(),
+            "Not enough operands for operator '*'\n  3 + * 4\n      ^"
+        );
+        // Display stays the bare message
+        assert_eq!(err.to_string(), "Not enough operands for operator '*'");
+    }
+
+    #[test]
+    fn test_end_of_input_is_marked_after_source() {
+        let err = parse_latex_raw("(x + 1").unwrap_err();
+        assert_eq!(err.span, Some(6..6));
+        assert!(
+            err.render().ends_with("\n  (x + 1\n        ^"),
+            "{}",
+            err.render()
+        );
+    }
+
+    #[test]
+    fn test_char_span_counts_characters_not_bytes() {
+        let err = parse_latex_raw("θ + )").unwrap_err();
+        assert_eq!(err.span, Some(5..6));
+        assert_eq!(err.char_span(), Some(4..5));
+        assert!(
+            err.render().ends_with("\n  θ + )\n      ^"),
+            "{}",
+            err.render()
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_error_spans_the_command() {
+        let err = parse_latex_raw("y + \\mathrm{a1}").unwrap_err();
+        assert_eq!(err.token_index, None);
+        assert_eq!(err.span, Some(4..15));
+        assert!(err.render().ends_with("^^^^^^^^^^^"), "{}", err.render());
+    }
+
+    #[test]
+    fn test_messages_quote_the_source() {
+        let cases = [
+            ("\\{1\\}", "Unknown token '\\{'", 0..2),
+            ("1 \\# 2", "Unexpected token '\\#'", 2..4),
+            // Not the tokenizer's name for the row separator
+            ("x \\\\ y", "Unexpected token '\\\\'", 2..4),
+        ];
+        for (latex, message, span) in cases {
+            let err = parse_latex_raw(latex).unwrap_err();
+            assert_eq!(err.message, message, "{}", latex);
+            assert_eq!(err.span, Some(span), "{}", latex);
+        }
+    }
+
+    #[test]
+    fn test_unknown_character_is_an_error() {
+        let err = parse_latex_raw("1 @ 2").unwrap_err();
+        assert_eq!(err.message, "Unexpected character '@'");
+        assert_eq!(err.span, Some(2..3));
+        assert!(
+            err.render().ends_with("\n  1 @ 2\n    ^"),
+            "{}",
+            err.render()
+        );
+    }
+
+    #[test]
+    fn test_empty_group_is_not_mismatched() {
+        let err = parse_latex_raw("()").unwrap_err();
+        assert_eq!(err.message, "Expected an expression inside '()'");
+        assert_eq!(err.span, Some(1..2));
+        // The tokenizer rewrites the braces of \frac as parentheses
+        let err = parse_latex_raw("\\frac{1}{}").unwrap_err();
+        assert_eq!(err.message, "Expected an expression inside '{}'");
+        for unbalanced in ["(1", "1)", "\\frac{1}{(2}"] {
+            let err = parse_latex_raw(unbalanced).unwrap_err();
+            assert_eq!(
+                err.message, "Mismatched parentheses or braces",
+                "{}",
+                unbalanced
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_expression_tree_keeps_token_index() {
+        let mut tokenizer = Tokenizer::new("x + )");
+        let err = build_expression_tree(tokenizer.tokenize()).unwrap_err();
+        assert_eq!(err.token_index, Some(2));
+        // Without the source there is nothing to underline
+        assert_eq!(err.span, None);
+        assert_eq!(err.render(), err.message);
+    }
+}
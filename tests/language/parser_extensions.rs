@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod parser_extensions_tests {
+    use arithma::{
+        parse_latex_raw, parse_latex_raw_with_extensions, CommandInput, ExactNum, Node, ParseError,
+        ParserExtension, ParserExtensions,
+    };
+
+    /// `\ce{H_2O}` is the species `H_2O`, kept as one opaque function
+    /// argument.
+    struct Chemistry;
+
+    impl ParserExtension for Chemistry {
+        fn parse_command(
+            &self,
+            command: &str,
+            input: &mut CommandInput<'_, '_>,
+        ) -> Option<Result<Node, ParseError>> {
+            if command != "ce" {
+                return None;
+            }
+            Some(input.group_tokens().and_then(|tokens| {
+                let species: String = tokens.into_iter().filter(|t| *t != "*").collect();
+                if species.is_empty() {
+                    return Err(input.error("Empty species in \\ce"));
+                }
+                Ok(Node::Function(
+                    "ce".to_string(),
+                    vec![Node::Variable(species)],
+                ))
+            }))
+        }
+    }
+
+    /// `\half{x}` is x/2, its argument parsed by the built-in grammar.
+    struct Half;
+
+    impl ParserExtension for Half {
+        fn parse_command(
+            &self,
+            command: &str,
+            input: &mut CommandInput<'_, '_>,
+        ) -> Option<Result<Node, ParseError>> {
+            (command == "half").then(|| {
+                let inner = input.parse_group()?;
+                Ok(Node::Divide(
+                    Box::new(inner),
+                    Box::new(Node::Num(ExactNum::integer(2))),
+                ))
+            })
+        }
+    }
+
+    /// Reads a group, then declines: the tokens must be handed on intact.
+    struct Greedy;
+
+    impl ParserExtension for Greedy {
+        fn parse_command(
+            &self,
+            _command: &str,
+            input: &mut CommandInput<'_, '_>,
+        ) -> Option<Result<Node, ParseError>> {
+            let _ = input.group_tokens();
+            None
+        }
+    }
+
+    fn parse(latex: &str, extensions: &ParserExtensions) -> String {
+        format!(
+            "{}",
+            parse_latex_raw_with_extensions(latex, extensions).unwrap()
+        )
+    }
+
+    #[test]
+    fn extension_parses_its_command() {
+        let extensions = ParserExtensions::new().with(Chemistry);
+        let node = parse_latex_raw_with_extensions("\\ce{H_2O}", &extensions).unwrap();
+        assert_eq!(
+            node,
+            Node::Function("ce".to_string(), vec![Node::Variable("H_2O".to_string())])
+        );
+        let node = parse_latex_raw_with_extensions("2\\ce{H_2O} + x", &extensions).unwrap();
+        assert!(matches!(node, Node::Add(..)), "{:?}", node);
+    }
+
+    #[test]
+    fn extension_arguments_use_the_builtin_grammar() {
+        let extensions = ParserExtensions::new().with(Half);
+        assert_eq!(
+            parse("\\half{x + 1} \\cdot 3", &extensions),
+            parse_latex_raw("\\frac{x + 1}{2} \\cdot 3")
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            parse("\\sin(\\half{\\half{y}})", &extensions),
+            parse_latex_raw("\\sin(\\frac{\\frac{y}{2}}{2})")
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn first_extension_to_accept_wins() {
+        let extensions = ParserExtensions::new()
+            .with(Greedy)
+            .with(Chemistry)
+            .with(Half);
+        assert_eq!(extensions.len(), 3);
+        assert_eq!(parse("\\half{4}", &extensions), "\\frac{4}{2}");
+        let node = parse_latex_raw_with_extensions("\\ce{CO_2}", &extensions).unwrap();
+        assert_eq!(
+            node,
+            Node::Function("ce".to_string(), vec![Node::Variable("CO_2".to_string())])
+        );
+    }
+
+    #[test]
+    fn declined_commands_use_the_builtin_grammar() {
+        let extensions = ParserExtensions::new().with(Greedy).with(Chemistry);
+        for latex in ["\\foo{x}", "\\sin x + 1", "ce{x}", "x^{2}"] {
+            assert_eq!(
+                parse(latex, &extensions),
+                parse_latex_raw(latex).unwrap().to_string(),
+                "{}",
+                latex
+            );
+        }
+        // Without extensions an unknown command stays a variable
+        assert_eq!(
+            parse("\\ce{x}", &ParserExtensions::new()),
+            parse_latex_raw("\\ce{x}").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn extension_errors_point_into_the_source() {
+        let extensions = ParserExtensions::new().with(Chemistry).with(Half);
+        let error = parse_latex_raw_with_extensions("1 + \\ce{}", &extensions).unwrap_err();
+        assert_eq!(error.message, "Empty species in \\ce");
+        let error = parse_latex_raw_with_extensions("1 + \\ce x", &extensions).unwrap_err();
+        assert_eq!(error.message, "Expected '{' after \\ce");
+        assert_eq!(error.span, Some(8..9));
+        let error = parse_latex_raw_with_extensions("\\half{x", &extensions).unwrap_err();
+        assert_eq!(error.message, "Unclosed argument of \\half");
+    }
+}
@@ -313,7 +313,7 @@ mod parser_hardening_tests {
         );
     }
 
-    fn mcp_eigenvalues(matrix_body: &str) -> Result<Vec<f64>, String> {
+    fn mcp_eigenvalues(matrix_body: &str) -> Result<Vec<f64>, arithma::ArithmaError> {
         let env = Environment::new();
         let latex = format!("\\begin{{pmatrix}} {} \\end{{pmatrix}}", matrix_body);
         let mat = arithma::parse_latex_matrix(&latex, &env)?;
@@ -326,24 +326,15 @@ mod parser_hardening_tests {
     #[test]
     fn test_leibniz_derivative_detection() {
         let env = Environment::new();
-        // \frac{d}{dx}(x^2) should error with a helpful message, not parse silently
-        let result = parse_latex("\\frac{d}{dx}(x^2)", &env);
-        assert!(
-            result.is_err(),
-            "Leibniz d/dx should produce an error, not parse silently"
-        );
-        let err = result.unwrap_err();
-        assert!(
-            err.contains("differentiate") || err.contains("diff"),
-            "Error should mention the differentiate tool: {}",
-            err
-        );
+        // \frac{d}{dx}(x^2) is a derivative, not the fraction d/(dx) times x^2
+        let result = parse_latex("\\frac{d}{dx}(x^2)", &env).unwrap();
+        assert_eq!(result.to_string(), "2x");
     }
 
     #[test]
     fn test_leibniz_ddt_detection() {
         let env = Environment::new();
-        // \frac{d}{dt} should also be caught
+        // \frac{d}{dt} with nothing to differentiate is an error
         let result = parse_latex("\\frac{d}{dt}", &env);
         assert!(result.is_err(), "Leibniz d/dt should produce an error");
     }
@@ -351,17 +342,8 @@ mod parser_hardening_tests {
     #[test]
     fn test_partial_derivative_detection() {
         let env = Environment::new();
-        let result = parse_latex("\\frac{\\partial}{\\partial x}(x^2)", &env);
-        assert!(
-            result.is_err(),
-            "Partial derivative notation should produce an error"
-        );
-        let err = result.unwrap_err();
-        assert!(
-            err.contains("differentiate"),
-            "Error should mention the differentiate tool: {}",
-            err
-        );
+        let result = parse_latex("\\frac{\\partial}{\\partial x}(x^2 y)", &env).unwrap();
+        assert_eq!(result, parse_latex("2x \\cdot y", &env).unwrap());
     }
 
     #[test]
@@ -557,3 +539,105 @@ fn bigint_fraction_stays_exact() {
         result
     );
 }
+
+// ── Grammar ─────
+// The parser is a Pratt parser over the token stream: functions,
+// bars, \sum/\prod and determinants are parsed in place, so each
+// composes with its surroundings instead of being special-cased.
+
+#[cfg(test)]
+mod grammar_tests {
+    use arithma::{parse_latex_raw, Environment, Evaluator, Node};
+
+    fn parse(s: &str) -> Node {
+        parse_latex_raw(s).unwrap()
+    }
+
+    fn eval(s: &str) -> f64 {
+        Evaluator::evaluate(&parse(s), &Environment::new()).unwrap()
+    }
+
+    #[test]
+    fn unbracketed_function_argument_is_the_following_product() {
+        assert_eq!(parse("\\sin x + 1"), parse("\\sin(x) + 1"));
+        assert_eq!(parse("\\sin 2x"), parse("\\sin(2x)"));
+        assert_eq!(parse("\\sin x \\cos x"), parse("\\sin(x) \\cdot \\cos(x)"));
+    }
+
+    #[test]
+    fn juxtaposed_operands_multiply() {
+        assert_eq!(parse("x^{2}y"), parse("x^{2} \\cdot y"));
+        assert!(parse_latex_raw("2 3").is_err());
+    }
+
+    #[test]
+    fn argument_lists_keep_order_and_nest() {
+        match parse("\\gcd(4, 6)") {
+            Node::Function(name, args) => {
+                assert_eq!(name, "gcd");
+                assert_eq!(args, vec![parse("4"), parse("6")]);
+            }
+            other => panic!("expected a function call, got {:?}", other),
+        }
+        assert_eq!(eval("1 + \\max(2, 3)"), 4.0);
+        assert_eq!(eval("\\max{\\min{1, 2}, 3}"), 3.0);
+    }
+
+    #[test]
+    fn brace_and_parenthesis_argument_lists_agree() {
+        for (braced, parenthesized) in [
+            ("\\min{3, 1, 4, 2}", "\\min(3, 1, 4, 2)"),
+            ("\\max{\\min{1,2}, 3}", "\\max(\\min(1, 2), 3)"),
+            ("\\max{1, \\min{5, 4}, -2}", "\\max(1, \\min(5, 4), -2)"),
+            ("\\lcm{4, 6, 10}", "\\lcm\\left(4, 6, 10\\right)"),
+        ] {
+            assert_eq!(parse(braced), parse(parenthesized), "{}", braced);
+        }
+        assert_eq!(eval("\\max{1, \\min{5, 4}, -2}"), 4.0);
+        assert_eq!(eval("\\lcm(4, 6, 10)"), 60.0);
+    }
+
+    #[test]
+    fn malformed_argument_lists_say_what_is_wrong() {
+        for (latex, message) in [
+            ("\\max(, 1)", "Empty argument in \\max"),
+            ("\\max(1, )", "Empty argument in \\max"),
+            ("\\max{1,, 2}", "Empty argument in \\max"),
+            ("\\max(1, 2", "Unclosed argument list in \\max"),
+            ("\\max(1, 2}", "expected ',' or ')'"),
+        ] {
+            let err = parse_latex_raw(latex).unwrap_err().to_string();
+            assert!(err.contains(message), "{}: {}", latex, err);
+        }
+    }
+
+    #[test]
+    fn power_is_right_associative_and_binds_tighter_than_minus() {
+        assert_eq!(eval("2^3^2"), 512.0);
+        assert_eq!(eval("-2^2"), -4.0);
+        assert_eq!(eval("2^-1 \\cdot 4"), 2.0);
+    }
+
+    #[test]
+    fn unbraced_summation_body_keeps_its_power() {
+        match parse("\\sum_{k=1}^{n} k^2 + 1") {
+            Node::Add(sum, _) => match *sum {
+                Node::Summation(_, _, _, body) => assert_eq!(*body, parse("k^{2}")),
+                other => panic!("expected a summation, got {:?}", other),
+            },
+            other => panic!("expected a sum, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn arity_errors_name_the_function() {
+        let err = parse_latex_raw("\\sin{0, 1}").unwrap_err().to_string();
+        assert!(
+            err.contains("Too many arguments for function sin"),
+            "{}",
+            err
+        );
+        let err = parse_latex_raw("\\binom{5}").unwrap_err().to_string();
+        assert!(err.contains("binom"), "{}", err);
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod parser_limits_tests {
+    use arithma::{
+        build_expression_tree_with_options, parse_latex_raw, parse_latex_raw_with_options,
+        ParseLimit, ParserOptions, Tokenizer,
+    };
+
+    fn limit_of(latex: &str) -> Option<ParseLimit> {
+        parse_latex_raw(latex).unwrap_err().limit
+    }
+
+    #[test]
+    fn deep_groups_are_rejected_before_parsing() {
+        let latex = format!("{}x{}", "(".repeat(1_000), ")".repeat(1_000));
+        let err = parse_latex_raw(&latex).unwrap_err();
+        assert_eq!(err.limit, Some(ParseLimit::BraceDepth));
+        assert_eq!(err.token_index, Some(64));
+        assert_eq!(err.span, Some(64..65));
+        assert_eq!(
+            limit_of(&format!("{}x", "{".repeat(3_000))),
+            Some(ParseLimit::BraceDepth)
+        );
+    }
+
+    #[test]
+    fn long_inputs_are_rejected_by_token_count() {
+        let latex = vec!["x"; 6_000].join(" + ");
+        assert_eq!(limit_of(&latex), Some(ParseLimit::Tokens));
+    }
+
+    #[test]
+    fn deep_recursion_without_groups_is_rejected() {
+        assert_eq!(
+            limit_of(&format!("{}x", "-".repeat(5_000))),
+            Some(ParseLimit::ExpressionDepth)
+        );
+        assert_eq!(
+            limit_of(&vec!["x"; 2_000].join("^")),
+            Some(ParseLimit::ExpressionDepth)
+        );
+    }
+
+    #[test]
+    fn nested_function_calls_are_limited() {
+        let latex = format!("{}x{}", "\\sin ".repeat(40), "");
+        assert_eq!(limit_of(&latex), Some(ParseLimit::FunctionNesting));
+        let options = ParserOptions {
+            max_function_nesting: 2,
+            ..ParserOptions::default()
+        };
+        assert!(parse_latex_raw_with_options("\\sin(\\cos(x))", &options).is_ok());
+        let err = parse_latex_raw_with_options("\\sin(\\cos(\\tan(x)))", &options).unwrap_err();
+        assert_eq!(err.limit, Some(ParseLimit::FunctionNesting));
+        assert!(err.message.contains("nested more than 2"), "{}", err);
+    }
+
+    #[test]
+    fn ordinary_input_is_unaffected_and_limits_are_configurable() {
+        assert!(parse_latex_raw("\\frac{(x+1)^2}{\\sqrt{x}} - \\sin(\\cos(x))").is_ok());
+        assert_eq!(parse_latex_raw("x + )").unwrap_err().limit, None);
+
+        let options = ParserOptions {
+            max_tokens: 5,
+            ..ParserOptions::default()
+        };
+        assert!(parse_latex_raw_with_options("x + y + z", &options).is_ok());
+        let err = parse_latex_raw_with_options("x + y + z + 1", &options).unwrap_err();
+        assert_eq!(err.limit, Some(ParseLimit::Tokens));
+
+        let tokens = Tokenizer::new("((x))").tokenize();
+        let options = ParserOptions {
+            max_brace_depth: 1,
+            ..ParserOptions::default()
+        };
+        let err = build_expression_tree_with_options(tokens, &options).unwrap_err();
+        assert_eq!(err.limit, Some(ParseLimit::BraceDepth));
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod plain_input_tests {
+    use arithma::{parse_latex_raw, parse_plain, Node, ParseLimit};
+
+    fn same_tree(plain: &str, latex: &str) {
+        assert_eq!(
+            parse_plain(plain).unwrap(),
+            parse_latex_raw(latex).unwrap(),
+            "{plain} vs {latex}"
+        );
+    }
+
+    #[test]
+    fn matches_the_latex_parser() {
+        same_tree("sin(x)^2 + 3*x", "\\sin(x)^{2} + 3x");
+        same_tree("x**2 - 2x + 1", "x^{2} - 2x + 1");
+        same_tree("-2*x", "-2x");
+        same_tree("-x^2", "-x^{2}");
+        same_tree("x^-1", "x^{-1}");
+        same_tree("2^3^4", "2^{3^{4}}");
+        same_tree("sqrt(x)/2", "\\frac{\\sqrt{x}}{2}");
+        same_tree("2(x + 1)", "2 \\cdot (x + 1)");
+        same_tree("x(y + 1)", "x (y + 1)");
+        same_tree("|x - 1| + abs(y)", "|x - 1| + |y|");
+        same_tree("pi*r^2 + e^x", "\\pi r^{2} + e^{x}");
+        same_tree("theta_1 + alpha", "\\theta_{1} + \\alpha");
+        same_tree("x >= 2 and x < 5", "x \\geq 2 \\land x < 5");
+        same_tree("n! + binom(n, k)", "n! + \\binom{n}{k}");
+        same_tree("a mod b", "a \\bmod b");
+    }
+
+    #[test]
+    fn notation_as_calls() {
+        same_tree("sum(k^2, k, 1, n)", "\\sum_{k=1}^{n} k^{2}");
+        same_tree("prod(i, i, 1, n)", "\\prod_{i=1}^{n} i");
+        same_tree("integrate(x^2, x, 0, 1)", "\\int_{0}^{1} x^{2} \\, dx");
+        same_tree("integrate(x, x)", "\\int x \\, dx");
+        same_tree("limit(1/x, x, inf)", "\\lim_{x \\to \\infty} \\frac{1}{x}");
+        same_tree(
+            "limit(1/x, x, 0, right)",
+            "\\lim_{x \\to 0^{+}} \\frac{1}{x}",
+        );
+        same_tree("diff(x^2, x)", "\\frac{d}{dx} x^{2}");
+        same_tree(
+            "floor(x) + ceil(y)",
+            "\\lfloor x \\rfloor + \\lceil y \\rceil",
+        );
+        same_tree(
+            "[[1, 2], [3, 4]]",
+            "\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}",
+        );
+    }
+
+    #[test]
+    fn names_are_whole_words() {
+        assert_eq!(
+            parse_plain("rate*time").unwrap(),
+            Node::Multiply(
+                Box::new(Node::Variable("rate".into())),
+                Box::new(Node::Variable("time".into()))
+            )
+        );
+    }
+
+    #[test]
+    fn reads_back_plain_output() {
+        for latex in [
+            "3x^2 + 2x - 1",
+            "\\frac{x+1}{x-1} - \\frac{a b}{c}",
+            "-x \\cdot y - (x y)",
+            "1 - -2 + (-x)^{2}",
+            "\\frac{a}{b c} \\cdot 2^{-x}",
+            "x = -\\sqrt{2}",
+            "\\lnot x \\lor y \\land z",
+            "\\sum_{k=1}^{n} \\frac{1}{k^2} + \\int_0^1 x^2 dx",
+            "\\lim_{x \\to 0^-} \\frac{|x|}{x}",
+            "0.5x + \\ln(x) \\log(y)",
+        ] {
+            let tree = parse_latex_raw(latex).unwrap();
+            let plain = tree.to_plain_string();
+            assert_eq!(parse_plain(&plain).unwrap(), tree, "{latex} → {plain}");
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_input() {
+        let error = parse_plain("sin x").unwrap_err();
+        assert_eq!(error.message, "Expected '(' after 'sin'");
+        let error = parse_plain("3 $ 4").unwrap_err();
+        assert_eq!(error.char_span(), Some(2..3));
+        assert!(parse_plain("abs(x").is_err());
+        assert!(parse_plain("sum(k, 1, 2)").is_err());
+        assert!(parse_plain("").is_err());
+        let deep = format!("{}x{}", "(".repeat(500), ")".repeat(500));
+        assert_eq!(
+            parse_plain(&deep).unwrap_err().limit,
+            Some(ParseLimit::ExpressionDepth)
+        );
+    }
+}
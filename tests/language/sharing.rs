@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod sharing_tests {
+    use arithma::{
+        differentiate, parse_latex_raw, share_subexpressions, share_subexpressions_with, Node,
+        SharingOptions,
+    };
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    #[test]
+    fn test_repeated_subtree_is_named_once() {
+        let expr = parse("\\frac{\\sin(x^2+1)}{\\cos(x^2+1)}");
+        let shared = share_subexpressions(&expr);
+        assert_eq!(shared.definitions, vec![("u".to_string(), parse("x^2+1"))]);
+        assert_eq!(shared.result, parse("\\frac{\\sin(u)}{\\cos(u)}"));
+        assert_eq!(shared.expand(), expr);
+    }
+
+    #[test]
+    fn test_quotient_rule_derivative_round_trips() {
+        let d = differentiate(&parse("\\frac{\\sin(x^2+1)}{x^3+\\cos(x)}"), "x").unwrap();
+        let shared = share_subexpressions(&d);
+        assert_eq!(shared.definitions.len(), 2, "{}", shared);
+        assert_eq!(shared.expand(), d);
+    }
+
+    #[test]
+    fn test_larger_definition_uses_smaller_one() {
+        let expr = parse("(x^2+1)^3 \\cdot (x^2+1)^3 + \\ln(x^2+1)");
+        let shared = share_subexpressions(&expr);
+        let names: Vec<&str> = shared.definitions.iter().map(|(n, _)| n.as_str()).collect();
+        // x^2+1 is defined first because (x^2+1)^3 is written in terms of it
+        assert_eq!(shared.definitions[0].1, parse("x^2+1"), "{:?}", names);
+        assert_eq!(
+            shared.definitions[1].1,
+            Node::Power(
+                Box::new(Node::Variable(names[0].to_string())),
+                Box::new(parse("3"))
+            )
+        );
+        assert_eq!(shared.expand(), expr);
+    }
+
+    #[test]
+    fn test_plain_and_latex_rendering() {
+        let shared = share_subexpressions(&parse("\\sqrt{x^2+1} + \\frac{1}{x^2+1}"));
+        assert_eq!(
+            shared.to_string(),
+            "u = x^{2} + 1\nresult = \\sqrt(u) + \\frac{1}{u}"
+        );
+        assert_eq!(
+            shared.to_latex(),
+            "\\begin{aligned}\nu &= x^{2} + 1 \\\\\n\\text{result} &= \\sqrt{u} + \\frac{1}{u}\n\\end{aligned}"
+        );
+    }
+
+    #[test]
+    fn test_small_or_unique_subtrees_stay_inline() {
+        let expr = parse("x^2 + \\sin(x^2)");
+        let shared = share_subexpressions(&expr);
+        assert!(shared.definitions.is_empty());
+        assert_eq!(shared.to_latex(), expr.to_latex());
+
+        let lowered = share_subexpressions_with(
+            &expr,
+            SharingOptions {
+                min_size: 3,
+                min_occurrences: 2,
+            },
+        );
+        assert_eq!(lowered.definitions.len(), 1);
+    }
+
+    #[test]
+    fn test_names_avoid_existing_variables_and_binder_bodies() {
+        let expr = parse("(u + v + 1)^2 + \\sin(u + v + 1) + \\sum_{k=1}^{3} (k + 1)(k + 1)");
+        let shared = share_subexpressions(&expr);
+        assert_eq!(shared.definitions.len(), 1, "{}", shared);
+        assert_eq!(shared.definitions[0].0, "w");
+        assert!(shared.result.to_string().contains("\\sum"), "{}", shared);
+        assert_eq!(shared.expand(), expr);
+    }
+
+    #[test]
+    fn test_large_expression_with_many_distinct_subtrees() {
+        // No subtree of 4 nodes repeats across the 1000 terms; each
+        // 3-node x + i repeats within its own term
+        let terms: Vec<String> = (0..1000)
+            .map(|i| format!("\\sin(x + {i})\\cos(x + {i})"))
+            .collect();
+        let expr = terms
+            .iter()
+            .map(|term| parse(term))
+            .reduce(|sum, term| Node::Add(Box::new(sum), Box::new(term)))
+            .unwrap();
+        assert!(share_subexpressions(&expr).definitions.is_empty());
+
+        let lowered = share_subexpressions_with(
+            &parse(&terms[..20].join(" + ")),
+            SharingOptions {
+                min_size: 3,
+                min_occurrences: 2,
+            },
+        );
+        assert_eq!(lowered.definitions.len(), 20);
+        assert_eq!(lowered.expand(), parse(&terms[..20].join(" + ")));
+    }
+}
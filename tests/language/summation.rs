@@ -112,6 +112,22 @@ mod summation_tests {
         assert_eq!(val, 121.0, "Closed form {} at n=4 should be 121", closed);
     }
 
+    #[test]
+    fn geometric_sum_ratio_below_one() {
+        // Σ_{k=1}^{n} 1/2^k = 1 − (½)^n, written from the first term
+        assert_eq!(
+            simplify_latex("\\sum_{k=1}^{n} \\frac{1}{2^{k}}"),
+            "1 - (\\frac{1}{2})^{n}"
+        );
+        assert_eq!(
+            simplify_latex("\\sum_{k=0}^{n} (\\frac{1}{3})^{k}"),
+            "\\frac{3}{2} \\cdot (1 - (\\frac{1}{3})^{n + 1})"
+        );
+        let closed = simplify_latex("\\sum_{k=0}^{n} (-\\frac{1}{2})^{k}");
+        // 1 − ½ + ¼ − ⅛ = 5/8
+        assert!((eval_with(&closed, "n", 3.0) - 0.625).abs() < 1e-12);
+    }
+
     #[test]
     fn geometric_sum_with_coefficient() {
         // Σ_{k=0}^{n} 5·2^k = 5·(2^{n+1} - 1)
@@ -356,6 +372,18 @@ mod summation_tests {
             simplified
         );
     }
+
+    #[test]
+    fn long_ranges_evaluate_exactly() {
+        // Long enough to run on several threads with the `parallel` feature
+        let env = Environment::new();
+        let eval = |input: &str| {
+            let expr = build_expression_tree(Tokenizer::new(input).tokenize()).unwrap();
+            Evaluator::evaluate_exact(&expr, &env).unwrap().to_string()
+        };
+        assert_eq!(eval("\\sum_{k=1}^{30000} k"), "450015000");
+        assert_eq!(eval("\\prod_{k=1}^{12000} \\frac{k+1}{k}"), "12001");
+    }
 }
 
 // ── Composition: Σ/Π as atoms inside larger expressions ─────
@@ -423,6 +451,29 @@ mod indexed_composition_tests {
         assert_eq!(eval("\\sum_{i=1}^{3}{\\sum_{j=1}^{2}{i \\cdot j}}"), 18.0);
     }
 
+    #[test]
+    fn nested_sum_unbraced() {
+        assert_eq!(eval("\\sum_{i=1}^{3}\\sum_{j=1}^{3} i*j"), 36.0);
+        // The inner bound may use the outer index
+        assert_eq!(eval("\\sum_{i=1}^{3}\\sum_{j=1}^{i} i j"), 25.0);
+        assert_eq!(
+            eval("\\sum_{i=1}^{3} (i + \\sum_{j=1}^{i} j)"),
+            6.0 + 1.0 + 3.0 + 6.0
+        );
+        assert_eq!(
+            eval("1 + \\sum_{i=1}^{3}\\sum_{j=1}^{2}\\sum_{k=1}^{2} 1"),
+            13.0
+        );
+    }
+
+    #[test]
+    fn limits_and_superscript_first() {
+        assert_eq!(eval("\\sum\\limits_{k=1}^{5} k"), 15.0);
+        assert_eq!(eval("\\displaystyle\\prod\\limits_{k=1}^{4} k"), 24.0);
+        assert_eq!(eval("\\sum^{5}_{k=1} k"), 15.0);
+        assert_eq!(eval("\\sum^{3}_{i=1}\\sum^{i}_{j=1} 1"), 6.0);
+    }
+
     #[test]
     fn sum_equation_still_parses() {
         // Σ = 15 must still build an Equation node, not error.
@@ -651,3 +702,309 @@ mod non_integer_bound_tests {
         assert_eq!(simplify_latex("\\sum_{k=1}^{100} k"), "5050");
     }
 }
+
+// ── Product notation: unbraced bounds and symbolic closed forms ─────
+
+#[cfg(test)]
+mod product_notation_tests {
+    use arithma::{parse_latex, parse_latex_raw, Environment, Evaluator};
+
+    fn eval(latex: &str) -> f64 {
+        Evaluator::evaluate(&parse_latex_raw(latex).unwrap(), &Environment::new()).unwrap()
+    }
+
+    fn simplify_latex(latex: &str) -> String {
+        parse_latex(latex, &Environment::new()).unwrap().to_string()
+    }
+
+    #[test]
+    fn unbraced_upper_bound_is_not_multiplied_into_the_body() {
+        assert_eq!(eval("\\prod_{i=1}^5 i"), 120.0);
+        assert_eq!(eval("\\sum_{i=1}^5 i"), 15.0);
+        assert_eq!(eval("\\prod_{i=1}^5 i + 1"), 121.0);
+    }
+
+    #[test]
+    fn product_of_index_from_one_is_factorial() {
+        assert_eq!(simplify_latex("\\prod_{i=1}^{n} i"), "n!");
+        assert_eq!(simplify_latex("\\prod_{i=1}^{n} i + 1"), "n! + 1");
+    }
+
+    #[test]
+    fn product_of_index_from_later_start_divides_out_the_prefix() {
+        let expr = parse_latex("\\prod_{i=3}^{n} i", &Environment::new()).unwrap();
+        assert!(!expr.to_string().contains("\\prod"), "{}", expr);
+        let mut env = Environment::new();
+        env.set("n", 6.0);
+        assert_eq!(Evaluator::evaluate(&expr, &env).unwrap(), 360.0);
+    }
+
+    #[test]
+    fn constant_power_of_index_factors_out() {
+        let expr = parse_latex("\\prod_{k=1}^{n} k^2", &Environment::new()).unwrap();
+        assert!(!expr.to_string().contains("\\prod"), "{}", expr);
+        let mut env = Environment::new();
+        env.set("n", 4.0);
+        assert_eq!(Evaluator::evaluate(&expr, &env).unwrap(), 576.0);
+    }
+}
+
+#[cfg(test)]
+mod expression_bound_tests {
+    use arithma::{parse_latex, parse_latex_raw, Environment, Evaluator};
+
+    fn eval(latex: &str, bindings: &[(&str, f64)]) -> Result<f64, String> {
+        let mut env = Environment::new();
+        for (name, value) in bindings {
+            env.set(name, *value);
+        }
+        Ok(Evaluator::evaluate(&parse_latex_raw(latex).unwrap(), &env)?)
+    }
+
+    #[test]
+    fn braced_expression_bounds_parse_whole() {
+        for (latex, shown) in [
+            ("\\sum_{i=1}^{n+1} i", "\\sum_{i = 1}^{n + 1}{i}"),
+            ("\\sum_{i=k}^{2k} i", "\\sum_{i = k}^{2k}{i}"),
+            ("\\sum_{i = k + 1}^{n^2} i", "\\sum_{i = k + 1}^{n^{2}}{i}"),
+            (
+                "\\prod_{i=1}^{n-1} (i + 1)",
+                "\\prod_{i = 1}^{n - 1}{i + 1}",
+            ),
+        ] {
+            assert_eq!(
+                parse_latex_raw(latex).unwrap().to_string(),
+                shown,
+                "{}",
+                latex
+            );
+        }
+    }
+
+    #[test]
+    fn unbraced_fraction_bound_is_one_operand() {
+        assert_eq!(
+            parse_latex_raw("\\sum_{i=1}^\\frac{n}{3} i")
+                .unwrap()
+                .to_string(),
+            "\\sum_{i = 1}^{\\frac{n}{3}}{i}"
+        );
+        assert_eq!(
+            parse_latex_raw("x^\\frac{1}{2}").unwrap().to_string(),
+            "x^{\\frac{1}{2}}"
+        );
+    }
+
+    #[test]
+    fn expression_bounds_evaluate_from_environment() {
+        assert_eq!(eval("\\sum_{i=1}^{n+1} i", &[("n", 3.0)]), Ok(10.0));
+        assert_eq!(eval("\\sum_{i=k}^{2k} i", &[("k", 2.0)]), Ok(9.0));
+        assert_eq!(eval("\\sum_{i=-n}^{n} i^2", &[("n", 2.0)]), Ok(10.0));
+        assert_eq!(eval("\\prod_{i=1}^{n+1} i", &[("n", 3.0)]), Ok(24.0));
+        assert_eq!(
+            eval("\\sum_{i=1}^{\\lfloor n/2 \\rfloor} i", &[("n", 9.0)]),
+            Ok(10.0)
+        );
+    }
+
+    #[test]
+    fn expression_bounds_keep_closed_forms() {
+        let env = Environment::new();
+        let simplified = |latex: &str| parse_latex(latex, &env).unwrap().to_string();
+        assert_eq!(
+            simplified("\\sum_{i=1}^{n+1} i"),
+            "\\frac{(n + 1) \\cdot (n + 2)}{2}"
+        );
+        assert_eq!(simplified("\\sum_{i=1}^{2n} i"), "n \\cdot (2n + 1)");
+    }
+
+    #[test]
+    fn unbound_or_self_referencing_bounds_are_errors() {
+        let err = eval("\\sum_{i=1}^{m+1} i", &[]).unwrap_err();
+        assert!(err.contains("upper bound m + 1"), "{}", err);
+        assert!(err.contains("'m' is not defined"), "{}", err);
+        let err = parse_latex_raw("\\sum_{i=1}^{i} i").unwrap_err();
+        assert!(
+            err.message.contains("cannot depend on its index 'i'"),
+            "{}",
+            err
+        );
+        // An enclosing index is fine
+        assert_eq!(
+            eval("\\sum_{j=1}^{n} \\sum_{i=1}^{j} i", &[("n", 3.0)]),
+            Ok(10.0)
+        );
+    }
+}
+
+#[cfg(test)]
+mod list_summation_tests {
+    use arithma::{differentiate, parse_latex_raw, Environment, Evaluator, Node};
+
+    fn env() -> Environment {
+        serde_json::from_str(r#"{"vars": {"m": 2.5, "data": [1, 2, 3, 4]}}"#).unwrap()
+    }
+
+    fn value(latex: &str) -> Result<f64, String> {
+        Ok(Evaluator::evaluate(
+            &parse_latex_raw(latex).unwrap(),
+            &env(),
+        )?)
+    }
+
+    #[test]
+    fn test_sum_over_list_parses_with_bound_index() {
+        match parse_latex_raw("\\sum_{x \\in data} x^2").unwrap() {
+            Node::SumOver(index, list, body) => {
+                assert_eq!(index, "x");
+                assert_eq!(*list, Node::Variable("data".to_string()));
+                assert_eq!(body.to_string(), "x^{2}");
+            }
+            other => panic!("expected a sum over a list, got {:?}", other),
+        }
+        let expr = parse_latex_raw("\\sum_{x \\in data}{(x - m)^2} + 1").unwrap();
+        assert!(!expr.contains_variable("x"));
+        assert!(expr.contains_variable("data") && expr.contains_variable("m"));
+        assert_eq!(parse_latex_raw(&expr.to_latex()).unwrap(), expr);
+    }
+
+    #[test]
+    fn test_sum_over_list_reads_env_json_arrays() {
+        assert_eq!(value("\\sum_{x \\in data} x").unwrap(), 10.0);
+        assert_eq!(value("\\sum_{x \\in data} x^2 + 1").unwrap(), 31.0);
+        assert_eq!(value("\\sum_{x \\in data}{(x - m)^2}").unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_list_splices_into_function_arguments() {
+        assert_eq!(value("\\max(data)").unwrap(), 4.0);
+        assert_eq!(value("\\min(data, 0)").unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_list_is_not_a_number() {
+        let err = value("data + 1").unwrap_err();
+        assert!(err.contains("is a list"), "{}", err);
+        let err = value("\\sum_{x \\in m} x").unwrap_err();
+        assert!(err.contains("not bound to a list"), "{}", err);
+        let err = parse_latex_raw("\\prod_{x \\in data} x").unwrap_err();
+        assert!(err.message.contains("not supported"), "{}", err);
+    }
+
+    #[test]
+    fn test_scalar_and_list_bindings_replace_each_other() {
+        let mut env = env();
+        env.set("data", 7.0);
+        assert!(env.get_list("data").is_none());
+        env.set_list("m", &[1.0, 2.0]);
+        assert_eq!(env.get("m"), None);
+        let json = serde_json::to_value(&env).unwrap();
+        assert_eq!(json["vars"]["m"], serde_json::json!([1.0, 2.0]));
+        assert_eq!(json["vars"]["data"], serde_json::json!(7.0));
+    }
+
+    #[test]
+    fn test_derivative_passes_through_the_sum() {
+        let expr = parse_latex_raw("\\sum_{x \\in data}{(x - m)^2}").unwrap();
+        let d = differentiate(&expr, "m").unwrap();
+        assert!(matches!(d, Node::SumOver(..)), "{}", d);
+        assert_eq!(
+            Evaluator::evaluate(&d, &env()).unwrap(),
+            -10.0 + 8.0 * 2.5 - 10.0
+        );
+        assert_eq!(differentiate(&expr, "x").unwrap().to_string(), "0");
+    }
+}
+
+#[cfg(test)]
+mod structural_summation_tests {
+    use arithma::{parse_latex, Environment, Evaluator, Node};
+
+    fn closed(latex: &str) -> Node {
+        parse_latex(latex, &Environment::new()).unwrap()
+    }
+
+    /// Closed form `latex` at `n`, against adding the terms one by one.
+    fn check_at(latex: &str, n: f64, term: impl Fn(f64) -> f64, from: i64) {
+        let expr = closed(latex);
+        assert!(
+            !matches!(expr, Node::Summation(..)),
+            "{} stayed a sum",
+            latex
+        );
+        let mut env = Environment::new();
+        env.set("n", n);
+        env.set("x", 1.5);
+        let value = Evaluator::evaluate(&expr, &env).unwrap();
+        let expected: f64 = (from..=n as i64).map(|k| term(k as f64)).sum();
+        assert!(
+            (value - expected).abs() < 1e-9 * (1.0 + expected.abs()),
+            "{} = {} at n = {}: {} vs {}",
+            latex,
+            expr,
+            n,
+            value,
+            expected
+        );
+    }
+
+    #[test]
+    fn geometric_by_consecutive_ratio() {
+        check_at(
+            "\\sum_{k=0}^{n} 3 \\cdot 2^{k+1}",
+            6.0,
+            |k| 3.0 * 2f64.powf(k + 1.0),
+            0,
+        );
+        check_at("\\sum_{k=1}^{n} \\frac{1}{3^k}", 7.0, |k| 3f64.powf(-k), 1);
+        check_at("\\sum_{k=1}^{n} x^{k-1}", 5.0, |k| 1.5f64.powf(k - 1.0), 1);
+        check_at("\\sum_{k=0}^{n} 2^{2k}", 4.0, |k| 4f64.powf(k), 0);
+        check_at(
+            "\\sum_{k=1}^{n} \\frac{5 \\cdot 3^{k}}{2^{k}}",
+            6.0,
+            |k| 5.0 * 1.5f64.powf(k),
+            1,
+        );
+        check_at("\\sum_{k=0}^{n} (-1)^k", 7.0, |k| (-1f64).powf(k), 0);
+        assert_eq!(
+            closed("\\sum_{k=0}^{n} 3 \\cdot 2^{k+1}").to_string(),
+            "6 \\cdot (2^{n + 1} - 1)"
+        );
+    }
+
+    #[test]
+    fn arithmetic_by_consecutive_difference() {
+        check_at(
+            "\\sum_{k=1}^{n} {\\frac{k}{x} + 2}",
+            9.0,
+            |k| k / 1.5 + 2.0,
+            1,
+        );
+    }
+
+    #[test]
+    fn odd_body_over_symmetric_range_is_zero() {
+        assert_eq!(closed("\\sum_{k=-n}^{n} \\sin(k)").to_string(), "0");
+        assert_eq!(closed("\\sum_{k=-n}^{n} k \\cos(k)").to_string(), "0");
+        // Not symmetric: left alone
+        assert!(matches!(
+            closed("\\sum_{k=-n}^{n+1} \\sin(k)"),
+            Node::Summation(..)
+        ));
+    }
+
+    #[test]
+    fn periodic_body_sums_one_cycle() {
+        // cos(πk/2) cycles 1, 0, −1, 0; 1001 terms leave one extra 1
+        assert_eq!(
+            closed("\\sum_{k=0}^{1000} \\cos(\\frac{\\pi k}{2})").to_string(),
+            "1"
+        );
+        assert_eq!(closed("\\sum_{k=1}^{100} 2\\cos(\\pi k)").to_string(), "0");
+        // sin(k) has no integer period and keeps its sum
+        assert!(matches!(
+            closed("\\sum_{k=1}^{100} \\sin(k)"),
+            Node::Summation(..)
+        ));
+    }
+}
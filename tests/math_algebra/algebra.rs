@@ -12,7 +12,7 @@ mod algebra_tests {
         // Tokenize and parse the input
         let tokens = tokenizer.tokenize(); // Call the instance method on tokenizer
         let parsed_expr = build_expression_tree(tokens)?;
-        Evaluator::evaluate(&parsed_expr, env)
+        Ok(Evaluator::evaluate(&parsed_expr, env)?)
     }
 
     // Helper function to evaluate LaTeX expression and return the result
@@ -301,6 +301,26 @@ mod algebra_tests {
         assert_eq!(result, 0.0); // 0.0 for false
     }
 
+    #[test]
+    fn test_logical_connectives() {
+        let mut env = Environment::new();
+        env.set("x", 4.0);
+        let inside = evaluate_expression_with_env("x > 0 && x < 10", &env).unwrap();
+        assert_eq!(inside, 1.0);
+        let outside = evaluate_expression_with_env("x > 5 \\land x < 10", &env).unwrap();
+        assert_eq!(outside, 0.0);
+        assert_eq!(evaluate_expression("1 > 2 || 2 > 1").unwrap(), 1.0);
+        assert_eq!(evaluate_expression("1 > 2 \\lor 3 < 2").unwrap(), 0.0);
+        assert_eq!(evaluate_expression("!(1 > 2)").unwrap(), 1.0);
+        assert_eq!(evaluate_expression("\\lnot 2 > 1").unwrap(), 0.0);
+        // Any nonzero operand is true; the result is always 0 or 1
+        assert_eq!(evaluate_expression("5 \\land 3").unwrap(), 1.0);
+        // `and` binds tighter than `or`
+        assert_eq!(evaluate_expression("1 \\lor 1 \\land 0").unwrap(), 1.0);
+        // `!` after an operand is still a factorial
+        assert_eq!(evaluate_expression("3! > 5").unwrap(), 1.0);
+    }
+
     #[test]
     fn test_combined_negative_numbers() {
         let result: f64 = evaluate_expression("5 + -3").unwrap();
@@ -950,14 +970,18 @@ mod algebra_tests {
     fn test_det_function() {
         let env = Environment::new();
 
-        // det(2, 3, 4) = 2 * 3 * 4 = 24
-        let result = evaluate_expression_with_env("\\det{2, 3, 4}", &env).unwrap();
-        assert!((result - 24.0).abs() < 1e-6);
+        // A determinant takes one matrix, not a list of numbers
+        assert!(evaluate_expression_with_env("\\det{2, 3, 4}", &env).is_err());
 
-        // det(5) = 5 (single argument)
+        // det(5) = 5: a number is a 1×1 matrix
         let result = evaluate_expression_with_env("\\det{5}", &env).unwrap();
         assert!((result - 5.0).abs() < 1e-6);
 
+        let result =
+            evaluate_expression_with_env("\\det\\begin{pmatrix}1&2\\\\3&4\\end{pmatrix}", &env)
+                .unwrap();
+        assert!((result + 2.0).abs() < 1e-6);
+
         // det() should panic or return an error
         assert!(evaluate_expression_with_env("\\det{}", &env).is_err());
     }
@@ -1118,6 +1142,67 @@ mod algebra_tests {
         assert_eq!(result.complex_omitted, 0);
     }
 
+    #[test]
+    fn test_double_root_is_not_counted_as_complex() {
+        // (x − 1)² = 0 has the single real root 1 twice, no complex roots
+        let expr = parse_eq("x^2 - 2x + 1 = 0");
+        let result = arithma::expression::solve_full(&expr, "x").unwrap();
+        assert_eq!(result.solutions.len(), 1);
+        assert_eq!(result.complex_omitted, 0);
+        assert!(result.complex_solutions.is_empty());
+    }
+
+    #[test]
+    fn test_complex_quadratic_roots_exact() {
+        let expr = parse_eq("x^2 + 2x + 5 = 0");
+        let result = arithma::expression::solve_full(&expr, "x").unwrap();
+        assert!(result.solutions.is_empty());
+        assert_eq!(result.complex_omitted, 2);
+        let roots: Vec<String> = result
+            .complex_solutions
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(roots, vec!["-1 + 2i", "-1 - 2i"]);
+    }
+
+    #[test]
+    fn test_complex_quadratic_roots_irrational() {
+        // x² + x + 1 = 0 → −1/2 ± (√3/2)i
+        let expr = parse_eq("x^2 + x + 1 = 0");
+        let result = arithma::expression::solve_full(&expr, "x").unwrap();
+        assert_eq!(result.complex_solutions.len(), 2);
+        assert!(result.complex_solutions[0]
+            .to_string()
+            .contains("\\sqrt{3}"));
+    }
+
+    #[test]
+    fn test_complex_roots_of_cubic_and_quartic() {
+        // x³ − 1: real root 1 plus the exact pair from x² + x + 1
+        let expr = parse_eq("x^3 - 1 = 0");
+        let result = arithma::expression::solve_full(&expr, "x").unwrap();
+        assert_eq!(result.solutions.len(), 1);
+        assert_eq!(result.complex_solutions.len(), 2);
+
+        // x⁴ + 1 is irreducible: four numeric roots ±√2/2 ± (√2/2)i
+        let expr = parse_eq("x^4 + 1 = 0");
+        let result = arithma::expression::solve_full(&expr, "x").unwrap();
+        assert_eq!(result.complex_omitted, 4);
+        assert_eq!(result.complex_solutions.len(), 4);
+        for root in &result.complex_solutions {
+            assert!(root.to_string().contains("0.7071067812"), "{}", root);
+        }
+    }
+
+    #[test]
+    fn test_quintic_complex_roots_not_listed() {
+        let expr = parse_eq("x^5 - x - 1 = 0");
+        let result = arithma::expression::solve_full(&expr, "x").unwrap();
+        assert!(result.complex_solutions.is_empty());
+        assert!(result.impossibility_reason.is_some());
+    }
+
     #[test]
     fn test_nth_root_cube() {
         let env = Environment::new();
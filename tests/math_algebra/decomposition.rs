@@ -0,0 +1,195 @@
+use arithma::decomposition::LuDecomposition;
+use arithma::matrix::{parse_latex_matrix, Matrix};
+use arithma::{Environment, ExactNum, Node};
+
+fn int(n: i64) -> ExactNum {
+    ExactNum::integer(n)
+}
+
+fn matrix(latex: &str) -> Matrix {
+    parse_latex_matrix(latex, &Environment::new()).unwrap()
+}
+
+/// The `n`×`n` matrix with 2 on the diagonal and -1 beside it
+fn tridiagonal(n: usize) -> Matrix {
+    let elements = (0..n * n)
+        .map(|k| {
+            let (i, j) = (k / n, k % n);
+            Node::Num(match i.abs_diff(j) {
+                0 => int(2),
+                1 => int(-1),
+                _ => int(0),
+            })
+        })
+        .collect();
+    Matrix::new(n, n, elements).unwrap()
+}
+
+#[test]
+fn test_lu_factors_reproduce_the_permuted_matrix() {
+    let env = Environment::new();
+    let a = matrix(r"\begin{pmatrix} 0 & 2 & 1 \\ 1 & 1 & 1 \\ 4 & 2 & 5 \end{pmatrix}");
+    let lu = a.lu(&env).unwrap().unwrap();
+    assert_eq!(lu.permutation(), &[2, 0, 1]);
+
+    let product = lu.lower().multiply(&lu.upper(), &env).unwrap();
+    let rows: Vec<Vec<Node>> = lu
+        .permutation()
+        .iter()
+        .map(|&i| a.elements[i * 3..i * 3 + 3].to_vec())
+        .collect();
+    let permuted = Matrix::from_elements(rows).unwrap();
+    assert!(product.equals(&permuted, &env));
+    assert_eq!(lu.determinant(), int(-4));
+}
+
+#[test]
+fn test_numeric_determinant_is_exact_and_fast() {
+    let env = Environment::new();
+    // Cofactor expansion would take 12! products
+    assert_eq!(
+        tridiagonal(12).determinant(&env).unwrap(),
+        Node::Num(int(13))
+    );
+
+    // The 5×5 Hilbert matrix, exactly
+    let hilbert = (0..25)
+        .map(|k| Node::Num(ExactNum::rational(1, (k / 5 + k % 5 + 1) as i64)))
+        .collect();
+    let hilbert = Matrix::new(5, 5, hilbert).unwrap();
+    assert_eq!(
+        hilbert.determinant(&env).unwrap(),
+        Node::Num(ExactNum::rational(1, 266_716_800_000))
+    );
+}
+
+#[test]
+fn test_numeric_inverse_and_solve() {
+    let env = Environment::new();
+    let a = tridiagonal(6);
+    let inverse = a.inverse(&env).unwrap();
+    assert!(a.multiply(&inverse, &env).unwrap().is_identity(&env));
+
+    let b = matrix(r"\begin{pmatrix} 1 \\ 0 \\ 0 \\ 0 \\ 0 \\ 1 \end{pmatrix}");
+    let x = a.solve(&b, &env).unwrap();
+    assert!(x.elements.iter().all(|e| *e == Node::Num(int(1))));
+}
+
+#[test]
+fn test_partial_pivoting_keeps_float_solutions_accurate() {
+    let env = Environment::new();
+    let float = |v: f64| Node::Num(ExactNum::Float(v));
+    let a = Matrix::new(2, 2, vec![float(1e-20), float(1.0), float(1.0), float(1.0)]).unwrap();
+    let b = Matrix::new(2, 1, vec![float(1.0), float(2.0)]).unwrap();
+    let x = a.solve(&b, &env).unwrap();
+    for element in &x.elements {
+        let Node::Num(n) = element else {
+            panic!("expected a number, got {}", element)
+        };
+        assert!((n.to_f64() - 1.0).abs() < 1e-12, "{}", x);
+    }
+}
+
+#[test]
+fn test_singular_matrices() {
+    let env = Environment::new();
+    let a = matrix(r"\begin{pmatrix} 1 & 2 & 3 \\ 4 & 5 & 6 \\ 7 & 8 & 9 \end{pmatrix}");
+    let lu = a.lu(&env).unwrap().unwrap();
+    assert!(lu.is_singular());
+    assert_eq!(a.determinant(&env).unwrap(), Node::Num(int(0)));
+    assert!(a.inverse(&env).is_err());
+    assert!(lu.solve(&[int(1), int(2), int(3)]).is_err());
+}
+
+#[test]
+fn test_symbolic_matrices_keep_cofactor_expansion() {
+    let env = Environment::new();
+    let a = matrix(r"\begin{pmatrix} x & 1 & 0 \\ 1 & x & 1 \\ 0 & 1 & x \end{pmatrix}");
+    assert!(a.lu(&env).unwrap().is_none());
+    let det = a.determinant(&env).unwrap();
+    let mut at_two = Environment::new();
+    at_two.set("x", 2.0);
+    assert_eq!(arithma::Evaluator::evaluate(&det, &at_two).unwrap(), 4.0);
+
+    assert!(LuDecomposition::new(2, vec![int(1)]).is_err());
+}
+
+fn assert_upper_triangular(r: &Matrix) {
+    for i in 0..r.rows {
+        for j in 0..i.min(r.cols) {
+            assert_eq!(r.elements[i * r.cols + j], Node::Num(int(0)), "{}", r);
+        }
+    }
+}
+
+#[test]
+fn test_qr_factors_are_orthogonal_and_triangular() {
+    let env = Environment::new();
+    for latex in [
+        r"\begin{pmatrix} 12 & -51 & 4 \\ 6 & 167 & -68 \\ -4 & 24 & -41 \end{pmatrix}",
+        r"\begin{pmatrix} 1 & 2 \\ 3 & 4 \\ 5 & 6 \end{pmatrix}",
+        r"\begin{pmatrix} 1 & 2 & 3 \\ 2 & 4 & 6 \end{pmatrix}",
+    ] {
+        let a = matrix(latex);
+        let (q, r) = a.qr(&env).unwrap();
+        assert_eq!((q.rows, q.cols), (a.rows, a.rows));
+        assert_eq!((r.rows, r.cols), (a.rows, a.cols));
+        assert!(q.multiply(&r, &env).unwrap().equals(&a, &env), "{}", latex);
+        assert!(q
+            .transpose()
+            .multiply(&q, &env)
+            .unwrap()
+            .equals(&Matrix::identity(a.rows), &env));
+        assert_upper_triangular(&r);
+    }
+
+    // The textbook example factors into whole numbers
+    let a = matrix(r"\begin{pmatrix} 12 & -51 & 4 \\ 6 & 167 & -68 \\ -4 & 24 & -41 \end{pmatrix}");
+    let (_, r) = a.qr(&env).unwrap();
+    let expected =
+        matrix(r"\begin{pmatrix} 14 & 21 & -14 \\ 0 & 175 & -70 \\ 0 & 0 & 35 \end{pmatrix}");
+    assert!(r.equals(&expected, &env), "{}", r);
+}
+
+#[test]
+fn test_cholesky_of_positive_definite_matrix() {
+    let env = Environment::new();
+    let a =
+        matrix(r"\begin{pmatrix} 4 & 12 & -16 \\ 12 & 37 & -43 \\ -16 & -43 & 98 \end{pmatrix}");
+    let l = a.cholesky(&env).unwrap();
+    let expected = matrix(r"\begin{pmatrix} 2 & 0 & 0 \\ 6 & 1 & 0 \\ -8 & 5 & 3 \end{pmatrix}");
+    assert_eq!(l.elements, expected.elements);
+
+    let a = matrix(r"\begin{pmatrix} 2 & 1 \\ 1 & 2 \end{pmatrix}");
+    let l = a.cholesky(&env).unwrap();
+    assert!(l.multiply(&l.transpose(), &env).unwrap().equals(&a, &env));
+    assert_eq!(l.elements[1], Node::Num(int(0)));
+}
+
+#[test]
+fn test_cholesky_and_qr_errors() {
+    let env = Environment::new();
+    let indefinite = matrix(r"\begin{pmatrix} 1 & 2 \\ 2 & 1 \end{pmatrix}");
+    let error = indefinite.cholesky(&env).unwrap_err();
+    assert!(error.to_string().contains("positive definite"), "{}", error);
+    assert!(error.to_string().contains("2×2"), "{}", error);
+
+    let singular = matrix(r"\begin{pmatrix} 1 & 1 \\ 1 & 1 \end{pmatrix}");
+    assert!(singular.cholesky(&env).is_err());
+
+    let unsymmetric = matrix(r"\begin{pmatrix} 4 & 1 \\ 2 & 3 \end{pmatrix}");
+    let error = unsymmetric.cholesky(&env).unwrap_err();
+    assert!(error.to_string().contains("symmetric"), "{}", error);
+
+    let rectangular = matrix(r"\begin{pmatrix} 1 & 2 \\ 3 & 4 \\ 5 & 6 \end{pmatrix}");
+    assert!(rectangular.cholesky(&env).is_err());
+
+    let symbolic = matrix(r"\begin{pmatrix} x & 1 \\ 1 & 2 \end{pmatrix}");
+    let error = symbolic.qr(&env).unwrap_err();
+    assert!(
+        error.to_string().contains("QR decomposition needs"),
+        "{}",
+        error
+    );
+    assert!(symbolic.cholesky(&env).is_err());
+}
@@ -0,0 +1,83 @@
+use arithma::factor::{factor, factor_latex, factor_polynomial};
+use arithma::simplify::Simplifiable;
+use arithma::{parse_latex_raw, Environment, Evaluator, Polynomial};
+
+#[test]
+fn test_factor_quadratic() {
+    assert_eq!(
+        factor_latex("x^2 + 5x + 6", "x").unwrap(),
+        "(x + 2) \\cdot (x + 3)"
+    );
+    assert_eq!(
+        factor_latex("6x^2 + 5x + 1", "x").unwrap(),
+        "(2x + 1) \\cdot (3x + 1)"
+    );
+}
+
+#[test]
+fn test_factor_common_and_repeated_factors() {
+    assert_eq!(
+        factor_latex("2x^3 - 2x", "x").unwrap(),
+        "2 \\cdot x \\cdot (x + 1) \\cdot (x - 1)"
+    );
+    assert_eq!(
+        factor_latex("x^3 + 3x^2 + 3x + 1", "x").unwrap(),
+        "(x + 1)^{3}"
+    );
+    assert_eq!(
+        factor_latex("-x^2 - 5x - 6", "x").unwrap(),
+        "-(x + 2) \\cdot (x + 3)"
+    );
+}
+
+#[test]
+fn test_factor_rational_coefficients_and_large_content() {
+    assert_eq!(
+        factor_latex("\\frac{1}{2}x^2 - \\frac{1}{2}", "x").unwrap(),
+        "\\frac{1}{2} \\cdot (x + 1) \\cdot (x - 1)"
+    );
+    // Content beyond i64 is kept exactly.
+    assert_eq!(
+        factor_latex("100000000000000000000x^2 + 100000000000000000000x", "x").unwrap(),
+        "100000000000000000000 \\cdot x \\cdot (x + 1)"
+    );
+}
+
+#[test]
+fn test_factor_irreducible() {
+    let poly = Polynomial::from_node(&parse_latex_raw("x^2 + 1").unwrap(), "x").unwrap();
+    let factorization = factor_polynomial(&poly);
+    assert!(factorization.is_irreducible());
+    assert_eq!(factor_latex("x^2 + 1", "x").unwrap(), "x^{2} + 1");
+
+    let squared = Polynomial::from_node(&parse_latex_raw("(x^2 + 1)^2").unwrap(), "x").unwrap();
+    assert!(!factor_polynomial(&squared).is_irreducible());
+}
+
+#[test]
+fn test_factor_multiplies_back() {
+    let env = Environment::new();
+    for latex in [
+        "x^4 - 1",
+        "x^3 - 6x^2 + 11x - 6",
+        "3x^5 - 3x",
+        "4x^2 - 12x + 9",
+    ] {
+        let expr = parse_latex_raw(latex).unwrap();
+        let factored = factor(&expr, "x").unwrap();
+        let difference = arithma::Node::Subtract(Box::new(factored), Box::new(expr));
+        let difference = difference.simplify(&env).unwrap();
+        for x in [-2.0, 0.5, 3.0] {
+            let mut point = Environment::new();
+            point.set("x", x);
+            let value = Evaluator::evaluate(&difference, &point).unwrap();
+            assert!(value.abs() < 1e-9, "{} at x={}: {}", latex, x, value);
+        }
+    }
+}
+
+#[test]
+fn test_factor_rejects_non_polynomials() {
+    assert!(factor_latex("\\sin(x)", "x").is_err());
+    assert!(factor_latex("x^2 y", "x").is_err());
+}
@@ -1,4 +1,10 @@
 mod algebra;
+mod decomposition;
+mod factor;
 mod matrix;
 mod matrix_basic;
 mod matrix_cdot_test;
+mod matrix_expression;
+mod poly_div;
+mod quaternion;
+mod vector;
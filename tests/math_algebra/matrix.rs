@@ -113,6 +113,36 @@ fn test_matrix_determinant() {
     assert_eq!(det_value, 1.0); // Should be 1.0
 }
 
+#[test]
+fn test_large_matrix_multiplication() {
+    // Large enough to run on several threads with the `parallel` feature
+    let env = Environment::default();
+    let n = 20;
+    let a = |i: usize, k: usize| (i * 3 + k) as i64 % 7 - 3;
+    let b = |k: usize, j: usize| (k + 2 * j) as i64 % 5 - 2;
+    let entries = |f: &dyn Fn(usize, usize) -> i64| {
+        (0..n * n)
+            .map(|e| Node::Num(ExactNum::integer(f(e / n, e % n))))
+            .collect()
+    };
+    let product = Matrix::new(n, n, entries(&a))
+        .unwrap()
+        .multiply(&Matrix::new(n, n, entries(&b)).unwrap(), &env)
+        .unwrap();
+    for i in 0..n {
+        for j in 0..n {
+            let expected: i64 = (0..n).map(|k| a(i, k) * b(k, j)).sum();
+            assert_eq!(
+                product.elements[i * n + j],
+                Node::Num(ExactNum::integer(expected)),
+                "entry ({}, {})",
+                i,
+                j
+            );
+        }
+    }
+}
+
 #[test]
 fn test_matrix_multiplication() {
     let env = Environment::default();
@@ -461,6 +491,208 @@ fn test_rref() {
     }
 }
 
+#[test]
+fn test_row_operations() {
+    let env = Environment::default();
+    let mut m =
+        parse_latex_matrix("\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}", &env).unwrap();
+
+    m.row_swap(0, 1).unwrap();
+    assert_eq!(m.get(0, 0).unwrap().to_string(), "3");
+    assert_eq!(m.get(1, 0).unwrap().to_string(), "1");
+
+    m.row_scale(1, &Node::Num(ExactNum::integer(2)), &env)
+        .unwrap();
+    assert_eq!(m.get(1, 1).unwrap().to_string(), "4");
+
+    // R1 → R1 + (-3/2)·R2 clears the first column: [3 4] - (3/2)[2 4] = [0 -2]
+    let factor = Node::Num(ExactNum::rational(-3, 2));
+    m.row_addmul(0, 1, &factor, &env).unwrap();
+    assert_eq!(m.get(0, 0).unwrap().to_string(), "0");
+    assert_eq!(m.get(0, 1).unwrap().to_string(), "-2");
+}
+
+#[test]
+fn test_row_operations_reject_invalid() {
+    let env = Environment::default();
+    let mut m = Matrix::identity(2);
+    assert!(m.row_swap(0, 2).is_err());
+    assert!(m.row_scale(0, &Node::Num(ExactNum::zero()), &env).is_err());
+    assert!(m
+        .row_addmul(1, 1, &Node::Num(ExactNum::one()), &env)
+        .is_err());
+}
+
+#[test]
+fn test_rref_with_steps_replays() {
+    let env = Environment::default();
+    let m = parse_latex_matrix(
+        "\\begin{pmatrix} 0 & 2 & 4 \\\\ 1 & 1 & 1 \\\\ 2 & 4 & 6 \\end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    let (rref, steps) = m.rref_with_steps(&env).unwrap();
+
+    // Zero pivot in row 1 forces a swap first.
+    assert!(matches!(steps[0], arithma::RowOp::Swap(1, 0)));
+    assert_eq!(steps[0].to_string(), "R_{2} \\leftrightarrow R_{1}");
+
+    let mut replayed = m.clone();
+    for step in &steps {
+        replayed.apply_row_op(step, &env).unwrap();
+    }
+    assert_eq!(replayed.to_latex(), rref.to_latex());
+    assert_eq!(rref.to_latex(), m.rref(&env).unwrap().to_latex());
+    assert_eq!(
+        rref.to_latex(),
+        "\\begin{pmatrix}\n1 & 0 & -1 \\\\\n0 & 1 & 2 \\\\\n0 & 0 & 0\n\\end{pmatrix}"
+    );
+}
+
+#[test]
+fn test_fundamental_subspaces() {
+    let env = Environment::default();
+    // Rank 2, 3×4: two free variables, one dependent row.
+    let m = parse_latex_matrix(
+        "\\begin{pmatrix} 1 & 2 & 0 & 1 \\\\ 0 & 0 & 1 & 3 \\\\ 1 & 2 & 1 & 4 \\end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+
+    let null = m.null_space(&env).unwrap();
+    assert_eq!(null.len(), 2);
+    for v in &null {
+        assert_eq!((v.rows, v.cols), (4, 1));
+        let product = m.multiply(v, &env).unwrap();
+        assert!(product.elements.iter().all(|e| e.to_string() == "0"));
+    }
+    let first: Vec<String> = null[0].elements.iter().map(|e| e.to_string()).collect();
+    assert_eq!(first, ["-2", "1", "0", "0"]);
+
+    // Pivot columns 0 and 2 of the original matrix.
+    let cols = m.column_space(&env).unwrap();
+    assert_eq!(cols.len(), 2);
+    let c1: Vec<String> = cols[1].elements.iter().map(|e| e.to_string()).collect();
+    assert_eq!(c1, ["0", "1", "1"]);
+
+    assert_eq!(m.row_space(&env).unwrap().len(), 2);
+    let left = m.left_null_space(&env).unwrap();
+    assert_eq!(left.len(), 1);
+    let check = m.transpose().multiply(&left[0], &env).unwrap();
+    assert!(check.elements.iter().all(|e| e.to_string() == "0"));
+}
+
+#[test]
+fn test_null_space_full_rank_is_empty() {
+    let env = Environment::default();
+    let m = Matrix::identity(3);
+    assert!(m.null_space(&env).unwrap().is_empty());
+    assert_eq!(m.column_space(&env).unwrap().len(), 3);
+}
+
+#[test]
+fn test_matrix_equals() {
+    let env = Environment::default();
+    let a = parse_latex_matrix(
+        "\\begin{pmatrix} (x+1)^2 & \\frac{1}{3} \\\\ 0 & 1 \\end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    let mut b = parse_latex_matrix(
+        "\\begin{pmatrix} x^2+2x+1 & 0 \\\\ 0 & 1 \\end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    // A float entry off from 1/3 by rounding-level error.
+    b.set(0, 1, Node::Num(ExactNum::Float(1.0 / 3.0 + 1e-12)))
+        .unwrap();
+    assert!(a.equals(&b, &env));
+    assert!(!a.approx_equals(&b, 1e-15, &env));
+
+    let c = parse_latex_matrix(
+        "\\begin{pmatrix} x^2+2x & \\frac{1}{3} \\\\ 0 & 1 \\end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    assert!(!a.equals(&c, &env));
+    assert!(!a.equals(&Matrix::identity(3), &env));
+}
+
+#[test]
+fn test_matrix_predicates() {
+    let env = Environment::default();
+    assert!(Matrix::identity(3).is_identity(&env));
+    assert!(Matrix::identity(3).is_orthogonal(&env));
+
+    let symmetric =
+        parse_latex_matrix("\\begin{pmatrix} 1 & a \\\\ a & 2 \\end{pmatrix}", &env).unwrap();
+    assert!(symmetric.is_symmetric(&env));
+    assert!(!symmetric.is_identity(&env));
+
+    let rotation = parse_latex_matrix(
+        "\\begin{pmatrix} \\frac{3}{5} & -\\frac{4}{5} \\\\ \\frac{4}{5} & \\frac{3}{5} \\end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    assert!(rotation.is_orthogonal(&env));
+    assert!(!rotation.is_symmetric(&env));
+
+    let shear =
+        parse_latex_matrix("\\begin{pmatrix} 1 & 1 \\\\ 0 & 1 \\end{pmatrix}", &env).unwrap();
+    assert!(!shear.is_orthogonal(&env));
+}
+
+#[test]
+fn test_matrix_evaluate_at() {
+    let env = Environment::new();
+    // Jacobian of (x², x·y) with respect to (x, y).
+    let jacobian =
+        parse_latex_matrix(r"\begin{pmatrix} 2x & 0 \\ y & x \end{pmatrix}", &env).unwrap();
+
+    // Partial substitution keeps the remaining variable symbolic.
+    let at_x = jacobian
+        .substitute("x", &Node::Num(ExactNum::integer(3)), &env)
+        .unwrap();
+    assert_eq!(format!("{}", at_x.get(0, 0).unwrap()), "6");
+    assert_eq!(format!("{}", at_x.get(1, 0).unwrap()), "y");
+
+    // Unbound variables are reported, bound ones come from the environment.
+    let err = jacobian
+        .evaluate_at("x", &Node::Num(ExactNum::integer(3)), &env)
+        .unwrap_err();
+    assert!(err.to_string().contains("(1, 0)"), "{}", err);
+    let mut with_y = Environment::new();
+    with_y.set("y", 2.0);
+    let numeric = jacobian
+        .evaluate_at("x", &Node::Num(ExactNum::integer(3)), &with_y)
+        .unwrap();
+    let values: Vec<f64> = numeric
+        .elements
+        .iter()
+        .map(|e| Evaluator::evaluate(e, &with_y).unwrap())
+        .collect();
+    assert_eq!(values, vec![6.0, 0.0, 2.0, 3.0]);
+}
+
+#[test]
+fn test_matrix_evaluate_at_exact_and_transcendental() {
+    let env = Environment::new();
+    let m =
+        parse_latex_matrix(r"\begin{pmatrix} \frac{x}{3} & \sin(x) \end{pmatrix}", &env).unwrap();
+    let at_one = m
+        .evaluate_at("x", &Node::Num(ExactNum::integer(1)), &env)
+        .unwrap();
+    // Exact where exact, floating point where no closed form exists.
+    assert!(matches!(
+        at_one.get(0, 0).unwrap(),
+        Node::Num(n) if *n == ExactNum::rational(1, 3)
+    ));
+    match at_one.get(0, 1).unwrap() {
+        Node::Num(ExactNum::Float(v)) => assert!((v - 1f64.sin()).abs() < 1e-12),
+        other => panic!("expected a float entry, got {:?}", other),
+    }
+}
+
 // ── Assumption-aware eigenvalues ────────────────────────────
 
 #[test]
@@ -618,3 +850,128 @@ fn eigenvalues_repeated_real_still_work() {
         );
     }
 }
+
+// ── Characteristic polynomial and eigenvectors ──────────────
+
+#[test]
+fn test_char_poly_symbolic() {
+    let env = Environment::new();
+    let m = parse_latex_matrix(r"\begin{pmatrix} a & b \\ c & d \end{pmatrix}", &env).unwrap();
+    assert_eq!(
+        m.char_poly("\\lambda", &env).unwrap().to_string(),
+        "\\lambda^{2} - (a + d) \\cdot \\lambda + d \\cdot a - c \\cdot b"
+    );
+    let rotation =
+        parse_latex_matrix(r"\begin{pmatrix} 0 & 1 \\ -k & 0 \end{pmatrix}", &env).unwrap();
+    assert_eq!(
+        rotation.char_poly("t", &env).unwrap().to_string(),
+        "t^{2} + k"
+    );
+}
+
+#[test]
+fn test_char_poly_numeric_3x3() {
+    let env = Environment::new();
+    let m = parse_latex_matrix(
+        r"\begin{pmatrix} 1 & 2 & 3 \\ 4 & 5 & 6 \\ 7 & 8 & 10 \end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    let poly = m.char_poly("x", &env).unwrap();
+    assert_eq!(poly.to_string(), "x^{3} - 16x^{2} - 12x + 3");
+    for value in m.eigenvalues(&env).unwrap() {
+        let mut at = Environment::new();
+        at.set("x", Evaluator::evaluate(&value, &env).unwrap());
+        assert!(Evaluator::evaluate(&poly, &at).unwrap().abs() < 1e-8);
+    }
+}
+
+/// Check `Av = λv` for every eigenpair, with the variables set to `values`.
+fn assert_eigenpairs(m: &Matrix, values: &[(&str, f64)]) {
+    let env = Environment::new();
+    let mut at = Environment::new();
+    for (name, value) in values {
+        at.set(name, *value);
+    }
+    let decomposition = m.eigen_decomposition(&env).unwrap();
+    for pair in &decomposition.pairs {
+        let lambda = Evaluator::evaluate(&pair.value, &at).unwrap();
+        for v in &pair.vectors {
+            let av = m.multiply(v, &env).unwrap();
+            let mut size = 0.0f64;
+            for (x, y) in av.elements.iter().zip(&v.elements) {
+                let (x, y) = (
+                    Evaluator::evaluate(x, &at).unwrap(),
+                    Evaluator::evaluate(y, &at).unwrap(),
+                );
+                assert!((x - lambda * y).abs() < 1e-9, "λ = {}", pair.value);
+                size = size.max(y.abs());
+            }
+            assert!(size > 1e-9, "zero eigenvector for λ = {}", pair.value);
+        }
+    }
+}
+
+#[test]
+fn test_eigen_decomposition_symbolic_2x2() {
+    let env = Environment::new();
+    let m = parse_latex_matrix(r"\begin{pmatrix} a & b \\ c & d \end{pmatrix}", &env).unwrap();
+    assert_eigenpairs(&m, &[("a", 1.0), ("b", 2.0), ("c", 3.0), ("d", 4.0)]);
+    assert_eigenpairs(&m, &[("a", -2.0), ("b", 0.5), ("c", 7.0), ("d", 1.0)]);
+    assert!(m.eigen_decomposition(&env).unwrap().is_diagonalizable());
+}
+
+#[test]
+fn test_eigen_decomposition_diagonalizes() {
+    let env = Environment::new();
+    let m = parse_latex_matrix(r"\begin{pmatrix} 2 & 1 \\ 1 & 2 \end{pmatrix}", &env).unwrap();
+    let decomposition = m.eigen_decomposition(&env).unwrap();
+    let p = decomposition.eigenvector_matrix().unwrap();
+    let d = decomposition.diagonal().unwrap();
+    // AP = PD
+    let ap = m.multiply(&p, &env).unwrap();
+    let pd = p.multiply(&d, &env).unwrap();
+    assert!(ap.equals(&pd, &env));
+
+    let repeated = parse_latex_matrix(
+        r"\begin{pmatrix} 1 & 1 & 0 \\ 1 & 1 & 0 \\ 0 & 0 & 2 \end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    assert_eigenpairs(&repeated, &[]);
+    let decomposition = repeated.eigen_decomposition(&env).unwrap();
+    assert!(decomposition
+        .pairs
+        .iter()
+        .any(|pair| pair.multiplicity == 2 && pair.vectors.len() == 2));
+}
+
+#[test]
+fn test_eigen_decomposition_defective() {
+    let env = Environment::new();
+    let jordan = parse_latex_matrix(r"\begin{pmatrix} 2 & 1 \\ 0 & 2 \end{pmatrix}", &env).unwrap();
+    let decomposition = jordan.eigen_decomposition(&env).unwrap();
+    assert_eq!(decomposition.pairs.len(), 1);
+    assert_eq!(decomposition.pairs[0].multiplicity, 2);
+    assert_eq!(decomposition.pairs[0].vectors.len(), 1);
+    assert!(!decomposition.is_diagonalizable());
+    assert!(decomposition.eigenvector_matrix().is_none());
+}
+
+#[test]
+fn test_eigen_decomposition_3x3() {
+    let env = Environment::new();
+    let m = parse_latex_matrix(
+        r"\begin{pmatrix} 1 & 2 & 3 \\ 4 & 5 & 6 \\ 7 & 8 & 10 \end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    assert_eigenpairs(&m, &[]);
+    let upper = parse_latex_matrix(
+        r"\begin{pmatrix} a & 1 & 0 \\ 0 & 2 & 0 \\ 0 & 0 & 3 \end{pmatrix}",
+        &env,
+    )
+    .unwrap();
+    assert_eigenpairs(&upper, &[("a", 5.0)]);
+    assert!(Matrix::identity(4).eigen_decomposition(&env).is_err());
+}
@@ -0,0 +1,159 @@
+use arithma::matrix::Matrix;
+use arithma::simplify::Simplifiable;
+use arithma::{
+    parse_latex, parse_latex_raw, Environment, Evaluator, Node, Session, SessionOutcome,
+};
+
+const A: &str = r"\begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}";
+const B: &str = r"\begin{pmatrix} 0 & 1 \\ 1 & 0 \end{pmatrix}";
+
+fn simplified(latex: &str) -> String {
+    parse_latex_raw(latex)
+        .unwrap()
+        .simplify(&Environment::new())
+        .unwrap()
+        .to_latex()
+}
+
+#[test]
+fn test_matrix_literal_parses_to_node() {
+    let node = parse_latex_raw(A).unwrap();
+    assert!(matches!(&node, Node::Matrix(rows) if rows.len() == 2 && rows[0].len() == 2));
+    assert_eq!(
+        node.to_latex(),
+        r"\begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}"
+    );
+    assert_eq!(parse_latex_raw(&node.to_latex()).unwrap(), node);
+
+    let matrix = Matrix::from_node(&node).unwrap();
+    assert_eq!((matrix.rows, matrix.cols), (2, 2));
+    assert_eq!(matrix.to_node(), node);
+
+    // A single column stays a vector literal; a single row is a 1×n matrix
+    let column = parse_latex_raw(r"\begin{pmatrix} 1 \\ 2 \end{pmatrix}").unwrap();
+    assert!(matches!(column, Node::Function(ref name, _) if name == "vector"));
+    let row = parse_latex_raw(r"\begin{pmatrix} 1 & 2 \end{pmatrix}").unwrap();
+    assert!(matches!(&row, Node::Matrix(rows) if rows.len() == 1 && rows[0].len() == 2));
+}
+
+#[test]
+fn test_row_matrices_keep_their_orientation() {
+    const ROW: &str = r"\begin{pmatrix} 1 & 2 \end{pmatrix}";
+    const COLUMN: &str = r"\begin{pmatrix} 1 \\ 2 \end{pmatrix}";
+    assert_eq!(
+        simplified(&format!(r"{} \cdot {}", ROW, A)),
+        r"\begin{pmatrix} 7 & 10 \end{pmatrix}"
+    );
+    // Column times row is the outer product, not a dot product
+    assert_eq!(
+        simplified(&format!(
+            r"{} \cdot \begin{{pmatrix}} 3 & 4 \end{{pmatrix}}",
+            COLUMN
+        )),
+        r"\begin{pmatrix} 3 & 4 \\ 6 & 8 \end{pmatrix}"
+    );
+    assert_eq!(
+        simplified(&format!("{}^T", ROW)),
+        r"\begin{pmatrix} 1 \\ 2 \end{pmatrix}"
+    );
+    assert_eq!(simplified(r"\det\begin{pmatrix} 5 \end{pmatrix}"), "5");
+    // A 2×2 matrix cannot multiply a 1×2 row
+    let env = Environment::new();
+    let error = parse_latex_raw(&format!(r"{} \cdot {}", A, ROW))
+        .unwrap()
+        .simplify(&env)
+        .unwrap_err();
+    assert!(error.contains("dimensions"), "{}", error);
+}
+
+#[test]
+fn test_matrix_arithmetic() {
+    assert_eq!(
+        simplified(&format!("{} + {}", A, B)),
+        r"\begin{pmatrix} 1 & 3 \\ 4 & 4 \end{pmatrix}"
+    );
+    assert_eq!(
+        simplified(&format!("2{} - {}", A, B)),
+        r"\begin{pmatrix} 2 & 3 \\ 5 & 8 \end{pmatrix}"
+    );
+    assert_eq!(
+        simplified(&format!("{} \\cdot {}", A, B)),
+        r"\begin{pmatrix} 2 & 1 \\ 4 & 3 \end{pmatrix}"
+    );
+    assert_eq!(
+        simplified(&format!("x{}", B)),
+        r"\begin{pmatrix} 0 & x \\ x & 0 \end{pmatrix}"
+    );
+    assert_eq!(
+        simplified(&format!(r"\frac{{{}}}{{2}}", A)),
+        r"\begin{pmatrix} \frac{1}{2} & 1 \\ \frac{3}{2} & 2 \end{pmatrix}"
+    );
+    // A matrix times a column vector is a vector
+    assert_eq!(
+        simplified(&format!(r"{} \begin{{pmatrix}} 1 \\ 2 \end{{pmatrix}}", A)),
+        r"\begin{pmatrix} 5 \\ 11 \end{pmatrix}"
+    );
+}
+
+#[test]
+fn test_matrix_transpose_powers_and_inverse() {
+    assert_eq!(
+        simplified(&format!("{}^T", A)),
+        r"\begin{pmatrix} 1 & 3 \\ 2 & 4 \end{pmatrix}"
+    );
+    assert_eq!(
+        simplified(&format!("{}^{{2}}", A)),
+        r"\begin{pmatrix} 7 & 10 \\ 15 & 22 \end{pmatrix}"
+    );
+    assert_eq!(
+        simplified(&format!("{}^{{-1}}", A)),
+        r"\begin{pmatrix} -2 & 1 \\ \frac{3}{2} & -\frac{1}{2} \end{pmatrix}"
+    );
+    assert_eq!(
+        simplified(&format!("{}^{{0}}", A)),
+        r"\begin{pmatrix} 1 & 0 \\ 0 & 1 \end{pmatrix}"
+    );
+}
+
+#[test]
+fn test_matrix_errors() {
+    let env = Environment::new();
+    let error = |latex: String| parse_latex_raw(&latex).unwrap().simplify(&env).unwrap_err();
+    assert!(error(format!("{} + 1", A)).contains("scalar"));
+    assert!(error(format!(
+        r"{} + \begin{{pmatrix}} 1 & 2 & 3 \\ 4 & 5 & 6 \end{{pmatrix}}",
+        A
+    ))
+    .contains("dimensions"));
+    assert!(error(format!(r"\frac{{1}}{{{}}}", A)).contains("inverse"));
+    assert!(error(format!(r"{}^{{\frac{{1}}{{2}}}}", A)).contains("integer"));
+
+    // A matrix has no numeric value, but its determinant does
+    assert!(Evaluator::evaluate(&parse_latex(A, &env).unwrap(), &env).is_err());
+    let det = parse_latex_raw(&format!(r"\det {}", A)).unwrap();
+    assert_eq!(Evaluator::evaluate(&det, &env).unwrap(), -2.0);
+}
+
+#[test]
+fn test_matrix_session() {
+    let mut session = Session::new();
+    session.execute(&format!("A = {}", A)).unwrap();
+    session.execute(&format!("B = {}", B)).unwrap();
+    let value = |session: &mut Session, latex: &str| match session.execute(latex).unwrap() {
+        SessionOutcome::Evaluated(node) => node.to_latex(),
+        other => panic!("expected a value, got {:?}", other),
+    };
+    assert_eq!(
+        value(&mut session, "A + B"),
+        r"\begin{pmatrix} 1 & 3 \\ 4 & 4 \end{pmatrix}"
+    );
+    assert_eq!(
+        value(&mut session, "A B - B A"),
+        r"\begin{pmatrix} -1 & -3 \\ 3 & 1 \end{pmatrix}"
+    );
+    assert_eq!(
+        value(&mut session, "A^T"),
+        r"\begin{pmatrix} 1 & 3 \\ 2 & 4 \end{pmatrix}"
+    );
+    assert_eq!(value(&mut session, r"3\det A + 1"), "-5");
+}
@@ -0,0 +1,51 @@
+use arithma::simplify::Simplifiable;
+use arithma::{parse_latex_raw, poly_div, poly_div_latex, ArithmaError, Environment, Node};
+
+#[test]
+fn test_poly_div_with_remainder() {
+    assert_eq!(
+        poly_div_latex("x^3+2x+1", "x-1", "x").unwrap(),
+        ("x^{2} + x + 3".to_string(), "4".to_string())
+    );
+    assert_eq!(
+        poly_div_latex("x^4-1", "x^2+1", "x").unwrap(),
+        ("x^{2} - 1".to_string(), "0".to_string())
+    );
+    // A divisor of higher degree leaves the numerator as the remainder
+    assert_eq!(
+        poly_div_latex("3t", "t^2", "t").unwrap(),
+        ("0".to_string(), "3t".to_string())
+    );
+}
+
+#[test]
+fn test_poly_div_multiplies_back() {
+    let numerator = parse_latex_raw("2x^4 - 3x^2 + \\frac{1}{2}x - 7").unwrap();
+    let denominator = parse_latex_raw("2x^2 + x - 1").unwrap();
+    let (quotient, remainder) = poly_div(&numerator, &denominator, "x").unwrap();
+    let env = Environment::new();
+    let back = Node::Subtract(
+        Box::new(Node::Add(
+            Box::new(Node::Multiply(Box::new(quotient), Box::new(denominator))),
+            Box::new(remainder),
+        )),
+        Box::new(numerator),
+    );
+    assert_eq!(back.simplify(&env).unwrap().to_string(), "0");
+}
+
+#[test]
+fn test_poly_div_errors() {
+    assert_eq!(
+        poly_div_latex("x^2", "0", "x").unwrap_err(),
+        ArithmaError::InvalidArgument("Division by zero polynomial".to_string())
+    );
+    assert!(matches!(
+        poly_div_latex("\\sin(x)", "x", "x").unwrap_err(),
+        ArithmaError::InvalidArgument(message) if message.starts_with("Not a polynomial")
+    ));
+    assert!(matches!(
+        poly_div_latex("x^", "x", "x").unwrap_err(),
+        ArithmaError::Parse(_)
+    ));
+}
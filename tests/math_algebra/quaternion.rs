@@ -0,0 +1,91 @@
+use arithma::quaternion::{parse_latex_quaternion, Quaternion};
+use arithma::{parse_latex_raw, Environment, ExactNum, Node};
+
+fn q(latex: &str) -> Quaternion {
+    parse_latex_quaternion(latex, &Environment::new()).unwrap()
+}
+
+fn int(n: i64) -> Node {
+    Node::Num(ExactNum::integer(n))
+}
+
+#[test]
+fn test_quaternion_parse_and_display() {
+    let env = Environment::new();
+    assert_eq!(q("1 + 2i + 3j + 4k").to_latex(), "1 + 2i + 3j + 4k");
+    assert_eq!(q("2(i+j) - k").to_latex(), "2i + 2j - k");
+    assert_eq!(q("-1 - i").to_latex(), "-1 - i");
+    assert_eq!(q("0").to_latex(), "0");
+
+    // Output parses back to the same quaternion.
+    let symbolic = q("a + b \\cdot i - c \\cdot j");
+    let reparsed = parse_latex_quaternion(&symbolic.to_latex(), &env).unwrap();
+    assert_eq!(reparsed.to_latex(), symbolic.to_latex());
+}
+
+#[test]
+fn test_quaternion_parse_rejects_nonlinear_units() {
+    let env = Environment::new();
+    assert!(parse_latex_quaternion("i^2", &env).is_err());
+    assert!(parse_latex_quaternion("i \\cdot j", &env).is_err());
+}
+
+#[test]
+fn test_quaternion_hamilton_product() {
+    let env = Environment::new();
+    let product = q("1 + 2i + 3j + 4k")
+        .multiply(&q("5 + 6i + 7j + 8k"), &env)
+        .unwrap();
+    assert_eq!(product.to_latex(), "-60 + 12i + 30j + 24k");
+
+    // ij = k but ji = -k.
+    assert_eq!(q("i").multiply(&q("j"), &env).unwrap().to_latex(), "k");
+    assert_eq!(q("j").multiply(&q("i"), &env).unwrap().to_latex(), "-k");
+    assert_eq!(q("i").multiply(&q("i"), &env).unwrap().to_latex(), "-1");
+}
+
+#[test]
+fn test_quaternion_conjugate_norm_inverse() {
+    let env = Environment::new();
+    let a = q("1 + 2i + 3j + 4k");
+    assert_eq!(a.conjugate(&env).unwrap().to_latex(), "1 - 2i - 3j - 4k");
+    assert_eq!(format!("{}", a.norm_squared(&env).unwrap()), "30");
+    assert_eq!(format!("{}", a.norm(&env).unwrap()), "\\sqrt{30}");
+
+    let identity = a.multiply(&a.inverse(&env).unwrap(), &env).unwrap();
+    assert_eq!(identity.to_latex(), Quaternion::identity().to_latex());
+    assert!(q("0").inverse(&env).is_err());
+
+    let symbolic = q("a + b \\cdot i + c \\cdot j + d \\cdot k");
+    let norm_sq = symbolic.norm_squared(&env).unwrap();
+    let product = symbolic
+        .multiply(&symbolic.conjugate(&env).unwrap(), &env)
+        .unwrap();
+    assert_eq!(product.to_latex(), format!("{}", norm_sq));
+}
+
+#[test]
+fn test_quaternion_rotation() {
+    let env = Environment::new();
+    let quarter_turn_z = Quaternion::from_axis_angle(
+        [int(0), int(0), int(1)],
+        parse_latex_raw("\\frac{\\pi}{2}").unwrap(),
+        &env,
+    )
+    .unwrap();
+    let rotated = quarter_turn_z
+        .rotate_vector(&[int(1), int(0), int(0)], &env)
+        .unwrap();
+    let rendered: Vec<String> = rotated.iter().map(|c| format!("{}", c)).collect();
+    assert_eq!(rendered, ["0", "1", "0"]);
+
+    // A non-unit quaternion rotates the same way: its scale cancels.
+    let scaled = quarter_turn_z.scale(&int(3), &env).unwrap();
+    let rotated = scaled
+        .rotate_vector(&[int(0), int(1), int(0)], &env)
+        .unwrap();
+    let rendered: Vec<String> = rotated.iter().map(|c| format!("{}", c)).collect();
+    assert_eq!(rendered, ["-1", "0", "0"]);
+
+    assert!(Quaternion::from_axis_angle([int(0), int(0), int(0)], int(1), &env).is_err());
+}
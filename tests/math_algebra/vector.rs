@@ -0,0 +1,168 @@
+use arithma::simplify::Simplifiable;
+use arithma::vector::{parse_latex_vector, Vector};
+use arithma::{parse_latex, parse_latex_raw, Environment, Evaluator, ExactNum, Node, Session};
+
+fn v(latex: &str) -> Vector {
+    parse_latex_vector(latex, &Environment::new()).unwrap()
+}
+
+fn int(n: i64) -> Node {
+    Node::Num(ExactNum::integer(n))
+}
+
+#[test]
+fn test_vector_parse_and_display() {
+    let column = v(r"\begin{pmatrix} 1 \\ 2 \\ 3 \end{pmatrix}");
+    assert_eq!(column.components, vec![int(1), int(2), int(3)]);
+    assert_eq!(column.to_string(), "(1, 2, 3)");
+    assert_eq!(
+        column.to_latex(),
+        r"\begin{pmatrix} 1 \\ 2 \\ 3 \end{pmatrix}"
+    );
+
+    // A row reads as the same vector
+    assert_eq!(v(r"\begin{bmatrix} 1 & 2 & 3 \end{bmatrix}"), column);
+    assert!(parse_latex_vector(
+        r"\begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}",
+        &Environment::new()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_vector_dot_cross_and_norm() {
+    let env = Environment::new();
+    let a = v(r"\begin{pmatrix} 1 \\ 2 \\ 3 \end{pmatrix}");
+    let b = v(r"\begin{pmatrix} 4 \\ 5 \\ 6 \end{pmatrix}");
+    assert_eq!(a.dot(&b, &env).unwrap(), int(32));
+    assert_eq!(a.cross(&b, &env).unwrap().to_string(), "(-3, 6, -3)");
+    assert_eq!(a.norm(&env).unwrap().to_latex(), r"\sqrt{14}");
+
+    let c = v(r"\begin{pmatrix} 1 \\ 2 \end{pmatrix}");
+    assert!(a.dot(&c, &env).is_err());
+    assert!(c.cross(&c, &env).is_err());
+}
+
+#[test]
+fn test_vector_symbolic_components() {
+    let env = Environment::new();
+    let a = v(r"\begin{pmatrix} x \\ y \\ 0 \end{pmatrix}");
+    let k = v(r"\begin{pmatrix} 0 \\ 0 \\ 1 \end{pmatrix}");
+    assert_eq!(a.dot(&k, &env).unwrap(), int(0));
+    assert_eq!(a.norm(&env).unwrap().to_latex(), r"\sqrt{x^{2} + y^{2}}");
+}
+
+#[test]
+fn test_vector_normalize_and_projection() {
+    let env = Environment::new();
+    let a = v(r"\begin{pmatrix} 3 \\ 4 \end{pmatrix}");
+    let x = v(r"\begin{pmatrix} 2 \\ 0 \end{pmatrix}");
+    assert_eq!(
+        a.normalize(&env).unwrap().to_latex(),
+        r"\begin{pmatrix} \frac{3}{5} \\ \frac{4}{5} \end{pmatrix}"
+    );
+    assert_eq!(a.scalar_projection(&x, &env).unwrap(), int(3));
+    assert_eq!(a.projection(&x, &env).unwrap().to_string(), "(3, 0)");
+
+    let zero = v(r"\begin{pmatrix} 0 \\ 0 \end{pmatrix}");
+    assert!(zero.normalize(&env).is_err());
+    assert!(a.projection(&zero, &env).is_err());
+}
+
+#[test]
+fn test_vector_products_parse_to_functions() {
+    let env = Environment::new();
+    let dot = parse_latex_raw(r"\vec{a} \cdot \vec{b}").unwrap();
+    assert!(matches!(&dot, Node::Function(name, args) if name == "dot" && args.len() == 2));
+    let cross = parse_latex_raw(r"\vec{a} \times \vec{b}").unwrap();
+    assert!(matches!(&cross, Node::Function(name, _) if name == "cross"));
+    assert_eq!(cross.to_latex(), r"\vec{a} \times \vec{b}");
+
+    // Between numbers both are still multiplication
+    assert_eq!(parse_latex(r"4 \times 2", &env).unwrap(), int(8));
+    assert_eq!(parse_latex(r"3 \cdot 2", &env).unwrap(), int(6));
+}
+
+#[test]
+fn test_vector_literal_products_simplify() {
+    let env = Environment::new();
+    let dot = r"\begin{pmatrix} 1 \\ 2 \\ 3 \end{pmatrix} \cdot \begin{pmatrix} 4 \\ 5 \\ 6 \end{pmatrix}";
+    assert_eq!(parse_latex(dot, &env).unwrap(), int(32));
+    let cross = r"\begin{pmatrix} 1 \\ 0 \\ 0 \end{pmatrix} \times \begin{pmatrix} 0 \\ 1 \\ 0 \end{pmatrix}";
+    assert_eq!(
+        parse_latex(cross, &env).unwrap().to_latex(),
+        r"\begin{pmatrix} 0 \\ 0 \\ 1 \end{pmatrix}"
+    );
+    // Bars around a column are its norm, around a square matrix its determinant
+    let norm = r"\left|\begin{pmatrix} 3 \\ 4 \end{pmatrix}\right|";
+    assert_eq!(parse_latex(norm, &env).unwrap(), int(5));
+    let norm = r"\|\begin{pmatrix} 3 \\ 4 \end{pmatrix}\|";
+    assert_eq!(parse_latex(norm, &env).unwrap(), int(5));
+    let det = r"|\begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}|";
+    assert_eq!(parse_latex(det, &env).unwrap(), int(-2));
+}
+
+#[test]
+fn test_vector_evaluate_with_bound_names() {
+    let mut env = Environment::new();
+    env.set_list("a", &[1.0, 2.0, 3.0]);
+    env.set_list("b", &[4.0, 5.0, 6.0]);
+    let eval = |latex: &str| Evaluator::evaluate(&parse_latex_raw(latex).unwrap(), &env);
+
+    assert_eq!(eval(r"\vec{a} \cdot \vec{b}").unwrap(), 32.0);
+    assert_eq!(eval(r"\vec{a} \cdot (\vec{b} - \vec{a})").unwrap(), 18.0);
+    assert_eq!(
+        eval(r"(\vec{a} \times \vec{b}) \cdot \vec{a}").unwrap(),
+        0.0
+    );
+    assert_eq!(eval(r"(2\vec{a}) \cdot \vec{a}").unwrap(), 28.0);
+    assert!((eval(r"|\vec{a}|").unwrap() - 14f64.sqrt()).abs() < 1e-12);
+    // Double bars are the same norm
+    for norm in [
+        r"\|\vec{a}\|",
+        r"\left\|\vec{a}\right\|",
+        r"\Vert\vec{a}\Vert",
+        r"\left\Vert\vec{a}\right\Vert",
+        r"\lVert\vec{a}\rVert",
+    ] {
+        assert!(
+            (eval(norm).unwrap() - 14f64.sqrt()).abs() < 1e-12,
+            "{}",
+            norm
+        );
+    }
+    assert!((eval(r"2\|\vec{a}\| + 1").unwrap() - (2.0 * 14f64.sqrt() + 1.0)).abs() < 1e-12);
+    // A vector is not a number
+    assert!(eval(r"\vec{a}")
+        .unwrap_err()
+        .to_string()
+        .contains("is a vector"));
+    assert!(eval(r"\vec{c} \cdot \vec{a}").is_err());
+
+    let cross = parse_latex_raw(r"\vec{a} \times \vec{b}").unwrap();
+    assert_eq!(
+        cross.simplify(&env).unwrap().to_string(),
+        r"\begin{pmatrix} -3 \\ 6 \\ -3 \end{pmatrix}"
+    );
+}
+
+#[test]
+fn test_vector_session() {
+    let mut session = Session::new();
+    session
+        .execute(r"a = \begin{pmatrix} 1 \\ 2 \\ 3 \end{pmatrix}")
+        .unwrap();
+    session
+        .execute(r"b = \begin{pmatrix} 4 \\ 5 \\ 6 \end{pmatrix}")
+        .unwrap();
+    let value = |session: &mut Session, latex: &str| match session.execute(latex).unwrap() {
+        arithma::SessionOutcome::Evaluated(node) => node.to_latex(),
+        other => panic!("expected a value, got {:?}", other),
+    };
+    assert_eq!(value(&mut session, r"\vec{a} \cdot \vec{b}"), "32");
+    assert_eq!(value(&mut session, r"|\vec{a}|"), r"\sqrt{14}");
+    assert_eq!(
+        value(&mut session, r"\vec{a} \times \vec{b}"),
+        r"\begin{pmatrix} -3 \\ 6 \\ -3 \end{pmatrix}"
+    );
+}
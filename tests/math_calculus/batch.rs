@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod batch_calculus_tests {
+    use arithma::{
+        differentiate_many, integrate_many, parse_latex, parse_latex_raw, ArithmaError,
+        Environment, Node,
+    };
+
+    fn parse_all(latex: &[&str]) -> Vec<Node> {
+        latex.iter().map(|l| parse_latex_raw(l).unwrap()).collect()
+    }
+
+    fn latex(result: &Result<Node, ArithmaError>) -> String {
+        result.as_ref().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_differentiate_many_matches_single_calls() {
+        let exprs = parse_all(&["x^3", "\\sin(x)", "x^3", "y"]);
+        let results = differentiate_many(&exprs, "x");
+        assert_eq!(results.len(), 4);
+        assert_eq!(latex(&results[0]), "3x^{2}");
+        assert_eq!(latex(&results[1]), "\\cos(x)");
+        assert_eq!(results[2], results[0]);
+        assert_eq!(latex(&results[3]), "0");
+    }
+
+    #[test]
+    fn test_jacobian_row_by_row() {
+        // f = (x²y, x + y²): one call per variable gives a Jacobian column
+        let f = parse_all(&["x^2 y", "x + y^2"]);
+        let env = Environment::new();
+        let dx = differentiate_many(&f, "x");
+        let dy = differentiate_many(&f, "y");
+        assert_eq!(
+            *dx[0].as_ref().unwrap(),
+            parse_latex("2y \\cdot x", &env).unwrap()
+        );
+        assert_eq!(latex(&dx[1]), "1");
+        assert_eq!(latex(&dy[0]), "x^{2}");
+        assert_eq!(*dy[1].as_ref().unwrap(), parse_latex("2y", &env).unwrap());
+    }
+
+    #[test]
+    fn test_integrate_many_isolates_failures() {
+        let exprs = parse_all(&["2x", "e^{-x^2}", "\\cos(x)"]);
+        let results = integrate_many(&exprs, "x");
+        assert_eq!(latex(&results[0]), "x^{2}");
+        assert!(results[1].is_err());
+        assert_eq!(latex(&results[2]), "\\sin(x)");
+    }
+
+    #[test]
+    fn test_empty_batch() {
+        assert!(differentiate_many(&[], "x").is_empty());
+        assert!(integrate_many(&[], "x").is_empty());
+    }
+}
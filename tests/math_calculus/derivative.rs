@@ -556,4 +556,228 @@ mod derivative_tests {
             result
         );
     }
+
+    // Derivatives come back as expressions, not values: each result below is
+    // the symbolic output of the general rules, not a number pre-evaluated at
+    // some point.
+    #[test]
+    fn test_derivatives_are_symbolic() {
+        use arithma::derivative::differentiate_latex;
+
+        // Product rule
+        assert_eq!(
+            differentiate_latex("x\\sin(x)", "x").unwrap(),
+            "x \\cdot \\cos(x) + \\sin(x)"
+        );
+        // Polynomial product collapses to canonical form
+        assert_eq!(
+            differentiate_latex("x^2(2x+3)", "x").unwrap(),
+            "6x^{2} + 6x"
+        );
+        // Quotient rule
+        assert_eq!(
+            differentiate_latex("\\frac{x}{x+1}", "x").unwrap(),
+            "\\frac{1}{(x + 1)^{2}}"
+        );
+        // Chain rule through a function of a polynomial
+        assert_eq!(
+            differentiate_latex("\\sin(x^2)", "x").unwrap(),
+            "\\cos(x^{2}) \\cdot 2x"
+        );
+        // Chain rule through a square root: f'/(2√f)
+        assert_eq!(
+            differentiate_latex("\\sqrt{\\sin(x)}", "x").unwrap(),
+            "\\frac{\\cos(x)}{2\\sqrt(\\sin(x))}"
+        );
+        assert_eq!(
+            differentiate_latex("\\sqrt{x^2+1}", "x").unwrap(),
+            "\\frac{x}{\\sqrt(x^{2} + 1)}"
+        );
+    }
+
+    #[test]
+    fn test_general_power_rule() {
+        use arithma::derivative::differentiate_latex;
+
+        // d/dx xˣ = xˣ(ln x + 1)
+        assert_eq!(
+            differentiate_latex("x^x", "x").unwrap(),
+            "x^{x} \\cdot (\\ln(x) + 1)"
+        );
+
+        // d/dx (x+1)^{sin x} = (x+1)^{sin x}·(cos x·ln(x+1) + sin x/(x+1)), at x=1
+        let mut env = Environment::new();
+        env.set("x", 1.0);
+        let result = differentiate_and_evaluate("(x+1)^{\\sin x}", "x", &env).unwrap();
+        let (s, c) = (1.0_f64.sin(), 1.0_f64.cos());
+        let expected = 2.0_f64.powf(s) * (c * 2.0_f64.ln() + s / 2.0);
+        assert!(
+            approx_eq(result, expected, 1e-10),
+            "d/dx (x+1)^sin(x) at x=1 should be {}, got {}",
+            expected,
+            result
+        );
+
+        // d/dx x^{ln x} = 2·ln(x)·x^{ln x}/x, at x=e: 2·e/e = 2
+        env.set("x", std::f64::consts::E);
+        let result = differentiate_and_evaluate("x^{\\ln(x)}", "x", &env).unwrap();
+        assert!(
+            approx_eq(result, 2.0, 1e-10),
+            "d/dx x^ln(x) at x=e should be 2, got {}",
+            result
+        );
+    }
+}
+
+#[cfg(test)]
+mod registry_derivative_tests {
+    use arithma::{
+        differentiate, differentiate_and_evaluate, parse_latex_raw, ArithmaError, Environment,
+    };
+
+    fn derivative_at(latex: &str, bindings: &[(&str, f64)]) -> f64 {
+        let mut env = Environment::new();
+        for (name, value) in bindings {
+            env.set(name, *value);
+        }
+        differentiate_and_evaluate(latex, "x", &env).unwrap()
+    }
+
+    #[test]
+    fn test_atan2_partials() {
+        // ∂/∂x atan2(y, x) = −y/(x² + y²)
+        let d = derivative_at("\\atan2(y, x)", &[("x", 1.0), ("y", 2.0)]);
+        assert!((d + 0.4).abs() < 1e-12, "{}", d);
+        // ∂/∂y atan2(y, x) = x/(x² + y²), through the first argument
+        let expr = parse_latex_raw("\\atan2(x, y)").unwrap();
+        let mut env = Environment::new();
+        env.set("x", 2.0);
+        env.set("y", 1.0);
+        let d = arithma::Evaluator::evaluate(&differentiate(&expr, "x").unwrap(), &env).unwrap();
+        assert!((d - 0.2).abs() < 1e-12, "{}", d);
+    }
+
+    #[test]
+    fn test_chain_rule_sums_over_arguments() {
+        // atan2(sin x, cos x) = x on (−π, π), so the derivative is 1
+        for x in [-2.0, 0.3, 1.5] {
+            let d = derivative_at("\\atan2(\\sin(x), \\cos(x))", &[("x", x)]);
+            assert!((d - 1.0).abs() < 1e-12, "x = {}: {}", x, d);
+        }
+        assert_eq!(derivative_at("\\atan2(y, 3)", &[("y", 1.0)]), 0.0);
+    }
+
+    #[test]
+    fn test_log_with_base() {
+        let d = derivative_at("\\log_{2}(x)", &[("x", 4.0)]);
+        assert!((d - 1.0 / (4.0 * 2f64.ln())).abs() < 1e-12, "{}", d);
+        let d = derivative_at("\\log_{b}(x^2)", &[("x", 3.0), ("b", 10.0)]);
+        assert!((d - 2.0 / (3.0 * 10f64.ln())).abs() < 1e-12, "{}", d);
+    }
+
+    #[test]
+    fn test_function_without_partials_names_the_argument() {
+        let expr = parse_latex_raw("\\gcd(x, 2)").unwrap();
+        let err = differentiate(&expr, "x").unwrap_err();
+        assert_eq!(
+            err,
+            ArithmaError::NotImplemented(
+                "Differentiation not implemented for function: gcd (argument 1)".to_string()
+            )
+        );
+        // Arguments free of the variable need no partial
+        let expr = parse_latex_raw("\\gcd(y, 2)").unwrap();
+        assert_eq!(differentiate(&expr, "x").unwrap().to_string(), "0");
+    }
+
+    #[test]
+    fn test_step_functions_differentiate_to_zero() {
+        let expr = parse_latex_raw("\\lfloor x \\rfloor + x^2").unwrap();
+        let derivative = arithma::derivative::differentiate_with_domain(&expr, "x").unwrap();
+        let simplified = arithma::Evaluator::simplify(&derivative.result, &Environment::new());
+        assert_eq!(simplified.unwrap().to_string(), "2x");
+        assert_eq!(
+            derivative.domain_note().unwrap(),
+            "valid except where \\floor{x} jumps"
+        );
+        // No note when nothing in the expression jumps
+        let expr = parse_latex_raw("x^2").unwrap();
+        let derivative = arithma::derivative::differentiate_with_domain(&expr, "x").unwrap();
+        assert!(derivative.domain_note().is_none());
+    }
+
+    #[test]
+    fn test_mod_partials() {
+        // ∂/∂a (a mod b) = 1 away from the jumps
+        let d = derivative_at("x \\bmod 3", &[("x", 4.5)]);
+        assert!((d - 1.0).abs() < 1e-12, "{}", d);
+        // ∂/∂b (a mod b) = −⌊a/b⌋
+        let d = derivative_at("7 \\bmod x", &[("x", 3.0)]);
+        assert!((d + 2.0).abs() < 1e-12, "{}", d);
+    }
+}
+
+#[cfg(test)]
+mod numeric_derivative_tests {
+    use arithma::status::Verdict;
+    use arithma::{check_derivative, differentiate_numeric, parse_latex_raw};
+
+    /// The symbolic derivative of `latex` agrees with numeric
+    /// differentiation across `[a, b]`.
+    fn assert_matches_numeric(latex: &str, a: f64, b: f64) {
+        let expr = parse_latex_raw(latex).unwrap();
+        let check = check_derivative(&expr, "x", a, b, 1e-8);
+        assert_eq!(
+            check.verdict,
+            Verdict::Pass,
+            "{}: d/dx = {:?}, samples {:?}, note {:?}",
+            latex,
+            check.derivative.map(|d| d.to_string()),
+            check.samples,
+            check.note
+        );
+    }
+
+    #[test]
+    fn test_richardson_accuracy() {
+        let numeric = |latex: &str, at: f64| {
+            let expr = parse_latex_raw(latex).unwrap();
+            differentiate_numeric(&expr, "x", at, 1e-2 * (1.0 + at.abs())).unwrap()
+        };
+        assert!((numeric("\\sin{x}", 1.0) - 1f64.cos()).abs() < 1e-13);
+        assert!((numeric("e^{x}", 2.0) - 2f64.exp()).abs() < 1e-12);
+        assert!((numeric("x^{10}", 3.0) - 196830.0).abs() < 1e-8);
+        // Steps that leave the domain of √x are shrunk until they fit
+        assert!((numeric("\\sqrt{x}", 0.01) - 5.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_numeric_errors() {
+        let expr = parse_latex_raw("\\ln(x)").unwrap();
+        assert!(differentiate_numeric(&expr, "x", 1.0, 0.0).is_err());
+        assert!(differentiate_numeric(&expr, "x", -1.0, 0.01).is_err());
+        let expr = parse_latex_raw("a x").unwrap();
+        assert!(differentiate_numeric(&expr, "x", 1.0, 0.01).is_err());
+    }
+
+    #[test]
+    fn test_symbolic_derivatives_match_numeric() {
+        for latex in [
+            "x^{3} - 2x",
+            "\\sin(x) \\cos(x)",
+            "\\tan(x)",
+            "e^{x^{2}}",
+            "x^{x}",
+            "\\ln(x^{2} + 1)",
+            "\\frac{x}{1 + x^{2}}",
+            "\\arcsin(x)",
+            "\\arctan(2x)",
+            "\\sqrt{1 - x^{2}}",
+            "\\sinh(x) \\cosh(x)",
+            "\\operatorname{arccosh}(x + 2)",
+            "|x - 2|",
+        ] {
+            assert_matches_numeric(latex, 0.1, 0.9);
+        }
+    }
 }
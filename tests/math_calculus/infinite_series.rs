@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod infinite_series_tests {
+    use arithma::{
+        parse_latex_raw, sum_infinite_series, Environment, Evaluator, SeriesAcceleration,
+        SeriesOptions, SeriesSum,
+    };
+
+    fn sum(latex: &str, start: i64, acceleration: SeriesAcceleration) -> Result<SeriesSum, String> {
+        let body = parse_latex_raw(latex).unwrap();
+        let options = SeriesOptions {
+            acceleration,
+            ..SeriesOptions::default()
+        };
+        sum_infinite_series(&body, "n", start, &Environment::new(), &options)
+            .map_err(|e| e.to_string())
+    }
+
+    fn auto(latex: &str, start: i64) -> SeriesSum {
+        sum(latex, start, SeriesAcceleration::Auto).unwrap()
+    }
+
+    #[test]
+    fn test_power_law_tails() {
+        let pi = std::f64::consts::PI;
+        let basel = auto("\\frac{1}{n^{2}}", 1);
+        assert!((basel.value - pi * pi / 6.0).abs() < 1e-10, "{:?}", basel);
+        assert!(basel.error_estimate < 1e-9);
+        assert!((auto("\\frac{1}{n^{3}}", 1).value - 1.2020569031595942).abs() < 1e-10);
+        assert!((auto("\\frac{1}{n^{1.5}}", 1).value - 2.612375348685488).abs() < 1e-10);
+        let coth = (1.0 + pi / pi.tanh()) / 2.0;
+        assert!((auto("\\frac{1}{n^{2} + 1}", 0).value - coth).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_geometric_and_factorial_tails() {
+        assert!((auto("\\frac{1}{2^{n}}", 0).value - 2.0).abs() < 1e-14);
+        assert!((auto("0.999^{n}", 0).value - 1000.0).abs() < 1e-8);
+        assert!((auto("\\frac{1}{n!}", 0).value - std::f64::consts::E).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_alternating_series_use_euler() {
+        let ln2 = auto("\\frac{(-1)^{n+1}}{n}", 1);
+        assert!((ln2.value - 2f64.ln()).abs() < 1e-13);
+        assert!(ln2.terms <= 128, "{}", ln2.terms);
+        let leibniz = sum("\\frac{(-1)^{n}}{2n + 1}", 0, SeriesAcceleration::Euler).unwrap();
+        assert!((leibniz.value - std::f64::consts::FRAC_PI_4).abs() < 1e-13);
+        let aitken = sum("\\frac{(-1)^{n}}{2n + 1}", 0, SeriesAcceleration::Aitken).unwrap();
+        assert!((aitken.value - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+        // Plain partial sums converge like 1/N and run out of terms
+        assert!(sum("\\frac{(-1)^{n}}{2n + 1}", 0, SeriesAcceleration::None).is_err());
+    }
+
+    #[test]
+    fn test_divergent_series() {
+        for latex in ["1", "n", "\\frac{1}{n}", "\\frac{1}{\\sqrt{n}}"] {
+            let err = sum(latex, 1, SeriesAcceleration::Auto).unwrap_err();
+            assert!(err.contains("diverges"), "{}: {}", latex, err);
+        }
+    }
+
+    #[test]
+    fn test_divergent_alternating_series() {
+        // Euler's means give 1/2 and −1/4 here, but neither series sums
+        for (latex, start) in [("(-1)^{n}", 0), ("(-1)^{n} n", 1)] {
+            for acceleration in [SeriesAcceleration::Auto, SeriesAcceleration::Euler] {
+                let err = sum(latex, start, acceleration).unwrap_err();
+                assert!(err.contains("diverges"), "{}: {}", latex, err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_evaluator_sums_to_infinity() {
+        let env = Environment::new();
+        let eval = |latex: &str| Evaluator::evaluate(&parse_latex_raw(latex).unwrap(), &env);
+        let basel = eval("\\sum_{n=1}^{\\infty} \\frac{1}{n^{2}}").unwrap();
+        assert!((basel - 1.6449340668482264).abs() < 1e-10);
+        assert!(eval("\\sum_{n=1}^{\\infty} \\frac{1}{n}").is_err());
+        // Other variables come from the environment
+        let mut env = Environment::new();
+        env.set("x", 0.5);
+        let expr = parse_latex_raw("\\sum_{n=0}^{\\infty} \\frac{x^{n}}{n!}").unwrap();
+        let value = Evaluator::evaluate(&expr, &env).unwrap();
+        assert!((value - 0.5f64.exp()).abs() < 1e-13);
+    }
+
+    #[test]
+    fn test_geometric_simplifies_exactly() {
+        use arithma::simplify::Simplifiable;
+        let simplify = |latex: &str| {
+            parse_latex_raw(latex)
+                .unwrap()
+                .simplify(&Environment::new())
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(simplify("\\sum_{n=0}^{\\infty} \\frac{1}{2^{n}}"), "2");
+        assert_eq!(simplify("\\sum_{n=1}^{\\infty} \\frac{3}{4^{n}}"), "1");
+        // No closed form: kept for numeric evaluation
+        assert_eq!(
+            simplify("\\sum_{n=1}^{\\infty} \\frac{1}{n^{2}}"),
+            "\\sum_{n = 1}^{\\infty}{\\frac{1}{n^{2}}}"
+        );
+    }
+}
@@ -1,19 +1,20 @@
 #[cfg(test)]
 mod integration_tests {
     use arithma::{
-        build_expression_tree, definite_integral_latex, integrate_latex, Environment, Evaluator,
-        Tokenizer,
+        build_expression_tree, definite_integral, definite_integral_exact_latex,
+        definite_integral_latex, definite_integral_with_bounds, improper_numeric_integral,
+        integrate_latex, ArithmaError, Environment, Evaluator, Tokenizer,
     };
 
     fn parse_expression(latex: &str) -> Result<arithma::Node, String> {
         let mut tokenizer = Tokenizer::new(latex);
         let tokens = tokenizer.tokenize();
-        build_expression_tree(tokens)
+        Ok(build_expression_tree(tokens)?)
     }
 
     fn evaluate_expression(latex: &str, env: &Environment) -> Result<f64, String> {
         let expr = parse_expression(latex)?;
-        Evaluator::evaluate(&expr, env)
+        Ok(Evaluator::evaluate(&expr, env)?)
     }
 
     fn evaluate_integral(expr: &str, var: &str, env: &Environment) -> Result<f64, String> {
@@ -246,10 +247,10 @@ mod integration_tests {
         let result = integrate_latex("\\exp(-x^2)", "x");
         assert!(result.is_err());
         let err = result.unwrap_err();
-        assert!(err.starts_with("NON_ELEMENTARY:"));
+        assert!(matches!(err, ArithmaError::NonElementary(_)));
         assert!(
-            err.contains("no elementary antiderivative")
-                || err.contains("No elementary antiderivative"),
+            err.to_string().contains("no elementary antiderivative")
+                || err.to_string().contains("No elementary antiderivative"),
             "Expected non-elementary explanation, got: {}",
             err
         );
@@ -259,7 +260,10 @@ mod integration_tests {
     fn test_integrate_latex_exp_x_cubed_non_elementary() {
         let result = integrate_latex("\\exp(x^3)", "x");
         assert!(result.is_err());
-        assert!(result.unwrap_err().starts_with("NON_ELEMENTARY:"));
+        assert!(matches!(
+            result.unwrap_err(),
+            ArithmaError::NonElementary(_)
+        ));
     }
 
     #[test]
@@ -292,7 +296,7 @@ mod integration_tests {
         assert!(result.is_err(), "∫1/ln(x)dx should be non-elementary");
         let err = result.unwrap_err();
         assert!(
-            err.starts_with("NON_ELEMENTARY:"),
+            matches!(err, ArithmaError::NonElementary(_)),
             "Expected NON_ELEMENTARY, got: {}",
             err
         );
@@ -316,7 +320,10 @@ mod integration_tests {
         // ∫1/(1+ln(x))dx — non-elementary (gives Ei)
         let result = integrate_latex("\\frac{1}{1 + \\ln(x)}", "x");
         assert!(result.is_err(), "∫1/(1+ln(x))dx should be non-elementary");
-        assert!(result.unwrap_err().starts_with("NON_ELEMENTARY:"));
+        assert!(matches!(
+            result.unwrap_err(),
+            ArithmaError::NonElementary(_)
+        ));
     }
 
     #[test]
@@ -417,6 +424,89 @@ mod integration_tests {
         );
     }
 
+    #[test]
+    fn test_definite_integral_with_symbolic_bounds() {
+        let integral = |expr: &str, lower: &str, upper: &str, env: &Environment| {
+            definite_integral_with_bounds(
+                &parse_expression(expr).unwrap(),
+                "x",
+                &parse_expression(lower).unwrap(),
+                &parse_expression(upper).unwrap(),
+                env,
+            )
+            .unwrap()
+        };
+        let env = Environment::new();
+        // Free variables in a bound stay symbolic: ∫₀ᵃ x dx = a²/2
+        assert_eq!(
+            integral("x", "0", "a", &env).to_string(),
+            "\\frac{1}{2} \\cdot a^{2}"
+        );
+        // Constants stay exact
+        assert_eq!(integral("\\sin(x)", "0", "\\pi", &env).to_string(), "2");
+
+        // Bound variables with a value in the environment take it
+        let mut env = Environment::new();
+        env.set("b", 3.0);
+        assert_eq!(integral("x^2", "0", "b", &env).to_string(), "9");
+        assert_eq!(
+            integral("x^2", "a", "b", &env).to_string(),
+            "-\\frac{1}{3} \\cdot a^{3} + 9"
+        );
+
+        // No antiderivative: numeric bounds fall back to quadrature
+        let value = integral("x^x", "1", "b - 1", &env);
+        let value = Evaluator::evaluate(&value, &env).unwrap();
+        assert!(approx_eq(value, 2.0504462, 1e-6), "got {}", value);
+    }
+
+    #[test]
+    fn test_improper_integrals() {
+        let integral = |expr: &str, lower: &str, upper: &str| {
+            definite_integral_exact_latex(expr, "x", lower, upper)
+        };
+        // Infinite bounds: the limit of the antiderivative
+        assert_eq!(integral("x^{-2}", "1", "\\infty").unwrap(), "1");
+        assert_eq!(integral("x e^{-x}", "0", "\\infty").unwrap(), "1");
+        assert_eq!(
+            integral("\\frac{1}{1+x^2}", "-\\infty", "\\infty").unwrap(),
+            "\\pi"
+        );
+        // Integrable singularities at an end
+        assert_eq!(integral("x^{-1/2}", "0", "1").unwrap(), "2");
+        assert_eq!(integral("\\ln(x)", "0", "1").unwrap(), "-1");
+        // Divergence is an error, not a number
+        assert!(matches!(
+            integral("\\frac{1}{x}", "1", "\\infty"),
+            Err(ArithmaError::Domain(_))
+        ));
+        assert!(matches!(
+            integral("x^{-2}", "0", "1"),
+            Err(ArithmaError::Domain(_))
+        ));
+        assert!(integral("x", "-\\infty", "\\infty").is_err());
+
+        // f64 bounds take ±∞; without an antiderivative, quadrature after
+        // a change of variable
+        let expr = parse_expression("x^{-2}").unwrap();
+        assert_eq!(
+            definite_integral(&expr, "x", 1.0, f64::INFINITY).unwrap(),
+            1.0
+        );
+        let expr = parse_expression("e^{-x^2}").unwrap();
+        let value = definite_integral(&expr, "x", f64::NEG_INFINITY, f64::INFINITY).unwrap();
+        assert!(
+            approx_eq(value, std::f64::consts::PI.sqrt(), 1e-9),
+            "got {}",
+            value
+        );
+        let expr = parse_expression("\\frac{\\cos(x)}{\\sqrt{x}}").unwrap();
+        let value = definite_integral(&expr, "x", 0.0, 1.0).unwrap();
+        assert!(approx_eq(value, 1.8090484758005438, 1e-6), "got {}", value);
+        let value = improper_numeric_integral(|x| Some(1.0 / x), 1.0, f64::INFINITY, 1e-10);
+        assert_eq!(value, None);
+    }
+
     // ===== Two-level tower: exp + ln integration =====
 
     #[test]
@@ -424,7 +514,10 @@ mod integration_tests {
         // ∫exp(x)·ln(x) dx → non-elementary (reduces to Ei)
         let result = integrate_latex("\\exp(x) \\cdot \\ln(x)", "x");
         assert!(result.is_err(), "∫exp(x)·ln(x)dx should be non-elementary");
-        assert!(result.unwrap_err().starts_with("NON_ELEMENTARY:"));
+        assert!(matches!(
+            result.unwrap_err(),
+            ArithmaError::NonElementary(_)
+        ));
     }
 
     #[test]
@@ -493,7 +586,10 @@ mod integration_tests {
         // ∫exp(x²)·ln(x) dx → non-elementary
         let result = integrate_latex("\\exp(x^2) \\cdot \\ln(x)", "x");
         assert!(result.is_err(), "∫exp(x²)·ln(x)dx should be non-elementary",);
-        assert!(result.unwrap_err().starts_with("NON_ELEMENTARY:"));
+        assert!(matches!(
+            result.unwrap_err(),
+            ArithmaError::NonElementary(_)
+        ));
     }
 
     #[test]
@@ -534,7 +630,7 @@ mod integration_tests {
             result,
         );
         assert!(
-            result.unwrap_err().starts_with("NON_ELEMENTARY:"),
+            matches!(result.unwrap_err(), ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY"
         );
     }
@@ -549,7 +645,7 @@ mod integration_tests {
             result,
         );
         assert!(
-            result.unwrap_err().starts_with("NON_ELEMENTARY:"),
+            matches!(result.unwrap_err(), ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY"
         );
     }
@@ -564,7 +660,7 @@ mod integration_tests {
             result,
         );
         assert!(
-            result.unwrap_err().starts_with("NON_ELEMENTARY:"),
+            matches!(result.unwrap_err(), ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY"
         );
     }
@@ -581,7 +677,7 @@ mod integration_tests {
             result,
         );
         assert!(
-            result.unwrap_err().starts_with("NON_ELEMENTARY:"),
+            matches!(result.unwrap_err(), ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY"
         );
     }
@@ -597,7 +693,7 @@ mod integration_tests {
         );
         let err = result.unwrap_err();
         assert!(
-            err.starts_with("NON_ELEMENTARY:"),
+            matches!(err, ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY, got: {}",
             err
         );
@@ -613,7 +709,7 @@ mod integration_tests {
             result,
         );
         assert!(
-            result.unwrap_err().starts_with("NON_ELEMENTARY:"),
+            matches!(result.unwrap_err(), ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY"
         );
     }
@@ -628,7 +724,7 @@ mod integration_tests {
             result,
         );
         assert!(
-            result.unwrap_err().starts_with("NON_ELEMENTARY:"),
+            matches!(result.unwrap_err(), ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY"
         );
     }
@@ -643,7 +739,7 @@ mod integration_tests {
             result,
         );
         assert!(
-            result.unwrap_err().starts_with("NON_ELEMENTARY:"),
+            matches!(result.unwrap_err(), ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY"
         );
     }
@@ -658,7 +754,7 @@ mod integration_tests {
             result,
         );
         assert!(
-            result.unwrap_err().starts_with("NON_ELEMENTARY:"),
+            matches!(result.unwrap_err(), ArithmaError::NonElementary(_)),
             "Should be NON_ELEMENTARY"
         );
     }
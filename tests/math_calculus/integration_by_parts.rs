@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod integration_by_parts_tests {
+    use arithma::integration::integrate;
+    use arithma::{build_expression_tree, Environment, Evaluator, Tokenizer};
+
+    fn parse_raw(latex: &str) -> arithma::Node {
+        let mut tokenizer = Tokenizer::new(latex);
+        let tokens = tokenizer.tokenize();
+        build_expression_tree(tokens).unwrap_or_else(|_| panic!("Failed to parse: {}", latex))
+    }
+
+    fn verify_antiderivative(integrand_latex: &str, var: &str, test_points: &[f64]) {
+        let expr = parse_raw(integrand_latex);
+        let integral = integrate(&expr, var)
+            .unwrap_or_else(|_| panic!("Failed to integrate: {}", integrand_latex));
+        let env_base = Environment::new();
+        let integral_simplified =
+            arithma::simplify::Simplifiable::simplify(&integral, &env_base).unwrap_or(integral);
+
+        for &x_val in test_points {
+            let h = 1e-6;
+            let mut env_plus = Environment::new();
+            env_plus.set(var, x_val + h);
+            let mut env_minus = Environment::new();
+            env_minus.set(var, x_val - h);
+            let f_plus = Evaluator::evaluate(&integral_simplified, &env_plus).unwrap();
+            let f_minus = Evaluator::evaluate(&integral_simplified, &env_minus).unwrap();
+            let numerical_deriv = (f_plus - f_minus) / (2.0 * h);
+
+            let mut env = Environment::new();
+            env.set(var, x_val);
+            let expected = Evaluator::evaluate(&parse_raw(integrand_latex), &env).unwrap();
+
+            assert!(
+                (numerical_deriv - expected).abs() < 1e-3,
+                "∫({}) d{} = {}: d/d{}[F] at {}={:.2} is {:.6}, expected {:.6}",
+                integrand_latex,
+                var,
+                integral_simplified,
+                var,
+                var,
+                x_val,
+                numerical_deriv,
+                expected
+            );
+        }
+    }
+
+    static POINTS: &[f64] = &[0.3, 0.7, 1.2, 2.0];
+
+    // === Cyclic integration by parts ===
+
+    #[test]
+    fn test_exp_times_sin() {
+        verify_antiderivative("\\exp(x) \\cdot \\sin(x)", "x", POINTS);
+    }
+
+    #[test]
+    fn test_exp_times_cos_scaled_args() {
+        verify_antiderivative("\\exp(2x) \\cdot \\cos(3x)", "x", POINTS);
+    }
+
+    // === Inverse trig × polynomial ===
+
+    #[test]
+    fn test_x_times_arctan() {
+        verify_antiderivative("x \\cdot \\arctan(x)", "x", POINTS);
+    }
+
+    #[test]
+    fn test_x_squared_times_arctan() {
+        verify_antiderivative("x^{2} \\cdot \\arctan(x)", "x", POINTS);
+    }
+
+    // === Substitution and elementary forms ===
+
+    #[test]
+    fn test_x_times_sqrt_quadratic() {
+        verify_antiderivative("x \\cdot \\sqrt{x^{2}+1}", "x", POINTS);
+    }
+
+    #[test]
+    fn test_sqrt_function() {
+        verify_antiderivative("\\sqrt{x}", "x", POINTS);
+    }
+
+    #[test]
+    fn test_ln_over_x_is_nonzero() {
+        verify_antiderivative("\\frac{\\ln(x)}{x}", "x", POINTS);
+        let integral = integrate(&parse_raw("\\frac{\\ln(x)}{x}"), "x").unwrap();
+        let mut env = Environment::new();
+        env.set("x", std::f64::consts::E);
+        let value = Evaluator::evaluate(&integral, &env).unwrap();
+        assert!((value - 0.5).abs() < 1e-9, "∫ln(x)/x at x=e: {}", value);
+    }
+
+    // === Unsupported forms fail instead of returning wrong results ===
+
+    #[test]
+    fn test_exp_sin_cos_is_not_misintegrated() {
+        // Any antiderivative returned must differentiate back to the integrand.
+        let integrand = "\\exp(x) \\cdot \\sin(x) \\cdot \\cos(x)";
+        if integrate(&parse_raw(integrand), "x").is_ok() {
+            verify_antiderivative(integrand, "x", POINTS);
+        }
+    }
+
+    #[test]
+    fn test_x_arcsin_terminates() {
+        // Must return (Ok or Err) without recursing indefinitely.
+        let _ = integrate(&parse_raw("x \\cdot \\arcsin(x)"), "x");
+    }
+}
@@ -63,3 +63,27 @@ fn sin_x_over_x_still_works_both_directions() {
         "0"
     );
 }
+
+#[test]
+fn arctan_levels_off_at_infinity() {
+    // The cascade's "cannot compute … → +∞" message used to be read as
+    // the answer +∞.
+    let value: f64 = limit_latex_str("\\arctan(x)", "x", "inf")
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((value - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+}
+
+#[test]
+fn sums_and_multiples_at_infinity() {
+    assert_eq!(limit_latex_str("-x^{-1}", "x", "inf").unwrap(), "0");
+    assert_eq!(
+        limit_latex_str("2 - \\frac{3}{x}", "x", "inf").unwrap(),
+        "2"
+    );
+    assert_eq!(
+        limit_latex_str("x \\cdot -e^{-x} - e^{-x}", "x", "inf").unwrap(),
+        "0"
+    );
+}
@@ -1,9 +1,17 @@
+mod batch;
 mod derivative;
+mod infinite_series;
 mod integration;
+mod integration_by_parts;
 mod inverse_trig;
 mod limits_bounded;
 mod partial_fraction_integration;
+mod piecewise_integration;
+mod plot;
+mod probability;
 mod special_functions;
+mod symmetry;
 mod trig_powers;
 mod trig_substitution;
 mod u_substitution;
+mod unevaluated;
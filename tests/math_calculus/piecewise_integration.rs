@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod piecewise_integration_tests {
+    use arithma::{
+        breakpoints, definite_integral, definite_integral_exact, parse_latex, parse_latex_raw,
+        Environment, Node,
+    };
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    fn exact(latex: &str, lower: &str, upper: &str) -> String {
+        definite_integral_exact(&parse(latex), "x", &parse(lower), &parse(upper))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_absolute_value_splits_at_its_roots() {
+        assert_eq!(exact("|x|", "-1", "1"), "1");
+        assert_eq!(exact("|x|", "-1", "2"), "\\frac{5}{2}");
+        assert_eq!(exact("|x - 1|", "0", "3"), "\\frac{5}{2}");
+        assert_eq!(exact("|x^2 - 1|", "-2", "2"), "4");
+        assert_eq!(exact("x|x|", "-1", "2"), "\\frac{7}{3}");
+        // Reversed bounds flip the sign
+        assert_eq!(exact("|x|", "2", "-1"), "-\\frac{5}{2}");
+    }
+
+    #[test]
+    fn test_floor_and_ceiling_split_at_integer_crossings() {
+        assert_eq!(
+            exact("\\lfloor x \\rfloor", "0", "\\frac{7}{2}"),
+            "\\frac{9}{2}"
+        );
+        assert_eq!(exact("\\lceil x \\rceil", "0", "2"), "3");
+        assert_eq!(exact("|\\lfloor x \\rfloor - 1|", "0", "3"), "2");
+        // ⌊x²⌋ steps at √1, √2, √3: 5 − √2 − √3
+        let v = definite_integral(&parse("\\lfloor x^2 \\rfloor"), "x", 0.0, 2.0).unwrap();
+        assert!(
+            (v - (5.0 - 2f64.sqrt() - 3f64.sqrt())).abs() < 1e-12,
+            "{}",
+            v
+        );
+    }
+
+    #[test]
+    fn test_piecewise_integrand_uses_each_arm_on_its_interval() {
+        let f = Node::Piecewise(vec![
+            (parse("x"), parse("x < 0")),
+            (parse("x^2"), parse("x \\geq 0")),
+        ]);
+        let total = definite_integral_exact(&f, "x", &parse("-1"), &parse("2")).unwrap();
+        // −1/2 + 8/3
+        assert_eq!(total.to_string(), "\\frac{13}{6}");
+    }
+
+    #[test]
+    fn test_compound_conditions_split_at_each_comparison() {
+        // 1 on (0, 2), 0 elsewhere
+        let f = Node::Piecewise(vec![
+            (parse("1"), parse("x > 0 \\land x < 2")),
+            (parse("0"), parse("\\lnot (x > 0 \\land x < 2)")),
+        ]);
+        let total = definite_integral_exact(&f, "x", &parse("-1"), &parse("3")).unwrap();
+        assert_eq!(total.to_string(), "2");
+    }
+
+    #[test]
+    fn test_breakpoints_are_exact_and_ordered() {
+        let points = breakpoints(&parse("|x^2 - 2|"), "x", &parse("-3"), &parse("3")).unwrap();
+        let shown: Vec<String> = points.iter().map(|p| p.to_string()).collect();
+        assert_eq!(shown, ["-\\sqrt{2}", "\\sqrt{2}"]);
+        let reversed = breakpoints(&parse("|x^2 - 2|"), "x", &parse("3"), &parse("-3")).unwrap();
+        assert_eq!(reversed.first(), points.last());
+    }
+
+    #[test]
+    fn test_integral_notation_resolves_piecewise_integrands() {
+        let env = Environment::new();
+        let simplified = parse_latex("\\int_{-1}^{1} |x| \\, dx", &env).unwrap();
+        assert_eq!(simplified.to_string(), "1");
+    }
+
+    #[test]
+    fn test_missed_breakpoint_is_an_error_not_a_wrong_answer() {
+        // The solver finds only the principal root of sin(x) = 0
+        let err = definite_integral_exact(&parse("|\\sin(x)|"), "x", &parse("0"), &parse("2\\pi"))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("does not keep one branch"),
+            "{}",
+            err
+        );
+    }
+}
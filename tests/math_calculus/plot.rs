@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod plot_tests {
+    use arithma::parse_latex_raw;
+    use arithma::plot::{plot, sample};
+
+    #[test]
+    fn samples_span_the_range_with_nan_where_undefined() {
+        let expr = parse_latex_raw("\\sqrt{x}").unwrap();
+        let points = sample(&expr, "x", -1.0, 4.0, 6);
+        let xs: Vec<f64> = points.iter().map(|p| p.0).collect();
+        assert_eq!(xs, [-1.0, 0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert!(points[0].1.is_nan());
+        assert_eq!(points[5].1, 2.0);
+    }
+
+    #[test]
+    fn jumps_and_poles_split_the_curve() {
+        let floor = plot(
+            &parse_latex_raw("\\lfloor x \\rfloor").unwrap(),
+            "x",
+            0.1,
+            2.9,
+            29,
+        )
+        .unwrap();
+        assert_eq!(floor.discontinuities.len(), 2);
+        assert!((floor.discontinuities[0] - 1.0).abs() < 1e-9);
+        assert!((floor.discontinuities[1] - 2.0).abs() < 1e-9);
+        assert_eq!(floor.segments.len(), 3);
+
+        let pole = plot(
+            &parse_latex_raw("\\frac{1}{x}").unwrap(),
+            "x",
+            -1.0,
+            1.0,
+            50,
+        )
+        .unwrap();
+        assert_eq!(pole.discontinuities.len(), 1);
+        assert!(pole.discontinuities[0].abs() < 1e-9);
+        assert!(pole.undefined.is_empty());
+
+        let tan = plot(&parse_latex_raw("\\tan(x)").unwrap(), "x", -3.0, 3.0, 100).unwrap();
+        let half_pi = std::f64::consts::FRAC_PI_2;
+        assert_eq!(tan.discontinuities.len(), 2);
+        assert!((tan.discontinuities[0] + half_pi).abs() < 1e-9);
+        assert!((tan.discontinuities[1] - half_pi).abs() < 1e-9);
+    }
+
+    #[test]
+    fn steep_continuous_curves_are_one_segment() {
+        for latex in ["e^{5x}", "\\arctan(100 x)", "x^{3}", "\\sin(40 x)"] {
+            let data = plot(&parse_latex_raw(latex).unwrap(), "x", -1.0, 1.0, 40).unwrap();
+            assert!(data.discontinuities.is_empty(), "{}: {:?}", latex, data);
+            assert_eq!(data.segments.len(), 1, "{}", latex);
+        }
+    }
+
+    #[test]
+    fn undefined_regions_are_reported_as_ranges() {
+        let data = plot(
+            &parse_latex_raw("\\ln(x^{2} - 1)").unwrap(),
+            "x",
+            -2.0,
+            2.0,
+            9,
+        )
+        .unwrap();
+        assert_eq!(data.undefined, [(-1.0, 1.0)]);
+        assert_eq!(data.segments.len(), 2);
+        assert!(plot(&parse_latex_raw("x + y").unwrap(), "x", 0.0, 1.0, 10).is_err());
+        assert!(plot(&parse_latex_raw("x").unwrap(), "x", 1.0, 0.0, 10).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod probability_tests {
+    use arithma::probability::{
+        expectation_latex, parse_latex_distribution, variance_latex, DiscreteDistribution,
+    };
+    use arithma::{parse_latex_raw, Environment, Evaluator, ExactNum, Node};
+
+    const THREE_POINT: &str = "(0, \\frac{1}{4}), (1, \\frac{1}{2}), (2, \\frac{1}{4})";
+
+    fn num(n: i64) -> Node {
+        Node::Num(ExactNum::integer(n))
+    }
+
+    #[test]
+    fn test_expectation_of_listed_outcomes() {
+        assert_eq!(expectation_latex("x", "x", THREE_POINT).unwrap(), "1");
+        assert_eq!(
+            expectation_latex("x^{2}", "x", THREE_POINT).unwrap(),
+            "\\frac{3}{2}"
+        );
+        assert_eq!(expectation_latex("2x + 1", "x", THREE_POINT).unwrap(), "3");
+    }
+
+    #[test]
+    fn test_variance_of_listed_outcomes() {
+        assert_eq!(
+            variance_latex("x", "x", THREE_POINT).unwrap(),
+            "\\frac{1}{2}"
+        );
+        // Var(2X + 1) = 4 Var(X)
+        assert_eq!(variance_latex("2x + 1", "x", THREE_POINT).unwrap(), "2");
+    }
+
+    #[test]
+    fn test_fair_die() {
+        let die = "\\left\\{\\left(1, \\frac{1}{6}\\right), (2, \\frac{1}{6}), (3, \\frac{1}{6}), \
+                   (4, \\frac{1}{6}), (5, \\frac{1}{6}), (6, \\frac{1}{6})\\right\\}";
+        assert_eq!(expectation_latex("x", "x", die).unwrap(), "\\frac{7}{2}");
+
+        let env = Environment::new();
+        let uniform = DiscreteDistribution::uniform((1..=6).map(num).collect(), &env).unwrap();
+        let x = Node::Variable("x".to_string());
+        assert_eq!(
+            uniform.expectation(&x, "x", &env),
+            parse_latex_distribution(die, &env)
+                .unwrap()
+                .expectation(&x, "x", &env)
+        );
+        assert_eq!(
+            uniform.variance(&x, "x", &env).unwrap().to_string(),
+            "\\frac{35}{12}"
+        );
+    }
+
+    #[test]
+    fn test_symbolic_probabilities() {
+        let bernoulli = "\\{(1, p), (0, 1 - p)\\}";
+        assert_eq!(expectation_latex("x", "x", bernoulli).unwrap(), "p");
+        let variance = parse_latex_raw(&variance_latex("x", "x", bernoulli).unwrap()).unwrap();
+        let expected = parse_latex_raw("p(1 - p)").unwrap();
+        for p in [0.1, 0.5, 0.8] {
+            let mut env = Environment::new();
+            env.set("p", p);
+            assert!(
+                (Evaluator::evaluate(&variance, &env).unwrap()
+                    - Evaluator::evaluate(&expected, &env).unwrap())
+                .abs()
+                    < 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_mass_function_uses_summation_engine() {
+        // Uniform on 1, …, n: E[X] = (n + 1)/2, Var(X) = (n² − 1)/12
+        let env = Environment::new();
+        let n = Node::Variable("n".to_string());
+        let pmf = Node::Divide(Box::new(num(1)), Box::new(n.clone()));
+        let uniform = DiscreteDistribution::from_pmf("k", num(1), n, pmf, &env).unwrap();
+        let mean = uniform.mean(&env).unwrap();
+        let x = Node::Variable("x".to_string());
+        let variance = uniform.variance(&x, "x", &env).unwrap();
+        assert!(!format!("{mean}{variance}").contains("\\sum"));
+        for n in [3.0, 10.0, 25.0] {
+            let mut env = Environment::new();
+            env.set("n", n);
+            let mean = Evaluator::evaluate(&mean, &env).unwrap();
+            let variance = Evaluator::evaluate(&variance, &env).unwrap();
+            assert!((mean - (n + 1.0) / 2.0).abs() < 1e-9, "n = {n}");
+            assert!((variance - (n * n - 1.0) / 12.0).abs() < 1e-9, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_distributions() {
+        let total = variance_latex("x", "x", "(1, 0.5), (0, 0.3)").unwrap_err();
+        assert!(total.contains("add up to"), "{total}");
+        let negative = variance_latex("x", "x", "(1, -0.5), (0, 1.5)").unwrap_err();
+        assert!(negative.contains("negative"), "{negative}");
+        assert!(expectation_latex("x", "x", "").is_err());
+        assert!(expectation_latex("x", "x", "(1, \\frac{1}{2}, 3)").is_err());
+        assert!(expectation_latex("x", "x", "(1, 1) (2, 0)").is_err());
+    }
+}
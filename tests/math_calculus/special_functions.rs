@@ -82,6 +82,108 @@ mod special_function_derivative_tests {
     }
 }
 
+#[cfg(test)]
+mod special_function_value_tests {
+    use arithma::simplify::Simplifiable;
+    use arithma::{
+        build_expression_tree, differentiate_and_evaluate, special, Environment, Evaluator, Node,
+        Tokenizer,
+    };
+
+    fn parse(latex: &str) -> Node {
+        build_expression_tree(Tokenizer::new(latex).tokenize()).unwrap()
+    }
+
+    fn evaluate(latex: &str) -> Result<f64, String> {
+        Ok(Evaluator::evaluate(&parse(latex), &Environment::new())?)
+    }
+
+    fn assert_relative(actual: f64, expected: f64) {
+        assert!(
+            ((actual - expected) / expected).abs() < 1e-14,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_erf_and_erfc_values() {
+        // Reference values from DLMF Table 7.3.1 / Abramowitz & Stegun 7.1.
+        assert_relative(special::erf(0.5), 0.520_499_877_813_046_5);
+        assert_relative(special::erf(1.0), 0.842_700_792_949_714_9);
+        assert_relative(special::erf(-2.0), -0.995_322_265_018_952_7);
+        assert_relative(special::erf(3.5), 0.999_999_256_901_627_7);
+        assert_relative(special::erfc(1.0), 0.157_299_207_050_285_1);
+        assert_relative(special::erfc(-1.0), 1.842_700_792_949_715);
+        // Deep in the tail, where 1 − erf(x) would have cancelled to zero.
+        assert_relative(special::erfc(6.0), 2.151_973_671_249_891_3e-17);
+        assert_relative(evaluate("\\erf(1) + \\erfc(1)").unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_gamma_values() {
+        assert_relative(evaluate("\\Gamma(5)").unwrap(), 24.0);
+        assert_relative(special::gamma(0.5), std::f64::consts::PI.sqrt());
+        assert_relative(
+            special::gamma(-1.5),
+            4.0 * std::f64::consts::PI.sqrt() / 3.0,
+        );
+        assert_relative(special::gamma(10.5), 1_133_278.388_948_785_3);
+        assert_relative(special::ln_gamma(100.0), 359.134_205_369_575_4);
+        assert!(evaluate("\\Gamma(0)").is_err());
+        assert!(evaluate("\\Gamma(-3)").is_err());
+    }
+
+    #[test]
+    fn test_beta_values() {
+        // B(2, 3) = 1!·2!/4! = 1/12, exactly
+        let simplified = parse("\\Beta(2, 3)").simplify(&Environment::new()).unwrap();
+        assert_eq!(simplified.to_string(), "\\frac{1}{12}");
+        assert_relative(evaluate("\\Beta(0.5, 0.5)").unwrap(), std::f64::consts::PI);
+    }
+
+    #[test]
+    fn test_factorial_of_non_integer_is_gamma() {
+        // (1/2)! = Γ(3/2) = √π/2
+        assert_relative(
+            evaluate("(\\frac{1}{2})!").unwrap(),
+            std::f64::consts::PI.sqrt() / 2.0,
+        );
+        assert_relative(evaluate("5!").unwrap(), 120.0);
+        assert!(evaluate("(-2)!").is_err());
+    }
+
+    #[test]
+    fn test_gamma_stays_exact_under_simplify() {
+        let env = Environment::new();
+        let folded = parse("\\Gamma(5)").simplify(&env).unwrap();
+        assert_eq!(folded.to_string(), "24");
+        // Γ(1/2) = √π is irrational: kept symbolic rather than rounded.
+        let kept = parse("\\Gamma(\\frac{1}{2})").simplify(&env).unwrap();
+        assert_eq!(kept.to_string(), "\\Gamma(\\frac{1}{2})");
+    }
+
+    #[test]
+    fn test_gamma_parses_only_when_applied() {
+        assert_eq!(
+            parse("\\Gamma(x)"),
+            Node::Function("Gamma".to_string(), vec![Node::Variable("x".to_string())])
+        );
+        assert_eq!(parse("\\Gamma"), Node::Variable("Γ".to_string()));
+    }
+
+    #[test]
+    fn test_erfc_derivative() {
+        let mut env = Environment::new();
+        env.set("x", 1.0);
+        // d/dx erfc(x) = −(2/√π)·e^{-x²}
+        let result = differentiate_and_evaluate("\\erfc(x)", "x", &env).unwrap();
+        let expected = -2.0 / std::f64::consts::PI.sqrt() * (-1.0_f64).exp();
+        assert_relative(result, expected);
+    }
+}
+
 #[cfg(test)]
 mod special_recognition_tests {
     use arithma::integration::{integrate_outcome, IntegralOutcome};
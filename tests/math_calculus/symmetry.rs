@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod symmetry_tests {
+    use arithma::integration::{definite_integral, definite_integral_exact_latex};
+    use arithma::symmetry::{detect_period, is_even_function, is_odd_function, parity, Parity};
+    use arithma::{parse_latex_raw, Environment, Evaluator};
+
+    fn parse(latex: &str) -> arithma::Node {
+        parse_latex_raw(latex).unwrap_or_else(|e| panic!("Failed to parse {}: {}", latex, e))
+    }
+
+    fn period(latex: &str) -> Option<String> {
+        detect_period(&parse(latex), "x").map(|p| p.to_string())
+    }
+
+    // === Parity ===
+
+    #[test]
+    fn test_even_functions() {
+        for f in [
+            "x^{2}",
+            "\\cos(x)",
+            "x \\cdot \\sin(x)",
+            "|x|",
+            "\\exp(x^{2})",
+            "\\frac{1}{1+x^{2}}",
+            "\\cosh(x) + 3",
+        ] {
+            assert!(is_even_function(&parse(f), "x"), "{} should be even", f);
+            assert!(!is_odd_function(&parse(f), "x"), "{} should not be odd", f);
+        }
+    }
+
+    #[test]
+    fn test_odd_functions() {
+        for f in [
+            "x^{3}",
+            "\\sin(x)",
+            "x \\cdot \\cos(x)",
+            "\\arctan(x^{3})",
+            "\\frac{x}{1+x^{2}}",
+            "\\tanh(x) - x",
+        ] {
+            assert!(is_odd_function(&parse(f), "x"), "{} should be odd", f);
+            assert!(
+                !is_even_function(&parse(f), "x"),
+                "{} should not be even",
+                f
+            );
+        }
+    }
+
+    #[test]
+    fn test_neither() {
+        for f in ["x + 1", "\\exp(x)", "x^{2} + x", "\\sin(x + 1)"] {
+            assert_eq!(parity(&parse(f), "x"), Parity::Neither, "{}", f);
+        }
+    }
+
+    #[test]
+    fn test_numeric_fallback_detects_cancellation() {
+        // Structurally even + odd, but the odd parts cancel: (x+1)² + (x−1)²
+        assert!(is_even_function(&parse("(x+1)^{2} + (x-1)^{2}"), "x"));
+        // sinh written through exp
+        assert!(is_odd_function(&parse("\\exp(x) - \\exp(-x)"), "x"));
+    }
+
+    #[test]
+    fn test_other_variables_are_constants() {
+        assert!(is_even_function(&parse("a \\cdot x^{2} + b"), "x"));
+        assert!(is_odd_function(&parse("a \\cdot \\sin(x)"), "x"));
+    }
+
+    // === Periodicity ===
+
+    #[test]
+    fn test_trig_periods() {
+        assert_eq!(period("\\sin(x)").as_deref(), Some("2\\pi"));
+        assert_eq!(period("\\tan(x)").as_deref(), Some("\\pi"));
+        assert_eq!(period("\\sin(x) + \\cos(x)").as_deref(), Some("2\\pi"));
+        assert_eq!(period("\\sin(2x) + \\cos(3x)").as_deref(), Some("2\\pi"));
+    }
+
+    #[test]
+    fn test_scaled_argument_period() {
+        let p = detect_period(&parse("\\cos(3x)"), "x").unwrap();
+        let value = Evaluator::evaluate(&p, &Environment::new()).unwrap();
+        assert!((value - 2.0 * std::f64::consts::PI / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fundamental_period_is_refined() {
+        // sin² repeats every π, |sin| too
+        assert_eq!(period("\\sin(x)^{2}").as_deref(), Some("\\pi"));
+        assert_eq!(period("|\\sin(x)|").as_deref(), Some("\\pi"));
+    }
+
+    #[test]
+    fn test_composed_periodic() {
+        assert_eq!(period("\\exp(\\sin(x))").as_deref(), Some("2\\pi"));
+    }
+
+    #[test]
+    fn test_numeric_period_search() {
+        // No structural rule covers a trig function of a trig function
+        assert_eq!(period("\\sin(\\sin(x))").as_deref(), Some("2\\pi"));
+        assert_eq!(period("\\cos(\\sin(x))").as_deref(), Some("\\pi"));
+        assert_eq!(
+            period("\\sin(\\cos(3x)) + \\sin(x)").as_deref(),
+            Some("2\\pi")
+        );
+    }
+
+    #[test]
+    fn test_non_periodic() {
+        assert_eq!(period("x^{2}"), None);
+        assert_eq!(period("x \\cdot \\sin(x)"), None);
+        assert_eq!(period("\\sin(x^{2})"), None);
+        assert_eq!(period("5"), None);
+    }
+
+    #[test]
+    fn test_incommensurable_periods() {
+        assert_eq!(period("\\sin(x) + \\sin(1.41421356x)"), None);
+        assert_eq!(period("\\cos(0.5x)"), None);
+        assert_eq!(period("\\sin(x) + \\sin(\\frac{99}{70}x)"), None);
+        // Commensurable parts still combine
+        assert_eq!(
+            period("\\sin(\\frac{x}{2}) + \\sin(\\frac{x}{3})").as_deref(),
+            Some("12\\pi")
+        );
+    }
+
+    // === Definite integrals over symmetric intervals ===
+
+    #[test]
+    fn test_odd_integrand_over_symmetric_interval_is_zero() {
+        // sin(x³) has no elementary antiderivative, but it is odd
+        let v = definite_integral_exact_latex("\\sin(x^{3})", "x", "-2", "2").unwrap();
+        assert_eq!(v, "0");
+        let v = definite_integral(&parse("x^{3} \\cdot \\cos(x)"), "x", -1.5, 1.5).unwrap();
+        assert_eq!(v, 0.0);
+    }
+
+    #[test]
+    fn test_even_integrand_uses_half_range() {
+        let v = definite_integral_exact_latex("x^{2}", "x", "-3", "3").unwrap();
+        assert_eq!(v, "18");
+        let v = definite_integral_exact_latex("\\cos(x)", "x", "-\\pi", "\\pi").unwrap();
+        assert_eq!(v, "0");
+    }
+
+    #[test]
+    fn test_numeric_parity_does_not_give_exact_integrals() {
+        // Odd up to a term too small for the probe to see; the structural
+        // rules leave a sum of an odd and an even term unclassified
+        let integrand = "x + \\frac{x^{2}}{10^{12}}";
+        assert_eq!(parity(&parse(integrand), "x"), Parity::Odd);
+        let v = definite_integral_exact_latex(integrand, "x", "-1", "1").unwrap();
+        assert_eq!(v, "\\frac{1}{1500000000000}");
+        let v = definite_integral(&parse(integrand), "x", -1.0, 1.0).unwrap();
+        assert!((v * 1.5e12 - 1.0).abs() < 1e-3, "{}", v);
+    }
+
+    #[test]
+    fn test_symmetric_interval_still_rejects_poles() {
+        assert!(definite_integral_exact_latex("\\frac{1}{x}", "x", "-1", "1").is_err());
+        assert!(definite_integral(&parse("\\frac{1}{x}"), "x", -1.0, 1.0).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod unevaluated_calculus_tests {
+    use arithma::{
+        differentiate, differentiate_or_unevaluated, integrate, integrate_or_unevaluated,
+        parse_latex, parse_latex_raw, substitute, Environment, Evaluator, Node,
+    };
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    /// An environment where `f(x) = x^3` is known only by evaluation
+    fn with_f() -> Environment {
+        let mut env = Environment::new();
+        env.define_function("f", &["x"], parse("x^3")).unwrap();
+        env
+    }
+
+    #[test]
+    fn test_leibniz_notation_parses_to_a_derivative() {
+        match parse("\\frac{d}{dx}(x^2)") {
+            Node::Derivative(var, body) => {
+                assert_eq!(var, "x");
+                assert_eq!(*body, parse("x^2"));
+            }
+            other => panic!("expected a derivative, got {:?}", other),
+        }
+        assert!(matches!(
+            parse("\\frac{\\partial}{\\partial y}(x y^2)"),
+            Node::Derivative(ref v, _) if v == "y"
+        ));
+        assert!(matches!(
+            parse("\\frac{d}{d\\theta}\\cos\\theta"),
+            Node::Derivative(ref v, _) if v == "θ"
+        ));
+        // The body extends like a \sum body
+        assert!(matches!(parse("\\frac{d}{dx} x^2 + 1"), Node::Add(..)));
+        // Any other fraction of d is still a fraction
+        assert!(matches!(parse("\\frac{d}{d+1}"), Node::Divide(..)));
+    }
+
+    #[test]
+    fn test_simplify_resolves_what_it_can() {
+        let env = Environment::new();
+        let simplified = |latex: &str| parse_latex(latex, &env).unwrap().to_string();
+        assert_eq!(simplified("\\frac{d}{dx}(x^2)"), "2x");
+        assert_eq!(simplified("2\\frac{d}{dx}\\sin(x)"), "2\\cos(x)");
+        assert_eq!(simplified("\\frac{d}{dx}{x + 1}"), "1");
+        assert_eq!(
+            simplified("\\frac{d}{dx}\\gcd(x, 6)"),
+            "\\frac{d}{dx}{\\gcd(x, 6)}"
+        );
+    }
+
+    #[test]
+    fn test_round_trips_through_latex_and_display() {
+        for latex in [
+            "\\frac{d}{dx}\\gcd(x, 6)",
+            "\\frac{d}{dx}{x + 1}",
+            "2 \\cdot \\frac{d}{dt}{t \\cdot y} - 1",
+            "\\frac{d}{dx_{1}} x_1^3",
+        ] {
+            let expr = parse(latex);
+            assert_eq!(parse(&expr.to_latex()), expr, "{}", expr.to_latex());
+            assert_eq!(parse(&expr.to_string()), expr, "{}", expr);
+        }
+        assert_eq!(
+            parse("\\frac{d}{dx}{x + 1}").to_latex(),
+            "\\frac{d}{dx}\\left(x + 1\\right)"
+        );
+    }
+
+    #[test]
+    fn test_unresolved_derivative_evaluates_numerically() {
+        let mut env = with_f();
+        let expr = parse_latex("\\frac{d}{dx} f(x)", &env).unwrap();
+        assert!(matches!(expr, Node::Derivative(..)), "{}", expr);
+        env.set("x", 2.0);
+        let v = Evaluator::evaluate(&expr, &env).unwrap();
+        assert!((v - 12.0).abs() < 1e-8, "{}", v);
+        let err = Evaluator::evaluate(&expr, &with_f()).unwrap_err();
+        assert!(err.to_string().contains("needs a value for 'x'"), "{}", err);
+    }
+
+    #[test]
+    fn test_differentiate_or_unevaluated_holds_only_the_unresolved_part() {
+        let d = differentiate_or_unevaluated(&parse("x^2 + \\gcd(x, 6)"), "x");
+        assert_eq!(d.to_string(), "2x + \\frac{d}{dx}{\\gcd(x, 6)}");
+        // Resolvable input gives the same result as differentiate
+        let expr = parse("x \\sin(x)");
+        assert_eq!(
+            differentiate_or_unevaluated(&expr, "x"),
+            differentiate(&expr, "x").unwrap()
+        );
+        assert!(differentiate(&parse("\\gcd(x, 6)"), "x").is_err());
+    }
+
+    #[test]
+    fn test_integrate_or_unevaluated_holds_only_the_unresolved_part() {
+        let i = integrate_or_unevaluated(&parse("x + 3e^{-x^2}"), "x");
+        match i {
+            Node::Add(_, held) => match *held {
+                Node::Multiply(k, integral) => {
+                    assert_eq!(*k, parse("3"));
+                    assert!(matches!(*integral, Node::Integral(ref v, None, _) if v == "x"));
+                }
+                other => panic!("expected a constant times an integral, got {}", other),
+            },
+            other => panic!("expected a sum, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_calculus_on_held_derivatives() {
+        let held = parse("\\frac{d}{dx}\\gcd(x, 6)");
+        // The fundamental theorem undoes the held derivative
+        assert_eq!(integrate(&held, "x").unwrap(), parse("\\gcd(x, 6)"));
+        // Free of y, so its y-derivative is zero
+        assert_eq!(differentiate(&held, "y").unwrap().to_string(), "0");
+        // A mixed derivative stays held around the inner one
+        let mixed = differentiate(&parse("\\frac{d}{dx}\\gcd(x, y)"), "y").unwrap();
+        assert!(
+            matches!(mixed, Node::Derivative(ref v, ref inner)
+                if v == "y" && matches!(**inner, Node::Derivative(..))),
+            "{}",
+            mixed
+        );
+    }
+
+    #[test]
+    fn test_substitution_into_a_held_derivative() {
+        let held = parse("\\frac{d}{dx}\\gcd(x, a)");
+        let replaced = substitute(&held, &[("a".to_string(), parse("6"))]).unwrap();
+        assert_eq!(replaced, parse("\\frac{d}{dx}\\gcd(x, 6)"));
+        let err = substitute(&held, &[("a".to_string(), parse("x"))]).unwrap_err();
+        assert!(err.contains("capture"), "{}", err);
+        let err = substitute(&held, &[("x".to_string(), parse("2"))]).unwrap_err();
+        assert!(err.contains("unresolved derivative"), "{}", err);
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod domain_tests {
+    use arithma::{build_expression_tree, domain_of, SolutionSet, Tokenizer};
+
+    fn domain(input: &str) -> Result<SolutionSet, String> {
+        let mut tokenizer = Tokenizer::new(input);
+        let expr = build_expression_tree(tokenizer.tokenize()).unwrap();
+        domain_of(&expr, "x")
+    }
+
+    fn domain_latex(input: &str) -> String {
+        domain(input).unwrap().to_latex_in("x")
+    }
+
+    #[test]
+    fn reciprocal_plus_square_root() {
+        let set = domain("\\frac{1}{x-2} + \\sqrt{x}").unwrap();
+        assert_eq!(set.to_string(), "[0, 2) ∪ (2, ∞)");
+        assert_eq!(set.to_latex_in("x"), "x \\geq 0, x \\neq 2");
+        assert!(set.contains(0.0));
+        assert!(!set.contains(2.0));
+        assert!(!set.contains(-1.0));
+    }
+
+    #[test]
+    fn polynomial_is_defined_everywhere() {
+        assert_eq!(domain_latex("x^3 - 2x + 1"), "x \\in \\mathbb{R}");
+    }
+
+    #[test]
+    fn denominators_exclude_their_roots() {
+        assert_eq!(domain_latex("\\frac{x}{x^2 - 1}"), "x \\neq -1, x \\neq 1");
+        assert_eq!(domain_latex("x^{-2}"), "x \\neq 0");
+    }
+
+    #[test]
+    fn logarithm_needs_a_positive_argument() {
+        assert_eq!(domain_latex("\\ln(x^2 - 4)"), "x < -2 \\lor x > 2");
+        assert_eq!(domain_latex("\\log(3 - x)"), "x < 3");
+    }
+
+    #[test]
+    fn even_roots_and_fractional_powers() {
+        assert_eq!(domain_latex("(x + 1)^{1/2}"), "x \\geq -1");
+        assert_eq!(domain_latex("(x + 1)^{-1/2}"), "x > -1");
+        assert_eq!(domain_latex("x^{1/3}"), "x \\in \\mathbb{R}");
+    }
+
+    #[test]
+    fn inverse_trig_ranges() {
+        assert_eq!(
+            domain_latex("\\arcsin(2x)"),
+            "-\\frac{1}{2} \\leq x \\leq \\frac{1}{2}"
+        );
+        assert_eq!(domain_latex("\\arccosh(x)"), "x \\geq 1");
+        assert_eq!(domain_latex("\\arctanh(x)"), "-1 < x < 1");
+    }
+
+    #[test]
+    fn conditions_intersect() {
+        assert_eq!(
+            domain_latex("\\sqrt{x} + \\sqrt{4 - x}"),
+            "0 \\leq x \\leq 4"
+        );
+        assert!(domain("\\sqrt{x - 5} + \\ln(3 - x)").unwrap().is_empty());
+    }
+
+    #[test]
+    fn periodic_poles_are_reported() {
+        assert!(domain("\\tan(x)").is_err());
+    }
+}
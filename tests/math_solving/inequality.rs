@@ -115,4 +115,45 @@ mod inequality_tests {
         let result = arithma::solve_inequality(&expr, "x").unwrap();
         assert_eq!(result, "(-3, 3)");
     }
+
+    // ── Solution sets ────────────────────────────────────────
+
+    fn solve_set(input: &str) -> arithma::SolutionSet {
+        let expr = arithma::parse_latex_raw(input).unwrap();
+        arithma::solve_inequality_set(&expr, "x").unwrap()
+    }
+
+    #[test]
+    fn linear_solution_set() {
+        let set = solve_set("2x + 5 > 11");
+        assert_eq!(set.to_latex_in("x"), "x > 3");
+        assert_eq!(set.to_latex(), "(3, \\infty)");
+        assert!(set.contains(3.5) && !set.contains(3.0));
+    }
+
+    #[test]
+    fn negative_coefficient_flips() {
+        assert_eq!(solve_set("-2x > 4").to_latex_in("x"), "x < -2");
+        assert_eq!(solve_set("5 - x \\leq 2").to_latex_in("x"), "x \\geq 3");
+    }
+
+    #[test]
+    fn solution_set_notation() {
+        let set = solve_set("x^2 - 4 > 0");
+        assert_eq!(set.to_latex(), "(-\\infty, -2) \\cup (2, \\infty)");
+        assert_eq!(set.to_latex_in("x"), "x < -2 \\lor x > 2");
+        assert_eq!(set.to_string(), "(-∞, -2) ∪ (2, ∞)");
+        assert_eq!(
+            solve_set("x^2 \\leq 4").to_latex_in("x"),
+            "-2 \\leq x \\leq 2"
+        );
+        assert_eq!(solve_set("x^2 \\leq 0").to_latex_in("x"), "x = 0");
+        assert_eq!(
+            solve_set("x^2 + 1 > 0").to_latex_in("x"),
+            "x \\in \\mathbb{R}"
+        );
+        let empty = solve_set("x^2 < 0");
+        assert!(empty.is_empty());
+        assert_eq!(empty.to_latex(), "\\emptyset");
+    }
 }
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod ivp_tests {
+    use arithma::ivp::{solve_ivp, solve_ivp_latex, solve_ivp_nodes, OdeMethod, OdeOptions};
+    use arithma::{parse_latex_raw, Environment};
+
+    fn decay(_t: f64, y: &[f64]) -> Result<Vec<f64>, String> {
+        Ok(vec![-y[0]])
+    }
+
+    fn last(solution: &arithma::OdeSolution) -> f64 {
+        solution.y.last().unwrap()[0]
+    }
+
+    #[test]
+    fn test_rk4_fixed_step() {
+        let options = OdeOptions {
+            step: Some(0.1),
+            ..OdeOptions::with_method(OdeMethod::Rk4)
+        };
+        let solution = solve_ivp(decay, 0.0, &[1.0], 1.0, &options).unwrap();
+        assert_eq!(solution.t.len(), 11);
+        assert_eq!(*solution.t.last().unwrap(), 1.0);
+        assert!((last(&solution) - (-1.0f64).exp()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rk45_meets_tolerance_and_adapts_step() {
+        let options = OdeOptions {
+            rtol: 1e-10,
+            atol: 1e-12,
+            ..OdeOptions::default()
+        };
+        let solution = solve_ivp(decay, 0.0, &[1.0], 5.0, &options).unwrap();
+        assert!((last(&solution) - (-5.0f64).exp()).abs() < 1e-9);
+
+        // y = exp(−50(t − 2)²): small steps across the pulse, large ones away from it
+        let pulse =
+            |t: f64, _: &[f64]| Ok(vec![-100.0 * (t - 2.0) * (-50.0 * (t - 2.0).powi(2)).exp()]);
+        let solution = solve_ivp(pulse, 0.0, &[0.0], 4.0, &OdeOptions::default()).unwrap();
+        assert!(last(&solution).abs() < 1e-6);
+        let peak = solution.at(2.0).unwrap()[0];
+        assert!((peak - 1.0).abs() < 1e-3, "{peak}");
+        let steps: Vec<f64> = solution.t.windows(2).map(|w| w[1] - w[0]).collect();
+        let (smallest, largest) = steps
+            .iter()
+            .fold((f64::MAX, 0.0f64), |(lo, hi), &h| (lo.min(h), hi.max(h)));
+        assert!(largest > 5.0 * smallest, "steps did not adapt: {steps:?}");
+    }
+
+    #[test]
+    fn test_system_harmonic_oscillator() {
+        // y'' = −y as (y, v)' = (v, −y)
+        let env = Environment::new();
+        let system = [
+            ("y", parse_latex_raw("v").unwrap()),
+            ("v", parse_latex_raw("-y").unwrap()),
+        ];
+        let period = 2.0 * std::f64::consts::PI;
+        let solution = solve_ivp_nodes(
+            &system,
+            "t",
+            0.0,
+            &[1.0, 0.0],
+            period,
+            &OdeOptions::default(),
+            &env,
+        )
+        .unwrap();
+        let end = solution.y.last().unwrap();
+        assert!(
+            (end[0] - 1.0).abs() < 1e-5 && end[1].abs() < 1e-5,
+            "{end:?}"
+        );
+    }
+
+    #[test]
+    fn test_implicit_euler_is_stable_on_stiff_problem() {
+        // y' = −1000(y − cos t) tracks cos t; explicit RK4 at h = 0.01 blows up
+        let rhs = "-1000(y - \\cos(t))";
+        let fixed = |method| OdeOptions {
+            step: Some(0.01),
+            ..OdeOptions::with_method(method)
+        };
+        let stiff = solve_ivp_latex(
+            rhs,
+            "t",
+            "y",
+            0.0,
+            0.0,
+            2.0,
+            &fixed(OdeMethod::ImplicitEuler),
+        )
+        .unwrap();
+        assert!((last(&stiff) - 2.0f64.cos()).abs() < 1e-2);
+        let explicit = solve_ivp_latex(rhs, "t", "y", 0.0, 0.0, 2.0, &fixed(OdeMethod::Rk4));
+        assert!(explicit.is_err(), "{explicit:?}");
+    }
+
+    #[test]
+    fn test_dense_output() {
+        let solution =
+            solve_ivp_latex("y", "t", "y", 0.0, 1.0, 1.0, &OdeOptions::default()).unwrap();
+        let y = solution.interpolant();
+        for t in [0.0, 0.123, 0.5, 0.777, 1.0] {
+            assert!((y(t).unwrap()[0] - f64::exp(t)).abs() < 1e-5, "t = {t}");
+        }
+        assert!(y(1.5).is_none() && y(-0.1).is_none());
+        let samples = solution.sample(5);
+        let times: Vec<f64> = samples.iter().map(|(t, _)| *t).collect();
+        assert_eq!(times, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn test_backward_integration() {
+        let solution = solve_ivp(decay, 1.0, &[1.0], 0.0, &OdeOptions::default()).unwrap();
+        assert_eq!(*solution.t.last().unwrap(), 0.0);
+        assert!((last(&solution) - 1.0f64.exp()).abs() < 1e-5);
+        assert!((solution.at(0.5).unwrap()[0] - 0.5f64.exp()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_errors() {
+        let options = OdeOptions::default();
+        assert!(solve_ivp(decay, 0.0, &[], 1.0, &options).is_err());
+        assert!(solve_ivp(decay, 0.0, &[1.0], f64::INFINITY, &options).is_err());
+        // y' = y² from y(0) = 1 blows up at t = 1
+        let blowup = solve_ivp_latex("y^{2}", "t", "y", 0.0, 1.0, 2.0, &options);
+        assert!(blowup.is_err(), "{blowup:?}");
+        let unknown = solve_ivp_latex("y + z", "t", "y", 0.0, 1.0, 1.0, &options);
+        assert!(unknown.is_err());
+    }
+}
@@ -1,2 +1,6 @@
+mod domain;
 mod inequality;
+mod ivp;
 mod ode;
+mod roots;
+mod transcendental;
@@ -1,4 +1,10 @@
-use arithma::ode::{solve_constant_coeff_latex, solve_ode_latex};
+use arithma::ivp::{solve_ivp, OdeOptions};
+use arithma::matrix::{parse_latex_matrix, Matrix};
+use arithma::ode::{
+    matrix_exponential, solve_constant_coeff_latex, solve_constant_coeff_system,
+    solve_constant_coeff_system_latex, solve_ode_equation_latex, solve_ode_latex,
+};
+use arithma::{Environment, Evaluator, ExactNum, Node};
 
 #[test]
 fn test_separable_x_squared() {
@@ -99,3 +105,143 @@ fn test_cc_a_nonzero_check() {
     let result = solve_constant_coeff_latex(0.0, 1.0, 1.0, "x");
     assert!(result.is_err());
 }
+
+// ── Linear systems y' = Ay ──────────────────────────────────
+
+fn exponential_entries(latex: &str) -> Vec<String> {
+    let env = Environment::new();
+    let a = parse_latex_matrix(latex, &env).unwrap();
+    let exponential = matrix_exponential(&a, "t", &env).unwrap();
+    exponential.elements.iter().map(|e| e.to_string()).collect()
+}
+
+/// `e^{At}` evaluated at `t`
+fn exponential_at(exponential: &Matrix, t: f64) -> Vec<f64> {
+    let mut env = Environment::new();
+    env.set("t", t);
+    exponential
+        .elements
+        .iter()
+        .map(|e| Evaluator::evaluate(e, &env).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_matrix_exponential_closed_forms() {
+    assert_eq!(
+        exponential_entries(r"\begin{pmatrix} 0 & 1 \\ -1 & 0 \end{pmatrix}"),
+        ["\\cos(t)", "\\sin(t)", "-\\sin(t)", "\\cos(t)"]
+    );
+    // Defective: one eigenvector for the double eigenvalue 1
+    assert_eq!(
+        exponential_entries(r"\begin{pmatrix} 1 & 1 \\ 0 & 1 \end{pmatrix}"),
+        ["\\exp(t)", "t \\cdot \\exp(t)", "0", "\\exp(t)"]
+    );
+    assert_eq!(
+        exponential_entries(r"\begin{pmatrix} 2 & 1 \\ 1 & 2 \end{pmatrix}")[1],
+        "-\\frac{1}{2} \\cdot \\exp(t) + \\frac{1}{2} \\cdot \\exp(3t)"
+    );
+    let jordan =
+        exponential_entries(r"\begin{pmatrix} 1 & 1 & 0 \\ 0 & 1 & 1 \\ 0 & 0 & 1 \end{pmatrix}");
+    assert_eq!(jordan[2], "\\frac{1}{2} \\cdot t^{2} \\cdot \\exp(t)");
+}
+
+#[test]
+fn test_matrix_exponential_satisfies_the_system() {
+    // e^{A·0} = I and d/dt e^{At} = A e^{At}
+    let env = Environment::new();
+    for latex in [
+        r"\begin{pmatrix} 1 & 2 \\ -2 & 1 \end{pmatrix}",
+        r"\begin{pmatrix} 2 & 0 & 0 \\ 0 & 3 & 4 \\ 0 & 4 & 9 \end{pmatrix}",
+        r"\begin{pmatrix} 3 & 1 & 0 \\ 0 & 3 & 0 \\ 1 & 0 & -1 \end{pmatrix}",
+    ] {
+        let a = parse_latex_matrix(latex, &env).unwrap();
+        let n = a.rows;
+        let exponential = matrix_exponential(&a, "t", &env).unwrap();
+        let identity = exponential_at(&exponential, 0.0);
+        for (k, value) in identity.iter().enumerate() {
+            let expected = if k / n == k % n { 1.0 } else { 0.0 };
+            assert!((value - expected).abs() < 1e-9, "{latex}: {identity:?}");
+        }
+        let entries: Vec<f64> = a
+            .elements
+            .iter()
+            .map(|e| Evaluator::evaluate(e, &env).unwrap())
+            .collect();
+        let (t, h) = (0.3, 1e-5);
+        let (ahead, behind) = (
+            exponential_at(&exponential, t + h),
+            exponential_at(&exponential, t - h),
+        );
+        let now = exponential_at(&exponential, t);
+        for i in 0..n {
+            for j in 0..n {
+                let derivative = (ahead[i * n + j] - behind[i * n + j]) / (2.0 * h);
+                let product: f64 = (0..n).map(|k| entries[i * n + k] * now[k * n + j]).sum();
+                assert!(
+                    (derivative - product).abs() < 1e-4 * product.abs().max(1.0),
+                    "{latex} entry ({i}, {j})"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_system_initial_values_match_numeric_solution() {
+    let env = Environment::new();
+    let a = parse_latex_matrix(r"\begin{pmatrix} -1 & 2 \\ 0 & -3 \end{pmatrix}", &env).unwrap();
+    let y0 = [
+        Node::Num(ExactNum::integer(1)),
+        Node::Num(ExactNum::integer(2)),
+    ];
+    let solution = solve_constant_coeff_system(&a, "t", Some(&y0), &env).unwrap();
+    let numeric = solve_ivp(
+        |_, y| Ok(vec![-y[0] + 2.0 * y[1], -3.0 * y[1]]),
+        0.0,
+        &[1.0, 2.0],
+        1.0,
+        &OdeOptions::default(),
+    )
+    .unwrap();
+    let mut at = Environment::new();
+    at.set("t", 1.0);
+    for (exact, approximate) in solution.iter().zip(numeric.y.last().unwrap()) {
+        assert!((Evaluator::evaluate(exact, &at).unwrap() - approximate).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_system_general_solution_latex() {
+    let result =
+        solve_constant_coeff_system_latex(r"\begin{pmatrix} 0 & 1 \\ -4 & 0 \end{pmatrix}", "t")
+            .unwrap();
+    assert_eq!(
+        result,
+        "y_{1} = \\cos(2t) \\cdot C_{1} + \\frac{1}{2} \\cdot \\sin(2t) \\cdot C_{2}, \
+         y_{2} = -2\\sin(2t) \\cdot C_{1} + \\cos(2t) \\cdot C_{2}"
+    );
+    assert!(solve_constant_coeff_system_latex(
+        r"\begin{pmatrix} 1 & 2 & 3 \\ 4 & 5 & 6 \end{pmatrix}",
+        "t"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_equation_form() {
+    // y' + 2y = 0 → y = C₁e^{-2x}
+    let result = solve_ode_equation_latex("y' + 2y = 0", "x", "y").unwrap();
+    assert_eq!(result, r"y = \exp(-2x) \cdot C_{1}");
+    let result = solve_ode_equation_latex(r"\frac{dy}{dx} = x y", "x", "y").unwrap();
+    assert_eq!(result, r"y = \exp(\frac{1}{2} \cdot x^{2}) \cdot C_{1}");
+    let result = solve_ode_equation_latex(r"2y^{\prime} = x", "x", "y").unwrap();
+    assert_eq!(result, r"y = C_{1} + \frac{1}{4} \cdot x^{2}");
+}
+
+#[test]
+fn test_equation_form_rejects() {
+    assert!(solve_ode_equation_latex("y'' = y", "x", "y").is_err());
+    assert!(solve_ode_equation_latex("y'^{2} = y", "x", "y").is_err());
+    assert!(solve_ode_equation_latex("y = x", "x", "y").is_err());
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod root_finding_tests {
+    use arithma::{find_root, find_roots_in_interval, parse_latex_raw};
+
+    fn root(latex: &str, guess: f64) -> Result<f64, String> {
+        find_root(&parse_latex_raw(latex).unwrap(), "x", guess)
+    }
+
+    fn roots(latex: &str, lo: f64, hi: f64) -> Vec<f64> {
+        find_roots_in_interval(&parse_latex_raw(latex).unwrap(), "x", lo, hi).unwrap()
+    }
+
+    #[test]
+    fn test_newton_from_guess() {
+        assert!((root("\\cos{x} = x", 0.0).unwrap() - 0.7390851332151607).abs() < 1e-14);
+        assert!((root("x^{2} - 2", 1.0).unwrap() - 2f64.sqrt()).abs() < 1e-14);
+        // Newton cycles between 0 and 1 from 0; the bracket search does not
+        assert!((root("x^{3} - 2x + 2", 0.0).unwrap() + 1.7692923542386314).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_guess_outside_domain() {
+        // √x is undefined at the guess, so the root is bracketed instead
+        assert!((root("\\sqrt{x} = 1", -3.0).unwrap() - 1.0).abs() < 1e-12);
+        assert!(root("x^{2} + 1", 0.0).is_err());
+    }
+
+    #[test]
+    fn test_all_roots_in_interval() {
+        let found = roots("\\sin{x}", -10.0, 10.0);
+        assert_eq!(found.len(), 7);
+        for (r, k) in found.iter().zip(-3..=3) {
+            assert!((r - k as f64 * std::f64::consts::PI).abs() < 1e-12);
+        }
+        let found = roots("e^{x} = x + 2", -10.0, 10.0);
+        assert_eq!(found.len(), 2);
+        assert!((found[0] + 1.8414056604369606).abs() < 1e-12);
+        assert!((found[1] - 1.1461932206205825).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_double_root_and_poles() {
+        // (x − 1)² touches zero without changing sign
+        let found = roots("(x - 1)^{2}", -2.0, 2.0);
+        assert_eq!(found.len(), 1);
+        assert!((found[0] - 1.0).abs() < 1e-6);
+        // tan changes sign across its poles at ±π/2, which are not roots
+        assert_eq!(roots("\\tan{x}", -2.0, 2.0), [0.0]);
+        assert!(roots("\\frac{1}{x}", -1.0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_interval() {
+        let expr = parse_latex_raw("x").unwrap();
+        assert!(find_roots_in_interval(&expr, "x", 1.0, -1.0).is_err());
+        assert!(find_roots_in_interval(&expr, "x", 0.0, f64::INFINITY).is_err());
+    }
+}
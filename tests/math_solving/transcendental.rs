@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod transcendental_equation_tests {
+    use arithma::{
+        parse_latex_raw, solve_for_variable, solve_for_variable_general, solve_for_variable_nodes,
+        Environment, Evaluator,
+    };
+
+    fn roots(latex: &str) -> Vec<String> {
+        let expr = parse_latex_raw(latex).unwrap();
+        solve_for_variable_nodes(&expr, "x")
+            .unwrap()
+            .iter()
+            .map(|r| r.to_string())
+            .collect()
+    }
+
+    fn value(latex: &str) -> f64 {
+        solve_for_variable(&parse_latex_raw(latex).unwrap(), "x").unwrap()
+    }
+
+    #[test]
+    fn test_logarithm_is_undone_by_exponentiation() {
+        assert!((value("\\ln(x) = 2") - 2f64.exp()).abs() < 1e-12);
+        assert_eq!(roots("\\log(x + 1) = 2"), ["99"]);
+        assert_eq!(roots("\\lg(x) = 5"), ["32"]);
+        assert_eq!(roots("\\ln(x) = \\ln(2)"), ["2"]);
+    }
+
+    #[test]
+    fn test_exponential_is_undone_by_logarithm() {
+        assert_eq!(roots("2^x = 8"), ["3"]);
+        assert_eq!(value("2^x = 8"), 3.0);
+        assert_eq!(roots("e^{x} = 5"), ["\\ln(5)"]);
+        assert_eq!(roots("3e^{2x} - 1 = 5"), ["\\frac{\\ln(2)}{2}"]);
+    }
+
+    #[test]
+    fn test_trig_gives_principal_value() {
+        assert_eq!(roots("\\sin(x) = 1"), ["\\frac{\\pi}{2}"]);
+        assert_eq!(roots("\\cos(x) = \\frac{1}{2}"), ["\\frac{\\pi}{3}"]);
+        assert_eq!(roots("\\tan(2x) = 1"), ["\\frac{\\pi}{8}"]);
+    }
+
+    #[test]
+    fn test_general_solution_families() {
+        let family = |latex: &str| {
+            solve_for_variable_general(&parse_latex_raw(latex).unwrap(), "x").unwrap()
+        };
+        // The two sine families coincide at the top of the wave
+        let sin = family("\\sin(x) = 1");
+        assert_eq!(sin.len(), 1);
+        assert_eq!(family("\\cos(x) = \\frac{1}{2}").len(), 2);
+        for (latex, n, expected) in [
+            ("\\sin(x) = 1", -2.0, -3.5 * std::f64::consts::PI),
+            ("\\tan(x) = 1", 3.0, 3.25 * std::f64::consts::PI),
+        ] {
+            let mut env = Environment::new();
+            env.set("n", n);
+            let v = Evaluator::evaluate(&family(latex)[0], &env).unwrap();
+            assert!((v - expected).abs() < 1e-12, "{}: {}", latex, v);
+        }
+        // Non-periodic equations are unaffected
+        assert_eq!(family("2^x = 8")[0].to_string(), "3");
+    }
+
+    #[test]
+    fn test_radicals_and_absolute_values() {
+        assert_eq!(roots("\\sqrt{x + 1} + 2 = 5"), ["8"]);
+        assert_eq!(roots("|x - 1| = 2"), ["3", "-1"]);
+    }
+
+    #[test]
+    fn test_values_outside_the_range_have_no_solution() {
+        for latex in [
+            "\\sin(x) = 2",
+            "e^{x} = -1",
+            "\\sqrt{x} = -3",
+            "\\cosh(x) = 0",
+        ] {
+            let err = solve_for_variable_nodes(&parse_latex_raw(latex).unwrap(), "x").unwrap_err();
+            assert_eq!(err, "No real solutions", "{}", latex);
+        }
+    }
+
+    #[test]
+    fn test_repeated_unknown_is_not_inverted() {
+        let err =
+            solve_for_variable_nodes(&parse_latex_raw("\\sin(x) = x").unwrap(), "x").unwrap_err();
+        assert!(err.contains("Cannot convert to polynomial"), "{}", err);
+    }
+}
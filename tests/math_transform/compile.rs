@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod compile_tests {
+    use arithma::{parse_latex_raw, Environment, Evaluator};
+
+    /// The compiled value agrees with the tree walk at each `x`.
+    fn assert_matches_tree_walk(latex: &str, xs: &[f64]) {
+        let expr = parse_latex_raw(latex).unwrap();
+        let compiled = Evaluator::compile(&expr, &["x"]).unwrap();
+        for &x in xs {
+            let mut env = Environment::new();
+            env.set("x", x);
+            let expected = Evaluator::evaluate(&expr, &env).unwrap_or(f64::NAN);
+            let actual = compiled.eval(&[x]);
+            assert!(
+                (expected.is_nan() && actual.is_nan())
+                    || (expected - actual).abs() <= 1e-9 * expected.abs().max(1.0),
+                "{} at x = {}: tree walk {}, compiled {}",
+                latex,
+                x,
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn compiled_expressions_agree_with_the_tree_walk() {
+        let xs = [-2.5, -1.0, 0.0, 0.5, 1.0, 3.0];
+        for latex in [
+            "3x^{2} - 2x + \\frac{1}{3}",
+            "\\sin(x) \\cdot e^{x} + \\cos(\\pi x)",
+            "\\frac{1}{x - 1}",
+            "\\sqrt{x} + \\ln(x) + \\arcsin(x)",
+            "|x| + \\lfloor x \\rfloor + x^{x}",
+            "\\sum_{k=1}^{10} \\frac{x^{k}}{k!}",
+            "\\frac{d}{dx}(x^{3} \\sin(x))",
+            "\\int_{0}^{1} t x \\, dt",
+            "(x < 0) + (x \\geq 1)",
+        ] {
+            assert_matches_tree_walk(latex, &xs);
+        }
+    }
+
+    #[test]
+    fn values_are_taken_in_the_order_of_the_variables() {
+        let expr = parse_latex_raw("x - 2y").unwrap();
+        let compiled = Evaluator::compile(&expr, &["y", "x"]).unwrap();
+        assert_eq!(compiled.eval(&[1.0, 5.0]), 3.0);
+        assert_eq!(compiled.variables(), ["y", "x"]);
+    }
+
+    #[test]
+    fn a_variable_left_out_is_an_error() {
+        let expr = parse_latex_raw("x + y").unwrap();
+        let err = Evaluator::compile(&expr, &["x"]).unwrap_err();
+        assert!(err.to_string().contains("'y'"), "{}", err);
+    }
+}
@@ -8,12 +8,12 @@ mod composition_tests {
     fn parse_expression(latex: &str) -> Result<arithma::Node, String> {
         let mut tokenizer = Tokenizer::new(latex);
         let tokens = tokenizer.tokenize();
-        build_expression_tree(tokens)
+        Ok(build_expression_tree(tokens)?)
     }
 
     fn evaluate_expression(latex: &str, env: &Environment) -> Result<f64, String> {
         let expr = parse_expression(latex)?;
-        Evaluator::evaluate(&expr, env)
+        Ok(Evaluator::evaluate(&expr, env)?)
     }
 
     #[test]
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod expand_tests {
+    use arithma::simplify::expand;
+    use arithma::{parse_latex_raw, Environment, Evaluator};
+
+    fn expand_latex(latex: &str) -> String {
+        let env = Environment::new();
+        let node = parse_latex_raw(latex).unwrap();
+        format!("{}", expand(&node, &env).unwrap())
+    }
+
+    #[test]
+    fn test_expand_binomial_square() {
+        assert_eq!(expand_latex("(x+1)^2"), "x^{2} + 2x + 1");
+        assert_eq!(expand_latex("(2x+3)^3"), "8x^{3} + 36x^{2} + 54x + 27");
+    }
+
+    #[test]
+    fn test_expand_product_of_sums() {
+        assert_eq!(expand_latex("(x+2)(x-3)"), "x^{2} - x - 6");
+        assert_eq!(expand_latex("(x^2+1)(x^2-1)"), "x^{4} - 1");
+        assert_eq!(expand_latex("-(x-1)(x+1)"), "-x^{2} + 1");
+    }
+
+    #[test]
+    fn test_expand_substitution_result() {
+        assert_eq!(expand_latex("(y+1)^2 + 2(y+1) + 1"), "y^{2} + 4y + 4");
+    }
+
+    #[test]
+    fn test_expand_multivariate_is_flat() {
+        assert_eq!(
+            expand_latex("(a-b)^3"),
+            "a^{3} - 3a^{2} \\cdot b + 3a \\cdot b^{2} - b^{3}"
+        );
+        assert_eq!(
+            expand_latex("(x+y+1)^2"),
+            "x^{2} + 2x \\cdot y + y^{2} + 2x + 2y + 1"
+        );
+    }
+
+    #[test]
+    fn test_expand_high_power_collects_terms() {
+        let result = expand_latex("(x+1)^{20}");
+        assert!(
+            result.starts_with("x^{20} + 20x^{19} + 190x^{18}"),
+            "{}",
+            result
+        );
+        assert!(result.contains("184756x^{10}"), "{}", result);
+    }
+
+    #[test]
+    fn test_expand_non_polynomial_factors() {
+        assert_eq!(
+            expand_latex("(\\sin(x)+1)^2"),
+            "\\sin(x)^{2} + 2\\sin(x) + 1"
+        );
+        assert_eq!(expand_latex("\\frac{(x+1)^2}{x}"), "x + 2 + \\frac{1}{x}");
+        // Negative and fractional powers are not multiplied out.
+        assert_eq!(expand_latex("(x+1)^{-1}"), "\\frac{1}{x + 1}");
+    }
+
+    #[test]
+    fn test_expand_equation_sides() {
+        assert_eq!(
+            expand_latex("2(x+1)^2 = (x-1)^2"),
+            "2x^{2} + 4x + 2 = x^{2} - 2x + 1"
+        );
+    }
+
+    #[test]
+    fn test_expand_preserves_value() {
+        let env = Environment::new();
+        for latex in [
+            "(x+2)^3(x-1)",
+            "(x+\\cos(x))(x-2)^2",
+            "\\frac{(x-1)(x+3)}{x+5}",
+        ] {
+            let original = parse_latex_raw(latex).unwrap();
+            let expanded = expand(&original, &env).unwrap();
+            for x in [-1.5, 0.25, 2.0] {
+                let mut point = Environment::new();
+                point.set("x", x);
+                let a = Evaluator::evaluate(&original, &point).unwrap();
+                let b = Evaluator::evaluate(&expanded, &point).unwrap();
+                assert!((a - b).abs() < 1e-9, "{} at x={}: {} vs {}", latex, x, a, b);
+            }
+        }
+    }
+}
@@ -91,6 +91,13 @@ mod idempotency_tests {
         assert_idempotent("5x + 3y - 2x");
     }
 
+    #[test]
+    fn idem_like_terms_of_functions_and_powers() {
+        assert_idempotent("x + \\sin(x) + x");
+        assert_idempotent("2 x^{-1} + x^{-1} - 3");
+        assert_idempotent("\\sin(x) - (2 - \\sin(x))");
+    }
+
     // === Power rules ===
 
     #[test]
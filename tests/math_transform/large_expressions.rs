@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod large_expression_tests {
+    use arithma::exact::ExactNum;
+    use arithma::simplify::Simplifiable;
+    use arithma::{differentiate, parse_latex_raw, Environment, Evaluator, Node, NodeArena};
+
+    fn num(n: i64) -> Box<Node> {
+        Box::new(Node::Num(ExactNum::integer(n)))
+    }
+
+    fn x() -> Box<Node> {
+        Box::new(Node::Variable("x".to_string()))
+    }
+
+    /// `1 + 2x + 3x^{2} + …`, one `c·x^{k}` term (six nodes) per degree
+    fn expanded_polynomial(degree: i64) -> Node {
+        (1..=degree).fold(*num(1), |sum, k| {
+            let term = Node::Multiply(num(k % 7 + 1), Box::new(Node::Power(x(), num(k))));
+            Node::Add(Box::new(sum), Box::new(term))
+        })
+    }
+
+    /// `\sin(x) - 2\cos(2x) + 3\sin(3x) - …`
+    fn trig_sum(terms: i64) -> Node {
+        (1..=terms).fold(*num(0), |sum, k| {
+            let name = if k % 2 == 0 { "cos" } else { "sin" };
+            let term = Node::Multiply(
+                num(k),
+                Box::new(Node::Function(
+                    name.to_string(),
+                    vec![Node::Multiply(num(k), x())],
+                )),
+            );
+            if k % 2 == 0 {
+                Node::Subtract(Box::new(sum), Box::new(term))
+            } else {
+                Node::Add(Box::new(sum), Box::new(term))
+            }
+        })
+    }
+
+    fn size(expr: &Node) -> usize {
+        let mut arena = NodeArena::new();
+        let id = arena.intern(expr);
+        arena.size(id)
+    }
+
+    /// The value of `expr` at `x`, summing a long `+`/`−` chain term by
+    /// term rather than through the evaluator's recursion.
+    fn at(expr: &Node, x: f64) -> f64 {
+        let mut env = Environment::new();
+        env.set("x", x);
+        let mut total = 0.0;
+        let mut pending = vec![(expr, 1.0)];
+        while let Some((node, sign)) = pending.pop() {
+            match node {
+                Node::Add(a, b) => pending.extend([(&**a, sign), (&**b, sign)]),
+                Node::Subtract(a, b) => pending.extend([(&**a, sign), (&**b, -sign)]),
+                term => total += sign * Evaluator::evaluate(term, &env).unwrap(),
+            }
+        }
+        total
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * (1.0 + a.abs().max(b.abs()))
+    }
+
+    #[test]
+    fn ten_thousand_node_polynomial() {
+        let expr = expanded_polynomial(1700);
+        assert!(size(&expr) >= 10_000);
+        let simplified = expr.simplify(&Environment::new()).unwrap();
+        let derivative = differentiate(&expr, "x").unwrap();
+        let value: f64 = (0..=1700)
+            .map(|k| (k % 7 + 1) as f64 * 0.5f64.powi(k))
+            .sum();
+        let slope: f64 = (1..=1700)
+            .map(|k| (k % 7 + 1) as f64 * k as f64 * 0.5f64.powi(k - 1))
+            .sum();
+        assert!(close(at(&simplified, 0.5), value));
+        assert!(close(at(&derivative, 0.5), slope));
+    }
+
+    #[test]
+    fn ten_thousand_node_trig_sum() {
+        let expr = trig_sum(1500);
+        assert!(size(&expr) >= 10_000);
+        let simplified = expr.simplify(&Environment::new()).unwrap();
+        let derivative = differentiate(&expr, "x").unwrap();
+        for point in [0.3, 1.7] {
+            assert!(close(at(&simplified, point), at(&expr, point)));
+            let slope: f64 = (1..=1500)
+                .map(|k| {
+                    let k = k as f64;
+                    if k as i64 % 2 == 0 {
+                        k * k * (k * point).sin()
+                    } else {
+                        k * k * (k * point).cos()
+                    }
+                })
+                .sum();
+            assert!(close(at(&derivative, point), slope));
+        }
+    }
+
+    fn simplified(latex: &str) -> String {
+        let expr = parse_latex_raw(latex).unwrap();
+        expr.simplify(&Environment::new()).unwrap().to_string()
+    }
+
+    /// Eight terms take the one-pass path for long sums, seven the
+    /// pairwise one; fractions must combine the same way on both.
+    #[test]
+    fn long_sums_with_fractions_simplify_like_short_ones() {
+        let cancelling = "\\frac{1}{x+1} - \\frac{1}{x+1} + a+b+c+d+f";
+        assert_eq!(simplified(cancelling), "a + b + c + d + f");
+        assert_eq!(
+            simplified(&format!("{} + g", cancelling)),
+            "a + b + c + d + f + g"
+        );
+
+        let like_denominators = "\\frac{1}{x}+\\frac{2}{x} + a+b+c+d+f";
+        assert_eq!(
+            simplified(like_denominators),
+            "\\frac{x \\cdot a + x \\cdot b + x \\cdot c + x \\cdot d + x \\cdot f + 3}{x}"
+        );
+        assert_eq!(
+            simplified(&format!("{} + g", like_denominators)),
+            "\\frac{x \\cdot a + x \\cdot b + x \\cdot c + x \\cdot d + x \\cdot f + x \\cdot g + 3}{x}"
+        );
+    }
+}
@@ -1,5 +1,9 @@
+mod compile;
 mod composition;
+mod expand;
 mod idempotency;
+mod large_expressions;
+mod rewrite;
 mod simplify;
 mod simplify_fraction_cancel;
 mod simplify_rational;
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod rewrite_tests {
+    use arithma::simplify::Simplifiable;
+    use arithma::{apply_rules, parse_latex_raw, Environment, Rule, RuleSet};
+
+    fn rewrite(rules: &[&str], latex: &str) -> String {
+        let rules = RuleSet::parse(rules).unwrap();
+        apply_rules(&parse_latex_raw(latex).unwrap(), &rules, 10).to_string()
+    }
+
+    #[test]
+    fn repeated_wildcards_match_equal_subexpressions() {
+        assert_eq!(
+            rewrite(&["a_ + a_ -> 2 a_"], "\\sin(x) + \\sin(x)"),
+            "2\\sin(x)"
+        );
+        assert_eq!(
+            rewrite(&["a_ + a_ -> 2 a_"], "(y + 1) + (1 + y)"),
+            "2 \\cdot (y + 1)"
+        );
+        assert_eq!(rewrite(&["a_ + a_ -> 2 a_"], "x + y"), "x + y");
+    }
+
+    #[test]
+    fn operands_of_sums_and_products_match_in_either_order() {
+        let rules = ["\\ln(a_) + \\ln(b_) -> \\ln(a_ b_)", "a_ \\cdot 1 -> a_"];
+        assert_eq!(rewrite(&rules, "\\ln(x) + \\ln(y)"), "\\ln(x \\cdot y)");
+        assert_eq!(rewrite(&rules, "1 \\cdot z"), "z");
+    }
+
+    #[test]
+    fn rewriting_stops_after_max_iters() {
+        let swap = RuleSet::parse(&["a_ + b_ -> b_ + a_"]).unwrap();
+        let sum = parse_latex_raw("x + y").unwrap();
+        assert_eq!(apply_rules(&sum, &swap, 1).to_string(), "y + x");
+        assert_eq!(apply_rules(&sum, &swap, 2).to_string(), "x + y");
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(Rule::parse("a_ + 1").is_err());
+        assert!(Rule::parse("a_ -> b_").is_err());
+    }
+
+    #[test]
+    fn builtin_rules_are_the_simplifier_identities() {
+        let builtin = RuleSet::builtin();
+        assert!(!builtin.is_empty());
+        assert_eq!(
+            rewrite(
+                &["\\sin^{2}(u_) + \\cos^{2}(u_) -> 1"],
+                "\\cos^{2}(t) + \\sin^{2}(t)"
+            ),
+            "1"
+        );
+        let composition = parse_latex_raw("\\tanh(\\arctanh(x))").unwrap();
+        assert_eq!(apply_rules(&composition, &builtin, 10).to_string(), "x");
+    }
+
+    #[test]
+    fn environment_rules_extend_simplification() {
+        let mut env = Environment::new();
+        env.set_rules(RuleSet::parse(&["\\ln(a_) + \\ln(b_) -> \\ln(a_ b_)"]).unwrap());
+        let sum = parse_latex_raw("\\ln(x) + \\ln(y) + 1").unwrap();
+        assert_eq!(
+            sum.simplify(&env).unwrap().to_string(),
+            "\\ln(y \\cdot x) + 1"
+        );
+        assert_eq!(
+            sum.simplify(&Environment::new()).unwrap().to_string(),
+            "\\ln(x) + \\ln(y) + 1"
+        );
+
+        // A rule simplification undoes is applied once, not forever
+        env.set_rules(RuleSet::parse(&["2 a_ -> a_ + a_"]).unwrap());
+        let double = parse_latex_raw("2x").unwrap();
+        assert_eq!(double.simplify(&env).unwrap().to_string(), "2x");
+    }
+}
@@ -619,6 +619,32 @@ mod test_simplify {
         assert_eq!(format!("{}", simplified), "-\\cos(x)^{2}");
     }
 
+    #[test]
+    fn test_pythagorean_non_adjacent_terms() {
+        let env = Environment::new();
+        // x + sin²(x) + cos²(x) → x + 1: the squares need not be neighbours
+        let expr = arithma::parse_latex_raw("x + \\sin{x}^2 + \\cos{x}^2").unwrap();
+        assert_eq!(format!("{}", expr.simplify(&env).unwrap()), "x + 1");
+
+        let expr = arithma::parse_latex_raw("3\\cos{y}^2 + x + 3\\sin{y}^2").unwrap();
+        assert_eq!(format!("{}", expr.simplify(&env).unwrap()), "x + 3");
+
+        let expr = arithma::parse_latex_raw("x - \\sin{x}^2 - \\cos{x}^2").unwrap();
+        assert_eq!(format!("{}", expr.simplify(&env).unwrap()), "x - 1");
+    }
+
+    #[test]
+    fn test_pythagorean_non_adjacent_requires_matching_coefficients() {
+        let env = Environment::new();
+        let expr = arithma::parse_latex_raw("\\sin{x}^2 + x + 2\\cos{x}^2").unwrap();
+        let s = format!("{}", expr.simplify(&env).unwrap());
+        assert!(s.contains("\\sin") && s.contains("\\cos"), "got {}", s);
+
+        let expr = arithma::parse_latex_raw("\\sin{x}^2 + \\cos{y}^2 + x").unwrap();
+        let s = format!("{}", expr.simplify(&env).unwrap());
+        assert!(s.contains("\\sin") && s.contains("\\cos"), "got {}", s);
+    }
+
     #[test]
     fn test_function_latex_display() {
         let sin_x = Node::Function("sin".to_string(), vec![Node::Variable("x".to_string())]);
@@ -1982,7 +2008,7 @@ mod test_simplify {
                 "\\sqrt{2} + 3 + \\sqrt{3} + 5 + \\sqrt{2} + \\sqrt{3}",
                 "8 + 2\\sqrt{2} + 2\\sqrt{3}",
             ),
-            ("2\\sqrt{5} + 1 + 3\\sqrt{5} + 4", "1 + 5\\sqrt{5} + 4"),
+            ("2\\sqrt{5} + 1 + 3\\sqrt{5} + 4", "5\\sqrt{5} + 5"),
             (
                 "\\sqrt{7} + 2 + \\sqrt{3} + \\sqrt{7} + \\sqrt{3}",
                 "2 + 2\\sqrt{7} + 2\\sqrt{3}",
@@ -2438,4 +2464,88 @@ mod test_simplify {
             "expected π/4 from unbraced \\sin^-1, got {s}"
         );
     }
+
+    #[test]
+    fn test_like_terms_beyond_polynomials() {
+        let env = Environment::new();
+        let cases = [
+            ("2 x^{-1} + x^{-1}", "3x^{-1}"),
+            ("\\sin(x)^{2} + 3\\sin(x)^{2}", "4\\sin(x)^{2}"),
+            ("x^{y} + 2x^{y}", "3x^{y}"),
+            ("x + \\sin(x) + x", "2x + \\sin(x)"),
+            ("3 - 2 + x - \\sin(x) + 2\\sin(x)", "x + \\sin(x) + 1"),
+            ("\\sin(x) - (2 - \\sin(x))", "2\\sin(x) - 2"),
+            ("2 x y^{-1} - y^{-1} x", "x \\cdot y^{-1}"),
+            ("\\cos(y) a - a\\cos(y) + \\ln(x)", "\\ln(x)"),
+        ];
+        for (input, expected) in cases {
+            let result = arithma::parse_latex(input, &env).unwrap();
+            assert_eq!(format!("{}", result), expected, "{}", input);
+        }
+        // Terms that do not combine are left as written
+        let result = arithma::parse_latex("\\sin(x) + x^{-1}", &env).unwrap();
+        assert_eq!(format!("{}", result), "\\sin(x) + x^{-1}");
+    }
+
+    #[test]
+    fn test_simplify_options_control_identities() {
+        use arithma::simplify::{simplify_with_options, SimplifyOptions};
+        let env = Environment::new();
+        let simplify = |latex: &str, options: SimplifyOptions| {
+            let expr = arithma::parse_latex_raw(latex).unwrap();
+            format!("{}", simplify_with_options(&expr, &env, options).unwrap())
+        };
+        let defaults = SimplifyOptions::default();
+        assert_eq!(env.simplify_options(), defaults);
+        assert_eq!(simplify("\\sin(x)^{2} + \\cos(x)^{2}", defaults), "1");
+        assert_eq!(simplify("\\ln(e^{x})", defaults), "x");
+        assert_eq!(simplify("e^{\\ln(x)}", defaults), "x");
+        assert_eq!(
+            simplify("\\ln(\\frac{a}{b})", defaults),
+            "\\ln(\\frac{a}{b})"
+        );
+        assert_eq!(simplify("\\frac{\\sin(x)}{\\cos(x)}", defaults), "\\tan(x)");
+
+        let cautious = SimplifyOptions {
+            pythagorean: false,
+            log_exp_inverses: false,
+            ..defaults
+        };
+        assert_eq!(
+            simplify("\\sin(x)^{2} + \\cos(x)^{2}", cautious),
+            "\\sin(x)^{2} + \\cos(x)^{2}"
+        );
+        assert_eq!(simplify("1 - \\sin(x)^{2}", cautious), "1 - \\sin(x)^{2}");
+        assert_eq!(simplify("e^{\\ln(x)}", cautious), "\\exp(\\ln(x))");
+        assert_eq!(simplify("\\ln(e^{2x})", cautious), "\\ln(\\exp(2x))");
+
+        let aggressive = SimplifyOptions {
+            expand_logarithms: true,
+            sin_cos_form: true,
+            ..defaults
+        };
+        assert_eq!(
+            simplify("\\ln(\\frac{a}{b})", aggressive),
+            "\\ln(a) - \\ln(b)"
+        );
+        assert_eq!(
+            simplify("\\tan(x)", aggressive),
+            "\\frac{\\sin(x)}{\\cos(x)}"
+        );
+        assert_eq!(simplify("\\csc(x)", aggressive), "\\frac{1}{\\sin(x)}");
+        assert_eq!(
+            simplify("\\frac{\\sin(x)}{\\cos(x)}", aggressive),
+            "\\frac{\\sin(x)}{\\cos(x)}"
+        );
+        assert_eq!(simplify("\\tan(\\frac{\\pi}{4})", aggressive), "1");
+
+        // The options travel with the environment
+        let mut env = Environment::new();
+        env.set_simplify_options(aggressive);
+        let expr = arithma::parse_latex_raw("\\cot(x)").unwrap();
+        assert_eq!(
+            format!("{}", expr.simplify(&env).unwrap()),
+            "\\frac{\\cos(x)}{\\sin(x)}"
+        );
+    }
 }
@@ -95,12 +95,9 @@ mod simplify_rational_tests {
 
     #[test]
     fn normalize_fraction_coefficient() {
-        let result = simplify_latex("\\frac{2x}{4}");
-        assert!(
-            result.contains("\\frac{1}{2}"),
-            "2x/4 should simplify to (1/2)x, got: {}",
-            result
-        );
+        assert_eq!(simplify_latex("\\frac{2x}{4}"), "\\frac{x}{2}");
+        assert_eq!(simplify_latex("\\frac{6x}{4}"), "\\frac{3x}{2}");
+        assert_eq!(simplify_latex("\\frac{-2x}{4}"), "-\\frac{x}{2}");
     }
 
     #[test]
@@ -135,4 +132,46 @@ mod simplify_rational_tests {
             result
         );
     }
+
+    // ── Cancellation and the domain it adds ──────────────────
+
+    #[test]
+    fn cancel_common_polynomial_factors() {
+        assert_eq!(simplify_latex("\\frac{x^{2} - 1}{x - 1}"), "x + 1");
+        assert_eq!(simplify_latex("\\frac{x^{3} - x}{x^{2} + x}"), "x - 1");
+        assert_eq!(
+            simplify_latex("\\frac{x^{2} - 1}{x^{2} + 2x + 1}"),
+            "\\frac{x - 1}{x + 1}"
+        );
+    }
+
+    #[test]
+    fn domain_note_for_cancelled_factors() {
+        use arithma::simplify::simplify_with_domain;
+        let env = Environment::new();
+        let parse = |latex: &str| {
+            let mut tokenizer = Tokenizer::new(latex);
+            build_expression_tree(tokenizer.tokenize()).unwrap()
+        };
+
+        let simplified = simplify_with_domain(&parse("\\frac{x^{2} - 1}{x - 1}"), &env).unwrap();
+        assert_eq!(format!("{}", simplified.result), "x + 1");
+        assert_eq!(simplified.domain_note().as_deref(), Some("x \\neq 1"));
+
+        // x = -1 is still a pole of the result; x = 0 and x = 1 are lost
+        let simplified =
+            simplify_with_domain(&parse("\\frac{x^{2} - x}{x^{3} - x}"), &env).unwrap();
+        assert_eq!(format!("{}", simplified.result), "\\frac{1}{x + 1}");
+        let mut excluded: Vec<f64> = simplified
+            .excluded
+            .iter()
+            .map(|(_, v)| v.to_f64())
+            .collect();
+        excluded.sort_by(f64::total_cmp);
+        assert_eq!(excluded, [0.0, 1.0]);
+
+        let simplified = simplify_with_domain(&parse("\\frac{2x}{4}"), &env).unwrap();
+        assert_eq!(format!("{}", simplified.result), "\\frac{x}{2}");
+        assert_eq!(simplified.domain_note(), None);
+    }
 }
@@ -1239,9 +1239,9 @@ fn refused_degree_bound_agreement_is_verified_not_exact() {
 // e^{−x²}) must be checkable as an integral_of step: differentiation
 // eliminates the special function, so the comparison is between elementary
 // expressions. The raw derivative, however, carries exact-zero terms that
-// still mention erf — which (deliberately) refuses numeric evaluation — so
-// the checker must fold those away before sampling rather than reporting
-// a starved, inconclusive step.
+// still mention the special function — and Ei and li (deliberately) refuse
+// numeric evaluation — so the checker must fold those away before sampling
+// rather than reporting a starved, inconclusive step.
 
 fn step(label: &str, expr: &str, relation: Relation) -> ChainStepInput {
     ChainStepInput {
@@ -1411,15 +1411,15 @@ fn retry_that_also_starves_is_audited_as_simplify_assisted() {
     // the simplify retry also comes back inconclusive, the mechanism must
     // say the retry ran (simplify+ prefix) — an auditor must be able to
     // distinguish "no retry possible" from "retry ran, also inconclusive".
-    // erf(x)² keeps erf in its derivative even after simplification, so
-    // both passes starve.
+    // Ei(x)² keeps Ei — which refuses numeric evaluation — in its
+    // derivative even after simplification, so both passes starve.
     let steps = vec![
         step(
             "integrand",
-            "2 \\cdot \\erf(x) \\cdot \\frac{2}{\\sqrt{\\pi}} \\cdot \\exp(-x^2)",
+            "2 \\cdot \\Ei(x) \\cdot \\frac{\\exp(x)}{x}",
             Relation::Equals,
         ),
-        step("claim", "\\erf(x)^2", Relation::IntegralOf),
+        step("claim", "\\Ei(x)^2", Relation::IntegralOf),
     ];
     let result = verify_chain(&steps, &Environment::new()).unwrap();
     assert_eq!(result.steps[1].verdict, Verdict::Inconclusive);
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod consistency_tests {
+    use arithma::status::Verdict;
+    use arithma::{check_fundamental_theorem, parse_latex_raw};
+
+    fn check(latex: &str, a: f64, b: f64) -> arithma::ConsistencyReport {
+        let expr = parse_latex_raw(latex).unwrap();
+        check_fundamental_theorem(&expr, "x", a, b, 1e-6)
+    }
+
+    #[test]
+    fn test_elementary_integrands_are_consistent() {
+        let integrands = [
+            "x^2",
+            "x^3 - 2x + 1",
+            "\\sin(x)",
+            "\\cos(2x)",
+            "e^{x}",
+            "x e^{x}",
+            "\\frac{1}{x}",
+            "\\ln(x)",
+            "\\frac{1}{x^2+1}",
+            "\\sqrt{x}",
+            "x \\sin(x)",
+            "\\tan(x)",
+            "\\sin(x)^2",
+            "\\frac{x}{x^2+1}",
+            "\\arctan(x)",
+            "x^2 \\ln(x)",
+            "\\frac{1}{x^2 - 4}",
+            "\\sinh(x)",
+        ];
+        for latex in integrands {
+            let report = check(latex, 0.5, 1.5);
+            assert_eq!(
+                report.verdict,
+                Verdict::Pass,
+                "{}: integral {:?} ({:?} vs {:?}), derivative {:?}",
+                latex,
+                report.integral.verdict,
+                report.integral.symbolic_value,
+                report.integral.numeric_value,
+                report.derivative.samples
+            );
+        }
+    }
+
+    #[test]
+    fn test_report_carries_both_values() {
+        let report = check("x^2", 0.0, 3.0);
+        assert!(report.integral.antiderivative.is_some());
+        let symbolic = report.integral.symbolic_value.unwrap();
+        let numeric = report.integral.numeric_value.unwrap();
+        assert!((symbolic - 9.0).abs() < 1e-12, "{}", symbolic);
+        assert!((numeric - 9.0).abs() < 1e-9, "{}", numeric);
+
+        assert!(report.derivative.samples.len() >= 3);
+        for sample in &report.derivative.samples {
+            assert!((sample.symbolic - 2.0 * sample.x).abs() < 1e-12);
+            assert!((sample.finite_difference - 2.0 * sample.x).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_non_elementary_integral_is_inconclusive() {
+        let report = check("e^{-x^2}", 0.0, 1.0);
+        assert_eq!(report.integral.verdict, Verdict::Inconclusive);
+        assert!(report.integral.note.is_some());
+        // The derivative half still runs
+        assert_eq!(report.derivative.verdict, Verdict::Pass);
+        assert_eq!(report.verdict, Verdict::Inconclusive);
+    }
+
+    #[test]
+    fn test_singular_integrand_is_inconclusive_not_failed() {
+        let report = check("\\frac{1}{x}", -1.0, 1.0);
+        assert_eq!(report.integral.verdict, Verdict::Inconclusive);
+        let note = report.integral.note.unwrap();
+        assert!(note.contains("undefined"), "{}", note);
+        assert_eq!(report.derivative.verdict, Verdict::Pass);
+    }
+
+    #[test]
+    fn test_free_parameter_is_inconclusive() {
+        let report = check("a \\cdot \\sin(x)", 0.0, 1.0);
+        assert_eq!(report.derivative.verdict, Verdict::Inconclusive);
+        assert_ne!(report.verdict, Verdict::Fail);
+    }
+}
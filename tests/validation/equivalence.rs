@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod equivalence_tests {
+    use arithma::assumptions::{Assumption, Assumptions};
+    use arithma::{are_equivalent, parse_latex_raw, Environment, Node};
+
+    fn parse(latex: &str) -> Node {
+        parse_latex_raw(latex).unwrap()
+    }
+
+    fn equivalent(a: &str, b: &str) -> bool {
+        are_equivalent(&parse(a), &parse(b), &Environment::new())
+    }
+
+    #[test]
+    fn structural_equality_ignores_operand_order() {
+        assert!(parse("x + (y + 1)").structurally_equal(&parse("(1 + y) + x")));
+        assert!(parse("2x\\sin(x)").structurally_equal(&parse("\\sin(x) \\cdot x \\cdot 2")));
+        assert!(!parse("x - y").structurally_equal(&parse("y - x")));
+        assert!(!parse("x + x").structurally_equal(&parse("2x")));
+        assert!(!parse("\\sin(x)").structurally_equal(&parse("\\cos(x)")));
+    }
+
+    #[test]
+    fn equivalent_by_simplification() {
+        assert!(equivalent("(x+1)^{2}", "x^{2}+2x+1"));
+        assert!(equivalent("\\sin^{2}(x) + \\cos^{2}(x)", "1"));
+        assert!(!equivalent("x + 1", "x + 2"));
+    }
+
+    #[test]
+    fn equivalent_by_sampling() {
+        assert!(equivalent(
+            "(x+y)^{3}",
+            "x^{3} + 3x^{2} y + 3x y^{2} + y^{3}"
+        ));
+        assert!(equivalent("\\sin(2x)", "2\\sin(x)\\cos(x)"));
+        assert!(!equivalent("\\sin(2x)", "2\\sin(x)"));
+    }
+
+    #[test]
+    fn sampling_respects_assumptions() {
+        // √x² is x only where x is not negative
+        let a = parse("\\sqrt{x^{2}}");
+        let b = parse("x");
+        assert!(!are_equivalent(&a, &b, &Environment::new()));
+        let mut positive = Assumptions::new();
+        positive.assume("x", Assumption::Positive);
+        assert!(are_equivalent(
+            &a,
+            &b,
+            &Environment::with_assumptions(positive)
+        ));
+    }
+}
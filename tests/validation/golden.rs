@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod golden_tests {
+    use arithma::golden::{diff_lines, Snapshot};
+    use arithma::parse_latex_raw;
+
+    const SNAPSHOT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/validation/snapshots");
+
+    #[test]
+    fn test_derivative_latex_golden() {
+        let mut snapshot = Snapshot::new(SNAPSHOT_DIR, "derivatives");
+        snapshot
+            .differentiate("x^3 + 2x", "x")
+            .differentiate("\\sin(x) \\cdot \\cos(x)", "x")
+            .differentiate("\\frac{1}{x}", "x")
+            .differentiate("e^{2x}", "x")
+            .differentiate("\\ln(x^2 + 1)", "x")
+            .differentiate("\\sqrt{x}", "x");
+        snapshot.assert();
+    }
+
+    #[test]
+    fn test_integral_latex_golden() {
+        let mut snapshot = Snapshot::new(SNAPSHOT_DIR, "integrals");
+        snapshot
+            .integrate("x^2", "x")
+            .integrate("\\cos(x)", "x")
+            .integrate("\\frac{1}{x}", "x")
+            .integrate("x \\cdot e^{x}", "x")
+            .integrate("\\frac{1}{x^2 + 1}", "x");
+        snapshot.assert();
+    }
+
+    #[test]
+    fn test_simplify_latex_golden() {
+        let mut snapshot = Snapshot::new(SNAPSHOT_DIR, "simplify");
+        snapshot
+            .simplify("x + x")
+            .simplify("2 \\cdot 3 + y")
+            .simplify("\\frac{x^2 - 1}{x - 1}")
+            .simplify("x^{2} \\cdot x^{3}")
+            .simplify("\\sin(x)^2 + \\cos(x)^2");
+        snapshot.assert();
+    }
+
+    #[test]
+    fn test_render_lists_label_and_latex() {
+        let mut snapshot = Snapshot::new(SNAPSHOT_DIR, "unused");
+        snapshot
+            .record("half", &parse_latex_raw("\\frac{1}{2}").unwrap())
+            .record_result("bad", Err("no".to_string()));
+        assert_eq!(
+            snapshot.render(),
+            "half => \\frac{1}{2}\nbad => error: no\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_golden_file_is_reported() {
+        if std::env::var_os(arithma::golden::BLESS_VAR).is_some() {
+            return;
+        }
+        let snapshot = Snapshot::new(SNAPSHOT_DIR, "does_not_exist");
+        let err = snapshot.check().unwrap_err();
+        assert!(err.contains("does_not_exist.golden"), "{}", err);
+        assert!(err.contains("ARITHMA_BLESS=1"), "{}", err);
+    }
+
+    #[test]
+    fn test_diff_marks_changed_lines() {
+        let diff = diff_lines("a\nb\nc\n", "a\nB\nc\n");
+        assert_eq!(diff, "  a\n- b\n+ B\n  c\n");
+    }
+
+    #[test]
+    fn test_diff_elides_distant_context() {
+        let expected = "1\n2\n3\n4\n5\n6\n7\n";
+        let actual = "1\n2\n3\n4\n5\n6\nseven\n";
+        let diff = diff_lines(expected, actual);
+        assert_eq!(diff, "  …\n  5\n  6\n- 7\n+ seven\n");
+    }
+
+    #[test]
+    fn test_diff_of_equal_text_has_no_changes() {
+        let diff = diff_lines("x\ny\n", "x\ny\n");
+        assert!(!diff.contains('-') && !diff.contains('+'), "{}", diff);
+    }
+}
@@ -1,4 +1,8 @@
 mod chain;
+mod consistency;
+mod equivalence;
 mod error_propagation;
+mod golden;
+mod problem_set;
 mod status;
 mod verify;
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod problem_set_tests {
+    use arithma::problem_set::{
+        parse_problem_csv, parse_problem_json, run_problem_set, run_problems, Problem,
+    };
+    use arithma::status::Verdict;
+
+    fn verdicts(csv: &str) -> Vec<Verdict> {
+        let report = run_problems(&parse_problem_csv(csv).unwrap());
+        report.results.iter().map(|r| r.verdict).collect()
+    }
+
+    #[test]
+    fn test_csv_quoting_header_and_comments() {
+        let csv = "input,expected\n\
+                   # derivatives\n\
+                   \"\\frac{d}{dx} x^3\",3x^{2}\n\
+                   \n\
+                   \"\\gcd(4, 6)\",\"2\"\n\
+                   \"say \"\"hi\"\"\",x\n";
+        let problems = parse_problem_csv(csv).unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                Problem {
+                    line: 3,
+                    input: "\\frac{d}{dx} x^3".to_string(),
+                    expected: "3x^{2}".to_string(),
+                },
+                Problem {
+                    line: 5,
+                    input: "\\gcd(4, 6)".to_string(),
+                    expected: "2".to_string(),
+                },
+                Problem {
+                    line: 6,
+                    input: "say \"hi\"".to_string(),
+                    expected: "x".to_string(),
+                },
+            ]
+        );
+        let err = parse_problem_csv("x, y, z\n").unwrap_err();
+        assert!(err.contains("line 1"), "{}", err);
+        assert!(parse_problem_csv("\"x, 1\n").is_err());
+    }
+
+    #[test]
+    fn test_answers_match_by_display_number_or_identity() {
+        let csv = "\"\\int_0^1 x \\, dx\",\\frac{1}{2}\n\
+                   0.5,\\frac{1}{2}\n\
+                   \\sqrt{2},1.41421356237\n\
+                   (x+1)^2,x^2 + 2x + 1\n\
+                   \\sin(x)^2 + \\cos(x)^2,1\n\
+                   x = 2 + 3,5\n\
+                   2x,3x\n\
+                   \\sqrt{2},1.414\n";
+        use Verdict::*;
+        assert_eq!(
+            verdicts(csv),
+            vec![Pass, Pass, Pass, Pass, Pass, Pass, Fail, Fail]
+        );
+    }
+
+    #[test]
+    fn test_expected_errors() {
+        let csv = "(x+1,error\n\
+                   (x+1,error: mismatched PARENTHESES\n\
+                   (x+1,error: division by zero\n\
+                   1 + 1,error\n";
+        use Verdict::*;
+        assert_eq!(verdicts(csv), vec![Pass, Pass, Fail, Fail]);
+    }
+
+    #[test]
+    fn test_report_lists_failures_as_diffs() {
+        let report = run_problem_set(
+            r#"[{"input": "2x", "expected": "2x"}, {"input": "2x", "expected": "3x"}]"#,
+        )
+        .unwrap();
+        assert_eq!((report.passed(), report.failed()), (1, 1));
+        assert!(!report.all_passed());
+        let text = report.to_string();
+        assert!(
+            text.starts_with("line 2 [fail]: 2x\n- 3x\n+ 2x\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.ends_with("2 problems: 1 passed, 1 failed, 0 inconclusive"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn test_json_problem_sets() {
+        let problems = parse_problem_json(r#"[{"input": "1+1", "expected": "2"}]"#).unwrap();
+        assert_eq!(problems[0].line, 1);
+        let err = parse_problem_json(r#"[{"input": "1+1"}]"#).unwrap_err();
+        assert!(err.contains("expected"), "{}", err);
+        assert!(parse_problem_json(r#"{"input": "1+1"}"#).is_err());
+    }
+
+    #[test]
+    fn test_problem_set_files() {
+        let dir = std::env::temp_dir();
+        let name = format!("arithma_problem_set_test_{}", std::process::id());
+        let csv = dir.join(format!("{}.csv", name));
+        std::fs::write(&csv, "x^2 - 1,(x-1)(x+1)\n").unwrap();
+        let report = run_problem_set(csv.to_str().unwrap()).unwrap();
+        assert!(report.all_passed(), "{}", report);
+
+        let json = dir.join(format!("{}.json", name));
+        std::fs::write(&json, r#"[{"input": "3!", "expected": "6"}]"#).unwrap();
+        let report = run_problem_set(json.to_str().unwrap()).unwrap();
+        assert!(report.all_passed(), "{}", report);
+        std::fs::remove_file(&csv).unwrap();
+        std::fs::remove_file(&json).unwrap();
+
+        let err = run_problem_set("/nonexistent/problems.csv").unwrap_err();
+        assert!(err.contains("Cannot read"), "{}", err);
+    }
+}